@@ -0,0 +1,218 @@
+use std::future::Future;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures_util::{future::join, try_join, StreamExt};
+use zbus::{
+    connection, interface, message::Type, names::BusName, Connection, Guid, MatchRule,
+    MessageStream,
+};
+
+const PATH: &str = "/org/zbus/Bench";
+const INTERFACE: &str = "org.zbus.Bench";
+// The number of signals emitted per iteration of the throughput benchmark.
+const SIGNALS: u64 = 1000;
+
+struct Bench;
+
+#[interface(name = "org.zbus.Bench")]
+impl Bench {
+    fn ping(&self) {}
+
+    fn echo(&self, data: Vec<u8>) -> Vec<u8> {
+        data
+    }
+}
+
+// Runs the futures of the benchmarks on the runtime of the enabled backend.
+struct Runtime {
+    #[cfg(feature = "tokio")]
+    runtime: tokio::runtime::Runtime,
+}
+
+impl Runtime {
+    const NAME: &'static str = if cfg!(feature = "tokio") {
+        "tokio"
+    } else {
+        "async-io"
+    };
+
+    fn new() -> Self {
+        Self {
+            #[cfg(feature = "tokio")]
+            runtime: tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        }
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tokio")]
+        {
+            self.runtime.block_on(future)
+        }
+        #[cfg(not(feature = "tokio"))]
+        {
+            zbus::block_on(future)
+        }
+    }
+}
+
+// A service serving `Bench`, and a client calling it.
+struct Transport {
+    name: &'static str,
+    service: Connection,
+    client: Connection,
+}
+
+impl Transport {
+    // The peer-to-peer connections over a unix socket pair, and the ones over the session bus if
+    // it's available.
+    fn all(runtime: &Runtime) -> Vec<Self> {
+        let mut transports = vec![runtime.block_on(Self::p2p())];
+        match runtime.block_on(Self::bus()) {
+            Ok(transport) => transports.push(transport),
+            Err(e) => eprintln!("Skipping the session bus benchmarks: {e}"),
+        }
+
+        transports
+    }
+
+    async fn p2p() -> Self {
+        #[cfg(not(feature = "tokio"))]
+        use std::os::unix::net::UnixStream;
+        #[cfg(feature = "tokio")]
+        use tokio::net::UnixStream;
+
+        let guid = Guid::generate();
+        let (p0, p1) = UnixStream::pair().unwrap();
+        let service = connection::Builder::unix_stream(p0)
+            .server(guid)
+            .unwrap()
+            .p2p()
+            .serve_at(PATH, Bench)
+            .unwrap()
+            .build();
+        let client = connection::Builder::unix_stream(p1).p2p().build();
+        let (service, client) = try_join!(service, client).unwrap();
+
+        Self {
+            name: "p2p",
+            service,
+            client,
+        }
+    }
+
+    async fn bus() -> zbus::Result<Self> {
+        let service = connection::Builder::session()?
+            .serve_at(PATH, Bench)?
+            .build()
+            .await?;
+        let client = connection::Builder::session()?.build().await?;
+
+        Ok(Self {
+            name: "bus",
+            service,
+            client,
+        })
+    }
+
+    // The destination of the calls to the service.
+    fn destination(&self) -> Option<BusName<'_>> {
+        self.service.unique_name().map(BusName::from)
+    }
+}
+
+fn round_trip(c: &mut Criterion) {
+    let runtime = Runtime::new();
+    let mut group = c.benchmark_group(format!("round_trip/{}", Runtime::NAME));
+    let data = vec![77u8; 4096];
+
+    for transport in Transport::all(&runtime) {
+        let client = &transport.client;
+        let destination = transport.destination();
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_function(BenchmarkId::new("ping", transport.name), |b| {
+            b.iter(|| {
+                runtime
+                    .block_on(client.call_method(
+                        destination.as_ref(),
+                        PATH,
+                        Some(INTERFACE),
+                        "Ping",
+                        &(),
+                    ))
+                    .unwrap()
+            })
+        });
+
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_function(BenchmarkId::new("echo_4k", transport.name), |b| {
+            b.iter(|| {
+                runtime
+                    .block_on(client.call_method(
+                        destination.as_ref(),
+                        PATH,
+                        Some(INTERFACE),
+                        "Echo",
+                        black_box(&data),
+                    ))
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn signal_throughput(c: &mut Criterion) {
+    let runtime = Runtime::new();
+    let mut group = c.benchmark_group(format!("signal_throughput/{}", Runtime::NAME));
+    group.throughput(Throughput::Elements(SIGNALS));
+
+    for transport in Transport::all(&runtime) {
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .interface(INTERFACE)
+            .unwrap()
+            .member("Tick")
+            .unwrap()
+            .build();
+        let mut stream = runtime
+            .block_on(MessageStream::for_match_rule(
+                rule,
+                &transport.client,
+                Some(SIGNALS as usize),
+            ))
+            .unwrap();
+        let service = &transport.service;
+
+        group.bench_function(transport.name, |b| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    // Receive while emitting, not to fill the queues up.
+                    let emit = async {
+                        for i in 0..SIGNALS {
+                            service
+                                .emit_signal(None::<BusName<'_>>, PATH, INTERFACE, "Tick", &i)
+                                .await
+                                .unwrap();
+                        }
+                    };
+                    let receive = async {
+                        for _ in 0..SIGNALS {
+                            stream.next().await.unwrap().unwrap();
+                        }
+                    };
+                    join(emit, receive).await
+                })
+            })
+        });
+        // Removing the match rule of the stream needs the runtime.
+        runtime.block_on(async move { drop(stream) });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, round_trip, signal_throughput);
+criterion_main!(benches);
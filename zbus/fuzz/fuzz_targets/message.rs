@@ -0,0 +1,22 @@
+#![no_main]
+
+use zbus::{
+    zvariant::{Endian, Structure},
+    Message,
+};
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    let Ok(msg) = Message::try_from_bytes(data, vec![]) else {
+        return;
+    };
+    let _ = msg.to_string();
+    let _ = msg.body().deserialize::<Structure<'_>>();
+
+    // A valid message remains valid in the other byte order.
+    let endian = match msg.data().context().endian() {
+        Endian::Little => Endian::Big,
+        Endian::Big => Endian::Little,
+    };
+    let swapped = msg.to_endian(endian).unwrap();
+    Message::try_from_bytes(swapped.data().bytes(), vec![]).unwrap();
+});
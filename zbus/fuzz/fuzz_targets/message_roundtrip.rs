@@ -0,0 +1,12 @@
+#![no_main]
+
+use zbus::Message;
+
+libfuzzer_sys::fuzz_target!(|msg: Message| {
+    let parsed = Message::try_from_bytes(msg.data().bytes(), vec![]).unwrap();
+    assert_eq!(parsed.data().bytes(), msg.data().bytes());
+    assert_eq!(
+        parsed.header().primary().serial_num(),
+        msg.header().primary().serial_num()
+    );
+});
@@ -2,17 +2,21 @@
 use async_executor::Executor as AsyncExecutor;
 #[cfg(not(feature = "tokio"))]
 use async_task::Task as AsyncTask;
-#[cfg(not(feature = "tokio"))]
-use std::sync::Arc;
+use futures_util::future::{FutureExt, RemoteHandle};
 #[cfg(feature = "tokio")]
 use std::{future::pending, marker::PhantomData};
 use std::{
     future::Future,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 #[cfg(feature = "tokio")]
 use tokio::task::JoinHandle;
+use tracing::{info_span, trace_span, Instrument, Span};
+
+use super::{timer::Timer, Runtime};
 
 /// A wrapper around the underlying runtime/executor.
 ///
@@ -20,46 +24,83 @@ use tokio::task::JoinHandle;
 /// See [`crate::Connection::executor`] for an example of integration with external runtimes.
 ///
 /// **Note:** You can (and should) completely ignore this type when building with `tokio` feature
-/// enabled.
+/// enabled, or when the connection runs on a [`Runtime`] of its own.
 #[cfg(not(feature = "tokio"))]
 #[derive(Debug, Clone)]
 pub struct Executor<'a> {
     executor: Arc<AsyncExecutor<'a>>,
+    runtime: Option<Arc<dyn Runtime>>,
 }
 #[cfg(feature = "tokio")]
 #[derive(Debug, Clone)]
 pub struct Executor<'a> {
     phantom: PhantomData<&'a ()>,
+    runtime: Option<Arc<dyn Runtime>>,
 }
 
 impl<'a> Executor<'a> {
     /// Spawns a task onto the executor.
+    ///
+    /// The task runs in a `task` trace span named `name`, which is also the name of the tokio
+    /// task, shown by tools like `tokio-console`, when built with `tokio_unstable`.
+    ///
+    /// If the executor has a [`Runtime`], the task is spawned on it instead.
     #[doc(hidden)]
     pub fn spawn<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+        name: &str,
+    ) -> Task<T> {
+        self.spawn_in(future, name, trace_span!("task", name))
+    }
+
+    /// Spawns a task running for as long as the connection, e.g the socket reader.
+    ///
+    /// Unlike [`Executor::spawn`], the `task` span is at the info level, as there are only a few of
+    /// these tasks.
+    pub(crate) fn spawn_long_lived<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+        name: &str,
+    ) -> Task<T> {
+        self.spawn_in(future, name, info_span!("task", name))
+    }
+
+    fn spawn_in<T: Send + 'static>(
         &self,
         future: impl Future<Output = T> + Send + 'static,
         #[allow(unused)] name: &str,
+        span: Span,
     ) -> Task<T> {
+        let future = future.instrument(span);
+
+        if let Some(runtime) = &self.runtime {
+            let (future, handle) = future.remote_handle();
+            runtime.spawn(Box::pin(future), name);
+
+            return Task(Some(TaskInner::Remote(handle)));
+        }
+
         #[cfg(not(feature = "tokio"))]
         {
-            Task(Some(self.executor.spawn(future)))
+            Task(Some(TaskInner::Async(self.executor.spawn(future))))
         }
 
         #[cfg(feature = "tokio")]
         {
             #[cfg(tokio_unstable)]
             {
-                Task(Some(
+                Task(Some(TaskInner::Tokio(
                     tokio::task::Builder::new()
                         .name(name)
                         .spawn(future)
                         // SAFETY: Looking at the code, this call always returns an `Ok`.
                         .unwrap(),
-                ))
+                )))
             }
             #[cfg(not(tokio_unstable))]
             {
-                Task(Some(tokio::task::spawn(future)))
+                Task(Some(TaskInner::Tokio(tokio::task::spawn(future))))
             }
         }
     }
@@ -92,12 +133,13 @@ impl<'a> Executor<'a> {
         }
     }
 
-    /// Create a new `Executor`.
-    pub(crate) fn new() -> Self {
+    /// Create a new `Executor`, spawning the tasks on `runtime` if any.
+    pub(crate) fn new(runtime: Option<Arc<dyn Runtime>>) -> Self {
         #[cfg(not(feature = "tokio"))]
         {
             Self {
                 executor: Arc::new(AsyncExecutor::new()),
+                runtime,
             }
         }
 
@@ -105,10 +147,19 @@ impl<'a> Executor<'a> {
         {
             Self {
                 phantom: PhantomData,
+                runtime,
             }
         }
     }
 
+    /// Create a timer that fires after `duration`, using the runtime of the executor.
+    pub(crate) fn timer(&self, duration: Duration) -> Timer {
+        match &self.runtime {
+            Some(runtime) => Timer::from_future(runtime.sleep(duration)),
+            None => Timer::after(duration),
+        }
+    }
+
     /// Runs the executor until the given future completes.
     ///
     /// With `tokio` feature enabled, it just awaits on the `future`.
@@ -131,28 +182,29 @@ impl<'a> Executor<'a> {
 /// * it will be cancelled, rather than detached. For detaching, use the `detach` method.
 /// * errors from the task cancellation will will be ignored. If you need to know about task errors,
 ///   convert the task to a `FallibleTask` using the `fallible` method.
-#[cfg(not(feature = "tokio"))]
 #[doc(hidden)]
 #[derive(Debug)]
-pub struct Task<T>(Option<AsyncTask<T>>);
-#[cfg(feature = "tokio")]
-#[doc(hidden)]
+pub struct Task<T>(Option<TaskInner<T>>);
+
 #[derive(Debug)]
-pub struct Task<T>(Option<JoinHandle<T>>);
+enum TaskInner<T> {
+    #[cfg(not(feature = "tokio"))]
+    Async(AsyncTask<T>),
+    #[cfg(feature = "tokio")]
+    Tokio(JoinHandle<T>),
+    // A task spawned on a custom `Runtime`.
+    Remote(RemoteHandle<T>),
+}
 
 impl<T> Task<T> {
     /// Detaches the task to let it keep running in the background.
-    #[allow(unused_mut)]
-    #[allow(unused)]
     pub fn detach(mut self) {
-        #[cfg(not(feature = "tokio"))]
-        {
-            self.0.take().expect("async_task::Task is none").detach()
-        }
-
-        #[cfg(feature = "tokio")]
-        {
-            self.0.take().expect("tokio::task::JoinHandle is none");
+        match self.0.take().expect("task is none") {
+            #[cfg(not(feature = "tokio"))]
+            TaskInner::Async(task) => task.detach(),
+            #[cfg(feature = "tokio")]
+            TaskInner::Tokio(_) => (),
+            TaskInner::Remote(handle) => handle.forget(),
         }
     }
 }
@@ -167,26 +219,41 @@ where
     where
         F: FnOnce() -> T + Send + 'static,
     {
+        let name = format!("zbus: {name}");
+        let span = trace_span!("blocking task", name);
+        let f = move || span.in_scope(f);
+
         #[cfg(not(feature = "tokio"))]
         {
-            Self(Some(blocking::unblock(f)))
+            Self(Some(TaskInner::Async(blocking::unblock(f))))
         }
 
         #[cfg(feature = "tokio")]
         {
+            // Outside of any tokio runtime, e.g on a custom `Runtime`.
+            if tokio::runtime::Handle::try_current().is_err() {
+                let (future, handle) = async move { f() }.remote_handle();
+                std::thread::Builder::new()
+                    .name(name)
+                    .spawn(move || future.now_or_never())
+                    .expect("failed to spawn a thread");
+
+                return Self(Some(TaskInner::Remote(handle)));
+            }
+
             #[cfg(tokio_unstable)]
             {
-                Self(Some(
+                Self(Some(TaskInner::Tokio(
                     tokio::task::Builder::new()
-                        .name(name)
+                        .name(&name)
                         .spawn_blocking(f)
                         // SAFETY: Looking at the code, this call always returns an `Ok`.
                         .unwrap(),
-                ))
+                )))
             }
             #[cfg(not(tokio_unstable))]
             {
-                Self(Some(tokio::task::spawn_blocking(f)))
+                Self(Some(TaskInner::Tokio(tokio::task::spawn_blocking(f))))
             }
         }
     }
@@ -196,33 +263,25 @@ impl<T> Drop for Task<T> {
     fn drop(&mut self) {
         #[cfg(feature = "tokio")]
         {
-            if let Some(join_handle) = self.0.take() {
+            if let Some(TaskInner::Tokio(join_handle)) = self.0.take() {
                 join_handle.abort();
             }
         }
     }
 }
 
-impl<T> Future for Task<T> {
+impl<T: 'static> Future for Task<T> {
     type Output = T;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        #[cfg(not(feature = "tokio"))]
-        {
-            Pin::new(&mut self.get_mut().0.as_mut().expect("async_task::Task is none")).poll(cx)
-        }
-
-        #[cfg(feature = "tokio")]
-        {
-            Pin::new(
-                &mut self
-                    .get_mut()
-                    .0
-                    .as_mut()
-                    .expect("tokio::task::JoinHandle is none"),
-            )
-            .poll(cx)
-            .map(|r| r.expect("tokio::task::JoinHandle error"))
+        match self.get_mut().0.as_mut().expect("task is none") {
+            #[cfg(not(feature = "tokio"))]
+            TaskInner::Async(task) => Pin::new(task).poll(cx),
+            #[cfg(feature = "tokio")]
+            TaskInner::Tokio(join_handle) => Pin::new(join_handle)
+                .poll(cx)
+                .map(|r| r.expect("tokio::task::JoinHandle error")),
+            TaskInner::Remote(handle) => Pin::new(handle).poll(cx),
         }
     }
 }
@@ -78,3 +78,53 @@ pub async fn metadata(path: impl AsRef<Path>) -> Result<Metadata> {
         tokio::fs::metadata(path).await
     }
 }
+
+/// A file read asynchronously, through blocking reads on a thread pool.
+///
+/// This works with any kind of file, including the ones that can't be polled for readiness, such
+/// as regular files.
+#[cfg(unix)]
+#[derive(Debug)]
+pub struct FileReader(
+    #[cfg(not(feature = "tokio"))] blocking::Unblock<std::fs::File>,
+    #[cfg(feature = "tokio")] tokio::fs::File,
+);
+
+#[cfg(unix)]
+impl FileReader {
+    pub fn new(file: std::fs::File) -> Self {
+        #[cfg(not(feature = "tokio"))]
+        {
+            Self(blocking::Unblock::new(file))
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            Self(tokio::fs::File::from_std(file))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl futures_util::io::AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<Result<usize>> {
+        #[cfg(not(feature = "tokio"))]
+        {
+            futures_util::io::AsyncRead::poll_read(Pin::new(&mut self.get_mut().0), cx, buf)
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            let mut buf = tokio::io::ReadBuf::new(buf);
+            match tokio::io::AsyncRead::poll_read(Pin::new(&mut self.get_mut().0), cx, &mut buf) {
+                Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                Poll::Pending => Poll::Pending,
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use futures_core::future::BoxFuture;
+use glib::{ControlFlow, IOCondition, MainContext, Priority, Source};
+use std::{
+    future::Future,
+    io,
+    os::fd::RawFd,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll, Waker},
+    time::Duration,
+};
+
+use super::{AsyncFd, Runtime};
+
+/// A [`Runtime`] running the tasks, timers and I/O on a GLib main context.
+#[derive(Debug)]
+pub(crate) struct MainContextRuntime {
+    context: MainContext,
+}
+
+impl MainContextRuntime {
+    pub(crate) fn new(context: MainContext) -> Self {
+        Self { context }
+    }
+}
+
+impl Runtime for MainContextRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>, _name: &str) {
+        // Dropping the handle detaches the task.
+        drop(self.context.spawn(future));
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let watch = Watch::new(&self.context, |state| {
+            glib::timeout_source_new(duration, Some("zbus timer"), Priority::DEFAULT, move || {
+                Watch::fire(&state);
+
+                ControlFlow::Break
+            })
+        });
+
+        Box::pin(Sleep(watch))
+    }
+
+    fn async_fd(&self, fd: RawFd) -> io::Result<Box<dyn AsyncFd>> {
+        Ok(Box::new(MainContextFd {
+            fd,
+            context: self.context.clone(),
+            readable: Mutex::new(None),
+            writable: Mutex::new(None),
+        }))
+    }
+}
+
+/// A file descriptor, whose readiness is watched by unix fd sources.
+#[derive(Debug)]
+struct MainContextFd {
+    fd: RawFd,
+    context: MainContext,
+    readable: Mutex<Option<Watch>>,
+    writable: Mutex<Option<Watch>>,
+}
+
+impl MainContextFd {
+    fn poll_condition(
+        &self,
+        watch: &Mutex<Option<Watch>>,
+        condition: IOCondition,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        let mut watch = watch.lock().expect("lock poisoned");
+        let fd = self.fd;
+        let pending = watch.get_or_insert_with(|| {
+            Watch::new(&self.context, |state| {
+                // Errors and hang ups are reported by the next I/O operation.
+                let condition = condition | IOCondition::ERR | IOCondition::HUP;
+                glib::unix_fd_source_new(
+                    fd,
+                    condition,
+                    Some("zbus socket"),
+                    Priority::DEFAULT,
+                    move |_, _| {
+                        Watch::fire(&state);
+
+                        ControlFlow::Break
+                    },
+                )
+            })
+        });
+        ready!(pending.poll(cx));
+        *watch = None;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncFd for MainContextFd {
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_condition(&self.readable, IOCondition::IN, cx)
+    }
+
+    fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.poll_condition(&self.writable, IOCondition::OUT, cx)
+    }
+}
+
+struct Sleep(Watch);
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll(cx)
+    }
+}
+
+/// A source attached to a main context, firing once.
+///
+/// The source is destroyed on drop.
+#[derive(Debug)]
+struct Watch {
+    source: Source,
+    state: Arc<Mutex<WatchState>>,
+}
+
+#[derive(Debug, Default)]
+struct WatchState {
+    fired: bool,
+    waker: Option<Waker>,
+}
+
+impl Watch {
+    /// Attach the source created by `new_source` to `context`.
+    ///
+    /// The source must call [`Watch::fire`] with the given state when it fires.
+    fn new<F>(context: &MainContext, new_source: F) -> Self
+    where
+        F: FnOnce(Arc<Mutex<WatchState>>) -> Source,
+    {
+        let state = Arc::new(Mutex::new(WatchState::default()));
+        let source = new_source(state.clone());
+        source.attach(Some(context));
+
+        Self { source, state }
+    }
+
+    fn fire(state: &Mutex<WatchState>) {
+        let mut state = state.lock().expect("lock poisoned");
+        state.fired = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.lock().expect("lock poisoned");
+        if state.fired {
+            return Poll::Ready(());
+        }
+        state.waker = Some(cx.waker().clone());
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.source.destroy();
+    }
+}
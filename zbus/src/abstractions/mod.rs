@@ -8,6 +8,13 @@ mod async_drop;
 pub(crate) mod async_lock;
 pub use async_drop::*;
 pub(crate) mod file;
+mod runtime;
+pub use runtime::*;
+#[cfg(all(unix, feature = "glib"))]
+pub(crate) mod main_context;
+pub(crate) mod timer;
+#[cfg(target_family = "wasm")]
+pub(crate) mod wasm;
 
 // Not macOS-specific itself but only used on macOS.
 #[cfg(target_os = "macos")]
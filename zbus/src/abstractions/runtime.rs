@@ -0,0 +1,130 @@
+use futures_core::future::BoxFuture;
+use std::{fmt::Debug, time::Duration};
+#[cfg(unix)]
+use std::{
+    io,
+    os::fd::RawFd,
+    task::{Context, Poll},
+};
+
+/// An async runtime, running the tasks and I/O of connections.
+///
+/// By default, connections use the runtime selected by the crate features, i.e `tokio` if the
+/// `tokio` feature is enabled and `async-io` otherwise. Implement this trait, and pass it to
+/// [`crate::connection::Builder::runtime`], to run a connection on any other executor and
+/// reactor. The tasks, timers and unix sockets of the connection then all go through it.
+///
+/// # Limitations
+///
+/// This doesn't make zbus independent of the crate features: one of the `async-io` and `tokio`
+/// features is still required, and its runtime is still used for the rest:
+///
+/// * TCP and VSOCK sockets.
+/// * Blocking tasks, e.g the methods of a
+///   [`BlockingInterface`](crate::object_server::BlockingInterface).
+/// * The [`blocking`](crate::blocking) API, which blocks on futures through it.
+///
+/// # Example
+///
+/// A runtime spawning the tasks of connections, and driving their I/O, on a given tokio runtime,
+/// without the `tokio` feature:
+///
+/// ```
+/// # #[cfg(unix)]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::{
+///     io,
+///     os::fd::RawFd,
+///     task::{ready, Context, Poll},
+///     time::Duration,
+/// };
+///
+/// use futures_util::{future::BoxFuture, FutureExt};
+/// use tokio::runtime::Handle;
+/// use zbus::{connection, fdo::DBusProxy, AsyncFd, Runtime};
+///
+/// #[derive(Debug)]
+/// struct Tokio(Handle);
+///
+/// impl Runtime for Tokio {
+///     fn spawn(&self, future: BoxFuture<'static, ()>, _name: &str) {
+///         self.0.spawn(future);
+///     }
+///
+///     fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+///         let _guard = self.0.enter();
+///
+///         tokio::time::sleep(duration).boxed()
+///     }
+///
+///     fn async_fd(&self, fd: RawFd) -> io::Result<Box<dyn AsyncFd>> {
+///         let _guard = self.0.enter();
+///
+///         Ok(Box::new(Fd(tokio::io::unix::AsyncFd::new(fd)?)))
+///     }
+/// }
+///
+/// #[derive(Debug)]
+/// struct Fd(tokio::io::unix::AsyncFd<RawFd>);
+///
+/// impl AsyncFd for Fd {
+///     fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+///         ready!(self.0.poll_read_ready(cx))?.clear_ready();
+///
+///         Poll::Ready(Ok(()))
+///     }
+///
+///     fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+///         ready!(self.0.poll_write_ready(cx))?.clear_ready();
+///
+///         Poll::Ready(Ok(()))
+///     }
+/// }
+///
+/// let runtime = tokio::runtime::Runtime::new()?;
+/// runtime.block_on(async {
+///     let conn = connection::Builder::session()?
+///         .runtime(Tokio(Handle::current()))
+///         .build()
+///         .await?;
+///     let id = DBusProxy::new(&conn).await?.get_id().await?;
+///     println!("Connected to the bus {id}");
+///
+///     Ok::<_, zbus::Error>(())
+/// })?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(unix))]
+/// # fn main() {}
+/// ```
+pub trait Runtime: Debug + Send + Sync + 'static {
+    /// Spawn a task, running `future` to completion in the background.
+    ///
+    /// The `name` describes the task, e.g `zbus: socket reader (session)`, for diagnostics.
+    fn spawn(&self, future: BoxFuture<'static, ()>, name: &str);
+
+    /// Create a future that completes after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Register the nonblocking socket `fd`, to wait for its readiness.
+    ///
+    /// The socket stays open for as long as the returned [`AsyncFd`] exists.
+    #[cfg(unix)]
+    fn async_fd(&self, fd: RawFd) -> io::Result<Box<dyn AsyncFd>>;
+}
+
+/// A file descriptor registered with a [`Runtime`], to wait for its readiness.
+#[cfg(unix)]
+pub trait AsyncFd: Debug + Send + Sync {
+    /// Poll for the file descriptor to become readable.
+    ///
+    /// This is only called once reading from it would block, so it's not to be considered readable
+    /// anymore. Only the waker of the last call needs to be woken up.
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+
+    /// Poll for the file descriptor to become writable.
+    ///
+    /// This is only called once writing to it would block, so it's not to be considered writable
+    /// anymore. Only the waker of the last call needs to be woken up.
+    fn poll_writable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+}
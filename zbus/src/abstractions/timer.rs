@@ -0,0 +1,66 @@
+use futures_core::future::BoxFuture;
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// A future that completes after a given duration, using the appropriate timer for the enabled
+/// runtime.
+pub(crate) struct Timer(Inner);
+
+enum Inner {
+    #[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
+    AsyncIo(async_io::Timer),
+    #[cfg(feature = "tokio")]
+    Tokio(Pin<Box<tokio::time::Sleep>>),
+    // The sleep of a custom `Runtime`.
+    Future(BoxFuture<'static, ()>),
+}
+
+impl Timer {
+    /// Create a timer that fires after `duration`.
+    pub(crate) fn after(duration: Duration) -> Self {
+        #[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
+        {
+            Self(Inner::AsyncIo(async_io::Timer::after(duration)))
+        }
+
+        #[cfg(target_family = "wasm")]
+        {
+            Self(Inner::Future(super::wasm::sleep(duration)))
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            Self(Inner::Tokio(Box::pin(tokio::time::sleep(duration))))
+        }
+    }
+
+    /// Create a timer that fires when `sleep` completes.
+    pub(crate) fn from_future(sleep: BoxFuture<'static, ()>) -> Self {
+        Self(Inner::Future(sleep))
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timer").finish_non_exhaustive()
+    }
+}
+
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        match &mut self.get_mut().0 {
+            #[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
+            Inner::AsyncIo(timer) => Pin::new(timer).poll(cx).map(|_| ()),
+            #[cfg(feature = "tokio")]
+            Inner::Tokio(sleep) => sleep.as_mut().poll(cx),
+            Inner::Future(sleep) => sleep.as_mut().poll(cx),
+        }
+    }
+}
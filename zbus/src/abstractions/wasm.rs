@@ -0,0 +1,42 @@
+use futures_channel::oneshot;
+use futures_core::future::BoxFuture;
+use futures_util::FutureExt;
+use std::time::Duration;
+use wasm_bindgen::{closure::Closure, prelude::wasm_bindgen, JsValue};
+
+use super::Runtime;
+
+#[wasm_bindgen]
+extern "C" {
+    // The global `setTimeout`, available in both windows and workers.
+    #[wasm_bindgen(js_name = setTimeout)]
+    fn set_timeout(handler: &JsValue, timeout: i32) -> JsValue;
+}
+
+/// The [`Runtime`] of the JS event loop, used by the connections on wasm by default.
+#[derive(Debug)]
+pub(crate) struct WasmRuntime;
+
+impl Runtime for WasmRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>, _name: &str) {
+        wasm_bindgen_futures::spawn_local(future);
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        sleep(duration)
+    }
+}
+
+/// Create a future that completes after `duration`, through a JS timeout.
+pub(crate) fn sleep(duration: Duration) -> BoxFuture<'static, ()> {
+    let (sender, receiver) = oneshot::channel();
+    let handler = Closure::once_into_js(move || {
+        // The future may have been dropped already.
+        let _ = sender.send(());
+    });
+    // Longer timeouts overflow and fire immediately.
+    let timeout = i32::try_from(duration.as_millis()).unwrap_or(i32::MAX);
+    set_timeout(&handler, timeout);
+
+    receiver.map(|_| ()).boxed()
+}
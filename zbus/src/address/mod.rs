@@ -80,6 +80,11 @@ impl Address {
 
                 #[cfg(target_os = "macos")]
                 return Self::from_str("launchd:env=DBUS_LAUNCHD_SESSION_BUS_SOCKET");
+
+                #[cfg(target_family = "wasm")]
+                return Err(Error::Address(
+                    "No session bus on wasm, connect through a bridge instead".into(),
+                ));
             }
         }
     }
@@ -99,6 +104,11 @@ impl Address {
 
                 #[cfg(target_os = "macos")]
                 return Self::from_str("launchd:env=DBUS_LAUNCHD_SESSION_BUS_SOCKET");
+
+                #[cfg(target_family = "wasm")]
+                return Err(Error::Address(
+                    "No system bus on wasm, connect through a bridge instead".into(),
+                ));
             }
         }
     }
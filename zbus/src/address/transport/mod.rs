@@ -5,10 +5,10 @@
 #[cfg(windows)]
 use crate::win32::windows_autolaunch_bus_address;
 use crate::{Error, Result};
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use async_io::Async;
 use std::collections::HashMap;
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use std::net::TcpStream;
 #[cfg(unix)]
 use std::os::unix::net::{SocketAddr, UnixStream};
@@ -80,6 +80,13 @@ pub enum Transport {
 }
 
 impl Transport {
+    #[cfg(target_family = "wasm")]
+    pub(super) async fn connect(self) -> Result<Stream> {
+        // There are no sockets on wasm, connections go through a `Bridge` instead.
+        Err(Error::Unsupported)
+    }
+
+    #[cfg(not(target_family = "wasm"))]
     #[cfg_attr(any(target_os = "macos", windows), async_recursion::async_recursion)]
     pub(super) async fn connect(self) -> Result<Stream> {
         match self {
@@ -113,27 +120,15 @@ impl Transport {
                     "unix stream connection",
                 )
                 .await?;
-                #[cfg(not(feature = "tokio"))]
+                #[cfg(any(unix, not(feature = "tokio")))]
                 {
-                    Async::new(stream)
-                        .map(Stream::Unix)
-                        .map_err(|e| Error::InputOutput(e.into()))
+                    Ok(Stream::Unix(stream))
                 }
 
-                #[cfg(feature = "tokio")]
+                #[cfg(all(not(unix), feature = "tokio"))]
                 {
-                    #[cfg(unix)]
-                    {
-                        tokio::net::UnixStream::from_std(stream)
-                            .map(Stream::Unix)
-                            .map_err(|e| Error::InputOutput(e.into()))
-                    }
-
-                    #[cfg(not(unix))]
-                    {
-                        let _ = stream;
-                        Err(Error::Unsupported)
-                    }
+                    let _ = stream;
+                    Err(Error::Unsupported)
                 }
             }
             #[cfg(all(feature = "vsock", not(feature = "tokio")))]
@@ -230,10 +225,11 @@ impl Transport {
     }
 }
 
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 #[derive(Debug)]
 pub(crate) enum Stream {
-    Unix(Async<UnixStream>),
+    // Registering it with the runtime is left to the connection builder.
+    Unix(UnixStream),
     Tcp(Async<TcpStream>),
     #[cfg(feature = "vsock")]
     Vsock(Async<VsockStream>),
@@ -242,13 +238,18 @@ pub(crate) enum Stream {
 #[cfg(feature = "tokio")]
 #[derive(Debug)]
 pub(crate) enum Stream {
+    // Registering it with the runtime is left to the connection builder.
     #[cfg(unix)]
-    Unix(tokio::net::UnixStream),
+    Unix(UnixStream),
     Tcp(TcpStream),
     #[cfg(feature = "tokio-vsock")]
     Vsock(VsockStream),
 }
 
+#[cfg(target_family = "wasm")]
+#[derive(Debug)]
+pub(crate) enum Stream {}
+
 fn decode_hex(c: char) -> Result<u8> {
     match c {
         '0'..='9' => Ok(c as u8 - b'0'),
@@ -1,8 +1,8 @@
 use super::encode_percents;
 use crate::{Error, Result};
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use async_io::Async;
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
 use std::{
     collections::HashMap,
@@ -127,7 +127,7 @@ impl Tcp {
         })
     }
 
-    #[cfg(not(feature = "tokio"))]
+    #[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
     pub(super) async fn connect(self) -> Result<Async<TcpStream>> {
         let addrs = crate::Task::spawn_blocking(
             move || -> Result<Vec<SocketAddr>> {
@@ -98,7 +98,7 @@ impl Display for UnixSocket {
                 encode_percents(f, path.as_bytes())?;
             }
 
-            #[cfg(windows)]
+            #[cfg(not(unix))]
             write!(f, "{}", path.to_str().ok_or(std::fmt::Error)?)?;
 
             Ok(())
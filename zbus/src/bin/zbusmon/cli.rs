@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+/// Monitor the messages going through a D-Bus message bus.
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+pub struct Args {
+    /// Monitor the system bus.
+    #[clap(long, conflicts_with_all = ["session", "address"])]
+    pub system: bool,
+
+    /// Monitor the session bus. This is the default.
+    #[clap(long, conflicts_with = "address")]
+    pub session: bool,
+
+    /// Monitor the bus at the specified address.
+    #[clap(long)]
+    pub address: Option<String>,
+
+    /// The format to print the messages in.
+    #[clap(short, long, value_enum, default_value_t = Format::Text)]
+    pub format: Format,
+
+    /// Write the messages to the specified file, instead of stdout.
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Only monitor the messages matching any of these match rules, e.g
+    /// "type='signal',interface='org.freedesktop.DBus'". All messages are monitored by default.
+    pub rules: Vec<String>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A human-readable description of each message, and its body.
+    Text,
+    /// A JSON object per line for each message.
+    Json,
+    /// A pcapng capture, as read by Wireshark.
+    Pcapng,
+}
@@ -0,0 +1,190 @@
+#![deny(rust_2018_idioms)]
+
+//! A D-Bus monitor, printing the messages going through a bus.
+//!
+//! It relies on [`MonitoringProxy::become_monitor`], and so needs a bus implementing the
+//! `org.freedesktop.DBus.Monitoring` interface. Monitoring the system bus usually requires to be
+//! root.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    time::{Duration, SystemTime},
+};
+
+use clap::Parser;
+use serde_json::{Map, Value as JsonValue};
+use zbus::{
+    blocking::{connection, fdo::MonitoringProxy},
+    capture::PcapngWriter,
+    message::{Body, Type},
+    zvariant::{Structure, Value},
+    MatchRule, Message,
+};
+
+mod cli;
+use cli::{Args, Format};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let rules = args
+        .rules
+        .iter()
+        .map(|rule| MatchRule::try_from(rule.as_str()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let builder = match &args.address {
+        Some(address) => connection::Builder::address(address.as_str())?,
+        None if args.system => connection::Builder::system()?,
+        None => connection::Builder::session()?,
+    };
+    let conn = builder.build()?;
+    let messages = MonitoringProxy::new(&conn)?.become_monitor(&rules)?;
+
+    let output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut output = match args.format {
+        Format::Text => Output::Text(output),
+        Format::Json => Output::Json(output),
+        Format::Pcapng => Output::Pcapng(PcapngWriter::new(output)?),
+    };
+
+    for msg in messages {
+        let msg = msg?;
+        match output.write(SystemTime::now(), &msg) {
+            Ok(()) => (),
+            // The reader went away, e.g `zbusmon | head`.
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Ok(())
+}
+
+enum Output {
+    Text(Box<dyn Write>),
+    Json(Box<dyn Write>),
+    Pcapng(PcapngWriter<Box<dyn Write>>),
+}
+
+impl Output {
+    fn write(&mut self, time: SystemTime, msg: &Message) -> io::Result<()> {
+        let timestamp = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        match self {
+            Output::Text(w) => {
+                write_text(w, timestamp, msg)?;
+                w.flush()
+            }
+            Output::Json(w) => {
+                writeln!(w, "{}", to_json(timestamp, msg))?;
+                w.flush()
+            }
+            Output::Pcapng(w) => w.write_at(msg, time),
+        }
+    }
+}
+
+// Write `msg` on a line, followed by its body on the next one.
+fn write_text(w: &mut dyn Write, timestamp: Duration, msg: &Message) -> io::Result<()> {
+    let hdr = msg.header();
+    write!(
+        w,
+        "{}.{:06} {} serial={}",
+        timestamp.as_secs(),
+        timestamp.subsec_micros(),
+        type_name(msg.message_type()),
+        hdr.primary().serial_num(),
+    )?;
+    if let Some(serial) = hdr.reply_serial() {
+        write!(w, " reply_serial={serial}")?;
+    }
+    if let Some(sender) = hdr.sender() {
+        write!(w, " sender={sender}")?;
+    }
+    if let Some(destination) = hdr.destination() {
+        write!(w, " destination={destination}")?;
+    }
+    if let Some(path) = hdr.path() {
+        write!(w, " path={path}")?;
+    }
+    if let Some(interface) = hdr.interface() {
+        write!(w, " interface={interface}")?;
+    }
+    if let Some(member) = hdr.member() {
+        write!(w, " member={member}")?;
+    }
+    if let Some(error_name) = hdr.error_name() {
+        write!(w, " error_name={error_name}")?;
+    }
+    writeln!(w)?;
+
+    let body = msg.body();
+    if !has_args(&body) {
+        return Ok(());
+    }
+    match body.deserialize::<Structure<'_>>() {
+        Ok(args) => writeln!(w, "  {args}"),
+        Err(e) => writeln!(w, "  <failed to decode the body: {e}>"),
+    }
+}
+
+// Describe `msg` as a JSON object.
+//
+// The body is an array of the arguments, see `zvariant::Value::to_json` for how they're mapped.
+fn to_json(timestamp: Duration, msg: &Message) -> JsonValue {
+    let hdr = msg.header();
+    let mut object = Map::new();
+    object.insert("timestamp".into(), timestamp.as_secs_f64().into());
+    object.insert("type".into(), type_name(msg.message_type()).into());
+    object.insert("serial".into(), hdr.primary().serial_num().get().into());
+    let mut insert = |key: &str, value: Option<String>| {
+        if let Some(value) = value {
+            object.insert(key.into(), value.into());
+        }
+    };
+    insert("sender", hdr.sender().map(|s| s.to_string()));
+    insert("destination", hdr.destination().map(|d| d.to_string()));
+    insert("path", hdr.path().map(|p| p.to_string()));
+    insert("interface", hdr.interface().map(|i| i.to_string()));
+    insert("member", hdr.member().map(|m| m.to_string()));
+    insert("error_name", hdr.error_name().map(|e| e.to_string()));
+    insert("signature", hdr.signature().map(|s| s.to_string()));
+    if let Some(serial) = hdr.reply_serial() {
+        object.insert("reply_serial".into(), serial.get().into());
+    }
+
+    let body = msg.body();
+    let args = if has_args(&body) {
+        body.deserialize::<Structure<'_>>()
+            .map_err(|e| e.to_string())
+            .and_then(|args| Value::from(args).to_json().map_err(|e| e.to_string()))
+    } else {
+        Ok(JsonValue::Array(vec![]))
+    };
+    match args {
+        Ok(args) => object.insert("body".into(), args),
+        Err(e) => object.insert("body_error".into(), e.into()),
+    };
+
+    JsonValue::Object(object)
+}
+
+// Whether the message has any arguments.
+fn has_args(body: &Body) -> bool {
+    body.signature().is_some_and(|s| !s.is_empty())
+}
+
+fn type_name(ty: Type) -> &'static str {
+    match ty {
+        Type::MethodCall => "method_call",
+        Type::MethodReturn => "method_return",
+        Type::Error => "error",
+        Type::Signal => "signal",
+    }
+}
@@ -1,5 +1,5 @@
 use static_assertions::assert_impl_all;
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use std::net::TcpStream;
 #[cfg(all(unix, not(feature = "tokio")))]
 use std::os::unix::net::UnixStream;
@@ -16,7 +16,7 @@ use zvariant::{ObjectPath, Str};
 use crate::Guid;
 use crate::{
     address::Address, blocking::Connection, connection::socket::BoxedSplit, names::WellKnownName,
-    object_server::Interface, utils::block_on, AuthMechanism, Error, Result,
+    object_server::Interface, utils::block_on, AuthMechanism, DBusError, Error, Result,
 };
 
 /// A builder for [`zbus::blocking::Connection`].
@@ -58,7 +58,7 @@ impl<'a> Builder<'a> {
     /// is not available when the `tokio` feature is enabled and building for Windows target.
     ///
     /// [tuds]: https://github.com/tokio-rs/tokio/issues/2201
-    #[cfg(any(unix, not(feature = "tokio")))]
+    #[cfg(any(unix, all(windows, not(feature = "tokio"))))]
     pub fn unix_stream(stream: UnixStream) -> Self {
         Self(crate::connection::Builder::unix_stream(stream))
     }
@@ -68,6 +68,7 @@ impl<'a> Builder<'a> {
     /// If the default `async-io` feature is disabled, this method will expect
     /// [`tokio::net::TcpStream`](https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html)
     /// argument.
+    #[cfg(not(target_family = "wasm"))]
     pub fn tcp_stream(stream: TcpStream) -> Self {
         Self(crate::connection::Builder::tcp_stream(stream))
     }
@@ -77,6 +78,13 @@ impl<'a> Builder<'a> {
         Self(crate::connection::Builder::socket(socket))
     }
 
+    /// Create a builder for a connection to an in-process bus.
+    ///
+    /// See [`crate::connection::Builder::mock`] for details.
+    pub fn mock(bus: &crate::test::MockBus) -> Self {
+        Self(crate::connection::Builder::mock(bus))
+    }
+
     /// Specify the mechanisms to use during authentication.
     pub fn auth_mechanisms(self, auth_mechanisms: &[AuthMechanism]) -> Self {
         Self(self.0.auth_mechanisms(auth_mechanisms))
@@ -170,6 +178,23 @@ impl<'a> Builder<'a> {
         self.0.serve_at(path, iface).map(Self)
     }
 
+    /// Register an application error type.
+    ///
+    /// See [`zbus::Connection::register_error`] for details.
+    pub fn register_error<E>(self) -> Self
+    where
+        E: DBusError + From<Error> + std::error::Error + Send + Sync + 'static,
+    {
+        Self(self.0.register_error::<E>())
+    }
+
+    /// Intercept the method calls of the connection with `interceptor`.
+    ///
+    /// See [`crate::connection::Builder::intercept`] for details.
+    pub fn intercept(self, interceptor: crate::test::Interceptor) -> Self {
+        Self(self.0.intercept(interceptor))
+    }
+
     /// Register a well-known name for this connection on the bus.
     ///
     /// This is similar to [`zbus::blocking::Connection::request_name`], except the name is
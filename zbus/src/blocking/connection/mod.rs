@@ -9,10 +9,12 @@ use zvariant::ObjectPath;
 
 use crate::{
     blocking::ObjectServer,
+    connection::SharedMatchRule,
     fdo::{ConnectionCredentials, RequestNameFlags, RequestNameReply},
     message::Message,
+    object_server::SignalContext,
     utils::block_on,
-    DBusError, Error, Result,
+    DBusError, Error, MatchRule, Result,
 };
 
 mod builder;
@@ -169,6 +171,16 @@ impl Connection {
         block_on(self.inner.reply_dbus_error(call, err))
     }
 
+    /// Register an application error type.
+    ///
+    /// See [`crate::Connection::register_error`] for details.
+    pub fn register_error<E>(&self)
+    where
+        E: DBusError + From<Error> + std::error::Error + Send + Sync + 'static,
+    {
+        self.inner.register_error::<E>()
+    }
+
     /// Register a well-known name for this service on the bus.
     ///
     /// Blocking version of [`crate::Connection::request_name`]. See docs there for more details
@@ -213,6 +225,17 @@ impl Connection {
         block_on(self.inner.release_name(well_known_name))
     }
 
+    /// Add a match rule that other match rules can share on the bus.
+    ///
+    /// See [`crate::Connection::add_shared_match`] for details.
+    pub fn add_shared_match<'m, R>(&self, rule: R) -> Result<SharedMatchRule>
+    where
+        R: TryInto<MatchRule<'m>>,
+        R::Error: Into<Error>,
+    {
+        block_on(self.inner.add_shared_match(rule))
+    }
+
     /// Checks if `self` is a connection to a message bus.
     ///
     /// This will return `false` for p2p connections.
@@ -220,6 +243,13 @@ impl Connection {
         self.inner.is_bus()
     }
 
+    /// Whether the connection became a monitor.
+    ///
+    /// See [`crate::Connection::is_monitor`] for details.
+    pub fn is_monitor(&self) -> bool {
+        self.inner.is_monitor()
+    }
+
     /// Get a reference to the associated [`ObjectServer`].
     ///
     /// The `ObjectServer` is created on-demand.
@@ -227,6 +257,17 @@ impl Connection {
         self.inner.sync_object_server(true, None)
     }
 
+    /// Create a [`SignalContext`] for emitting signals from the object at `path`.
+    ///
+    /// See [`crate::Connection::signal_context`] for details.
+    pub fn signal_context<'p, P>(&self, path: P) -> Result<SignalContext<'p>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        self.inner.signal_context(path)
+    }
+
     /// Get a reference to the underlying async Connection.
     pub fn inner(&self) -> &crate::Connection {
         &self.inner
@@ -3,39 +3,359 @@
 //! Provides blocking versions of the proxy types in [`zbus::fdo`] module.
 
 use enumflags2::BitFlags;
+use futures_util::StreamExt;
 use static_assertions::assert_impl_all;
 use std::collections::HashMap;
 use zbus_names::{
-    BusName, InterfaceName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName, UniqueName,
-    WellKnownName,
+    BusName, InterfaceName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName, OwnedWellKnownName,
+    UniqueName, WellKnownName,
 };
 use zvariant::{ObjectPath, Optional, OwnedValue, Value};
 
 use crate::{
+    blocking::Connection,
     fdo::{
-        ConnectionCredentials, ManagedObjects, ReleaseNameReply, RequestNameFlags,
-        RequestNameReply, Result,
+        self, ActivationEnvironment, BusFeature, BusInterface, BusStats, ConnectionCredentials,
+        ConnectionMatchRules, ConnectionStats, ManagedObjects, NameEvent, NameRequestState,
+        ObjectChange, ReleaseNameReply, RequestNameFlags, RequestNameReply, Result,
     },
-    proxy, OwnedGuid,
+    message::Message,
+    proxy,
+    utils::block_on,
+    MatchRule, OwnedGuid,
 };
 
+#[cfg(feature = "polkit")]
+pub mod polkit;
+
 gen_introspectable_proxy!(false, true);
 assert_impl_all!(IntrospectableProxy<'_>: Send, Sync, Unpin);
 
+#[cfg(feature = "xml")]
+impl<'p> IntrospectableProxy<'p> {
+    /// Introspect the object and all the objects below it.
+    ///
+    /// See [`crate::fdo::IntrospectableProxy::introspect_tree`] for details.
+    pub fn introspect_tree(&self) -> Result<fdo::IntrospectionTree> {
+        let proxy = fdo::IntrospectableProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.introspect_tree())
+    }
+}
+
 gen_properties_proxy!(false, true);
 assert_impl_all!(PropertiesProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> PropertiesProxy<'p> {
+    /// Get all the properties of the interface `interface_name`, deserialized into `T`.
+    ///
+    /// See [`crate::fdo::PropertiesProxy::get_all_as`] for details.
+    pub fn get_all_as<T>(&self, interface_name: InterfaceName<'_>) -> Result<T>
+    where
+        T: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        let proxy = fdo::PropertiesProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.get_all_as(interface_name))
+    }
+}
+
 gen_object_manager_proxy!(false, true);
 assert_impl_all!(ObjectManagerProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> ObjectManagerProxy<'p> {
+    /// Keep a local copy of the managed objects, and receive the changes to them.
+    ///
+    /// See [`crate::fdo::ObjectManagerProxy::receive_object_changes`] for details.
+    pub fn receive_object_changes(&self) -> Result<ObjectChangesIterator> {
+        let proxy = fdo::ObjectManagerProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.receive_object_changes()).map(|c| ObjectChangesIterator(Some(c)))
+    }
+}
+
+/// A blocking wrapper of [`crate::fdo::ObjectChanges`].
+#[derive(Debug)]
+pub struct ObjectChangesIterator(
+    // Wrapped in an `Option` to drop it in a `block_on` call, as its `Drop` impl spawns a task
+    // deregistering the match rule, which needs a runtime context in case of tokio.
+    Option<fdo::ObjectChanges>,
+);
+
+assert_impl_all!(ObjectChangesIterator: Send, Sync, Unpin);
+
+impl ObjectChangesIterator {
+    /// The managed objects, as of the last yielded change.
+    pub fn objects(&self) -> &ManagedObjects {
+        self.0.as_ref().expect("Inner stream is `None`").objects()
+    }
+}
+
+impl Iterator for ObjectChangesIterator {
+    type Item = ObjectChange;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.0.as_mut()?.next())
+    }
+}
+
+impl Drop for ObjectChangesIterator {
+    fn drop(&mut self) {
+        block_on(async {
+            self.0.take();
+        });
+    }
+}
+
 gen_peer_proxy!(false, true);
 assert_impl_all!(PeerProxy<'_>: Send, Sync, Unpin);
 
 gen_monitoring_proxy!(false, true);
 assert_impl_all!(MonitoringProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> MonitoringProxy<'p> {
+    /// Converts the connection into a monitor connection, and returns an iterator over the
+    /// monitored messages.
+    ///
+    /// See [`crate::fdo::MonitoringProxy::become_monitor`] for details.
+    pub fn become_monitor(self, match_rules: &[MatchRule<'_>]) -> Result<MonitorIterator> {
+        let proxy = fdo::MonitoringProxy::from(self.into_inner().into_inner());
+
+        block_on(proxy.become_monitor(match_rules)).map(MonitorIterator)
+    }
+}
+
+/// A blocking wrapper of [`crate::fdo::MonitorStream`].
+#[derive(Debug)]
+pub struct MonitorIterator(fdo::MonitorStream);
+
+assert_impl_all!(MonitorIterator: Send, Sync, Unpin);
+
+impl Iterator for MonitorIterator {
+    type Item = crate::Result<Message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.0.next())
+    }
+}
+
 gen_stats_proxy!(false, true);
 assert_impl_all!(StatsProxy<'_>: Send, Sync, Unpin);
 
+#[cfg(feature = "dbus-broker")]
+impl<'p> StatsProxy<'p> {
+    /// Get the statistics of the bus, including those specific to dbus-broker.
+    ///
+    /// See [`crate::fdo::StatsProxy::get_broker_stats`] for details.
+    pub fn get_broker_stats(&self) -> Result<fdo::broker::BrokerStats> {
+        let proxy = fdo::StatsProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.get_broker_stats())
+    }
+}
+
 gen_dbus_proxy!(false, true);
 assert_impl_all!(DBusProxy<'_>: Send, Sync, Unpin);
+
+impl<'p> DBusProxy<'p> {
+    /// The features of the message bus.
+    ///
+    /// See [`crate::fdo::DBusProxy::bus_features`] for details.
+    pub fn bus_features(&self) -> Result<Vec<BusFeature>> {
+        let proxy = fdo::DBusProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.bus_features())
+    }
+
+    /// The optional interfaces provided by the message bus.
+    ///
+    /// See [`crate::fdo::DBusProxy::bus_interfaces`] for details.
+    pub fn bus_interfaces(&self) -> Result<Vec<BusInterface>> {
+        let proxy = fdo::DBusProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.bus_interfaces())
+    }
+
+    /// Add the variables of `env` to the environment of the services activated from now on.
+    ///
+    /// See [`crate::fdo::DBusProxy::apply_activation_environment`] for details.
+    pub fn apply_activation_environment(&self, env: &ActivationEnvironment) -> Result<()> {
+        let proxy = fdo::DBusProxy::from(self.inner().inner().clone());
+
+        block_on(proxy.apply_activation_environment(env))
+    }
+}
+
+/// A blocking wrapper of [`crate::fdo::NameWatcher`].
+#[derive(Debug)]
+pub struct NameWatcher(
+    // Wrapped in an `Option` to drop it in a `block_on` call, as its `Drop` impl spawns a task
+    // deregistering the match rule, which needs a runtime context in case of tokio.
+    Option<fdo::NameWatcher>,
+);
+
+assert_impl_all!(NameWatcher: Send, Sync, Unpin);
+
+impl NameWatcher {
+    /// Watch the owner of `name`.
+    ///
+    /// See [`crate::fdo::NameWatcher::new`] for details.
+    pub fn new<'n, N>(conn: &Connection, name: N) -> Result<Self>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(fdo::NameWatcher::new(conn.inner(), name)).map(|w| Self(Some(w)))
+    }
+
+    /// The name being watched.
+    pub fn name(&self) -> &OwnedBusName {
+        self.inner().name()
+    }
+
+    /// The owner of the name, as of the last yielded event.
+    pub fn owner(&self) -> Option<&OwnedUniqueName> {
+        self.inner().owner()
+    }
+
+    fn inner(&self) -> &fdo::NameWatcher {
+        self.0.as_ref().expect("Inner watcher is `None`")
+    }
+}
+
+impl Iterator for NameWatcher {
+    type Item = NameEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.0.as_mut()?.next())
+    }
+}
+
+impl Drop for NameWatcher {
+    fn drop(&mut self) {
+        block_on(async {
+            self.0.take();
+        });
+    }
+}
+
+/// A blocking wrapper of [`crate::fdo::NameRequest`].
+#[derive(Debug)]
+pub struct NameRequest(
+    // Wrapped in an `Option` to drop it in a `block_on` call, as its `Drop` impl spawns a task
+    // deregistering the match rule, which needs a runtime context in case of tokio.
+    Option<fdo::NameRequest>,
+);
+
+assert_impl_all!(NameRequest: Send, Sync, Unpin);
+
+impl NameRequest {
+    /// Request the name `name` for `conn`, with the flags `flags`.
+    ///
+    /// See [`crate::fdo::NameRequest::new`] for details.
+    pub fn new<'n, N>(conn: &Connection, name: N, flags: BitFlags<RequestNameFlags>) -> Result<Self>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(fdo::NameRequest::new(conn.inner(), name, flags)).map(|r| Self(Some(r)))
+    }
+
+    /// The name requested.
+    pub fn name(&self) -> &OwnedWellKnownName {
+        self.inner().name()
+    }
+
+    /// The state of the request, as of the last yielded change.
+    pub fn state(&self) -> NameRequestState {
+        self.inner().state()
+    }
+
+    /// Release the name, or leave its queue.
+    ///
+    /// See [`crate::fdo::NameRequest::release`] for details.
+    pub fn release(mut self) -> Result<bool> {
+        let request = self.0.take().expect("Inner request is `None`");
+
+        block_on(request.release())
+    }
+
+    fn inner(&self) -> &fdo::NameRequest {
+        self.0.as_ref().expect("Inner request is `None`")
+    }
+}
+
+impl Iterator for NameRequest {
+    type Item = NameRequestState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(self.0.as_mut()?.next())
+    }
+}
+
+impl Drop for NameRequest {
+    fn drop(&mut self) {
+        block_on(async {
+            self.0.take();
+        });
+    }
+}
+
+/// A blocking wrapper of [`crate::fdo::Services`].
+#[derive(Clone, Debug)]
+pub struct Services<'a>(fdo::Services<'a>);
+
+assert_impl_all!(Services<'_>: Send, Sync, Unpin);
+
+impl<'a> Services<'a> {
+    /// Create the services of the bus `conn` is connected to.
+    pub fn new(conn: &Connection) -> Result<Services<'static>> {
+        block_on(fdo::Services::new(conn.inner())).map(Services)
+    }
+
+    /// The services currently running.
+    pub fn running(&self) -> Result<Vec<OwnedWellKnownName>> {
+        block_on(self.0.running())
+    }
+
+    /// The services that can be started by the bus, be they running or not.
+    pub fn activatable(&self) -> Result<Vec<OwnedWellKnownName>> {
+        block_on(self.0.activatable())
+    }
+
+    /// Whether the service `name` is currently running.
+    pub fn is_running<'n, N>(&self, name: N) -> Result<bool>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(self.0.is_running(name))
+    }
+
+    /// Whether the service `name` can be started by the bus.
+    pub fn is_activatable<'n, N>(&self, name: N) -> Result<bool>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(self.0.is_activatable(name))
+    }
+
+    /// The connection currently owning the service `name`, if it's running.
+    pub fn owner<'n, N>(&self, name: N) -> Result<Option<OwnedUniqueName>>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(self.0.owner(name))
+    }
+
+    /// The connections waiting to own the service `name`, the current owner first.
+    pub fn queued_owners<'n, N>(&self, name: N) -> Result<Vec<OwnedUniqueName>>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        block_on(self.0.queued_owners(name))
+    }
+}
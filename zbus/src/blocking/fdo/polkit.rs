@@ -0,0 +1,18 @@
+//! Proxy for the polkit authority.
+//!
+//! Provides a blocking version of the proxy in [`zbus::fdo::polkit`] module.
+
+use enumflags2::BitFlags;
+use static_assertions::assert_impl_all;
+use std::collections::HashMap;
+
+use crate::{
+    fdo::{
+        polkit::{AuthorizationResult, CheckAuthorizationFlags, Subject},
+        Result,
+    },
+    proxy,
+};
+
+gen_authority_proxy!(false, true);
+assert_impl_all!(AuthorityProxy<'_>: Send, Sync, Unpin);
@@ -1,10 +1,16 @@
 //! The object server API.
 
 use static_assertions::assert_impl_all;
-use zvariant::ObjectPath;
+use std::{collections::HashMap, time::Duration};
+use zbus_names::{InterfaceName, MemberName, OwnedInterfaceName};
+use zvariant::{ObjectPath, OwnedObjectPath};
 
 use crate::{
-    object_server::{Interface, InterfaceDeref, InterfaceDerefMut, SignalContext},
+    object_server::{
+        DynamicInterface, Interface, InterfaceDeref, InterfaceDerefMut, InterfaceShim,
+        InterfaceStats, MachineIdSource, Middleware, MiddlewareScope, Object, SignalContext,
+        SlowHandler,
+    },
     utils::block_on,
     Error, Result,
 };
@@ -73,6 +79,63 @@ where
     pub fn signal_context(&self) -> &SignalContext<'static> {
         self.azync.signal_context()
     }
+
+    /// Emit a single `PropertiesChanged` signal for all the given properties.
+    ///
+    /// See [`crate::object_server::InterfaceRef::properties_changed`] for details.
+    pub fn properties_changed(&self, property_names: &[&str]) -> Result<()>
+    where
+        I: Interface,
+    {
+        block_on(self.azync.properties_changed(property_names))
+    }
+}
+
+/// A blocking wrapper of [`crate::object_server::Registration`].
+///
+/// Dropping it unregisters the interface before returning, rather than in the background.
+#[derive(Debug)]
+#[must_use = "the interface is unregistered once the registration is dropped"]
+pub struct Registration {
+    // Wrapped in an `Option` to unregister in a `block_on` call, as the `Drop` impl of the async
+    // registration spawns a task, which needs a runtime context in case of tokio.
+    azync: Option<crate::object_server::Registration>,
+}
+
+assert_impl_all!(Registration: Send, Sync, Unpin);
+
+impl Registration {
+    /// The path of the object.
+    pub fn path(&self) -> &ObjectPath<'_> {
+        self.inner().path()
+    }
+
+    /// The name of the interface.
+    pub fn interface_name(&self) -> &InterfaceName<'_> {
+        self.inner().interface_name()
+    }
+
+    /// Unregister the interface now.
+    ///
+    /// See [`crate::object_server::Registration::unregister`] for details.
+    pub fn unregister(mut self) -> Result<bool> {
+        let azync = self.azync.take().expect("Inner registration is `None`");
+
+        block_on(azync.unregister())
+    }
+
+    fn inner(&self) -> &crate::object_server::Registration {
+        self.azync.as_ref().expect("Inner registration is `None`")
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if let Some(azync) = self.azync.take() {
+            // Errors are only about the interface being gone already.
+            let _ = block_on(azync.unregister());
+        }
+    }
 }
 
 /// A blocking wrapper of [`crate::ObjectServer`].
@@ -157,6 +220,57 @@ impl ObjectServer {
         block_on(self.azync.at(path, iface))
     }
 
+    /// Register a D-Bus [`Interface`] at a given path, until the returned [`Registration`] is
+    /// dropped.
+    ///
+    /// See [`crate::ObjectServer::at_scoped`] for details.
+    pub fn at_scoped<'p, P, I>(&self, path: P, iface: I) -> Result<Option<Registration>>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.at_scoped(path, iface))
+            .map(|r| r.map(|azync| Registration { azync: Some(azync) }))
+    }
+
+    /// Replace the implementation of a D-Bus [`Interface`] at a given path.
+    ///
+    /// See [`crate::ObjectServer::replace_at`] for details.
+    pub fn replace_at<'p, P, I>(&self, path: P, iface: I) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.replace_at(path, iface))
+    }
+
+    /// Register all the interfaces of `object` at a given path.
+    ///
+    /// See [`crate::ObjectServer::at_object`] for details.
+    pub fn at_object<'p, P>(&self, path: P, object: Object) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.at_object(path, object))
+    }
+
+    /// Serve the interface `I` registered at `path` at `alias` as well.
+    ///
+    /// See [`crate::ObjectServer::alias`] for details.
+    pub fn alias<'p, 'a, I, P, A>(&self, path: P, alias: A) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        A: TryInto<ObjectPath<'a>>,
+        A::Error: Into<Error>,
+    {
+        block_on(self.azync.alias::<I, P, A>(path, alias))
+    }
+
     /// Unregister a D-Bus [`Interface`] at a given path.
     ///
     /// If there are no more interfaces left at that path, destroys the object as well.
@@ -172,6 +286,85 @@ impl ObjectServer {
         block_on(self.azync.remove::<I, P>(path))
     }
 
+    /// Register a [`DynamicInterface`] at a given path.
+    ///
+    /// See [`crate::ObjectServer::at_dynamic`] for details.
+    pub fn at_dynamic<'p, P>(&self, path: P, iface: DynamicInterface) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.at_dynamic(path, iface))
+    }
+
+    /// Serve an [`InterfaceShim`] at a given path.
+    ///
+    /// See [`crate::ObjectServer::at_shim`] for details.
+    pub fn at_shim<'p, P>(&self, path: P, shim: InterfaceShim) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.at_shim(path, shim))
+    }
+
+    /// Unregister a [`DynamicInterface`] (or any other interface) by name at a given path.
+    ///
+    /// See [`crate::ObjectServer::remove_dynamic`] for details.
+    pub fn remove_dynamic<'p, 'i, P, N>(&self, path: P, name: N) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        block_on(self.azync.remove_dynamic(path, name))
+    }
+
+    /// Unregister all the interfaces at a given path, after waiting for their in-flight method
+    /// calls to complete.
+    ///
+    /// See [`crate::ObjectServer::remove_graceful`] for details.
+    pub fn remove_graceful<'p, P>(&self, path: P, timeout: Duration) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.remove_graceful(path, timeout))
+    }
+
+    /// Register a fallback D-Bus [`Interface`] for a path and all paths under it.
+    ///
+    /// See [`crate::ObjectServer::at_fallback`] for details.
+    pub fn at_fallback<'p, P, I>(&self, path: P, iface: I) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.at_fallback(path, iface))
+    }
+
+    /// Unregister a fallback D-Bus [`Interface`] registered with [`ObjectServer::at_fallback`].
+    pub fn remove_fallback<'p, I, P>(&self, path: P) -> Result<()>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.remove_fallback::<I, P>(path))
+    }
+
+    /// Register a middleware wrapping the dispatch of the method calls in the given `scope`.
+    ///
+    /// See [`crate::ObjectServer::add_middleware`] for details.
+    pub fn add_middleware<M>(&self, scope: MiddlewareScope, middleware: M)
+    where
+        M: Middleware,
+    {
+        block_on(self.azync.add_middleware(scope, middleware))
+    }
+
     /// Get the interface at the given path.
     ///
     /// # Errors
@@ -222,6 +415,170 @@ impl ObjectServer {
         })
     }
 
+    /// Emit `PropertiesChanged` signals for the given properties of all the interfaces at `path`.
+    ///
+    /// See [`crate::ObjectServer::properties_changed`] for details.
+    pub fn properties_changed<'p, P>(&self, path: P, property_names: &[&str]) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.properties_changed(path, property_names))
+    }
+
+    /// Limit the number of method calls to the object at the given path being handled at the same
+    /// time.
+    ///
+    /// See [`crate::ObjectServer::set_dispatch_limit`] for details.
+    pub fn set_dispatch_limit<'p, P>(&self, path: P, max: Option<usize>) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.set_dispatch_limit(path, max))
+    }
+
+    /// Attach extra content to the introspection data of the object at the given path.
+    ///
+    /// See [`crate::ObjectServer::set_extra_introspection`] for details.
+    pub fn set_extra_introspection<'p, P>(&self, path: P, xml: Option<String>) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.set_extra_introspection(path, xml))
+    }
+
+    /// List child nodes in the introspection data of the objects created on demand.
+    ///
+    /// See [`crate::ObjectServer::set_virtual_children`] for details.
+    pub fn set_virtual_children<'p, P, F>(&self, path: P, children: F) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        F: Fn(&ObjectPath<'_>) -> Vec<String> + Send + Sync + 'static,
+    {
+        block_on(self.azync.set_virtual_children(path, children))
+    }
+
+    /// Stop listing child nodes set with [`ObjectServer::set_virtual_children`].
+    ///
+    /// See [`crate::ObjectServer::remove_virtual_children`] for details.
+    pub fn remove_virtual_children<'p, P>(&self, path: P) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.remove_virtual_children(path))
+    }
+
+    /// Report the method calls taking longer than `threshold` to be handled.
+    ///
+    /// See [`crate::ObjectServer::watch_slow_handlers`] for details.
+    pub fn watch_slow_handlers<F>(&self, threshold: Duration, callback: F)
+    where
+        F: Fn(SlowHandler) + Send + Sync + 'static,
+    {
+        block_on(self.azync.watch_slow_handlers(threshold, callback))
+    }
+
+    /// Set where the `GetMachineId` method of the `org.freedesktop.DBus.Peer` interface gets the
+    /// machine ID from.
+    ///
+    /// See [`crate::ObjectServer::set_machine_id_source`] for details.
+    pub fn set_machine_id_source(&self, source: MachineIdSource) {
+        block_on(self.azync.set_machine_id_source(source))
+    }
+
+    /// Set a deadline for handling the method calls to `interface`.
+    ///
+    /// See [`crate::ObjectServer::set_deadline`] for details.
+    pub fn set_deadline<'i, I>(&self, interface: I, deadline: Option<Duration>) -> Result<()>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        block_on(self.azync.set_deadline(interface, deadline))
+    }
+
+    /// Set a deadline for handling the calls to `method` of `interface`.
+    ///
+    /// See [`crate::ObjectServer::set_method_deadline`] for details.
+    pub fn set_method_deadline<'i, 'm, I, M>(
+        &self,
+        interface: I,
+        method: M,
+        deadline: Option<Duration>,
+    ) -> Result<()>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+    {
+        block_on(self.azync.set_method_deadline(interface, method, deadline))
+    }
+
+    /// Get statistics about the method calls handled so far, by interface.
+    ///
+    /// See [`crate::ObjectServer::stats`] for details.
+    pub fn stats(&self) -> HashMap<OwnedInterfaceName, InterfaceStats> {
+        self.azync.stats()
+    }
+
+    /// The paths of all the objects currently served, sorted.
+    ///
+    /// See [`crate::ObjectServer::paths`] for details.
+    pub fn paths(&self) -> Vec<OwnedObjectPath> {
+        block_on(self.azync.paths())
+    }
+
+    /// The names of all the interfaces served at the given path, sorted.
+    ///
+    /// See [`crate::ObjectServer::interfaces_at`] for details.
+    pub fn interfaces_at<'p, P>(&self, path: P) -> Result<Vec<OwnedInterfaceName>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.interfaces_at(path))
+    }
+
+    /// The introspection data of the interface `name` served at `path`, as a [`zbus_xml`] model.
+    ///
+    /// See [`crate::ObjectServer::introspect_interface`] for details. Only available with the `xml`
+    /// feature.
+    #[cfg(feature = "xml")]
+    pub fn introspect_interface<'p, 'i, P, N>(
+        &self,
+        path: P,
+        name: N,
+    ) -> Result<zbus_xml::Interface<'static>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        block_on(self.azync.introspect_interface(path, name))
+    }
+
+    /// The introspection data of all the interfaces served at `path`, sorted by name.
+    ///
+    /// See [`crate::ObjectServer::introspect_interfaces_at`] for details. Only available with the
+    /// `xml` feature.
+    #[cfg(feature = "xml")]
+    pub fn introspect_interfaces_at<'p, P>(
+        &self,
+        path: P,
+    ) -> Result<Vec<zbus_xml::Interface<'static>>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        block_on(self.azync.introspect_interfaces_at(path))
+    }
+
     /// Get a reference to the underlying async ObjectServer.
     pub fn inner(&self) -> &crate::ObjectServer {
         &self.azync
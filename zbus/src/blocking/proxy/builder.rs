@@ -2,7 +2,10 @@ use static_assertions::assert_impl_all;
 use zbus_names::{BusName, InterfaceName};
 use zvariant::ObjectPath;
 
-use crate::{blocking::Connection, proxy::CacheProperties, utils::block_on, Error, Result};
+use crate::{
+    blocking::Connection, connection::SharedMatchRule, proxy::CacheProperties, utils::block_on,
+    Error, Result,
+};
 
 pub use crate::proxy::ProxyDefault;
 
@@ -52,6 +55,22 @@ impl<'a, T> Builder<'a, T> {
         Self(self.0.uncached_properties(properties))
     }
 
+    /// Keep a shared match rule alive for the lifetime of the proxy.
+    ///
+    /// See [`crate::proxy::Builder::shared_match_rule`] for details.
+    #[must_use]
+    pub fn shared_match_rule(self, rule: &SharedMatchRule) -> Self {
+        Self(self.0.shared_match_rule(rule))
+    }
+
+    /// Verify the remote object when building the proxy.
+    ///
+    /// See [`crate::proxy::Builder::verify`] for details.
+    #[must_use]
+    pub fn verify(self, verify: bool) -> Self {
+        Self(self.0.verify(verify))
+    }
+
     /// Build a proxy from the builder.
     ///
     /// # Panics
@@ -5,14 +5,14 @@ use futures_util::StreamExt;
 use static_assertions::assert_impl_all;
 use std::{fmt, ops::Deref};
 use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
-use zvariant::{ObjectPath, OwnedValue, Value};
+use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
 
 use crate::{
     blocking::Connection,
     message::Message,
     proxy::{MethodFlags, ProxyDefault},
     utils::block_on,
-    Error, Result,
+    Error, MatchRule, Result,
 };
 
 use crate::fdo;
@@ -201,6 +201,16 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().get_property(property_name))
     }
 
+    /// Get all the properties of the interface, deserialized into `T`.
+    ///
+    /// See [`crate::Proxy::get_all_as`] for details.
+    pub fn get_all_as<T>(&self) -> Result<T>
+    where
+        T: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        block_on(self.inner().get_all_as())
+    }
+
     /// Set the property `property_name`.
     ///
     /// Effectively, call the `Set` method of the `org.freedesktop.DBus.Properties` interface.
@@ -278,6 +288,54 @@ impl<'a> Proxy<'a> {
         block_on(self.inner().call_noreply(method_name, body))
     }
 
+    /// Call a method and wait for a signal correlated with its reply.
+    ///
+    /// See [`crate::Proxy::call_and_receive_signal`] for details.
+    pub fn call_and_receive_signal<'m, 'r, M, B, R, F>(
+        &self,
+        method_name: M,
+        body: &B,
+        signal_rule: MatchRule<'r>,
+        is_terminal: F,
+    ) -> Result<(R, Message)>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: for<'d> zvariant::DynamicDeserialize<'d>,
+        F: FnMut(&R, &Message) -> Result<bool>,
+    {
+        block_on(
+            self.inner()
+                .call_and_receive_signal(method_name, body, signal_rule, is_terminal),
+        )
+    }
+
+    /// Call a method returning a job object path and wait for the job's terminal signal.
+    ///
+    /// See [`crate::Proxy::call_job`] for details.
+    pub fn call_job<'m, 'i, 's, M, B, I, S>(
+        &self,
+        method_name: M,
+        body: &B,
+        signal_interface: I,
+        signal_name: S,
+    ) -> Result<(OwnedObjectPath, Message)>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+        S: TryInto<MemberName<'s>>,
+        S::Error: Into<Error>,
+    {
+        block_on(
+            self.inner()
+                .call_job(method_name, body, signal_interface, signal_name),
+        )
+    }
+
     /// Create a stream for signal named `signal_name`.
     ///
     /// # Errors
@@ -363,6 +421,14 @@ impl<'a> Proxy<'a> {
     pub fn into_inner(mut self) -> crate::Proxy<'a> {
         self.azync.take().expect("Inner proxy is `None`")
     }
+
+    /// Convert into an [async proxy](crate::Proxy).
+    ///
+    /// The returned proxy shares the connection, configuration and property cache of `self`. This
+    /// is the same as [`Proxy::into_inner`].
+    pub fn into_async(self) -> crate::Proxy<'a> {
+        self.into_inner()
+    }
 }
 
 impl ProxyDefault for Proxy<'_> {
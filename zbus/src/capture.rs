@@ -0,0 +1,537 @@
+//! Capturing messages.
+//!
+//! [`PcapngWriter`] writes messages as a [pcapng] capture with the D-Bus link type, which
+//! [Wireshark] and other tools can open and dissect. The messages can come from any source, such as
+//! the stream of a monitor connection (see [`MonitoringProxy::become_monitor`]) or the calls
+//! recorded by an [`Interceptor`].
+//!
+//! [`PcapngReader`] reads the messages of such a capture back, e.g. to replay them with a
+//! [`Replay`].
+//!
+//! [pcapng]: https://datatracker.ietf.org/doc/draft-ietf-opsawg-pcapng/
+//! [Wireshark]: https://www.wireshark.org/
+//! [`MonitoringProxy::become_monitor`]: crate::fdo::MonitoringProxy::become_monitor
+//! [`Interceptor`]: crate::test::Interceptor
+//! [`Replay`]: crate::test::Replay
+
+use std::{
+    io::{self, Read, Write},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use zvariant::{
+    serialized::{self, Context},
+    Endian,
+};
+
+use crate::{message::EndianSig, Message, Result};
+
+// The block types.
+const SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const ENHANCED_PACKET: u32 = 0x0000_0006;
+
+// The interface description options.
+const OPTION_END: u16 = 0;
+const OPTION_TIMESTAMP_RESOLUTION: u16 = 9;
+
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+// The link type of D-Bus messages, see https://www.tcpdump.org/linktypes.html.
+const LINKTYPE_DBUS: u16 = 231;
+
+/// Writes messages as a pcapng capture.
+///
+/// The capture has a single section and interface, with the default microsecond timestamp
+/// resolution. The blocks are written in the native byte order, as allowed by the format. Each
+/// message is written as a packet holding its serialized form, without its file descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use zbus::{capture::PcapngWriter, Message};
+///
+/// let mut capture = PcapngWriter::new(Vec::new())?;
+/// let msg = Message::signal("/org/zbus/Capture", "org.zbus.Capture", "Captured")?.build(&())?;
+/// capture.write(&msg)?;
+///
+/// let bytes = capture.into_inner();
+/// // The section header, the interface description and the packet blocks.
+/// assert_eq!(&bytes[..4], &0x0A0D_0D0Au32.to_ne_bytes());
+/// assert_eq!(bytes.len(), 28 + 20 + 32 + msg.data().len().next_multiple_of(4));
+/// # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+/// ```
+#[derive(Debug)]
+pub struct PcapngWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> PcapngWriter<W> {
+    /// Create a writer, writing the headers of the capture to `writer` right away.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        let mut body = Vec::with_capacity(16);
+        body.extend_from_slice(&BYTE_ORDER_MAGIC.to_ne_bytes());
+        // The version, 1.0.
+        body.extend_from_slice(&1u16.to_ne_bytes());
+        body.extend_from_slice(&0u16.to_ne_bytes());
+        // The section length, unknown.
+        body.extend_from_slice(&(-1i64).to_ne_bytes());
+        write_block(&mut writer, SECTION_HEADER, &body)?;
+
+        let mut body = Vec::with_capacity(8);
+        body.extend_from_slice(&LINKTYPE_DBUS.to_ne_bytes());
+        // Reserved.
+        body.extend_from_slice(&0u16.to_ne_bytes());
+        // The snapshot length, unlimited.
+        body.extend_from_slice(&0u32.to_ne_bytes());
+        write_block(&mut writer, INTERFACE_DESCRIPTION, &body)?;
+
+        Ok(Self { writer })
+    }
+
+    /// Write `msg`, timestamped with the current time.
+    pub fn write(&mut self, msg: &Message) -> io::Result<()> {
+        self.write_at(msg, SystemTime::now())
+    }
+
+    /// Write `msg`, timestamped with `time`.
+    ///
+    /// The writer is flushed afterwards, so the capture can be read while it's being written.
+    pub fn write_at(&mut self, msg: &Message, time: SystemTime) -> io::Result<()> {
+        let data = msg.data();
+        let micros = time
+            .duration_since(UNIX_EPOCH)
+            .map(|t| u64::try_from(t.as_micros()).unwrap_or(u64::MAX))
+            .unwrap_or_default();
+        let len = u32::try_from(data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "message too large"))?;
+
+        let mut body = Vec::with_capacity(20 + data.len() + 3);
+        // The interface ID.
+        body.extend_from_slice(&0u32.to_ne_bytes());
+        body.extend_from_slice(&((micros >> 32) as u32).to_ne_bytes());
+        body.extend_from_slice(&(micros as u32).to_ne_bytes());
+        // The captured and original lengths.
+        body.extend_from_slice(&len.to_ne_bytes());
+        body.extend_from_slice(&len.to_ne_bytes());
+        body.extend_from_slice(&data[..]);
+        write_block(&mut self.writer, ENHANCED_PACKET, &body)?;
+
+        self.writer.flush()
+    }
+
+    /// The underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Take the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads the messages of a pcapng capture.
+///
+/// The packets of the interfaces with the D-Bus link type are read as messages, along with their
+/// timestamp. The other blocks and interfaces are skipped. The sections can be in either byte
+/// order, and the interfaces can have any timestamp resolution.
+///
+/// # Examples
+///
+/// ```
+/// use zbus::{capture::{PcapngReader, PcapngWriter}, Message};
+///
+/// let mut capture = PcapngWriter::new(Vec::new())?;
+/// let msg = Message::signal("/org/zbus/Capture", "org.zbus.Capture", "Captured")?.build(&())?;
+/// capture.write(&msg)?;
+///
+/// let bytes = capture.into_inner();
+/// let mut reader = PcapngReader::new(&bytes[..])?;
+/// let (_, read) = reader.next().unwrap()?;
+/// assert_eq!(read.header().member().unwrap(), "Captured");
+/// assert!(reader.next().is_none());
+/// # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+/// ```
+#[derive(Debug)]
+pub struct PcapngReader<R> {
+    reader: R,
+    // Whether the current section is in the other byte order.
+    swapped: bool,
+    // The timestamp resolution of the interfaces of the current section, in units per second, or
+    // `None` for the interfaces with another link type.
+    interfaces: Vec<Option<u64>>,
+}
+
+impl<R: Read> PcapngReader<R> {
+    /// Create a reader, reading the header of the capture from `reader` right away.
+    pub fn new(reader: R) -> Result<Self> {
+        let mut capture = Self {
+            reader,
+            swapped: false,
+            interfaces: vec![],
+        };
+        match capture.read_block()? {
+            Some((SECTION_HEADER, _)) => Ok(capture),
+            _ => Err(invalid_data("not a pcapng capture").into()),
+        }
+    }
+
+    /// The underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Take the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_message(&mut self) -> Result<Option<(SystemTime, Message)>> {
+        loop {
+            match self.read_block()? {
+                Some((ENHANCED_PACKET, body)) => {
+                    if let Some(packet) = self.read_packet(&body)? {
+                        return Ok(Some(packet));
+                    }
+                }
+                Some(_) => (),
+                None => return Ok(None),
+            }
+        }
+    }
+
+    // Read the next block, handling the section and interface headers on the way.
+    fn read_block(&mut self) -> Result<Option<(u32, Vec<u8>)>> {
+        let mut header = [0; 8];
+        match self.reader.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        // The section header type reads the same in both byte orders, and its body starts with the
+        // byte order magic.
+        let mut body = vec![];
+        if header[..4] == SECTION_HEADER.to_ne_bytes() {
+            let mut magic = [0; 4];
+            self.reader.read_exact(&mut magic)?;
+            self.swapped = match u32::from_ne_bytes(magic) {
+                BYTE_ORDER_MAGIC => false,
+                magic if magic.swap_bytes() == BYTE_ORDER_MAGIC => true,
+                _ => return Err(invalid_data("invalid byte order magic").into()),
+            };
+            body.extend_from_slice(&magic);
+        }
+        let ty = self.u32(&header[..4]);
+        let total_len = self.u32(&header[4..]) as usize;
+        if total_len % 4 != 0 || total_len < 12 + body.len() {
+            return Err(invalid_data("invalid block length").into());
+        }
+
+        // Don't trust the length for allocating the body.
+        let body_len = total_len - 12;
+        (&mut self.reader)
+            .take((body_len - body.len()) as u64)
+            .read_to_end(&mut body)?;
+        if body.len() != body_len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        let mut trailer = [0; 4];
+        self.reader.read_exact(&mut trailer)?;
+        if self.u32(&trailer) as usize != total_len {
+            return Err(invalid_data("mismatched block lengths").into());
+        }
+
+        match ty {
+            SECTION_HEADER => {
+                if body.len() < 16 || self.u16(&body[4..]) != 1 {
+                    return Err(invalid_data("unsupported pcapng version").into());
+                }
+                self.interfaces.clear();
+            }
+            INTERFACE_DESCRIPTION => {
+                let resolution = self.read_interface(&body)?;
+                self.interfaces.push(resolution);
+            }
+            _ => (),
+        }
+
+        Ok(Some((ty, body)))
+    }
+
+    fn read_interface(&self, body: &[u8]) -> Result<Option<u64>> {
+        if body.len() < 8 {
+            return Err(invalid_data("invalid interface description").into());
+        }
+        if self.u16(body) != LINKTYPE_DBUS {
+            return Ok(None);
+        }
+
+        let mut resolution = 1_000_000;
+        let mut options = &body[8..];
+        while options.len() >= 4 {
+            let code = self.u16(options);
+            let len = self.u16(&options[2..]) as usize;
+            let value = options
+                .get(4..4 + len)
+                .ok_or_else(|| invalid_data("invalid interface option"))?;
+            match code {
+                OPTION_END => break,
+                OPTION_TIMESTAMP_RESOLUTION if len == 1 => {
+                    // The negative power of 2 or 10 of the resolution, in seconds.
+                    let exp = u32::from(value[0] & 0x7F);
+                    let units = if value[0] & 0x80 == 0 {
+                        10u64.checked_pow(exp)
+                    } else {
+                        2u64.checked_pow(exp)
+                    };
+                    resolution =
+                        units.ok_or_else(|| invalid_data("invalid timestamp resolution"))?;
+                }
+                _ => (),
+            }
+            options = options
+                .get(4 + len + (4 - len % 4) % 4..)
+                .unwrap_or_default();
+        }
+
+        Ok(Some(resolution))
+    }
+
+    fn read_packet(&self, body: &[u8]) -> Result<Option<(SystemTime, Message)>> {
+        if body.len() < 20 {
+            return Err(invalid_data("invalid packet").into());
+        }
+        let resolution = match self.interfaces.get(self.u32(body) as usize) {
+            Some(Some(resolution)) => *resolution,
+            Some(None) => return Ok(None),
+            None => return Err(invalid_data("packet of an unknown interface").into()),
+        };
+        let timestamp = u64::from(self.u32(&body[4..])) << 32 | u64::from(self.u32(&body[8..]));
+        let captured_len = self.u32(&body[12..]) as usize;
+        if captured_len != self.u32(&body[16..]) as usize {
+            return Err(invalid_data("truncated message").into());
+        }
+        let data = body
+            .get(20..20 + captured_len)
+            .filter(|data| !data.is_empty())
+            .ok_or_else(|| invalid_data("invalid packet"))?;
+
+        let nanos = u128::from(timestamp % resolution) * 1_000_000_000 / u128::from(resolution);
+        let time = UNIX_EPOCH
+            + Duration::from_secs(timestamp / resolution)
+            + Duration::from_nanos(nanos as u64);
+        let endian = Endian::from(EndianSig::try_from(data[0])?);
+        let data = serialized::Data::new(data.to_vec(), Context::new_dbus(endian, 0));
+        let msg = Message::from_raw_parts(data, 0)?;
+
+        Ok(Some((time, msg)))
+    }
+
+    fn u32(&self, bytes: &[u8]) -> u32 {
+        let value = u32::from_ne_bytes(bytes[..4].try_into().unwrap());
+        if self.swapped {
+            value.swap_bytes()
+        } else {
+            value
+        }
+    }
+
+    fn u16(&self, bytes: &[u8]) -> u16 {
+        let value = u16::from_ne_bytes(bytes[..2].try_into().unwrap());
+        if self.swapped {
+            value.swap_bytes()
+        } else {
+            value
+        }
+    }
+}
+
+impl<R: Read> Iterator for PcapngReader<R> {
+    type Item = Result<(SystemTime, Message)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_message().transpose()
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+// Write a block of type `ty`, padding `body` to 32 bits.
+fn write_block<W: Write>(writer: &mut W, ty: u32, body: &[u8]) -> io::Result<()> {
+    let padding = (4 - body.len() % 4) % 4;
+    let total_len = u32::try_from(12 + body.len() + padding)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "block too large"))?;
+
+    writer.write_all(&ty.to_ne_bytes())?;
+    writer.write_all(&total_len.to_ne_bytes())?;
+    writer.write_all(body)?;
+    writer.write_all(&[0; 3][..padding])?;
+    writer.write_all(&total_len.to_ne_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+    use test_log::test;
+
+    use super::{PcapngReader, PcapngWriter};
+    use crate::Message;
+
+    fn read_u32(bytes: &[u8], pos: usize) -> u32 {
+        u32::from_ne_bytes(bytes[pos..pos + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn blocks() {
+        let mut capture = PcapngWriter::new(vec![]).unwrap();
+        let msgs = [
+            Message::method("/org/zbus/Capture", "Odd")
+                .unwrap()
+                .build(&"a")
+                .unwrap(),
+            Message::method("/org/zbus/Capture", "Even")
+                .unwrap()
+                .build(&(1u32, 2u32))
+                .unwrap(),
+        ];
+        let time = UNIX_EPOCH + Duration::from_micros(0x1_0000_0002);
+        for msg in &msgs {
+            capture.write_at(msg, time).unwrap();
+        }
+        let bytes = capture.into_inner();
+
+        // Walk the blocks, checking their lengths are consistent.
+        let mut blocks = vec![];
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let len = read_u32(&bytes, pos + 4) as usize;
+            assert_eq!(len % 4, 0);
+            assert_eq!(read_u32(&bytes, pos + len - 4) as usize, len);
+            blocks.push((read_u32(&bytes, pos), pos));
+            pos += len;
+        }
+        assert_eq!(pos, bytes.len());
+        let types: Vec<_> = blocks.iter().map(|(ty, _)| *ty).collect();
+        assert_eq!(types, [0x0A0D_0D0A, 1, 6, 6]);
+
+        // The D-Bus link type.
+        let idb = blocks[1].1;
+        assert_eq!(&bytes[idb + 8..idb + 10], &231u16.to_ne_bytes());
+
+        for ((_, epb), msg) in blocks[2..].iter().zip(&msgs) {
+            let data = msg.data();
+            assert_eq!(read_u32(&bytes, epb + 12), 1);
+            assert_eq!(read_u32(&bytes, epb + 16), 2);
+            assert_eq!(read_u32(&bytes, epb + 20) as usize, data.len());
+            assert_eq!(&bytes[epb + 28..epb + 28 + data.len()], &data[..]);
+        }
+    }
+
+    #[test]
+    fn read() {
+        let msgs = [
+            Message::method("/org/zbus/Capture", "Odd")
+                .unwrap()
+                .build(&"a")
+                .unwrap(),
+            Message::signal("/org/zbus/Capture", "org.zbus.Capture", "Even")
+                .unwrap()
+                .build(&(1u32, 2u32))
+                .unwrap(),
+        ];
+        let times = [
+            UNIX_EPOCH + Duration::from_micros(0x1_0000_0002),
+            UNIX_EPOCH + Duration::from_micros(0x1_0000_0003),
+        ];
+        let mut capture = PcapngWriter::new(vec![]).unwrap();
+        for (msg, time) in msgs.iter().zip(times) {
+            capture.write_at(msg, time).unwrap();
+        }
+        let bytes = capture.into_inner();
+
+        let read = PcapngReader::new(&bytes[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read.len(), 2);
+        for ((time, read), (msg, expected_time)) in read.iter().zip(msgs.iter().zip(times)) {
+            assert_eq!(*time, expected_time);
+            assert_eq!(&read.data()[..], &msg.data()[..]);
+        }
+
+        // A truncated capture.
+        let mut reader = PcapngReader::new(&bytes[..bytes.len() - 4]).unwrap();
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_err());
+
+        assert!(PcapngReader::new(&b"not a capture"[..]).is_err());
+    }
+
+    #[test]
+    fn read_swapped() {
+        // A capture in the other byte order, with a nanosecond resolution and another interface.
+        fn block(ty: u32, body: &[u8]) -> Vec<u8> {
+            let len = (12 + body.len() as u32).swap_bytes().to_ne_bytes();
+            [&ty.swap_bytes().to_ne_bytes()[..], &len, body, &len].concat()
+        }
+        let msg = Message::signal("/org/zbus/Capture", "org.zbus.Capture", "Swapped")
+            .unwrap()
+            .build(&"a")
+            .unwrap();
+        let mut data = msg.data().to_vec();
+        data.resize(data.len().next_multiple_of(4), 0);
+        let epb = |interface: u32| {
+            let len = (msg.data().len() as u32).swap_bytes().to_ne_bytes();
+            let packet = [
+                &interface.swap_bytes().to_ne_bytes()[..],
+                &1u32.swap_bytes().to_ne_bytes(),
+                &2u32.swap_bytes().to_ne_bytes(),
+                &len,
+                &len,
+                &data,
+            ]
+            .concat();
+            block(6, &packet)
+        };
+
+        let bytes = [
+            block(
+                0x0A0D_0D0A,
+                &[
+                    &0x1A2B_3C4Du32.swap_bytes().to_ne_bytes()[..],
+                    &1u16.swap_bytes().to_ne_bytes(),
+                    &0u16.to_ne_bytes(),
+                    &[0xFF; 8],
+                ]
+                .concat(),
+            ),
+            // An ethernet interface.
+            block(1, &[&1u16.swap_bytes().to_ne_bytes()[..], &[0; 6]].concat()),
+            block(
+                1,
+                &[
+                    &231u16.swap_bytes().to_ne_bytes()[..],
+                    &[0; 6],
+                    // The resolution option, followed by the end of options.
+                    &9u16.swap_bytes().to_ne_bytes(),
+                    &1u16.swap_bytes().to_ne_bytes(),
+                    &[9, 0, 0, 0],
+                    &[0; 4],
+                ]
+                .concat(),
+            ),
+            epb(0),
+            epb(1),
+        ]
+        .concat();
+
+        let read = PcapngReader::new(&bytes[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].0, UNIX_EPOCH + Duration::from_nanos(0x1_0000_0002));
+        assert_eq!(&read[0].1.data()[..], &msg.data()[..]);
+    }
+}
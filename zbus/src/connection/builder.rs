@@ -1,8 +1,8 @@
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use async_io::Async;
 use event_listener::Event;
 use static_assertions::assert_impl_all;
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use std::net::TcpStream;
 #[cfg(all(unix, not(feature = "tokio")))]
 use std::os::unix::net::UnixStream;
@@ -23,16 +23,24 @@ use vsock::VsockStream;
 
 use zvariant::{ObjectPath, Str};
 
+#[cfg(all(unix, feature = "glib"))]
+use crate::abstractions::main_context::MainContextRuntime;
+#[cfg(target_family = "wasm")]
+use crate::abstractions::wasm::WasmRuntime;
+#[cfg(not(target_family = "wasm"))]
+use crate::address;
 #[cfg(feature = "p2p")]
 use crate::Guid;
 use crate::{
-    address::{self, Address},
+    address::Address,
     async_lock::RwLock,
     names::{InterfaceName, WellKnownName},
     object_server::{ArcInterface, Interface},
-    Connection, Error, Executor, OwnedGuid, Result,
+    Connection, DBusError, Error, ErrorRegistry, Executor, OwnedGuid, Result, Runtime,
 };
 
+#[cfg(unix)]
+use super::socket::AsyncFdStream;
 use super::{
     handshake::{AuthMechanism, Authenticated},
     socket::{BoxedSplit, ReadHalf, Split, WriteHalf},
@@ -42,8 +50,9 @@ const DEFAULT_MAX_QUEUED: usize = 64;
 
 #[derive(Debug)]
 enum Target {
-    #[cfg(any(unix, not(feature = "tokio")))]
+    #[cfg(any(unix, all(windows, not(feature = "tokio"))))]
     UnixStream(UnixStream),
+    #[cfg(not(target_family = "wasm"))]
     TcpStream(TcpStream),
     #[cfg(any(
         all(feature = "vsock", not(feature = "tokio")),
@@ -68,6 +77,15 @@ pub struct Builder<'a> {
     #[cfg(feature = "p2p")]
     p2p: bool,
     internal_executor: bool,
+    // Runs the connection instead of the runtime of the crate features.
+    runtime: Option<Arc<dyn Runtime>>,
+    // Whether the socket loops back to the connection itself.
+    loopback: bool,
+    // Whether the socket is connected to a `MockBus`.
+    mock: bool,
+    interceptor: Option<crate::test::Interceptor>,
+    // What the connection is to, e.g `session`, naming its tasks.
+    kind: &'static str,
     interfaces: Interfaces<'a>,
     names: HashSet<WellKnownName<'a>>,
     auth_mechanisms: Option<VecDeque<AuthMechanism>>,
@@ -75,6 +93,7 @@ pub struct Builder<'a> {
     unique_name: Option<crate::names::UniqueName<'a>>,
     cookie_context: Option<super::handshake::CookieContext<'a>>,
     cookie_id: Option<usize>,
+    errors: ErrorRegistry,
 }
 
 assert_impl_all!(Builder<'_>: Send, Sync, Unpin);
@@ -82,12 +101,18 @@ assert_impl_all!(Builder<'_>: Send, Sync, Unpin);
 impl<'a> Builder<'a> {
     /// Create a builder for the session/user message bus connection.
     pub fn session() -> Result<Self> {
-        Ok(Self::new(Target::Address(Address::session()?)))
+        let mut builder = Self::new(Target::Address(Address::session()?));
+        builder.kind = "session";
+
+        Ok(builder)
     }
 
     /// Create a builder for the system-wide message bus connection.
     pub fn system() -> Result<Self> {
-        Ok(Self::new(Target::Address(Address::system()?)))
+        let mut builder = Self::new(Target::Address(Address::system()?));
+        builder.kind = "system";
+
+        Ok(builder)
     }
 
     /// Create a builder for connection that will use the given [D-Bus bus address].
@@ -141,7 +166,7 @@ impl<'a> Builder<'a> {
     /// is not available when the `tokio` feature is enabled and building for Windows target.
     ///
     /// [tuds]: https://github.com/tokio-rs/tokio/issues/2201
-    #[cfg(any(unix, not(feature = "tokio")))]
+    #[cfg(any(unix, all(windows, not(feature = "tokio"))))]
     pub fn unix_stream(stream: UnixStream) -> Self {
         Self::new(Target::UnixStream(stream))
     }
@@ -151,6 +176,7 @@ impl<'a> Builder<'a> {
     /// If the default `async-io` feature is disabled, this method will expect
     /// [`tokio::net::TcpStream`](https://docs.rs/tokio/latest/tokio/net/struct.TcpStream.html)
     /// argument.
+    #[cfg(not(target_family = "wasm"))]
     pub fn tcp_stream(stream: TcpStream) -> Self {
         Self::new(Target::TcpStream(stream))
     }
@@ -173,6 +199,30 @@ impl<'a> Builder<'a> {
         Self::new(Target::Socket(socket.into()))
     }
 
+    /// Create a builder for a connection to itself, through an in-memory socket.
+    ///
+    /// The messages sent on the connection are received back, so method calls are dispatched to
+    /// its own object server. There's no handshake and no bus.
+    pub(crate) fn loopback() -> Self {
+        let mut builder = Self::new(Target::Socket(super::socket::loopback()));
+        builder.loopback = true;
+        builder.kind = "loopback";
+
+        builder
+    }
+
+    /// Create a builder for a connection to an in-process bus.
+    ///
+    /// The connection behaves like one to a bus daemon, without the daemon and the handshake. See
+    /// the [`test`](mod@crate::test) module for details.
+    pub fn mock(bus: &crate::test::MockBus) -> Self {
+        let mut builder = Self::new(Target::Socket(bus.connect()));
+        builder.mock = true;
+        builder.kind = "mock";
+
+        builder
+    }
+
     /// Specify the mechanisms to use during authentication.
     pub fn auth_mechanisms(mut self, auth_mechanisms: &[AuthMechanism]) -> Self {
         self.auth_mechanisms = Some(VecDeque::from(auth_mechanisms.to_vec()));
@@ -280,6 +330,51 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Run the connection on the given runtime.
+    ///
+    /// Instead of the runtime selected by the crate features, the tasks of the connection are
+    /// spawned on `runtime`, and its timers and unix sockets are driven by it. See [`Runtime`] for
+    /// details.
+    ///
+    /// The [internal executor](Builder::internal_executor) setting is ignored then.
+    pub fn runtime(mut self, runtime: impl Runtime) -> Self {
+        self.runtime = Some(Arc::new(runtime));
+
+        self
+    }
+
+    /// Run the connection on a GLib main context.
+    ///
+    /// The tasks of the connection are spawned on `context`, and its timers and unix sockets are
+    /// watched by sources attached to it. This allows using the connection, its proxies and object
+    /// server, from a GTK application without any other runtime. The context must be iterated for
+    /// the connection to make any progress, including while it's being built.
+    ///
+    /// This is a shorthand for [`Builder::runtime`] with a runtime using `context`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use zbus::{connection, fdo::DBusProxy};
+    ///
+    /// let context = glib::MainContext::default();
+    /// context.block_on(async {
+    ///     let conn = connection::Builder::session()?
+    ///         .main_context(&glib::MainContext::default())
+    ///         .build()
+    ///         .await?;
+    ///     let id = DBusProxy::new(&conn).await?.get_id().await?;
+    ///     println!("Connected to the bus {id}");
+    ///
+    ///     Ok::<_, zbus::Error>(())
+    /// })?;
+    /// # Ok::<_, zbus::Error>(())
+    /// ```
+    #[cfg(all(unix, feature = "glib"))]
+    pub fn main_context(self, context: &glib::MainContext) -> Self {
+        self.runtime(MainContextRuntime::new(context.clone()))
+    }
+
     /// Register a D-Bus [`Interface`] to be served at a given path.
     ///
     /// This is similar to [`zbus::ObjectServer::at`], except that it allows you to have your
@@ -294,11 +389,34 @@ impl<'a> Builder<'a> {
     {
         let path = path.try_into().map_err(Into::into)?;
         let entry = self.interfaces.entry(path).or_default();
-        entry.insert(I::name(), ArcInterface(Arc::new(RwLock::new(iface))));
+        entry.insert(I::name(), ArcInterface::new(Arc::new(RwLock::new(iface))));
 
         Ok(self)
     }
 
+    /// Register an application error type.
+    ///
+    /// See [`zbus::Connection::register_error`] for details.
+    pub fn register_error<E>(self) -> Self
+    where
+        E: DBusError + From<Error> + std::error::Error + Send + Sync + 'static,
+    {
+        self.errors.register::<E>();
+
+        self
+    }
+
+    /// Intercept the method calls of the connection with `interceptor`.
+    ///
+    /// The calls made while the connection is being set up, such as the ones requesting the names
+    /// given to [`Builder::name`], are intercepted as well, except for the initial `Hello` call to
+    /// the bus. See [`Interceptor`](crate::test::Interceptor) for details.
+    pub fn intercept(mut self, interceptor: crate::test::Interceptor) -> Self {
+        self.interceptor = Some(interceptor);
+
+        self
+    }
+
     /// Register a well-known name for this connection on the bus.
     ///
     /// This is similar to [`zbus::Connection::request_name`], except the name is requested as part
@@ -347,9 +465,10 @@ impl<'a> Builder<'a> {
     /// Until server-side bus connection is supported, attempting to build such a connection will
     /// result in [`Error::Unsupported`] error.
     pub async fn build(self) -> Result<Connection> {
-        let executor = Executor::new();
+        let executor = Executor::new(self.runtime.clone());
+        // The tasks run on the runtime if any.
         #[cfg(not(feature = "tokio"))]
-        let internal_executor = self.internal_executor;
+        let internal_executor = self.internal_executor && self.runtime.is_none();
         // Box the future as it's large and can cause stack overflow.
         let conn = Box::pin(executor.run(self.build_(executor.clone()))).await?;
 
@@ -364,6 +483,7 @@ impl<'a> Builder<'a> {
         let (mut stream, server_guid) = self.target_connect().await?;
         #[cfg(feature = "p2p")]
         let mut auth = match self.guid {
+            None if self.loopback || self.mock => Authenticated::loopback(stream),
             None => {
                 // SASL Handshake
                 Authenticated::client(stream, server_guid, self.auth_mechanisms).await?
@@ -373,6 +493,7 @@ impl<'a> Builder<'a> {
                     return Err(Error::Unsupported);
                 }
 
+                #[cfg(not(target_family = "wasm"))]
                 let creds = stream.read_mut().peer_credentials().await?;
                 #[cfg(unix)]
                 let client_uid = creds.unix_user_id();
@@ -395,18 +516,30 @@ impl<'a> Builder<'a> {
         };
 
         #[cfg(not(feature = "p2p"))]
-        let mut auth = Authenticated::client(stream, server_guid, self.auth_mechanisms).await?;
+        let mut auth = if self.loopback || self.mock {
+            Authenticated::loopback(stream)
+        } else {
+            Authenticated::client(stream, server_guid, self.auth_mechanisms).await?
+        };
 
         // SAFETY: `Authenticated` is always built with these fields set to `Some`.
         let socket_read = auth.socket_read.take().unwrap();
         let already_received_bytes = auth.already_received_bytes.take().unwrap();
 
         #[cfg(feature = "p2p")]
-        let is_bus_conn = !self.p2p;
+        let is_bus_conn = !self.p2p && !self.loopback;
+        #[cfg(not(feature = "p2p"))]
+        let is_bus_conn = !self.loopback;
+        #[cfg(feature = "p2p")]
+        let kind = if self.p2p { "p2p" } else { self.kind };
         #[cfg(not(feature = "p2p"))]
-        let is_bus_conn = true;
-        let mut conn = Connection::new(auth, is_bus_conn, executor).await?;
+        let kind = self.kind;
+        let mut conn = Connection::new(auth, is_bus_conn, kind, executor).await?;
         conn.set_max_queued(self.max_queued.unwrap_or(DEFAULT_MAX_QUEUED));
+        conn.extend_errors(self.errors);
+        if let Some(interceptor) = self.interceptor {
+            conn.set_interceptor(interceptor);
+        }
         #[cfg(feature = "bus-impl")]
         if let Some(unique_name) = self.unique_name {
             conn.set_unique_name(unique_name)?;
@@ -420,7 +553,7 @@ impl<'a> Builder<'a> {
                     let future =
                         object_server
                             .inner()
-                            .at_ready(path.to_owned(), name.clone(), || iface.0);
+                            .at_ready(path.to_owned(), name.clone(), || iface.instance);
                     let added = future.await?;
                     // Duplicates shouldn't happen.
                     assert!(added);
@@ -463,6 +596,20 @@ impl<'a> Builder<'a> {
     }
 
     fn new(target: Target) -> Self {
+        let kind = match &target {
+            #[cfg(any(unix, all(windows, not(feature = "tokio"))))]
+            Target::UnixStream(_) => "unix stream",
+            #[cfg(not(target_family = "wasm"))]
+            Target::TcpStream(_) => "tcp stream",
+            #[cfg(any(
+                all(feature = "vsock", not(feature = "tokio")),
+                feature = "tokio-vsock"
+            ))]
+            Target::VsockStream(_) => "vsock stream",
+            Target::Address(_) => "address",
+            Target::Socket(_) => "socket",
+        };
+
         Self {
             target: Some(target),
             #[cfg(feature = "p2p")]
@@ -471,6 +618,15 @@ impl<'a> Builder<'a> {
             #[cfg(feature = "p2p")]
             guid: None,
             internal_executor: true,
+            #[cfg(not(target_family = "wasm"))]
+            runtime: None,
+            // There are no threads on wasm, the tasks run on the JS event loop instead.
+            #[cfg(target_family = "wasm")]
+            runtime: Some(Arc::new(WasmRuntime)),
+            loopback: false,
+            mock: false,
+            interceptor: None,
+            kind,
             interfaces: HashMap::new(),
             names: HashSet::new(),
             auth_mechanisms: None,
@@ -478,6 +634,7 @@ impl<'a> Builder<'a> {
             unique_name: None,
             cookie_id: None,
             cookie_context: None,
+            errors: ErrorRegistry::default(),
         }
     }
 
@@ -485,11 +642,16 @@ impl<'a> Builder<'a> {
         // SAFETY: `self.target` is always `Some` from the beginning and this method is only called
         // once.
         let split = match self.target.take().unwrap() {
-            #[cfg(not(feature = "tokio"))]
-            Target::UnixStream(stream) => Async::new(stream)?.into(),
+            #[cfg(all(unix, not(feature = "tokio")))]
+            Target::UnixStream(stream) => self.unix_stream_split(stream)?,
             #[cfg(all(unix, feature = "tokio"))]
-            Target::UnixStream(stream) => stream.into(),
-            #[cfg(not(feature = "tokio"))]
+            Target::UnixStream(stream) => match self.runtime {
+                Some(_) => self.unix_stream_split(stream.into_std()?)?,
+                None => stream.into(),
+            },
+            #[cfg(all(windows, not(feature = "tokio")))]
+            Target::UnixStream(stream) => Async::new(stream)?.into(),
+            #[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
             Target::TcpStream(stream) => Async::new(stream)?.into(),
             #[cfg(feature = "tokio")]
             Target::TcpStream(stream) => stream.into(),
@@ -497,11 +659,17 @@ impl<'a> Builder<'a> {
             Target::VsockStream(stream) => Async::new(stream)?.into(),
             #[cfg(feature = "tokio-vsock")]
             Target::VsockStream(stream) => stream.into(),
+            // There are no sockets to connect to on wasm.
+            #[cfg(target_family = "wasm")]
+            Target::Address(address) => match address.connect().await? {},
+            #[cfg(not(target_family = "wasm"))]
             Target::Address(address) => {
                 let guid = address.guid().map(|g| g.to_owned().into());
                 let split = match address.connect().await? {
-                    #[cfg(any(unix, not(feature = "tokio")))]
-                    address::transport::Stream::Unix(stream) => stream.into(),
+                    #[cfg(unix)]
+                    address::transport::Stream::Unix(stream) => self.unix_stream_split(stream)?,
+                    #[cfg(all(windows, not(feature = "tokio")))]
+                    address::transport::Stream::Unix(stream) => Async::new(stream)?.into(),
                     address::transport::Stream::Tcp(stream) => stream.into(),
                     #[cfg(any(
                         all(feature = "vsock", not(feature = "tokio")),
@@ -516,6 +684,24 @@ impl<'a> Builder<'a> {
 
         Ok((split, None))
     }
+
+    // Watch the unix `stream` through the runtime of the connection.
+    #[cfg(unix)]
+    fn unix_stream_split(&self, stream: std::os::unix::net::UnixStream) -> Result<BoxedSplit> {
+        if let Some(runtime) = &self.runtime {
+            return Ok(AsyncFdStream::new(stream, runtime.as_ref())?.into());
+        }
+
+        #[cfg(not(feature = "tokio"))]
+        {
+            Ok(Async::new(stream)?.into())
+        }
+
+        #[cfg(feature = "tokio")]
+        {
+            Ok(tokio::net::UnixStream::from_std(stream)?.into())
+        }
+    }
 }
 
 /// Start the internal executor thread.
@@ -29,6 +29,8 @@ impl Client {
         let mechanisms = mechanisms.unwrap_or_else(|| {
             let mut mechanisms = VecDeque::new();
             mechanisms.push_back(AuthMechanism::External);
+            // There are no keyrings on wasm.
+            #[cfg(not(target_family = "wasm"))]
             mechanisms.push_back(AuthMechanism::Cookie);
             mechanisms.push_back(AuthMechanism::Anonymous);
             mechanisms
@@ -136,9 +138,13 @@ impl Handshake for Client {
             trace!("Trying {mechanism} mechanism");
             let auth_cmd = match mechanism {
                 AuthMechanism::Anonymous => Command::Auth(Some(mechanism), Some("zbus".into())),
+                #[cfg(not(target_family = "wasm"))]
                 AuthMechanism::External => {
                     Command::Auth(Some(mechanism), Some(sasl_auth_id()?.into_bytes()))
                 }
+                // Let the server use the credentials of the other end of the bridge.
+                #[cfg(target_family = "wasm")]
+                AuthMechanism::External => Command::Auth(Some(mechanism), None),
                 AuthMechanism::Cookie => Command::Auth(
                     Some(AuthMechanism::Cookie),
                     Some(sasl_auth_id()?.into_bytes()),
@@ -153,6 +159,14 @@ impl Handshake for Client {
 
                     break;
                 }
+                // Without an identity, the server asks for it and the empty response lets it use
+                // the credentials of the socket.
+                Command::Data(None) if mechanism == AuthMechanism::External => {
+                    trace!("Received identity request from server");
+                    commands.push(Command::Data(None));
+
+                    break;
+                }
                 Command::Data(data) if mechanism == AuthMechanism::Cookie => {
                     let data = data.ok_or_else(|| {
                         Error::Handshake("Received DATA with no data from server".into())
@@ -2,11 +2,18 @@ use std::{fmt, path::PathBuf};
 
 use futures_util::StreamExt;
 use tracing::trace;
+#[cfg(not(target_family = "wasm"))]
 use xdg_home::home_dir;
 use zvariant::Str;
 
 use crate::{file::FileLines, Error, Result};
 
+// There are no keyrings on wasm.
+#[cfg(target_family = "wasm")]
+fn home_dir() -> Option<PathBuf> {
+    None
+}
+
 #[derive(Debug)]
 pub(super) struct Cookie {
     id: usize,
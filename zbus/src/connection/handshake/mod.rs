@@ -56,6 +56,22 @@ impl Authenticated {
         Client::new(socket, mechanisms, server_guid).perform().await
     }
 
+    /// Create an `Authenticated` for a `socket` looping back to the connection itself.
+    ///
+    /// There's no peer to authenticate, so no handshake takes place.
+    pub(crate) fn loopback(socket: BoxedSplit) -> Self {
+        let (read, write) = socket.take();
+
+        Self {
+            socket_write: write,
+            server_guid: OwnedGuid::from(crate::Guid::generate()),
+            #[cfg(unix)]
+            cap_unix_fd: false,
+            socket_read: Some(read),
+            already_received_bytes: Some(vec![]),
+        }
+    }
+
     /// Create a server-side `Authenticated` for the given `socket`.
     ///
     /// The function takes `client_uid` on Unix only. On Windows, it takes `client_sid` instead.
@@ -106,6 +122,7 @@ fn random_ascii(len: usize) -> String {
         .collect()
 }
 
+#[cfg(not(target_family = "wasm"))]
 fn sasl_auth_id() -> Result<String> {
     let id = {
         #[cfg(unix)]
@@ -122,6 +139,12 @@ fn sasl_auth_id() -> Result<String> {
     Ok(id)
 }
 
+#[cfg(target_family = "wasm")]
+fn sasl_auth_id() -> Result<String> {
+    // There are no users on wasm, the other end of the bridge authenticates the connection.
+    Err(Error::Handshake("No user identity on wasm".into()))
+}
+
 #[cfg(feature = "p2p")]
 #[cfg(unix)]
 #[cfg(test)]
@@ -12,24 +12,28 @@ use std::{
     num::NonZeroU32,
     ops::Deref,
     pin::Pin,
-    sync::{Arc, OnceLock, Weak},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock, Weak,
+    },
     task::{Context, Poll},
 };
-use tracing::{debug, info_span, instrument, trace, trace_span, warn, Instrument};
+use tracing::{debug, instrument, trace, warn};
 use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, OwnedUniqueName, WellKnownName};
 use zvariant::ObjectPath;
 
 use futures_core::Future;
-use futures_util::StreamExt;
+use futures_util::{future::Either, StreamExt};
 
 use crate::{
     async_lock::Mutex,
     blocking,
     fdo::{self, ConnectionCredentials, RequestNameFlags, RequestNameReply},
     message::{Flags, Message, Type},
+    object_server::SignalContext,
     proxy::CacheProperties,
-    DBusError, Error, Executor, MatchRule, MessageStream, ObjectServer, OwnedGuid, OwnedMatchRule,
-    Result, Task,
+    DBusError, Error, ErrorRegistry, Executor, MatchRule, MessageStream, ObjectServer, OwnedGuid,
+    OwnedMatchRule, Result, Task,
 };
 
 mod builder;
@@ -44,6 +48,9 @@ use socket_reader::SocketReader;
 pub(crate) mod handshake;
 use handshake::Authenticated;
 
+mod shared_match_rule;
+pub use shared_match_rule::SharedMatchRule;
+
 const DEFAULT_MAX_QUEUED: usize = 64;
 const DEFAULT_MAX_METHOD_RETURN_QUEUED: usize = 8;
 
@@ -76,9 +83,31 @@ pub(crate) struct ConnectionInner {
 
     object_server: OnceLock<blocking::ObjectServer>,
     object_server_dispatch_task: OnceLock<Task<()>>,
+
+    /// See [`Connection::register_error`].
+    errors: Arc<ErrorRegistry>,
+
+    /// See [`Connection::is_monitor`].
+    monitor: AtomicBool,
+
+    /// See [`Builder::intercept`].
+    interceptor: OnceLock<crate::test::Interceptor>,
+
+    // What the connection is to, e.g `session`, naming its tasks.
+    kind: &'static str,
 }
 
-type Subscriptions = HashMap<OwnedMatchRule, (u64, InactiveReceiver<Result<Message>>)>;
+type Subscriptions = HashMap<OwnedMatchRule, Subscription>;
+
+#[derive(Debug)]
+struct Subscription {
+    num_subscriptions: u64,
+    receiver: InactiveReceiver<Result<Message>>,
+    /// If this rule can cover other rules on the bus (see [`Connection::add_shared_match`]).
+    shared: bool,
+    /// The shared rule covering this one, in which case this rule wasn't added to the bus.
+    covered_by: Option<OwnedMatchRule>,
+}
 
 pub(crate) type MsgBroadcaster = Broadcaster<Result<Message>>;
 
@@ -153,26 +182,19 @@ pub(crate) type MsgBroadcaster = Broadcaster<Result<Message>>;
 /// ```rust,no_run
 /// # zbus::block_on(async {
 /// use futures_util::stream::TryStreamExt;
-/// use zbus::{Connection, MessageStream};
+/// use zbus::{fdo::MonitoringProxy, Connection};
 ///
 /// let connection = Connection::session().await?;
 ///
-/// connection
-///     .call_method(
-///         Some("org.freedesktop.DBus"),
-///         "/org/freedesktop/DBus",
-///         Some("org.freedesktop.DBus.Monitoring"),
-///         "BecomeMonitor",
-///         &(&[] as &[&str], 0u32),
-///     )
+/// let mut stream = MonitoringProxy::new(&connection)
+///     .await?
+///     .become_monitor(&[])
 ///     .await?;
-///
-/// let mut stream = MessageStream::from(connection);
 /// while let Some(msg) = stream.try_next().await? {
 ///     println!("Got message: {}", msg);
 /// }
 ///
-/// # Ok::<(), zbus::Error>(())
+/// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
 /// # }).unwrap();
 /// ```
 ///
@@ -206,6 +228,7 @@ assert_impl_all!(Connection: Send, Sync, Unpin);
 pub(crate) struct PendingMethodCall {
     stream: Option<MessageStream>,
     serial: NonZeroU32,
+    errors: Arc<ErrorRegistry>,
 }
 
 impl Future for PendingMethodCall {
@@ -243,7 +266,7 @@ impl OrderedFuture for PendingMethodCall {
                             continue;
                         }
                         let res = match msg.message_type() {
-                            Type::Error => Err(msg.into()),
+                            Type::Error => Err(this.errors.convert(msg.into())),
                             Type::MethodReturn => Ok(msg),
                             _ => continue,
                         };
@@ -273,13 +296,45 @@ impl OrderedFuture for PendingMethodCall {
 
 impl Connection {
     /// Send `msg` to the peer.
+    ///
+    /// Monitor connections can't send messages (see [`Connection::is_monitor`]), so this returns
+    /// an `Error::Failure` error for them.
     pub async fn send(&self, msg: &Message) -> Result<()> {
+        if self.is_monitor() {
+            return Err(Error::Failure(
+                "Monitor connections can't send messages".into(),
+            ));
+        }
         let data = msg.data();
         #[cfg(unix)]
         if !data.fds().is_empty() && !self.inner.cap_unix_fd {
             return Err(Error::Unsupported);
         }
+        if let Some(intercepted) = self.inner.interceptor.get().and_then(|i| i.intercept(msg)) {
+            trace!("Intercepted message: {:?}", msg);
+            let weak_conn = WeakConnection::from(self);
+            let executor = self.executor().clone();
+            self.executor()
+                .spawn(
+                    async move {
+                        if let Some(reply) = intercepted.reply(&executor).await {
+                            if let Some(conn) = weak_conn.upgrade() {
+                                conn.inject(reply).await;
+                            }
+                        }
+                    },
+                    &self.task_name("intercepted call"),
+                )
+                .detach();
+
+            return Ok(());
+        }
         let serial = msg.primary_header().serial_num();
+        if msg.message_type() == Type::Error {
+            if let Some(server) = self.inner.object_server.get() {
+                server.inner().record_error_reply(msg);
+            }
+        }
 
         trace!("Sending message: {:?}", msg);
         self.inner.activity_event.notify(usize::MAX);
@@ -400,7 +455,11 @@ impl Connection {
         if flags.contains(Flags::NoReplyExpected) {
             Ok(None)
         } else {
-            Ok(Some(PendingMethodCall { stream, serial }))
+            Ok(Some(PendingMethodCall {
+                stream,
+                serial,
+                errors: self.inner.errors.clone(),
+            }))
         }
     }
 
@@ -485,6 +544,67 @@ impl Connection {
         self.send(&m).await
     }
 
+    /// Register an application error type.
+    ///
+    /// Error replies to method calls made on this connection, with any of the names of `E` (as
+    /// returned by [`DBusError::error_names`]), are then returned as [`Error::Registered`]
+    /// errors holding the `E` converted from the reply. Error types generated by the
+    /// [`DBusError` macro][dm] implement all the required traits, as long as they have a
+    /// `#[zbus(error)]` variant.
+    ///
+    /// Since the converted errors keep their name and description, the generated conversion from
+    /// [`Error`] (e.g used by proxies returning `E`) keeps working as before.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{Connection, DBusError};
+    ///
+    /// #[derive(Debug, DBusError)]
+    /// #[zbus(prefix = "org.example.Ledger")]
+    /// enum LedgerError {
+    ///     #[zbus(error)]
+    ///     ZBus(zbus::Error),
+    ///     InsufficientFunds(String),
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// connection.register_error::<LedgerError>();
+    ///
+    /// let err = connection
+    ///     .call_method(
+    ///         Some("org.example.Ledger"),
+    ///         "/org/example/Ledger",
+    ///         Some("org.example.Ledger"),
+    ///         "Withdraw",
+    ///         &(100u32,),
+    ///     )
+    ///     .await
+    ///     .unwrap_err();
+    /// if let zbus::Error::Registered(e) = &err {
+    ///     if let Some(LedgerError::InsufficientFunds(msg)) = e.downcast_ref() {
+    ///         println!("Not enough funds: {msg}");
+    ///     }
+    /// }
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    ///
+    /// [dm]: macro@crate::DBusError
+    pub fn register_error<E>(&self)
+    where
+        E: DBusError + From<Error> + std::error::Error + Send + Sync + 'static,
+    {
+        self.inner.errors.register::<E>();
+    }
+
+    pub(crate) fn extend_errors(&self, errors: ErrorRegistry) {
+        self.inner.errors.extend(errors);
+    }
+
     /// Register a well-known name for this connection.
     ///
     /// When connecting to a bus, the name is requested from the bus. In case of p2p connection, the
@@ -538,7 +658,10 @@ impl Connection {
     ///
     /// If the [`RequestNameFlags::AllowReplacement`] flag is specified, the requested name can be
     /// lost if another peer requests the same name. You can use [`fdo::NameLostStream`] to be
-    /// notified when the name is lost
+    /// notified when the name is lost. Unless [`RequestNameFlags::DoNotQueue`] is specified as
+    /// well, the connection is then put back in the queue of the name.
+    ///
+    /// [`fdo::NameRequest`] follows these changes of state for you.
     ///
     /// # Example
     ///
@@ -615,13 +738,19 @@ impl Connection {
         let mut names = self.inner.registered_names.lock().await;
 
         match names.get(&well_known_name) {
-            Some(NameStatus::Owner(_)) => return Ok(RequestNameReply::AlreadyOwner),
-            Some(NameStatus::Queued(_)) => return Ok(RequestNameReply::InQueue),
+            Some(NameStatus { queued: false, .. }) => return Ok(RequestNameReply::AlreadyOwner),
+            Some(NameStatus { queued: true, .. }) => return Ok(RequestNameReply::InQueue),
             None => (),
         }
 
         if !self.is_bus() {
-            names.insert(well_known_name.to_owned(), NameStatus::Owner(None));
+            names.insert(
+                well_known_name.to_owned(),
+                NameStatus {
+                    queued: false,
+                    task: None,
+                },
+            );
 
             return Ok(RequestNameReply::PrimaryOwner);
         }
@@ -630,113 +759,81 @@ impl Connection {
             .cache_properties(CacheProperties::No)
             .build()
             .await?;
-        let mut acquired_stream = dbus_proxy.receive_name_acquired().await?;
-        let mut lost_stream = dbus_proxy.receive_name_lost().await?;
+        let acquired_stream = dbus_proxy.receive_name_acquired().await?;
+        let lost_stream = dbus_proxy.receive_name_lost().await?;
         let reply = dbus_proxy
             .request_name(well_known_name.clone(), flags)
             .await?;
-        let lost_task_name = format!("monitor name {well_known_name} lost");
-        let name_lost_fut = if flags.contains(RequestNameFlags::AllowReplacement) {
+        let queued = match reply {
+            RequestNameReply::InQueue => true,
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => false,
+            RequestNameReply::Exists => return Err(Error::NameTaken),
+        };
+        // The name can only be acquired later if queued, and only be lost if replacement is
+        // allowed. Otherwise, there's nothing to follow.
+        let task = (queued || flags.contains(RequestNameFlags::AllowReplacement)).then(|| {
             let weak_conn = WeakConnection::from(self);
             let well_known_name = well_known_name.to_owned();
-            Some(
+            let task_name = self.task_name(&format!("monitor name `{well_known_name}`"));
+            let mut signals = futures_util::stream::select(
+                acquired_stream.map(Either::Left),
+                lost_stream.map(Either::Right),
+            );
+
+            self.executor().spawn_long_lived(
                 async move {
-                    loop {
-                        let signal = lost_stream.next().await;
+                    while let Some(signal) = signals.next().await {
                         let inner = match weak_conn.upgrade() {
                             Some(conn) => conn.inner.clone(),
-                            None => break,
+                            None => return,
                         };
+                        let (args, acquired) = match &signal {
+                            Either::Left(signal) => (signal.args().map(|args| args.name), true),
+                            Either::Right(signal) => (signal.args().map(|args| args.name), false),
+                        };
+                        match args {
+                            Ok(name) if name == well_known_name => (),
+                            Ok(_) => continue,
+                            Err(e) => {
+                                warn!("Failed to parse name ownership signal: {}", e);
 
-                        match signal {
-                            Some(signal) => match signal.args() {
-                                Ok(args) if args.name == well_known_name => {
-                                    tracing::info!(
-                                        "Connection `{}` lost name `{}`",
-                                        // SAFETY: This is bus connection so unique name can't be
-                                        // None.
-                                        inner.unique_name.get().unwrap(),
-                                        well_known_name
-                                    );
-                                    inner.registered_names.lock().await.remove(&well_known_name);
-
-                                    break;
-                                }
-                                Ok(_) => (),
-                                Err(e) => warn!("Failed to parse `NameLost` signal: {}", e),
-                            },
-                            None => {
-                                trace!("`NameLost` signal stream closed");
-                                // This is a very strange state we end up in. Now the name is
-                                // question remains in the queue
-                                // forever. Maybe we can do better here but I
-                                // think it's a very unlikely scenario anyway.
-                                //
-                                // Can happen if the connection is lost/dropped but then the whole
-                                // `Connection` instance will go away soon anyway and hence this
-                                // strange state along with it.
-                                break;
+                                continue;
                             }
                         }
-                    }
-                }
-                .instrument(info_span!("{}", lost_task_name)),
-            )
-        } else {
-            None
-        };
-        let status = match reply {
-            RequestNameReply::InQueue => {
-                let weak_conn = WeakConnection::from(self);
-                let well_known_name = well_known_name.to_owned();
-                let task_name = format!("monitor name {well_known_name} acquired");
-                let task = self.executor().spawn(
-                    async move {
-                        loop {
-                            let signal = acquired_stream.next().await;
-                            let inner = match weak_conn.upgrade() {
-                                Some(conn) => conn.inner.clone(),
-                                None => break,
-                            };
-                            match signal {
-                                Some(signal) => match signal.args() {
-                                    Ok(args) if args.name == well_known_name => {
-                                        let mut names = inner.registered_names.lock().await;
-                                        if let Some(status) = names.get_mut(&well_known_name) {
-                                            let task = name_lost_fut.map(|fut| {
-                                                inner.executor.spawn(fut, &lost_task_name)
-                                            });
-                                            *status = NameStatus::Owner(task);
-
-                                            break;
-                                        }
-                                        // else the name was released in the meantime. :shrug:
-                                    }
-                                    Ok(_) => (),
-                                    Err(e) => warn!("Failed to parse `NameAcquired` signal: {}", e),
-                                },
-                                None => {
-                                    trace!("`NameAcquired` signal stream closed");
-                                    // See comment above for similar state in case of `NameLost`
-                                    // stream.
-                                    break;
-                                }
-                            }
+
+                        let mut names = inner.registered_names.lock().await;
+                        if !acquired && flags.contains(RequestNameFlags::DoNotQueue) {
+                            tracing::info!(
+                                "Connection `{}` lost name `{}`",
+                                // SAFETY: This is bus connection so unique name can't be None.
+                                inner.unique_name.get().unwrap(),
+                                well_known_name
+                            );
+                            names.remove(&well_known_name);
+
+                            return;
+                        }
+                        // A replaced owner that didn't opt out of queueing is put back in the
+                        // queue, where it can acquire the name again later.
+                        if let Some(status) = names.get_mut(&well_known_name) {
+                            status.queued = !acquired;
                         }
+                        // else the name was released in the meantime. :shrug:
                     }
-                    .instrument(info_span!("{}", task_name)),
-                    &task_name,
-                );
 
-                NameStatus::Queued(task)
-            }
-            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {
-                let task = name_lost_fut.map(|fut| self.executor().spawn(fut, &lost_task_name));
-
-                NameStatus::Owner(task)
-            }
-            RequestNameReply::Exists => return Err(Error::NameTaken),
-        };
+                    trace!("`NameAcquired` and `NameLost` signal streams closed");
+                    // This is a very strange state we end up in. Now the name is question remains
+                    // in the queue forever. Maybe we can do better here but I think it's a very
+                    // unlikely scenario anyway.
+                    //
+                    // Can happen if the connection is lost/dropped but then the whole `Connection`
+                    // instance will go away soon anyway and hence this strange state along with
+                    // it.
+                },
+                &task_name,
+            )
+        });
+        let status = NameStatus { queued, task };
 
         names.insert(well_known_name.to_owned(), status);
 
@@ -921,6 +1018,51 @@ impl Connection {
         Wrapper(self.sync_object_server(true, None))
     }
 
+    /// Create a [`SignalContext`] for emitting signals from the object at `path`.
+    ///
+    /// This is useful for emitting the signals of a served interface from outside of its
+    /// handlers (e.g from a timer or from a thread watching for hardware events), without having
+    /// to look the interface up through [`ObjectServer::interface`]. The object doesn't need to be
+    /// served for the signals to be emitted though.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, object_server::SignalContext, Connection};
+    ///
+    /// struct Button;
+    ///
+    /// #[interface(name = "org.example.Button")]
+    /// impl Button {
+    ///     #[zbus(signal)]
+    ///     async fn pressed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// connection
+    ///     .object_server()
+    ///     .at("/org/example/Button", Button)
+    ///     .await?;
+    ///
+    /// let ctxt = connection.signal_context("/org/example/Button")?;
+    /// std::thread::spawn(move || {
+    ///     // Wait for the button to be pressed...
+    ///     zbus::block_on(Button::pressed(&ctxt)).unwrap();
+    /// });
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub fn signal_context<'p, P>(&self, path: P) -> Result<SignalContext<'p>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        SignalContext::new(self, path)
+    }
+
     pub(crate) fn sync_object_server(
         &self,
         start: bool,
@@ -949,8 +1091,8 @@ impl Connection {
             trace!("starting ObjectServer task");
             let weak_conn = WeakConnection::from(self);
 
-            let obj_server_task_name = "ObjectServer task";
-            self.inner.executor.spawn(
+            let obj_server_task_name = self.task_name("object server");
+            self.inner.executor.spawn_long_lived(
                 async move {
                     let mut stream = match weak_conn.upgrade() {
                         Some(conn) => {
@@ -1011,21 +1153,22 @@ impl Connection {
                                 }
                             };
                             trace!("Got `{}`. Will spawn a task for dispatch..", msg);
+                            // Tickets have to be taken in the order the messages are received.
+                            let ticket = conn.object_server().dispatch_ticket(&msg).await;
                             let executor = conn.inner.executor.clone();
-                            let task_name = format!("`{member}` method dispatcher");
+                            let task_name = conn.task_name(&format!("`{member}` method dispatcher"));
                             executor
                                 .spawn(
                                     async move {
                                         trace!("spawned a task to dispatch `{}`.", msg);
                                         let server = conn.object_server();
-                                        if let Err(e) = server.dispatch_message(&msg).await {
+                                        if let Err(e) = server.dispatch_message(&msg, ticket).await {
                                             debug!(
                                                 "Error dispatching message. Message: {:?}, error: {:?}",
                                                 msg, e
                                             );
                                         }
-                                    }
-                                    .instrument(trace_span!("{}", task_name)),
+                                    },
                                     &task_name,
                                 )
                                 .detach();
@@ -1035,20 +1178,73 @@ impl Connection {
                             break;
                         }
                     }
-                }
-                .instrument(info_span!("{}", obj_server_task_name)),
-                obj_server_task_name,
+                },
+                &obj_server_task_name,
             )
         });
     }
 
+    /// Add a match rule that other match rules can share on the bus.
+    ///
+    /// Each match rule added by zbus on behalf of a [`MessageStream`], a [`crate::Proxy`] or the
+    /// streams it creates, is normally also added on the bus through an `AddMatch` call. Since
+    /// buses limit the number of match rules per connection, applications coordinating many
+    /// proxies can run into this limit. While identical rules are always shared, this method allows
+    /// registering a broader rule, which any match rule subsequently added on this connection will
+    /// share instead of calling `AddMatch`, as long as it [covers](MatchRule::covers) it.
+    ///
+    /// The rule is kept on the bus for as long as the returned [`SharedMatchRule`] (or any of its
+    /// clones) exists, or any of the rules sharing it are still in use. You may also want to hand
+    /// it to [`crate::proxy::Builder::shared_match_rule`] to tie its lifetime to that of a proxy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use zbus::{fdo::PropertiesProxy, message::Type, Connection, MatchRule};
+    ///
+    /// let conn = Connection::system().await?;
+    /// // A single `AddMatch` for all property changes of all the devices..
+    /// let rule = MatchRule::builder()
+    ///     .msg_type(Type::Signal)
+    ///     .sender("org.bluez")?
+    ///     .interface("org.freedesktop.DBus.Properties")?
+    ///     .member("PropertiesChanged")?
+    ///     .build();
+    /// let shared = conn.add_shared_match(rule).await?;
+    ///
+    /// for path in ["/org/bluez/hci0/dev_1", "/org/bluez/hci0/dev_2"] {
+    ///     // ..instead of one per proxy.
+    ///     let proxy = PropertiesProxy::builder(&conn)
+    ///         .destination("org.bluez")?
+    ///         .path(path)?
+    ///         .shared_match_rule(&shared)
+    ///         .build()
+    ///         .await?;
+    ///     let _stream = proxy.receive_properties_changed().await?;
+    /// }
+    /// # Ok::<_, zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn add_shared_match<'m, R>(&self, rule: R) -> Result<SharedMatchRule>
+    where
+        R: TryInto<MatchRule<'m>>,
+        R::Error: Into<Error>,
+    {
+        let rule: OwnedMatchRule = rule.try_into().map_err(Into::into)?.into_owned().into();
+        self.add_match(rule.clone(), None).await?;
+        if let Some(subscription) = self.inner.subscriptions.lock().await.get_mut(&rule) {
+            subscription.shared = true;
+        }
+
+        Ok(SharedMatchRule::new(self.clone(), rule))
+    }
+
     pub(crate) async fn add_match(
         &self,
         rule: OwnedMatchRule,
         max_queued: Option<usize>,
     ) -> Result<Receiver<Result<Message>>> {
-        use std::collections::hash_map::Entry;
-
         if self.inner.msg_senders.lock().await.is_empty() {
             // This only happens if socket reader task has errored out.
             return Err(Error::InputOutput(Arc::new(io::Error::new(
@@ -1058,41 +1254,60 @@ impl Connection {
         }
 
         let mut subscriptions = self.inner.subscriptions.lock().await;
+        if let Some(subscription) = subscriptions.get_mut(&rule) {
+            subscription.num_subscriptions += 1;
+            if let Some(max_queued) = max_queued {
+                if max_queued > subscription.receiver.capacity() {
+                    subscription.receiver.set_capacity(max_queued);
+                }
+            }
+
+            return Ok(subscription.receiver.activate_cloned());
+        }
+
+        let max_queued = max_queued.unwrap_or(DEFAULT_MAX_QUEUED);
+        let (sender, mut receiver) = broadcast(max_queued);
+        receiver.set_await_active(false);
         let msg_type = rule.msg_type().unwrap_or(Type::Signal);
-        match subscriptions.entry(rule.clone()) {
-            Entry::Vacant(e) => {
-                let max_queued = max_queued.unwrap_or(DEFAULT_MAX_QUEUED);
-                let (sender, mut receiver) = broadcast(max_queued);
-                receiver.set_await_active(false);
-                if self.is_bus() && msg_type == Type::Signal {
+        let mut covered_by = None;
+        if self.is_bus() && msg_type == Type::Signal {
+            covered_by = subscriptions
+                .iter()
+                .find(|(shared_rule, s)| s.shared && shared_rule.covers(&rule))
+                .map(|(shared_rule, _)| shared_rule.clone());
+            match &covered_by {
+                Some(shared_rule) => {
+                    trace!("Match rule `{}` covered by `{}`", *rule, **shared_rule);
+                    if let Some(shared) = subscriptions.get_mut(shared_rule) {
+                        shared.num_subscriptions += 1;
+                    }
+                }
+                None => {
                     fdo::DBusProxy::builder(self)
                         .cache_properties(CacheProperties::No)
                         .build()
                         .await?
-                        .add_match_rule(e.key().inner().clone())
+                        .add_match_rule(rule.inner().clone())
                         .await?;
                 }
-                e.insert((1, receiver.clone().deactivate()));
-                self.inner
-                    .msg_senders
-                    .lock()
-                    .await
-                    .insert(Some(rule), sender);
-
-                Ok(receiver)
-            }
-            Entry::Occupied(mut e) => {
-                let (num_subscriptions, receiver) = e.get_mut();
-                *num_subscriptions += 1;
-                if let Some(max_queued) = max_queued {
-                    if max_queued > receiver.capacity() {
-                        receiver.set_capacity(max_queued);
-                    }
-                }
-
-                Ok(receiver.activate_cloned())
             }
         }
+        subscriptions.insert(
+            rule.clone(),
+            Subscription {
+                num_subscriptions: 1,
+                receiver: receiver.clone().deactivate(),
+                shared: false,
+                covered_by,
+            },
+        );
+        self.inner
+            .msg_senders
+            .lock()
+            .await
+            .insert(Some(rule), sender);
+
+        Ok(receiver)
     }
 
     pub(crate) async fn remove_match(&self, rule: OwnedMatchRule) -> Result<bool> {
@@ -1100,38 +1315,48 @@ impl Connection {
         let mut subscriptions = self.inner.subscriptions.lock().await;
         // TODO when it becomes stable, use HashMap::raw_entry and only require expr: &str
         // (both here and in add_match)
-        let msg_type = rule.msg_type().unwrap_or(Type::Signal);
-        match subscriptions.entry(rule) {
-            Entry::Vacant(_) => Ok(false),
-            Entry::Occupied(mut e) => {
-                let rule = e.key().inner().clone();
-                e.get_mut().0 -= 1;
-                if e.get().0 == 0 {
-                    if self.is_bus() && msg_type == Type::Signal {
-                        fdo::DBusProxy::builder(self)
-                            .cache_properties(CacheProperties::No)
-                            .build()
-                            .await?
-                            .remove_match_rule(rule.clone())
-                            .await?;
-                    }
-                    e.remove();
-                    self.inner
-                        .msg_senders
-                        .lock()
-                        .await
-                        .remove(&Some(rule.into()));
-                }
-                Ok(true)
+        let mut rule = rule;
+        let mut removed = false;
+        // Removing the last reference to a rule covered by a shared rule, drops a reference to the
+        // latter.
+        loop {
+            let msg_type = rule.msg_type().unwrap_or(Type::Signal);
+            let mut e = match subscriptions.entry(rule) {
+                Entry::Vacant(_) => return Ok(removed),
+                Entry::Occupied(e) => e,
+            };
+            removed = true;
+            e.get_mut().num_subscriptions -= 1;
+            if e.get().num_subscriptions != 0 {
+                return Ok(true);
+            }
+            let covered_by = e.get().covered_by.clone();
+            if covered_by.is_none() && self.is_bus() && msg_type == Type::Signal {
+                fdo::DBusProxy::builder(self)
+                    .cache_properties(CacheProperties::No)
+                    .build()
+                    .await?
+                    .remove_match_rule(e.key().inner().clone())
+                    .await?;
+            }
+            let (removed_rule, _) = e.remove_entry();
+            self.inner
+                .msg_senders
+                .lock()
+                .await
+                .remove(&Some(removed_rule));
+
+            match covered_by {
+                Some(shared_rule) => rule = shared_rule,
+                None => return Ok(true),
             }
         }
     }
 
     pub(crate) fn queue_remove_match(&self, rule: OwnedMatchRule) {
         let conn = self.clone();
-        let task_name = format!("Remove match `{}`", *rule);
-        let remove_match =
-            async move { conn.remove_match(rule).await }.instrument(trace_span!("{}", task_name));
+        let task_name = self.task_name(&format!("remove match `{}`", *rule));
+        let remove_match = async move { conn.remove_match(rule).await };
         self.inner.executor.spawn(remove_match, &task_name).detach()
     }
 
@@ -1154,6 +1379,7 @@ impl Connection {
     pub(crate) async fn new(
         auth: Authenticated,
         #[allow(unused)] bus_connection: bool,
+        kind: &'static str,
         executor: Executor<'static>,
     ) -> Result<Self> {
         #[cfg(unix)]
@@ -1205,6 +1431,10 @@ impl Connection {
                 msg_receiver,
                 method_return_receiver,
                 registered_names: Mutex::new(HashMap::new()),
+                errors: Default::default(),
+                monitor: AtomicBool::new(false),
+                interceptor: OnceLock::new(),
+                kind,
             }),
         };
 
@@ -1221,6 +1451,18 @@ impl Connection {
         Builder::system()?.build().await
     }
 
+    /// Whether the connection became a monitor.
+    ///
+    /// As per the specification, a connection that became a monitor through
+    /// [`fdo::MonitoringProxy::become_monitor`] must not send messages anymore.
+    pub fn is_monitor(&self) -> bool {
+        self.inner.monitor.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_monitor(&self) {
+        self.inner.monitor.store(true, Ordering::SeqCst);
+    }
+
     /// Returns a listener, notified on various connection activity.
     ///
     /// This function is meant for the caller to implement idle or timeout on inactivity.
@@ -1259,6 +1501,26 @@ impl Connection {
             .map_err(Into::into)
     }
 
+    /// The name of the `task` of the connection, as shown by runtime diagnostics, e.g
+    /// `zbus: socket reader (session)`.
+    pub(crate) fn task_name(&self, task: &str) -> String {
+        format!("zbus: {task} ({})", self.inner.kind)
+    }
+
+    pub(crate) fn set_interceptor(&self, interceptor: crate::test::Interceptor) {
+        self.inner
+            .interceptor
+            .set(interceptor)
+            .expect("Attempted to set `interceptor` twice");
+    }
+
+    /// Deliver `msg` to the connection, as if it was received on the socket.
+    async fn inject(&self, msg: Message) {
+        trace!("Injecting message: {:?}", msg);
+        let senders = self.inner.msg_senders.lock().await;
+        socket_reader::broadcast_msg(&senders, &Ok(msg)).await;
+    }
+
     pub(crate) fn init_socket_reader(
         &self,
         socket_read: Box<dyn socket::ReadHalf>,
@@ -1274,7 +1536,7 @@ impl Connection {
                     already_read,
                     inner.activity_event.clone(),
                 )
-                .spawn(&inner.executor),
+                .spawn(&inner.executor, &self.task_name("socket reader")),
             )
             .expect("Attempted to set `socket_reader_task` twice");
     }
@@ -1287,7 +1549,7 @@ impl From<crate::blocking::Connection> for Connection {
 }
 
 // Internal API that allows keeping a weak connection ref around.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct WeakConnection {
     inner: Weak<ConnectionInner>,
 }
@@ -1308,17 +1570,18 @@ impl From<&Connection> for WeakConnection {
 }
 
 #[derive(Debug)]
-enum NameStatus {
-    // The task waits for name lost signal if owner allows replacement.
-    Owner(#[allow(unused)] Option<Task<()>>),
-    // The task waits for name acquisition signal.
-    Queued(#[allow(unused)] Task<()>),
+struct NameStatus {
+    // Whether the connection is in the queue of the name, rather than its owner.
+    queued: bool,
+    // The task follows the name acquisition and name lost signals, to keep `queued` up to date.
+    #[allow(unused)]
+    task: Option<Task<()>>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::fdo::DBusProxy;
+    use crate::{fdo::DBusProxy, AsyncDrop};
     use ntest::timeout;
     use test_log::test;
 
@@ -1387,6 +1650,266 @@ mod tests {
         let name_has_owner = dbus.name_has_owner(name.try_into().unwrap()).await.unwrap();
         assert!(!name_has_owner);
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn shared_match() {
+        crate::utils::block_on(test_shared_match()).unwrap();
+    }
+
+    async fn test_shared_match() -> Result<()> {
+        let emitter = Connection::session().await?;
+        let conn = Connection::session().await?;
+        let sender = emitter.unique_name().unwrap().to_owned();
+
+        let shared_rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender(sender.as_ref())?
+            .interface("org.zbus.Shared")?
+            .build();
+        let shared = conn.add_shared_match(shared_rule).await?;
+        let ping_rule: OwnedMatchRule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender(sender.as_ref())?
+            .interface("org.zbus.Shared")?
+            .member("Ping")?
+            .path("/org/zbus/Shared")?
+            .build()
+            .into();
+        let mut stream = MessageStream::for_match_rule(ping_rule.clone(), &conn, Some(1)).await?;
+        {
+            let subscriptions = conn.inner.subscriptions.lock().await;
+            assert_eq!(
+                subscriptions[&ping_rule].covered_by.as_ref(),
+                Some(shared.rule())
+            );
+            assert_eq!(subscriptions[shared.rule()].num_subscriptions, 2);
+        }
+
+        // The rule is still needed by the stream.
+        let shared_rule = shared.rule().clone();
+        drop(shared);
+        emitter
+            .emit_signal(
+                None::<()>,
+                "/org/zbus/Shared",
+                "org.zbus.Shared",
+                "Ping",
+                &(),
+            )
+            .await?;
+        let msg = stream.next().await.unwrap()?;
+        assert_eq!(msg.header().member().unwrap(), "Ping");
+
+        stream.async_drop().await;
+        // Dropping `shared` removes its reference asynchronously.
+        while !conn.inner.subscriptions.lock().await.is_empty() {
+            crate::abstractions::timer::Timer::after(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(!conn.remove_match(shared_rule).await?);
+
+        Ok(())
+    }
+
+    #[derive(Debug, crate::DBusError)]
+    #[zbus(prefix = "org.zbus.Ledger")]
+    enum LedgerError {
+        #[zbus(error)]
+        ZBus(Error),
+        InsufficientFunds(String),
+        Frozen,
+    }
+
+    struct Ledger;
+
+    #[crate::interface(name = "org.zbus.Ledger")]
+    impl Ledger {
+        fn withdraw(&self, amount: u32) -> fdo::Result<()> {
+            let err = match amount {
+                0 => fdo::Error::InvalidArgs("Nothing to withdraw".into()),
+                1..=100 => LedgerError::InsufficientFunds(format!("{amount} > 0")).into_fdo(),
+                _ => LedgerError::Frozen.into_fdo(),
+            };
+
+            Err(err)
+        }
+    }
+
+    impl LedgerError {
+        fn into_fdo(self) -> fdo::Error {
+            fdo::Error::ZBus(Error::Registered(crate::RegisteredError::new(self)))
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn registered_errors() {
+        crate::utils::block_on(test_registered_errors()).unwrap();
+    }
+
+    async fn test_registered_errors() -> Result<()> {
+        assert_eq!(
+            LedgerError::error_names(),
+            [
+                "org.zbus.Ledger.InsufficientFunds",
+                "org.zbus.Ledger.Frozen"
+            ]
+        );
+
+        let service = Builder::session()?
+            .serve_at("/org/zbus/Ledger", Ledger)?
+            .build()
+            .await?;
+        let client = Builder::session()?
+            .register_error::<LedgerError>()
+            .build()
+            .await?;
+        let unregistered = Connection::session().await?;
+        let withdraw = |conn: &Connection, amount: u32| {
+            let conn = conn.clone();
+            let dest = service.unique_name().unwrap().to_owned();
+            async move {
+                conn.call_method(
+                    Some(dest),
+                    "/org/zbus/Ledger",
+                    Some("org.zbus.Ledger"),
+                    "Withdraw",
+                    &(amount,),
+                )
+                .await
+                .unwrap_err()
+            }
+        };
+
+        let err = withdraw(&client, 42).await;
+        let Error::Registered(registered) = &err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(registered.name(), "org.zbus.Ledger.InsufficientFunds");
+        assert!(matches!(
+            registered.downcast_ref(),
+            Some(LedgerError::InsufficientFunds(desc)) if desc == "42 > 0"
+        ));
+        // The original reply is kept along with the converted error.
+        let reply = registered.reply().unwrap();
+        assert_eq!(
+            reply.header().error_name().unwrap(),
+            "org.zbus.Ledger.InsufficientFunds"
+        );
+        assert_eq!(reply.body().deserialize::<&str>()?, "42 > 0");
+        assert_eq!(
+            err.reply().unwrap().header().error_name().unwrap(),
+            "org.zbus.Ledger.InsufficientFunds"
+        );
+        assert!(matches!(
+            LedgerError::from(err),
+            LedgerError::InsufficientFunds(desc) if desc == "42 > 0"
+        ));
+
+        let err = withdraw(&client, 1000).await;
+        assert!(matches!(
+            &err,
+            Error::Registered(e) if matches!(e.downcast_ref(), Some(LedgerError::Frozen))
+        ));
+
+        // Other errors are left alone.
+        let err = withdraw(&client, 0).await;
+        assert!(matches!(&err, Error::MethodError(name, _, _) if name.ends_with("InvalidArgs")));
+
+        // Without registration, the error is still correctly named.
+        let err = withdraw(&unregistered, 1000).await;
+        assert!(
+            matches!(&err, Error::MethodError(name, _, _) if name.as_str() == "org.zbus.Ledger.Frozen")
+        );
+        assert!(matches!(LedgerError::from(err), LedgerError::Frozen));
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn signal_context() {
+        crate::utils::block_on(test_signal_context()).unwrap();
+    }
+
+    async fn test_signal_context() -> Result<()> {
+        struct Button;
+
+        #[crate::interface(name = "org.zbus.Button")]
+        impl Button {
+            #[zbus(signal)]
+            async fn pressed(ctxt: &SignalContext<'_>, count: u32) -> Result<()>;
+        }
+
+        let service = Builder::session()?
+            .serve_at("/org/zbus/Button", Button)?
+            .build()
+            .await?;
+        let listener = Connection::session().await?;
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender(service.unique_name().unwrap())?
+            .interface("org.zbus.Button")?
+            .build();
+        let mut stream = MessageStream::for_match_rule(rule, &listener, None).await?;
+
+        let ctxt = service.signal_context("/org/zbus/Button")?;
+        std::thread::spawn(move || crate::utils::block_on(Button::pressed(&ctxt, 3)))
+            .join()
+            .unwrap()?;
+
+        let msg = stream.next().await.unwrap()?;
+        let hdr = msg.header();
+        assert_eq!(hdr.path().unwrap(), "/org/zbus/Button");
+        assert_eq!(hdr.member().unwrap(), "Pressed");
+        assert_eq!(msg.body().deserialize::<u32>()?, 3);
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "glib"))]
+    #[test]
+    #[timeout(15000)]
+    fn main_context() {
+        glib::MainContext::new()
+            .block_on(test_main_context())
+            .unwrap();
+    }
+
+    #[cfg(all(unix, feature = "glib"))]
+    async fn test_main_context() -> Result<()> {
+        struct Greeter;
+
+        #[crate::interface(name = "org.zbus.Greeter")]
+        impl Greeter {
+            // The thread the call is dispatched in.
+            fn thread(&self) -> String {
+                format!("{:?}", std::thread::current().id())
+            }
+        }
+
+        let context = glib::MainContext::ref_thread_default();
+        let service = Builder::session()?
+            .main_context(&context)
+            .serve_at("/org/zbus/Greeter", Greeter)?
+            .build()
+            .await?;
+        let client = Builder::session()?.main_context(&context).build().await?;
+
+        let reply = client
+            .call_method(
+                service.unique_name(),
+                "/org/zbus/Greeter",
+                Some("org.zbus.Greeter"),
+                "Thread",
+                &(),
+            )
+            .await?;
+        let thread = format!("{:?}", std::thread::current().id());
+        assert_eq!(reply.body().deserialize::<String>()?, thread);
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "p2p")]
@@ -1399,7 +1922,7 @@ mod p2p_tests {
 
     use crate::{AuthMechanism, Guid};
 
-    use super::*;
+    use super::{socket::Bridge, *};
 
     // Same numbered client and server are already paired up.
     async fn test_p2p(
@@ -1557,6 +2080,59 @@ mod p2p_tests {
         test_p2p(server1, client1, server2, client2).await
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn bridge_p2p() {
+        crate::utils::block_on(test_bridge_p2p()).unwrap();
+    }
+
+    async fn test_bridge_p2p() -> Result<()> {
+        let (server1, client1) = bridge_p2p_pipe().await?;
+        let (server2, client2) = bridge_p2p_pipe().await?;
+
+        test_p2p(server1, client1, server2, client2).await
+    }
+
+    async fn bridge_p2p_pipe() -> Result<(Connection, Connection)> {
+        let guid = Guid::generate();
+
+        let (p0, p1) = Bridge::pair();
+
+        futures_util::try_join!(
+            Builder::socket(p0)
+                .server(guid)
+                .unwrap()
+                .p2p()
+                // There are no peer credentials to check through a bridge.
+                .auth_mechanisms(&[AuthMechanism::Anonymous])
+                .build(),
+            Builder::socket(p1).p2p().build(),
+        )
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[timeout(15000)]
+    fn task_names() {
+        crate::utils::block_on(async {
+            let (server, client) = unix_p2p_pipe().await.unwrap();
+            assert_eq!(
+                server.task_name("socket reader"),
+                "zbus: socket reader (p2p)"
+            );
+            assert_eq!(
+                client.task_name("object server"),
+                "zbus: object server (p2p)"
+            );
+
+            let session = Connection::session().await.unwrap();
+            assert_eq!(
+                session.task_name("socket reader"),
+                "zbus: socket reader (session)"
+            );
+        });
+    }
+
     #[cfg(unix)]
     async fn unix_p2p_pipe() -> Result<(Connection, Connection)> {
         #[cfg(not(feature = "tokio"))]
@@ -0,0 +1,46 @@
+use static_assertions::assert_impl_all;
+use std::sync::Arc;
+
+use crate::{Connection, OwnedMatchRule};
+
+/// A match rule shared on the bus by other match rules of the same connection.
+///
+/// Use [`Connection::add_shared_match`] to create an instance of this type. The rule is removed
+/// from the bus once all clones of this handle are dropped and none of the rules covered by it are
+/// in use anymore.
+#[derive(Clone, Debug)]
+pub struct SharedMatchRule {
+    inner: Arc<Inner>,
+}
+
+assert_impl_all!(SharedMatchRule: Send, Sync, Unpin);
+
+#[derive(Debug)]
+struct Inner {
+    conn: Connection,
+    rule: OwnedMatchRule,
+}
+
+impl SharedMatchRule {
+    pub(crate) fn new(conn: Connection, rule: OwnedMatchRule) -> Self {
+        Self {
+            inner: Arc::new(Inner { conn, rule }),
+        }
+    }
+
+    /// The match rule.
+    pub fn rule(&self) -> &OwnedMatchRule {
+        &self.inner.rule
+    }
+
+    /// The connection the rule was added to.
+    pub fn connection(&self) -> &Connection {
+        &self.inner.conn
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.conn.queue_remove_match(self.rule.clone());
+    }
+}
@@ -0,0 +1,109 @@
+use std::{
+    future::poll_fn,
+    io,
+    os::{
+        fd::{AsRawFd, BorrowedFd},
+        unix::net::UnixStream,
+    },
+    sync::Arc,
+    task::Poll,
+};
+
+use super::unix::{fd_recvmsg, fd_sendmsg, get_unix_peer_creds};
+use crate::{AsyncFd, Runtime};
+
+/// A unix stream, whose readiness is watched by a [`Runtime`].
+#[derive(Debug)]
+pub(crate) struct AsyncFdStream {
+    // Dropped first, as it's registered with the socket.
+    fd: Box<dyn AsyncFd>,
+    stream: UnixStream,
+}
+
+impl AsyncFdStream {
+    pub(crate) fn new(stream: UnixStream, runtime: &dyn Runtime) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        let fd = runtime.async_fd(stream.as_raw_fd())?;
+
+        Ok(Self { fd, stream })
+    }
+}
+
+impl super::Socket for AsyncFdStream {
+    type ReadHalf = Arc<AsyncFdStream>;
+    type WriteHalf = Arc<AsyncFdStream>;
+
+    fn split(self) -> super::Split<Self::ReadHalf, Self::WriteHalf> {
+        let arc = Arc::new(self);
+
+        super::Split {
+            read: arc.clone(),
+            write: arc,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl super::ReadHalf for Arc<AsyncFdStream> {
+    async fn recvmsg(&mut self, buf: &mut [u8]) -> super::RecvmsgResult {
+        poll_fn(|cx| loop {
+            match fd_recvmsg(self.stream.as_raw_fd(), buf) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    match self.fd.poll_readable(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(res) => res?,
+                    }
+                }
+                v => return Poll::Ready(v),
+            }
+        })
+        .await
+    }
+
+    /// Supports passing file descriptors.
+    fn can_pass_unix_fd(&self) -> bool {
+        true
+    }
+
+    async fn peer_credentials(&mut self) -> io::Result<crate::fdo::ConnectionCredentials> {
+        get_unix_peer_creds(&self.stream).await
+    }
+}
+
+#[async_trait::async_trait]
+impl super::WriteHalf for Arc<AsyncFdStream> {
+    async fn sendmsg(&mut self, buffer: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        poll_fn(|cx| loop {
+            match fd_sendmsg(self.stream.as_raw_fd(), buffer, fds) {
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    match self.fd.poll_writable(cx) {
+                        Poll::Pending => return Poll::Pending,
+                        Poll::Ready(res) => res?,
+                    }
+                }
+                v => return Poll::Ready(v),
+            }
+        })
+        .await
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.stream.shutdown(std::net::Shutdown::Both)
+    }
+
+    #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
+    async fn send_zero_byte(&mut self) -> io::Result<Option<usize>> {
+        super::unix::send_zero_byte(&self.stream).await.map(Some)
+    }
+
+    /// Supports passing file descriptors.
+    fn can_pass_unix_fd(&self) -> bool {
+        true
+    }
+
+    async fn peer_credentials(&mut self) -> io::Result<crate::fdo::ConnectionCredentials> {
+        get_unix_peer_creds(&self.stream).await
+    }
+}
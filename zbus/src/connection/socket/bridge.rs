@@ -0,0 +1,278 @@
+use futures_channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures_util::StreamExt;
+use std::io;
+#[cfg(unix)]
+use std::os::fd::BorrowedFd;
+#[cfg(target_family = "wasm")]
+use {
+    js_sys::{ArrayBuffer, Promise, Uint8Array},
+    tracing::debug,
+    wasm_bindgen::{closure::Closure, JsCast, JsValue},
+    wasm_bindgen_futures::JsFuture,
+    web_sys::{BinaryType, Event, MessageEvent, MessagePort, WebSocket},
+};
+
+use super::{BoxedSplit, ReadHalf, RecvmsgResult, Split, WriteHalf};
+
+/// One end of an in-memory socket, bridging a connection over another transport.
+///
+/// The two ends of a bridge are created together, by [`Bridge::pair`], and the chunks of bytes
+/// sent through one of them are received from the other. One end is given to
+/// [`Builder::socket`](crate::connection::Builder::socket), while the other one relays the chunks
+/// to and from the actual transport.
+///
+/// The bytes of the D-Bus stream go through as is, authentication included, so on the other side
+/// of the transport, a gateway only needs to pipe them to the socket of the bus. The `EXTERNAL`
+/// mechanism then authenticates the connection with the credentials of the gateway.
+///
+/// This is how connections are made on wasm, where there are no sockets: `Bridge::web_socket`
+/// and `Bridge::message_port` relay a bridge over a `WebSocket` and a `MessagePort` respectively.
+///
+/// File descriptors can't be passed through it.
+#[derive(Debug)]
+pub struct Bridge {
+    sender: UnboundedSender<Vec<u8>>,
+    receiver: UnboundedReceiver<Vec<u8>>,
+}
+
+impl Bridge {
+    /// Create the two ends of a bridge.
+    pub fn pair() -> (Self, Self) {
+        let (sender0, receiver1) = unbounded();
+        let (sender1, receiver0) = unbounded();
+
+        (
+            Self {
+                sender: sender0,
+                receiver: receiver0,
+            },
+            Self {
+                sender: sender1,
+                receiver: receiver1,
+            },
+        )
+    }
+
+    /// Send a chunk of bytes to the other end.
+    ///
+    /// # Errors
+    ///
+    /// An error of kind `BrokenPipe` is returned if the other end was dropped.
+    pub fn send(&self, chunk: Vec<u8>) -> io::Result<()> {
+        self.sender
+            .unbounded_send(chunk)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+
+    /// Receive the next chunk of bytes sent by the other end.
+    ///
+    /// Returns `None` once the other end is closed or dropped.
+    pub async fn recv(&mut self) -> Option<Vec<u8>> {
+        self.receiver.next().await
+    }
+
+    /// Close this end, for the other one to receive the end of the stream.
+    pub fn close(&self) {
+        self.sender.close_channel();
+    }
+}
+
+#[cfg(target_family = "wasm")]
+impl Bridge {
+    /// Create a bridge relayed over `socket`, e.g to a gateway piping it to the bus.
+    ///
+    /// The chunks are sent as binary messages, and a text message closes the bridge. The socket is
+    /// closed once the connection is.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[cfg(target_family = "wasm")]
+    /// # async fn connect() -> zbus::Result<()> {
+    /// use web_sys::WebSocket;
+    /// use zbus::{connection::{socket::Bridge, Builder}, fdo::DBusProxy};
+    ///
+    /// let socket = WebSocket::new("wss://localhost:8080/dbus").expect("invalid URL");
+    /// let conn = Builder::socket(Bridge::web_socket(socket)).build().await?;
+    /// let id = DBusProxy::new(&conn).await?.get_id().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn web_socket(socket: WebSocket) -> Self {
+        let (bridge, relay) = Self::pair();
+        socket.set_binary_type(BinaryType::Arraybuffer);
+        wasm_bindgen_futures::spawn_local(relay_web_socket(socket, relay));
+
+        bridge
+    }
+
+    /// Create a bridge relayed over `port`, e.g to a page or a worker owning the actual transport.
+    ///
+    /// The chunks are posted as `Uint8Array`s, or `ArrayBuffer`s from the other side, and any
+    /// other message closes the bridge. `null` is posted before closing the port, once the
+    /// connection is closed.
+    pub fn message_port(port: MessagePort) -> Self {
+        let (bridge, relay) = Self::pair();
+        wasm_bindgen_futures::spawn_local(relay_message_port(port, relay));
+
+        bridge
+    }
+}
+
+impl From<Bridge> for BoxedSplit {
+    fn from(bridge: Bridge) -> Self {
+        Split {
+            read: Box::new(BridgeRead {
+                receiver: bridge.receiver,
+                pending: vec![],
+                pos: 0,
+            }),
+            write: Box::new(BridgeWrite(bridge.sender)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BridgeRead {
+    receiver: UnboundedReceiver<Vec<u8>>,
+    // The data received but not read yet, from `pos`.
+    pending: Vec<u8>,
+    pos: usize,
+}
+
+#[async_trait::async_trait]
+impl ReadHalf for BridgeRead {
+    async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
+        if self.pos == self.pending.len() {
+            // A closed channel is the end of the stream.
+            self.pending = self.receiver.next().await.unwrap_or_default();
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.pending.len() - self.pos);
+        buf[..len].copy_from_slice(&self.pending[self.pos..self.pos + len]);
+        self.pos += len;
+
+        #[cfg(unix)]
+        let ret = (len, vec![]);
+        #[cfg(not(unix))]
+        let ret = len;
+        Ok(ret)
+    }
+}
+
+#[derive(Debug)]
+struct BridgeWrite(UnboundedSender<Vec<u8>>);
+
+#[async_trait::async_trait]
+impl WriteHalf for BridgeWrite {
+    async fn sendmsg(
+        &mut self,
+        buffer: &[u8],
+        #[cfg(unix)] fds: &[BorrowedFd<'_>],
+    ) -> io::Result<usize> {
+        #[cfg(unix)]
+        if !fds.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "fds cannot be sent over a bridge",
+            ));
+        }
+        self.0
+            .unbounded_send(buffer.to_vec())
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+
+        Ok(buffer.len())
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.0.close_channel();
+
+        Ok(())
+    }
+}
+
+// Relay the chunks between the `relay` end of a bridge and `socket`, until either is closed.
+#[cfg(target_family = "wasm")]
+async fn relay_web_socket(socket: WebSocket, mut relay: Bridge) {
+    let sender = relay.sender.clone();
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        match chunk(&event.data()) {
+            Some(chunk) => {
+                // The connection may have been dropped already.
+                let _ = sender.unbounded_send(chunk);
+            }
+            None => sender.close_channel(),
+        }
+    });
+    let sender = relay.sender.clone();
+    let on_close = Closure::<dyn FnMut(Event)>::new(move |_: Event| sender.close_channel());
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    if socket.ready_state() == WebSocket::CONNECTING {
+        // Settled by the first of the `open` and `close` events.
+        let settled = Promise::new(&mut |resolve, _| {
+            for event in ["open", "close"] {
+                let _ = socket.add_event_listener_with_callback(event, &resolve);
+            }
+        });
+        let _ = JsFuture::from(settled).await;
+    }
+    while socket.ready_state() == WebSocket::OPEN {
+        let Some(chunk) = relay.recv().await else {
+            break;
+        };
+        if let Err(e) = socket.send_with_u8_array(&chunk) {
+            debug!("Failed to send on the WebSocket: {e:?}");
+
+            break;
+        }
+    }
+
+    // The handlers must not be called once their closures are dropped.
+    socket.set_onmessage(None);
+    socket.set_onclose(None);
+    relay.close();
+    let _ = socket.close();
+}
+
+// Relay the chunks between the `relay` end of a bridge and `port`, until either is closed.
+#[cfg(target_family = "wasm")]
+async fn relay_message_port(port: MessagePort, mut relay: Bridge) {
+    let sender = relay.sender.clone();
+    let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+        match chunk(&event.data()) {
+            Some(chunk) => {
+                // The connection may have been dropped already.
+                let _ = sender.unbounded_send(chunk);
+            }
+            None => sender.close_channel(),
+        }
+    });
+    // Setting the handler also starts the port.
+    port.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    while let Some(chunk) = relay.recv().await {
+        if let Err(e) = port.post_message(&Uint8Array::from(&chunk[..])) {
+            debug!("Failed to post on the MessagePort: {e:?}");
+
+            break;
+        }
+    }
+
+    // Let the other side know, as ports have no close event.
+    let _ = port.post_message(&JsValue::NULL);
+    port.set_onmessage(None);
+    relay.close();
+    port.close();
+}
+
+// The bytes of the data of a message, if it's an `ArrayBuffer` or a `Uint8Array`.
+#[cfg(target_family = "wasm")]
+fn chunk(data: &JsValue) -> Option<Vec<u8>> {
+    if let Some(buffer) = data.dyn_ref::<ArrayBuffer>() {
+        Some(Uint8Array::new(buffer).to_vec())
+    } else {
+        data.dyn_ref::<Uint8Array>().map(Uint8Array::to_vec)
+    }
+}
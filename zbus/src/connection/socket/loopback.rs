@@ -0,0 +1,86 @@
+use async_broadcast::{broadcast, Receiver, Sender};
+use std::io;
+#[cfg(unix)]
+use std::os::fd::BorrowedFd;
+
+use super::{BoxedSplit, ReadHalf, RecvmsgResult, Split, WriteHalf};
+
+/// The number of writes that can be queued before the writer waits for the reader.
+const CAPACITY: usize = 64;
+
+/// Create an in-memory socket, reading back what's written to it.
+///
+/// File descriptors can't be passed through it.
+pub(crate) fn loopback() -> BoxedSplit {
+    let (mut sender, receiver) = broadcast(CAPACITY);
+    sender.set_await_active(false);
+
+    Split {
+        read: Box::new(LoopbackRead {
+            receiver,
+            pending: vec![],
+            pos: 0,
+        }),
+        write: Box::new(LoopbackWrite(sender)),
+    }
+}
+
+#[derive(Debug)]
+struct LoopbackRead {
+    receiver: Receiver<Vec<u8>>,
+    // The data received but not read yet, from `pos`.
+    pending: Vec<u8>,
+    pos: usize,
+}
+
+#[async_trait::async_trait]
+impl ReadHalf for LoopbackRead {
+    async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
+        if self.pos == self.pending.len() {
+            // A closed channel is the end of the stream.
+            self.pending = self.receiver.recv().await.unwrap_or_default();
+            self.pos = 0;
+        }
+        let len = buf.len().min(self.pending.len() - self.pos);
+        buf[..len].copy_from_slice(&self.pending[self.pos..self.pos + len]);
+        self.pos += len;
+
+        #[cfg(unix)]
+        let ret = (len, vec![]);
+        #[cfg(not(unix))]
+        let ret = len;
+        Ok(ret)
+    }
+}
+
+#[derive(Debug)]
+struct LoopbackWrite(Sender<Vec<u8>>);
+
+#[async_trait::async_trait]
+impl WriteHalf for LoopbackWrite {
+    async fn sendmsg(
+        &mut self,
+        buffer: &[u8],
+        #[cfg(unix)] fds: &[BorrowedFd<'_>],
+    ) -> io::Result<usize> {
+        #[cfg(unix)]
+        if !fds.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "fds cannot be sent over a loopback socket",
+            ));
+        }
+        self.0
+            .broadcast_direct(buffer.to_vec())
+            .await
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+
+        Ok(buffer.len())
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.0.close();
+
+        Ok(())
+    }
+}
@@ -1,14 +1,25 @@
 mod split;
 pub use split::{BoxedSplit, Split};
 
+mod bridge;
+pub use bridge::Bridge;
+
+mod loopback;
+pub(crate) use loopback::loopback;
+#[cfg(unix)]
+mod async_fd;
+#[cfg(unix)]
+pub(crate) use async_fd::AsyncFdStream;
+#[cfg(not(target_family = "wasm"))]
 mod tcp;
+#[cfg(not(target_family = "wasm"))]
 mod unix;
 mod vsock;
 
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use async_io::Async;
 use std::io;
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 use std::sync::Arc;
 
 use crate::fdo::ConnectionCredentials;
@@ -16,10 +27,10 @@ use crate::fdo::ConnectionCredentials;
 use std::os::fd::{BorrowedFd, OwnedFd};
 
 #[cfg(unix)]
-type RecvmsgResult = io::Result<(usize, Vec<OwnedFd>)>;
+pub(crate) type RecvmsgResult = io::Result<(usize, Vec<OwnedFd>)>;
 
 #[cfg(not(unix))]
-type RecvmsgResult = io::Result<usize>;
+pub(crate) type RecvmsgResult = io::Result<usize>;
 
 /// Trait representing some transport layer over which the DBus protocol can be used
 ///
@@ -166,7 +177,7 @@ impl WriteHalf for Box<dyn WriteHalf> {
     }
 }
 
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 impl<T> Socket for Async<T>
 where
     T: std::fmt::Debug + Send + Sync,
@@ -3,8 +3,8 @@ use super::{ReadHalf, Socket, WriteHalf};
 /// A pair of socket read and write halves.
 #[derive(Debug)]
 pub struct Split<R: ReadHalf, W: WriteHalf> {
-    pub(super) read: R,
-    pub(super) write: W,
+    pub(crate) read: R,
+    pub(crate) write: W,
 }
 
 impl<R: ReadHalf, W: WriteHalf> Split<R, W> {
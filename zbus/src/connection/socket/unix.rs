@@ -265,7 +265,7 @@ impl super::WriteHalf for Arc<Async<UnixStream>> {
 }
 
 #[cfg(unix)]
-fn fd_recvmsg(fd: RawFd, buffer: &mut [u8]) -> io::Result<(usize, Vec<OwnedFd>)> {
+pub(super) fn fd_recvmsg(fd: RawFd, buffer: &mut [u8]) -> io::Result<(usize, Vec<OwnedFd>)> {
     let mut iov = [IoSliceMut::new(buffer)];
     let mut cmsgspace = cmsg_space!([RawFd; FDS_MAX]);
 
@@ -295,7 +295,7 @@ fn fd_recvmsg(fd: RawFd, buffer: &mut [u8]) -> io::Result<(usize, Vec<OwnedFd>)>
 }
 
 #[cfg(unix)]
-fn fd_sendmsg(fd: RawFd, buffer: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+pub(super) fn fd_sendmsg(fd: RawFd, buffer: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
     // FIXME: Remove this conversion once nix supports BorrowedFd here.
     //
     // Tracking issue: https://github.com/nix-rust/nix/issues/1750
@@ -318,7 +318,9 @@ fn fd_sendmsg(fd: RawFd, buffer: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<us
 }
 
 #[cfg(unix)]
-async fn get_unix_peer_creds(fd: &impl AsRawFd) -> io::Result<crate::fdo::ConnectionCredentials> {
+pub(super) async fn get_unix_peer_creds(
+    fd: &impl AsRawFd,
+) -> io::Result<crate::fdo::ConnectionCredentials> {
     let fd = fd.as_raw_fd();
     // FIXME: Is it likely enough for sending of 1 byte to block, to justify a task (possibly
     // launching a thread in turn)?
@@ -361,7 +363,7 @@ fn get_unix_peer_creds_blocking(fd: RawFd) -> io::Result<crate::fdo::ConnectionC
 
 // Send 0 byte as a separate SCM_CREDS message.
 #[cfg(any(target_os = "freebsd", target_os = "dragonfly"))]
-async fn send_zero_byte(fd: &impl AsRawFd) -> io::Result<usize> {
+pub(super) async fn send_zero_byte(fd: &impl AsRawFd) -> io::Result<usize> {
     let fd = fd.as_raw_fd();
     crate::Task::spawn_blocking(move || send_zero_byte_blocking(fd), "send zero byte").await
 }
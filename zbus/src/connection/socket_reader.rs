@@ -41,8 +41,8 @@ impl SocketReader {
         }
     }
 
-    pub fn spawn(self, executor: &Executor<'_>) -> Task<()> {
-        executor.spawn(self.receive_msg(), "socket reader")
+    pub fn spawn(self, executor: &Executor<'_>, name: &str) -> Task<()> {
+        executor.spawn_long_lived(self.receive_msg(), name)
     }
 
     // Keep receiving messages and put them on the queue.
@@ -57,35 +57,7 @@ impl SocketReader {
             };
 
             let mut senders = self.senders.lock().await;
-            for (rule, sender) in &*senders {
-                if let Ok(msg) = &msg {
-                    if let Some(rule) = rule.as_ref() {
-                        match rule.matches(msg) {
-                            Ok(true) => (),
-                            Ok(false) => continue,
-                            Err(e) => {
-                                debug!("Error matching message against rule: {:?}", e);
-
-                                continue;
-                            }
-                        }
-                    }
-                }
-
-                if let Err(e) = sender.broadcast_direct(msg.clone()).await {
-                    // An error would be due to either of these:
-                    //
-                    // 1. the channel is closed.
-                    // 2. No active receivers.
-                    //
-                    // In either case, just log it.
-                    trace!(
-                        "Error broadcasting message to stream for `{:?}`: {:?}",
-                        rule,
-                        e
-                    );
-                }
-            }
+            broadcast_msg(&senders, &msg).await;
             trace!("Broadcasted to all streams: {:?}", msg);
 
             if msg.is_err() {
@@ -192,3 +164,39 @@ impl SocketReader {
         Message::from_raw_parts(bytes, seq)
     }
 }
+
+/// Broadcast `msg` to the streams whose match rule it matches.
+pub(crate) async fn broadcast_msg(
+    senders: &HashMap<Option<OwnedMatchRule>, MsgBroadcaster>,
+    msg: &crate::Result<Message>,
+) {
+    for (rule, sender) in senders {
+        if let Ok(msg) = &msg {
+            if let Some(rule) = rule.as_ref() {
+                match rule.matches(msg) {
+                    Ok(true) => (),
+                    Ok(false) => continue,
+                    Err(e) => {
+                        debug!("Error matching message against rule: {:?}", e);
+
+                        continue;
+                    }
+                }
+            }
+        }
+
+        if let Err(e) = sender.broadcast_direct(msg.clone()).await {
+            // An error would be due to either of these:
+            //
+            // 1. the channel is closed.
+            // 2. No active receivers.
+            //
+            // In either case, just log it.
+            trace!(
+                "Error broadcasting message to stream for `{:?}`: {:?}",
+                rule,
+                e
+            );
+        }
+    }
+}
@@ -1,7 +1,14 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    error, fmt,
+    sync::{Arc, RwLock},
+};
+
 use crate::{
     message::{Header, Message},
-    names::ErrorName,
-    Result,
+    names::{ErrorName, OwnedErrorName},
+    Error, Result,
 };
 
 /// A trait that needs to be implemented by error types to be returned from D-Bus methods.
@@ -23,4 +30,152 @@ pub trait DBusError {
 
     // The optional description for the error.
     fn description(&self) -> Option<&str>;
+
+    /// The names of all the errors this type represents.
+    ///
+    /// This is used by [`Connection::register_error`](crate::Connection::register_error) to find
+    /// the error replies to convert to this type. The default implementation returns an empty
+    /// list. The [`DBusError` macro][dm] implements it for you.
+    ///
+    /// [dm]: derive.DBusError.html
+    fn error_names() -> Vec<ErrorName<'static>>
+    where
+        Self: Sized,
+    {
+        vec![]
+    }
+}
+
+/// An application error type registered with
+/// [`Connection::register_error`](crate::Connection::register_error).
+///
+/// Error replies with a registered name are returned as [`Error::Registered`] by method calls,
+/// holding an instance of the registered type. Use [`RegisteredError::downcast_ref`] to get to it.
+/// The error reply itself is kept as well, so its arguments remain accessible through
+/// [`RegisteredError::reply`].
+///
+/// Method handlers can also return it (e.g through [`crate::fdo::Error::ZBus`]), in which case the
+/// error reply is generated by the wrapped error.
+#[derive(Clone)]
+pub struct RegisteredError {
+    error: Arc<dyn AnyDBusError>,
+    reply: Option<Message>,
+}
+
+impl RegisteredError {
+    /// Wrap an application error.
+    pub fn new<E>(error: E) -> Self
+    where
+        E: DBusError + error::Error + Send + Sync + 'static,
+    {
+        Self {
+            error: Arc::new(error),
+            reply: None,
+        }
+    }
+
+    /// Get the wrapped error, if it's of type `E`.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: 'static,
+    {
+        self.error.as_any().downcast_ref()
+    }
+
+    /// The error reply this error was converted from.
+    ///
+    /// This is `None` for errors created with [`RegisteredError::new`].
+    pub fn reply(&self) -> Option<&Message> {
+        self.reply.as_ref()
+    }
+}
+
+impl DBusError for RegisteredError {
+    fn create_reply(&self, msg: &Header<'_>) -> Result<Message> {
+        self.error.create_reply(msg)
+    }
+
+    fn name(&self) -> ErrorName<'_> {
+        self.error.name()
+    }
+
+    fn description(&self) -> Option<&str> {
+        DBusError::description(&*self.error)
+    }
+}
+
+impl fmt::Debug for RegisteredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.error, f)
+    }
+}
+
+impl fmt::Display for RegisteredError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.error, f)
+    }
+}
+
+impl error::Error for RegisteredError {}
+
+trait AnyDBusError: DBusError + error::Error + Send + Sync + 'static {
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<E> AnyDBusError for E
+where
+    E: DBusError + error::Error + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+type Converter = fn(Error) -> RegisteredError;
+
+/// The application error types registered on a connection, by error name.
+#[derive(Default)]
+pub(crate) struct ErrorRegistry(RwLock<HashMap<OwnedErrorName, Converter>>);
+
+impl ErrorRegistry {
+    pub(crate) fn register<E>(&self)
+    where
+        E: DBusError + From<Error> + error::Error + Send + Sync + 'static,
+    {
+        let mut converters = self.0.write().expect("lock poisoned");
+        for name in E::error_names() {
+            converters.insert(name.into(), |e| RegisteredError::new(E::from(e)));
+        }
+    }
+
+    pub(crate) fn extend(&self, other: ErrorRegistry) {
+        let other = other.0.into_inner().expect("lock poisoned");
+        self.0.write().expect("lock poisoned").extend(other);
+    }
+
+    /// Convert `error` to [`Error::Registered`] if it's an error reply with a registered name.
+    pub(crate) fn convert(&self, error: Error) -> Error {
+        let (converter, reply) = match &error {
+            Error::MethodError(name, _, reply) => (
+                self.0.read().expect("lock poisoned").get(name).copied(),
+                reply.clone(),
+            ),
+            _ => return error,
+        };
+
+        match converter {
+            Some(convert) => Error::Registered(RegisteredError {
+                reply: Some(reply),
+                ..convert(error)
+            }),
+            None => error,
+        }
+    }
+}
+
+impl fmt::Debug for ErrorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let converters = self.0.read().expect("lock poisoned");
+        f.debug_set().entries(converters.keys()).finish()
+    }
 }
@@ -6,6 +6,7 @@ use zvariant::Error as VariantError;
 use crate::{
     fdo,
     message::{Message, Type},
+    DBusError, RegisteredError,
 };
 
 /// The error type for `zbus`.
@@ -36,8 +37,9 @@ pub enum Error {
     /// Unexpected or incorrect reply.
     InvalidReply,
     /// A D-Bus method error reply.
-    // According to the spec, there can be all kinds of details in D-Bus errors but nobody adds
-    // anything more than a string description.
+    ///
+    /// The description is the first argument of the reply, if it's a string. Use
+    /// [`Error::reply`] to get to the other arguments.
     MethodError(OwnedErrorName, Option<String>, Message),
     /// A required field is missing in the message headers.
     MissingField,
@@ -59,6 +61,9 @@ pub enum Error {
     MissingParameter(&'static str),
     /// Serial number in the message header is 0 (which is invalid).
     InvalidSerial,
+    /// A D-Bus method error reply, converted to an application error type registered with
+    /// [`Connection::register_error`](crate::Connection::register_error).
+    Registered(RegisteredError),
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
@@ -85,6 +90,7 @@ impl PartialEq for Error {
             (Self::NameTaken, Self::NameTaken) => true,
             (Error::InputOutput(_), Self::InputOutput(_)) => false,
             (Self::Failure(s1), Self::Failure(s2)) => s1 == s2,
+            (Self::Registered(s), Self::Registered(o)) => s.name() == o.name(),
             (_, _) => false,
         }
     }
@@ -113,6 +119,7 @@ impl error::Error for Error {
             Error::Failure(_) => None,
             Error::MissingParameter(_) => None,
             Error::InvalidSerial => None,
+            Error::Registered(e) => Some(e),
         }
     }
 }
@@ -147,6 +154,7 @@ impl fmt::Display for Error {
                 write!(f, "Parameter `{}` was not specified but it is required", p)
             }
             Error::InvalidSerial => write!(f, "Serial number in the message header is 0"),
+            Error::Registered(e) => write!(f, "{e}"),
         }
     }
 }
@@ -176,6 +184,36 @@ impl Clone for Error {
             Error::Failure(e) => Error::Failure(e.clone()),
             Error::MissingParameter(p) => Error::MissingParameter(p),
             Error::InvalidSerial => Error::InvalidSerial,
+            Error::Registered(e) => Error::Registered(e.clone()),
+        }
+    }
+}
+
+impl Error {
+    /// The error reply this error was created from, if any.
+    ///
+    /// This gives access to all the arguments of the reply, not just its description. Some
+    /// services send structured details with their errors, e.g:
+    ///
+    /// ```
+    /// # use zbus::message::Message;
+    /// # let call = Message::method("/", "Start")?.build(&())?;
+    /// # let reply = Message::method_error(&call, "org.example.Error.Failed")?
+    /// #     .build(&("No such unit", 404u32))?;
+    /// let err = zbus::Error::from(reply);
+    /// if let Some(reply) = err.reply() {
+    ///     let (description, code): (String, u32) = reply.body().deserialize()?;
+    ///     assert_eq!(description, "No such unit");
+    ///     assert_eq!(code, 404);
+    /// }
+    /// # Ok::<_, zbus::Error>(())
+    /// ```
+    pub fn reply(&self) -> Option<&Message> {
+        match self {
+            Error::MethodError(_, _, reply) => Some(reply),
+            Error::FDO(e) => e.reply(),
+            Error::Registered(e) => e.reply(),
+            _ => None,
         }
     }
 }
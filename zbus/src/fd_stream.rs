@@ -0,0 +1,256 @@
+//! Streaming of large data out-of-band, through file descriptors.
+//!
+//! Messages are not meant to carry large amounts of data, such as logs, images or firmware. The
+//! common pattern for those is to pass a file descriptor instead, from which the data is read
+//! independently of the bus. [`FdStream`] standardizes that pattern: a method returns one, and
+//! its caller reads the data from it as an [`AsyncRead`].
+//!
+//! Passing file descriptors requires a Unix socket transport.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! use futures_util::io::AsyncReadExt;
+//! use std::collections::HashMap;
+//! use zbus::{fd_stream::FdStream, fdo, interface, proxy, Connection};
+//!
+//! struct Logs;
+//!
+//! #[interface(name = "org.example.Logs")]
+//! impl Logs {
+//!     fn dump(&self) -> fdo::Result<FdStream> {
+//!         let logs = b"All good so far\n".to_vec();
+//!         let metadata = HashMap::from([("size".to_string(), (logs.len() as u64).into())]);
+//!
+//!         FdStream::from_bytes(logs, metadata).map_err(|e| fdo::Error::IOError(e.to_string()))
+//!     }
+//! }
+//!
+//! #[proxy(
+//!     interface = "org.example.Logs",
+//!     default_service = "org.example.Logs",
+//!     default_path = "/org/example/Logs"
+//! )]
+//! trait Logs {
+//!     fn dump(&self) -> zbus::Result<FdStream>;
+//! }
+//!
+//! # zbus::block_on(async {
+//! // On the service side.
+//! let service = Connection::session().await?;
+//! service.object_server().at("/org/example/Logs", Logs).await?;
+//! service.request_name("org.example.Logs").await?;
+//!
+//! // On the client side.
+//! let connection = Connection::session().await?;
+//! let stream = LogsProxy::new(&connection).await?.dump().await?;
+//! let size = u64::try_from(&stream.metadata()["size"])?;
+//! let mut logs = Vec::with_capacity(size as usize);
+//! stream.into_reader().read_to_end(&mut logs).await?;
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! # })?;
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! ```
+//!
+//! [`AsyncRead`]: futures_util::io::AsyncRead
+
+use futures_util::io::AsyncRead;
+use serde::{Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, Write},
+    os::{fd, unix::net::UnixStream},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tracing::debug;
+use zvariant::{OwnedFd, OwnedValue, Type};
+
+use crate::{abstractions::file::FileReader, Task};
+
+/// Data passed through a file descriptor, along with metadata.
+///
+/// This is sent as a `(ha{sv})` structure. The metadata is free-form, and typically describes the
+/// data, e.g its size or content type.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug, Serialize, Deserialize, Type)]
+pub struct FdStream {
+    fd: OwnedFd,
+    metadata: HashMap<String, OwnedValue>,
+}
+
+assert_impl_all!(FdStream: Send, Sync, Unpin);
+
+impl FdStream {
+    /// Create a stream reading from `fd`.
+    ///
+    /// This can be any readable file descriptor, e.g a pipe or a memfd.
+    pub fn new<F>(fd: F, metadata: HashMap<String, OwnedValue>) -> Self
+    where
+        F: Into<fd::OwnedFd>,
+    {
+        Self {
+            fd: OwnedFd::from(fd.into()),
+            metadata,
+        }
+    }
+
+    /// Create a stream reading `data`.
+    ///
+    /// The data is written to the stream in the background, until the reader is done with it.
+    pub fn from_bytes<D>(data: D, metadata: HashMap<String, OwnedValue>) -> io::Result<Self>
+    where
+        D: Into<Vec<u8>>,
+    {
+        let data = data.into();
+        let (stream, mut writer) = Self::channel(metadata)?;
+        Task::spawn_blocking(
+            move || {
+                if let Err(e) = writer.write_all(&data) {
+                    debug!("Failed to write streamed data: {e}");
+                }
+            },
+            "fd stream writer",
+        )
+        .detach();
+
+        Ok(stream)
+    }
+
+    /// Create a stream, along with the writing end of it.
+    ///
+    /// This is for data that is produced progressively. The writer is a blocking socket, meant to
+    /// be written from a separate thread, and the stream ends once it's dropped.
+    pub fn channel(metadata: HashMap<String, OwnedValue>) -> io::Result<(Self, UnixStream)> {
+        let (reader, writer) = UnixStream::pair()?;
+        reader.shutdown(std::net::Shutdown::Write)?;
+        writer.shutdown(std::net::Shutdown::Read)?;
+
+        Ok((Self::new(reader, metadata), writer))
+    }
+
+    /// The metadata describing the data.
+    pub fn metadata(&self) -> &HashMap<String, OwnedValue> {
+        &self.metadata
+    }
+
+    /// Read the data asynchronously.
+    pub fn into_reader(self) -> FdReader {
+        let fd = fd::OwnedFd::from(self.fd);
+
+        FdReader(FileReader::new(File::from(fd)))
+    }
+
+    /// The file descriptor to read the data from.
+    pub fn into_fd(self) -> fd::OwnedFd {
+        self.fd.into()
+    }
+}
+
+/// Reads the data of a [`FdStream`].
+///
+/// The reads happen on a thread reserved for blocking operations, so any kind of file descriptor
+/// is supported.
+#[derive(Debug)]
+pub struct FdReader(FileReader);
+
+assert_impl_all!(FdReader: Send, Sync, Unpin);
+
+impl AsyncRead for FdReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::io::AsyncReadExt;
+    use ntest::timeout;
+    use std::{collections::HashMap, io::Write};
+    use test_log::test;
+
+    use super::FdStream;
+    use crate::{connection, fdo, interface, utils::block_on};
+
+    struct Firmware(Vec<u8>);
+
+    #[interface(name = "org.zbus.Firmware")]
+    impl Firmware {
+        fn download(&self) -> fdo::Result<FdStream> {
+            let metadata = HashMap::from([("size".to_string(), (self.0.len() as u64).into())]);
+
+            FdStream::from_bytes(self.0.clone(), metadata)
+                .map_err(|e| fdo::Error::IOError(e.to_string()))
+        }
+
+        fn generate(&self, count: u32) -> fdo::Result<FdStream> {
+            let (stream, mut writer) = FdStream::channel(HashMap::new())
+                .map_err(|e| fdo::Error::IOError(e.to_string()))?;
+            std::thread::spawn(move || {
+                for i in 0..count {
+                    writeln!(writer, "{i}").unwrap();
+                }
+            });
+
+            Ok(stream)
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn fd_stream() {
+        block_on(async {
+            // Larger than the buffer of a socket.
+            let firmware: Vec<u8> = (0..1024 * 1024).map(|i| i as u8).collect();
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Firmware", Firmware(firmware.clone()))?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let reply = client
+                .call_method(
+                    service.unique_name(),
+                    "/org/zbus/Firmware",
+                    Some("org.zbus.Firmware"),
+                    "Download",
+                    &(),
+                )
+                .await?;
+            let stream: FdStream = reply.body().deserialize()?;
+            let size = u64::try_from(&stream.metadata()["size"])?;
+            assert_eq!(size, firmware.len() as u64);
+            let mut data = vec![];
+            stream.into_reader().read_to_end(&mut data).await.unwrap();
+            assert_eq!(data, firmware);
+
+            let reply = client
+                .call_method(
+                    service.unique_name(),
+                    "/org/zbus/Firmware",
+                    Some("org.zbus.Firmware"),
+                    "Generate",
+                    &(3u32,),
+                )
+                .await?;
+            let stream: FdStream = reply.body().deserialize()?;
+            let mut data = String::new();
+            stream
+                .into_reader()
+                .read_to_string(&mut data)
+                .await
+                .unwrap();
+            assert_eq!(data, "0\n1\n2\n");
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -4,23 +4,39 @@
 //! be useful across various D-Bus applications. This module provides their proxy.
 
 use enumflags2::{bitflags, BitFlags};
+use futures_core::{stream::FusedStream, Stream};
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use static_assertions::assert_impl_all;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
 use zbus_names::{
-    BusName, InterfaceName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName, UniqueName,
-    WellKnownName,
+    BusName, InterfaceName, OwnedBusName, OwnedInterfaceName, OwnedUniqueName, OwnedWellKnownName,
+    UniqueName, WellKnownName,
 };
 use zvariant::{
     DeserializeDict, ObjectPath, Optional, OwnedObjectPath, OwnedValue, SerializeDict, Type, Value,
 };
 
 use crate::{
-    interface, message::Header, object_server::SignalContext, proxy, DBusError, ObjectServer,
-    OwnedGuid,
+    interface,
+    message::{self, Header, Message},
+    object_server::SignalContext,
+    proxy, Connection, DBusError, MessageStream, ObjectServer, OwnedGuid,
 };
 
+#[cfg(feature = "polkit")]
+#[macro_use]
+pub mod polkit;
+
+#[cfg(feature = "dbus-broker")]
+pub mod broker;
+
 #[rustfmt::skip]
 macro_rules! gen_introspectable_proxy {
     ($gen_async:literal, $gen_blocking:literal) => {
@@ -42,6 +58,118 @@ macro_rules! gen_introspectable_proxy {
 gen_introspectable_proxy!(true, false);
 assert_impl_all!(IntrospectableProxy<'_>: Send, Sync, Unpin);
 
+#[cfg(feature = "xml")]
+impl<'p> IntrospectableProxy<'p> {
+    /// Introspect the object and all the objects below it.
+    ///
+    /// This walks the child nodes reported by each object, and parses the introspection data into
+    /// an [`IntrospectionTree`]. Only available with the `xml` feature.
+    pub async fn introspect_tree(&self) -> Result<IntrospectionTree> {
+        let proxy = self.inner();
+
+        introspect_tree(
+            proxy.connection(),
+            proxy.destination().to_owned(),
+            proxy.path().to_owned(),
+        )
+        .await
+    }
+}
+
+#[cfg(feature = "xml")]
+fn introspect_tree<'c>(
+    conn: &'c Connection,
+    destination: BusName<'static>,
+    path: ObjectPath<'static>,
+) -> Pin<Box<dyn std::future::Future<Output = Result<IntrospectionTree>> + Send + 'c>> {
+    Box::pin(async move {
+        let xml = IntrospectableProxy::builder(conn)
+            .destination(destination.clone())?
+            .path(path.clone())?
+            .cache_properties(crate::CacheProperties::No)
+            .build()
+            .await?
+            .introspect()
+            .await?;
+        let node = zbus_xml::Node::from_reader(xml.as_bytes()).map_err(|e| {
+            Error::Failed(format!(
+                "Failed to parse the introspection data of `{path}`: {e}"
+            ))
+        })?;
+        let mut children = vec![];
+        for name in node.nodes().iter().filter_map(|n| n.name()) {
+            let child_path = match path.as_str() {
+                "/" => format!("/{name}"),
+                parent => format!("{parent}/{name}"),
+            };
+            let child_path = ObjectPath::try_from(child_path).map_err(|e| {
+                Error::Failed(format!("Invalid child node `{name}` of `{path}`: {e}"))
+            })?;
+            children.push(introspect_tree(conn, destination.clone(), child_path).await?);
+        }
+
+        Ok(IntrospectionTree {
+            path: path.into(),
+            node,
+            children,
+        })
+    })
+}
+
+/// The introspection data of an object and of all the objects below it.
+///
+/// Returned by [`IntrospectableProxy::introspect_tree`]. Only available with the `xml` feature.
+#[cfg(feature = "xml")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntrospectionTree {
+    path: OwnedObjectPath,
+    node: zbus_xml::Node<'static>,
+    children: Vec<IntrospectionTree>,
+}
+
+#[cfg(feature = "xml")]
+assert_impl_all!(IntrospectionTree: Send, Sync, Unpin);
+
+#[cfg(feature = "xml")]
+impl IntrospectionTree {
+    /// The absolute path of the object.
+    pub fn path(&self) -> &OwnedObjectPath {
+        &self.path
+    }
+
+    /// The introspection data of the object, as it was returned.
+    pub fn node(&self) -> &zbus_xml::Node<'static> {
+        &self.node
+    }
+
+    /// The interfaces of the object.
+    pub fn interfaces(&self) -> &[zbus_xml::Interface<'static>] {
+        self.node.interfaces()
+    }
+
+    /// The objects directly below the object.
+    pub fn children(&self) -> &[IntrospectionTree] {
+        &self.children
+    }
+
+    /// The object at `path`, be it this object or one below it.
+    pub fn find(&self, path: &ObjectPath<'_>) -> Option<&IntrospectionTree> {
+        self.iter().find(|tree| *tree.path == *path)
+    }
+
+    /// Iterate over this object and all the objects below it, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &IntrospectionTree> {
+        let mut stack = vec![self];
+
+        std::iter::from_fn(move || {
+            let tree = stack.pop()?;
+            stack.extend(tree.children.iter().rev());
+
+            Some(tree)
+        })
+    }
+}
+
 /// Server-side implementation for the `org.freedesktop.DBus.Introspectable` interface.
 /// This interface is implemented automatically for any object registered to the
 /// [ObjectServer](crate::ObjectServer).
@@ -56,12 +184,14 @@ impl Introspectable {
     ) -> Result<String> {
         let path = header.path().ok_or(crate::Error::MissingField)?;
         let root = server.root().read().await;
-        let node = root
-            .get_child(path, false)
-            .0
-            .ok_or_else(|| Error::UnknownObject(format!("Unknown object '{path}'")))?;
-
-        Ok(node.introspect().await)
+        let virtual_children = root.virtual_children(path);
+        match root.get_child(path, false).0 {
+            Some(node) => Ok(node.introspect(&virtual_children).await),
+            None => match root.fallback_node(path) {
+                Some(node) => Ok(node.introspect(&virtual_children).await),
+                None => Err(Error::UnknownObject(format!("Unknown object '{path}'"))),
+            },
+        }
     }
 }
 
@@ -110,6 +240,56 @@ macro_rules! gen_properties_proxy {
 gen_properties_proxy!(true, false);
 assert_impl_all!(PropertiesProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> PropertiesProxy<'p> {
+    /// Get all the properties of the interface `interface_name`, deserialized into `T`.
+    ///
+    /// This is [`PropertiesProxy::get_all`], but deserializing the returned `a{sv}` dictionary
+    /// into `T` instead of a `HashMap`. The easiest way to create a suitable type is to derive
+    /// [`zvariant::DeserializeDict`] and [`zvariant::Type`] for a struct, with the `a{sv}`
+    /// signature. Properties without a matching field are ignored, and fields should be `Option`
+    /// for properties that may be absent.
+    ///
+    /// See also [`crate::Proxy::get_all_as`], for when you have a proxy for the interface itself.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use zbus::{
+    ///     fdo::PropertiesProxy,
+    ///     names::InterfaceName,
+    ///     zvariant::{DeserializeDict, Type},
+    ///     Connection,
+    /// };
+    ///
+    /// #[derive(Debug, DeserializeDict, Type)]
+    /// #[zvariant(signature = "a{sv}", rename_all = "PascalCase")]
+    /// struct BusProperties {
+    ///     features: Option<Vec<String>>,
+    /// }
+    ///
+    /// let connection = Connection::session().await?;
+    /// let proxy = PropertiesProxy::builder(&connection)
+    ///     .destination("org.freedesktop.DBus")?
+    ///     .path("/org/freedesktop/DBus")?
+    ///     .build()
+    ///     .await?;
+    /// let iface = InterfaceName::from_static_str("org.freedesktop.DBus")?;
+    /// let props: BusProperties = proxy.get_all_as(iface).await?;
+    /// println!("Bus features: {:?}", props.features);
+    /// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn get_all_as<T>(&self, interface_name: InterfaceName<'_>) -> Result<T>
+    where
+        T: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        let reply = self.inner().call_method("GetAll", &interface_name).await?;
+
+        reply.body().deserialize().map_err(Into::into)
+    }
+}
+
 /// Server-side implementation for the `org.freedesktop.DBus.Properties` interface.
 /// This interface is implemented automatically for any object registered to the
 /// [ObjectServer].
@@ -124,19 +304,22 @@ impl Properties {
         interface_name: InterfaceName<'_>,
         property_name: &str,
         #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(connection)] conn: &Connection,
         #[zbus(header)] header: Header<'_>,
     ) -> Result<OwnedValue> {
         let path = header.path().ok_or(crate::Error::MissingField)?;
         let root = server.root().read().await;
         let iface = root
-            .get_child(path, false)
-            .0
-            .and_then(|node| node.interface_lock(interface_name.as_ref()))
+            .interface_lock_at(path, interface_name.as_ref())
             .ok_or_else(|| {
                 Error::UnknownInterface(format!("Unknown interface '{interface_name}'"))
             })?;
 
-        let res = iface.read().await.get(property_name).await;
+        let res = iface
+            .read()
+            .await
+            .get_for_caller(property_name, server, conn, &header)
+            .await;
         res.unwrap_or_else(|| {
             Err(Error::UnknownProperty(format!(
                 "Unknown property '{property_name}'"
@@ -156,9 +339,7 @@ impl Properties {
         let path = header.path().ok_or(crate::Error::MissingField)?;
         let root = server.root().read().await;
         let iface = root
-            .get_child(path, false)
-            .0
-            .and_then(|node| node.interface_lock(interface_name.as_ref()))
+            .interface_lock_at(path, interface_name.as_ref())
             .ok_or_else(|| {
                 Error::UnknownInterface(format!("Unknown interface '{interface_name}'"))
             })?;
@@ -190,19 +371,22 @@ impl Properties {
         &self,
         interface_name: InterfaceName<'_>,
         #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(connection)] conn: &Connection,
         #[zbus(header)] header: Header<'_>,
     ) -> Result<HashMap<String, OwnedValue>> {
         let path = header.path().ok_or(crate::Error::MissingField)?;
         let root = server.root().read().await;
         let iface = root
-            .get_child(path, false)
-            .0
-            .and_then(|node| node.interface_lock(interface_name.as_ref()))
+            .interface_lock_at(path, interface_name.as_ref())
             .ok_or_else(|| {
                 Error::UnknownInterface(format!("Unknown interface '{interface_name}'"))
             })?;
 
-        let res = iface.read().await.get_all().await?;
+        let res = iface
+            .read()
+            .await
+            .get_all_for_caller(server, conn, &header)
+            .await?;
         Ok(res)
     }
 
@@ -270,6 +454,149 @@ macro_rules! gen_object_manager_proxy {
 gen_object_manager_proxy!(true, false);
 assert_impl_all!(ObjectManagerProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> ObjectManagerProxy<'p> {
+    /// Keep a local copy of the managed objects, and receive the changes to them.
+    ///
+    /// This queries the managed objects and follows the `InterfacesAdded` and `InterfacesRemoved`
+    /// signals, taking care of the ones received while the query is in flight. The returned stream
+    /// yields an [`ObjectChange`] for each change, and keeps the managed objects up to date in
+    /// [`ObjectChanges::objects`].
+    ///
+    /// As with the signals themselves, changes to the properties of existing interfaces are not
+    /// reported.
+    pub async fn receive_object_changes(&self) -> Result<ObjectChanges> {
+        // Subscribe before querying the objects, so that no change is missed in between.
+        let stream = self.inner().receive_all_signals().await?;
+        let reply = self.inner().call_method("GetManagedObjects", &()).await?;
+        let objects = reply.body().deserialize()?;
+
+        Ok(ObjectChanges {
+            objects,
+            initial_position: reply.recv_position(),
+            stream,
+        })
+    }
+}
+
+/// A change of the objects managed by an object manager.
+///
+/// See [`ObjectManagerProxy::receive_object_changes`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ObjectChange {
+    /// An object was added.
+    ObjectAdded(OwnedObjectPath),
+    /// An object was removed, along with all its interfaces.
+    ObjectRemoved(OwnedObjectPath),
+    /// An existing object gained or lost interfaces.
+    InterfacesChanged {
+        /// The path of the object.
+        path: OwnedObjectPath,
+        /// The interfaces added to the object, or whose properties were replaced.
+        added: Vec<OwnedInterfaceName>,
+        /// The interfaces removed from the object.
+        removed: Vec<OwnedInterfaceName>,
+    },
+}
+
+/// A stream of the changes of the objects managed by an object manager.
+///
+/// Use [`ObjectManagerProxy::receive_object_changes`] to create an instance of this type.
+#[derive(Debug)]
+pub struct ObjectChanges {
+    objects: ManagedObjects,
+    // The signals received before this were sent before the objects were queried.
+    initial_position: message::Sequence,
+    stream: crate::proxy::SignalStream<'static>,
+}
+
+assert_impl_all!(ObjectChanges: Send, Sync, Unpin);
+
+impl ObjectChanges {
+    /// The managed objects, as of the last yielded change.
+    pub fn objects(&self) -> &ManagedObjects {
+        &self.objects
+    }
+
+    /// Apply the change of the signal `msg` to the objects.
+    fn apply(&mut self, msg: &Message) -> Option<ObjectChange> {
+        let hdr = msg.header();
+        match hdr.member()?.as_str() {
+            "InterfacesAdded" => {
+                let (path, interfaces): (
+                    OwnedObjectPath,
+                    HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>,
+                ) = msg.body().deserialize().ok()?;
+                let added = interfaces.keys().cloned().collect();
+                match self.objects.get_mut(&path) {
+                    Some(object) => {
+                        object.extend(interfaces);
+
+                        Some(ObjectChange::InterfacesChanged {
+                            path,
+                            added,
+                            removed: vec![],
+                        })
+                    }
+                    None => {
+                        self.objects.insert(path.clone(), interfaces);
+
+                        Some(ObjectChange::ObjectAdded(path))
+                    }
+                }
+            }
+            "InterfacesRemoved" => {
+                let (path, interfaces): (OwnedObjectPath, Vec<OwnedInterfaceName>) =
+                    msg.body().deserialize().ok()?;
+                let object = self.objects.get_mut(&path)?;
+                let removed: Vec<_> = interfaces
+                    .into_iter()
+                    .filter(|name| object.remove(name).is_some())
+                    .collect();
+                if object.is_empty() {
+                    self.objects.remove(&path);
+
+                    Some(ObjectChange::ObjectRemoved(path))
+                } else if removed.is_empty() {
+                    None
+                } else {
+                    Some(ObjectChange::InterfacesChanged {
+                        path,
+                        added: vec![],
+                        removed,
+                    })
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Stream for ObjectChanges {
+    type Item = ObjectChange;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            let Some(msg) = futures_core::ready!(this.stream.poll_next_unpin(cx)) else {
+                return Poll::Ready(None);
+            };
+            if msg.recv_position() < this.initial_position {
+                continue;
+            }
+            if let Some(change) = this.apply(&msg) {
+                return Poll::Ready(Some(change));
+            }
+        }
+    }
+}
+
+impl FusedStream for ObjectChanges {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
 /// Service-side [Object Manager][om] interface implementation.
 ///
 /// The recommended path to add this interface at is the path form of the well-known name of a D-Bus
@@ -283,6 +610,13 @@ assert_impl_all!(ObjectManagerProxy<'_>: Send, Sync, Unpin);
 /// the `path` its added at. You can use this fact to minimize the signal emissions by populating
 /// the entire (sub)tree under `path` before registering an object manager.
 ///
+/// Afterwards, `InterfacesAdded` and `InterfacesRemoved` signals are emitted on your behalf
+/// whenever interfaces are added or removed under `path` through the [`ObjectServer`]. Intermediate
+/// nodes, i-e paths without any interface of their own, are not considered objects and hence are
+/// not reported.
+///
+/// This type is also available as [`crate::object_server::ObjectManager`].
+///
 /// [om]: https://dbus.freedesktop.org/doc/dbus-specification.html#standard-interfaces-objectmanager
 #[derive(Debug, Clone)]
 pub struct ObjectManager;
@@ -360,23 +694,8 @@ pub(crate) struct Peer;
 impl Peer {
     fn ping(&self) {}
 
-    fn get_machine_id(&self) -> Result<String> {
-        let mut id = match std::fs::read_to_string("/var/lib/dbus/machine-id") {
-            Ok(id) => id,
-            Err(e) => {
-                if let Ok(id) = std::fs::read_to_string("/etc/machine-id") {
-                    id
-                } else {
-                    return Err(Error::IOError(format!(
-                        "Failed to read from /var/lib/dbus/machine-id or /etc/machine-id: {e}"
-                    )));
-                }
-            }
-        };
-
-        let len = id.trim_end().len();
-        id.truncate(len);
-        Ok(id)
+    async fn get_machine_id(&self, #[zbus(object_server)] server: &ObjectServer) -> Result<String> {
+        server.machine_id().await
     }
 }
 
@@ -391,39 +710,106 @@ macro_rules! gen_monitoring_proxy {
             gen_async = $gen_async,
             gen_blocking = $gen_blocking,
         )]
-        trait Monitoring {
-            /// Converts the connection into a monitor connection which can be used as a
-            /// debugging/monitoring tool.
-            ///
-            /// After this call successfully returns, sending any messages on the bus will result
-            /// in an error. This is why this method takes ownership of `self`, since there is not
-            /// much use for the proxy anymore. It is highly recommended to convert the underlying
-            /// [`Connection`] to a [`MessageStream`] and iterate over messages from the stream,
-            /// after this call.
-            ///
-            /// See [the spec] for details on all the implications and caveats.
-            /// 
-            /// # Arguments
-            ///
-            /// * `match_rules` - A list of match rules describing the messages you want to receive.
-            ///   An empty list means you are want to receive all messages going through the bus.
-            /// * `flags` - This argument is currently unused by the bus. Just pass a `0`.
-            ///
-            /// [the spec]: https://dbus.freedesktop.org/doc/dbus-specification.html#bus-messages-become-monitor
-            /// [`Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
-            /// [`MessageStream`]: https://docs.rs/zbus/latest/zbus/struct.MessageStream.html
-            fn become_monitor(
-                self,
-                match_rules: &[crate::MatchRule<'_>],
-                flags: u32,
-            ) -> Result<()>;
-        }
+        trait Monitoring {}
     };
 }
 
 gen_monitoring_proxy!(true, false);
 assert_impl_all!(MonitoringProxy<'_>: Send, Sync, Unpin);
 
+impl<'p> MonitoringProxy<'p> {
+    /// Converts the connection into a monitor connection which can be used as a
+    /// debugging/monitoring tool, and returns the stream of monitored messages.
+    ///
+    /// After this call successfully returns, the connection can't send messages anymore, as
+    /// required by [the spec], which also details the other implications and caveats. This is why
+    /// this method takes ownership of `self`, since there is no use for the proxy anymore.
+    ///
+    /// `match_rules` describes the messages you want to receive. An empty list means you want to
+    /// receive all messages going through the bus.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # zbus::block_on(async {
+    /// use futures_util::stream::TryStreamExt;
+    /// use zbus::{fdo::MonitoringProxy, Connection};
+    ///
+    /// let connection = Connection::session().await?;
+    /// let mut stream = MonitoringProxy::new(&connection)
+    ///     .await?
+    ///     .become_monitor(&[])
+    ///     .await?;
+    /// while let Some(msg) = stream.try_next().await? {
+    ///     println!("Got message: {}", msg);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// [the spec]: https://dbus.freedesktop.org/doc/dbus-specification.html#bus-messages-become-monitor
+    pub async fn become_monitor(
+        self,
+        match_rules: &[crate::MatchRule<'_>],
+    ) -> Result<MonitorStream> {
+        let proxy = self.into_inner();
+        let conn = proxy.connection().clone();
+        let call = Message::method(proxy.path().clone(), "BecomeMonitor")?
+            .destination(proxy.destination().clone())?
+            .interface(proxy.interface().clone())?
+            .build(&(match_rules, 0u32))?;
+        let serial = call.primary_header().serial_num();
+        // Listen before calling, so that no message is missed once the reply is received.
+        let mut stream = MessageStream::from(&conn);
+        conn.send(&call).await?;
+
+        while let Some(msg) = stream.next().await {
+            let msg = msg?;
+            let hdr = msg.header();
+            if hdr.reply_serial() != Some(serial) {
+                continue;
+            }
+            match msg.message_type() {
+                message::Type::MethodReturn => {
+                    conn.set_monitor();
+
+                    return Ok(MonitorStream(stream));
+                }
+                message::Type::Error => return Err(crate::Error::from(msg.clone()).into()),
+                _ => (),
+            }
+        }
+
+        Err(
+            crate::Error::InputOutput(std::io::Error::from(std::io::ErrorKind::BrokenPipe).into())
+                .into(),
+        )
+    }
+}
+
+/// The stream of the messages received by a monitor connection.
+///
+/// See [`MonitoringProxy::become_monitor`]. Unlike a [`MessageStream`], it
+/// doesn't give access to the connection, since it can't be used for anything else anymore.
+#[derive(Debug)]
+pub struct MonitorStream(crate::MessageStream);
+
+assert_impl_all!(MonitorStream: Send, Sync, Unpin);
+
+impl Stream for MonitorStream {
+    type Item = crate::Result<Message>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().0).poll_next(cx)
+    }
+}
+
+impl FusedStream for MonitorStream {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
+
 #[rustfmt::skip]
 macro_rules! gen_stats_proxy {
     ($gen_async:literal, $gen_blocking:literal) => {
@@ -436,22 +822,14 @@ macro_rules! gen_stats_proxy {
             gen_blocking = $gen_blocking,
         )]
         trait Stats {
-            /// GetStats (undocumented)
-            fn get_stats(&self) -> Result<Vec<HashMap<String, OwnedValue>>>;
-
-            /// GetConnectionStats (undocumented)
-            fn get_connection_stats(&self, name: BusName<'_>) -> Result<Vec<HashMap<String, OwnedValue>>>;
-
-            /// GetAllMatchRules (undocumented)
-            fn get_all_match_rules(&self) -> 
-                Result<
-                    Vec<
-                        HashMap<
-                            crate::names::OwnedUniqueName,
-                            Vec<crate::OwnedMatchRule>,
-                        >
-                    >
-                >;
+            /// Get the statistics of the bus.
+            fn get_stats(&self) -> Result<BusStats>;
+
+            /// Get the statistics of the connection owning `name`.
+            fn get_connection_stats(&self, name: BusName<'_>) -> Result<ConnectionStats>;
+
+            /// Get the match rules of all the connections.
+            fn get_all_match_rules(&self) -> Result<ConnectionMatchRules>;
         }
     };
 }
@@ -459,6 +837,307 @@ macro_rules! gen_stats_proxy {
 gen_stats_proxy!(true, false);
 assert_impl_all!(StatsProxy<'_>: Send, Sync, Unpin);
 
+/// The statistics of a bus, as returned by [`StatsProxy::get_stats`].
+///
+/// The peak values are the highest ones since the bus started. Statistics that the bus doesn't
+/// keep track of are omitted.
+#[derive(Clone, Debug, Default, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[zvariant(signature = "a{sv}")]
+pub struct BusStats {
+    #[zvariant(rename = "Serial")]
+    serial: Option<u32>,
+
+    #[zvariant(rename = "ActiveConnections")]
+    active_connections: Option<u32>,
+
+    #[zvariant(rename = "IncompleteConnections")]
+    incomplete_connections: Option<u32>,
+
+    #[zvariant(rename = "MatchRules")]
+    match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRules")]
+    peak_match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRulesPerConnection")]
+    peak_match_rules_per_connection: Option<u32>,
+
+    #[zvariant(rename = "BusNames")]
+    bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNames")]
+    peak_bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNamesPerConnection")]
+    peak_bus_names_per_connection: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolUsedBytes")]
+    list_mem_pool_used_bytes: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolCachedBytes")]
+    list_mem_pool_cached_bytes: Option<u32>,
+
+    #[zvariant(rename = "ListMemPoolAllocatedBytes")]
+    list_mem_pool_allocated_bytes: Option<u32>,
+}
+
+assert_impl_all!(BusStats: Send, Sync, Unpin);
+
+impl BusStats {
+    /// The serial number of the statistics, incremented each time they're taken.
+    pub fn serial(&self) -> Option<u32> {
+        self.serial
+    }
+
+    /// The number of authenticated connections.
+    pub fn active_connections(&self) -> Option<u32> {
+        self.active_connections
+    }
+
+    /// The number of connections that aren't authenticated yet.
+    pub fn incomplete_connections(&self) -> Option<u32> {
+        self.incomplete_connections
+    }
+
+    /// The number of match rules.
+    pub fn match_rules(&self) -> Option<u32> {
+        self.match_rules
+    }
+
+    /// The peak number of match rules.
+    pub fn peak_match_rules(&self) -> Option<u32> {
+        self.peak_match_rules
+    }
+
+    /// The peak number of match rules of a single connection.
+    pub fn peak_match_rules_per_connection(&self) -> Option<u32> {
+        self.peak_match_rules_per_connection
+    }
+
+    /// The number of names owned, unique names included.
+    pub fn bus_names(&self) -> Option<u32> {
+        self.bus_names
+    }
+
+    /// The peak number of names owned.
+    pub fn peak_bus_names(&self) -> Option<u32> {
+        self.peak_bus_names
+    }
+
+    /// The peak number of names owned by a single connection.
+    pub fn peak_bus_names_per_connection(&self) -> Option<u32> {
+        self.peak_bus_names_per_connection
+    }
+
+    /// The size of the memory pool used for lists, in use.
+    pub fn list_mem_pool_used_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_used_bytes
+    }
+
+    /// The size of the memory pool used for lists, cached for reuse.
+    pub fn list_mem_pool_cached_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_cached_bytes
+    }
+
+    /// The size of the memory pool used for lists, allocated in total.
+    pub fn list_mem_pool_allocated_bytes(&self) -> Option<u32> {
+        self.list_mem_pool_allocated_bytes
+    }
+}
+
+/// The statistics of a connection to a bus, as returned by [`StatsProxy::get_connection_stats`].
+///
+/// The peak values are the highest ones since the connection was established. The incoming
+/// messages are those queued by the bus for the connection to read, and the outgoing ones those
+/// sent by the connection but not yet routed by the bus. Statistics that the bus doesn't keep
+/// track of are omitted.
+#[derive(Clone, Debug, Default, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[zvariant(signature = "a{sv}")]
+pub struct ConnectionStats {
+    #[zvariant(rename = "Serial")]
+    serial: Option<u32>,
+
+    #[zvariant(rename = "UniqueName")]
+    unique_name: Option<OwnedUniqueName>,
+
+    #[zvariant(rename = "BusNames")]
+    bus_names: Option<u32>,
+
+    #[zvariant(rename = "PeakBusNames")]
+    peak_bus_names: Option<u32>,
+
+    #[zvariant(rename = "MatchRules")]
+    match_rules: Option<u32>,
+
+    #[zvariant(rename = "PeakMatchRules")]
+    peak_match_rules: Option<u32>,
+
+    #[zvariant(rename = "IncomingMessages")]
+    incoming_messages: Option<u32>,
+
+    #[zvariant(rename = "IncomingBytes")]
+    incoming_bytes: Option<u32>,
+
+    #[zvariant(rename = "IncomingFDs")]
+    incoming_fds: Option<u32>,
+
+    #[zvariant(rename = "PeakIncomingBytes")]
+    peak_incoming_bytes: Option<u32>,
+
+    #[zvariant(rename = "PeakIncomingFDs")]
+    peak_incoming_fds: Option<u32>,
+
+    #[zvariant(rename = "OutgoingMessages")]
+    outgoing_messages: Option<u32>,
+
+    #[zvariant(rename = "OutgoingBytes")]
+    outgoing_bytes: Option<u32>,
+
+    #[zvariant(rename = "OutgoingFDs")]
+    outgoing_fds: Option<u32>,
+
+    #[zvariant(rename = "PeakOutgoingBytes")]
+    peak_outgoing_bytes: Option<u32>,
+
+    #[zvariant(rename = "PeakOutgoingFDs")]
+    peak_outgoing_fds: Option<u32>,
+}
+
+assert_impl_all!(ConnectionStats: Send, Sync, Unpin);
+
+impl ConnectionStats {
+    /// The serial number of the statistics, incremented each time they're taken.
+    pub fn serial(&self) -> Option<u32> {
+        self.serial
+    }
+
+    /// The unique name of the connection.
+    pub fn unique_name(&self) -> Option<&OwnedUniqueName> {
+        self.unique_name.as_ref()
+    }
+
+    /// The number of names owned by the connection, its unique name included.
+    pub fn bus_names(&self) -> Option<u32> {
+        self.bus_names
+    }
+
+    /// The peak number of names owned by the connection.
+    pub fn peak_bus_names(&self) -> Option<u32> {
+        self.peak_bus_names
+    }
+
+    /// The number of match rules of the connection.
+    pub fn match_rules(&self) -> Option<u32> {
+        self.match_rules
+    }
+
+    /// The peak number of match rules of the connection.
+    pub fn peak_match_rules(&self) -> Option<u32> {
+        self.peak_match_rules
+    }
+
+    /// The number of incoming messages.
+    pub fn incoming_messages(&self) -> Option<u32> {
+        self.incoming_messages
+    }
+
+    /// The size of the incoming messages.
+    pub fn incoming_bytes(&self) -> Option<u32> {
+        self.incoming_bytes
+    }
+
+    /// The number of file descriptors in the incoming messages.
+    pub fn incoming_fds(&self) -> Option<u32> {
+        self.incoming_fds
+    }
+
+    /// The peak size of the incoming messages.
+    pub fn peak_incoming_bytes(&self) -> Option<u32> {
+        self.peak_incoming_bytes
+    }
+
+    /// The peak number of file descriptors in the incoming messages.
+    pub fn peak_incoming_fds(&self) -> Option<u32> {
+        self.peak_incoming_fds
+    }
+
+    /// The number of outgoing messages.
+    pub fn outgoing_messages(&self) -> Option<u32> {
+        self.outgoing_messages
+    }
+
+    /// The size of the outgoing messages.
+    pub fn outgoing_bytes(&self) -> Option<u32> {
+        self.outgoing_bytes
+    }
+
+    /// The number of file descriptors in the outgoing messages.
+    pub fn outgoing_fds(&self) -> Option<u32> {
+        self.outgoing_fds
+    }
+
+    /// The peak size of the outgoing messages.
+    pub fn peak_outgoing_bytes(&self) -> Option<u32> {
+        self.peak_outgoing_bytes
+    }
+
+    /// The peak number of file descriptors in the outgoing messages.
+    pub fn peak_outgoing_fds(&self) -> Option<u32> {
+        self.peak_outgoing_fds
+    }
+}
+
+/// The match rules of each connection to a bus, as returned by
+/// [`StatsProxy::get_all_match_rules`].
+pub type ConnectionMatchRules = HashMap<OwnedUniqueName, Vec<crate::OwnedMatchRule>>;
+
+/// Service-side implementation for the `org.freedesktop.DBus.Debug.Stats` interface.
+///
+/// This exposes the statistics of the [`ObjectServer`] (see [`ObjectServer::stats`]), so they can
+/// be inspected with generic D-Bus tools while the service is running:
+///
+/// ```text
+/// busctl --user call org.example.Service /org/example/Service \
+///     org.freedesktop.DBus.Debug.Stats GetStats
+/// ```
+///
+/// `GetStats` returns a dictionary with an entry for each interface that was called, itself a
+/// dictionary holding the number of `Calls` and `Errors`, as well as the `TotalLatencyUsec` and
+/// `MaxLatencyUsec` of the calls, in microseconds.
+///
+/// Since this interface is usually implemented by the bus, it must be served at a path of your
+/// own. It's only available with the `debug-stats` feature.
+#[cfg(feature = "debug-stats")]
+#[derive(Debug, Clone)]
+pub struct Stats;
+
+#[cfg(feature = "debug-stats")]
+#[interface(name = "org.freedesktop.DBus.Debug.Stats")]
+impl Stats {
+    async fn get_stats(
+        &self,
+        #[zbus(object_server)] server: &ObjectServer,
+    ) -> Result<HashMap<String, OwnedValue>> {
+        server
+            .stats()
+            .into_iter()
+            .map(|(name, stats)| {
+                let micros = |d: std::time::Duration| d.as_micros() as u64;
+                let stats = HashMap::from([
+                    ("Calls", stats.calls()),
+                    ("Errors", stats.errors()),
+                    ("TotalLatencyUsec", micros(stats.total_latency())),
+                    ("MaxLatencyUsec", micros(stats.max_latency())),
+                ]);
+
+                let stats = Value::from(stats).try_into().map_err(crate::Error::from)?;
+
+                Ok((name.to_string(), stats))
+            })
+            .collect()
+    }
+}
+
 /// The flags used by the bus [`request_name`] method.
 ///
 /// [`request_name`]: struct.DBusProxy.html#method.request_name
@@ -560,7 +1239,7 @@ assert_impl_all!(ReleaseNameReply: Send, Sync, Unpin);
 ///
 /// **Note**: unknown keys, in particular those with "." that are not from the specification, will
 /// be ignored. Use your own implementation or contribute your keys here, or in the specification.
-#[derive(Debug, Default, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
+#[derive(Clone, Debug, Default, DeserializeDict, PartialEq, Eq, SerializeDict, Type)]
 #[zvariant(signature = "a{sv}")]
 pub struct ConnectionCredentials {
     #[zvariant(rename = "UnixUserID")]
@@ -821,14 +1500,730 @@ macro_rules! gen_dbus_proxy {
 gen_dbus_proxy!(true, false);
 assert_impl_all!(DBusProxy<'_>: Send, Sync, Unpin);
 
-/// Errors from <https://gitlab.freedesktop.org/dbus/dbus/-/blob/master/dbus/dbus-protocol.h>
-#[derive(Clone, Debug, DBusError, PartialEq)]
-#[zbus(prefix = "org.freedesktop.DBus.Error", impl_display = true)]
-#[allow(clippy::upper_case_acronyms)]
-pub enum Error {
-    /// Unknown or fall-through ZBus error.
-    #[zbus(error)]
-    ZBus(zbus::Error),
+/// A change of the owner of a name, yielded by [`NameWatcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NameEvent {
+    /// The name got an owner, or changed owner.
+    Appeared(OwnedUniqueName),
+    /// The name has no owner anymore.
+    Vanished,
+}
+
+/// A stream of the changes of the owner of a name.
+///
+/// Following a name reliably requires listening to the `NameOwnerChanged` signal, querying the
+/// current owner of the name, and reconciling both since the signal may be received before or after
+/// the reply. This does all that, and yields a [`NameEvent`] whenever the owner of the name
+/// changes. The first event describes the owner of the name at the time the watcher was created.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// use futures_util::stream::StreamExt;
+/// use zbus::{
+///     fdo::{NameEvent, NameWatcher},
+///     Connection,
+/// };
+///
+/// let connection = Connection::session().await?;
+/// let mut watcher = NameWatcher::new(&connection, "org.freedesktop.Notifications").await?;
+/// while let Some(event) = watcher.next().await {
+///     match event {
+///         NameEvent::Appeared(owner) => println!("Owned by {owner}"),
+///         NameEvent::Vanished => println!("Not owned"),
+///     }
+/// }
+/// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct NameWatcher {
+    name: OwnedBusName,
+    owner: Option<OwnedUniqueName>,
+    // `None` once yielded.
+    initial: Option<NameEvent>,
+    // The signals received before this were sent before the initial owner was queried.
+    initial_position: message::Sequence,
+    stream: MessageStream,
+}
+
+assert_impl_all!(NameWatcher: Send, Sync, Unpin);
+
+impl NameWatcher {
+    /// Watch the owner of `name`.
+    ///
+    /// `name` can be a unique name as well, in which case the watcher yields
+    /// [`NameEvent::Vanished`] once its owner disconnects from the bus.
+    pub async fn new<'n, N>(conn: &Connection, name: N) -> Result<Self>
+    where
+        N: TryInto<BusName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+        let rule = crate::MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .sender("org.freedesktop.DBus")?
+            .path("/org/freedesktop/DBus")?
+            .interface("org.freedesktop.DBus")?
+            .member("NameOwnerChanged")?
+            .add_arg(name.as_str())?
+            .build();
+        // Subscribe before querying the owner, so that no change is missed in between.
+        let stream = MessageStream::for_match_rule(rule, conn, None).await?;
+        let reply = conn
+            .call_method(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                Some("org.freedesktop.DBus"),
+                "GetNameOwner",
+                &name,
+            )
+            .await;
+        let (owner, initial_position) = match reply {
+            Ok(reply) => (
+                Some(reply.body().deserialize::<OwnedUniqueName>()?),
+                reply.recv_position(),
+            ),
+            Err(crate::Error::MethodError(error_name, _, reply))
+                if error_name == "org.freedesktop.DBus.Error.NameHasNoOwner" =>
+            {
+                (None, reply.recv_position())
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let initial = match &owner {
+            Some(owner) => NameEvent::Appeared(owner.clone()),
+            None => NameEvent::Vanished,
+        };
+
+        Ok(Self {
+            name: name.into(),
+            owner,
+            initial: Some(initial),
+            initial_position,
+            stream,
+        })
+    }
+
+    /// The name being watched.
+    pub fn name(&self) -> &OwnedBusName {
+        &self.name
+    }
+
+    /// The owner of the name, as of the last yielded event.
+    pub fn owner(&self) -> Option<&OwnedUniqueName> {
+        self.owner.as_ref()
+    }
+}
+
+impl Stream for NameWatcher {
+    type Item = NameEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(event) = this.initial.take() {
+            return Poll::Ready(Some(event));
+        }
+
+        loop {
+            let msg = match futures_core::ready!(this.stream.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(_)) => continue,
+                None => return Poll::Ready(None),
+            };
+            if msg.recv_position() < this.initial_position {
+                continue;
+            }
+            let Some(signal) = NameOwnerChanged::from_message(msg) else {
+                continue;
+            };
+            let Ok(args) = signal.args() else {
+                continue;
+            };
+            let owner = args
+                .new_owner()
+                .as_ref()
+                .map(|owner| OwnedUniqueName::from(owner.to_owned()));
+            if owner == this.owner {
+                continue;
+            }
+            this.owner = owner.clone();
+
+            return Poll::Ready(Some(match owner {
+                Some(owner) => NameEvent::Appeared(owner),
+                None => NameEvent::Vanished,
+            }));
+        }
+    }
+}
+
+impl FusedStream for NameWatcher {
+    fn is_terminated(&self) -> bool {
+        self.initial.is_none() && self.stream.is_terminated()
+    }
+}
+
+/// The state of a [`NameRequest`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NameRequestState {
+    /// The connection is the primary owner of the name.
+    PrimaryOwner,
+    /// The connection is in the queue of the name, waiting for the current owner to release it.
+    InQueue,
+    /// The connection neither owns the name nor is in its queue anymore.
+    Lost,
+}
+
+/// A request for the ownership of a well-known name, following its state.
+///
+/// Unless [`RequestNameFlags::DoNotQueue`] is used, a name request can end up in the queue of the
+/// name and be granted later, and the name can be taken over by another peer if
+/// [`RequestNameFlags::AllowReplacement`] is used. This requests the name through
+/// [`Connection::request_name_with_flags`] and follows the `NameAcquired` and `NameLost` signals,
+/// so that services can react to these changes. It's a stream of the changes to its
+/// [`NameRequestState`], which ends once the name is lost.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use futures_util::StreamExt;
+/// use zbus::{
+///     fdo::{NameRequest, NameRequestState, RequestNameFlags},
+///     Connection,
+/// };
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::session().await?;
+/// let mut request = NameRequest::new(
+///     &connection,
+///     "org.example.Singleton",
+///     RequestNameFlags::AllowReplacement.into(),
+/// )
+/// .await?;
+/// while let Some(state) = request.next().await {
+///     match state {
+///         NameRequestState::PrimaryOwner => println!("Took over"),
+///         NameRequestState::InQueue => println!("Waiting for my turn"),
+///         NameRequestState::Lost => println!("Gave up"),
+///     }
+/// }
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Debug)]
+pub struct NameRequest {
+    conn: Connection,
+    name: OwnedWellKnownName,
+    flags: BitFlags<RequestNameFlags>,
+    state: NameRequestState,
+    stream: MessageStream,
+}
+
+assert_impl_all!(NameRequest: Send, Sync, Unpin);
+
+impl NameRequest {
+    /// Request the name `name` for `conn`, with the flags `flags`.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `zbus::Error::NameTaken` if the name is owned by another peer and the request
+    /// wasn't queued.
+    pub async fn new<'n, N>(
+        conn: &Connection,
+        name: N,
+        flags: BitFlags<RequestNameFlags>,
+    ) -> Result<Self>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+        let rule = crate::MatchRule::builder()
+            .msg_type(message::Type::Signal)
+            .sender("org.freedesktop.DBus")?
+            .path("/org/freedesktop/DBus")?
+            .interface("org.freedesktop.DBus")?
+            .add_arg(name.as_str())?
+            .build();
+        // Subscribe before requesting the name, so that no change is missed in between.
+        let stream = MessageStream::for_match_rule(rule, conn, None).await?;
+        let state = match conn.request_name_with_flags(&name, flags).await? {
+            RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner => {
+                NameRequestState::PrimaryOwner
+            }
+            RequestNameReply::InQueue => NameRequestState::InQueue,
+            RequestNameReply::Exists => NameRequestState::Lost,
+        };
+
+        Ok(Self {
+            conn: conn.clone(),
+            name: name.into(),
+            flags,
+            state,
+            stream,
+        })
+    }
+
+    /// The name requested.
+    pub fn name(&self) -> &OwnedWellKnownName {
+        &self.name
+    }
+
+    /// The state of the request, as of the last yielded change.
+    pub fn state(&self) -> NameRequestState {
+        self.state
+    }
+
+    /// Release the name, or leave its queue.
+    ///
+    /// See [`Connection::release_name`].
+    pub async fn release(self) -> Result<bool> {
+        self.conn.release_name(&self.name).await.map_err(Into::into)
+    }
+}
+
+impl Stream for NameRequest {
+    type Item = NameRequestState;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.state == NameRequestState::Lost {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            let msg = match futures_core::ready!(this.stream.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(_)) => continue,
+                None => return Poll::Ready(None),
+            };
+            let state = if let Some(signal) = NameAcquired::from_message(msg.clone()) {
+                match signal.args() {
+                    Ok(args) if args.name == *this.name => NameRequestState::PrimaryOwner,
+                    _ => continue,
+                }
+            } else if let Some(signal) = NameLost::from_message(msg) {
+                match signal.args() {
+                    // Replaced owners that didn't opt out of queueing are put back in the queue.
+                    Ok(args) if args.name == *this.name => {
+                        if this.flags.contains(RequestNameFlags::DoNotQueue) {
+                            NameRequestState::Lost
+                        } else {
+                            NameRequestState::InQueue
+                        }
+                    }
+                    _ => continue,
+                }
+            } else {
+                continue;
+            };
+            // The signals for the initial state are sent before the reply to the request.
+            if state == this.state {
+                continue;
+            }
+            this.state = state;
+
+            return Poll::Ready(Some(state));
+        }
+    }
+}
+
+impl FusedStream for NameRequest {
+    fn is_terminated(&self) -> bool {
+        self.state == NameRequestState::Lost || self.stream.is_terminated()
+    }
+}
+
+/// The services available on a message bus.
+///
+/// This is a typed layer over the name-listing methods of [`DBusProxy`], for launchers and other
+/// components that need to know which services run or can be started. Only well-known names are
+/// considered services, unique names are left out.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// use zbus::{fdo::Services, Connection};
+///
+/// let connection = Connection::session().await?;
+/// let services = Services::new(&connection).await?;
+/// if services.is_activatable("org.freedesktop.Notifications").await?
+///     && !services.is_running("org.freedesktop.Notifications").await?
+/// {
+///     println!("Notifications will be started on first use");
+/// }
+/// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Services<'a> {
+    proxy: DBusProxy<'a>,
+}
+
+assert_impl_all!(Services<'_>: Send, Sync, Unpin);
+
+impl<'a> Services<'a> {
+    /// Create the services of the bus `conn` is connected to.
+    pub async fn new(conn: &Connection) -> Result<Services<'static>> {
+        let proxy = DBusProxy::new(conn).await?;
+
+        Ok(Services { proxy })
+    }
+
+    /// The services currently running.
+    pub async fn running(&self) -> Result<Vec<OwnedWellKnownName>> {
+        self.proxy.list_names().await.map(well_known_names)
+    }
+
+    /// The services that can be started by the bus, be they running or not.
+    pub async fn activatable(&self) -> Result<Vec<OwnedWellKnownName>> {
+        self.proxy
+            .list_activatable_names()
+            .await
+            .map(well_known_names)
+    }
+
+    /// Whether the service `name` is currently running.
+    pub async fn is_running<'n, N>(&self, name: N) -> Result<bool>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+
+        self.proxy.name_has_owner(name.into()).await
+    }
+
+    /// Whether the service `name` can be started by the bus.
+    pub async fn is_activatable<'n, N>(&self, name: N) -> Result<bool>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+
+        Ok(self
+            .proxy
+            .list_activatable_names()
+            .await?
+            .iter()
+            .any(|n| *n == name.as_str()))
+    }
+
+    /// The connection currently owning the service `name`, if it's running.
+    pub async fn owner<'n, N>(&self, name: N) -> Result<Option<OwnedUniqueName>>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+
+        match self.proxy.get_name_owner(name.into()).await {
+            Ok(owner) => Ok(Some(owner)),
+            Err(Error::NameHasNoOwner(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// The connections waiting to own the service `name`, the current owner first.
+    pub async fn queued_owners<'n, N>(&self, name: N) -> Result<Vec<OwnedUniqueName>>
+    where
+        N: TryInto<WellKnownName<'n>>,
+        N::Error: Into<crate::Error>,
+    {
+        let name = name.try_into().map_err(Into::into)?;
+
+        self.proxy.list_queued_owners(name).await
+    }
+
+    /// The underlying proxy.
+    pub fn inner(&self) -> &DBusProxy<'a> {
+        &self.proxy
+    }
+}
+
+fn well_known_names(names: Vec<OwnedBusName>) -> Vec<OwnedWellKnownName> {
+    names
+        .into_iter()
+        .filter_map(|name| match name.into_inner() {
+            BusName::WellKnown(name) => Some(name.into()),
+            BusName::Unique(_) => None,
+        })
+        .collect()
+}
+
+/// Environment variables to add to the environment of activated services.
+///
+/// This builds the argument of [`DBusProxy::update_activation_environment`], making sure that the
+/// variable names are valid. Display managers and session startup code typically import some
+/// variables of their own environment, e.g `DISPLAY` or `XDG_*`, so that the services started by
+/// the bus from then on get them.
+///
+/// # Example
+///
+/// ```no_run
+/// # zbus::block_on(async {
+/// use zbus::{
+///     fdo::{ActivationEnvironment, DBusProxy},
+///     Connection,
+/// };
+///
+/// let env = ActivationEnvironment::new()
+///     .import(&["DISPLAY", "WAYLAND_DISPLAY"])?
+///     .import_where(|name| name.starts_with("XDG_"))
+///     .set("DESKTOP_SESSION", "example")?;
+///
+/// let connection = Connection::session().await?;
+/// DBusProxy::new(&connection)
+///     .await?
+///     .apply_activation_environment(&env)
+///     .await?;
+/// # Ok::<(), Box<dyn std::error::Error + Send + Sync>>(())
+/// # }).unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ActivationEnvironment {
+    variables: HashMap<String, String>,
+}
+
+assert_impl_all!(ActivationEnvironment: Send, Sync, Unpin);
+
+impl ActivationEnvironment {
+    /// Create an empty environment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the variable `name` to `value`.
+    ///
+    /// # Errors
+    ///
+    /// If `name` isn't a valid variable name, i.e made of ASCII letters, digits and underscores
+    /// and not starting with a digit, or if `value` contains a NUL character,
+    /// `Error::InvalidArgs` error is returned.
+    pub fn set(mut self, name: &str, value: &str) -> Result<Self> {
+        self.insert(name, value)?;
+
+        Ok(self)
+    }
+
+    /// Set the variables `names` to their value in the environment of the current process.
+    ///
+    /// The variables not set in the current process are skipped.
+    ///
+    /// # Errors
+    ///
+    /// If a variable set in the current process has an invalid name, or a value that isn't valid
+    /// UTF-8, `Error::InvalidArgs` error is returned.
+    pub fn import(mut self, names: &[&str]) -> Result<Self> {
+        for name in names {
+            match std::env::var(name) {
+                Ok(value) => self.insert(name, &value)?,
+                Err(std::env::VarError::NotPresent) => (),
+                Err(std::env::VarError::NotUnicode(_)) => {
+                    return Err(Error::InvalidArgs(format!(
+                        "Value of environment variable `{name}` isn't valid UTF-8"
+                    )))
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Set the variables of the current process for which `predicate` returns `true`.
+    ///
+    /// Unlike [`ActivationEnvironment::import`], the variables that can't be passed, because of an
+    /// invalid name or value, are silently skipped.
+    pub fn import_where<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&str) -> bool,
+    {
+        for (name, value) in std::env::vars_os() {
+            let (Some(name), Some(value)) = (name.to_str(), value.to_str()) else {
+                continue;
+            };
+            if !predicate(name) {
+                continue;
+            }
+            let _ = self.insert(name, value);
+        }
+
+        self
+    }
+
+    /// The variables, by name.
+    pub fn variables(&self) -> &HashMap<String, String> {
+        &self.variables
+    }
+
+    fn insert(&mut self, name: &str, value: &str) -> Result<()> {
+        if !is_variable_name(name) {
+            return Err(Error::InvalidArgs(format!(
+                "Invalid environment variable name `{name}`"
+            )));
+        }
+        if value.contains('\0') {
+            return Err(Error::InvalidArgs(format!(
+                "Value of environment variable `{name}` contains a NUL character"
+            )));
+        }
+        self.variables.insert(name.to_string(), value.to_string());
+
+        Ok(())
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// A feature of the message bus, as listed by its `Features` property.
+///
+/// See [`DBusProxy::bus_features`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BusFeature {
+    /// The bus enforces AppArmor policies.
+    AppArmor,
+    /// The bus only delivers header fields it knows of, so that their values can be trusted.
+    HeaderFiltering,
+    /// The bus enforces SELinux policies.
+    SELinux,
+    /// The bus can activate services through systemd.
+    SystemdActivation,
+    /// A feature unknown to zbus.
+    Other(String),
+}
+
+assert_impl_all!(BusFeature: Send, Sync, Unpin);
+
+impl BusFeature {
+    /// The name of the feature, as used in the `Features` property.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::AppArmor => "AppArmor",
+            Self::HeaderFiltering => "HeaderFiltering",
+            Self::SELinux => "SELinux",
+            Self::SystemdActivation => "SystemdActivation",
+            Self::Other(feature) => feature,
+        }
+    }
+}
+
+impl From<String> for BusFeature {
+    fn from(feature: String) -> Self {
+        match feature.as_str() {
+            "AppArmor" => Self::AppArmor,
+            "HeaderFiltering" => Self::HeaderFiltering,
+            "SELinux" => Self::SELinux,
+            "SystemdActivation" => Self::SystemdActivation,
+            _ => Self::Other(feature),
+        }
+    }
+}
+
+impl fmt::Display for BusFeature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An optional interface of the message bus, as listed by its `Interfaces` property.
+///
+/// See [`DBusProxy::bus_interfaces`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum BusInterface {
+    /// `org.freedesktop.DBus.Monitoring`, see [`MonitoringProxy`].
+    Monitoring,
+    /// `org.freedesktop.DBus.Debug.Stats`, see [`StatsProxy`].
+    Stats,
+    /// An interface unknown to zbus.
+    Other(OwnedInterfaceName),
+}
+
+assert_impl_all!(BusInterface: Send, Sync, Unpin);
+
+impl BusInterface {
+    /// The name of the interface.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Monitoring => "org.freedesktop.DBus.Monitoring",
+            Self::Stats => "org.freedesktop.DBus.Debug.Stats",
+            Self::Other(name) => name.as_str(),
+        }
+    }
+}
+
+impl From<OwnedInterfaceName> for BusInterface {
+    fn from(name: OwnedInterfaceName) -> Self {
+        match name.as_str() {
+            "org.freedesktop.DBus.Monitoring" => Self::Monitoring,
+            "org.freedesktop.DBus.Debug.Stats" => Self::Stats,
+            _ => Self::Other(name),
+        }
+    }
+}
+
+impl fmt::Display for BusInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<'p> DBusProxy<'p> {
+    /// The features of the message bus.
+    ///
+    /// This is the `Features` property, with the features known to zbus parsed into
+    /// [`BusFeature`] variants.
+    pub async fn bus_features(&self) -> Result<Vec<BusFeature>> {
+        let features = self.features().await?;
+
+        Ok(features.into_iter().map(BusFeature::from).collect())
+    }
+
+    /// The optional interfaces provided by the message bus.
+    ///
+    /// This is the `Interfaces` property, with the interfaces known to zbus parsed into
+    /// [`BusInterface`] variants.
+    pub async fn bus_interfaces(&self) -> Result<Vec<BusInterface>> {
+        let interfaces = self.interfaces().await?;
+
+        Ok(interfaces.into_iter().map(BusInterface::from).collect())
+    }
+
+    /// Add the variables of `env` to the environment of the services activated from now on.
+    ///
+    /// This is [`DBusProxy::update_activation_environment`], taking an [`ActivationEnvironment`].
+    pub async fn apply_activation_environment(&self, env: &ActivationEnvironment) -> Result<()> {
+        let variables = env
+            .variables()
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+
+        self.update_activation_environment(variables).await
+    }
+}
+
+/// Errors from <https://gitlab.freedesktop.org/dbus/dbus/-/blob/master/dbus/dbus-protocol.h>
+#[derive(Clone, Debug, DBusError, PartialEq)]
+#[zbus(prefix = "org.freedesktop.DBus.Error", impl_display = true)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum Error {
+    /// Unknown or fall-through ZBus error.
+    #[zbus(error)]
+    ZBus(zbus::Error),
 
     /// A generic error; "something went wrong" - see the error message for more.
     Failed(String),
@@ -992,6 +2387,20 @@ pub enum Error {
 
 assert_impl_all!(Error: Send, Sync, Unpin);
 
+impl Error {
+    /// The error reply this error was created from, if any.
+    ///
+    /// The standard errors only carry a description, so replies with more arguments than that are
+    /// kept as [`Error::ZBus`], even if they have a standard error name. This is also the case for
+    /// the errors with a name specific to a service. See [`zbus::Error::reply`].
+    pub fn reply(&self) -> Option<&Message> {
+        match self {
+            Error::ZBus(e) => e.reply(),
+            _ => None,
+        }
+    }
+}
+
 /// Alias for a `Result` with the error type [`zbus::fdo::Error`].
 ///
 /// [`zbus::fdo::Error`]: enum.Error.html
@@ -1025,6 +2434,81 @@ mod tests {
         assert_eq!(e.description(), Some("so long"));
     }
 
+    #[test]
+    fn error_reply_args() {
+        #[derive(Debug, DBusError, PartialEq)]
+        #[zbus(prefix = "org.zbus.Units")]
+        enum UnitError {
+            #[zbus(error)]
+            ZBus(Error),
+            NoSuchUnit(String, u32),
+            Busy {
+                desc: String,
+                retry_after: u32,
+            },
+        }
+
+        let call = Message::method("/", "foo")
+            .unwrap()
+            .destination(":1.2")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let reply = |name, body: &(&str, u32)| {
+            Message::method_error(&call, name)
+                .unwrap()
+                .build(body)
+                .unwrap()
+        };
+
+        let e = Error::from(reply("org.zbus.Units.NoSuchUnit", &("no unit", 42)));
+        let args: (String, u32) = e.reply().unwrap().body().deserialize().unwrap();
+        assert_eq!(args, ("no unit".to_string(), 42));
+        assert_eq!(
+            UnitError::from(e.clone()),
+            UnitError::NoSuchUnit("no unit".into(), 42)
+        );
+        // Errors of other names keep the whole reply.
+        let e = fdo::Error::from(e);
+        assert!(matches!(e, fdo::Error::ZBus(_)));
+        assert_eq!(e.reply().unwrap().body().signature().unwrap(), "su");
+        // So do the standard errors, if the reply has more than a description.
+        let e = Error::from(reply("org.freedesktop.DBus.Error.Failed", &("failed", 7)));
+        let e = fdo::Error::from(e);
+        assert!(matches!(e, fdo::Error::ZBus(_)));
+        let args: (String, u32) = e.reply().unwrap().body().deserialize().unwrap();
+        assert_eq!(args, ("failed".to_string(), 7));
+        let e = Message::method_error(&call, "org.freedesktop.DBus.Error.Failed")
+            .unwrap()
+            .build(&("failed",))
+            .unwrap();
+        assert_eq!(
+            fdo::Error::from(Error::from(e)),
+            fdo::Error::Failed("failed".into())
+        );
+
+        let e = Error::from(reply("org.zbus.Units.Busy", &("busy", 5)));
+        let e = UnitError::from(e);
+        assert_eq!(
+            e,
+            UnitError::Busy {
+                desc: "busy".into(),
+                retry_after: 5
+            }
+        );
+        assert_eq!(e.description(), Some("busy"));
+
+        // Arguments not matching the fields.
+        let reply = Message::method_error(&call, "org.zbus.Units.Busy")
+            .unwrap()
+            .build(&("busy",))
+            .unwrap();
+        assert!(matches!(
+            UnitError::from(Error::from(reply)),
+            UnitError::ZBus(Error::MethodError(..))
+        ));
+    }
+
     #[test]
     #[timeout(15000)]
     fn signal() {
@@ -1151,4 +2635,505 @@ mod tests {
             .collect()
         );
     }
+
+    #[test]
+    #[timeout(15000)]
+    fn bus_stats() {
+        use zbus::blocking;
+
+        let conn = blocking::Connection::session().unwrap();
+        conn.request_name("org.zbus.StatsTest").unwrap();
+        let proxy = blocking::fdo::StatsProxy::new(&conn).unwrap();
+        let stats = match proxy.get_stats() {
+            Ok(stats) => stats,
+            // Not all buses keep statistics.
+            Err(super::Error::UnknownMethod(_) | super::Error::UnknownInterface(_)) => return,
+            Err(e) => panic!("{e}"),
+        };
+        assert!(stats.active_connections().unwrap() >= 1);
+        assert!(stats.bus_names().unwrap() >= 2);
+
+        let name = conn.unique_name().unwrap().to_owned();
+        let stats = proxy.get_connection_stats(name.clone().into()).unwrap();
+        assert_eq!(stats.unique_name().unwrap(), &name);
+        assert_eq!(stats.bus_names(), Some(2));
+        assert!(stats.incoming_bytes().is_some());
+
+        let rules = proxy.get_all_match_rules().unwrap();
+        assert!(rules.contains_key(&name));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn become_monitor() {
+        use zbus::blocking;
+
+        let monitor = blocking::Connection::session().unwrap();
+        let mut messages = blocking::fdo::MonitoringProxy::new(&monitor)
+            .unwrap()
+            .become_monitor(&[])
+            .unwrap();
+        assert!(monitor.is_monitor());
+        assert!(monitor.request_name("org.zbus.MonitorTest").is_err());
+
+        let conn = blocking::Connection::session().unwrap();
+        conn.emit_signal(
+            None::<()>,
+            "/org/zbus/MonitorTest",
+            "org.zbus.MonitorTest",
+            "Ping",
+            &(),
+        )
+        .unwrap();
+        let signal = messages
+            .find(|msg| {
+                let msg = msg.as_ref().unwrap();
+                msg.header().member().map(|m| m.as_str()) == Some("Ping")
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            signal.header().sender().unwrap(),
+            conn.unique_name().unwrap()
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn name_watcher() {
+        use zbus::blocking;
+
+        let conn = blocking::Connection::session().unwrap();
+        let mut watcher =
+            blocking::fdo::NameWatcher::new(&conn, "org.zbus.NameWatcherTest").unwrap();
+        assert_eq!(watcher.next(), Some(super::NameEvent::Vanished));
+        assert_eq!(watcher.owner(), None);
+
+        let service = blocking::Connection::session().unwrap();
+        service.request_name("org.zbus.NameWatcherTest").unwrap();
+        let owner = service.unique_name().unwrap().clone();
+        assert_eq!(
+            watcher.next(),
+            Some(super::NameEvent::Appeared(owner.clone()))
+        );
+        assert_eq!(watcher.owner(), Some(&owner));
+
+        // The initial event reflects the current owner.
+        let mut other = blocking::fdo::NameWatcher::new(&conn, "org.zbus.NameWatcherTest").unwrap();
+        assert_eq!(other.next(), Some(super::NameEvent::Appeared(owner)));
+
+        service.release_name("org.zbus.NameWatcherTest").unwrap();
+        assert_eq!(watcher.next(), Some(super::NameEvent::Vanished));
+        assert_eq!(other.next(), Some(super::NameEvent::Vanished));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn services() {
+        use zbus::blocking;
+
+        let conn = blocking::Connection::session().unwrap();
+        let services = blocking::fdo::Services::new(&conn).unwrap();
+        assert!(!services.is_running("org.zbus.ServicesTest").unwrap());
+        assert_eq!(services.owner("org.zbus.ServicesTest").unwrap(), None);
+        assert!(!services.is_activatable("org.zbus.ServicesTest").unwrap());
+        assert!(services
+            .activatable()
+            .unwrap()
+            .iter()
+            .all(|name| !name.starts_with(':')));
+
+        conn.request_name("org.zbus.ServicesTest").unwrap();
+        assert!(services.is_running("org.zbus.ServicesTest").unwrap());
+        let owner = conn.unique_name().unwrap().clone();
+        assert_eq!(
+            services.owner("org.zbus.ServicesTest").unwrap(),
+            Some(owner.clone())
+        );
+        assert_eq!(
+            services.queued_owners("org.zbus.ServicesTest").unwrap(),
+            [owner]
+        );
+        let running = services.running().unwrap();
+        assert!(running.iter().any(|name| *name == "org.zbus.ServicesTest"));
+        // Unique names aren't services.
+        assert!(running.iter().all(|name| !name.starts_with(':')));
+        assert!(services.is_running(":1.0").is_err());
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn object_changes() {
+        use zbus::blocking;
+
+        use super::{ObjectChange, OwnedInterfaceName, OwnedObjectPath};
+
+        struct Item;
+        #[super::interface(name = "org.zbus.Item")]
+        impl Item {}
+
+        struct Tag;
+        #[super::interface(name = "org.zbus.Tag")]
+        impl Tag {}
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Changes", super::ObjectManager)
+            .unwrap()
+            .serve_at("/org/zbus/Changes/a", Item)
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+        let client = blocking::Connection::session().unwrap();
+        let mut changes = blocking::fdo::ObjectManagerProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path("/org/zbus/Changes")
+            .unwrap()
+            .build()
+            .unwrap()
+            .receive_object_changes()
+            .unwrap();
+        let path: OwnedObjectPath = "/org/zbus/Changes/a".try_into().unwrap();
+        assert!(changes.objects().contains_key(&path));
+
+        server.at("/org/zbus/Changes/b", Item).unwrap();
+        let added: OwnedObjectPath = "/org/zbus/Changes/b".try_into().unwrap();
+        assert_eq!(
+            changes.next(),
+            Some(ObjectChange::ObjectAdded(added.clone()))
+        );
+        assert!(changes.objects().contains_key(&added));
+
+        let tag: OwnedInterfaceName = "org.zbus.Tag".try_into().unwrap();
+        server.at(&path, Tag).unwrap();
+        assert_eq!(
+            changes.next(),
+            Some(ObjectChange::InterfacesChanged {
+                path: path.clone(),
+                added: vec![tag.clone()],
+                removed: vec![],
+            })
+        );
+        server.remove::<Tag, _>(&path).unwrap();
+        assert_eq!(
+            changes.next(),
+            Some(ObjectChange::InterfacesChanged {
+                path: path.clone(),
+                added: vec![],
+                removed: vec![tag],
+            })
+        );
+
+        server.remove::<Item, _>(&added).unwrap();
+        assert_eq!(
+            changes.next(),
+            Some(ObjectChange::ObjectRemoved(added.clone()))
+        );
+        assert!(!changes.objects().contains_key(&added));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn properties_get_all_as() {
+        use zbus::blocking;
+        use zvariant::{DeserializeDict, Type};
+
+        use super::InterfaceName;
+
+        struct Device;
+        #[super::interface(name = "org.zbus.Device")]
+        impl Device {
+            #[zbus(property)]
+            fn model(&self) -> &str {
+                "ZB-1"
+            }
+
+            #[zbus(property)]
+            fn battery_level(&self) -> u8 {
+                42
+            }
+        }
+
+        // `BatteryLevel` is left out on purpose.
+        #[derive(Debug, DeserializeDict, Type, PartialEq)]
+        #[zvariant(signature = "a{sv}", rename_all = "PascalCase")]
+        struct DeviceProperties {
+            model: String,
+            serial_number: Option<String>,
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Device", Device)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client = blocking::Connection::session().unwrap();
+        let proxy = blocking::fdo::PropertiesProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path("/org/zbus/Device")
+            .unwrap()
+            .build()
+            .unwrap();
+        let iface = InterfaceName::from_static_str("org.zbus.Device").unwrap();
+        let props: DeviceProperties = proxy.get_all_as(iface).unwrap();
+        assert_eq!(
+            props,
+            DeviceProperties {
+                model: "ZB-1".into(),
+                serial_number: None,
+            }
+        );
+
+        let iface = InterfaceName::from_static_str("org.zbus.Unknown").unwrap();
+        assert!(proxy.get_all_as::<DeviceProperties>(iface).is_err());
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn activation_environment() {
+        use zbus::blocking;
+
+        use super::ActivationEnvironment;
+
+        let env = ActivationEnvironment::new()
+            .set("ZBUS_TEST", "1")
+            .unwrap()
+            .set("_ZBUS_2", "two words")
+            .unwrap();
+        assert_eq!(env.variables().len(), 2);
+        assert_eq!(env.variables()["_ZBUS_2"], "two words");
+        for name in ["", "2ZBUS", "ZBUS=1", "ZBUS TEST", "ZBÜS"] {
+            let err = ActivationEnvironment::new().set(name, "1").unwrap_err();
+            assert!(matches!(err, super::Error::InvalidArgs(_)), "{name}: {err}");
+        }
+        assert!(ActivationEnvironment::new().set("ZBUS", "a\0b").is_err());
+
+        std::env::set_var("ZBUS_ACTIVATION_ENVIRONMENT_TEST", "imported");
+        let env = env
+            .import(&["ZBUS_ACTIVATION_ENVIRONMENT_TEST", "ZBUS_NOT_SET"])
+            .unwrap();
+        assert_eq!(
+            env.variables()["ZBUS_ACTIVATION_ENVIRONMENT_TEST"],
+            "imported"
+        );
+        assert!(!env.variables().contains_key("ZBUS_NOT_SET"));
+        let env = ActivationEnvironment::new()
+            .import_where(|name| name.starts_with("ZBUS_ACTIVATION_ENVIRONMENT_"));
+        assert_eq!(env.variables().len(), 1);
+
+        let conn = blocking::Connection::session().unwrap();
+        blocking::fdo::DBusProxy::new(&conn)
+            .unwrap()
+            .apply_activation_environment(&env)
+            .unwrap();
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn machine_id_source() {
+        use zbus::{blocking, fdo::ObjectManager, object_server::MachineIdSource};
+
+        // Serve an interface for the object server to be started along with the connection.
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/", ObjectManager)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client = blocking::Connection::session().unwrap();
+        let proxy = blocking::fdo::PeerProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path("/")
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+
+        let id = "0123456789abcdef0123456789abcdef";
+        server.set_machine_id_source(MachineIdSource::Fixed(id.into()));
+        assert_eq!(proxy.get_machine_id().unwrap(), id);
+
+        let file = std::env::temp_dir().join(format!("zbus-machine-id-{}", std::process::id()));
+        std::fs::write(&file, "fedcba9876543210fedcba9876543210\n").unwrap();
+        server.set_machine_id_source(MachineIdSource::File(file.clone()));
+        assert_eq!(
+            proxy.get_machine_id().unwrap(),
+            "fedcba9876543210fedcba9876543210"
+        );
+        std::fs::remove_file(&file).unwrap();
+        assert!(matches!(
+            proxy.get_machine_id(),
+            Err(super::Error::IOError(_))
+        ));
+
+        server.set_machine_id_source(MachineIdSource::callback(|| {
+            Err(super::Error::AccessDenied("Masked".into()))
+        }));
+        assert!(matches!(
+            proxy.get_machine_id(),
+            Err(super::Error::AccessDenied(_))
+        ));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn bus_features_and_interfaces() {
+        use super::{BusFeature, BusInterface, OwnedInterfaceName};
+        use zbus::blocking;
+
+        let feature = BusFeature::from("HeaderFiltering".to_string());
+        assert_eq!(feature, BusFeature::HeaderFiltering);
+        assert_eq!(feature.to_string(), "HeaderFiltering");
+        let feature = BusFeature::from("TimeTravel".to_string());
+        assert_eq!(feature, BusFeature::Other("TimeTravel".into()));
+        assert_eq!(feature.as_str(), "TimeTravel");
+
+        let iface = BusInterface::from(
+            OwnedInterfaceName::try_from("org.freedesktop.DBus.Monitoring").unwrap(),
+        );
+        assert_eq!(iface, BusInterface::Monitoring);
+        let name = OwnedInterfaceName::try_from("org.zbus.Extension").unwrap();
+        let iface = BusInterface::from(name.clone());
+        assert_eq!(iface, BusInterface::Other(name));
+        assert_eq!(iface.to_string(), "org.zbus.Extension");
+
+        let conn = blocking::Connection::session().unwrap();
+        let proxy = blocking::fdo::DBusProxy::new(&conn).unwrap();
+        let features = proxy.features().unwrap();
+        let bus_features = proxy.bus_features().unwrap();
+        assert_eq!(
+            bus_features.iter().map(|f| f.as_str()).collect::<Vec<_>>(),
+            features
+        );
+        let interfaces = proxy.interfaces().unwrap();
+        let bus_interfaces = proxy.bus_interfaces().unwrap();
+        assert_eq!(
+            bus_interfaces
+                .iter()
+                .map(|i| i.as_str())
+                .collect::<Vec<_>>(),
+            interfaces.iter().map(|i| i.as_str()).collect::<Vec<_>>()
+        );
+        // The reference bus and dbus-broker both support monitoring.
+        assert!(bus_interfaces.contains(&BusInterface::Monitoring));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn name_request() {
+        use super::{NameRequestState, RequestNameFlags};
+        use enumflags2::BitFlags;
+        use zbus::blocking;
+
+        let name = "org.zbus.NameRequestTest";
+        let conn1 = blocking::Connection::session().unwrap();
+        let mut request1 = blocking::fdo::NameRequest::new(
+            &conn1,
+            name,
+            RequestNameFlags::AllowReplacement.into(),
+        )
+        .unwrap();
+        assert_eq!(request1.state(), NameRequestState::PrimaryOwner);
+
+        let conn2 = blocking::Connection::session().unwrap();
+        let mut request2 =
+            blocking::fdo::NameRequest::new(&conn2, name, BitFlags::empty()).unwrap();
+        assert_eq!(request2.state(), NameRequestState::InQueue);
+
+        // Replacing the owner puts it back in the queue.
+        let conn3 = blocking::Connection::session().unwrap();
+        let request3 =
+            blocking::fdo::NameRequest::new(&conn3, name, RequestNameFlags::ReplaceExisting.into())
+                .unwrap();
+        assert_eq!(request3.state(), NameRequestState::PrimaryOwner);
+        assert_eq!(request1.next(), Some(NameRequestState::InQueue));
+
+        assert!(request3.release().unwrap());
+        assert_eq!(request1.next(), Some(NameRequestState::PrimaryOwner));
+        assert_eq!(request1.state(), NameRequestState::PrimaryOwner);
+        assert_eq!(request2.state(), NameRequestState::InQueue);
+
+        // Without queueing, the name is lost for good.
+        let conn4 = blocking::Connection::session().unwrap();
+        let mut request4 = blocking::fdo::NameRequest::new(
+            &conn4,
+            "org.zbus.NameRequestTest.NoQueue",
+            RequestNameFlags::AllowReplacement | RequestNameFlags::DoNotQueue,
+        )
+        .unwrap();
+        assert_eq!(request4.state(), NameRequestState::PrimaryOwner);
+        conn1
+            .request_name("org.zbus.NameRequestTest.NoQueue")
+            .unwrap();
+        assert_eq!(request4.next(), Some(NameRequestState::Lost));
+        assert_eq!(request4.next(), None);
+
+        assert!(request1.release().unwrap());
+        assert_eq!(request2.next(), Some(NameRequestState::PrimaryOwner));
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    #[timeout(15000)]
+    fn introspect_tree() {
+        use zbus::blocking;
+        use zvariant::ObjectPath;
+
+        struct Leaf;
+
+        #[super::interface(name = "org.zbus.Leaf")]
+        impl Leaf {
+            fn ping(&self) {}
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Tree/a", Leaf)
+            .unwrap()
+            .serve_at("/org/zbus/Tree/a/b", Leaf)
+            .unwrap()
+            .serve_at("/org/zbus/Tree/c", Leaf)
+            .unwrap()
+            .build()
+            .unwrap();
+        let client = blocking::Connection::session().unwrap();
+        let tree = blocking::fdo::IntrospectableProxy::builder(&client)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path("/org/zbus/Tree")
+            .unwrap()
+            .build()
+            .unwrap()
+            .introspect_tree()
+            .unwrap();
+
+        assert_eq!(tree.path().as_str(), "/org/zbus/Tree");
+        let paths: Vec<_> = tree.iter().map(|t| t.path().as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/org/zbus/Tree",
+                "/org/zbus/Tree/a",
+                "/org/zbus/Tree/a/b",
+                "/org/zbus/Tree/c"
+            ]
+        );
+        assert_eq!(tree.children().len(), 2);
+        let leaf = tree
+            .find(&ObjectPath::from_static_str_unchecked("/org/zbus/Tree/a/b"))
+            .unwrap();
+        assert!(leaf.children().is_empty());
+        assert!(leaf
+            .interfaces()
+            .iter()
+            .any(|i| i.name() == "org.zbus.Leaf"));
+        assert!(tree
+            .find(&ObjectPath::from_static_str_unchecked("/org/zbus/Tree/d"))
+            .is_none());
+    }
 }
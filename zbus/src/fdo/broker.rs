@@ -0,0 +1,292 @@
+//! Statistics specific to dbus-broker.
+//!
+//! Besides the statistics of the reference implementation, [dbus-broker] reports its resource
+//! accounting through the `org.freedesktop.DBus.Debug.Stats` interface, under keys of the
+//! `org.bus1.DBus.Debug.Stats` namespace. This provides types for them, to be retrieved with
+//! [`StatsProxy::get_broker_stats`].
+//!
+//! Only available with the `dbus-broker` feature. Note that the `org.bus1.DBus.Broker` interface
+//! isn't covered, since dbus-broker only serves it to its launcher, on a private socket.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use zbus::{fdo::StatsProxy, Connection};
+//!
+//! # zbus::block_on(async {
+//! let connection = Connection::system().await?;
+//! let stats = StatsProxy::new(&connection).await?.get_broker_stats().await?;
+//! for peer in stats.peer_accounting().unwrap_or_default() {
+//!     println!("{}: {:?}", peer.name(), peer.usage());
+//! }
+//! # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+//! # })?;
+//! # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+//! ```
+//!
+//! [dbus-broker]: https://github.com/bus1/dbus-broker
+
+use static_assertions::assert_impl_all;
+use std::collections::HashMap;
+use zvariant::{OwnedValue, Type, Value};
+
+use super::{BusStats, Result, StatsProxy};
+
+const PEER_ACCOUNTING: &str = "org.bus1.DBus.Debug.Stats.PeerAccounting";
+const USER_ACCOUNTING: &str = "org.bus1.DBus.Debug.Stats.UserAccounting";
+
+impl<'p> StatsProxy<'p> {
+    /// Get the statistics of the bus, including those specific to dbus-broker.
+    pub async fn get_broker_stats(&self) -> Result<BrokerStats> {
+        let reply = self.inner().call_method("GetStats", &()).await?;
+        let body = reply.body();
+        let bus = body.deserialize::<BusStats>()?;
+        let mut extensions: HashMap<String, OwnedValue> = body.deserialize()?;
+        extensions.retain(|key, _| key.starts_with("org.bus1."));
+
+        Ok(BrokerStats { bus, extensions })
+    }
+}
+
+/// The statistics of a bus, as returned by [`StatsProxy::get_broker_stats`].
+///
+/// The dbus-broker specific statistics are only available when the bus is dbus-broker, and are
+/// reported as missing if they're not in the expected format.
+#[derive(Debug, PartialEq)]
+pub struct BrokerStats {
+    bus: BusStats,
+    extensions: HashMap<String, OwnedValue>,
+}
+
+assert_impl_all!(BrokerStats: Send, Sync, Unpin);
+
+impl BrokerStats {
+    /// The statistics common to all buses.
+    pub fn bus(&self) -> &BusStats {
+        &self.bus
+    }
+
+    /// The resources used by each connection.
+    pub fn peer_accounting(&self) -> Option<Vec<PeerAccounting>> {
+        self.extension(PEER_ACCOUNTING)
+    }
+
+    /// The quotas of each user.
+    pub fn user_accounting(&self) -> Option<Vec<UserAccounting>> {
+        self.extension(USER_ACCOUNTING)
+    }
+
+    /// All the dbus-broker specific statistics, by key.
+    ///
+    /// This gives access to the statistics not covered by this module.
+    pub fn extensions(&self) -> &HashMap<String, OwnedValue> {
+        &self.extensions
+    }
+
+    fn extension<T>(&self, key: &str) -> Option<T>
+    where
+        T: TryFrom<OwnedValue>,
+    {
+        let value = self.extensions.get(key)?.try_clone().ok()?;
+
+        T::try_from(value).ok()
+    }
+}
+
+/// The resources used by a connection, as reported by dbus-broker.
+#[derive(Debug, PartialEq, Type, Value)]
+pub struct PeerAccounting {
+    name: String,
+    credentials: HashMap<String, OwnedValue>,
+    usage: HashMap<String, u32>,
+}
+
+assert_impl_all!(PeerAccounting: Send, Sync, Unpin);
+
+impl PeerAccounting {
+    /// The unique name of the connection.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The credentials of the connection, e.g `UnixUserID` and `ProcessID`.
+    pub fn credentials(&self) -> &HashMap<String, OwnedValue> {
+        &self.credentials
+    }
+
+    /// The amount of each resource used by the connection, e.g `Matches` or `IncomingBytes`.
+    pub fn usage(&self) -> &HashMap<String, u32> {
+        &self.usage
+    }
+}
+
+/// The quotas of a user, as reported by dbus-broker.
+#[derive(Clone, Debug, PartialEq, Eq, Type, Value)]
+pub struct UserAccounting {
+    uid: u32,
+    limits: Vec<UserLimit>,
+    charges: Vec<UserCharge>,
+}
+
+assert_impl_all!(UserAccounting: Send, Sync, Unpin);
+
+impl UserAccounting {
+    /// The ID of the user.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The limit of each resource.
+    pub fn limits(&self) -> &[UserLimit] {
+        &self.limits
+    }
+
+    /// The resources of the user held by the connections of each user.
+    pub fn charges(&self) -> &[UserCharge] {
+        &self.charges
+    }
+}
+
+/// The limit of a resource of a user, as reported by dbus-broker.
+#[derive(Clone, Debug, PartialEq, Eq, Type, Value)]
+pub struct UserLimit {
+    resource: String,
+    limit: u32,
+    remaining: u32,
+}
+
+assert_impl_all!(UserLimit: Send, Sync, Unpin);
+
+impl UserLimit {
+    /// The name of the resource, e.g `Bytes`, `Fds`, `Matches` or `Objects`.
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    /// The maximum amount of the resource.
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+
+    /// The amount of the resource still available.
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+}
+
+/// The resources of a user held on behalf of another user, as reported by dbus-broker.
+#[derive(Clone, Debug, PartialEq, Eq, Type, Value)]
+pub struct UserCharge {
+    uid: u32,
+    bytes: u32,
+    fds: u32,
+    matches: u32,
+    objects: u32,
+}
+
+assert_impl_all!(UserCharge: Send, Sync, Unpin);
+
+impl UserCharge {
+    /// The ID of the user holding the resources.
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    /// The number of bytes held.
+    pub fn bytes(&self) -> u32 {
+        self.bytes
+    }
+
+    /// The number of file descriptors held.
+    pub fn fds(&self) -> u32 {
+        self.fds
+    }
+
+    /// The number of match rules held.
+    pub fn matches(&self) -> u32 {
+        self.matches
+    }
+
+    /// The number of objects, e.g names or pending replies, held.
+    pub fn objects(&self) -> u32 {
+        self.objects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use std::collections::HashMap;
+    use test_log::test;
+    use zvariant::{OwnedValue, Type, Value};
+
+    use super::{PeerAccounting, UserAccounting};
+    use crate::{connection, fdo::StatsProxy, interface, utils::block_on};
+
+    struct Broker;
+
+    #[interface(name = "org.freedesktop.DBus.Debug.Stats")]
+    impl Broker {
+        fn get_stats(&self) -> HashMap<&str, Value<'_>> {
+            let peer = (
+                ":1.42",
+                HashMap::from([("UnixUserID", Value::from(1000u32))]),
+                HashMap::from([("Matches", 3u32)]),
+            );
+            let user = (
+                1000u32,
+                vec![("Bytes", 1024u32, 512u32)],
+                vec![(0u32, 16u32, 1u32, 2u32, 3u32)],
+            );
+
+            HashMap::from([
+                ("Serial", Value::from(7u32)),
+                (super::PEER_ACCOUNTING, Value::from(vec![peer])),
+                (super::USER_ACCOUNTING, Value::from(vec![user])),
+                ("org.bus1.DBus.Debug.Stats.Future", Value::from("?")),
+            ])
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn broker_stats() {
+        assert_eq!(PeerAccounting::signature(), "(sa{sv}a{su})");
+        assert_eq!(UserAccounting::signature(), "(ua(suu)a(uuuuu))");
+
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/freedesktop/DBus", Broker)?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let stats = StatsProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .build()
+                .await?
+                .get_broker_stats()
+                .await?;
+            assert_eq!(stats.bus().serial(), Some(7));
+            assert_eq!(stats.extensions().len(), 3);
+
+            let peers = stats.peer_accounting().unwrap();
+            assert_eq!(peers[0].name(), ":1.42");
+            assert_eq!(
+                peers[0].credentials()["UnixUserID"],
+                OwnedValue::from(1000u32)
+            );
+            assert_eq!(peers[0].usage()["Matches"], 3);
+
+            let users = stats.user_accounting().unwrap();
+            assert_eq!(users[0].uid(), 1000);
+            assert_eq!(users[0].limits()[0].resource(), "Bytes");
+            assert_eq!(users[0].limits()[0].limit(), 1024);
+            assert_eq!(users[0].limits()[0].remaining(), 512);
+            assert_eq!(users[0].charges()[0].uid(), 0);
+            assert_eq!(users[0].charges()[0].objects(), 3);
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
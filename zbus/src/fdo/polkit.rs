@@ -0,0 +1,342 @@
+//! Proxy for the polkit authority.
+//!
+//! Privileged services check with [polkit] whether their callers are authorized for the actions
+//! they request. This provides a proxy for the `org.freedesktop.PolicyKit1.Authority` interface,
+//! along with the types it uses.
+//!
+//! Only available with the `polkit` feature. Note that interface methods only need this for custom
+//! checks, since the `polkit_action` attribute of the [`interface`] macro already takes care of the
+//! common case.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::collections::HashMap;
+//! use zbus::{
+//!     fdo::{
+//!         self,
+//!         polkit::{AuthorityProxy, CheckAuthorizationFlags, Subject},
+//!     },
+//!     interface,
+//!     message::Header,
+//!     Connection,
+//! };
+//!
+//! struct Power;
+//!
+//! #[interface(name = "org.example.Power")]
+//! impl Power {
+//!     async fn reboot(
+//!         &self,
+//!         #[zbus(header)] hdr: Header<'_>,
+//!         #[zbus(connection)] conn: &Connection,
+//!     ) -> fdo::Result<()> {
+//!         let subject = Subject::from_header(&hdr)
+//!             .ok_or_else(|| fdo::Error::AccessDenied("No sender".into()))?;
+//!         let result = AuthorityProxy::new(conn)
+//!             .await?
+//!             .check_authorization(
+//!                 &subject,
+//!                 "org.example.power.reboot",
+//!                 &HashMap::new(),
+//!                 CheckAuthorizationFlags::AllowUserInteraction.into(),
+//!                 "",
+//!             )
+//!             .await?;
+//!         if !result.is_authorized() {
+//!             return Err(fdo::Error::AccessDenied("Not authorized".into()));
+//!         }
+//!
+//!         // Reboot...
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # zbus::block_on(async {
+//! let connection = Connection::system().await?;
+//! connection
+//!     .object_server()
+//!     .at("/org/example/Power", Power)
+//!     .await?;
+//! # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+//! # })?;
+//! # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+//! ```
+//!
+//! [polkit]: https://www.freedesktop.org/software/polkit/docs/latest/
+//! [`interface`]: crate::interface
+
+use enumflags2::{bitflags, BitFlags};
+use serde::{Deserialize, Serialize};
+use static_assertions::assert_impl_all;
+use std::collections::HashMap;
+use zbus_names::UniqueName;
+use zvariant::{OwnedValue, Type, Value};
+
+use super::Result;
+use crate::{message::Header, proxy};
+
+#[rustfmt::skip]
+macro_rules! gen_authority_proxy {
+    ($gen_async:literal, $gen_blocking:literal) => {
+        /// Proxy for the `org.freedesktop.PolicyKit1.Authority` interface.
+        #[proxy(
+            interface = "org.freedesktop.PolicyKit1.Authority",
+            default_service = "org.freedesktop.PolicyKit1",
+            default_path = "/org/freedesktop/PolicyKit1/Authority",
+            gen_async = $gen_async,
+            gen_blocking = $gen_blocking,
+        )]
+        trait Authority {
+            /// Checks if `subject` is authorized to perform the action `action_id`.
+            ///
+            /// `details` describes the action, e.g for the authentication dialog. If
+            /// `cancellation_id` isn't empty, the check can be cancelled with
+            /// `cancel_check_authorization`.
+            fn check_authorization(
+                &self,
+                subject: &Subject,
+                action_id: &str,
+                details: &HashMap<&str, &str>,
+                flags: BitFlags<CheckAuthorizationFlags>,
+                cancellation_id: &str,
+            ) -> Result<AuthorizationResult>;
+
+            /// Cancels the authorization check started with `cancellation_id`.
+            fn cancel_check_authorization(&self, cancellation_id: &str) -> Result<()>;
+
+            /// This signal is emitted when actions or authorizations change.
+            #[zbus(signal)]
+            fn changed(&self);
+
+            /// The name of the authority backend.
+            #[zbus(property)]
+            fn backend_name(&self) -> Result<String>;
+
+            /// The version of the authority backend.
+            #[zbus(property)]
+            fn backend_version(&self) -> Result<String>;
+
+            /// The features supported by the authority backend.
+            #[zbus(property)]
+            fn backend_features(&self) -> Result<u32>;
+        }
+    };
+}
+
+gen_authority_proxy!(true, false);
+assert_impl_all!(AuthorityProxy<'_>: Send, Sync, Unpin);
+
+/// The entity whose authorization is checked by [`AuthorityProxy::check_authorization`].
+///
+/// A subject is made of its kind, e.g `system-bus-name`, and of details identifying it.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Type)]
+pub struct Subject {
+    kind: String,
+    details: HashMap<String, OwnedValue>,
+}
+
+assert_impl_all!(Subject: Send, Sync, Unpin);
+
+impl Subject {
+    /// Create a subject of kind `kind`, identified by `details`.
+    pub fn new(kind: &str, details: HashMap<String, OwnedValue>) -> Self {
+        Self {
+            kind: kind.to_string(),
+            details,
+        }
+    }
+
+    /// The connection of unique name `name`, on the system bus.
+    pub fn system_bus_name(name: UniqueName<'_>) -> Self {
+        Self::new("system-bus-name", details([("name", Value::from(name))]))
+    }
+
+    /// The process `pid`, started at `start_time`.
+    ///
+    /// `start_time` is in jiffies since boot, as found in `/proc/<pid>/stat`. It's used to detect
+    /// the reuse of process IDs and can be `0` to let polkit look it up.
+    pub fn unix_process(pid: u32, start_time: u64) -> Self {
+        Self::new(
+            "unix-process",
+            details([
+                ("pid", Value::from(pid)),
+                ("start-time", Value::from(start_time)),
+            ]),
+        )
+    }
+
+    /// The login session `session_id`.
+    pub fn unix_session(session_id: &str) -> Self {
+        Self::new(
+            "unix-session",
+            details([("session-id", Value::from(session_id))]),
+        )
+    }
+
+    /// The sender of the message of header `hdr`.
+    ///
+    /// Returns `None` if the message has no sender, as is the case on peer-to-peer connections.
+    pub fn from_header(hdr: &Header<'_>) -> Option<Self> {
+        hdr.sender()
+            .map(|sender| Self::system_bus_name(sender.clone()))
+    }
+
+    /// The kind of subject.
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The details identifying the subject.
+    pub fn details(&self) -> &HashMap<String, OwnedValue> {
+        &self.details
+    }
+}
+
+fn details<const N: usize>(details: [(&str, Value<'_>); N]) -> HashMap<String, OwnedValue> {
+    details
+        .into_iter()
+        .map(|(key, value)| {
+            let value = value
+                .try_to_owned()
+                .expect("subject details don't contain file descriptors");
+
+            (key.to_string(), value)
+        })
+        .collect()
+}
+
+/// Flags to use with [`AuthorityProxy::check_authorization`].
+#[bitflags]
+#[repr(u32)]
+#[derive(Type, Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum CheckAuthorizationFlags {
+    /// If the subject can obtain the authorization through authentication, and an authentication
+    /// agent is available, then attempt to do so.
+    ///
+    /// Note that this means the method call can take a long time, while the user is prompted.
+    AllowUserInteraction = 0x1,
+}
+
+assert_impl_all!(CheckAuthorizationFlags: Send, Sync, Unpin);
+
+/// The result of [`AuthorityProxy::check_authorization`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct AuthorizationResult {
+    is_authorized: bool,
+    is_challenge: bool,
+    details: HashMap<String, String>,
+}
+
+assert_impl_all!(AuthorizationResult: Send, Sync, Unpin);
+
+impl AuthorizationResult {
+    /// Whether the subject is authorized for the action.
+    pub fn is_authorized(&self) -> bool {
+        self.is_authorized
+    }
+
+    /// Whether the subject could be authorized through authentication.
+    ///
+    /// This is only relevant if the subject isn't authorized.
+    pub fn is_challenge(&self) -> bool {
+        self.is_challenge
+    }
+
+    /// Details about the result, e.g `polkit.dismissed` if the user dismissed the authentication
+    /// dialog.
+    pub fn details(&self) -> &HashMap<String, String> {
+        &self.details
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use enumflags2::BitFlags;
+    use ntest::timeout;
+    use std::collections::HashMap;
+    use test_log::test;
+    use zvariant::{OwnedValue, Type};
+
+    use super::{AuthorityProxy, AuthorizationResult, CheckAuthorizationFlags, Subject};
+    use crate::{connection, fdo, interface, utils::block_on};
+
+    struct Authority;
+
+    #[interface(name = "org.freedesktop.PolicyKit1.Authority")]
+    impl Authority {
+        fn check_authorization(
+            &self,
+            subject: Subject,
+            action_id: String,
+            _details: HashMap<String, String>,
+            flags: BitFlags<CheckAuthorizationFlags>,
+            _cancellation_id: String,
+        ) -> fdo::Result<AuthorizationResult> {
+            let authorized = subject.kind() == "system-bus-name"
+                && subject.details().contains_key("name")
+                && action_id == "org.zbus.allowed";
+
+            Ok(AuthorizationResult {
+                is_authorized: authorized,
+                is_challenge: !authorized
+                    && flags.contains(CheckAuthorizationFlags::AllowUserInteraction),
+                details: HashMap::new(),
+            })
+        }
+
+        #[zbus(property)]
+        fn backend_name(&self) -> &str {
+            "zbus"
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn authority() {
+        assert_eq!(Subject::signature(), "(sa{sv})");
+        assert_eq!(AuthorizationResult::signature(), "(bba{ss})");
+        let subject = Subject::unix_process(42, 0);
+        assert_eq!(subject.kind(), "unix-process");
+        assert_eq!(subject.details()["pid"], OwnedValue::from(42u32));
+
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/freedesktop/PolicyKit1/Authority", Authority)?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let proxy = AuthorityProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .build()
+                .await?;
+            assert_eq!(proxy.backend_name().await?, "zbus");
+
+            let subject = Subject::system_bus_name(client.unique_name().unwrap().inner().clone());
+            let result = proxy
+                .check_authorization(
+                    &subject,
+                    "org.zbus.allowed",
+                    &HashMap::new(),
+                    BitFlags::empty(),
+                    "",
+                )
+                .await?;
+            assert!(result.is_authorized());
+            let result = proxy
+                .check_authorization(
+                    &subject,
+                    "org.zbus.denied",
+                    &HashMap::new(),
+                    CheckAuthorizationFlags::AllowUserInteraction.into(),
+                    "",
+                )
+                .await?;
+            assert!(!result.is_authorized());
+            assert!(result.is_challenge());
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
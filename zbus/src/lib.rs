@@ -30,7 +30,10 @@ mod error_message {
     #[cfg(windows)]
     compile_error!("Either \"async-io\" (default) or \"tokio\" must be enabled. On Windows \"async-io\" is (currently) required for UNIX socket support");
 
-    #[cfg(not(windows))]
+    #[cfg(target_family = "wasm")]
+    compile_error!("The \"async-io\" feature (default) must be enabled on wasm.");
+
+    #[cfg(not(any(windows, target_family = "wasm")))]
     compile_error!("Either \"async-io\" (default) or \"tokio\" must be enabled.");
 }
 
@@ -125,8 +128,16 @@ pub use proxy::PropertyStream;
 #[doc(hidden)]
 pub use proxy::ProxyDefault;
 
+pub mod signal_bridge;
+pub use signal_bridge::SignalBridge;
+
+#[cfg(unix)]
+pub mod fd_stream;
+
+pub mod capture;
+
 pub mod object_server;
-pub use object_server::ObjectServer;
+pub use object_server::{CallerInfo, ObjectServer};
 
 #[deprecated(since = "4.0.0", note = "Use `object_server::DispatchResult` instead")]
 #[doc(hidden)]
@@ -168,7 +179,9 @@ pub use connection::Socket;
 
 pub mod blocking;
 
-pub use zbus_macros::{interface, proxy, DBusError};
+pub mod test;
+
+pub use zbus_macros::{interface, proxy, test, DBusError};
 // Old names used for backwards compatibility
 pub use zbus_macros::{dbus_interface, dbus_proxy};
 
@@ -187,6 +200,8 @@ pub mod export {
 }
 
 pub use zbus_names as names;
+#[cfg(feature = "xml")]
+pub use zbus_xml as xml;
 pub use zvariant;
 
 #[cfg(test)]
@@ -879,26 +894,21 @@ mod tests {
         Ok(())
     }
 
-    #[test]
+    #[zbus::test]
     #[timeout(15000)]
-    fn issue_260() {
+    async fn issue_260(connection: Connection) -> Result<()> {
         // Low-level server example in the book doesn't work. The reason was that
         // `Connection::request_name` implicitly created the associated `ObjectServer` to avoid
         // #68. This meant that the `ObjectServer` ended up replying to the incoming method call
         // with an error, before the service code could do so.
-        block_on(async {
-            let connection = Connection::session().await?;
+        connection.request_name("org.zbus.Issue260").await?;
 
-            connection.request_name("org.zbus.Issue260").await?;
+        futures_util::try_join!(
+            issue_260_service(&connection),
+            issue_260_client(&connection),
+        )?;
 
-            futures_util::try_join!(
-                issue_260_service(&connection),
-                issue_260_client(&connection),
-            )?;
-
-            Ok::<(), zbus::Error>(())
-        })
-        .unwrap();
+        Ok(())
     }
 
     async fn issue_260_service(connection: &Connection) -> Result<()> {
@@ -260,6 +260,15 @@ impl<'m> Builder<'m> {
         Ok(self)
     }
 
+    /// Set whether messages not addressed to the connection are matched as well.
+    ///
+    /// The bus only lets privileged connections, such as monitors, eavesdrop.
+    pub fn eavesdrop(mut self, eavesdrop: bool) -> Self {
+        self.0.eavesdrop = Some(eavesdrop);
+
+        self
+    }
+
     /// Create a builder for `MatchRule`.
     pub(crate) fn new() -> Self {
         Self(MatchRule {
@@ -272,6 +281,7 @@ impl<'m> Builder<'m> {
             args: Vec::with_capacity(MAX_ARGS as usize),
             arg_paths: Vec::with_capacity(MAX_ARGS as usize),
             arg0ns: None,
+            eavesdrop: None,
         })
     }
 }
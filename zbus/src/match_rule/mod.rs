@@ -11,9 +11,9 @@ use zvariant::Structure;
 
 use crate::{
     message::Type,
-    names::{BusName, InterfaceName, MemberName, UniqueName},
+    names::{starts_with_namespace, BusName, InterfaceName, MemberName, UniqueName},
     zvariant::{ObjectPath, Str, Type as VariantType},
-    Error, Result,
+    Error, Proxy, Result,
 };
 
 mod builder;
@@ -79,6 +79,29 @@ pub use builder::Builder;
 /// let parsed_rule = MatchRule::try_from(rule_str.as_str())?;
 /// assert_eq!(rule, parsed_rule);
 ///
+/// // The keys used by monitors, to eavesdrop on the method calls to a peer.
+/// let rule = MatchRule::builder()
+///     .msg_type(zbus::message::Type::MethodCall)
+///     .destination(":1.42")?
+///     .path_namespace("/org/zbus")?
+///     .eavesdrop(true)
+///     .build();
+/// let rule_str = rule.to_string();
+/// assert_eq!(
+///     rule_str,
+///     "type='method_call',\
+///      destination=':1.42',\
+///      path_namespace='/org/zbus',\
+///      eavesdrop='true'",
+/// );
+/// assert_eq!(MatchRule::try_from(rule_str.as_str())?, rule);
+///
+/// // Invalid rules are rejected.
+/// assert!(MatchRule::try_from("path='/org/zbus',path_namespace='/org'").is_err());
+/// assert!(MatchRule::try_from("member='Foo',member='Bar'").is_err());
+/// assert!(MatchRule::try_from("eavesdrop='yes'").is_err());
+/// assert!(MatchRule::try_from("arg64='zbus'").is_err());
+///
 /// # Ok(())
 /// # }
 /// ```
@@ -100,6 +123,7 @@ pub struct MatchRule<'m> {
     pub(crate) args: Vec<(u8, Str<'m>)>,
     pub(crate) arg_paths: Vec<(u8, ObjectPath<'m>)>,
     pub(crate) arg0ns: Option<Str<'m>>,
+    pub(crate) eavesdrop: Option<bool>,
 }
 
 assert_impl_all!(MatchRule<'_>: Send, Sync, Unpin);
@@ -110,6 +134,54 @@ impl<'m> MatchRule<'m> {
         Builder::new()
     }
 
+    /// Create a rule matching the `member` signal emitted by the peer `proxy` is for.
+    ///
+    /// The rule matches the sender, path and interface of `proxy`. Use [`Proxy::inner`] to
+    /// get to the underlying asynchronous proxy of a [`blocking::Proxy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # zbus::block_on(async {
+    /// use zbus::{Connection, MatchRule, Proxy};
+    ///
+    /// let connection = Connection::session().await?;
+    /// let proxy = Proxy::new(
+    ///     &connection,
+    ///     "org.freedesktop.DBus",
+    ///     "/org/freedesktop/DBus",
+    ///     "org.freedesktop.DBus",
+    /// )
+    /// .await?;
+    /// let rule = MatchRule::signal_for(&proxy, "NameOwnerChanged")?;
+    /// assert_eq!(
+    ///     rule.to_string(),
+    ///     "type='signal',\
+    ///      sender='org.freedesktop.DBus',\
+    ///      interface='org.freedesktop.DBus',\
+    ///      member='NameOwnerChanged',\
+    ///      path='/org/freedesktop/DBus'",
+    /// );
+    /// # Ok::<(), zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    ///
+    /// [`Proxy::inner`]: crate::blocking::Proxy::inner
+    /// [`blocking::Proxy`]: crate::blocking::Proxy
+    pub fn signal_for<M>(proxy: &'m Proxy<'_>, member: M) -> Result<Self>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+    {
+        Ok(Self::builder()
+            .msg_type(Type::Signal)
+            .sender(proxy.destination().clone())?
+            .path(proxy.path().clone())?
+            .interface(proxy.interface().clone())?
+            .member(member)?
+            .build())
+    }
+
     /// The sender, if set.
     pub fn sender(&self) -> Option<&BusName<'_>> {
         self.sender.as_ref()
@@ -155,6 +227,11 @@ impl<'m> MatchRule<'m> {
         self.arg0ns.as_ref()
     }
 
+    /// Whether messages not addressed to the connection are matched as well, if set.
+    pub fn eavesdrop(&self) -> Option<bool> {
+        self.eavesdrop
+    }
+
     /// Creates an owned clone of `self`.
     pub fn to_owned(&self) -> MatchRule<'static> {
         MatchRule {
@@ -171,6 +248,7 @@ impl<'m> MatchRule<'m> {
                 .map(|(i, p)| (*i, p.to_owned()))
                 .collect(),
             arg0ns: self.arg0ns.as_ref().map(|a| a.to_owned()),
+            eavesdrop: self.eavesdrop,
         }
     }
 
@@ -194,6 +272,7 @@ impl<'m> MatchRule<'m> {
                 .map(|(i, p)| (i, p.into_owned()))
                 .collect(),
             arg0ns: self.arg0ns.map(|a| a.into_owned()),
+            eavesdrop: self.eavesdrop,
         }
     }
 
@@ -268,7 +347,7 @@ impl<'m> MatchRule<'m> {
             };
             match path_spec {
                 PathSpec::Path(path) if path != msg_path => return Ok(false),
-                PathSpec::PathNamespace(path_ns) if !msg_path.starts_with(path_ns.as_str()) => {
+                PathSpec::PathNamespace(path_ns) if !in_path_namespace(path_ns, msg_path) => {
                     return Ok(false);
                 }
                 PathSpec::Path(_) | PathSpec::PathNamespace(_) => (),
@@ -278,10 +357,8 @@ impl<'m> MatchRule<'m> {
         // The arg0 namespace.
         if let Some(arg0_ns) = self.arg0ns() {
             if let Ok(arg0) = msg.body().deserialize_unchecked::<BusName<'_>>() {
-                match arg0.strip_prefix(arg0_ns.as_str()) {
-                    None => return Ok(false),
-                    Some(s) if !s.is_empty() && !s.starts_with('.') => return Ok(false),
-                    _ => (),
+                if !arg0.is_in_namespace(arg0_ns) {
+                    return Ok(false);
                 }
             } else {
                 return Ok(false);
@@ -324,6 +401,89 @@ impl<'m> MatchRule<'m> {
 
         Ok(true)
     }
+
+    /// Check if every message matched by `other` is also matched by this rule.
+    ///
+    /// This is a conservative check, i-e it may return `false` even if this rule effectively
+    /// matches a superset of what `other` matches but it never returns `true` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use zbus::MatchRule;
+    /// let all_props = MatchRule::builder()
+    ///     .msg_type(zbus::message::Type::Signal)
+    ///     .sender("org.zbus")?
+    ///     .interface("org.freedesktop.DBus.Properties")?
+    ///     .member("PropertiesChanged")?
+    ///     .path_namespace("/org/zbus")?
+    ///     .build();
+    /// let device_props = MatchRule::builder()
+    ///     .msg_type(zbus::message::Type::Signal)
+    ///     .sender("org.zbus")?
+    ///     .interface("org.freedesktop.DBus.Properties")?
+    ///     .member("PropertiesChanged")?
+    ///     .path("/org/zbus/Device1")?
+    ///     .add_arg("org.zbus.Device")?
+    ///     .build();
+    /// assert!(all_props.covers(&device_props));
+    /// assert!(!device_props.covers(&all_props));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn covers(&self, other: &MatchRule<'_>) -> bool {
+        fn covers_field<T: PartialEq>(ours: Option<&T>, theirs: Option<&T>) -> bool {
+            match ours {
+                Some(ours) => theirs == Some(ours),
+                None => true,
+            }
+        }
+
+        if !covers_field(self.msg_type.as_ref(), other.msg_type.as_ref())
+            || !covers_field(self.eavesdrop.as_ref(), other.eavesdrop.as_ref())
+            || !covers_field(self.sender(), other.sender())
+            || !covers_field(self.interface(), other.interface())
+            || !covers_field(self.member(), other.member())
+            || !covers_field(self.destination(), other.destination())
+        {
+            return false;
+        }
+
+        let path_covered = match (self.path_spec(), other.path_spec()) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(PathSpec::Path(ours)), Some(PathSpec::Path(theirs))) => ours == theirs,
+            (Some(PathSpec::Path(_)), Some(PathSpec::PathNamespace(_))) => false,
+            (
+                Some(PathSpec::PathNamespace(ns)),
+                Some(PathSpec::Path(path) | PathSpec::PathNamespace(path)),
+            ) => in_path_namespace(ns, path),
+        };
+        if !path_covered {
+            return false;
+        }
+
+        if let Some(ns) = self.arg0ns() {
+            let in_ns = |name: &str| starts_with_namespace(name, ns);
+            let arg0 = other
+                .args()
+                .iter()
+                .find(|(i, _)| *i == 0)
+                .map(|(_, arg)| arg);
+            match (other.arg0ns(), arg0) {
+                (Some(other_ns), _) if in_ns(other_ns.as_str()) => (),
+                (_, Some(arg0)) if in_ns(arg0.as_str()) => (),
+                _ => return false,
+            }
+        }
+
+        self.args().iter().all(|arg| other.args().contains(arg))
+            && self
+                .arg_paths()
+                .iter()
+                .all(|arg_path| other.arg_paths().contains(arg_path))
+    }
 }
 
 impl Display for MatchRule<'_> {
@@ -369,11 +529,25 @@ impl Display for MatchRule<'_> {
             write_comma(f, &mut first_component)?;
             write!(f, "arg0namespace='{arg0namespace}'")?;
         }
+        if let Some(eavesdrop) = self.eavesdrop() {
+            write_comma(f, &mut first_component)?;
+            write!(f, "eavesdrop='{eavesdrop}'")?;
+        }
 
         Ok(())
     }
 }
 
+// Whether `path` is `namespace` or one of its descendants.
+fn in_path_namespace(namespace: &ObjectPath<'_>, path: &ObjectPath<'_>) -> bool {
+    namespace.as_str() == "/"
+        || path
+            .as_str()
+            .strip_prefix(namespace.as_str())
+            .map(|rest| rest.is_empty() || rest.starts_with('/'))
+            .unwrap_or(false)
+}
+
 fn write_match_rule_string_component(
     f: &mut std::fmt::Formatter<'_>,
     key: &str,
@@ -408,6 +582,7 @@ impl<'m> TryFrom<&'m str> for MatchRule<'m> {
             return Err(Error::InvalidMatchRule);
         }
         let mut builder = MatchRule::builder();
+        let mut keys = Vec::new();
         for component in components {
             let (key, value) = component.split_once('=').ok_or(Error::InvalidMatchRule)?;
             if key.is_empty()
@@ -417,6 +592,12 @@ impl<'m> TryFrom<&'m str> for MatchRule<'m> {
             {
                 return Err(Error::InvalidMatchRule);
             }
+            // Keys can only appear once, and `path` and `path_namespace` are exclusive.
+            let slot = if key == "path_namespace" { "path" } else { key };
+            if keys.contains(&slot) {
+                return Err(Error::InvalidMatchRule);
+            }
+            keys.push(slot);
             let value = &value[1..value.len() - 1];
             builder = match key {
                 "type" => {
@@ -436,6 +617,11 @@ impl<'m> TryFrom<&'m str> for MatchRule<'m> {
                 "path_namespace" => builder.path_namespace(value)?,
                 "destination" => builder.destination(value)?,
                 "arg0namespace" => builder.arg0ns(value)?,
+                "eavesdrop" => match value {
+                    "true" => builder.eavesdrop(true),
+                    "false" => builder.eavesdrop(false),
+                    _ => return Err(Error::InvalidMatchRule),
+                },
                 key if key.starts_with("arg") => {
                     if let Some(trailing_idx) = key.find("path") {
                         let idx = key[3..trailing_idx]
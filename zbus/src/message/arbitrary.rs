@@ -0,0 +1,210 @@
+//! Generating arbitrary messages, for fuzzing.
+
+use arbitrary::{Arbitrary, Unstructured};
+use std::num::NonZeroU32;
+use zbus_names::{BusName, ErrorName, InterfaceName, MemberName, UniqueName};
+use zvariant::{Array, Dict, Endian, ObjectPath, Signature, Str, StructureBuilder, Value};
+
+use super::{Builder, Field, Fields, Flags, Header, Message, PrimaryHeader, Type};
+
+// The maximum nesting of container values in the body.
+const MAX_DEPTH: u8 = 4;
+
+/// Generates structurally valid messages, of any type, byte order, flags and serial number, with
+/// any header fields and body.
+///
+/// The body has no file descriptors, and its floating point values may be NaN.
+impl<'a> Arbitrary<'a> for Message {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let ty = *u.choose(&[
+            Type::MethodCall,
+            Type::MethodReturn,
+            Type::Error,
+            Type::Signal,
+        ])?;
+        let mut primary = PrimaryHeader::new(ty, 0);
+        let endian = if u.arbitrary()? {
+            Endian::Little
+        } else {
+            Endian::Big
+        };
+        primary.set_endian_sig(endian.into());
+        primary.set_serial_num(u.arbitrary()?);
+        let mut flags = u
+            .arbitrary::<bool>()?
+            .then_some(Flags::NoAutoStart)
+            .into_iter()
+            .collect();
+        if ty == Type::MethodCall && u.arbitrary()? {
+            flags |= Flags::NoReplyExpected;
+        }
+        if u.arbitrary()? {
+            flags |= Flags::AllowInteractiveAuth;
+        }
+        primary.set_flags(flags);
+
+        let mut fields = Fields::new();
+        let (path, interface, member) = match ty {
+            Type::MethodCall => (true, u.arbitrary()?, true),
+            Type::Signal => (true, true, true),
+            Type::MethodReturn | Type::Error => (false, false, false),
+        };
+        if path {
+            fields.add(Field::Path(object_path(u)?));
+        }
+        if interface {
+            fields.add(Field::Interface(InterfaceName::from_string_unchecked(
+                dotted_name(u)?,
+            )));
+        }
+        if member {
+            fields.add(Field::Member(MemberName::from_string_unchecked(element(
+                u,
+            )?)));
+        }
+        if ty == Type::Error {
+            fields.add(Field::ErrorName(ErrorName::from_string_unchecked(
+                dotted_name(u)?,
+            )));
+        }
+        if matches!(ty, Type::MethodReturn | Type::Error) {
+            fields.add(Field::ReplySerial(u.arbitrary::<NonZeroU32>()?));
+        }
+        if u.arbitrary()? {
+            fields.add(Field::Destination(bus_name(u)?));
+        }
+        if u.arbitrary()? {
+            fields.add(Field::Sender(unique_name(u)?));
+        }
+        let builder = Builder::from(Header::new(primary, fields));
+
+        let args = u.int_in_range(0..=4)?;
+        let result = if args == 0 {
+            builder.build(&())
+        } else {
+            let mut body = StructureBuilder::new();
+            for _ in 0..args {
+                body = body.append_field(value(u, 0)?);
+            }
+            builder.build(&body.build())
+        };
+
+        // The generated messages are valid, apart from the ones exceeding the maximum size.
+        result.map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+fn value(u: &mut Unstructured<'_>, depth: u8) -> arbitrary::Result<Value<'static>> {
+    let kinds = if depth < MAX_DEPTH { 16 } else { 12 };
+    let value = match u.int_in_range(0..=kinds - 1)? {
+        0 => Value::U8(u.arbitrary()?),
+        1 => Value::Bool(u.arbitrary()?),
+        2 => Value::I16(u.arbitrary()?),
+        3 => Value::U16(u.arbitrary()?),
+        4 => Value::I32(u.arbitrary()?),
+        5 => Value::U32(u.arbitrary()?),
+        6 => Value::I64(u.arbitrary()?),
+        7 => Value::U64(u.arbitrary()?),
+        8 => Value::F64(u.arbitrary()?),
+        9 => Value::Str(Str::from(string(u)?)),
+        10 => Value::ObjectPath(object_path(u)?),
+        11 => {
+            let signature = value(u, MAX_DEPTH)?.value_signature().to_string();
+            Value::Signature(Signature::try_from(signature).unwrap())
+        }
+        12 => Value::Value(Box::new(value(u, depth + 1)?)),
+        13 => {
+            let mut fields = StructureBuilder::new().append_field(value(u, depth + 1)?);
+            for _ in 0..u.int_in_range(0..=3)? {
+                fields = fields.append_field(value(u, depth + 1)?);
+            }
+            Value::Structure(fields.build())
+        }
+        14 => {
+            // An array of the values of the same type as the first one.
+            let first = value(u, depth + 1)?;
+            let signature = first.value_signature().to_owned();
+            let mut array = Array::new(signature.clone());
+            array.append(first).unwrap();
+            while u.arbitrary()? {
+                let element = value(u, depth + 1)?;
+                if element.value_signature() == signature {
+                    array.append(element).unwrap();
+                }
+            }
+            Value::Array(array)
+        }
+        _ => {
+            let mut dict = Dict::new(
+                <&str as zvariant::Type>::signature(),
+                <Value<'_> as zvariant::Type>::signature(),
+            );
+            for _ in 0..u.int_in_range(0..=3)? {
+                let entry = Value::Value(Box::new(value(u, depth + 1)?));
+                dict.append(Value::Str(Str::from(string(u)?)), entry)
+                    .unwrap();
+            }
+            Value::Dict(dict)
+        }
+    };
+
+    Ok(value)
+}
+
+// A string, without the NUL characters D-Bus forbids.
+fn string(u: &mut Unstructured<'_>) -> arbitrary::Result<String> {
+    Ok(u.arbitrary::<String>()?.replace('\0', ""))
+}
+
+// An element of a name or path, e.g `Foo_2`.
+fn element(u: &mut Unstructured<'_>) -> arbitrary::Result<String> {
+    const FIRST: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_";
+    const REST: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz_0123456789";
+
+    let mut element = String::from(*u.choose(FIRST)? as char);
+    for _ in 0..u.int_in_range(0..=8)? {
+        element.push(*u.choose(REST)? as char);
+    }
+
+    Ok(element)
+}
+
+// A name of 2 to 4 elements, as used by interface, error and well-known bus names.
+fn dotted_name(u: &mut Unstructured<'_>) -> arbitrary::Result<String> {
+    let mut name = element(u)?;
+    for _ in 0..u.int_in_range(1..=3)? {
+        name.push('.');
+        name.push_str(&element(u)?);
+    }
+
+    Ok(name)
+}
+
+fn object_path(u: &mut Unstructured<'_>) -> arbitrary::Result<ObjectPath<'static>> {
+    let mut path = String::new();
+    for _ in 0..u.int_in_range(0..=4)? {
+        path.push('/');
+        path.push_str(&element(u)?);
+    }
+    if path.is_empty() {
+        path.push('/');
+    }
+
+    Ok(ObjectPath::from_string_unchecked(path))
+}
+
+fn unique_name(u: &mut Unstructured<'_>) -> arbitrary::Result<UniqueName<'static>> {
+    let name = format!(":{}.{}", u.arbitrary::<u16>()?, u.arbitrary::<u32>()?);
+
+    Ok(UniqueName::from_string_unchecked(name))
+}
+
+fn bus_name(u: &mut Unstructured<'_>) -> arbitrary::Result<BusName<'static>> {
+    if u.arbitrary()? {
+        unique_name(u).map(BusName::Unique)
+    } else {
+        let name = dotted_name(u)?;
+
+        Ok(BusName::WellKnown(name.try_into().unwrap()))
+    }
+}
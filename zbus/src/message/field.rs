@@ -59,6 +59,21 @@ impl<'f> Field<'f> {
             Field::UnixFDs(_) => FieldCode::UnixFDs,
         }
     }
+
+    /// Creates an owned clone of `self`.
+    pub fn to_owned(&self) -> Field<'static> {
+        match self {
+            Field::Path(v) => Field::Path(v.to_owned()),
+            Field::Interface(v) => Field::Interface(v.to_owned()),
+            Field::Member(v) => Field::Member(v.to_owned()),
+            Field::ErrorName(v) => Field::ErrorName(v.to_owned()),
+            Field::ReplySerial(v) => Field::ReplySerial(*v),
+            Field::Destination(v) => Field::Destination(v.to_owned()),
+            Field::Sender(v) => Field::Sender(v.to_owned()),
+            Field::Signature(v) => Field::Signature(v.to_owned()),
+            Field::UnixFDs(v) => Field::UnixFDs(*v),
+        }
+    }
 }
 
 /// The dynamic message header.
@@ -46,6 +46,11 @@ impl<'m> Fields<'m> {
         None
     }
 
+    /// Creates an owned clone of `self`.
+    pub fn to_owned(&self) -> Fields<'static> {
+        Fields(self.0.iter().map(Field::to_owned).collect())
+    }
+
     /// Returns a slice with all the [`Field`] in the message.
     ///
     /// [`Field`]: enum.Field.html
@@ -272,6 +272,14 @@ impl<'m> Header<'m> {
         Self { primary, fields }
     }
 
+    /// Creates an owned clone of `self`.
+    pub fn to_owned(&self) -> Header<'static> {
+        Header {
+            primary: self.primary.clone(),
+            fields: self.fields.to_owned(),
+        }
+    }
+
     /// Get a reference to the primary header.
     pub fn primary(&self) -> &PrimaryHeader {
         &self.primary
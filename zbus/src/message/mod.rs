@@ -3,7 +3,9 @@ use std::{fmt, num::NonZeroU32, sync::Arc};
 
 use static_assertions::assert_impl_all;
 use zbus_names::{ErrorName, InterfaceName, MemberName};
-use zvariant::{serialized, Endian};
+#[cfg(unix)]
+use zvariant::OwnedFd;
+use zvariant::{serialized, Endian, Structure};
 
 use crate::{utils::padding_for_8_bytes, zvariant::ObjectPath, Error, Result};
 
@@ -19,9 +21,12 @@ use fields::{Fields, QuickFields};
 mod body;
 pub use body::Body;
 
+#[cfg(feature = "fuzzing")]
+mod arbitrary;
+
 pub(crate) mod header;
-use header::MIN_MESSAGE_SIZE;
 pub use header::{EndianSig, Flags, Header, PrimaryHeader, Type, NATIVE_ENDIAN_SIG};
+use header::{MAX_MESSAGE_SIZE, MIN_MESSAGE_SIZE};
 
 /// A position in the stream of [`Message`] objects received by a single [`zbus::Connection`].
 ///
@@ -128,6 +133,71 @@ impl Message {
         Self::from_raw_parts(bytes, 0)
     }
 
+    /// Create a message from bytes, validating them.
+    ///
+    /// Unlike [`Message::from_bytes`], the whole message is checked: `bytes` must hold exactly one
+    /// message, with the header fields required by its type, the file descriptors it refers to in
+    /// `fds`, and a body matching its signature. This makes it suitable for untrusted input, such
+    /// as the one of a fuzzer.
+    ///
+    /// **Note:** As with [`Message::from_bytes`], the receive sequence is set to `0`.
+    pub fn try_from_bytes(bytes: &[u8], #[cfg(unix)] fds: Vec<OwnedFd>) -> Result<Self> {
+        if bytes.len() < MIN_MESSAGE_SIZE {
+            return Err(Error::Variant(zvariant::Error::OutOfBounds));
+        }
+        let (primary_header, fields_len) = PrimaryHeader::read(bytes)?;
+        let header_len = MIN_MESSAGE_SIZE + fields_len as usize;
+        let total_len =
+            header_len + padding_for_8_bytes(header_len) + primary_header.body_len() as usize;
+        if total_len > MAX_MESSAGE_SIZE || bytes.len() > total_len {
+            return Err(Error::ExcessData);
+        }
+        if bytes.len() < total_len {
+            return Err(Error::Variant(zvariant::Error::OutOfBounds));
+        }
+
+        let ctxt = serialized::Context::new_dbus(primary_header.endian_sig().into(), 0);
+        #[cfg(unix)]
+        let fds_len = fds.len();
+        #[cfg(unix)]
+        let bytes = serialized::Data::new_fds(bytes.to_vec(), ctxt, fds);
+        #[cfg(not(unix))]
+        let (bytes, fds_len) = (serialized::Data::new(bytes.to_vec(), ctxt), 0);
+        let msg = Self::from_raw_parts(bytes, 0)?;
+
+        let header = msg.header();
+        let has_required_fields = match header.message_type() {
+            Type::MethodCall => header.path().is_some() && header.member().is_some(),
+            Type::MethodReturn => header.reply_serial().is_some(),
+            Type::Error => header.error_name().is_some() && header.reply_serial().is_some(),
+            Type::Signal => {
+                header.path().is_some() && header.interface().is_some() && header.member().is_some()
+            }
+        };
+        if !has_required_fields {
+            return Err(Error::MissingField);
+        }
+        if header.unix_fds().unwrap_or(0) as usize != fds_len {
+            return Err(Error::InvalidField);
+        }
+
+        let body = msg.body();
+        match body.signature() {
+            Some(signature) if !signature.is_empty() => {
+                let (_, len) = body
+                    .data()
+                    .deserialize_for_dynamic_signature::<_, Structure<'_>>(signature)?;
+                if len != body.len() {
+                    return Err(Error::ExcessData);
+                }
+            }
+            _ if !body.is_empty() => return Err(Error::ExcessData),
+            _ => (),
+        }
+
+        Ok(msg)
+    }
+
     /// Create a message from its full contents
     pub(crate) fn from_raw_parts(
         bytes: serialized::Data<'static, 'static>,
@@ -278,6 +348,46 @@ impl Message {
         &self.inner.bytes
     }
 
+    /// Convert the message to the `endian` byte order.
+    ///
+    /// This is useful to normalize messages from peers using a different byte order, e.g when
+    /// recording or bridging traffic. The file descriptors of the message, if any, are duplicated
+    /// into the returned message.
+    pub fn to_endian(&self, endian: Endian) -> Result<Self> {
+        let data = self.data();
+        let current = data.context().endian();
+        if endian == current {
+            return Ok(self.clone());
+        }
+
+        let body_offset = self.inner.body_offset;
+        let header = serialized::Data::new(&data[..body_offset], data.context());
+        let mut bytes = header
+            .to_endian(<Header<'_> as zvariant::Type>::signature(), endian)?
+            .to_vec();
+        if let Some(signature) = self.body().signature() {
+            let ctxt = serialized::Context::new_dbus(current, body_offset);
+            let body = serialized::Data::new(&data[body_offset..], ctxt);
+            bytes.extend_from_slice(&body.to_endian(signature, endian)?);
+        }
+        bytes[0] = EndianSig::from(endian) as u8;
+
+        let ctxt = serialized::Context::new_dbus(endian, 0);
+        #[cfg(unix)]
+        let bytes = serialized::Data::new_fds(
+            bytes,
+            ctxt,
+            data.fds()
+                .iter()
+                .map(|fd| fd.try_to_owned().map(zvariant::OwnedFd::from))
+                .collect::<zvariant::Result<Vec<_>>>()?,
+        );
+        #[cfg(not(unix))]
+        let bytes = serialized::Data::new(bytes, ctxt);
+
+        Self::from_raw_parts(bytes, self.inner.recv_seq.recv_seq)
+    }
+
     /// Get the receive ordering of a message.
     ///
     /// This may be used to identify how two events were ordered on the bus.  It only produces a
@@ -424,4 +534,131 @@ mod tests {
             .unwrap();
         assert_eq!(e.to_string(), "Error org.freedesktop.zbus.Error: kaboom!");
     }
+
+    #[test]
+    fn to_endian() {
+        use zvariant::{BE, LE};
+
+        #[cfg(unix)]
+        let stdout = std::io::stdout();
+        let m = Message::method("/org/example", "Do")
+            .unwrap()
+            .sender(":1.72")
+            .unwrap()
+            .endian(BE)
+            .build(&(
+                #[cfg(unix)]
+                Fd::from(&stdout),
+                "foo",
+                vec![(0x1234u16, 0x12345678u64)],
+            ))
+            .unwrap();
+
+        let converted = m.to_endian(LE).unwrap();
+        assert_eq!(converted.data().context().endian(), LE);
+        assert_eq!(converted.data()[0], b'l');
+        assert_eq!(converted.data().len(), m.data().len());
+        assert_eq!(
+            converted.primary_header().serial_num(),
+            m.primary_header().serial_num()
+        );
+        assert_eq!(converted.header().path(), m.header().path());
+        assert_eq!(converted.header().sender(), m.header().sender());
+        assert_eq!(converted.body().signature(), m.body().signature());
+        let body = converted.body();
+        #[cfg(unix)]
+        let (_, s, a): (Fd<'_>, &str, Vec<(u16, u64)>) = body.deserialize().unwrap();
+        #[cfg(not(unix))]
+        let (s, a): (&str, Vec<(u16, u64)>) = body.deserialize().unwrap();
+        assert_eq!((s, a), ("foo", vec![(0x1234, 0x12345678)]));
+        #[cfg(unix)]
+        assert_eq!(converted.data().fds().len(), 1);
+
+        let back = converted.to_endian(BE).unwrap();
+        assert_eq!(back.data().bytes(), m.data().bytes());
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let m = Message::method("/org/zbus/Parse", "Parse")
+            .unwrap()
+            .build(&("foo", 7u32))
+            .unwrap();
+        let bytes = m.data().bytes();
+        let parse = |bytes: &[u8]| {
+            Message::try_from_bytes(
+                bytes,
+                #[cfg(unix)]
+                vec![],
+            )
+        };
+
+        let parsed = parse(bytes).unwrap();
+        assert_eq!(parsed.data().bytes(), bytes);
+        assert_eq!(
+            parsed.body().deserialize::<(&str, u32)>().unwrap(),
+            ("foo", 7)
+        );
+
+        // Truncated or followed by more data.
+        assert!(parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse(&bytes[..8]).is_err());
+        assert!(matches!(
+            parse(&[bytes, &[0]].concat()),
+            Err(Error::ExcessData)
+        ));
+
+        // A body not matching the signature.
+        let mut bad = bytes.to_vec();
+        let len = bad.len();
+        bad[len - 12..len - 8].copy_from_slice(&[0xff; 4]);
+        assert!(parse(&bad).is_err());
+
+        // Missing the member.
+        let signal = Message::signal("/org/zbus/Parse", "org.zbus.Parse", "Parsed")
+            .unwrap()
+            .build(&())
+            .unwrap();
+        let mut header = signal.header().to_owned();
+        header.fields_mut().remove(super::FieldCode::Member);
+        let incomplete = super::Builder::from(header).build(&()).unwrap();
+        assert!(matches!(
+            parse(incomplete.data().bytes()),
+            Err(Error::MissingField)
+        ));
+
+        // File descriptors not matching the header.
+        #[cfg(unix)]
+        {
+            let fd = zvariant::OwnedFd::from(std::os::fd::OwnedFd::from(
+                std::fs::File::open("/dev/null").unwrap(),
+            ));
+            assert!(matches!(
+                Message::try_from_bytes(bytes, vec![fd]),
+                Err(Error::InvalidField)
+            ));
+        }
+    }
+
+    #[cfg(feature = "fuzzing")]
+    #[test]
+    fn arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        use rand::RngCore;
+
+        let mut data = vec![0; 4096];
+        for _ in 0..100 {
+            rand::thread_rng().fill_bytes(&mut data);
+            let Ok(m) = Message::arbitrary(&mut Unstructured::new(&data)) else {
+                continue;
+            };
+            let parsed = Message::try_from_bytes(
+                m.data().bytes(),
+                #[cfg(unix)]
+                vec![],
+            )
+            .unwrap();
+            assert_eq!(parsed.data().bytes(), m.data().bytes());
+        }
+    }
 }
@@ -0,0 +1,401 @@
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+use zbus_names::{InterfaceName, MemberName};
+use zvariant::{OwnedValue, Value};
+
+use crate::{
+    fdo,
+    message::{Header, Message},
+    object_server::{
+        DispatchMode, DispatchResult, EmitsChangedSignal, Interface, ObjectServer, SignalContext,
+    },
+    utils::block_on,
+    Connection, Task,
+};
+
+/// An adapter running the methods of a synchronous interface on a thread pool.
+///
+/// The method calls to interfaces served on an async [`Connection`] are handled on its executor,
+/// so an interface whose methods block (e.g on system calls, or on FFI calls to a library) starves
+/// the dispatching of other method calls. Wrapping such an interface in a `BlockingInterface`
+/// makes its method calls and property accesses run on a thread reserved for blocking operations
+/// instead, without having to rewrite the interface as async.
+///
+/// The interface is kept behind a [`RwLock`], so that methods taking `&self` can run concurrently
+/// while those taking `&mut self` run exclusively. Use [`BlockingInterface::read`] and
+/// [`BlockingInterface::write`] to access it outside of method calls, e.g through an
+/// [`InterfaceRef`](crate::object_server::InterfaceRef).
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{interface, object_server::BlockingInterface, Connection};
+///
+/// struct Sensor;
+///
+/// #[interface(name = "org.example.Sensor")]
+/// impl Sensor {
+///     fn read(&self) -> u32 {
+///         // Imagine a slow `ioctl` here.
+///         std::thread::sleep(std::time::Duration::from_millis(500));
+///         42
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::session().await?;
+/// connection
+///     .object_server()
+///     .at("/org/example/Sensor", BlockingInterface::new(Sensor))
+///     .await?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+pub struct BlockingInterface<I> {
+    inner: Arc<RwLock<I>>,
+    // The data that doesn't change is gathered upfront, so that it can be queried without waiting
+    // for the lock.
+    introspection: String,
+    polkit_actions: HashMap<String, String>,
+    emits_changed_signal: HashMap<String, EmitsChangedSignal>,
+    dispatch_mode: DispatchMode,
+}
+
+impl<I> BlockingInterface<I>
+where
+    I: Interface,
+{
+    /// Wrap the interface `iface`.
+    pub fn new(iface: I) -> Self {
+        let mut introspection = String::new();
+        iface.introspect_to_writer(&mut introspection, 0);
+        let polkit_actions = names_of(&introspection, "method")
+            .filter_map(|name| Some((name.to_string(), iface.polkit_action(name)?.to_string())))
+            .collect();
+        let emits_changed_signal = names_of(&introspection, "property")
+            .map(|name| (name.to_string(), iface.emits_changed_signal(name)))
+            .collect();
+        let dispatch_mode = iface.dispatch_mode();
+
+        Self {
+            inner: Arc::new(RwLock::new(iface)),
+            introspection,
+            polkit_actions,
+            emits_changed_signal,
+            dispatch_mode,
+        }
+    }
+
+    /// Lock the interface for reading, blocking until it's available.
+    pub fn read(&self) -> RwLockReadGuard<'_, I> {
+        self.inner.read().expect("lock poisoned")
+    }
+
+    /// Lock the interface for writing, blocking until it's available.
+    pub fn write(&self) -> RwLockWriteGuard<'_, I> {
+        self.inner.write().expect("lock poisoned")
+    }
+
+    /// Run `f` on the interface, on a thread reserved for blocking operations.
+    fn unblock<F, T>(&self, f: F) -> Task<T>
+    where
+        F: FnOnce(&Arc<RwLock<I>>) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let inner = self.inner.clone();
+
+        Task::spawn_blocking(move || f(&inner), "blocking interface")
+    }
+}
+
+/// The values of the `name` attributes of the `element` elements in the introspection data.
+fn names_of<'x>(xml: &'x str, element: &str) -> impl Iterator<Item = &'x str> {
+    let prefix = format!("<{element} name=\"");
+
+    xml.lines().filter_map(move |line| {
+        let rest = line.trim_start().strip_prefix(prefix.as_str())?;
+
+        rest.split('"').next()
+    })
+}
+
+#[async_trait]
+impl<I> Interface for BlockingInterface<I>
+where
+    I: Interface,
+{
+    fn name() -> InterfaceName<'static> {
+        I::name()
+    }
+
+    async fn get(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        let property_name = property_name.to_string();
+
+        self.unblock(move |inner| {
+            let iface = inner.read().expect("lock poisoned");
+
+            block_on(iface.get(&property_name))
+        })
+        .await
+    }
+
+    async fn get_all(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        self.unblock(|inner| {
+            let iface = inner.read().expect("lock poisoned");
+
+            block_on(iface.get_all())
+        })
+        .await
+    }
+
+    async fn get_for_caller(
+        &self,
+        property_name: &str,
+        _server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> Option<fdo::Result<OwnedValue>> {
+        let property_name = property_name.to_string();
+        let conn = connection.clone();
+        let header = header.to_owned();
+
+        self.unblock(move |inner| {
+            let server = conn.object_server();
+            let iface = inner.read().expect("lock poisoned");
+
+            block_on(iface.get_for_caller(&property_name, &server, &conn, &header))
+        })
+        .await
+    }
+
+    async fn get_all_for_caller(
+        &self,
+        _server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let conn = connection.clone();
+        let header = header.to_owned();
+
+        self.unblock(move |inner| {
+            let server = conn.object_server();
+            let iface = inner.read().expect("lock poisoned");
+
+            block_on(iface.get_all_for_caller(&server, &conn, &header))
+        })
+        .await
+    }
+
+    async fn set_mut(
+        &mut self,
+        property_name: &str,
+        value: &Value<'_>,
+        ctxt: &SignalContext<'_>,
+    ) -> Option<fdo::Result<()>> {
+        let property_name = property_name.to_string();
+        let value = match value.try_to_owned() {
+            Ok(value) => value,
+            Err(e) => return Some(Err(fdo::Error::InvalidArgs(e.to_string()))),
+        };
+        let ctxt = ctxt.to_owned();
+
+        self.unblock(move |inner| {
+            let mut iface = inner.write().expect("lock poisoned");
+            match iface.set(&property_name, &value, &ctxt) {
+                DispatchResult::NotFound => return None,
+                DispatchResult::Async(f) => return Some(block_on(f).map_err(Into::into)),
+                DispatchResult::RequiresMut => (),
+            }
+
+            block_on(iface.set_mut(&property_name, &value, &ctxt))
+        })
+        .await
+    }
+
+    fn call<'call>(
+        &'call self,
+        _server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        let conn = connection.clone();
+        let msg = msg.clone();
+        let name = name.to_owned();
+
+        DispatchResult::Async(Box::pin(self.unblock(move |inner| {
+            // The blocking thread can't borrow `server`, so get it from the connection instead.
+            let server = conn.object_server();
+            {
+                let iface = inner.read().expect("lock poisoned");
+                match iface.call(&server, &conn, &msg, name.clone()) {
+                    DispatchResult::NotFound => {
+                        let err = fdo::Error::UnknownMethod(format!("Unknown method '{name}'"));
+                        return block_on(conn.reply_dbus_error(&msg.header(), err));
+                    }
+                    DispatchResult::Async(f) => return block_on(f),
+                    DispatchResult::RequiresMut => (),
+                };
+            }
+
+            let mut iface = inner.write().expect("lock poisoned");
+            let res = match iface.call_mut(&server, &conn, &msg, name.clone()) {
+                DispatchResult::Async(f) => block_on(f),
+                DispatchResult::NotFound | DispatchResult::RequiresMut => {
+                    let err = fdo::Error::UnknownMethod(format!("Unknown method '{name}'"));
+                    block_on(conn.reply_dbus_error(&msg.header(), err))
+                }
+            };
+
+            res
+        })))
+    }
+
+    fn call_mut<'call>(
+        &'call mut self,
+        server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        self.call(server, connection, msg, name)
+    }
+
+    fn polkit_action(&self, method: &str) -> Option<&str> {
+        self.polkit_actions.get(method).map(String::as_str)
+    }
+
+    fn dispatch_mode(&self) -> DispatchMode {
+        self.dispatch_mode
+    }
+
+    fn emits_changed_signal(&self, property: &str) -> EmitsChangedSignal {
+        self.emits_changed_signal
+            .get(property)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize) {
+        for line in self.introspection.lines() {
+            writeln!(writer, "{:indent$}{line}", "", indent = level).unwrap();
+        }
+    }
+}
+
+impl<I> fmt::Debug for BlockingInterface<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingInterface")
+            .field("dispatch_mode", &self.dispatch_mode)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use std::{
+        sync::atomic::{AtomicU32, Ordering},
+        thread::sleep,
+        time::{Duration, Instant},
+    };
+    use test_log::test;
+
+    use zbus_names::InterfaceName;
+
+    use super::BlockingInterface;
+    use crate::{connection, fdo, interface, utils::block_on};
+
+    #[derive(Default)]
+    struct Sensor {
+        reading: AtomicU32,
+    }
+
+    #[interface(name = "org.zbus.Sensor")]
+    impl Sensor {
+        fn measure(&self) -> u32 {
+            sleep(Duration::from_millis(500));
+
+            self.reading.fetch_add(1, Ordering::SeqCst) + 1
+        }
+
+        fn ping(&self) {}
+
+        #[zbus(property)]
+        fn reading(&self) -> u32 {
+            self.reading.load(Ordering::SeqCst)
+        }
+
+        #[zbus(property)]
+        fn set_reading(&mut self, reading: u32) {
+            *self.reading.get_mut() = reading;
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn blocking_interface() {
+        block_on(async {
+            let path = "/org/zbus/Sensor";
+            let service = connection::Builder::session()?
+                .serve_at(path, BlockingInterface::new(Sensor::default()))?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let call = |method| {
+                client.call_method(
+                    service.unique_name(),
+                    path,
+                    Some("org.zbus.Sensor"),
+                    method,
+                    &(),
+                )
+            };
+
+            // `Ping` isn't held up by `Measure` blocking.
+            let start = Instant::now();
+            let (measured, pinged) = futures_util::join!(call("Measure"), async {
+                call("Ping").await?;
+
+                Ok::<_, crate::Error>(start.elapsed())
+            });
+            assert_eq!(measured?.body().deserialize::<u32>()?, 1);
+            assert!(pinged? < Duration::from_millis(500));
+
+            let props = fdo::PropertiesProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .path(path)?
+                .build()
+                .await?;
+            let iface: InterfaceName<'_> = "org.zbus.Sensor".try_into()?;
+            props.set(iface.clone(), "Reading", &42u32.into()).await?;
+            let reading = props.get(iface.clone(), "Reading").await?;
+            assert_eq!(u32::try_from(reading)?, 42);
+            assert_eq!(props.get_all(Some(iface).into()).await?.len(), 1);
+
+            let iface_ref = service
+                .object_server()
+                .interface::<_, BlockingInterface<Sensor>>(path)
+                .await?;
+            assert_eq!(iface_ref.get().await.read().reading(), 42);
+
+            let xml = fdo::IntrospectableProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .path(path)?
+                .build()
+                .await?
+                .introspect()
+                .await?;
+            assert!(xml.contains(r#"<method name="Measure">"#), "{xml}");
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,220 @@
+use std::{collections::VecDeque, sync::Mutex};
+use zbus_names::{OwnedUniqueName, UniqueName};
+
+use crate::{
+    fdo::{self, ConnectionCredentials, DBusProxy},
+    message::Header,
+    Connection,
+};
+
+/// Information about the caller of a method.
+///
+/// Interface methods can receive this by declaring an argument with the `#[zbus(caller_info)]`
+/// attribute. The object server fills it with the credentials of the caller, as reported by the
+/// bus (through `GetConnectionCredentials`), or of the peer on peer-to-peer connections.
+///
+/// The fields are populated on a best effort basis, and may be `None` if the bus or the platform
+/// doesn't provide them.
+#[derive(Clone, Debug)]
+pub struct CallerInfo {
+    sender: Option<OwnedUniqueName>,
+    credentials: ConnectionCredentials,
+}
+
+impl CallerInfo {
+    /// The unique name of the caller.
+    ///
+    /// This is `None` on peer-to-peer connections.
+    pub fn sender(&self) -> Option<&UniqueName<'static>> {
+        self.sender.as_deref()
+    }
+
+    /// The numeric Unix user ID of the caller.
+    pub fn unix_user_id(&self) -> Option<u32> {
+        self.credentials.unix_user_id()
+    }
+
+    /// The numeric process ID of the caller.
+    pub fn process_id(&self) -> Option<u32> {
+        self.credentials.process_id()
+    }
+
+    /// The security label of the caller.
+    ///
+    /// See [`ConnectionCredentials::linux_security_label`] for details.
+    pub fn linux_security_label(&self) -> Option<&Vec<u8>> {
+        self.credentials.linux_security_label()
+    }
+
+    /// All the credentials of the caller.
+    pub fn credentials(&self) -> &ConnectionCredentials {
+        &self.credentials
+    }
+}
+
+/// The number of callers whose credentials are kept around.
+const CACHE_SIZE: usize = 64;
+
+/// A cache of the credentials of the most recent callers.
+///
+/// Unique names are never reused on a bus, so the entries never get stale.
+#[derive(Debug, Default)]
+pub(crate) struct CredentialsCache(Mutex<VecDeque<(OwnedUniqueName, ConnectionCredentials)>>);
+
+impl CredentialsCache {
+    /// Get the information about the caller of the message with header `hdr`.
+    pub(crate) async fn caller_info(
+        &self,
+        conn: &Connection,
+        hdr: &Header<'_>,
+    ) -> fdo::Result<CallerInfo> {
+        if !conn.is_bus() {
+            let credentials = conn
+                .peer_credentials()
+                .await
+                .map_err(|e| fdo::Error::IOError(e.to_string()))?;
+
+            return Ok(CallerInfo {
+                sender: None,
+                credentials,
+            });
+        }
+
+        let sender = hdr
+            .sender()
+            .ok_or_else(|| fdo::Error::Failed("Missing sender".into()))?;
+        let cached = self
+            .0
+            .lock()
+            .expect("lock poisoned")
+            .iter()
+            .find(|(name, _)| name.as_str() == sender.as_str())
+            .map(|(_, credentials)| credentials.clone());
+        let credentials = match cached {
+            Some(credentials) => credentials,
+            None => {
+                let credentials = DBusProxy::new(conn)
+                    .await?
+                    .get_connection_credentials(sender.as_ref().into())
+                    .await?;
+                let mut cache = self.0.lock().expect("lock poisoned");
+                if cache.len() == CACHE_SIZE {
+                    cache.pop_front();
+                }
+                cache.push_back((sender.to_owned().into(), credentials.clone()));
+
+                credentials
+            }
+        };
+
+        Ok(CallerInfo {
+            sender: Some(sender.to_owned().into()),
+            credentials,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+
+    use zbus_names::InterfaceName;
+
+    use super::CallerInfo;
+    use crate::{connection, fdo, interface, utils::block_on};
+
+    struct WhoAmI;
+
+    #[interface(name = "org.zbus.WhoAmI")]
+    impl WhoAmI {
+        fn who_am_i(&self, #[zbus(caller_info)] caller: CallerInfo) -> (String, u32, u32) {
+            (
+                caller.sender().unwrap().to_string(),
+                caller.unix_user_id().unwrap(),
+                caller.process_id().unwrap(),
+            )
+        }
+
+        #[zbus(property)]
+        fn name(&self, #[zbus(caller_info)] caller: CallerInfo) -> String {
+            caller.sender().unwrap().to_string()
+        }
+
+        #[zbus(property)]
+        fn secret(&self, #[zbus(caller_info)] caller: CallerInfo) -> fdo::Result<String> {
+            Err(fdo::Error::AccessDenied(format!(
+                "`{}` may not know the secret",
+                caller.sender().unwrap(),
+            )))
+        }
+
+        #[zbus(property)]
+        fn greeting(&self) -> &str {
+            "Hello"
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn caller_info() {
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/WhoAmI", WhoAmI)?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+
+            for _ in 0..2 {
+                let (sender, uid, pid): (String, u32, u32) = client
+                    .call_method(
+                        service.unique_name(),
+                        "/org/zbus/WhoAmI",
+                        Some("org.zbus.WhoAmI"),
+                        "WhoAmI",
+                        &(),
+                    )
+                    .await?
+                    .body()
+                    .deserialize()?;
+                assert_eq!(sender, client.unique_name().unwrap().as_str());
+                #[cfg(unix)]
+                assert_eq!(uid, nix::unistd::Uid::current().as_raw());
+                assert_eq!(pid, std::process::id());
+            }
+
+            let props = fdo::PropertiesProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .path("/org/zbus/WhoAmI")?
+                .build()
+                .await?;
+            let iface: InterfaceName<'_> = "org.zbus.WhoAmI".try_into()?;
+            let name = props.get(iface.clone(), "Name").await?;
+            assert_eq!(
+                String::try_from(name)?,
+                client.unique_name().unwrap().as_str()
+            );
+            let err = props.get(iface.clone(), "Secret").await.unwrap_err();
+            assert!(matches!(err, fdo::Error::AccessDenied(_)), "{err}");
+            let all = props.get_all(Some(iface).into()).await?;
+            let mut names: Vec<_> = all.keys().map(String::as_str).collect();
+            names.sort_unstable();
+            assert_eq!(names, ["Greeting", "Name"]);
+
+            // The value isn't sent along with the change notification.
+            let changes = service
+                .object_server()
+                .interface::<_, WhoAmI>("/org/zbus/WhoAmI")
+                .await?;
+            assert!(changes
+                .get()
+                .await
+                .name_changed(changes.signal_context())
+                .await
+                .is_ok());
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,606 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use zbus_names::{InterfaceName, MemberName};
+use zvariant::{OwnedValue, Signature, Structure, StructureBuilder, Value};
+
+use crate::{
+    fdo::{self, Properties},
+    message::Message,
+    object_server::{DispatchResult, Interface, SignalContext},
+    Connection, Error, ObjectServer, Result,
+};
+
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = fdo::Result<T>> + Send>>;
+type MethodHandler =
+    Arc<dyn Fn(Message, Vec<OwnedValue>) -> BoxedFuture<Vec<OwnedValue>> + Send + Sync>;
+type Getter = Arc<dyn Fn() -> BoxedFuture<OwnedValue> + Send + Sync>;
+type Setter = Arc<dyn Fn(OwnedValue) -> BoxedFuture<()> + Send + Sync>;
+
+/// An interface defined at runtime.
+///
+/// While the [`interface`] macro is the recommended way to implement interfaces, it requires them
+/// to be known at compile time. This type allows defining the methods, properties and signals of
+/// an interface at runtime instead, with handlers operating on [`OwnedValue`](struct@OwnedValue)
+/// arguments. This is useful for bridges or language bindings for example. The introspection data
+/// is generated from the given names and signatures.
+///
+/// Use [`ObjectServer::at_dynamic`] to serve it at a given path.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{
+///     object_server::DynamicInterface,
+///     zvariant::{OwnedValue, Str},
+///     Connection,
+/// };
+///
+/// # zbus::block_on(async {
+/// let iface = DynamicInterface::new("org.zbus.Calculator")?
+///     .method(
+///         "Add",
+///         &[("a", "i"), ("b", "i")],
+///         &[("sum", "i")],
+///         |_msg, args| async move {
+///             let a = i32::try_from(&args[0]).map_err(zbus::Error::from)?;
+///             let b = i32::try_from(&args[1]).map_err(zbus::Error::from)?;
+///
+///             Ok(vec![OwnedValue::from(a + b)])
+///         },
+///     )?
+///     .property("Model", "s", || async { Ok(OwnedValue::from(Str::from("abacus"))) })?
+///     .signal("Overflow", &[("operation", "s")])?;
+///
+/// let connection = Connection::session().await?;
+/// connection
+///     .object_server()
+///     .at_dynamic("/org/zbus/Calculator", iface)
+///     .await?;
+///
+/// // Signals are emitted like any other signal.
+/// connection
+///     .emit_signal(
+///         None::<()>,
+///         "/org/zbus/Calculator",
+///         "org.zbus.Calculator",
+///         "Overflow",
+///         &("Add",),
+///     )
+///     .await?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+///
+/// [`interface`]: crate::interface
+#[derive(Clone)]
+pub struct DynamicInterface {
+    name: InterfaceName<'static>,
+    methods: Vec<Method>,
+    properties: Vec<Property>,
+    signals: Vec<Member>,
+}
+
+#[derive(Clone)]
+struct Method {
+    member: Member,
+    out_args: Vec<Arg>,
+    handler: MethodHandler,
+}
+
+#[derive(Clone)]
+struct Property {
+    name: String,
+    signature: Signature<'static>,
+    getter: Getter,
+    setter: Option<Setter>,
+}
+
+#[derive(Clone)]
+struct Member {
+    name: MemberName<'static>,
+    args: Vec<Arg>,
+}
+
+#[derive(Clone)]
+struct Arg {
+    name: String,
+    signature: Signature<'static>,
+}
+
+impl DynamicInterface {
+    /// Create a new interface with the given name and no members.
+    pub fn new<N>(name: N) -> Result<Self>
+    where
+        N: TryInto<InterfaceName<'static>>,
+        N::Error: Into<Error>,
+    {
+        Ok(Self {
+            name: name.try_into().map_err(Into::into)?,
+            methods: vec![],
+            properties: vec![],
+            signals: vec![],
+        })
+    }
+
+    /// The name of the interface.
+    pub fn name(&self) -> &InterfaceName<'static> {
+        &self.name
+    }
+
+    /// Add a method.
+    ///
+    /// `in_args` and `out_args` are the names and signatures of the input and output arguments
+    /// respectively. The `handler` is given the method call message and the input arguments and
+    /// returns the output arguments. Calls with arguments not matching `in_args` are replied to
+    /// with an [`fdo::Error::InvalidArgs`] error, without invoking the `handler`.
+    pub fn method<M, F, Fut>(
+        mut self,
+        name: M,
+        in_args: &[(&str, &str)],
+        out_args: &[(&str, &str)],
+        handler: F,
+    ) -> Result<Self>
+    where
+        M: TryInto<MemberName<'static>>,
+        M::Error: Into<Error>,
+        F: Fn(Message, Vec<OwnedValue>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = fdo::Result<Vec<OwnedValue>>> + Send + 'static,
+    {
+        self.methods.push(Method {
+            member: Member::new(name, in_args)?,
+            out_args: Arg::list(out_args)?,
+            handler: Arc::new(move |msg, args| Box::pin(handler(msg, args))),
+        });
+
+        Ok(self)
+    }
+
+    /// Add a read-only property of the given signature.
+    pub fn property<F, Fut>(self, name: &str, signature: &str, getter: F) -> Result<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = fdo::Result<OwnedValue>> + Send + 'static,
+    {
+        self.add_property(name, signature, getter, None)
+    }
+
+    /// Add a read-write property of the given signature.
+    ///
+    /// The `PropertiesChanged` signal is emitted on your behalf, after `setter` succeeds.
+    pub fn writable_property<F, Fut, S, SFut>(
+        self,
+        name: &str,
+        signature: &str,
+        getter: F,
+        setter: S,
+    ) -> Result<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = fdo::Result<OwnedValue>> + Send + 'static,
+        S: Fn(OwnedValue) -> SFut + Send + Sync + 'static,
+        SFut: Future<Output = fdo::Result<()>> + Send + 'static,
+    {
+        let setter: Setter = Arc::new(move |value| Box::pin(setter(value)));
+
+        self.add_property(name, signature, getter, Some(setter))
+    }
+
+    /// Declare a signal with the given argument names and signatures.
+    ///
+    /// This is only used for the introspection data. Signals are emitted through
+    /// [`Connection::emit_signal`].
+    pub fn signal<M>(mut self, name: M, args: &[(&str, &str)]) -> Result<Self>
+    where
+        M: TryInto<MemberName<'static>>,
+        M::Error: Into<Error>,
+    {
+        self.signals.push(Member::new(name, args)?);
+
+        Ok(self)
+    }
+
+    fn add_property<F, Fut>(
+        mut self,
+        name: &str,
+        signature: &str,
+        getter: F,
+        setter: Option<Setter>,
+    ) -> Result<Self>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = fdo::Result<OwnedValue>> + Send + 'static,
+    {
+        // Property names follow the same rules as member names.
+        MemberName::try_from(name)?;
+        self.properties.push(Property {
+            name: name.to_string(),
+            signature: Signature::try_from(signature)?.to_owned(),
+            getter: Arc::new(move || Box::pin(getter())),
+            setter,
+        });
+
+        Ok(self)
+    }
+
+    fn property_by_name(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+impl fmt::Debug for DynamicInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicInterface")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Member {
+    fn new<M>(name: M, args: &[(&str, &str)]) -> Result<Self>
+    where
+        M: TryInto<MemberName<'static>>,
+        M::Error: Into<Error>,
+    {
+        Ok(Self {
+            name: name.try_into().map_err(Into::into)?,
+            args: Arg::list(args)?,
+        })
+    }
+}
+
+impl Arg {
+    fn list(args: &[(&str, &str)]) -> Result<Vec<Self>> {
+        args.iter()
+            .map(|(name, signature)| {
+                Ok(Self {
+                    name: name.to_string(),
+                    signature: Signature::try_from(*signature)?.to_owned(),
+                })
+            })
+            .collect()
+    }
+}
+
+// The signature of the message body corresponding to `args`.
+fn body_signature(args: &[Arg]) -> String {
+    args.iter().map(|arg| arg.signature.as_str()).collect()
+}
+
+// Checks that the values match the signatures of `args`.
+fn check_values(values: &[Value<'_>], args: &[Arg]) -> bool {
+    values.len() == args.len()
+        && values
+            .iter()
+            .zip(args)
+            .all(|(value, arg)| value.value_signature() == arg.signature)
+}
+
+/// The dispatching wrapper of a [`DynamicInterface`].
+///
+/// This is a separate type, so that a `DynamicInterface` can't be passed to the APIs relying on
+/// [`Interface::name`], which isn't known at compile time.
+pub(crate) struct DynamicInterfaceImpl(pub(crate) DynamicInterface);
+
+#[async_trait]
+impl Interface for DynamicInterfaceImpl {
+    fn name() -> InterfaceName<'static> {
+        // Never used since dynamic interfaces are registered under their runtime name.
+        InterfaceName::from_static_str_unchecked("org.zbus.DynamicInterface")
+    }
+
+    async fn get(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        let getter = self.0.property_by_name(property_name)?.getter.clone();
+
+        Some(getter().await)
+    }
+
+    async fn get_all(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let mut all = HashMap::new();
+        for property in &self.0.properties {
+            all.insert(property.name.clone(), (property.getter)().await?);
+        }
+
+        Ok(all)
+    }
+
+    fn set<'call>(
+        &'call self,
+        property_name: &'call str,
+        value: &'call Value<'_>,
+        ctxt: &'call SignalContext<'_>,
+    ) -> DispatchResult<'call> {
+        let property = match self.0.property_by_name(property_name) {
+            Some(property) => property,
+            None => return DispatchResult::NotFound,
+        };
+        let interface_name = self.0.name.clone();
+
+        DispatchResult::Async(Box::pin(async move {
+            let setter = property.setter.as_ref().ok_or_else(|| {
+                fdo::Error::PropertyReadOnly(format!("Property `{property_name}` is read-only"))
+            })?;
+            if value.value_signature() != property.signature {
+                return Err(fdo::Error::InvalidArgs(format!(
+                    "Property `{property_name}` expects a value of type `{}`",
+                    property.signature,
+                ))
+                .into());
+            }
+            let value = OwnedValue::try_from(value)?;
+            setter(value.try_clone()?).await?;
+
+            let changed = HashMap::from([(property_name, &*value)]);
+            Properties::properties_changed(ctxt, interface_name, &changed, &[]).await
+        }))
+    }
+
+    async fn set_mut(
+        &mut self,
+        _property_name: &str,
+        _value: &Value<'_>,
+        _ctxt: &SignalContext<'_>,
+    ) -> Option<fdo::Result<()>> {
+        // Handled by `set`.
+        None
+    }
+
+    fn call<'call>(
+        &'call self,
+        _server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        let method = match self.0.methods.iter().find(|m| m.member.name == name) {
+            Some(method) => method,
+            None => return DispatchResult::NotFound,
+        };
+
+        DispatchResult::new_async(connection, msg, async move {
+            let hdr = msg.header();
+            let in_signature = body_signature(&method.member.args);
+            let msg_signature = hdr.signature().map(|s| s.as_str()).unwrap_or_default();
+            if msg_signature != in_signature {
+                return Err(fdo::Error::InvalidArgs(format!(
+                    "Expected arguments of type `{in_signature}`, got `{msg_signature}`"
+                )));
+            }
+            let args = if in_signature.is_empty() {
+                vec![]
+            } else {
+                msg.body()
+                    .deserialize::<Structure<'_>>()?
+                    .into_fields()
+                    .into_iter()
+                    .map(OwnedValue::try_from)
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(Error::from)?
+            };
+
+            let out = (method.handler)(msg.clone(), args).await?;
+            let out: Vec<Value<'_>> = out.into_iter().map(Into::into).collect();
+            if !check_values(&out, &method.out_args) {
+                return Err(fdo::Error::Failed(format!(
+                    "Method `{name}` returned values not matching `{}`",
+                    body_signature(&method.out_args),
+                )));
+            }
+
+            Ok(out
+                .into_iter()
+                .fold(StructureBuilder::new(), StructureBuilder::append_field)
+                .build())
+        })
+    }
+
+    fn call_mut<'call>(
+        &'call mut self,
+        _server: &'call ObjectServer,
+        _connection: &'call Connection,
+        _msg: &'call Message,
+        _name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        // All methods are handled by `call`.
+        DispatchResult::NotFound
+    }
+
+    fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize) {
+        let iface = &self.0;
+        writeln!(
+            writer,
+            r#"{:indent$}<interface name="{}">"#,
+            "",
+            iface.name,
+            indent = level
+        )
+        .unwrap();
+        let level = level + 2;
+        for method in &iface.methods {
+            let name = &method.member.name;
+            writeln!(
+                writer,
+                "{:indent$}<method name=\"{name}\">",
+                "",
+                indent = level
+            )
+            .unwrap();
+            for (arg, dir) in method
+                .member
+                .args
+                .iter()
+                .map(|arg| (arg, "in"))
+                .chain(method.out_args.iter().map(|arg| (arg, "out")))
+            {
+                writeln!(
+                    writer,
+                    "{:indent$}<arg name=\"{}\" type=\"{}\" direction=\"{dir}\"/>",
+                    "",
+                    arg.name,
+                    arg.signature,
+                    indent = level + 2
+                )
+                .unwrap();
+            }
+            writeln!(writer, "{:indent$}</method>", "", indent = level).unwrap();
+        }
+        for signal in &iface.signals {
+            let name = &signal.name;
+            writeln!(
+                writer,
+                "{:indent$}<signal name=\"{name}\">",
+                "",
+                indent = level
+            )
+            .unwrap();
+            for arg in &signal.args {
+                writeln!(
+                    writer,
+                    "{:indent$}<arg name=\"{}\" type=\"{}\"/>",
+                    "",
+                    arg.name,
+                    arg.signature,
+                    indent = level + 2
+                )
+                .unwrap();
+            }
+            writeln!(writer, "{:indent$}</signal>", "", indent = level).unwrap();
+        }
+        for property in &iface.properties {
+            let access = if property.setter.is_some() {
+                "readwrite"
+            } else {
+                "read"
+            };
+            writeln!(
+                writer,
+                "{:indent$}<property name=\"{}\" type=\"{}\" access=\"{access}\"/>",
+                "",
+                property.name,
+                property.signature,
+                indent = level
+            )
+            .unwrap();
+        }
+        writeln!(writer, "{:indent$}</interface>", "", indent = level - 2).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connection, utils::block_on};
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use std::sync::Mutex;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn dynamic_interface() {
+        block_on(test_dynamic_interface()).unwrap();
+    }
+
+    async fn test_dynamic_interface() -> Result<()> {
+        let model = Arc::new(Mutex::new(String::from("abacus")));
+        let getter_model = model.clone();
+        let iface = DynamicInterface::new("org.zbus.Calculator")?
+            .method(
+                "Add",
+                &[("a", "i"), ("b", "i")],
+                &[("sum", "i")],
+                |_, args| async move {
+                    let a = i32::try_from(&args[0]).map_err(Error::from)?;
+                    let b = i32::try_from(&args[1]).map_err(Error::from)?;
+
+                    Ok(vec![OwnedValue::from(a + b)])
+                },
+            )?
+            .method("Broken", &[], &[("out", "s")], |_, _| async {
+                Ok(vec![OwnedValue::from(42u32)])
+            })?
+            .writable_property(
+                "Model",
+                "s",
+                move || {
+                    let model = getter_model.lock().unwrap().clone();
+                    async move { Ok(OwnedValue::from(zvariant::Str::from(model))) }
+                },
+                move |value| {
+                    let model = model.clone();
+                    async move {
+                        *model.lock().unwrap() = String::try_from(value).map_err(Error::from)?;
+
+                        Ok(())
+                    }
+                },
+            )?
+            .signal("Overflow", &[("operation", "s")])?;
+
+        let service_name = "org.freedesktop.zbus.DynamicInterfaceTest";
+        // Serving an interface from the builder ensures the object server is ready to handle
+        // calls once the connection is built.
+        let server_conn = connection::Builder::session()?
+            .name(service_name)?
+            .serve_at("/org/zbus", fdo::ObjectManager)?
+            .build()
+            .await?;
+        assert!(
+            server_conn
+                .object_server()
+                .at_dynamic("/org/zbus/Calculator", iface)
+                .await?
+        );
+
+        let client_conn = Connection::session().await?;
+        let proxy = crate::Proxy::new(
+            &client_conn,
+            service_name,
+            "/org/zbus/Calculator",
+            "org.zbus.Calculator",
+        )
+        .await?;
+        assert_eq!(proxy.call::<_, _, i32>("Add", &(40, 2)).await?, 42);
+        let err = proxy
+            .call::<_, _, i32>("Add", &("40", 2))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::MethodError(name, _, _) if name.as_str().ends_with("InvalidArgs")),
+            "{err}"
+        );
+        let err = proxy.call::<_, _, String>("Broken", &()).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::MethodError(name, _, _) if name.as_str().ends_with("Failed")),
+            "{err}"
+        );
+
+        assert_eq!(proxy.get_property::<String>("Model").await?, "abacus");
+        let mut changes = proxy.receive_property_changed::<String>("Model").await;
+        proxy.set_property("Model", "slide rule").await.unwrap();
+        let changed = changes.next().await.unwrap();
+        assert_eq!(changed.get().await?, "slide rule");
+
+        let xml = proxy.introspect().await?;
+        assert!(xml.contains(r#"<arg name="sum" type="i" direction="out"/>"#));
+        assert!(xml.contains(r#"<property name="Model" type="s" access="readwrite"/>"#));
+        assert!(xml.contains(r#"<signal name="Overflow">"#));
+
+        assert!(
+            server_conn
+                .object_server()
+                .remove_dynamic("/org/zbus/Calculator", "org.zbus.Calculator")
+                .await?
+        );
+
+        Ok(())
+    }
+}
@@ -4,17 +4,24 @@ use std::{
     fmt::{self, Write},
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use async_trait::async_trait;
+use event_listener::Event;
 use zbus::message::Flags;
 use zbus_names::{InterfaceName, MemberName};
 use zvariant::{DynamicType, OwnedValue, Value};
 
 use crate::{
-    async_lock::RwLock, fdo, message::Message, object_server::SignalContext, Connection,
-    ObjectServer, Result,
+    async_lock::RwLock,
+    fdo,
+    message::{Header, Message},
+    object_server::{sequencer::Sequencer, SignalContext},
+    Connection, ObjectServer, Result,
 };
 use tracing::trace;
 
@@ -57,6 +64,47 @@ impl<'a> DispatchResult<'a> {
     }
 }
 
+/// How the method calls to an interface are dispatched, as returned by
+/// [`Interface::dispatch_mode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DispatchMode {
+    /// Method calls are dispatched concurrently, as soon as they are received.
+    ///
+    /// Calls may therefore complete in a different order than they were received in, e.g if an
+    /// earlier call awaits on something.
+    #[default]
+    Concurrent,
+
+    /// Method calls are dispatched one at a time, in the order they were received in.
+    ///
+    /// Each call is only dispatched after the previous one is replied to.
+    Serial,
+}
+
+/// How changes to a property are signaled, as returned by [`Interface::emits_changed_signal`].
+///
+/// This corresponds to the standard `org.freedesktop.DBus.Property.EmitsChangedSignal`
+/// annotation. See the [D-Bus specification] for details.
+///
+/// [D-Bus specification]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmitsChangedSignal {
+    /// The `PropertiesChanged` signal is emitted with the new value of the property.
+    #[default]
+    True,
+
+    /// The `PropertiesChanged` signal is emitted, but the new value is not included in it.
+    Invalidates,
+
+    /// The property never changes, so no signal is ever emitted for it.
+    Const,
+
+    /// No signal is emitted when the property changes.
+    False,
+}
+
 /// The trait is used to dispatch messages to an interface instance.
 ///
 /// This trait should be treated as unstable API and compatibility may break in minor
@@ -78,6 +126,35 @@ pub trait Interface: Any + Send + Sync {
     /// Return all the properties.
     async fn get_all(&self) -> fdo::Result<HashMap<String, OwnedValue>>;
 
+    /// Get a property value on behalf of the caller of the method call with header `header`.
+    ///
+    /// This is used by the `org.freedesktop.DBus.Properties` interface, so that properties can
+    /// depend on who's asking. The default implementation calls [`Interface::get`].
+    async fn get_for_caller(
+        &self,
+        property_name: &str,
+        server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> Option<fdo::Result<OwnedValue>> {
+        let _ = (server, connection, header);
+        self.get(property_name).await
+    }
+
+    /// Return all the properties, on behalf of the caller of the method call with header `header`.
+    ///
+    /// This is used by the `org.freedesktop.DBus.Properties` interface, so that properties can
+    /// depend on who's asking. The default implementation calls [`Interface::get_all`].
+    async fn get_all_for_caller(
+        &self,
+        server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let _ = (server, connection, header);
+        self.get_all().await
+    }
+
     /// Set a property value.
     ///
     /// Return [`DispatchResult::NotFound`] if the property doesn't exist, or
@@ -130,13 +207,111 @@ pub trait Interface: Any + Send + Sync {
         name: MemberName<'call>,
     ) -> DispatchResult<'call>;
 
+    /// The polkit action the caller must be authorized for, to call the given method.
+    ///
+    /// If this returns an action ID, the [`ObjectServer`] asks the polkit authority whether the
+    /// sender of the method call is authorized for it, before dispatching the call. If not, the
+    /// call is replied to with an [`fdo::Error::AccessDenied`] error. The default implementation
+    /// returns `None`.
+    fn polkit_action(&self, method: &str) -> Option<&str> {
+        let _ = method;
+        None
+    }
+
+    /// How the method calls to this interface are to be dispatched.
+    ///
+    /// This is queried once, when the interface is registered. The default implementation returns
+    /// [`DispatchMode::Concurrent`].
+    fn dispatch_mode(&self) -> DispatchMode {
+        DispatchMode::Concurrent
+    }
+
+    /// How changes to the given property are signaled.
+    ///
+    /// This is honored by [`InterfaceRef::properties_changed`]. The default implementation returns
+    /// [`EmitsChangedSignal::True`].
+    ///
+    /// [`InterfaceRef::properties_changed`]: crate::object_server::InterfaceRef::properties_changed
+    fn emits_changed_signal(&self, property: &str) -> EmitsChangedSignal {
+        let _ = property;
+        EmitsChangedSignal::True
+    }
+
     /// Write introspection XML to the writer, with the given indentation level.
     fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize);
 }
 
 /// A newtype for a reference counted Interface trait-object, with a manual Debug impl.
 #[derive(Clone)]
-pub(crate) struct ArcInterface(pub(crate) Arc<RwLock<dyn Interface>>);
+pub(crate) struct ArcInterface {
+    pub(crate) instance: Arc<RwLock<dyn Interface>>,
+    /// Orders the method calls of [`DispatchMode::Serial`] interfaces.
+    pub(crate) sequencer: Option<Arc<Sequencer>>,
+    /// The method calls being handled.
+    pub(crate) calls: Arc<Calls>,
+}
+
+impl ArcInterface {
+    pub(crate) fn new(instance: Arc<RwLock<dyn Interface>>) -> Self {
+        // The interface was just created so it can't be locked.
+        let mode = instance
+            .try_read()
+            .map(|iface| iface.dispatch_mode())
+            .unwrap_or_default();
+        let sequencer = (mode == DispatchMode::Serial).then(Default::default);
+
+        Self {
+            instance,
+            sequencer,
+            calls: Default::default(),
+        }
+    }
+}
+
+/// Counts the method calls being handled by an interface.
+#[derive(Debug, Default)]
+pub(crate) struct Calls {
+    in_flight: AtomicUsize,
+    drained: Event,
+}
+
+impl Calls {
+    /// Count a method call as in-flight until the returned guard is dropped.
+    pub(crate) fn enter(self: &Arc<Self>) -> Call {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+
+        Call(self.clone())
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Wait for the method calls in flight to complete.
+    pub(crate) async fn drained(&self) {
+        loop {
+            // Listen before checking, so the last call completing in between isn't missed.
+            let listener = self.drained.listen();
+            if self.in_flight.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            listener.await;
+        }
+    }
+}
+
+/// A method call being handled, counted in [`Calls`].
+#[derive(Debug)]
+pub(crate) struct Call(Arc<Calls>);
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.drained.notify(usize::MAX);
+        }
+    }
+}
 
 impl fmt::Debug for ArcInterface {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -0,0 +1,68 @@
+use std::{fmt, path::PathBuf, sync::Arc};
+
+use crate::fdo;
+
+type Callback = Arc<dyn Fn() -> fdo::Result<String> + Send + Sync>;
+
+/// Where the `GetMachineId` method of the `org.freedesktop.DBus.Peer` interface gets the machine ID
+/// from.
+///
+/// See [`ObjectServer::set_machine_id_source`](crate::ObjectServer::set_machine_id_source).
+#[derive(Clone, Default)]
+pub enum MachineIdSource {
+    /// Read from `/var/lib/dbus/machine-id`, or from `/etc/machine-id` if the former doesn't
+    /// exist.
+    #[default]
+    System,
+    /// Read from the file at the given path.
+    File(PathBuf),
+    /// The given ID.
+    Fixed(String),
+    /// Returned by a callback, on each method call.
+    Callback(Callback),
+}
+
+impl MachineIdSource {
+    /// The ID returned by `callback`, on each method call.
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: Fn() -> fdo::Result<String> + Send + Sync + 'static,
+    {
+        Self::Callback(Arc::new(callback))
+    }
+
+    /// Get the machine ID.
+    pub fn machine_id(&self) -> fdo::Result<String> {
+        let mut id = match self {
+            Self::System => match std::fs::read_to_string("/var/lib/dbus/machine-id") {
+                Ok(id) => id,
+                Err(e) => std::fs::read_to_string("/etc/machine-id").map_err(|_| {
+                    fdo::Error::IOError(format!(
+                        "Failed to read from /var/lib/dbus/machine-id or /etc/machine-id: {e}"
+                    ))
+                })?,
+            },
+            Self::File(path) => std::fs::read_to_string(path).map_err(|e| {
+                fdo::Error::IOError(format!("Failed to read from {}: {e}", path.display()))
+            })?,
+            Self::Fixed(id) => id.clone(),
+            Self::Callback(callback) => callback()?,
+        };
+
+        let len = id.trim_end().len();
+        id.truncate(len);
+
+        Ok(id)
+    }
+}
+
+impl fmt::Debug for MachineIdSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::System => f.write_str("System"),
+            Self::File(path) => f.debug_tuple("File").field(path).finish(),
+            Self::Fixed(id) => f.debug_tuple("Fixed").field(id).finish(),
+            Self::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
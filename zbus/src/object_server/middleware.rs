@@ -0,0 +1,264 @@
+use async_trait::async_trait;
+use std::{fmt, sync::Arc};
+use zbus_names::OwnedInterfaceName;
+use zvariant::OwnedObjectPath;
+
+use crate::{message::Message, Connection, Error, ObjectServer, Result};
+
+/// A layer wrapping the dispatch of method calls by the [`ObjectServer`].
+///
+/// Middleware is useful for concerns shared by many methods, such as logging, metrics, filtering
+/// of callers or fault injection. Each middleware is given the method call and a [`Next`] handle
+/// to the rest of the chain, which it may run or not. Rejecting a call is done by returning an
+/// [`fdo::Error`](crate::fdo::Error), which is then sent back to the caller.
+///
+/// Use [`ObjectServer::add_middleware`] to register a middleware. Middleware is run in the order
+/// of registration.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{
+///     fdo,
+///     message::Message,
+///     object_server::{Middleware, MiddlewareScope, Next},
+///     Connection,
+/// };
+///
+/// struct DenyList(Vec<String>);
+///
+/// #[async_trait::async_trait]
+/// impl Middleware for DenyList {
+///     async fn handle(&self, msg: &Message, next: Next<'_>) -> zbus::Result<()> {
+///         let hdr = msg.header();
+///         if let Some(sender) = hdr.sender() {
+///             if self.0.iter().any(|denied| denied == sender.as_str()) {
+///                 return Err(fdo::Error::AccessDenied(format!("`{sender}` is denied")).into());
+///             }
+///         }
+///
+///         next.run().await
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::session().await?;
+/// connection
+///     .object_server()
+///     .add_middleware(MiddlewareScope::All, DenyList(vec![":1.42".into()]))
+///     .await;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[async_trait]
+pub trait Middleware: Send + Sync + 'static {
+    /// Handle the method call `msg`.
+    ///
+    /// Returning an [`Error::FDO`] error replies to the caller with it. Other errors are returned
+    /// to the task dispatching the message. Errors from [`Next::run`] should be returned as is.
+    async fn handle(&self, msg: &Message, next: Next<'_>) -> Result<()>;
+}
+
+/// The method calls a [`Middleware`] applies to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MiddlewareScope {
+    /// All the method calls received by the object server.
+    All,
+    /// Method calls to the given path and all the paths under it.
+    PathPrefix(OwnedObjectPath),
+    /// Method calls to the given interface.
+    Interface(OwnedInterfaceName),
+}
+
+impl MiddlewareScope {
+    fn matches(&self, msg: &Message) -> bool {
+        let hdr = msg.header();
+        match self {
+            Self::All => true,
+            Self::PathPrefix(prefix) => hdr.path().is_some_and(|path| {
+                let (prefix, path) = (prefix.as_str(), path.as_str());
+
+                prefix == "/"
+                    || path == prefix
+                    || path
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }),
+            Self::Interface(name) => hdr.interface().is_some_and(|iface| *iface == *name),
+        }
+    }
+}
+
+/// The rest of the dispatch chain of a method call, given to [`Middleware::handle`].
+pub struct Next<'n> {
+    middleware: &'n [Arc<dyn Middleware>],
+    server: &'n ObjectServer,
+    connection: &'n Connection,
+    msg: &'n Message,
+}
+
+impl<'n> Next<'n> {
+    pub(crate) fn new(
+        middleware: &'n [Arc<dyn Middleware>],
+        server: &'n ObjectServer,
+        connection: &'n Connection,
+        msg: &'n Message,
+    ) -> Self {
+        Self {
+            middleware,
+            server,
+            connection,
+            msg,
+        }
+    }
+
+    /// Run the next middleware, or dispatch the method call to the interface if there is none
+    /// left.
+    pub async fn run(self) -> Result<()> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => {
+                let next = Next::new(rest, self.server, self.connection, self.msg);
+
+                first.handle(self.msg, next).await
+            }
+            None => match self
+                .server
+                .dispatch_method_call_try(self.connection, self.msg)
+                .await
+            {
+                Ok(r) => r,
+                Err(e) => Err(Error::FDO(Box::new(e))),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Next")
+            .field("remaining", &self.middleware.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A registered [`Middleware`] along with its scope.
+#[derive(Clone)]
+pub(crate) struct Layer {
+    pub(crate) scope: MiddlewareScope,
+    pub(crate) middleware: Arc<dyn Middleware>,
+}
+
+impl fmt::Debug for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Layer")
+            .field("scope", &self.scope)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The middleware applying to `msg`, in order of registration.
+pub(crate) fn applicable(layers: &[Layer], msg: &Message) -> Vec<Arc<dyn Middleware>> {
+    layers
+        .iter()
+        .filter(|layer| layer.scope.matches(msg))
+        .map(|layer| layer.middleware.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use ntest::timeout;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use test_log::test;
+    use zbus_names::OwnedInterfaceName;
+
+    use super::{Middleware, MiddlewareScope, Next};
+    use crate::{connection, fdo, interface, message::Message, utils::block_on, Result};
+
+    struct Counter;
+
+    #[interface(name = "org.zbus.Counter")]
+    impl Counter {
+        fn ping(&self) {}
+    }
+
+    struct Count(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Middleware for Count {
+        async fn handle(&self, _msg: &Message, next: Next<'_>) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+
+            next.run().await
+        }
+    }
+
+    struct Reject;
+
+    #[async_trait]
+    impl Middleware for Reject {
+        async fn handle(&self, _msg: &Message, _next: Next<'_>) -> Result<()> {
+            Err(fdo::Error::AccessDenied("Rejected".into()).into())
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn middleware() {
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Counter", Counter)?
+                .serve_at("/org/zbus/Counter/Child", Counter)?
+                .serve_at("/org/zbus/CounterSibling", Counter)?
+                .build()
+                .await?;
+            let server = service.object_server();
+            let all = Arc::new(AtomicUsize::new(0));
+            server
+                .add_middleware(MiddlewareScope::All, Count(all.clone()))
+                .await;
+            let prefixed = Arc::new(AtomicUsize::new(0));
+            server
+                .add_middleware(
+                    MiddlewareScope::PathPrefix("/org/zbus/Counter".try_into()?),
+                    Count(prefixed.clone()),
+                )
+                .await;
+            let iface: OwnedInterfaceName = "org.freedesktop.DBus.Peer".try_into()?;
+            server
+                .add_middleware(MiddlewareScope::Interface(iface), Reject)
+                .await;
+
+            let client = connection::Builder::session()?.build().await?;
+            let ping = |path: &'static str, iface: &'static str| {
+                let client = client.clone();
+                let dest = service.unique_name().unwrap().to_owned();
+                async move {
+                    client
+                        .call_method(Some(dest), path, Some(iface), "Ping", &())
+                        .await
+                }
+            };
+            ping("/org/zbus/Counter", "org.zbus.Counter").await?;
+            ping("/org/zbus/Counter/Child", "org.zbus.Counter").await?;
+            ping("/org/zbus/CounterSibling", "org.zbus.Counter").await?;
+            let err = ping("/org/zbus/Counter", "org.freedesktop.DBus.Peer")
+                .await
+                .unwrap_err();
+            assert!(matches!(fdo::Error::from(err), fdo::Error::AccessDenied(_)));
+
+            assert_eq!(all.load(Ordering::SeqCst), 4);
+            assert_eq!(prefixed.load(Ordering::SeqCst), 3);
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
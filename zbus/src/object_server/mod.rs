@@ -1,36 +1,94 @@
 //! The object server API.
 
 use event_listener::{Event, EventListener};
+use futures_util::future::{select, Either};
 use serde::Serialize;
 use std::{
     collections::{hash_map::Entry, HashMap},
-    fmt::Write,
+    fmt::{self, Write},
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    pin::pin,
     sync::Arc,
+    time::Duration,
 };
-use tracing::{debug, instrument, trace};
+use tracing::{debug, instrument, trace, warn};
 
 use static_assertions::assert_impl_all;
-use zbus_names::InterfaceName;
+use zbus_names::{InterfaceName, MemberName, OwnedInterfaceName};
 use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Signature, Type, Value};
 
 use crate::{
     async_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard},
     connection::WeakConnection,
     fdo,
-    fdo::{Introspectable, ManagedObjects, ObjectManager, Peer, Properties},
-    message::Message,
+    fdo::{Introspectable, ManagedObjects, Peer, Properties},
+    message::{Header, Message},
     Connection, Error, Result,
 };
 
 mod interface;
 pub(crate) use interface::ArcInterface;
-pub use interface::{DispatchResult, Interface};
+pub use interface::{DispatchMode, DispatchResult, EmitsChangedSignal, Interface};
+
+mod sequencer;
+use sequencer::{Sequencer, Ticket};
+
+mod machine_id;
+pub use machine_id::MachineIdSource;
+
+mod watchdog;
+pub use watchdog::SlowHandler;
+use watchdog::{Deadlines, DispatchLimit, Watchdog};
 
 mod signal_context;
 pub use signal_context::SignalContext;
 
+mod dynamic;
+pub use dynamic::DynamicInterface;
+use dynamic::DynamicInterfaceImpl;
+
+mod polkit;
+
+mod middleware;
+
+mod caller_info;
+pub use caller_info::CallerInfo;
+use caller_info::CredentialsCache;
+use middleware::Layer;
+pub use middleware::{Middleware, MiddlewareScope, Next};
+
+#[cfg(feature = "tower")]
+mod service;
+#[cfg(feature = "tower")]
+pub use service::DispatchService;
+#[cfg(feature = "tower")]
+use service::ServiceMiddleware;
+
+mod registration;
+pub use registration::Registration;
+
+mod responder;
+pub use responder::Responder;
+
+mod blocking_interface;
+pub use blocking_interface::BlockingInterface;
+
+mod stats;
+pub use stats::InterfaceStats;
+use stats::Stats;
+
+mod object;
+pub use object::Object;
+
+mod shim;
+pub use shim::InterfaceShim;
+use shim::InterfaceShimImpl;
+
+pub mod test;
+
+pub use crate::fdo::ObjectManager;
+
 /// Opaque structure that derefs to an `Interface` type.
 pub struct InterfaceDeref<'d, I> {
     iface: RwLockReadGuard<'d, dyn Interface>,
@@ -169,6 +227,130 @@ where
     pub fn signal_context(&self) -> &SignalContext<'static> {
         &self.ctxt
     }
+
+    /// Emit a single `PropertiesChanged` signal for all the given properties.
+    ///
+    /// The generated `<property>_changed` methods emit one signal per property, which can flood
+    /// clients when many properties change together. Instead, make all the changes and then call
+    /// this method with the names of the changed properties. Their current values are included in
+    /// the signal, unless they're declared as [`EmitsChangedSignal::Invalidates`], in which case
+    /// they're only listed as invalidated. Properties declared as [`EmitsChangedSignal::False`] are
+    /// left out.
+    ///
+    /// # Errors
+    ///
+    /// If any of the properties doesn't exist, [`fdo::Error::UnknownProperty`] error is returned,
+    /// and if any is declared as [`EmitsChangedSignal::Const`], [`Error::Failure`] is. In both
+    /// cases, no signal is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use async_io::block_on;
+    /// # use zbus::{Connection, interface};
+    ///
+    /// struct Position {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// #[interface(name = "org.myiface.Position")]
+    /// impl Position {
+    ///    #[zbus(property)]
+    ///    async fn x(&self) -> i32 {
+    ///        self.x
+    ///    }
+    ///
+    ///    #[zbus(property)]
+    ///    async fn y(&self) -> i32 {
+    ///        self.y
+    ///    }
+    /// }
+    ///
+    /// # block_on(async {
+    /// # let connection = Connection::session().await?;
+    /// #
+    /// # let path = "/org/zbus/path";
+    /// # connection.object_server().at(path, Position { x: 0, y: 0 }).await?;
+    /// let object_server = connection.object_server();
+    /// let iface_ref = object_server.interface::<_, Position>(path).await?;
+    /// {
+    ///     let mut iface = iface_ref.get_mut().await;
+    ///     iface.x = 4;
+    ///     iface.y = 2;
+    /// }
+    /// iface_ref.properties_changed(&["X", "Y"]).await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// #
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn properties_changed(&self, property_names: &[&str]) -> Result<()>
+    where
+        I: Interface,
+    {
+        let mut changes = PropertyChanges::default();
+        {
+            let iface = self.lock.read().await;
+            for name in property_names {
+                changes.add(&*iface, name).await.ok_or_else(|| {
+                    fdo::Error::UnknownProperty(format!("Unknown property '{name}'"))
+                })??;
+            }
+        }
+
+        changes.emit(&self.ctxt, I::name()).await
+    }
+}
+
+/// The changes to the properties of an interface, to be signaled with a single `PropertiesChanged`
+/// signal.
+#[derive(Default)]
+struct PropertyChanges<'n> {
+    changed: Vec<(&'n str, OwnedValue)>,
+    invalidated: Vec<&'n str>,
+}
+
+impl<'n> PropertyChanges<'n> {
+    /// Add the property `name` of `iface`, as declared by [`Interface::emits_changed_signal`].
+    ///
+    /// Returns `None` if `iface` has no such property.
+    async fn add(&mut self, iface: &dyn Interface, name: &'n str) -> Option<Result<()>> {
+        let value = iface.get(name).await?;
+        match iface.emits_changed_signal(name) {
+            EmitsChangedSignal::True => match value {
+                Ok(value) => self.changed.push((name, value)),
+                Err(e) => return Some(Err(e.into())),
+            },
+            EmitsChangedSignal::Invalidates => self.invalidated.push(name),
+            EmitsChangedSignal::False => (),
+            EmitsChangedSignal::Const => {
+                return Some(Err(Error::Failure(format!(
+                    "Property `{name}` is constant"
+                ))));
+            }
+        }
+
+        Some(Ok(()))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.invalidated.is_empty()
+    }
+
+    async fn emit(self, ctxt: &SignalContext<'_>, iface_name: InterfaceName<'_>) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let changed = self
+            .changed
+            .iter()
+            .map(|(name, value)| (*name, &**value))
+            .collect();
+
+        Properties::properties_changed(ctxt, iface_name, &changed, &self.invalidated).await
+    }
 }
 
 impl<I> Clone for InterfaceRef<I> {
@@ -186,6 +368,25 @@ pub(crate) struct Node {
     path: OwnedObjectPath,
     children: HashMap<String, Node>,
     interfaces: HashMap<InterfaceName<'static>, ArcInterface>,
+    /// Interfaces serving this path and all paths under it (see [`ObjectServer::at_fallback`]).
+    fallbacks: HashMap<InterfaceName<'static>, ArcInterface>,
+    /// See [`ObjectServer::set_dispatch_limit`].
+    dispatch_limit: Option<Arc<DispatchLimit>>,
+    /// See [`ObjectServer::set_extra_introspection`].
+    extra_introspection: Option<String>,
+    /// See [`ObjectServer::set_virtual_children`].
+    virtual_children: Option<VirtualChildren>,
+}
+
+type ListChildren = dyn Fn(&ObjectPath<'_>) -> Vec<String> + Send + Sync;
+
+/// Lists the child nodes of the objects that are created on demand.
+struct VirtualChildren(Box<ListChildren>);
+
+impl fmt::Debug for VirtualChildren {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VirtualChildren").finish_non_exhaustive()
+    }
 }
 
 impl Node {
@@ -272,7 +473,108 @@ impl Node {
         &self,
         interface_name: InterfaceName<'_>,
     ) -> Option<Arc<RwLock<dyn Interface>>> {
-        self.interfaces.get(&interface_name).map(|x| x.0.clone())
+        self.interfaces
+            .get(&interface_name)
+            .map(|x| x.instance.clone())
+    }
+
+    // Get the interface serving `path`, taking fallback interfaces into account.
+    //
+    // An interface registered at the exact `path` always takes precedence over a fallback one. If
+    // multiple ancestors have a fallback interface, the closest one is returned. Standard
+    // interfaces are served for paths covered by a fallback, even if there is no node for them.
+    pub(crate) fn interface_lock_at(
+        &self,
+        path: &ObjectPath<'_>,
+        interface_name: InterfaceName<'_>,
+    ) -> Option<Arc<RwLock<dyn Interface>>> {
+        self.interface_at(path, interface_name)
+            .map(|iface| iface.instance)
+    }
+
+    pub(crate) fn interface_at(
+        &self,
+        path: &ObjectPath<'_>,
+        interface_name: InterfaceName<'_>,
+    ) -> Option<ArcInterface> {
+        let mut node = Some(self);
+        let mut fallback = self.fallbacks.get(&interface_name);
+        let mut covered = !self.fallbacks.is_empty();
+        for i in path.split('/').skip(1).filter(|i| !i.is_empty()) {
+            node = node.and_then(|n| n.children.get(i));
+            if let Some(n) = node {
+                fallback = n.fallbacks.get(&interface_name).or(fallback);
+                covered |= !n.fallbacks.is_empty();
+            }
+        }
+
+        match node.and_then(|n| n.interfaces.get(&interface_name)) {
+            Some(iface) => Some(iface.clone()),
+            None => match fallback {
+                Some(iface) => Some(iface.clone()),
+                // `self` is the root node, which always has the standard interfaces.
+                None if covered && node.is_none() && is_standard_interface(&interface_name) => {
+                    self.interfaces.get(&interface_name).cloned()
+                }
+                None => None,
+            },
+        }
+    }
+
+    // Create a node for `path`, with the fallback interfaces covering it.
+    //
+    // This is used for paths without a node of their own but served by fallback interfaces.
+    pub(crate) fn fallback_node(&self, path: &ObjectPath<'_>) -> Option<Node> {
+        let mut node = Some(self);
+        let mut fallbacks = self.fallbacks.clone();
+        for i in path.split('/').skip(1).filter(|i| !i.is_empty()) {
+            node = node.and_then(|n| n.children.get(i));
+            if let Some(n) = node {
+                fallbacks.extend(n.fallbacks.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        if fallbacks.is_empty() {
+            return None;
+        }
+
+        let mut node = Node::new(path.to_owned().into());
+        for (name, iface) in fallbacks {
+            node.interfaces.entry(name).or_insert(iface);
+        }
+
+        Some(node)
+    }
+
+    // The names of the virtual child nodes of `path`, from the closest provider covering it.
+    //
+    // Names that aren't valid path elements are skipped, as are those of actual child nodes.
+    pub(crate) fn virtual_children(&self, path: &ObjectPath<'_>) -> Vec<String> {
+        let mut node = Some(self);
+        let mut provider = self.virtual_children.as_ref();
+        for i in path.split('/').skip(1).filter(|i| !i.is_empty()) {
+            node = node.and_then(|n| n.children.get(i));
+            match node {
+                Some(n) => provider = n.virtual_children.as_ref().or(provider),
+                None => break,
+            }
+        }
+        let Some(provider) = provider else {
+            return vec![];
+        };
+
+        let mut names = (provider.0)(path);
+        names.retain(|name| {
+            let valid =
+                !name.is_empty() && name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+            if !valid {
+                debug!("Skipping invalid child node name `{name}` of `{path}`");
+            }
+
+            valid && !node.is_some_and(|n| n.children.contains_key(name))
+        });
+        names.dedup();
+
+        names
     }
 
     fn remove_interface(&mut self, interface_name: InterfaceName<'static>) -> bool {
@@ -280,18 +582,50 @@ impl Node {
     }
 
     fn is_empty(&self) -> bool {
-        !self.interfaces.keys().any(|k| {
-            *k != Peer::name()
-                && *k != Introspectable::name()
-                && *k != Properties::name()
-                && *k != ObjectManager::name()
-        })
+        !self
+            .interfaces
+            .keys()
+            .any(|k| !is_standard_interface(k) && *k != ObjectManager::name())
     }
 
     fn remove_node(&mut self, node: &str) -> bool {
         self.children.remove(node).is_some()
     }
 
+    /// Remove the node at `path` if there are no more interfaces on it. Returns whether the object
+    /// was destroyed.
+    ///
+    /// The node itself is kept around if it's needed as an intermediate node for its children.
+    fn remove_if_empty(&mut self, path: &ObjectPath<'_>) -> bool {
+        let node = match self.get_child_mut(path, false).0 {
+            Some(node) if node.is_empty() => node,
+            _ => return false,
+        };
+        if !node.children.is_empty() || !node.fallbacks.is_empty() || path.as_str() == "/" {
+            return true;
+        }
+        let mut path_parts = path.rsplit('/').filter(|i| !i.is_empty());
+        let last_part = path_parts.next().unwrap();
+        let ppath = ObjectPath::from_string_unchecked(
+            path_parts.fold(String::new(), |a, p| format!("/{p}{a}")),
+        );
+        self.get_child_mut(&ppath, false)
+            .0
+            .unwrap()
+            .remove_node(last_part);
+
+        true
+    }
+
+    /// Set the implementation of the interface `name`, returning the previous one, if any.
+    fn replace(
+        &mut self,
+        name: InterfaceName<'static>,
+        instance: Arc<RwLock<dyn Interface>>,
+    ) -> Option<ArcInterface> {
+        self.interfaces.insert(name, ArcInterface::new(instance))
+    }
+
     // Takes a closure so caller can avoid having to create an Arc & RwLock in case interface was
     // already added.
     fn at<F>(&mut self, name: InterfaceName<'static>, iface_creator: F) -> bool
@@ -299,14 +633,18 @@ impl Node {
         F: FnOnce() -> Arc<RwLock<dyn Interface>>,
     {
         match self.interfaces.entry(name) {
-            Entry::Vacant(e) => e.insert(ArcInterface(iface_creator())),
+            Entry::Vacant(e) => e.insert(ArcInterface::new(iface_creator())),
             Entry::Occupied(_) => return false,
         };
 
         true
     }
 
-    async fn introspect_to_writer<W: Write + Send>(&self, writer: &mut W) {
+    async fn introspect_to_writer<W: Write + Send>(
+        &self,
+        writer: &mut W,
+        virtual_children: &[String],
+    ) {
         enum Fragment<'a> {
             /// Represent an unclosed node tree, could be further splitted into sub-`Fragment`s
             Node {
@@ -362,7 +700,21 @@ impl Node {
                     }
 
                     for iface in node.interfaces.values() {
-                        iface.0.read().await.introspect_to_writer(writer, level + 2);
+                        iface
+                            .instance
+                            .read()
+                            .await
+                            .introspect_to_writer(writer, level + 2);
+                    }
+
+                    for line in node.extra_introspection.iter().flat_map(|x| x.lines()) {
+                        writeln!(writer, "{:indent$}{}", "", line, indent = level + 2).unwrap();
+                    }
+
+                    if level == 0 {
+                        for name in virtual_children {
+                            writeln!(writer, "  <node name=\"{name}\"/>").unwrap();
+                        }
                     }
                 }
                 Fragment::End { level } => {
@@ -372,10 +724,10 @@ impl Node {
         }
     }
 
-    pub(crate) async fn introspect(&self) -> String {
+    pub(crate) async fn introspect(&self, virtual_children: &[String]) -> String {
         let mut xml = String::with_capacity(1024);
 
-        self.introspect_to_writer(&mut xml).await;
+        self.introspect_to_writer(&mut xml, virtual_children).await;
 
         xml
     }
@@ -386,6 +738,12 @@ impl Node {
         // Recursively get all properties of all interfaces of descendants.
         let mut node_list: Vec<_> = self.children.values().collect();
         while let Some(node) = node_list.pop() {
+            node_list.extend(node.children.values());
+            if node.is_empty() {
+                // Intermediate nodes are not objects.
+                continue;
+            }
+
             let mut interfaces = HashMap::new();
             for iface_name in node.interfaces.keys().filter(|n| {
                 // Filter standard interfaces.
@@ -398,7 +756,6 @@ impl Node {
                 interfaces.insert(iface_name.clone().into(), props);
             }
             managed_objects.insert(node.path.clone(), interfaces);
-            node_list.extend(node.children.values());
         }
 
         Ok(managed_objects)
@@ -417,6 +774,10 @@ impl Node {
     }
 }
 
+fn is_standard_interface(name: &InterfaceName<'_>) -> bool {
+    *name == Peer::name() || *name == Introspectable::name() || *name == Properties::name()
+}
+
 /// An object server, holding server-side D-Bus objects & interfaces.
 ///
 /// Object servers hold interfaces on various object paths, and expose them over D-Bus.
@@ -478,6 +839,12 @@ impl Node {
 pub struct ObjectServer {
     conn: WeakConnection,
     root: RwLock<Node>,
+    layers: RwLock<Vec<Layer>>,
+    credentials: CredentialsCache,
+    watchdog: RwLock<Option<Watchdog>>,
+    deadlines: RwLock<Deadlines>,
+    stats: Stats,
+    machine_id: RwLock<MachineIdSource>,
 }
 
 assert_impl_all!(ObjectServer: Send, Sync, Unpin);
@@ -488,6 +855,12 @@ impl ObjectServer {
         Self {
             conn: conn.into(),
             root: RwLock::new(Node::new("/".try_into().expect("zvariant bug"))),
+            layers: RwLock::new(vec![]),
+            credentials: CredentialsCache::default(),
+            watchdog: RwLock::new(None),
+            deadlines: RwLock::new(Deadlines::default()),
+            stats: Stats::default(),
+            machine_id: RwLock::new(MachineIdSource::default()),
         }
     }
 
@@ -522,184 +895,1378 @@ impl ObjectServer {
         Ok(added)
     }
 
-    /// Same as `at` but expects an interface already in `Arc<RwLock<dyn Interface>>` form.
+    /// Register a D-Bus [`Interface`] at a given path, until the returned [`Registration`] is
+    /// dropped.
     ///
-    /// Also, doesn't emit `InterfacesAdded` signal.
-    // FIXME: Better name?
-    pub(crate) async fn at_ready<'node, 'p, F>(
-        &'node self,
-        path: ObjectPath<'p>,
-        name: InterfaceName<'static>,
-        iface_creator: F,
-    ) -> Result<bool>
-    where
-        F: FnOnce() -> Arc<RwLock<dyn Interface + 'static>>,
-    {
-        let mut root = self.root().write().await;
-        let node = root.get_child_mut(&path, true).0;
-        let node = node.unwrap();
-        let added = node.at(name.clone(), iface_creator);
-
-        Ok(added)
-    }
-
-    pub(crate) async fn emit_object_manager_signals<'node, 'p>(
-        &'node self,
-        path: ObjectPath<'p>,
-        name: InterfaceName<'static>,
-    ) -> Result<()> {
-        let root = self.root().read().await;
-        let (node, manager_path) = root.get_child(&path, true);
-        let node = node.unwrap();
-
-        if name == ObjectManager::name() {
-            // Just added an object manager. Need to signal all managed objects under it.
-            let ctxt = SignalContext::new(&self.connection(), path)?;
-            let objects = node.get_managed_objects().await?;
-            for (path, owned_interfaces) in objects {
-                let interfaces = owned_interfaces
-                    .iter()
-                    .map(|(i, props)| {
-                        let props = props
-                            .iter()
-                            .map(|(k, v)| Ok((k.as_str(), Value::try_from(v)?)))
-                            .collect::<Result<_>>();
-                        Ok((i.into(), props?))
-                    })
-                    .collect::<Result<_>>()?;
-                ObjectManager::interfaces_added(&ctxt, &path, &interfaces).await?;
-            }
-        } else if let Some(manager_path) = manager_path {
-            let ctxt = SignalContext::new(&self.connection(), manager_path.clone())?;
-            let mut interfaces = HashMap::new();
-            let owned_props = node.get_properties(name.clone()).await?;
-            let props = owned_props
-                .iter()
-                .map(|(k, v)| Ok((k.as_str(), Value::try_from(v)?)))
-                .collect::<Result<_>>()?;
-            interfaces.insert(name, props);
-
-            ObjectManager::interfaces_added(&ctxt, &path, &interfaces).await?;
-        }
-
-        Ok(())
-    }
-
-    /// Unregister a D-Bus [`Interface`] at a given path.
+    /// This is the same as [`ObjectServer::at`], except that the interface doesn't outlive the
+    /// returned guard, so that temporarily exported objects can't be left behind on early returns.
     ///
-    /// If there are no more interfaces left at that path, destroys the object as well.
-    /// Returns whether the object was destroyed.
-    pub async fn remove<'p, I, P>(&self, path: P) -> Result<bool>
+    /// If the interface already exists at this path, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, Connection};
+    ///
+    /// struct Transfer {
+    ///     progress: u8,
+    /// }
+    ///
+    /// #[interface(name = "org.example.Transfer")]
+    /// impl Transfer {
+    ///     #[zbus(property)]
+    ///     fn progress(&self) -> u8 {
+    ///         self.progress
+    ///     }
+    /// }
+    ///
+    /// async fn transfer(connection: &Connection) -> zbus::Result<()> {
+    ///     let _registration = connection
+    ///         .object_server()
+    ///         .at_scoped("/org/example/Transfer/1", Transfer { progress: 0 })
+    ///         .await?
+    ///         .expect("transfer already exported");
+    ///
+    ///     // Transfer the data, returning early on errors. The object is unexported either way.
+    ///     # Ok(())
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// transfer(&connection).await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn at_scoped<'p, P, I>(&self, path: P, iface: I) -> Result<Option<Registration>>
     where
         I: Interface,
         P: TryInto<ObjectPath<'p>>,
         P::Error: Into<Error>,
     {
         let path = path.try_into().map_err(Into::into)?;
-        let mut root = self.root.write().await;
-        let (node, manager_path) = root.get_child_mut(&path, false);
-        let node = node.ok_or(Error::InterfaceNotFound)?;
-        if !node.remove_interface(I::name()) {
-            return Err(Error::InterfaceNotFound);
-        }
-        if let Some(manager_path) = manager_path {
-            let ctxt = SignalContext::new(&self.connection(), manager_path.clone())?;
-            ObjectManager::interfaces_removed(&ctxt, &path, &[I::name()]).await?;
-        }
-        if node.is_empty() {
-            let mut path_parts = path.rsplit('/').filter(|i| !i.is_empty());
-            let last_part = path_parts.next().unwrap();
-            let ppath = ObjectPath::from_string_unchecked(
-                path_parts.fold(String::new(), |a, p| format!("/{p}{a}")),
-            );
-            root.get_child_mut(&ppath, false)
-                .0
-                .unwrap()
-                .remove_node(last_part);
-            return Ok(true);
+        let instance: Arc<RwLock<dyn Interface>> = Arc::new(RwLock::new(iface));
+        let added = self
+            .at_ready(path.clone(), I::name(), || instance.clone())
+            .await?;
+        if !added {
+            return Ok(None);
         }
-        Ok(false)
+        let registration =
+            Registration::new(&self.connection(), path.clone(), I::name(), &instance);
+        self.emit_object_manager_signals(path, I::name()).await?;
+
+        Ok(Some(registration))
     }
 
-    /// Get the interface at the given path.
+    /// Replace the implementation of a D-Bus [`Interface`] at a given path.
     ///
-    /// # Errors
+    /// The swap is atomic: method calls are dispatched either to the previous implementation or to
+    /// `iface`, and none fails for the object being missing in between. Calls already being
+    /// handled by the previous implementation are left to complete.
     ///
-    /// If the interface is not registered at the given path, `Error::InterfaceNotFound` error is
-    /// returned.
+    /// A `PropertiesChanged` signal is then emitted for the properties of `iface` whose values
+    /// differ from the ones of the previous implementation, as declared by their
+    /// `emits_changed_signal` attribute.
     ///
-    /// # Examples
+    /// Returns whether an implementation was replaced. If there was none, `iface` is registered
+    /// just like [`ObjectServer::at`] does.
     ///
-    /// The typical use of this is property changes outside of a dispatched handler:
+    /// # Example
     ///
     /// ```no_run
     /// # use std::error::Error;
-    /// # use zbus::{Connection, interface};
-    /// # use async_io::block_on;
-    /// #
-    /// struct MyIface(u32);
+    /// use zbus::{interface, Connection};
     ///
-    /// #[interface(name = "org.myiface.MyIface")]
-    /// impl MyIface {
-    ///      #[zbus(property)]
-    ///      async fn count(&self) -> u32 {
-    ///          self.0
-    ///      }
+    /// struct Backend {
+    ///     version: u32,
     /// }
     ///
-    /// # block_on(async {
-    /// # let connection = Connection::session().await?;
-    /// #
-    /// # let path = "/org/zbus/path";
-    /// # connection.object_server().at(path, MyIface(0)).await?;
-    /// let iface_ref = connection
-    ///     .object_server()
+    /// #[interface(name = "org.example.Backend")]
+    /// impl Backend {
+    ///     #[zbus(property)]
+    ///     fn version(&self) -> u32 {
+    ///         self.version
+    ///     }
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server
+    ///     .at("/org/example/Backend", Backend { version: 1 })
+    ///     .await?;
+    ///
+    /// // Later, after an upgrade. This emits `PropertiesChanged` for `Version`.
+    /// object_server
+    ///     .replace_at("/org/example/Backend", Backend { version: 2 })
+    ///     .await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn replace_at<'p, P, I>(&self, path: P, iface: I) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        // Gather the new properties upfront, while `iface` isn't shared yet.
+        let mut props = iface.get_all().await?;
+        let emits: HashMap<String, EmitsChangedSignal> = props
+            .keys()
+            .map(|name| (name.clone(), iface.emits_changed_signal(name)))
+            .collect();
+        let instance: Arc<RwLock<dyn Interface>> = Arc::new(RwLock::new(iface));
+
+        let previous = {
+            let mut root = self.root.write().await;
+            let node = root.get_child_mut(&path, true).0.unwrap();
+
+            node.replace(I::name(), instance)
+        };
+        let Some(previous) = previous else {
+            self.emit_object_manager_signals(path, I::name()).await?;
+
+            return Ok(false);
+        };
+
+        let previous_props = previous
+            .instance
+            .read()
+            .await
+            .get_all()
+            .await
+            .unwrap_or_default();
+        let mut changes = PropertyChanges::default();
+        for (name, emits) in &emits {
+            let Some(value) = props.remove(name) else {
+                continue;
+            };
+            if previous_props.get(name) == Some(&value) {
+                continue;
+            }
+            match emits {
+                EmitsChangedSignal::True => changes.changed.push((name, value)),
+                EmitsChangedSignal::Invalidates => changes.invalidated.push(name),
+                EmitsChangedSignal::False | EmitsChangedSignal::Const => (),
+            }
+        }
+        let ctxt = SignalContext::new(&self.connection(), path)?;
+        changes.emit(&ctxt, I::name()).await?;
+
+        Ok(true)
+    }
+
+    /// Serve the interface `I` registered at `path` at `alias` as well.
+    ///
+    /// Both paths then share the same instance of the interface, so a method call made through
+    /// either of them sees the changes made through the other. This is useful for services that
+    /// need to keep serving a legacy path while migrating to a new one. Each path remains a
+    /// distinct object though: signals emitted through an [`InterfaceRef`] or a [`SignalContext`]
+    /// are only emitted from the path they were created for, and so are the `PropertiesChanged`
+    /// signals. Removing or replacing the interface at one of the paths doesn't affect the other.
+    ///
+    /// Returns `false` if `I` is already registered at `alias`.
+    ///
+    /// # Errors
+    ///
+    /// If `I` isn't registered at `path`, `Error::InterfaceNotFound` error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, Connection};
+    ///
+    /// struct Player;
+    ///
+    /// #[interface(name = "org.example.Player")]
+    /// impl Player {
+    ///     fn play(&self) {}
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server.at("/org/example/Player", Player).await?;
+    /// // Older clients still use the previous path.
+    /// object_server
+    ///     .alias::<Player, _, _>("/org/example/Player", "/com/example/Player")
+    ///     .await?;
+    ///
+    /// // Emit signals from the legacy path as well.
+    /// let legacy = object_server
+    ///     .interface::<_, Player>("/com/example/Player")
+    ///     .await?;
+    /// # let _ = legacy;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn alias<'p, 'a, I, P, A>(&self, path: P, alias: A) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        A: TryInto<ObjectPath<'a>>,
+        A::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let alias = alias.try_into().map_err(Into::into)?;
+        let added = {
+            let mut root = self.root.write().await;
+            let iface = root
+                .get_child(&path, false)
+                .0
+                .and_then(|node| node.interfaces.get(&I::name()).cloned())
+                .ok_or(Error::InterfaceNotFound)?;
+            let node = root.get_child_mut(&alias, true).0.unwrap();
+            match node.interfaces.entry(I::name()) {
+                Entry::Vacant(e) => {
+                    e.insert(iface);
+
+                    true
+                }
+                Entry::Occupied(_) => false,
+            }
+        };
+        if added {
+            self.emit_object_manager_signals(alias, I::name()).await?;
+        }
+
+        Ok(added)
+    }
+
+    /// Register all the interfaces of `object` at a given path.
+    ///
+    /// This is the same as calling [`ObjectServer::at`] for each interface, except that the
+    /// interfaces are all registered at once: method calls are never dispatched to a partially
+    /// registered object. If any of the interfaces already exists at this path, none is registered
+    /// and `false` is returned.
+    pub async fn at_object<'p, P>(&self, path: P, object: Object) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let names: Vec<_> = object.interface_names().cloned().collect();
+        {
+            let mut root = self.root.write().await;
+            let node = root.get_child_mut(&path, true).0.unwrap();
+            if names.iter().any(|name| node.interfaces.contains_key(name)) {
+                return Ok(false);
+            }
+            for (name, instance) in object.interfaces {
+                node.interfaces.insert(name, ArcInterface::new(instance));
+            }
+        }
+        for name in names {
+            self.emit_object_manager_signals(path.clone(), name).await?;
+        }
+
+        Ok(true)
+    }
+
+    /// Register a [`DynamicInterface`] at a given path.
+    ///
+    /// This is the same as [`ObjectServer::at`], except that the interface is defined at runtime.
+    ///
+    /// If an interface of the same name already exists at this path, returns false.
+    pub async fn at_dynamic<'p, P>(&self, path: P, iface: DynamicInterface) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let name = iface.name().clone();
+        let added = self
+            .at_ready(path.clone(), name.clone(), move || {
+                Arc::new(RwLock::new(DynamicInterfaceImpl(iface)))
+            })
+            .await?;
+        if added {
+            self.emit_object_manager_signals(path, name).await?;
+        }
+
+        Ok(added)
+    }
+
+    /// Serve an [`InterfaceShim`] at a given path.
+    ///
+    /// The target interface of the shim must already be registered at `path`. The shim is bound
+    /// to its current instance, so if the target is later replaced (e.g through
+    /// [`ObjectServer::replace_at`]), the shim has to be registered again.
+    ///
+    /// If an interface of the same name as the shim already exists at this path, returns false.
+    ///
+    /// # Errors
+    ///
+    /// If the target interface isn't registered at `path`, `Error::InterfaceNotFound` error is
+    /// returned. If it doesn't have one of the members the shim maps to, `Error::Failure` error is.
+    pub async fn at_shim<'p, P>(&self, path: P, shim: InterfaceShim) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let target = {
+            let root = self.root.read().await;
+            root.interface_lock_at(&path, shim.target().as_ref())
+                .ok_or(Error::InterfaceNotFound)?
+        };
+        let shim = {
+            let iface = target.read().await;
+            InterfaceShimImpl::new(shim, target.clone(), &*iface)?
+        };
+        let name = shim.name().clone();
+        let added = self
+            .at_ready(path.clone(), name.clone(), move || {
+                Arc::new(RwLock::new(shim))
+            })
+            .await?;
+        if added {
+            self.emit_object_manager_signals(path, name).await?;
+        }
+
+        Ok(added)
+    }
+
+    /// Same as `at` but expects an interface already in `Arc<RwLock<dyn Interface>>` form.
+    ///
+    /// Also, doesn't emit `InterfacesAdded` signal.
+    // FIXME: Better name?
+    pub(crate) async fn at_ready<'node, 'p, F>(
+        &'node self,
+        path: ObjectPath<'p>,
+        name: InterfaceName<'static>,
+        iface_creator: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce() -> Arc<RwLock<dyn Interface + 'static>>,
+    {
+        let mut root = self.root().write().await;
+        let node = root.get_child_mut(&path, true).0;
+        let node = node.unwrap();
+        let added = node.at(name.clone(), iface_creator);
+
+        Ok(added)
+    }
+
+    pub(crate) async fn emit_object_manager_signals<'node, 'p>(
+        &'node self,
+        path: ObjectPath<'p>,
+        name: InterfaceName<'static>,
+    ) -> Result<()> {
+        let root = self.root().read().await;
+        let (node, manager_path) = root.get_child(&path, true);
+        let node = node.unwrap();
+
+        if name == ObjectManager::name() {
+            // Just added an object manager. Need to signal all managed objects under it.
+            let ctxt = SignalContext::new(&self.connection(), path)?;
+            let objects = node.get_managed_objects().await?;
+            for (path, owned_interfaces) in objects {
+                let interfaces = owned_interfaces
+                    .iter()
+                    .map(|(i, props)| {
+                        let props = props
+                            .iter()
+                            .map(|(k, v)| Ok((k.as_str(), Value::try_from(v)?)))
+                            .collect::<Result<_>>();
+                        Ok((i.into(), props?))
+                    })
+                    .collect::<Result<_>>()?;
+                ObjectManager::interfaces_added(&ctxt, &path, &interfaces).await?;
+            }
+        } else if let Some(manager_path) = manager_path {
+            let ctxt = SignalContext::new(&self.connection(), manager_path.clone())?;
+            let mut interfaces = HashMap::new();
+            let owned_props = node.get_properties(name.clone()).await?;
+            let props = owned_props
+                .iter()
+                .map(|(k, v)| Ok((k.as_str(), Value::try_from(v)?)))
+                .collect::<Result<_>>()?;
+            interfaces.insert(name, props);
+
+            ObjectManager::interfaces_added(&ctxt, &path, &interfaces).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Unregister a D-Bus [`Interface`] at a given path.
+    ///
+    /// If there are no more interfaces left at that path, destroys the object as well. Objects
+    /// registered under that path, if any, are kept. Returns whether the object was destroyed.
+    pub async fn remove<'p, I, P>(&self, path: P) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+
+        self.remove_by_name(path, I::name()).await
+    }
+
+    /// Unregister a [`DynamicInterface`] (or any other interface) by name at a given path.
+    ///
+    /// This is the same as [`ObjectServer::remove`], except that the interface is specified by
+    /// name.
+    pub async fn remove_dynamic<'p, 'i, P, N>(&self, path: P, name: N) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let name = name.try_into().map_err(Into::into)?;
+
+        self.remove_by_name(path, name.into_owned()).await
+    }
+
+    async fn remove_by_name(
+        &self,
+        path: ObjectPath<'_>,
+        name: InterfaceName<'static>,
+    ) -> Result<bool> {
+        self.remove_matching(path, name, |_| true).await
+    }
+
+    /// Unregister the interface `name` at `path`, if `is_match` returns true for it.
+    pub(crate) async fn remove_matching<F>(
+        &self,
+        path: ObjectPath<'_>,
+        name: InterfaceName<'static>,
+        is_match: F,
+    ) -> Result<bool>
+    where
+        F: FnOnce(&ArcInterface) -> bool,
+    {
+        let mut root = self.root.write().await;
+        let (node, manager_path) = root.get_child_mut(&path, false);
+        let node = node.ok_or(Error::InterfaceNotFound)?;
+        if !node.interfaces.get(&name).is_some_and(is_match) {
+            return Err(Error::InterfaceNotFound);
+        }
+        node.remove_interface(name.clone());
+        if let Some(manager_path) = manager_path {
+            let ctxt = SignalContext::new(&self.connection(), manager_path.clone())?;
+            ObjectManager::interfaces_removed(&ctxt, &path, &[name]).await?;
+        }
+
+        Ok(root.remove_if_empty(&path))
+    }
+
+    /// Unregister all the interfaces at a given path, after waiting for their in-flight method
+    /// calls to complete.
+    ///
+    /// Unlike [`ObjectServer::remove`], which may drop an interface while some of its method calls
+    /// are still being handled, this method first stops dispatching new calls to the object. It
+    /// then waits, for at most `timeout`, for the calls that are already being handled to complete.
+    /// Only then is the `InterfacesRemoved` signal emitted, and the interfaces dropped. Objects
+    /// registered under that path, if any, are kept.
+    ///
+    /// Returns whether all the in-flight calls completed within `timeout`. The object is removed
+    /// regardless.
+    ///
+    /// # Errors
+    ///
+    /// If there is no object at the given path, `Error::InterfaceNotFound` error is returned.
+    pub async fn remove_graceful<'p, P>(&self, path: P, timeout: Duration) -> Result<bool>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        // Detach the interfaces first, so no new calls are dispatched to them.
+        let (ifaces, manager_path) = {
+            let mut root = self.root.write().await;
+            let (node, manager_path) = root.get_child_mut(&path, false);
+            let manager_path = manager_path.map(ObjectPath::into_owned);
+            let node = node.ok_or(Error::InterfaceNotFound)?;
+            let names: Vec<_> = node
+                .interfaces
+                .keys()
+                .filter(|name| !is_standard_interface(name))
+                .cloned()
+                .collect();
+            if names.is_empty() {
+                return Err(Error::InterfaceNotFound);
+            }
+            let ifaces: Vec<_> = names
+                .into_iter()
+                .filter_map(|name| node.interfaces.remove(&name).map(|iface| (name, iface)))
+                .collect();
+            root.remove_if_empty(&path);
+
+            (ifaces, manager_path)
+        };
+
+        let drain = async {
+            for (_, iface) in &ifaces {
+                iface.calls.drained().await;
+            }
+        };
+        let timer = self.connection().executor().timer(timeout);
+        let drained = match select(pin!(drain), timer).await {
+            Either::Left(_) => true,
+            Either::Right(_) => {
+                debug!("Timed out waiting for in-flight calls on `{path}` to complete");
+
+                false
+            }
+        };
+
+        if let Some(manager_path) = manager_path {
+            let ctxt = SignalContext::new(&self.connection(), manager_path)?;
+            let names: Vec<_> = ifaces.iter().map(|(name, _)| name.clone()).collect();
+            ObjectManager::interfaces_removed(&ctxt, &path, &names).await?;
+        }
+
+        Ok(drained)
+    }
+
+    /// Register a fallback D-Bus [`Interface`] for a path and all paths under it.
+    ///
+    /// This allows a single interface instance to serve a whole subtree, e.g all the objects under
+    /// `/org/example/items`, which is useful for services exposing a lot of dynamic objects,
+    /// without having to register each one of them. The concrete object path of each call is
+    /// available to the method implementations through the `#[zbus(header)]` argument.
+    ///
+    /// Interfaces registered with [`ObjectServer::at`] on a specific path take precedence over
+    /// fallback interfaces. When multiple fallback interfaces of the same name cover a path, the
+    /// one registered closest to it is used. The standard interfaces, such as
+    /// `org.freedesktop.DBus.Properties`, are also served on all the paths covered by a fallback.
+    ///
+    /// Note that no `InterfacesAdded` signals are emitted for the objects served by fallback
+    /// interfaces, nor are they listed as child nodes in the introspection data.
+    ///
+    /// If a fallback interface of the same name already exists at this path, returns false.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, message::Header, Connection};
+    ///
+    /// struct Items;
+    ///
+    /// #[interface(name = "org.example.Item")]
+    /// impl Items {
+    ///     fn name(&self, #[zbus(header)] header: Header<'_>) -> String {
+    ///         // `/org/example/items/42` -> `42`
+    ///         let path = header.path().unwrap();
+    ///         path.rsplit('/').next().unwrap().to_string()
+    ///     }
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// connection
+    ///     .object_server()
+    ///     .at_fallback("/org/example/items", Items)
+    ///     .await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn at_fallback<'p, P, I>(&self, path: P, iface: I) -> Result<bool>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut root = self.root().write().await;
+        let node = root.get_child_mut(&path, true).0.unwrap();
+        let added = match node.fallbacks.entry(I::name()) {
+            Entry::Vacant(e) => {
+                e.insert(ArcInterface::new(Arc::new(RwLock::new(iface))));
+
+                true
+            }
+            Entry::Occupied(_) => false,
+        };
+
+        Ok(added)
+    }
+
+    /// Unregister a fallback D-Bus [`Interface`] registered with [`ObjectServer::at_fallback`].
+    ///
+    /// Returns an `Error::InterfaceNotFound` error if there was no such fallback interface.
+    pub async fn remove_fallback<'p, I, P>(&self, path: P) -> Result<()>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut root = self.root.write().await;
+        let node = root
+            .get_child_mut(&path, false)
+            .0
+            .ok_or(Error::InterfaceNotFound)?;
+        node.fallbacks
+            .remove(&I::name())
+            .map(|_| ())
+            .ok_or(Error::InterfaceNotFound)
+    }
+
+    /// Get the interface at the given path.
+    ///
+    /// # Errors
+    ///
+    /// If the interface is not registered at the given path, `Error::InterfaceNotFound` error is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// The typical use of this is property changes outside of a dispatched handler:
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use zbus::{Connection, interface};
+    /// # use async_io::block_on;
+    /// #
+    /// struct MyIface(u32);
+    ///
+    /// #[interface(name = "org.myiface.MyIface")]
+    /// impl MyIface {
+    ///      #[zbus(property)]
+    ///      async fn count(&self) -> u32 {
+    ///          self.0
+    ///      }
+    /// }
+    ///
+    /// # block_on(async {
+    /// # let connection = Connection::session().await?;
+    /// #
+    /// # let path = "/org/zbus/path";
+    /// # connection.object_server().at(path, MyIface(0)).await?;
+    /// let iface_ref = connection
+    ///     .object_server()
     ///     .interface::<_, MyIface>(path).await?;
     /// let mut iface = iface_ref.get_mut().await;
     /// iface.0 = 42;
     /// iface.count_changed(iface_ref.signal_context()).await?;
     /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
     /// # })?;
-    /// #
+    /// #
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn interface<'p, P, I>(&self, path: P) -> Result<InterfaceRef<I>>
+    where
+        I: Interface,
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let root = self.root().read().await;
+        let node = root
+            .get_child(&path, false)
+            .0
+            .ok_or(Error::InterfaceNotFound)?;
+
+        let lock = node
+            .interface_lock(I::name())
+            .ok_or(Error::InterfaceNotFound)?
+            .clone();
+
+        // Ensure what we return can later be dowcasted safely.
+        lock.read()
+            .await
+            .downcast_ref::<I>()
+            .ok_or(Error::InterfaceNotFound)?;
+
+        let conn = self.connection();
+        // SAFETY: We know that there is a valid path on the node as we already converted w/o error.
+        let ctxt = SignalContext::new(&conn, path).unwrap().into_owned();
+
+        Ok(InterfaceRef {
+            ctxt,
+            lock,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Emit `PropertiesChanged` signals for the given properties of all the interfaces at `path`.
+    ///
+    /// This is meant for objects serving several versions of an interface (e.g
+    /// `org.example.Foo1` and `org.example.Foo2`) backed by the same state. After changing the
+    /// state, call this method with the names of the changed properties, and each interface
+    /// having any of them emits a single signal with their current values. For an
+    /// [`InterfaceShim`], the names are the ones of the properties of its target interface, and
+    /// the signal uses the names of the shim. Other interfaces name their properties
+    /// independently, so a property renamed in a separately implemented version can be signaled
+    /// by passing both names.
+    ///
+    /// Just like [`InterfaceRef::properties_changed`], this honors the
+    /// [`EmitsChangedSignal`] declaration of each property.
+    ///
+    /// # Errors
+    ///
+    /// If no interface at `path` has one of the properties, [`fdo::Error::UnknownProperty`] error
+    /// is returned, and if any is declared as [`EmitsChangedSignal::Const`], [`Error::Failure`]
+    /// is. In both cases, no signal is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, object_server::InterfaceShim, Connection};
+    ///
+    /// struct Thermostat {
+    ///     celsius: f64,
+    /// }
+    ///
+    /// #[interface(name = "org.example.Thermostat2")]
+    /// impl Thermostat {
+    ///     #[zbus(property)]
+    ///     fn celsius(&self) -> f64 {
+    ///         self.celsius
+    ///     }
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// let path = "/org/example/Thermostat";
+    /// object_server.at(path, Thermostat { celsius: 20. }).await?;
+    /// let shim = InterfaceShim::new("org.example.Thermostat1", "org.example.Thermostat2")?
+    ///     .property("Temperature", "Celsius")?;
+    /// object_server.at_shim(path, shim).await?;
+    ///
+    /// object_server
+    ///     .interface::<_, Thermostat>(path)
+    ///     .await?
+    ///     .get_mut()
+    ///     .await
+    ///     .celsius = 21.5;
+    /// // Signals `Celsius` on `org.example.Thermostat2` and `Temperature` on
+    /// // `org.example.Thermostat1`.
+    /// object_server.properties_changed(path, &["Celsius"]).await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn properties_changed<'p, P>(&self, path: P, property_names: &[&str]) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let ifaces: Vec<_> = {
+            let root = self.root.read().await;
+            let node = root
+                .get_child(&path, false)
+                .0
+                .ok_or(Error::InterfaceNotFound)?;
+            node.interfaces
+                .iter()
+                .filter(|(name, _)| !is_standard_interface(name))
+                .map(|(name, iface)| (name.clone(), iface.instance.clone()))
+                .collect()
+        };
+
+        // Shims name the properties of their target differently.
+        let mut queries = Vec::with_capacity(ifaces.len());
+        for (iface_name, iface) in ifaces {
+            let names: Vec<(usize, String)> = {
+                let iface = iface.read().await;
+                let shim = iface.downcast_ref::<InterfaceShimImpl>();
+                property_names
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, name)| match shim {
+                        Some(shim) => Some((i, shim.property_for_target(name)?.to_string())),
+                        None => Some((i, name.to_string())),
+                    })
+                    .collect()
+            };
+            queries.push((iface_name, iface, names));
+        }
+
+        let mut found = vec![false; property_names.len()];
+        let mut signals = vec![];
+        for (iface_name, iface, names) in &queries {
+            let iface = iface.read().await;
+            let mut changes = PropertyChanges::default();
+            for (i, name) in names {
+                if let Some(res) = changes.add(&*iface, name).await {
+                    res?;
+                    found[*i] = true;
+                }
+            }
+            signals.push((iface_name.clone(), changes));
+        }
+        if let Some((name, _)) = property_names
+            .iter()
+            .zip(&found)
+            .find(|(_, found)| !**found)
+        {
+            return Err(fdo::Error::UnknownProperty(format!("Unknown property '{name}'")).into());
+        }
+
+        let conn = self.connection();
+        let ctxt = SignalContext::new(&conn, path)?;
+        for (iface_name, changes) in signals {
+            changes.emit(&ctxt, iface_name).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The paths of all the objects currently served, sorted.
+    ///
+    /// Intermediate nodes, i.e paths with no interfaces other than the standard ones, are not
+    /// included. Neither are the paths covered by fallback interfaces.
+    pub async fn paths(&self) -> Vec<OwnedObjectPath> {
+        let root = self.root.read().await;
+        let mut paths = vec![];
+        let mut node_list = vec![&*root];
+        while let Some(node) = node_list.pop() {
+            node_list.extend(node.children.values());
+            if node.interfaces.keys().any(|k| !is_standard_interface(k)) {
+                paths.push(node.path.clone());
+            }
+        }
+        paths.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        paths
+    }
+
+    /// The names of all the interfaces served at the given path, sorted.
+    ///
+    /// This includes the standard interfaces, such as `org.freedesktop.DBus.Properties`, and the
+    /// fallback interfaces covering the path. Returns an empty list if nothing is served at the
+    /// given path, not even the standard interfaces.
+    pub async fn interfaces_at<'p, P>(&self, path: P) -> Result<Vec<OwnedInterfaceName>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let root = self.root.read().await;
+        let fallback_node = root.fallback_node(&path);
+        let nodes = root
+            .get_child(&path, false)
+            .0
+            .into_iter()
+            .chain(&fallback_node);
+        let mut names: Vec<OwnedInterfaceName> = nodes
+            .flat_map(|node| node.interfaces.keys())
+            .map(|name| name.clone().into())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        Ok(names)
+    }
+
+    /// The introspection data of the interface `name` served at `path`, as a [`zbus_xml`] model.
+    ///
+    /// This is the data that `org.freedesktop.DBus.Introspectable.Introspect` returns for the
+    /// interface, typed, e.g. to document the API from within the service or to compare it in
+    /// tests. Fallback interfaces covering `path` are taken into account. Returns
+    /// [`Error::InterfaceNotFound`] if the interface isn't served at `path`.
+    ///
+    /// Only available with the `xml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use zbus::{block_on, interface, Connection};
+    /// #
+    /// struct Greeter;
+    ///
+    /// #[interface(name = "org.zbus.Greeter")]
+    /// impl Greeter {
+    ///     fn say_hello(&self, name: &str) -> String {
+    ///         format!("Hello {name}!")
+    ///     }
+    /// }
+    ///
+    /// # block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let server = connection.object_server();
+    /// server.at("/org/zbus/Greeter", Greeter).await?;
+    ///
+    /// let iface = server
+    ///     .introspect_interface("/org/zbus/Greeter", "org.zbus.Greeter")
+    ///     .await?;
+    /// assert_eq!(iface.methods()[0].name(), "SayHello");
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    #[cfg(feature = "xml")]
+    pub async fn introspect_interface<'p, 'i, P, N>(
+        &self,
+        path: P,
+        name: N,
+    ) -> Result<zbus_xml::Interface<'static>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        N: TryInto<InterfaceName<'i>>,
+        N::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let name = name.try_into().map_err(Into::into)?;
+        let lock = self
+            .root()
+            .read()
+            .await
+            .interface_lock_at(&path, name.as_ref())
+            .ok_or(Error::InterfaceNotFound)?;
+
+        // The introspection data of an interface is a fragment of a node.
+        let mut xml = String::from("<node>\n");
+        lock.read().await.introspect_to_writer(&mut xml, 2);
+        xml.push_str("</node>\n");
+        let node = zbus_xml::Node::from_reader(xml.as_bytes()).map_err(|e| {
+            Error::Failure(format!(
+                "Failed to parse the introspection data of `{name}`: {e}"
+            ))
+        })?;
+
+        node.interfaces()
+            .first()
+            .cloned()
+            .ok_or_else(|| Error::Failure(format!("No introspection data for interface `{name}`")))
+    }
+
+    /// The introspection data of all the interfaces served at `path`, sorted by name.
+    ///
+    /// See [`ObjectServer::introspect_interface`] and [`ObjectServer::interfaces_at`] for
+    /// details. Only available with the `xml` feature.
+    #[cfg(feature = "xml")]
+    pub async fn introspect_interfaces_at<'p, P>(
+        &self,
+        path: P,
+    ) -> Result<Vec<zbus_xml::Interface<'static>>>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut interfaces = vec![];
+        for name in self.interfaces_at(path.as_ref()).await? {
+            interfaces.push(
+                self.introspect_interface(path.as_ref(), name.inner())
+                    .await?,
+            );
+        }
+
+        Ok(interfaces)
+    }
+
+    /// Register a middleware wrapping the dispatch of the method calls in the given `scope`.
+    ///
+    /// See [`Middleware`] for details.
+    pub async fn add_middleware<M>(&self, scope: MiddlewareScope, middleware: M)
+    where
+        M: Middleware,
+    {
+        self.layers.write().await.push(Layer {
+            scope,
+            middleware: Arc::new(middleware),
+        });
+    }
+
+    /// Mount a tower stack handling the method calls in the given `scope`.
+    ///
+    /// This makes the layers of the tower ecosystem, e.g load shedding or concurrency limits,
+    /// available to D-Bus services. The innermost service is usually a [`DispatchService`], which
+    /// dispatches the method calls to the interfaces. The service is cloned for each call, and
+    /// called once it's ready.
+    ///
+    /// The stack is run as a [`Middleware`], in the order of registration, but it handles the
+    /// method calls instead of the middleware registered after it. Errors from the stack are
+    /// replied to the caller: [`Error::FDO`] and [`fdo::Error`] errors as is, and the other ones
+    /// as an [`fdo::Error::Failed`] error with their description.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use tower::ServiceBuilder;
+    /// use zbus::{
+    ///     object_server::{DispatchService, MiddlewareScope},
+    ///     Connection,
+    /// };
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// // Reject the method calls beyond 16 concurrent ones.
+    /// let stack = ServiceBuilder::new()
+    ///     .load_shed()
+    ///     .concurrency_limit(16)
+    ///     .service(DispatchService::new(&connection));
+    /// connection
+    ///     .object_server()
+    ///     .add_service(MiddlewareScope::All, stack)
+    ///     .await;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    #[cfg(feature = "tower")]
+    pub async fn add_service<S>(&self, scope: MiddlewareScope, service: S)
+    where
+        S: tower_service::Service<Message, Response = ()> + Clone + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        S::Future: Send,
+    {
+        self.add_middleware(scope, ServiceMiddleware::new(service))
+            .await;
+    }
+
+    /// Limit the number of method calls to the object at the given path being handled at the same
+    /// time.
+    ///
+    /// Method calls received while `max` calls are already being handled are replied to with an
+    /// [`fdo::Error::LimitsExceeded`] error. This protects a busy service from a single object
+    /// taking up all of its resources. Pass `None` to remove the limit.
+    ///
+    /// # Errors
+    ///
+    /// If there is no object at the given path, `Error::InterfaceNotFound` error is returned.
+    pub async fn set_dispatch_limit<'p, P>(&self, path: P, max: Option<usize>) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut root = self.root.write().await;
+        let node = root
+            .get_child_mut(&path, false)
+            .0
+            .ok_or(Error::InterfaceNotFound)?;
+        node.dispatch_limit = max.map(|max| Arc::new(DispatchLimit::new(max)));
+
+        Ok(())
+    }
+
+    /// Attach extra content to the introspection data of the object at the given path.
+    ///
+    /// The introspection data is generated from the interfaces served at each path. This allows
+    /// including information that can't be derived from them, such as annotations, or child nodes
+    /// that are created on demand (e.g those served by a fallback interface). The `xml` is
+    /// inserted as is, after the interfaces of the node, so it must be a valid fragment of the
+    /// [introspection format]. Pass `None` to remove the extra content.
+    ///
+    /// # Errors
+    ///
+    /// If there is no object at the given path, `Error::InterfaceNotFound` error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{object_server::ObjectManager, Connection};
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server.at("/org/example/items", ObjectManager).await?;
+    /// object_server
+    ///     .set_extra_introspection(
+    ///         "/org/example/items",
+    ///         Some(r#"<node name="42"/>"#.to_string()),
+    ///     )
+    ///     .await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    ///
+    /// [introspection format]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
+    pub async fn set_extra_introspection<'p, P>(&self, path: P, xml: Option<String>) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut root = self.root.write().await;
+        let node = root
+            .get_child_mut(&path, false)
+            .0
+            .ok_or(Error::InterfaceNotFound)?;
+        node.extra_introspection = xml;
+
+        Ok(())
+    }
+
+    /// List child nodes in the introspection data of the objects created on demand.
+    ///
+    /// The introspection data of an object lists the objects registered under it as child nodes,
+    /// so that the whole tree can be explored. The objects served by a fallback interface (see
+    /// [`ObjectServer::at_fallback`]) don't exist until they're called though. This sets
+    /// `children` to list the names of the child nodes of the object at the given path, and of
+    /// all the paths under it, which is called with the path being introspected. The closest
+    /// one applies when several paths have one. Any previously set function is replaced.
+    ///
+    /// The names must be valid path elements, and those of registered objects are listed anyway.
+    ///
+    /// # Errors
+    ///
+    /// If there is no object at the given path, `Error::InterfaceNotFound` error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{interface, Connection};
+    ///
+    /// struct Item;
+    ///
+    /// #[interface(name = "org.example.Item")]
+    /// impl Item {
+    ///     fn open(&self) {}
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server.at_fallback("/org/example/items", Item).await?;
+    /// object_server
+    ///     .set_virtual_children("/org/example/items", |path| {
+    ///         if path.as_str() == "/org/example/items" {
+    ///             (0..3).map(|i| i.to_string()).collect()
+    ///         } else {
+    ///             vec![]
+    ///         }
+    ///     })
+    ///     .await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn set_virtual_children<'p, P, F>(&self, path: P, children: F) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+        F: Fn(&ObjectPath<'_>) -> Vec<String> + Send + Sync + 'static,
+    {
+        self.replace_virtual_children(path, Some(VirtualChildren(Box::new(children))))
+            .await
+    }
+
+    /// Stop listing child nodes set with [`ObjectServer::set_virtual_children`].
+    ///
+    /// # Errors
+    ///
+    /// If there is no object at the given path, `Error::InterfaceNotFound` error is returned.
+    pub async fn remove_virtual_children<'p, P>(&self, path: P) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        self.replace_virtual_children(path, None).await
+    }
+
+    async fn replace_virtual_children<'p, P>(
+        &self,
+        path: P,
+        children: Option<VirtualChildren>,
+    ) -> Result<()>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        P::Error: Into<Error>,
+    {
+        let path = path.try_into().map_err(Into::into)?;
+        let mut root = self.root.write().await;
+        let node = root
+            .get_child_mut(&path, false)
+            .0
+            .ok_or(Error::InterfaceNotFound)?;
+        node.virtual_children = children;
+
+        Ok(())
+    }
+
+    /// Report the method calls taking longer than `threshold` to be handled.
+    ///
+    /// A warning is logged and `callback` is called for each method call still being handled after
+    /// `threshold`, which helps finding the handlers starving a busy service. The handlers are not
+    /// interrupted. Any previously set callback is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use std::time::Duration;
+    /// use zbus::Connection;
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// connection
+    ///     .object_server()
+    ///     .watch_slow_handlers(Duration::from_secs(1), |slow| {
+    ///         let hdr = slow.message().header();
+    ///         eprintln!(
+    ///             "`{}` on `{}` is taking more than {:?}",
+    ///             hdr.member().unwrap(),
+    ///             hdr.path().unwrap(),
+    ///             slow.elapsed(),
+    ///         );
+    ///     })
+    ///     .await;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
     /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
     /// ```
-    pub async fn interface<'p, P, I>(&self, path: P) -> Result<InterfaceRef<I>>
+    pub async fn watch_slow_handlers<F>(&self, threshold: Duration, callback: F)
     where
-        I: Interface,
-        P: TryInto<ObjectPath<'p>>,
-        P::Error: Into<Error>,
+        F: Fn(SlowHandler) + Send + Sync + 'static,
     {
-        let path = path.try_into().map_err(Into::into)?;
-        let root = self.root().read().await;
-        let node = root
-            .get_child(&path, false)
-            .0
-            .ok_or(Error::InterfaceNotFound)?;
+        *self.watchdog.write().await = Some(Watchdog::new(threshold, callback));
+    }
 
-        let lock = node
-            .interface_lock(I::name())
-            .ok_or(Error::InterfaceNotFound)?
-            .clone();
+    /// Set a deadline for handling the method calls to `interface`.
+    ///
+    /// Method calls still being handled after `deadline` are replied to with an
+    /// [`fdo::Error::TimedOut`] error, and their handlers are cancelled, i.e their future is
+    /// dropped at the point they're awaiting on. This keeps a stuck backend from leaving the
+    /// callers waiting until their own timeout. Beware that handlers with `&mut self` may be
+    /// cancelled halfway through modifying the interface. Pass `None` to remove the deadline.
+    ///
+    /// The deadline applies to the interface on all objects, and includes the time spent in
+    /// middleware (see [`ObjectServer::add_middleware`]).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use std::time::Duration;
+    /// use zbus::Connection;
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let object_server = connection.object_server();
+    /// object_server
+    ///     .set_deadline("org.example.Backend", Some(Duration::from_secs(5)))
+    ///     .await?;
+    /// // Except for this method, which is known to take longer.
+    /// object_server
+    ///     .set_method_deadline("org.example.Backend", "Rebuild", Some(Duration::from_secs(60)))
+    ///     .await?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn set_deadline<'i, I>(&self, interface: I, deadline: Option<Duration>) -> Result<()>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+        self.deadlines.write().await.set(interface, None, deadline);
 
-        // Ensure what we return can later be dowcasted safely.
-        lock.read()
+        Ok(())
+    }
+
+    /// Set a deadline for handling the calls to `method` of `interface`.
+    ///
+    /// This is the same as [`ObjectServer::set_deadline`], except that the deadline only applies
+    /// to `method`, and takes precedence over the deadline of the interface.
+    pub async fn set_method_deadline<'i, 'm, I, M>(
+        &self,
+        interface: I,
+        method: M,
+        deadline: Option<Duration>,
+    ) -> Result<()>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+    {
+        let interface = interface.try_into().map_err(Into::into)?;
+        let method = method.try_into().map_err(Into::into)?;
+        self.deadlines
+            .write()
             .await
-            .downcast_ref::<I>()
-            .ok_or(Error::InterfaceNotFound)?;
+            .set(interface, Some(method), deadline);
 
-        let conn = self.connection();
-        // SAFETY: We know that there is a valid path on the node as we already converted w/o error.
-        let ctxt = SignalContext::new(&conn, path).unwrap().into_owned();
+        Ok(())
+    }
 
-        Ok(InterfaceRef {
-            ctxt,
-            lock,
-            phantom: PhantomData,
-        })
+    /// Set where the `GetMachineId` method of the `org.freedesktop.DBus.Peer` interface gets the
+    /// machine ID from.
+    ///
+    /// By default, it's read from the system files. Containers and tests may need to provide a
+    /// deterministic or masked ID instead.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// use zbus::{object_server::MachineIdSource, Connection};
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// connection
+    ///     .object_server()
+    ///     .set_machine_id_source(MachineIdSource::Fixed(
+    ///         "0123456789abcdef0123456789abcdef".into(),
+    ///     ))
+    ///     .await;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// # })?;
+    /// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+    /// ```
+    pub async fn set_machine_id_source(&self, source: MachineIdSource) {
+        *self.machine_id.write().await = source;
+    }
+
+    /// Get the machine ID from the configured source.
+    pub(crate) async fn machine_id(&self) -> fdo::Result<String> {
+        self.machine_id.read().await.machine_id()
+    }
+
+    /// Get statistics about the method calls handled so far, by interface.
+    ///
+    /// Method calls to unknown objects or interfaces aren't accounted for. The latency of a call
+    /// is the time from the start of its dispatch to its reply, including the time spent in
+    /// middleware and waiting for the interface to be available. Replies sent later through a
+    /// [`Responder`] aren't accounted for.
+    ///
+    /// With the `debug-stats` feature enabled, these can also be exposed on the bus, by serving
+    /// `fdo::Stats`.
+    pub fn stats(&self) -> HashMap<OwnedInterfaceName, InterfaceStats> {
+        self.stats.snapshot()
+    }
+
+    /// Record that the error reply `reply` was sent, for the statistics of the method call it's
+    /// replying to.
+    pub(crate) fn record_error_reply(&self, reply: &Message) {
+        self.stats.error_replied(reply);
+    }
+
+    /// Get information about the caller of the method call with header `header`, received on
+    /// `connection`.
+    ///
+    /// Interface methods are better off using the `#[zbus(caller_info)]` argument attribute
+    /// instead. The credentials of the most recent callers are cached, so this doesn't always
+    /// involve a round-trip to the bus.
+    pub async fn caller_info(
+        &self,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> fdo::Result<CallerInfo> {
+        self.credentials.caller_info(connection, header).await
     }
 
     #[instrument(skip(self, connection))]
-    async fn dispatch_method_call_try(
+    pub(crate) async fn dispatch_method_call_try(
         &self,
         connection: &Connection,
         msg: &Message,
@@ -722,18 +2289,46 @@ impl ObjectServer {
 
         // Ensure the root lock isn't held while dispatching the message. That
         // way, the object server can be mutated during that time.
-        let iface = {
+        let (iface, dispatch_limit, _call) = {
             let root = self.root.read().await;
-            let node = root
-                .get_child(path, false)
-                .0
-                .ok_or_else(|| fdo::Error::UnknownObject(format!("Unknown object '{path}'")))?;
-
-            node.interface_lock(iface_name.as_ref()).ok_or_else(|| {
-                fdo::Error::UnknownInterface(format!("Unknown interface '{iface_name}'"))
-            })?
+            let node = root.get_child(path, false).0;
+            match root.interface_at(path, iface_name.as_ref()) {
+                // Counted while the root is locked, so `remove_graceful` can't detach the
+                // interface in between and miss the call.
+                Some(iface) => (
+                    iface.instance,
+                    node.and_then(|n| n.dispatch_limit.clone()),
+                    iface.calls.enter(),
+                ),
+                None if node.is_none() => {
+                    return Err(fdo::Error::UnknownObject(format!(
+                        "Unknown object '{path}'"
+                    )));
+                }
+                None => {
+                    return Err(fdo::Error::UnknownInterface(format!(
+                        "Unknown interface '{iface_name}'"
+                    )));
+                }
+            }
+        };
+        let _in_flight = match dispatch_limit {
+            Some(limit) => Some(limit.acquire().ok_or_else(|| {
+                fdo::Error::LimitsExceeded(format!("Too many calls to '{path}' in progress"))
+            })?),
+            None => None,
         };
 
+        // Don't hold the interface lock while waiting on polkit.
+        let action_id = iface
+            .read()
+            .await
+            .polkit_action(member.as_str())
+            .map(ToOwned::to_owned);
+        if let Some(action_id) = action_id {
+            polkit::check_authorization(connection, msg, &action_id).await?;
+        }
+
         trace!("acquiring read lock on interface `{}`", iface_name);
         let read_lock = iface.read().await;
         trace!("acquired read lock on interface `{}`", iface_name);
@@ -767,15 +2362,78 @@ impl ObjectServer {
 
     #[instrument(skip(self, connection))]
     async fn dispatch_method_call(&self, connection: &Connection, msg: &Message) -> Result<()> {
-        match self.dispatch_method_call_try(connection, msg).await {
+        let middleware = middleware::applicable(&self.layers.read().await, msg);
+        let dispatch = async {
+            if middleware.is_empty() {
+                self.dispatch_method_call_try(connection, msg).await
+            } else {
+                match Next::new(&middleware, self, connection, msg).run().await {
+                    Err(Error::FDO(e)) => Err(*e),
+                    r => Ok(r),
+                }
+            }
+        };
+        let deadline = {
+            let hdr = msg.header();
+            match (hdr.interface(), hdr.member()) {
+                (Some(interface), Some(member)) => {
+                    self.deadlines.read().await.get(interface, member)
+                }
+                _ => None,
+            }
+        };
+        let dispatch = async {
+            let Some(deadline) = deadline else {
+                return dispatch.await;
+            };
+            match select(pin!(dispatch), connection.executor().timer(deadline)).await {
+                Either::Left((res, _)) => res,
+                Either::Right(_) => {
+                    debug!("Method call not handled within {:?}: {}", deadline, msg);
+
+                    Err(fdo::Error::TimedOut(format!(
+                        "Method call not handled within {deadline:?}"
+                    )))
+                }
+            }
+        };
+        let start = self.stats.start(msg);
+        let watchdog = self.watchdog.read().await.clone();
+        let res = match watchdog {
+            Some(watchdog) => {
+                let timer = connection.executor().timer(watchdog.threshold);
+                match select(pin!(dispatch), timer).await {
+                    Either::Left((res, _)) => res,
+                    Either::Right((_, dispatch)) => {
+                        warn!(
+                            "Method call is taking more than {:?} to be handled: {}",
+                            watchdog.threshold, msg
+                        );
+                        watchdog.report(msg);
+
+                        dispatch.await
+                    }
+                }
+            }
+            None => dispatch.await,
+        };
+        let known = !matches!(
+            res,
+            Err(fdo::Error::UnknownObject(_) | fdo::Error::UnknownInterface(_))
+        );
+        let res = match res {
             Err(e) => {
                 let hdr = msg.header();
                 debug!("Returning error: {}", e);
-                connection.reply_dbus_error(&hdr, e).await?;
-                Ok(())
+                connection.reply_dbus_error(&hdr, e).await
             }
             Ok(r) => r,
+        };
+        if let Some(start) = start {
+            self.stats.finish(msg, start, known);
         }
+
+        res
     }
 
     /// Dispatch an incoming message to a registered interface.
@@ -790,8 +2448,17 @@ impl ObjectServer {
     ///   the caller through the associated server connection.
     ///
     /// Returns an error if the message is malformed, true if it's handled, false otherwise.
+    ///
+    /// If a `ticket` is given, the dispatch waits for its turn.
     #[instrument(skip(self))]
-    pub(crate) async fn dispatch_message(&self, msg: &Message) -> Result<bool> {
+    pub(crate) async fn dispatch_message(
+        &self,
+        msg: &Message,
+        ticket: Option<Ticket>,
+    ) -> Result<bool> {
+        if let Some(ticket) = &ticket {
+            ticket.wait().await;
+        }
         let conn = self.connection();
         self.dispatch_method_call(&conn, msg).await?;
         trace!("Handled: {}", msg);
@@ -799,6 +2466,21 @@ impl ObjectServer {
         Ok(true)
     }
 
+    /// Get a ticket ordering the dispatch of the method call `msg`, if it's to an interface with
+    /// [`DispatchMode::Serial`].
+    ///
+    /// This is to be called in the order messages are received in.
+    pub(crate) async fn dispatch_ticket(&self, msg: &Message) -> Option<Ticket> {
+        let hdr = msg.header();
+        let (path, iface_name) = (hdr.path()?, hdr.interface()?);
+        let root = self.root.read().await;
+
+        root.interface_at(path, iface_name.as_ref())?
+            .sequencer
+            .as_ref()
+            .map(Sequencer::ticket)
+    }
+
     pub(crate) fn connection(&self) -> Connection {
         self.conn
             .upgrade()
@@ -878,3 +2560,976 @@ impl<T> Drop for ResponseDispatchNotifier<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{fdo, Error};
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn object_manager_intermediate_nodes() {
+        use zbus::{blocking, object_server::ObjectManager};
+
+        struct TestObj;
+        #[crate::interface(name = "org.zbus.TestObj")]
+        impl TestObj {}
+
+        let path = "/org/zbus/ObjectManagerIntermediateNodes";
+        let child = "/org/zbus/ObjectManagerIntermediateNodes/Intermediate/Child";
+        let intermediate = "/org/zbus/ObjectManagerIntermediateNodes/Intermediate";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .name("org.zbus.ObjectManagerIntermediateNodes")
+            .unwrap()
+            .serve_at(path, ObjectManager)
+            .unwrap()
+            .serve_at(child, TestObj)
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let proxy = blocking::fdo::ObjectManagerProxy::builder(&conn)
+            .destination("org.zbus.ObjectManagerIntermediateNodes")
+            .unwrap()
+            .path(path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let managed_paths = || {
+            let mut paths: Vec<_> = proxy
+                .get_managed_objects()
+                .unwrap()
+                .into_keys()
+                .map(|p| p.to_string())
+                .collect();
+            paths.sort();
+            paths
+        };
+        assert_eq!(managed_paths(), [child]);
+
+        // Removing a parent object keeps its children.
+        let object_server = service.object_server();
+        assert!(object_server.at(intermediate, TestObj).unwrap());
+        assert_eq!(managed_paths(), [intermediate, child]);
+        assert!(object_server.remove::<TestObj, _>(intermediate).unwrap());
+        assert_eq!(managed_paths(), [child]);
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn fallback_interfaces() {
+        use zbus::{blocking, message::Header};
+
+        struct Items;
+        #[crate::interface(name = "org.zbus.Item")]
+        impl Items {
+            fn id(&self, #[zbus(header)] header: Header<'_>) -> String {
+                header
+                    .path()
+                    .unwrap()
+                    .rsplit('/')
+                    .next()
+                    .unwrap()
+                    .to_string()
+            }
+
+            #[zbus(property)]
+            fn kind(&self) -> &str {
+                "fallback"
+            }
+        }
+
+        struct Special;
+        #[crate::interface(name = "org.zbus.Item")]
+        impl Special {
+            fn id(&self) -> String {
+                "special".into()
+            }
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .name("org.zbus.FallbackInterfaces")
+            .unwrap()
+            .serve_at("/org/zbus/Items/Special", Special)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(service
+            .object_server()
+            .at_fallback("/org/zbus/Items", Items)
+            .unwrap());
+
+        let conn = blocking::Connection::session().unwrap();
+        let item = |path| {
+            blocking::Proxy::new(&conn, "org.zbus.FallbackInterfaces", path, "org.zbus.Item")
+                .unwrap()
+        };
+        let id = |path| item(path).call::<_, _, String>("Id", &()).unwrap();
+        assert_eq!(id("/org/zbus/Items/42"), "42");
+        assert_eq!(id("/org/zbus/Items/a/b"), "b");
+        assert_eq!(id("/org/zbus/Items/Special"), "special");
+        assert_eq!(
+            item("/org/zbus/Items/42")
+                .get_property::<String>("Kind")
+                .unwrap(),
+            "fallback"
+        );
+        let xml = blocking::fdo::IntrospectableProxy::builder(&conn)
+            .destination("org.zbus.FallbackInterfaces")
+            .unwrap()
+            .path("/org/zbus/Items/42")
+            .unwrap()
+            .build()
+            .unwrap()
+            .introspect()
+            .unwrap();
+        assert!(xml.contains(r#"<interface name="org.zbus.Item">"#));
+
+        service
+            .object_server()
+            .remove_fallback::<Items, _>("/org/zbus/Items")
+            .unwrap();
+        let err = item("/org/zbus/Items/42")
+            .call::<_, _, String>("Id", &())
+            .unwrap_err();
+        let unknown_object = "org.freedesktop.DBus.Error.UnknownObject";
+        assert!(
+            matches!(&err, zbus::Error::MethodError(name, _, _) if *name == unknown_object),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn batched_properties_changed() {
+        use zbus::blocking;
+
+        struct Position {
+            x: i32,
+            y: i32,
+        }
+        #[crate::interface(name = "org.zbus.Position")]
+        impl Position {
+            #[zbus(property)]
+            fn x(&self) -> i32 {
+                self.x
+            }
+
+            #[zbus(property)]
+            fn y(&self) -> i32 {
+                self.y
+            }
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Position", Position { x: 0, y: 0 })
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let props = blocking::fdo::PropertiesProxy::builder(&conn)
+            .destination(service.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path("/org/zbus/Position")
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut changes = props.receive_properties_changed().unwrap();
+
+        let iface_ref = service
+            .object_server()
+            .interface::<_, Position>("/org/zbus/Position")
+            .unwrap();
+        {
+            let mut iface = iface_ref.get_mut();
+            iface.x = 4;
+            iface.y = 2;
+        }
+        iface_ref.properties_changed(&["X", "Y"]).unwrap();
+
+        let changed = changes.next().unwrap();
+        let args = changed.args().unwrap();
+        assert_eq!(args.interface_name, "org.zbus.Position");
+        let changed = args.changed_properties();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed["X"], zvariant::Value::from(4));
+        assert_eq!(changed["Y"], zvariant::Value::from(2));
+
+        let err = iface_ref.properties_changed(&["X", "Z"]).unwrap_err();
+        assert!(
+            matches!(&err, Error::FDO(e) if matches!(**e, fdo::Error::UnknownProperty(_))),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn graceful_removal() {
+        use crate::{abstractions::timer::Timer, connection, utils::block_on};
+        use event_listener::Event;
+        use std::{
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+            time::Duration,
+        };
+
+        struct Slow {
+            started: Arc<Event>,
+            completed: Arc<AtomicUsize>,
+            duration: Duration,
+        }
+        #[crate::interface(name = "org.zbus.Slow")]
+        impl Slow {
+            async fn work(&self) {
+                self.started.notify(1);
+                Timer::after(self.duration).await;
+                self.completed.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        block_on(async {
+            let started = Arc::new(Event::new());
+            let completed = Arc::new(AtomicUsize::new(0));
+            let slow = |duration| Slow {
+                started: started.clone(),
+                completed: completed.clone(),
+                duration,
+            };
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Slow", slow(Duration::from_millis(100)))?
+                .serve_at("/org/zbus/Blocked", slow(Duration::from_millis(100)))?
+                .serve_at("/org/zbus/Slower", slow(Duration::from_secs(5)))?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let work = |path: &'static str| {
+                let client = client.clone();
+                let dest = service.unique_name().unwrap().to_owned();
+                async move {
+                    client
+                        .call_method(Some(dest), path, Some("org.zbus.Slow"), "Work", &())
+                        .await
+                }
+            };
+            let server = service.object_server();
+
+            // The in-flight call completes before the object is removed.
+            let listener = started.listen();
+            let call = work("/org/zbus/Slow");
+            let remove = async {
+                listener.await;
+                server
+                    .remove_graceful("/org/zbus/Slow", Duration::from_secs(5))
+                    .await
+            };
+            let (reply, drained) = futures_util::join!(call, remove);
+            reply?;
+            assert!(drained?);
+            let err = work("/org/zbus/Slow").await.unwrap_err();
+            assert!(matches!(
+                fdo::Error::from(err),
+                fdo::Error::UnknownObject(_)
+            ));
+
+            // So does a call dispatched before the object is detached, but still waiting for the
+            // interface to be available.
+            let path = zvariant::ObjectPath::try_from("/org/zbus/Blocked")?;
+            let calls = {
+                let root = server.root().read().await;
+
+                root.interface_at(&path, <Slow as crate::object_server::Interface>::name())
+                    .unwrap()
+                    .calls
+            };
+            let iface = server.interface::<_, Slow>(&path).await?;
+            let guard = iface.get_mut().await;
+            let call = work("/org/zbus/Blocked");
+            let remove = async {
+                while calls.in_flight() == 0 {
+                    Timer::after(Duration::from_millis(10)).await;
+                }
+                let remove = server.remove_graceful(&path, Duration::from_secs(5));
+                let release = async {
+                    Timer::after(Duration::from_millis(50)).await;
+                    drop(guard);
+                };
+                let (drained, _) = futures_util::join!(remove, release);
+
+                (drained, completed.load(Ordering::SeqCst))
+            };
+            let (reply, (drained, completed_before)) = futures_util::join!(call, remove);
+            reply?;
+            assert!(drained?);
+            assert_eq!(completed_before, 2);
+
+            // The wait is bounded.
+            let listener = started.listen();
+            let _call = service
+                .executor()
+                .spawn(work("/org/zbus/Slower"), "slow call");
+            listener.await;
+            let drained = server
+                .remove_graceful("/org/zbus/Slower", Duration::from_millis(50))
+                .await?;
+            assert!(!drained);
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn served_paths_and_interfaces() {
+        use zbus::blocking;
+
+        struct Item;
+        #[crate::interface(name = "org.zbus.Item")]
+        impl Item {}
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Items/b", Item)
+            .unwrap()
+            .serve_at("/org/zbus/Items/a", Item)
+            .unwrap()
+            .serve_at("/org/zbus/Items", crate::fdo::ObjectManager)
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+        server.at_fallback("/org/zbus/Others", Item).unwrap();
+
+        let paths = server.paths();
+        let paths: Vec<_> = paths.iter().map(|p| p.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["/org/zbus/Items", "/org/zbus/Items/a", "/org/zbus/Items/b"]
+        );
+
+        let ifaces = server.interfaces_at("/org/zbus/Items/a").unwrap();
+        let ifaces: Vec<_> = ifaces.iter().map(|i| i.as_str()).collect();
+        assert_eq!(
+            ifaces,
+            [
+                "org.freedesktop.DBus.Introspectable",
+                "org.freedesktop.DBus.Peer",
+                "org.freedesktop.DBus.Properties",
+                "org.zbus.Item",
+            ]
+        );
+        let ifaces = server.interfaces_at("/org/zbus/Others/42").unwrap();
+        assert!(ifaces.iter().any(|i| i.as_str() == "org.zbus.Item"));
+        assert!(server
+            .interfaces_at("/org/zbus/Nothing")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    #[timeout(15000)]
+    fn introspect_interface() {
+        use zbus::{blocking, object_server::SignalContext};
+
+        struct Counter(u32);
+
+        #[crate::interface(name = "org.zbus.Counter")]
+        impl Counter {
+            fn increment(&mut self, by: u32) -> u32 {
+                self.0 += by;
+                self.0
+            }
+
+            #[zbus(property)]
+            fn count(&self) -> u32 {
+                self.0
+            }
+
+            #[zbus(signal)]
+            async fn overflowed(ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Counter", Counter(0))
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+        server
+            .at_fallback("/org/zbus/Counters", Counter(0))
+            .unwrap();
+
+        let iface = server
+            .introspect_interface("/org/zbus/Counter", "org.zbus.Counter")
+            .unwrap();
+        assert_eq!(iface.name(), "org.zbus.Counter");
+        let method = &iface.methods()[0];
+        assert_eq!(method.name(), "Increment");
+        let args: Vec<_> = method
+            .args()
+            .iter()
+            .map(|a| (a.ty().to_string(), a.direction()))
+            .collect();
+        assert_eq!(
+            args,
+            [
+                ("u".to_string(), Some(zbus_xml::ArgDirection::In)),
+                ("u".to_string(), Some(zbus_xml::ArgDirection::Out)),
+            ]
+        );
+        assert_eq!(iface.properties()[0].name(), "Count");
+        assert_eq!(iface.signals()[0].name(), "Overflowed");
+
+        // The same data as `Introspect` returns.
+        let conn = blocking::Connection::session().unwrap();
+        let xml = blocking::fdo::IntrospectableProxy::builder(&conn)
+            .destination(service.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path("/org/zbus/Counter")
+            .unwrap()
+            .build()
+            .unwrap()
+            .introspect()
+            .unwrap();
+        let node = zbus_xml::Node::from_reader(xml.as_bytes()).unwrap();
+        let interfaces = server
+            .introspect_interfaces_at("/org/zbus/Counter")
+            .unwrap();
+        let mut expected = node.interfaces().to_vec();
+        expected.sort_by(|a, b| a.name().cmp(&b.name()));
+        assert_eq!(interfaces, expected);
+        let names: Vec<_> = interfaces.iter().map(|i| i.name().to_string()).collect();
+        assert!(names.contains(&"org.freedesktop.DBus.Properties".to_string()));
+
+        // Fallback interfaces are served for the paths they cover.
+        let fallback = server
+            .introspect_interface("/org/zbus/Counters/1", "org.zbus.Counter")
+            .unwrap();
+        assert_eq!(fallback, iface);
+
+        assert!(matches!(
+            server.introspect_interface("/org/zbus/Counter", "org.zbus.Nothing"),
+            Err(Error::InterfaceNotFound)
+        ));
+        assert!(matches!(
+            server.introspect_interface("/org/zbus/Nothing", "org.zbus.Counter"),
+            Err(Error::InterfaceNotFound)
+        ));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn extra_introspection() {
+        use zbus::blocking;
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Items", crate::fdo::ObjectManager)
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+        server
+            .set_extra_introspection(
+                "/org/zbus/Items",
+                Some(
+                    "<annotation name=\"org.zbus.Vendor\" value=\"zbus\"/>\n<node name=\"42\"/>"
+                        .into(),
+                ),
+            )
+            .unwrap();
+        assert!(server
+            .set_extra_introspection("/org/zbus/Nothing", None)
+            .is_err());
+
+        let conn = blocking::Connection::session().unwrap();
+        let introspect = || {
+            blocking::fdo::IntrospectableProxy::builder(&conn)
+                .destination(service.unique_name().unwrap().to_owned())
+                .unwrap()
+                .path("/org/zbus/Items")
+                .unwrap()
+                .build()
+                .unwrap()
+                .introspect()
+                .unwrap()
+        };
+        let xml = introspect();
+        assert!(xml.contains("\n  <annotation name=\"org.zbus.Vendor\" value=\"zbus\"/>\n"));
+        assert!(xml.contains("\n  <node name=\"42\"/>\n"));
+
+        server
+            .set_extra_introspection("/org/zbus/Items", None)
+            .unwrap();
+        assert!(!introspect().contains("org.zbus.Vendor"));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn emits_changed_signal() {
+        use crate::{
+            block_on, blocking,
+            object_server::{EmitsChangedSignal, Interface},
+        };
+
+        struct Gauge;
+        #[crate::interface(name = "org.zbus.Gauge")]
+        impl Gauge {
+            #[zbus(property)]
+            fn level(&self) -> u32 {
+                7
+            }
+
+            #[zbus(property(emits_changed_signal = "invalidates"))]
+            fn load(&self) -> u32 {
+                42
+            }
+
+            #[zbus(property(emits_changed_signal = "false"))]
+            fn noise(&self) -> u32 {
+                0
+            }
+
+            #[zbus(property(emits_changed_signal = "const"))]
+            fn model(&self) -> &str {
+                "G-1"
+            }
+        }
+
+        assert_eq!(
+            Gauge.emits_changed_signal("Level"),
+            EmitsChangedSignal::True
+        );
+        assert_eq!(
+            Gauge.emits_changed_signal("Load"),
+            EmitsChangedSignal::Invalidates
+        );
+        assert_eq!(
+            Gauge.emits_changed_signal("Noise"),
+            EmitsChangedSignal::False
+        );
+        assert_eq!(
+            Gauge.emits_changed_signal("Model"),
+            EmitsChangedSignal::Const
+        );
+        let mut xml = String::new();
+        Gauge.introspect_to_writer(&mut xml, 0);
+        assert!(xml.contains(
+            r#"<annotation name="org.freedesktop.DBus.Property.EmitsChangedSignal" value="const"/>"#
+        ));
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Gauge", Gauge)
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let props = blocking::fdo::PropertiesProxy::builder(&conn)
+            .destination(service.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path("/org/zbus/Gauge")
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut changes = props.receive_properties_changed().unwrap();
+        let iface_ref = service
+            .object_server()
+            .interface::<_, Gauge>("/org/zbus/Gauge")
+            .unwrap();
+
+        iface_ref
+            .properties_changed(&["Level", "Load", "Noise"])
+            .unwrap();
+        let changed = changes.next().unwrap();
+        let args = changed.args().unwrap();
+        assert_eq!(args.changed_properties().len(), 1);
+        assert_eq!(
+            args.changed_properties()["Level"],
+            zvariant::Value::from(7u32)
+        );
+        assert_eq!(args.invalidated_properties(), &["Load"]);
+
+        let err = iface_ref.properties_changed(&["Model"]).unwrap_err();
+        assert!(matches!(err, Error::Failure(_)), "{err}");
+
+        let ctxt = iface_ref.signal_context();
+        let iface = iface_ref.get();
+        block_on(iface.noise_changed(ctxt)).unwrap();
+        block_on(iface.model_changed(ctxt)).unwrap_err();
+        block_on(iface.load_changed(ctxt)).unwrap();
+        // Nothing was emitted for `Noise` so the next signal is for `Load`, without its value.
+        let changed = changes.next().unwrap();
+        let args = changed.args().unwrap();
+        assert!(args.changed_properties().is_empty());
+        assert_eq!(args.invalidated_properties(), &["Load"]);
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn properties_changed_across_interfaces() {
+        use crate::blocking;
+        use std::{
+            collections::HashMap,
+            sync::{Arc, Mutex},
+        };
+        use zvariant::OwnedValue;
+
+        #[derive(Default)]
+        struct Thermostat {
+            celsius: f64,
+        }
+
+        // The old version, a shim over the state of the new one.
+        struct Thermostat1(Arc<Mutex<Thermostat>>);
+        #[crate::interface(name = "org.zbus.Thermostat1")]
+        impl Thermostat1 {
+            fn set(&self, temperature: i32) {
+                self.0.lock().unwrap().celsius = temperature.into();
+            }
+
+            #[zbus(property)]
+            fn temperature(&self) -> i32 {
+                self.0.lock().unwrap().celsius.round() as i32
+            }
+        }
+
+        struct Thermostat2(Arc<Mutex<Thermostat>>);
+        #[crate::interface(name = "org.zbus.Thermostat2")]
+        impl Thermostat2 {
+            #[zbus(property)]
+            fn celsius(&self) -> f64 {
+                self.0.lock().unwrap().celsius
+            }
+
+            #[zbus(property(emits_changed_signal = "invalidates"))]
+            fn fahrenheit(&self) -> f64 {
+                self.0.lock().unwrap().celsius * 9. / 5. + 32.
+            }
+        }
+
+        let state = Arc::new(Mutex::new(Thermostat::default()));
+        let path = "/org/zbus/Thermostat";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(path, Thermostat1(state.clone()))
+            .unwrap()
+            .serve_at(path, Thermostat2(state.clone()))
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let props = blocking::fdo::PropertiesProxy::builder(&conn)
+            .destination(service.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path(path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut changes = props.receive_properties_changed().unwrap();
+
+        conn.call_method(
+            service.unique_name(),
+            path,
+            Some("org.zbus.Thermostat1"),
+            "Set",
+            &(20,),
+        )
+        .unwrap();
+        let celsius = props
+            .get("org.zbus.Thermostat2".try_into().unwrap(), "Celsius")
+            .unwrap();
+        assert_eq!(f64::try_from(celsius).unwrap(), 20.);
+
+        state.lock().unwrap().celsius = 21.6;
+        let object_server = service.object_server();
+        object_server
+            .properties_changed(path, &["Temperature", "Celsius", "Fahrenheit"])
+            .unwrap();
+        let mut signals = HashMap::new();
+        for _ in 0..2 {
+            let changed = changes.next().unwrap();
+            let args = changed.args().unwrap();
+            let changed: HashMap<_, _> = args
+                .changed_properties()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.try_to_owned().unwrap()))
+                .collect();
+            let invalidated: Vec<_> = args
+                .invalidated_properties()
+                .iter()
+                .map(|name| name.to_string())
+                .collect();
+            signals.insert(args.interface_name.to_string(), (changed, invalidated));
+        }
+        let (changed, invalidated) = &signals["org.zbus.Thermostat1"];
+        assert_eq!(changed["Temperature"], OwnedValue::from(22));
+        assert!(invalidated.is_empty());
+        let (changed, invalidated) = &signals["org.zbus.Thermostat2"];
+        assert_eq!(changed["Celsius"], OwnedValue::from(21.6));
+        assert_eq!(invalidated, &["Fahrenheit"]);
+
+        let err = object_server
+            .properties_changed(path, &["Celsius", "Kelvin"])
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::FDO(e) if matches!(**e, fdo::Error::UnknownProperty(_))),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn replace_at() {
+        use crate::blocking;
+        use std::thread;
+
+        struct Thermostat {
+            target: u32,
+            label: &'static str,
+            vendor: &'static str,
+        }
+        #[crate::interface(name = "org.zbus.Thermostat")]
+        impl Thermostat {
+            fn ping(&self) {}
+
+            #[zbus(property)]
+            fn target(&self) -> u32 {
+                self.target
+            }
+
+            #[zbus(property(emits_changed_signal = "invalidates"))]
+            fn label(&self) -> &str {
+                self.label
+            }
+
+            #[zbus(property(emits_changed_signal = "const"))]
+            fn vendor(&self) -> &str {
+                self.vendor
+            }
+        }
+        let thermostat = |target, label| Thermostat {
+            target,
+            label,
+            vendor: "zbus",
+        };
+
+        let path = "/org/zbus/Thermostat";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(path, thermostat(20, "Living room"))
+            .unwrap()
+            .build()
+            .unwrap();
+        let object_server = service.object_server();
+        let conn = blocking::Connection::session().unwrap();
+        let props = blocking::fdo::PropertiesProxy::builder(&conn)
+            .destination(service.unique_name().unwrap())
+            .unwrap()
+            .path(path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut changes = props.receive_properties_changed().unwrap();
+
+        // Calls keep succeeding while the implementation is being swapped.
+        thread::scope(|s| {
+            let caller = s.spawn(|| {
+                for _ in 0..100 {
+                    conn.call_method(
+                        service.unique_name(),
+                        path,
+                        Some("org.zbus.Thermostat"),
+                        "Ping",
+                        &(),
+                    )
+                    .unwrap();
+                }
+            });
+            for _ in 0..20 {
+                let replaced = object_server
+                    .replace_at(path, thermostat(20, "Living room"))
+                    .unwrap();
+                assert!(replaced);
+            }
+            caller.join().unwrap();
+        });
+
+        // Only the properties whose values differ are signaled.
+        let replaced = object_server
+            .replace_at(path, thermostat(22, "Kitchen"))
+            .unwrap();
+        assert!(replaced);
+        let signal = changes.next().unwrap();
+        let args = signal.args().unwrap();
+        assert_eq!(args.interface_name, "org.zbus.Thermostat");
+        let changed: Vec<_> = args.changed_properties.keys().copied().collect();
+        assert_eq!(changed, ["Target"]);
+        assert_eq!(
+            u32::try_from(&args.changed_properties["Target"]).unwrap(),
+            22
+        );
+        assert_eq!(args.invalidated_properties, ["Label"]);
+        let target: u32 = props
+            .get("org.zbus.Thermostat".try_into().unwrap(), "Target")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(target, 22);
+
+        // Without a previous implementation, the interface is simply registered.
+        let replaced = object_server
+            .replace_at("/org/zbus/Other", thermostat(18, "Bedroom"))
+            .unwrap();
+        assert!(!replaced);
+        let interfaces = object_server.interfaces_at("/org/zbus/Other").unwrap();
+        assert!(interfaces
+            .iter()
+            .any(|i| i.as_str() == "org.zbus.Thermostat"));
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn alias() {
+        use crate::blocking;
+
+        #[derive(Default)]
+        struct Volume {
+            level: u32,
+        }
+        #[crate::interface(name = "org.zbus.Volume")]
+        impl Volume {
+            fn raise(&mut self) {
+                self.level += 1;
+            }
+
+            #[zbus(property)]
+            fn level(&self) -> u32 {
+                self.level
+            }
+        }
+
+        let (path, alias) = ("/org/zbus/Volume", "/org/zbus/Legacy/Volume");
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(path, Volume::default())
+            .unwrap()
+            .build()
+            .unwrap();
+        let object_server = service.object_server();
+        assert!(object_server.alias::<Volume, _, _>(path, alias).unwrap());
+        assert!(!object_server.alias::<Volume, _, _>(path, alias).unwrap());
+        let err = object_server
+            .alias::<Volume, _, _>("/org/zbus/Unknown", "/org/zbus/Other")
+            .unwrap_err();
+        assert_eq!(err, Error::InterfaceNotFound);
+
+        let conn = blocking::Connection::session().unwrap();
+        let props = |path| {
+            blocking::fdo::PropertiesProxy::builder(&conn)
+                .destination(service.unique_name().unwrap())
+                .unwrap()
+                .path(path)
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let level = |path| -> u32 {
+            props(path)
+                .get("org.zbus.Volume".try_into().unwrap(), "Level")
+                .unwrap()
+                .try_into()
+                .unwrap()
+        };
+
+        // Both paths share the same instance.
+        conn.call_method(
+            service.unique_name(),
+            alias,
+            Some("org.zbus.Volume"),
+            "Raise",
+            &(),
+        )
+        .unwrap();
+        assert_eq!(level(path), 1);
+
+        // Property changes are signaled from the path of the interface reference only.
+        let legacy_props = props(alias);
+        let mut legacy_changes = legacy_props.receive_properties_changed().unwrap();
+        let iface_ref = object_server.interface::<_, Volume>(alias).unwrap();
+        assert_eq!(iface_ref.signal_context().path().as_str(), alias);
+        iface_ref.get_mut().level = 5;
+        crate::utils::block_on(iface_ref.get().level_changed(iface_ref.signal_context())).unwrap();
+        let signal = legacy_changes.next().unwrap();
+        assert_eq!(signal.message().header().path().unwrap(), alias);
+        assert_eq!(level(path), 5);
+
+        // Removing the interface from one path leaves it at the other.
+        object_server.remove::<Volume, _>(path).unwrap();
+        assert_eq!(level(alias), 5);
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn virtual_children() {
+        use zbus::blocking;
+
+        struct Item;
+        #[crate::interface(name = "org.zbus.Item")]
+        impl Item {
+            fn open(&self) {}
+        }
+
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at("/org/zbus/Items/3", Item)
+            .unwrap()
+            .build()
+            .unwrap();
+        let server = service.object_server();
+        server.at_fallback("/org/zbus/Items", Item).unwrap();
+        server
+            .set_virtual_children("/org/zbus/Items", |path| match path.as_str() {
+                "/org/zbus/Items" => ["1", "2", "3", "not-valid"].map(Into::into).to_vec(),
+                _ => vec!["parts".into()],
+            })
+            .unwrap();
+        assert!(server
+            .set_virtual_children("/org/zbus/Nothing", |_| vec![])
+            .is_err());
+
+        let conn = blocking::Connection::session().unwrap();
+        let introspect = |path| {
+            blocking::fdo::IntrospectableProxy::builder(&conn)
+                .destination(service.unique_name().unwrap().to_owned())
+                .unwrap()
+                .path(path)
+                .unwrap()
+                .build()
+                .unwrap()
+                .introspect()
+                .unwrap()
+        };
+        let xml = introspect("/org/zbus/Items");
+        assert!(xml.contains("\n  <node name=\"1\"/>\n"), "{xml}");
+        assert!(xml.contains("\n  <node name=\"2\"/>\n"), "{xml}");
+        // The registered object is listed as usual, and only once.
+        assert_eq!(xml.matches("<node name=\"3\"").count(), 1, "{xml}");
+        assert!(!xml.contains("not-valid"), "{xml}");
+        // Paths without an object of their own are covered as well.
+        let xml = introspect("/org/zbus/Items/1");
+        assert!(xml.contains("<interface name=\"org.zbus.Item\">"), "{xml}");
+        assert!(xml.contains("\n  <node name=\"parts\"/>\n"), "{xml}");
+
+        server.remove_virtual_children("/org/zbus/Items").unwrap();
+        assert!(!introspect("/org/zbus/Items").contains("<node name=\"1\"/>"));
+    }
+}
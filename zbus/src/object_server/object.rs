@@ -0,0 +1,233 @@
+use std::{fmt, sync::Arc};
+use zbus_names::InterfaceName;
+
+use crate::{async_lock::RwLock, Error, Result};
+
+use super::{DynamicInterface, DynamicInterfaceImpl, Interface, InterfaceShim, InterfaceShimImpl};
+
+/// An object composed of several interfaces, to be registered at once.
+///
+/// Services made of plugins often can't implement all the interfaces of an object in one place.
+/// This gathers the interfaces of an object, be they implemented with the [`interface`] macro or
+/// defined at runtime as a [`DynamicInterface`], so that they're all registered in a single call
+/// to [`ObjectServer::at_object`].
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{
+///     interface,
+///     object_server::{DynamicInterface, Object},
+///     zvariant::OwnedValue,
+///     Connection,
+/// };
+///
+/// struct Battery;
+///
+/// #[interface(name = "org.example.Battery")]
+/// impl Battery {
+///     #[zbus(property)]
+///     fn percentage(&self) -> u8 {
+///         42
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// let mut object = Object::new().interface(Battery)?;
+/// // Added by a plugin.
+/// let firmware = DynamicInterface::new("org.example.Firmware")?
+///     .method("Update", &[], &[], |_msg, _args| async { Ok(vec![]) })?
+///     .property("Version", "u", || async { Ok(OwnedValue::from(3u32)) })?;
+/// object = object.dynamic_interface(firmware)?;
+///
+/// let connection = Connection::session().await?;
+/// connection
+///     .object_server()
+///     .at_object("/org/example/Device", object)
+///     .await?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+///
+/// [`interface`]: crate::interface
+/// [`ObjectServer::at_object`]: super::ObjectServer::at_object
+#[derive(Default)]
+pub struct Object {
+    pub(super) interfaces: Vec<(InterfaceName<'static>, Arc<RwLock<dyn Interface>>)>,
+}
+
+impl Object {
+    /// Create an object without any interface.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the interface `iface`.
+    ///
+    /// # Errors
+    ///
+    /// If the object already has an interface of the same name, `Error::Failure` error is
+    /// returned.
+    pub fn interface<I>(self, iface: I) -> Result<Self>
+    where
+        I: Interface,
+    {
+        self.add(I::name(), Arc::new(RwLock::new(iface)))
+    }
+
+    /// Add the interface `iface`, defined at runtime.
+    ///
+    /// # Errors
+    ///
+    /// If the object already has an interface of the same name, `Error::Failure` error is
+    /// returned.
+    pub fn dynamic_interface(self, iface: DynamicInterface) -> Result<Self> {
+        let name = iface.name().clone();
+
+        self.add(name, Arc::new(RwLock::new(DynamicInterfaceImpl(iface))))
+    }
+
+    /// Add the interface `shim`, forwarding to one of the interfaces already added.
+    ///
+    /// # Errors
+    ///
+    /// If the target interface of `shim` isn't part of the object, `Error::InterfaceNotFound` error
+    /// is returned. If it doesn't have one of the members the shim maps to, or if the object
+    /// already has an interface of the same name as the shim, `Error::Failure` error is.
+    pub fn shim(self, shim: InterfaceShim) -> Result<Self> {
+        let target = self
+            .interfaces
+            .iter()
+            .find(|(name, _)| name == shim.target())
+            .map(|(_, instance)| instance.clone())
+            .ok_or(Error::InterfaceNotFound)?;
+        let shim = {
+            // The interfaces of the object aren't shared yet, so they can't be locked.
+            let iface = target.try_read();
+            #[cfg(feature = "tokio")]
+            let iface = iface.ok();
+            let iface = iface.ok_or_else(|| Error::Failure("Interface is locked".into()))?;
+            InterfaceShimImpl::new(shim, target.clone(), &*iface)?
+        };
+        let name = shim.name().clone();
+
+        self.add(name, Arc::new(RwLock::new(shim)))
+    }
+
+    /// The names of the interfaces of the object, in the order they were added.
+    pub fn interface_names(&self) -> impl Iterator<Item = &InterfaceName<'static>> {
+        self.interfaces.iter().map(|(name, _)| name)
+    }
+
+    fn add(
+        mut self,
+        name: InterfaceName<'static>,
+        instance: Arc<RwLock<dyn Interface>>,
+    ) -> Result<Self> {
+        if self.interfaces.iter().any(|(n, _)| *n == name) {
+            return Err(Error::Failure(format!(
+                "Interface `{name}` is already part of the object"
+            )));
+        }
+        self.interfaces.push((name, instance));
+
+        Ok(self)
+    }
+}
+
+impl fmt::Debug for Object {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Object")
+            .field("interfaces", &self.interface_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+    use zvariant::OwnedValue;
+
+    use super::Object;
+    use crate::{connection, fdo, interface, object_server::DynamicInterface, utils::block_on};
+
+    struct Battery;
+
+    #[interface(name = "org.zbus.Battery")]
+    impl Battery {
+        fn charge(&self) -> u32 {
+            100
+        }
+    }
+
+    struct Display;
+
+    #[interface(name = "org.zbus.Display")]
+    impl Display {
+        fn brightness(&self) -> u32 {
+            80
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn object() {
+        block_on(async {
+            let path = "/org/zbus/Device";
+            let firmware = DynamicInterface::new("org.zbus.Firmware")?.method(
+                "Version",
+                &[],
+                &[("version", "u")],
+                |_msg, _args| async { Ok(vec![OwnedValue::from(3u32)]) },
+            )?;
+            let object = Object::new()
+                .interface(Battery)?
+                .interface(Display)?
+                .dynamic_interface(firmware)?;
+            let names: Vec<_> = object.interface_names().map(|n| n.as_str()).collect();
+            assert_eq!(
+                names,
+                ["org.zbus.Battery", "org.zbus.Display", "org.zbus.Firmware"]
+            );
+            assert!(Object::new()
+                .interface(Battery)?
+                .interface(Battery)
+                .is_err());
+
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Other", fdo::ObjectManager)?
+                .build()
+                .await?;
+            let object_server = service.object_server();
+            assert!(object_server.at_object(path, object).await?);
+
+            let client = connection::Builder::session()?.build().await?;
+            for (interface, method, expected) in [
+                ("org.zbus.Battery", "Charge", 100u32),
+                ("org.zbus.Display", "Brightness", 80),
+                ("org.zbus.Firmware", "Version", 3),
+            ] {
+                let reply = client
+                    .call_method(service.unique_name(), path, Some(interface), method, &())
+                    .await?;
+                assert_eq!(reply.body().deserialize::<u32>()?, expected);
+            }
+
+            // Nothing is registered if any of the interfaces already exists.
+            let object = Object::new()
+                .interface(Battery)?
+                .interface(fdo::ObjectManager)?;
+            assert!(!object_server.at_object(path, object).await?);
+            let interfaces = object_server.interfaces_at(path).await?;
+            assert!(!interfaces
+                .iter()
+                .any(|i| i.as_str() == "org.freedesktop.DBus.ObjectManager"));
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use tracing::debug;
+use zvariant::Value;
+
+use crate::{
+    fdo,
+    message::{Flags, Message},
+    proxy, Connection,
+};
+
+#[proxy(
+    interface = "org.freedesktop.PolicyKit1.Authority",
+    default_service = "org.freedesktop.PolicyKit1",
+    default_path = "/org/freedesktop/PolicyKit1/Authority",
+    gen_blocking = false
+)]
+trait Authority {
+    #[allow(clippy::type_complexity)]
+    fn check_authorization(
+        &self,
+        subject: &(&str, HashMap<&str, Value<'_>>),
+        action_id: &str,
+        details: &HashMap<&str, &str>,
+        flags: u32,
+        cancellation_id: &str,
+    ) -> crate::Result<(bool, bool, HashMap<String, String>)>;
+}
+
+/// `AllowUserInteraction` flag of `CheckAuthorization`.
+const ALLOW_USER_INTERACTION: u32 = 0x1;
+
+/// Check with polkit if the sender of `msg` is authorized for `action_id`.
+///
+/// Any failure to get an answer from polkit is treated as a denial.
+pub(crate) async fn check_authorization(
+    conn: &Connection,
+    msg: &Message,
+    action_id: &str,
+) -> fdo::Result<()> {
+    let hdr = msg.header();
+    let sender = hdr.sender().ok_or_else(|| {
+        fdo::Error::AccessDenied(format!(
+            "Authorization for `{action_id}` requires a sender on the bus"
+        ))
+    })?;
+    let subject = (
+        "system-bus-name",
+        HashMap::from([("name", Value::from(sender.as_str()))]),
+    );
+    let flags = if hdr.primary().flags().contains(Flags::AllowInteractiveAuth) {
+        ALLOW_USER_INTERACTION
+    } else {
+        0
+    };
+
+    let authorized = async {
+        AuthorityProxy::builder(conn)
+            .cache_properties(proxy::CacheProperties::No)
+            .build()
+            .await?
+            .check_authorization(&subject, action_id, &HashMap::new(), flags, "")
+            .await
+    }
+    .await;
+    match authorized {
+        Ok((true, _, _)) => Ok(()),
+        Ok((false, _, _)) => Err(fdo::Error::AccessDenied(format!(
+            "`{sender}` is not authorized for `{action_id}`"
+        ))),
+        Err(e) => {
+            debug!("Failed to check authorization for `{action_id}`: {e}");
+
+            Err(fdo::Error::AccessDenied(format!(
+                "Failed to check authorization for `{action_id}`"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+
+    use crate::{connection, fdo, interface, utils::block_on};
+
+    struct Guarded;
+
+    #[interface(name = "org.zbus.Guarded", polkit_action = "org.zbus.guarded.default")]
+    impl Guarded {
+        #[zbus(polkit_action = "org.zbus.guarded.poke")]
+        fn poke(&self) {}
+
+        fn prod(&self) {}
+
+        #[zbus(property)]
+        fn open(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn polkit_action() {
+        use crate::object_server::Interface;
+
+        assert_eq!(Guarded.polkit_action("Poke"), Some("org.zbus.guarded.poke"));
+        assert_eq!(
+            Guarded.polkit_action("Prod"),
+            Some("org.zbus.guarded.default")
+        );
+        assert_eq!(Guarded.polkit_action("Open"), None);
+        assert_eq!(Guarded.polkit_action("Unknown"), None);
+
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Guarded", Guarded)?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+
+            // The session bus has no polkit authority so all calls are denied.
+            let err = client
+                .call_method(
+                    service.unique_name(),
+                    "/org/zbus/Guarded",
+                    Some("org.zbus.Guarded"),
+                    "Poke",
+                    &(),
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(fdo::Error::from(err), fdo::Error::AccessDenied(_)));
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    fmt,
+    sync::{Arc, Weak},
+};
+use tracing::debug;
+use zbus_names::{InterfaceName, OwnedInterfaceName};
+use zvariant::{ObjectPath, OwnedObjectPath};
+
+use crate::{async_lock::RwLock, connection::WeakConnection, Connection, Error, Result};
+
+use super::{ArcInterface, Interface};
+
+/// An interface registered on the [`ObjectServer`](super::ObjectServer), until dropped.
+///
+/// This is returned by [`ObjectServer::at_scoped`](super::ObjectServer::at_scoped), for objects
+/// only exported for a limited time (e.g a transfer job). Dropping it unregisters the interface,
+/// emitting the `InterfacesRemoved` signal if the object is managed, just like
+/// [`ObjectServer::remove`](super::ObjectServer::remove) does. Since that can't be done from
+/// `Drop`, the removal happens in the background, on the executor of the connection. Use
+/// [`Registration::unregister`] to wait for it instead.
+///
+/// If the interface has already been removed from the object server by other means, nothing
+/// happens. An interface of the same name that was registered since at the same path is left
+/// alone.
+#[must_use = "the interface is unregistered once the registration is dropped"]
+pub struct Registration {
+    conn: WeakConnection,
+    path: OwnedObjectPath,
+    name: OwnedInterfaceName,
+    // `None` once unregistered.
+    instance: Option<Weak<RwLock<dyn Interface>>>,
+}
+
+impl Registration {
+    pub(super) fn new(
+        conn: &Connection,
+        path: ObjectPath<'_>,
+        name: InterfaceName<'static>,
+        instance: &Arc<RwLock<dyn Interface>>,
+    ) -> Self {
+        Self {
+            conn: conn.into(),
+            path: path.into(),
+            name: name.into(),
+            instance: Some(Arc::downgrade(instance)),
+        }
+    }
+
+    /// The path of the object.
+    pub fn path(&self) -> &ObjectPath<'_> {
+        &self.path
+    }
+
+    /// The name of the interface.
+    pub fn interface_name(&self) -> &InterfaceName<'_> {
+        &self.name
+    }
+
+    /// Unregister the interface now.
+    ///
+    /// Returns whether the object was destroyed, as [`ObjectServer::remove`] does.
+    ///
+    /// # Errors
+    ///
+    /// If the interface was already removed, or the connection is gone, `Error::InterfaceNotFound`
+    /// error is returned.
+    ///
+    /// [`ObjectServer::remove`]: super::ObjectServer::remove
+    pub async fn unregister(mut self) -> Result<bool> {
+        let instance = self.instance.take().ok_or(Error::InterfaceNotFound)?;
+        let conn = self.conn.upgrade().ok_or(Error::InterfaceNotFound)?;
+
+        remove(&conn, &self.path, &self.name, &instance).await
+    }
+}
+
+async fn remove(
+    conn: &Connection,
+    path: &OwnedObjectPath,
+    name: &OwnedInterfaceName,
+    instance: &Weak<RwLock<dyn Interface>>,
+) -> Result<bool> {
+    conn.object_server()
+        .remove_matching(
+            path.as_ref(),
+            name.inner().clone(),
+            |iface: &ArcInterface| Weak::ptr_eq(&Arc::downgrade(&iface.instance), instance),
+        )
+        .await
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        let (Some(instance), Some(conn)) = (self.instance.take(), self.conn.upgrade()) else {
+            return;
+        };
+        let path = self.path.clone();
+        let name = self.name.clone();
+
+        let executor = conn.executor().clone();
+        let task_name = conn.task_name(&format!("unregister `{name}` at `{path}`"));
+
+        executor
+            .spawn(
+                async move {
+                    if let Err(e) = remove(&conn, &path, &name, &instance).await {
+                        debug!("Failed to unregister `{name}` at `{path}`: {e}");
+                    }
+                },
+                &task_name,
+            )
+            .detach();
+    }
+}
+
+impl fmt::Debug for Registration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registration")
+            .field("path", &self.path)
+            .field("name", &self.name)
+            .field("registered", &self.instance.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn scoped_registration() {
+        use crate::{blocking, fdo::ObjectManager};
+
+        struct Job;
+        #[crate::interface(name = "org.zbus.Job")]
+        impl Job {
+            #[zbus(property)]
+            fn progress(&self) -> u8 {
+                42
+            }
+        }
+
+        let path = "/org/zbus/Jobs";
+        let job = "/org/zbus/Jobs/1";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(path, ObjectManager)
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let proxy = blocking::fdo::ObjectManagerProxy::builder(&conn)
+            .destination(service.unique_name().unwrap().to_owned())
+            .unwrap()
+            .path(path)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut removed = proxy.receive_interfaces_removed().unwrap();
+        let managed_paths = || -> Vec<_> {
+            proxy
+                .get_managed_objects()
+                .unwrap()
+                .into_keys()
+                .map(|p| p.to_string())
+                .collect()
+        };
+
+        let object_server = service.object_server();
+        let registration = object_server.at_scoped(job, Job).unwrap().unwrap();
+        assert_eq!(registration.path().as_str(), job);
+        assert!(object_server.at_scoped(job, Job).unwrap().is_none());
+        assert_eq!(managed_paths(), [job]);
+
+        // Dropping the registration removes the object.
+        drop(registration);
+        let signal = removed.next().unwrap();
+        let args = signal.args().unwrap();
+        assert_eq!(args.object_path().as_str(), job);
+        assert_eq!(args.interfaces(), &["org.zbus.Job"]);
+        assert!(managed_paths().is_empty());
+
+        // A registration doesn't remove an interface registered again after it.
+        let registration = object_server.at_scoped(job, Job).unwrap().unwrap();
+        assert!(object_server.remove::<Job, _>(job).unwrap());
+        assert!(object_server.at(job, Job).unwrap());
+        let err = registration.unregister().unwrap_err();
+        assert_eq!(err, Error::InterfaceNotFound);
+        assert_eq!(managed_paths(), [job]);
+
+        assert!(object_server.remove::<Job, _>(job).unwrap());
+        let registration = object_server.at_scoped(job, Job).unwrap().unwrap();
+        assert!(registration.unregister().unwrap());
+        assert!(managed_paths().is_empty());
+    }
+}
@@ -0,0 +1,234 @@
+use serde::Serialize;
+use static_assertions::assert_impl_all;
+use std::{fmt, marker::PhantomData};
+use tracing::debug;
+use zvariant::DynamicType;
+
+use crate::{fdo, message::Message, Connection, DBusError, Result};
+
+/// A handle to reply to a method call later.
+///
+/// Interface methods can receive this by declaring an argument with the `#[zbus(responder)]`
+/// attribute. The method then returns right away, without a reply being sent, and the reply is sent
+/// through the responder instead, once available. This is useful when the reply depends on a user
+/// interaction or on another method call, which shouldn't hold the object busy in the meantime.
+///
+/// `T` is the type of the reply, which is also what the method is described to return in the
+/// introspection data. Methods taking a responder must not return a value themselves.
+///
+/// If the responder is dropped without replying, a `org.freedesktop.DBus.Error.Failed` error is
+/// sent in the background, so the caller doesn't wait for a reply that will never come.
+///
+/// # Example
+///
+/// ```no_run
+/// use zbus::{fdo, interface, object_server::Responder};
+///
+/// struct Dialog;
+///
+/// #[interface(name = "org.example.Dialog")]
+/// impl Dialog {
+///     fn ask(&self, question: String, #[zbus(responder)] responder: Responder<bool>) {
+///         std::thread::spawn(move || {
+///             // Wait for the user to answer the question...
+///             let answer = !question.is_empty();
+///             let _ = zbus::block_on(responder.reply(answer));
+///         });
+///     }
+///
+///     async fn cancel(&self, #[zbus(responder)] responder: Responder<()>) {
+///         let _ = responder
+///             .error(fdo::Error::NotSupported("Can't cancel".into()))
+///             .await;
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// let connection = zbus::Connection::session().await?;
+/// connection
+///     .object_server()
+///     .at("/org/example/Dialog", Dialog)
+///     .await?;
+/// # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+/// ```
+#[must_use = "the caller gets an error reply if the responder is dropped without replying"]
+pub struct Responder<T> {
+    conn: Connection,
+    // `None` once replied.
+    call: Option<Message>,
+    phantom: PhantomData<fn(T)>,
+}
+
+assert_impl_all!(Responder<()>: Send, Sync, Unpin);
+
+impl<T> Responder<T>
+where
+    T: Serialize + DynamicType,
+{
+    /// Create a responder for the method call `call`, received on `conn`.
+    ///
+    /// You don't need to call this when using the `#[zbus(responder)]` argument attribute.
+    pub fn new(conn: &Connection, call: &Message) -> Self {
+        Self {
+            conn: conn.clone(),
+            call: Some(call.clone()),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The method call to reply to.
+    pub fn call(&self) -> &Message {
+        self.call.as_ref().expect("responder already replied")
+    }
+
+    /// Reply with `value`.
+    pub async fn reply(mut self, value: T) -> Result<()> {
+        let call = self.call.take().expect("responder already replied");
+
+        self.conn.reply(&call, &value).await
+    }
+
+    /// Reply with the error `err`.
+    pub async fn error<E>(mut self, err: E) -> Result<()>
+    where
+        E: DBusError,
+    {
+        let call = self.call.take().expect("responder already replied");
+
+        self.conn.reply_dbus_error(&call.header(), err).await
+    }
+}
+
+impl<T> Drop for Responder<T> {
+    fn drop(&mut self) {
+        let Some(call) = self.call.take() else {
+            return;
+        };
+        let conn = self.conn.clone();
+        let executor = conn.executor().clone();
+        let task_name = conn.task_name("reply to dropped method call");
+
+        executor
+            .spawn(
+                async move {
+                    let err = fdo::Error::Failed("No reply was sent".into());
+                    if let Err(e) = conn.reply_dbus_error(&call.header(), err).await {
+                        debug!("Failed to reply to dropped method call: {e}");
+                    }
+                },
+                &task_name,
+            )
+            .detach();
+    }
+}
+
+impl<T> fmt::Debug for Responder<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Responder")
+            .field("call", &self.call)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::Responder;
+    use crate::{connection, fdo, interface, utils::block_on, Error};
+
+    #[derive(Default)]
+    struct Deferred {
+        pending: Option<Responder<(u32, String)>>,
+    }
+
+    #[interface(name = "org.zbus.Deferred")]
+    impl Deferred {
+        async fn ask(&mut self, #[zbus(responder)] responder: Responder<(u32, String)>) {
+            self.pending = Some(responder);
+        }
+
+        async fn answer(&mut self) -> fdo::Result<()> {
+            let responder = self
+                .pending
+                .take()
+                .ok_or_else(|| fdo::Error::Failed("Nothing to answer".into()))?;
+            responder.reply((42, "answer".into())).await?;
+
+            Ok(())
+        }
+
+        async fn refuse(&self, #[zbus(responder)] responder: Responder<()>) {
+            responder
+                .error(fdo::Error::AccessDenied("Refused".into()))
+                .await
+                .unwrap();
+        }
+
+        fn forget(&self, #[zbus(responder)] responder: Responder<()>) {
+            drop(responder);
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn deferred_reply() {
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Deferred", Deferred::default())?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let call = |method| {
+                client.call_method(
+                    service.unique_name(),
+                    "/org/zbus/Deferred",
+                    Some("org.zbus.Deferred"),
+                    method,
+                    &(),
+                )
+            };
+
+            // `Ask` is answered by `Answer`, which needs the interface exclusively as well.
+            let (asked, ()) = futures_util::join!(call("Ask"), async {
+                while call("Answer").await.is_err() {}
+            });
+            let (n, s): (u32, String) = asked?.body().deserialize()?;
+            assert_eq!((n, s.as_str()), (42, "answer"));
+
+            let err = call("Refuse").await.unwrap_err();
+            assert!(
+                matches!(&err, Error::MethodError(name, _, _) if name.as_str() == "org.freedesktop.DBus.Error.AccessDenied"),
+                "{err}"
+            );
+            let err = call("Forget").await.unwrap_err();
+            assert!(
+                matches!(&err, Error::MethodError(name, _, _) if name.as_str() == "org.freedesktop.DBus.Error.Failed"),
+                "{err}"
+            );
+
+            let xml = fdo::IntrospectableProxy::builder(&client)
+                .destination(service.unique_name().unwrap())?
+                .path("/org/zbus/Deferred")?
+                .build()
+                .await?
+                .introspect()
+                .await?;
+            let node = zbus_xml::Node::from_reader(xml.as_bytes()).unwrap();
+            let iface = node
+                .interfaces()
+                .iter()
+                .find(|i| i.name() == "org.zbus.Deferred")
+                .unwrap();
+            let ask = iface.methods().iter().find(|m| m.name() == "Ask").unwrap();
+            let out_args: Vec<_> = ask.args().iter().map(|arg| arg.ty().to_string()).collect();
+            assert_eq!(out_args, ["u", "s"]);
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
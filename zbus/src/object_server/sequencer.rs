@@ -0,0 +1,146 @@
+use event_listener::Event;
+use std::{
+    collections::BTreeSet,
+    sync::{Arc, Mutex},
+};
+
+/// Hands out tickets to method calls on arrival, and lets them through in the same order.
+///
+/// Used for [`DispatchMode::Serial`](super::DispatchMode::Serial) interfaces.
+#[derive(Debug, Default)]
+pub(crate) struct Sequencer {
+    state: Mutex<State>,
+    turn_changed: Event,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: u64,
+    serving: u64,
+    /// Tickets dropped before their turn came.
+    abandoned: BTreeSet<u64>,
+}
+
+impl Sequencer {
+    /// Take the next ticket.
+    pub(crate) fn ticket(self: &Arc<Self>) -> Ticket {
+        let mut state = self.state.lock().expect("lock poisoned");
+        let number = state.next;
+        state.next += 1;
+
+        Ticket {
+            sequencer: self.clone(),
+            number,
+        }
+    }
+}
+
+/// A place in the queue of a [`Sequencer`]. The turn passes to the next ticket once dropped.
+#[derive(Debug)]
+pub(crate) struct Ticket {
+    sequencer: Arc<Sequencer>,
+    number: u64,
+}
+
+impl Ticket {
+    /// Wait for the turn of this ticket.
+    pub(crate) async fn wait(&self) {
+        loop {
+            if self.is_turn() {
+                return;
+            }
+            let listener = self.sequencer.turn_changed.listen();
+            if self.is_turn() {
+                return;
+            }
+            listener.await;
+        }
+    }
+
+    fn is_turn(&self) -> bool {
+        self.sequencer.state.lock().expect("lock poisoned").serving == self.number
+    }
+}
+
+impl Drop for Ticket {
+    fn drop(&mut self) {
+        let mut state = self.sequencer.state.lock().expect("lock poisoned");
+        if state.serving != self.number {
+            state.abandoned.insert(self.number);
+
+            return;
+        }
+
+        let State {
+            serving, abandoned, ..
+        } = &mut *state;
+        *serving += 1;
+        while abandoned.remove(serving) {
+            *serving += 1;
+        }
+        drop(state);
+        self.sequencer.turn_changed.notify(usize::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{message::Message, Error};
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn serial_dispatch() {
+        use crate::{abstractions::timer::Timer, connection, utils::block_on};
+        use std::{sync::Mutex, time::Duration};
+
+        #[derive(Default)]
+        struct Log(Mutex<Vec<u32>>);
+        #[crate::interface(name = "org.zbus.Log", dispatch = "serial")]
+        impl Log {
+            async fn append(&self, n: u32) {
+                // Earlier calls take longer, so would complete last if dispatched concurrently.
+                Timer::after(Duration::from_millis(10 * (5 - n as u64))).await;
+                self.0.lock().unwrap().push(n);
+            }
+
+            fn entries(&self) -> Vec<u32> {
+                self.0.lock().unwrap().clone()
+            }
+        }
+
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Log", Log::default())?
+                .build()
+                .await?;
+            let client = connection::Builder::session()?.build().await?;
+            let dest = service.unique_name().unwrap().to_owned();
+
+            for n in 0..5u32 {
+                let msg = Message::method("/org/zbus/Log", "Append")?
+                    .destination(dest.clone())?
+                    .interface("org.zbus.Log")?
+                    .with_flags(crate::message::Flags::NoReplyExpected)?
+                    .build(&(n,))?;
+                client.send(&msg).await?;
+            }
+            let entries: Vec<u32> = client
+                .call_method(
+                    Some(dest),
+                    "/org/zbus/Log",
+                    Some("org.zbus.Log"),
+                    "Entries",
+                    &(),
+                )
+                .await?
+                .body()
+                .deserialize()?;
+            assert_eq!(entries, [0, 1, 2, 3, 4]);
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+}
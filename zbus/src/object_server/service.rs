@@ -0,0 +1,215 @@
+use async_trait::async_trait;
+use std::{
+    future::{poll_fn, Future},
+    io,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+use tower_service::Service;
+
+use crate::{
+    connection::WeakConnection,
+    fdo,
+    message::{Message, Type},
+    object_server::{Middleware, Next},
+    Connection, Error, Result,
+};
+
+/// A [`tower::Service`] dispatching method calls to the interfaces of an [`ObjectServer`].
+///
+/// The replies are sent by the interfaces, as when the object server dispatches the calls itself,
+/// so the response is `()`. Failing calls resolve to an [`Error::FDO`] error, which has not been
+/// replied to yet, and other messages than method calls to an `Error::Unsupported` error. The
+/// [middleware](crate::object_server::Middleware) of the object server isn't run.
+///
+/// This is the innermost service of a tower stack mounted with [`ObjectServer::add_service`], but
+/// it can also be used on its own, e.g to dispatch method calls received through another channel.
+///
+/// Only a weak reference to the connection is kept, and the calls fail with an I/O error once the
+/// connection is dropped.
+///
+/// [`tower::Service`]: https://docs.rs/tower/latest/tower/trait.Service.html
+/// [`ObjectServer`]: crate::ObjectServer
+/// [`ObjectServer::add_service`]: crate::ObjectServer::add_service
+#[derive(Clone, Debug)]
+pub struct DispatchService {
+    conn: WeakConnection,
+}
+
+impl DispatchService {
+    /// Create a service dispatching method calls to the object server of `conn`.
+    pub fn new(conn: &Connection) -> Self {
+        Self { conn: conn.into() }
+    }
+}
+
+impl Service<Message> for DispatchService {
+    type Response = ();
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, msg: Message) -> Self::Future {
+        let conn = self.conn.upgrade();
+
+        Box::pin(async move {
+            if msg.message_type() != Type::MethodCall {
+                return Err(Error::Unsupported);
+            }
+            let conn = conn.ok_or_else(|| {
+                Error::InputOutput(io::Error::from(io::ErrorKind::BrokenPipe).into())
+            })?;
+
+            let server = conn.object_server();
+
+            match server.dispatch_method_call_try(&conn, &msg).await {
+                Ok(r) => r,
+                Err(e) => Err(Error::FDO(Box::new(e))),
+            }
+        })
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A tower stack mounted on the object server, see [`ObjectServer::add_service`].
+///
+/// [`ObjectServer::add_service`]: crate::ObjectServer::add_service
+pub(crate) struct ServiceMiddleware<S>(Mutex<S>);
+
+impl<S> ServiceMiddleware<S> {
+    pub(crate) fn new(service: S) -> Self {
+        // Services are usually not `Sync`, only cloned.
+        Self(Mutex::new(service))
+    }
+}
+
+#[async_trait]
+impl<S> Middleware for ServiceMiddleware<S>
+where
+    S: Service<Message, Response = ()> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    async fn handle(&self, msg: &Message, _next: Next<'_>) -> Result<()> {
+        // Like tower's `Buffer`, the clones share the state of the stack, if any.
+        let mut service = self.0.lock().expect("lock poisoned").clone();
+        poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .map_err(|e| error(e.into()))?;
+
+        service.call(msg.clone()).await.map_err(|e| error(e.into()))
+    }
+}
+
+// The error to reply with, for errors of the layers. Those of zbus are kept as is.
+fn error(e: BoxError) -> Error {
+    match e.downcast::<Error>() {
+        Ok(e) => *e,
+        Err(e) => match e.downcast::<fdo::Error>() {
+            Ok(e) => Error::FDO(e),
+            Err(e) => Error::FDO(Box::new(fdo::Error::Failed(e.to_string()))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use event_listener::Event;
+    use futures_util::future::join;
+    use ntest::timeout;
+    use std::sync::Arc;
+    use test_log::test;
+    use tower::ServiceBuilder;
+
+    use super::DispatchService;
+    use crate::{connection, fdo, interface, object_server::MiddlewareScope, utils::block_on};
+
+    struct Gate {
+        entered: Arc<Event>,
+        open: Arc<Event>,
+    }
+
+    #[interface(name = "org.zbus.Gate")]
+    impl Gate {
+        async fn wait(&self) {
+            let open = self.open.listen();
+            self.entered.notify(usize::MAX);
+            open.await;
+        }
+
+        fn ping(&self) {}
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn service() {
+        block_on(async {
+            let (entered, open) = (Arc::new(Event::new()), Arc::new(Event::new()));
+            let gate = Gate {
+                entered: entered.clone(),
+                open: open.clone(),
+            };
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Gate", gate)?
+                .build()
+                .await?;
+            let stack = ServiceBuilder::new()
+                .load_shed()
+                .concurrency_limit(1)
+                .service(DispatchService::new(&service));
+            service
+                .object_server()
+                .add_service(
+                    MiddlewareScope::Interface("org.zbus.Gate".try_into()?),
+                    stack,
+                )
+                .await;
+
+            let client = connection::Builder::session()?.build().await?;
+            let call = |method: &'static str| {
+                let client = client.clone();
+                let dest = service.unique_name().unwrap().to_owned();
+                async move {
+                    client
+                        .call_method(
+                            Some(dest),
+                            "/org/zbus/Gate",
+                            Some("org.zbus.Gate"),
+                            method,
+                            &(),
+                        )
+                        .await
+                }
+            };
+            call("Ping").await?;
+
+            // The limit is reached while `Wait` is in progress.
+            let listener = entered.listen();
+            let shed = async {
+                listener.await;
+                let err = call("Ping").await.unwrap_err();
+                open.notify(usize::MAX);
+
+                err
+            };
+            let (waited, err) = join(call("Wait"), shed).await;
+            waited?;
+            assert!(matches!(fdo::Error::from(err), fdo::Error::Failed(_)));
+
+            // The errors of the dispatch are replied as is.
+            let err = call("Nope").await.unwrap_err();
+            assert!(matches!(
+                fdo::Error::from(err),
+                fdo::Error::UnknownMethod(_)
+            ));
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
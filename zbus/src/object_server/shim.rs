@@ -0,0 +1,682 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use zbus_names::{InterfaceName, MemberName};
+use zvariant::{OwnedValue, Value};
+
+use crate::{
+    async_lock::RwLock,
+    fdo::{self, Properties},
+    message::{Header, Message},
+    object_server::{DispatchMode, DispatchResult, EmitsChangedSignal, Interface, SignalContext},
+    Connection, Error, ObjectServer, Result,
+};
+
+/// An older version of an interface, served on top of the current one.
+///
+/// Services evolving their API often need to keep serving the previous version of an interface
+/// (e.g `org.example.Thermostat1`) next to the current one (e.g `org.example.Thermostat2`), so
+/// that existing clients keep working. Instead of implementing both, the shim declares which
+/// members of the old version map to which members of the current one, and forwards the method
+/// calls and property accesses to the instance of the current version registered at the same
+/// path. Both versions are then backed by the same state object, including the property values.
+///
+/// The introspection data of the shim is derived from the one of the current version, with the
+/// members renamed. Hence, mapped members must have the same arguments and types in both
+/// versions.
+///
+/// Signals are not forwarded: the ones of the old version have to be emitted explicitly, if
+/// needed. `PropertiesChanged` signals are taken care of for properties set through the shim,
+/// and [`ObjectServer::properties_changed`] signals the changes on all versions.
+///
+/// Use [`ObjectServer::at_shim`] or [`Object::shim`](super::Object::shim) to serve it.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{interface, object_server::InterfaceShim, Connection};
+///
+/// struct Thermostat {
+///     celsius: f64,
+/// }
+///
+/// #[interface(name = "org.example.Thermostat2")]
+/// impl Thermostat {
+///     fn set_target(&mut self, celsius: f64) {
+///         self.celsius = celsius;
+///     }
+///
+///     #[zbus(property)]
+///     fn celsius(&self) -> f64 {
+///         self.celsius
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// let connection = Connection::session().await?;
+/// let object_server = connection.object_server();
+/// let path = "/org/example/Thermostat";
+/// object_server.at(path, Thermostat { celsius: 20. }).await?;
+///
+/// // The first version called them differently.
+/// let shim = InterfaceShim::new("org.example.Thermostat1", "org.example.Thermostat2")?
+///     .method("Set", "SetTarget")?
+///     .property("Temperature", "Celsius")?;
+/// object_server.at_shim(path, shim).await?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct InterfaceShim {
+    name: InterfaceName<'static>,
+    target: InterfaceName<'static>,
+    methods: Vec<(MemberName<'static>, MemberName<'static>)>,
+    properties: Vec<(String, String)>,
+}
+
+impl InterfaceShim {
+    /// Create a shim named `name`, forwarding to the interface `target`.
+    pub fn new<N, T>(name: N, target: T) -> Result<Self>
+    where
+        N: TryInto<InterfaceName<'static>>,
+        N::Error: Into<Error>,
+        T: TryInto<InterfaceName<'static>>,
+        T::Error: Into<Error>,
+    {
+        Ok(Self {
+            name: name.try_into().map_err(Into::into)?,
+            target: target.try_into().map_err(Into::into)?,
+            methods: vec![],
+            properties: vec![],
+        })
+    }
+
+    /// The name of the interface served by the shim.
+    pub fn name(&self) -> &InterfaceName<'static> {
+        &self.name
+    }
+
+    /// The name of the interface the shim forwards to.
+    pub fn target(&self) -> &InterfaceName<'static> {
+        &self.target
+    }
+
+    /// Serve the method `name`, by calling the method `target` of the target interface.
+    pub fn method<N, T>(mut self, name: N, target: T) -> Result<Self>
+    where
+        N: TryInto<MemberName<'static>>,
+        N::Error: Into<Error>,
+        T: TryInto<MemberName<'static>>,
+        T::Error: Into<Error>,
+    {
+        self.methods.push((
+            name.try_into().map_err(Into::into)?,
+            target.try_into().map_err(Into::into)?,
+        ));
+
+        Ok(self)
+    }
+
+    /// Serve the property `name`, backed by the property `target` of the target interface.
+    pub fn property(mut self, name: &str, target: &str) -> Result<Self> {
+        // Property names follow the same rules as member names.
+        MemberName::try_from(name)?;
+        MemberName::try_from(target)?;
+        self.properties.push((name.to_string(), target.to_string()));
+
+        Ok(self)
+    }
+}
+
+/// The dispatching wrapper of an [`InterfaceShim`], bound to the instance of its target.
+pub(crate) struct InterfaceShimImpl {
+    shim: InterfaceShim,
+    target: Arc<RwLock<dyn Interface>>,
+    introspection: String,
+    dispatch_mode: DispatchMode,
+    emits_changed_signal: HashMap<String, EmitsChangedSignal>,
+    polkit_actions: HashMap<String, String>,
+}
+
+impl InterfaceShimImpl {
+    /// Bind `shim` to `target`, whose instance is `iface`.
+    ///
+    /// # Errors
+    ///
+    /// If one of the mapped members doesn't exist in `iface`, `Error::Failure` error is returned.
+    pub(crate) fn new(
+        shim: InterfaceShim,
+        target: Arc<RwLock<dyn Interface>>,
+        iface: &dyn Interface,
+    ) -> Result<Self> {
+        let mut target_xml = String::new();
+        iface.introspect_to_writer(&mut target_xml, 0);
+        let members = Member::parse_all(&target_xml);
+
+        let mut introspection = String::new();
+        let methods = shim
+            .methods
+            .iter()
+            .map(|(n, t)| ("method", n.as_str(), t.as_str()));
+        let properties = shim
+            .properties
+            .iter()
+            .map(|(n, t)| ("property", n.as_str(), t.as_str()));
+        for (kind, name, target_name) in methods.chain(properties) {
+            let member = members
+                .iter()
+                .find(|m| m.kind == kind && m.name == target_name)
+                .ok_or_else(|| {
+                    Error::Failure(format!(
+                        "Interface `{}` has no {kind} `{target_name}`",
+                        shim.target
+                    ))
+                })?;
+            member.write_renamed(&mut introspection, name);
+        }
+
+        let emits_changed_signal = shim
+            .properties
+            .iter()
+            .map(|(name, target)| (name.clone(), iface.emits_changed_signal(target)))
+            .collect();
+        let polkit_actions = shim
+            .methods
+            .iter()
+            .filter_map(|(name, target)| {
+                iface
+                    .polkit_action(target)
+                    .map(|action| (name.to_string(), action.to_string()))
+            })
+            .collect();
+
+        Ok(Self {
+            dispatch_mode: iface.dispatch_mode(),
+            shim,
+            target,
+            introspection,
+            emits_changed_signal,
+            polkit_actions,
+        })
+    }
+
+    pub(crate) fn name(&self) -> &InterfaceName<'static> {
+        &self.shim.name
+    }
+
+    /// The name of the shim property backed by the target property `target`, if any.
+    pub(crate) fn property_for_target(&self, target: &str) -> Option<&str> {
+        self.shim
+            .properties
+            .iter()
+            .find(|(_, t)| t == target)
+            .map(|(name, _)| name.as_str())
+    }
+
+    fn target_property(&self, name: &str) -> Option<&str> {
+        self.shim
+            .properties
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, target)| target.as_str())
+    }
+
+    fn target_method(&self, name: &str) -> Option<&MemberName<'static>> {
+        self.shim
+            .methods
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, target)| target)
+    }
+}
+
+#[async_trait]
+impl Interface for InterfaceShimImpl {
+    fn name() -> InterfaceName<'static> {
+        // Never used since shims are registered under their runtime name.
+        InterfaceName::from_static_str_unchecked("org.zbus.InterfaceShim")
+    }
+
+    async fn get(&self, property_name: &str) -> Option<fdo::Result<OwnedValue>> {
+        let target = self.target_property(property_name)?;
+
+        self.target.read().await.get(target).await
+    }
+
+    async fn get_all(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let mut all = self.target.read().await.get_all().await?;
+
+        Ok(self
+            .shim
+            .properties
+            .iter()
+            .filter_map(|(name, target)| Some((name.clone(), all.remove(target)?)))
+            .collect())
+    }
+
+    async fn get_for_caller(
+        &self,
+        property_name: &str,
+        server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> Option<fdo::Result<OwnedValue>> {
+        let target = self.target_property(property_name)?;
+
+        self.target
+            .read()
+            .await
+            .get_for_caller(target, server, connection, header)
+            .await
+    }
+
+    async fn get_all_for_caller(
+        &self,
+        server: &ObjectServer,
+        connection: &Connection,
+        header: &Header<'_>,
+    ) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let mut all = self
+            .target
+            .read()
+            .await
+            .get_all_for_caller(server, connection, header)
+            .await?;
+
+        Ok(self
+            .shim
+            .properties
+            .iter()
+            .filter_map(|(name, target)| Some((name.clone(), all.remove(target)?)))
+            .collect())
+    }
+
+    fn set<'call>(
+        &'call self,
+        property_name: &'call str,
+        value: &'call Value<'_>,
+        ctxt: &'call SignalContext<'_>,
+    ) -> DispatchResult<'call> {
+        let Some(target) = self.target_property(property_name) else {
+            return DispatchResult::NotFound;
+        };
+
+        DispatchResult::Async(Box::pin(async move {
+            let iface = self.target.read().await;
+            let res = match iface.set(target, value, ctxt) {
+                DispatchResult::Async(f) => Some(Some(f.await)),
+                DispatchResult::NotFound => Some(None),
+                DispatchResult::RequiresMut => None,
+            };
+            drop(iface);
+            let res = match res {
+                Some(res) => res,
+                None => self
+                    .target
+                    .write()
+                    .await
+                    .set_mut(target, value, ctxt)
+                    .await
+                    .map(|res| res.map_err(Into::into)),
+            };
+            res.ok_or_else(|| {
+                fdo::Error::UnknownProperty(format!("Unknown property '{property_name}'"))
+            })??;
+
+            // The target only signals the change of its own property.
+            let emits = self
+                .emits_changed_signal
+                .get(property_name)
+                .copied()
+                .unwrap_or_default();
+            let (changed, invalidated) = match emits {
+                EmitsChangedSignal::True => (HashMap::from([(property_name, value)]), vec![]),
+                EmitsChangedSignal::Invalidates => (HashMap::new(), vec![property_name]),
+                EmitsChangedSignal::False | EmitsChangedSignal::Const => return Ok(()),
+            };
+            Properties::properties_changed(ctxt, self.shim.name.clone(), &changed, &invalidated)
+                .await
+        }))
+    }
+
+    async fn set_mut(
+        &mut self,
+        _property_name: &str,
+        _value: &Value<'_>,
+        _ctxt: &SignalContext<'_>,
+    ) -> Option<fdo::Result<()>> {
+        // Handled by `set`.
+        None
+    }
+
+    fn call<'call>(
+        &'call self,
+        server: &'call ObjectServer,
+        connection: &'call Connection,
+        msg: &'call Message,
+        name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        let Some(target) = self.target_method(&name) else {
+            return DispatchResult::NotFound;
+        };
+
+        DispatchResult::Async(Box::pin(async move {
+            let iface = self.target.read().await;
+            if let DispatchResult::Async(f) = iface.call(server, connection, msg, target.as_ref()) {
+                return f.await;
+            }
+            drop(iface);
+            let mut iface = self.target.write().await;
+            if let DispatchResult::Async(f) =
+                iface.call_mut(server, connection, msg, target.as_ref())
+            {
+                return f.await;
+            }
+            drop(iface);
+
+            let err = fdo::Error::UnknownMethod(format!("Unknown method '{name}'"));
+            connection
+                .reply_dbus_error(&msg.header(), err)
+                .await
+                .map(|_| ())
+        }))
+    }
+
+    fn call_mut<'call>(
+        &'call mut self,
+        _server: &'call ObjectServer,
+        _connection: &'call Connection,
+        _msg: &'call Message,
+        _name: MemberName<'call>,
+    ) -> DispatchResult<'call> {
+        // All methods are handled by `call`.
+        DispatchResult::NotFound
+    }
+
+    fn polkit_action(&self, method: &str) -> Option<&str> {
+        self.polkit_actions.get(method).map(String::as_str)
+    }
+
+    fn dispatch_mode(&self) -> DispatchMode {
+        self.dispatch_mode
+    }
+
+    fn emits_changed_signal(&self, property: &str) -> EmitsChangedSignal {
+        self.emits_changed_signal
+            .get(property)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn introspect_to_writer(&self, writer: &mut dyn Write, level: usize) {
+        writeln!(
+            writer,
+            r#"{:indent$}<interface name="{}">"#,
+            "",
+            self.shim.name,
+            indent = level
+        )
+        .unwrap();
+        for line in self.introspection.lines() {
+            if line.is_empty() {
+                writeln!(writer).unwrap();
+            } else {
+                writeln!(writer, "{:indent$}{line}", "", indent = level).unwrap();
+            }
+        }
+        writeln!(writer, "{:indent$}</interface>", "", indent = level).unwrap();
+    }
+}
+
+impl fmt::Debug for InterfaceShimImpl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterfaceShimImpl")
+            .field("shim", &self.shim)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A member element of introspection XML, as written by [`Interface::introspect_to_writer`].
+struct Member<'x> {
+    kind: &'x str,
+    name: &'x str,
+    /// The lines of the element, preceded by its documentation comment, if any.
+    lines: Vec<&'x str>,
+}
+
+impl<'x> Member<'x> {
+    /// Split the XML of an interface into its members.
+    ///
+    /// This relies on each element being on its own line, which is the case for the XML written
+    /// by the `interface` macro and by [`super::DynamicInterface`].
+    fn parse_all(xml: &'x str) -> Vec<Self> {
+        let mut members = vec![];
+        let mut current: Option<Member<'x>> = None;
+        let mut comment = vec![];
+        let mut in_comment = false;
+        for line in xml.lines() {
+            let trimmed = line.trim();
+            if let Some(member) = &mut current {
+                member.lines.push(line);
+                if trimmed.strip_prefix("</").and_then(|s| s.strip_suffix('>')) == Some(member.kind)
+                {
+                    members.extend(current.take());
+                }
+            } else if in_comment {
+                comment.push(line);
+                in_comment = !trimmed.ends_with("-->");
+            } else if trimmed.starts_with("<!--") {
+                comment = vec![line];
+                in_comment = !trimmed.ends_with("-->");
+            } else if let Some((kind, name)) = Self::parse_start(trimmed) {
+                let mut lines = std::mem::take(&mut comment);
+                lines.push(line);
+                let member = Member { kind, name, lines };
+                if trimmed.ends_with("/>") {
+                    members.push(member);
+                } else {
+                    current = Some(member);
+                }
+            } else {
+                comment.clear();
+            }
+        }
+
+        members
+    }
+
+    fn parse_start(line: &'x str) -> Option<(&'x str, &'x str)> {
+        let (kind, rest) = line.strip_prefix('<')?.split_once(' ')?;
+        if !["method", "property", "signal"].contains(&kind) {
+            return None;
+        }
+        let name = rest.strip_prefix("name=\"")?.split('"').next()?;
+
+        Some((kind, name))
+    }
+
+    /// Write the element, named `name`.
+    fn write_renamed(&self, writer: &mut String, name: &str) {
+        let mut renamed = false;
+        for line in &self.lines {
+            if !renamed && line.trim_start().starts_with(&format!("<{} ", self.kind)) {
+                let line = line.replacen(
+                    &format!("name=\"{}\"", self.name),
+                    &format!("name=\"{name}\""),
+                    1,
+                );
+                writeln!(writer, "{line}").unwrap();
+                renamed = true;
+            } else {
+                writeln!(writer, "{line}").unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connection, utils::block_on};
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    struct Thermostat {
+        celsius: f64,
+        mode: String,
+    }
+
+    /// The current version.
+    #[crate::interface(name = "org.zbus.Thermostat2")]
+    impl Thermostat {
+        /// Set the target temperature.
+        fn set_target(&mut self, celsius: f64) -> f64 {
+            let previous = self.celsius;
+            self.celsius = celsius;
+
+            previous
+        }
+
+        fn boost(&self) {}
+
+        #[zbus(property)]
+        fn celsius(&self) -> f64 {
+            self.celsius
+        }
+
+        #[zbus(property(emits_changed_signal = "invalidates"))]
+        fn mode(&self) -> &str {
+            &self.mode
+        }
+
+        #[zbus(property)]
+        fn set_mode(&mut self, mode: String) {
+            self.mode = mode;
+        }
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn interface_shim() {
+        block_on(test_interface_shim()).unwrap();
+    }
+
+    async fn test_interface_shim() -> Result<()> {
+        let path = "/org/zbus/Thermostat";
+        let service = connection::Builder::session()?
+            .serve_at(
+                path,
+                Thermostat {
+                    celsius: 20.,
+                    mode: "eco".into(),
+                },
+            )?
+            .build()
+            .await?;
+        let object_server = service.object_server();
+
+        let shim = InterfaceShim::new("org.zbus.Thermostat1", "org.zbus.Thermostat2")?
+            .method("Set", "SetTarget")?
+            .property("Temperature", "Celsius")?
+            .property("Mode", "Mode")?;
+        // Mapped members have to exist.
+        let broken = shim.clone().method("Reset", "Reset")?;
+        assert!(matches!(
+            object_server.at_shim(path, broken).await,
+            Err(Error::Failure(_))
+        ));
+        assert!(matches!(
+            object_server
+                .at_shim("/org/zbus/Nothing", shim.clone())
+                .await,
+            Err(Error::InterfaceNotFound)
+        ));
+        assert!(object_server.at_shim(path, shim.clone()).await?);
+        assert!(!object_server.at_shim(path, shim).await?);
+
+        let client = Connection::session().await?;
+        let dest = service.unique_name().unwrap().to_owned();
+        let v1 = crate::Proxy::new(&client, dest.clone(), path, "org.zbus.Thermostat1").await?;
+        let v2 = crate::Proxy::new(&client, dest, path, "org.zbus.Thermostat2").await?;
+
+        // Calls through either version act on the same state.
+        assert_eq!(v1.call::<_, _, f64>("Set", &(22.5,)).await?, 20.);
+        assert_eq!(v2.get_property::<f64>("Celsius").await?, 22.5);
+        assert_eq!(v1.get_property::<f64>("Temperature").await?, 22.5);
+        let err = v1.call::<_, _, ()>("Boost", &()).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::MethodError(name, _, _) if name.as_str().ends_with("UnknownMethod")),
+            "{err}"
+        );
+
+        // Setting a property through the shim signals the change on both versions.
+        let mut v1_changes = v1.receive_property_changed::<String>("Mode").await;
+        let mut v2_changes = v2.receive_property_changed::<String>("Mode").await;
+        v1.set_property("Mode", "comfort").await.unwrap();
+        let changed = v1_changes.next().await.unwrap();
+        assert_eq!(changed.get().await?, "comfort");
+        let changed = v2_changes.next().await.unwrap();
+        assert_eq!(changed.get().await?, "comfort");
+
+        let props = fdo::PropertiesProxy::builder(&client)
+            .destination(service.unique_name().unwrap().to_owned())?
+            .path(path)?
+            .build()
+            .await?;
+        let all = props
+            .get_all(Some("org.zbus.Thermostat1".try_into()?).into())
+            .await?;
+        let mut names: Vec<_> = all.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["Mode", "Temperature"]);
+
+        // Changes of the shared state are signaled on all versions, under their own names.
+        let mut changes = props.receive_properties_changed().await?;
+        object_server
+            .interface::<_, Thermostat>(path)
+            .await?
+            .get_mut()
+            .await
+            .celsius = 18.;
+        object_server.properties_changed(path, &["Celsius"]).await?;
+        let mut signals = HashMap::new();
+        for _ in 0..2 {
+            let signal = changes.next().await.unwrap();
+            let args = signal.args()?;
+            let changed: Vec<_> = args
+                .changed_properties()
+                .iter()
+                .map(|(name, value)| (name.to_string(), f64::try_from(value).unwrap()))
+                .collect();
+            signals.insert(args.interface_name.to_string(), changed);
+        }
+        assert_eq!(
+            signals["org.zbus.Thermostat1"],
+            [("Temperature".to_string(), 18.)]
+        );
+        assert_eq!(
+            signals["org.zbus.Thermostat2"],
+            [("Celsius".to_string(), 18.)]
+        );
+
+        let xml = v1.introspect().await?;
+        let start = xml
+            .find(r#"<interface name="org.zbus.Thermostat1">"#)
+            .unwrap();
+        let xml = &xml[start..];
+        let xml = &xml[..xml.find("</interface>").unwrap()];
+        assert!(xml.contains(r#"<method name="Set">"#));
+        assert!(xml.contains("Set the target temperature."));
+        assert!(xml.contains(r#"<property name="Temperature" type="d" access="read"/>"#));
+        assert!(!xml.contains("Boost"));
+
+        Ok(())
+    }
+}
@@ -6,8 +6,8 @@ use crate::{zvariant::ObjectPath, Connection, Error, Result};
 ///
 /// For signal emission using the high-level API, you'll need instances of this type.
 ///
-/// See [`crate::InterfaceRef::signal_context`] and [`crate::interface`]
-/// documentation for details and examples of this type in use.
+/// See [`crate::InterfaceRef::signal_context`], [`Connection::signal_context`] and
+/// [`crate::interface`] documentation for details and examples of this type in use.
 #[derive(Clone, Debug)]
 pub struct SignalContext<'s> {
     conn: Connection,
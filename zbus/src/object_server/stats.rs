@@ -0,0 +1,219 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use zbus_names::{BusName, OwnedInterfaceName, OwnedUniqueName};
+
+use crate::message::Message;
+
+/// Statistics about the method calls to an interface, over all the objects implementing it.
+///
+/// See [`ObjectServer::stats`](crate::ObjectServer::stats).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InterfaceStats {
+    calls: u64,
+    errors: u64,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+impl InterfaceStats {
+    /// The number of method calls handled.
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// The number of method calls that were replied to with an error.
+    pub fn errors(&self) -> u64 {
+        self.errors
+    }
+
+    /// The total time spent handling method calls.
+    pub fn total_latency(&self) -> Duration {
+        self.total_latency
+    }
+
+    /// The average time spent handling a method call.
+    pub fn mean_latency(&self) -> Duration {
+        match self.calls {
+            0 => Duration::ZERO,
+            calls => Duration::from_nanos((self.total_latency.as_nanos() / calls as u128) as u64),
+        }
+    }
+
+    /// The longest time spent handling a method call.
+    pub fn max_latency(&self) -> Duration {
+        self.max_latency
+    }
+}
+
+/// A method call being dispatched, identified by its sender and serial number.
+type CallId = (Option<OwnedUniqueName>, NonZeroU32);
+
+/// Gathers the [`InterfaceStats`] of the object server.
+#[derive(Debug, Default)]
+pub(crate) struct Stats {
+    interfaces: Mutex<HashMap<OwnedInterfaceName, InterfaceStats>>,
+    // The method calls being dispatched, and whether an error was replied to them.
+    in_flight: Mutex<HashMap<CallId, bool>>,
+}
+
+impl Stats {
+    /// Start tracking the dispatch of the method call `msg`.
+    pub(crate) fn start(&self, msg: &Message) -> Option<Instant> {
+        let hdr = msg.header();
+        hdr.interface()?;
+        self.in_flight
+            .lock()
+            .expect("lock poisoned")
+            .insert(call_id(msg), false);
+
+        Some(Instant::now())
+    }
+
+    /// Record that the error reply `reply` was sent.
+    ///
+    /// Error replies to method calls that aren't being dispatched are ignored.
+    pub(crate) fn error_replied(&self, reply: &Message) {
+        let hdr = reply.header();
+        let Some(serial) = hdr.reply_serial() else {
+            return;
+        };
+        let destination = match hdr.destination() {
+            Some(BusName::Unique(name)) => Some(name.to_owned().into()),
+            Some(BusName::WellKnown(_)) => return,
+            None => None,
+        };
+        let id = (destination, serial);
+        if let Some(failed) = self.in_flight.lock().expect("lock poisoned").get_mut(&id) {
+            *failed = true;
+        }
+    }
+
+    /// Stop tracking the dispatch of the method call `msg`, started at `start`.
+    ///
+    /// The call is only accounted for if `record` is `true`.
+    pub(crate) fn finish(&self, msg: &Message, start: Instant, record: bool) {
+        let latency = start.elapsed();
+        let hdr = msg.header();
+        let Some(interface) = hdr.interface() else {
+            return;
+        };
+        let failed = self
+            .in_flight
+            .lock()
+            .expect("lock poisoned")
+            .remove(&call_id(msg))
+            .unwrap_or_default();
+        if !record {
+            return;
+        }
+
+        let mut interfaces = self.interfaces.lock().expect("lock poisoned");
+        let stats = interfaces.entry(interface.to_owned().into()).or_default();
+        stats.calls += 1;
+        if failed {
+            stats.errors += 1;
+        }
+        stats.total_latency += latency;
+        stats.max_latency = stats.max_latency.max(latency);
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<OwnedInterfaceName, InterfaceStats> {
+        self.interfaces.lock().expect("lock poisoned").clone()
+    }
+}
+
+fn call_id(call: &Message) -> CallId {
+    let hdr = call.header();
+
+    (
+        hdr.sender().map(|sender| sender.to_owned().into()),
+        hdr.primary().serial_num(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fdo;
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn object_server_stats() {
+        use crate::blocking;
+        use std::{thread::sleep, time::Duration};
+
+        struct Counter;
+        #[crate::interface(name = "org.zbus.Counter")]
+        impl Counter {
+            fn ping(&self) {}
+
+            fn fail(&self) -> fdo::Result<()> {
+                Err(fdo::Error::Failed("Failed on purpose".into()))
+            }
+        }
+
+        let path = "/org/zbus/Counter";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(path, Counter)
+            .unwrap()
+            .build()
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let call = |interface, method| {
+            conn.call_method(service.unique_name(), path, Some(interface), method, &())
+        };
+
+        call("org.zbus.Counter", "Ping").unwrap();
+        call("org.zbus.Counter", "Ping").unwrap();
+        call("org.zbus.Counter", "Fail").unwrap_err();
+        call("org.zbus.Counter", "Frobnicate").unwrap_err();
+        // Calls to unknown interfaces aren't accounted for.
+        call("org.zbus.Unknown", "Ping").unwrap_err();
+
+        // The stats of a call are recorded right after its reply is sent.
+        let object_server = service.object_server();
+        let stats = loop {
+            let stats = object_server.stats();
+            match stats.get("org.zbus.Counter") {
+                Some(counter) if counter.calls() == 4 => break stats,
+                _ => sleep(Duration::from_millis(10)),
+            }
+        };
+        assert_eq!(stats.len(), 1);
+        let counter = &stats["org.zbus.Counter"];
+        assert_eq!(counter.errors(), 2);
+        assert!(counter.max_latency() <= counter.total_latency());
+        assert!(counter.mean_latency() <= counter.max_latency());
+
+        #[cfg(feature = "debug-stats")]
+        {
+            use std::collections::HashMap;
+            use zvariant::OwnedValue;
+
+            object_server.at("/org/zbus/Stats", fdo::Stats).unwrap();
+            let reply = conn
+                .call_method(
+                    service.unique_name(),
+                    "/org/zbus/Stats",
+                    Some("org.freedesktop.DBus.Debug.Stats"),
+                    "GetStats",
+                    &(),
+                )
+                .unwrap();
+            let stats: HashMap<String, OwnedValue> = reply.body().deserialize().unwrap();
+            let counter: HashMap<String, u64> = stats["org.zbus.Counter"]
+                .try_clone()
+                .unwrap()
+                .try_into()
+                .unwrap();
+            assert_eq!(counter["Calls"], 4);
+            assert_eq!(counter["Errors"], 2);
+        }
+    }
+}
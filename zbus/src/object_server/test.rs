@@ -0,0 +1,243 @@
+//! Testing interfaces without a bus.
+//!
+//! Testing an interface implementation usually involves a bus, and two connections to it: one
+//! serving the interface, and one calling it. [`TestServer`] instead dispatches the method calls
+//! directly to its object server, through an in-memory channel, and hands back the replies. The
+//! tests don't depend on a running bus, and the replies only depend on the interfaces.
+//!
+//! # Example
+//!
+//! ```
+//! # use std::error::Error;
+//! use zbus::{interface, message::Message, object_server::test::TestServer};
+//!
+//! struct Calculator;
+//!
+//! #[interface(name = "org.example.Calculator")]
+//! impl Calculator {
+//!     fn add(&self, a: u32, b: u32) -> u32 {
+//!         a + b
+//!     }
+//! }
+//!
+//! # zbus::block_on(async {
+//! let server = TestServer::new().await?;
+//! server
+//!     .object_server()
+//!     .at("/org/example/Calculator", Calculator)
+//!     .await?;
+//!
+//! let call = Message::method("/org/example/Calculator", "Add")?
+//!     .interface("org.example.Calculator")?
+//!     .build(&(2u32, 3u32))?;
+//! let reply = server.call(&call).await?;
+//! assert_eq!(reply.body().deserialize::<u32>()?, 5);
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! # })?;
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! ```
+
+use event_listener::Event;
+use futures_util::StreamExt;
+use serde::Serialize;
+use static_assertions::assert_impl_all;
+use zbus_names::{InterfaceName, MemberName};
+use zvariant::{DynamicType, ObjectPath};
+
+use crate::{
+    connection,
+    message::{Flags, Message, Type},
+    Connection, Error, MessageStream, ObjectServer, Result,
+};
+
+/// An object server to test interfaces with, without a bus.
+///
+/// The method calls are sent on a connection to itself, whose messages never leave the process.
+/// As there's no bus, the calls have no sender, and the caller credentials are those of the
+/// current process. File descriptors can't be passed.
+///
+/// The signals emitted by the interfaces can be received through a [`MessageStream`] on
+/// [`TestServer::connection`].
+///
+/// See the [module documentation](self) for an example.
+#[derive(Debug)]
+pub struct TestServer {
+    conn: Connection,
+}
+
+assert_impl_all!(TestServer: Send, Sync, Unpin);
+
+impl TestServer {
+    /// Create a server without any object.
+    pub async fn new() -> Result<Self> {
+        let conn = connection::Builder::loopback().build().await?;
+        let started_event = Event::new();
+        let listener = started_event.listen();
+        conn.sync_object_server(true, Some(started_event));
+        listener.await;
+
+        Ok(Self { conn })
+    }
+
+    /// The object server to register the interfaces to test on.
+    pub fn object_server(&self) -> &ObjectServer {
+        self.conn.sync_object_server(true, None).inner()
+    }
+
+    /// The connection the method calls are dispatched from.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Dispatch the method call `msg`, and return the reply.
+    ///
+    /// Unlike [`Connection::call_method`], error replies are returned as is.
+    ///
+    /// # Errors
+    ///
+    /// If `msg` isn't a method call, or it doesn't expect a reply, `Error::Unsupported` error is
+    /// returned.
+    pub async fn call(&self, msg: &Message) -> Result<Message> {
+        if msg.message_type() != Type::MethodCall
+            || msg
+                .primary_header()
+                .flags()
+                .contains(Flags::NoReplyExpected)
+        {
+            return Err(Error::Unsupported);
+        }
+        let serial = msg.primary_header().serial_num();
+        let mut stream = MessageStream::from(&self.conn);
+        self.conn.send(msg).await?;
+
+        while let Some(reply) = stream.next().await {
+            let reply = reply?;
+            if matches!(reply.message_type(), Type::MethodReturn | Type::Error)
+                && reply.header().reply_serial() == Some(serial)
+            {
+                return Ok(reply);
+            }
+        }
+
+        Err(Error::InputOutput(
+            std::io::Error::from(std::io::ErrorKind::BrokenPipe).into(),
+        ))
+    }
+
+    /// Dispatch a method call built from the given arguments, and return the reply.
+    ///
+    /// See [`TestServer::call`] for details.
+    pub async fn call_method<'p, 'i, 'm, P, I, M, B>(
+        &self,
+        path: P,
+        interface: Option<I>,
+        method_name: M,
+        body: &B,
+    ) -> Result<Message>
+    where
+        P: TryInto<ObjectPath<'p>>,
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        P::Error: Into<Error>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+        B: Serialize + DynamicType,
+    {
+        let mut builder = Message::method(path, method_name)?;
+        if let Some(interface) = interface {
+            builder = builder.interface(interface)?;
+        }
+        let msg = builder.build(body)?;
+
+        self.call(&msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::TestServer;
+    use crate::{
+        interface,
+        message::{Message, Type},
+        object_server::SignalContext,
+        utils::block_on,
+        Error, MessageStream,
+    };
+
+    #[derive(Default)]
+    struct Counter(u32);
+
+    #[interface(name = "org.zbus.Counter")]
+    impl Counter {
+        async fn increment(
+            &mut self,
+            #[zbus(signal_context)] ctxt: SignalContext<'_>,
+        ) -> crate::fdo::Result<u32> {
+            self.0 += 1;
+            Self::incremented(&ctxt, self.0).await?;
+
+            Ok(self.0)
+        }
+
+        #[zbus(signal)]
+        async fn incremented(ctxt: &SignalContext<'_>, count: u32) -> crate::Result<()>;
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn test_server() {
+        block_on(async {
+            let server = TestServer::new().await?;
+            server
+                .object_server()
+                .at("/org/zbus/Counter", Counter::default())
+                .await?;
+            let mut signals = MessageStream::from(server.connection())
+                .filter(|m| std::future::ready(m.as_ref().unwrap().message_type() == Type::Signal));
+
+            for expected in 1..=3u32 {
+                let reply = server
+                    .call_method(
+                        "/org/zbus/Counter",
+                        Some("org.zbus.Counter"),
+                        "Increment",
+                        &(),
+                    )
+                    .await?;
+                assert_eq!(reply.body().deserialize::<u32>()?, expected);
+                let signal = signals.next().await.unwrap()?;
+                assert_eq!(signal.header().member().unwrap(), "Incremented");
+                assert_eq!(signal.body().deserialize::<u32>()?, expected);
+            }
+
+            // Errors are replies like any other.
+            let reply = server
+                .call_method(
+                    "/org/zbus/Nothing",
+                    Some("org.zbus.Counter"),
+                    "Increment",
+                    &(),
+                )
+                .await?;
+            assert_eq!(reply.message_type(), Type::Error);
+            assert_eq!(
+                reply.header().error_name().unwrap(),
+                "org.freedesktop.DBus.Error.UnknownObject"
+            );
+
+            let signal = Message::signal("/org/zbus/Counter", "org.zbus.Counter", "Incremented")?
+                .build(&(0u32,))?;
+            assert!(matches!(
+                server.call(&signal).await,
+                Err(Error::Unsupported)
+            ));
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use zbus_names::{InterfaceName, MemberName, OwnedInterfaceName, OwnedMemberName};
+
+use crate::message::Message;
+
+/// A method call taking longer to handle than the threshold given to
+/// [`ObjectServer::watch_slow_handlers`](crate::ObjectServer::watch_slow_handlers).
+#[derive(Clone, Debug)]
+pub struct SlowHandler {
+    msg: Message,
+    elapsed: Duration,
+}
+
+impl SlowHandler {
+    /// The method call.
+    pub fn message(&self) -> &Message {
+        &self.msg
+    }
+
+    /// How long the method call has been handled for.
+    ///
+    /// The handler may still be running.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+type Callback = Arc<dyn Fn(SlowHandler) + Send + Sync>;
+
+/// Reports method calls taking longer than a threshold.
+#[derive(Clone)]
+pub(crate) struct Watchdog {
+    pub(crate) threshold: Duration,
+    callback: Callback,
+}
+
+impl Watchdog {
+    pub(crate) fn new<F>(threshold: Duration, callback: F) -> Self
+    where
+        F: Fn(SlowHandler) + Send + Sync + 'static,
+    {
+        Self {
+            threshold,
+            callback: Arc::new(callback),
+        }
+    }
+
+    pub(crate) fn report(&self, msg: &Message) {
+        (self.callback)(SlowHandler {
+            msg: msg.clone(),
+            elapsed: self.threshold,
+        })
+    }
+}
+
+impl fmt::Debug for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watchdog")
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The maximum number of method calls to an object being handled at the same time.
+#[derive(Debug)]
+pub(crate) struct DispatchLimit {
+    max: usize,
+    in_flight: AtomicUsize,
+}
+
+impl DispatchLimit {
+    pub(crate) fn new(max: usize) -> Self {
+        Self {
+            max,
+            in_flight: AtomicUsize::new(0),
+        }
+    }
+
+    /// Count a method call as in-flight until the returned guard is dropped.
+    ///
+    /// Returns `None` if the limit is already reached.
+    pub(crate) fn acquire(self: &Arc<Self>) -> Option<InFlight> {
+        self.in_flight
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .ok()?;
+
+        Some(InFlight(self.clone()))
+    }
+}
+
+/// An in-flight method call, counted towards a [`DispatchLimit`].
+#[derive(Debug)]
+pub(crate) struct InFlight(Arc<DispatchLimit>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The execution deadlines of method calls, per interface or per method.
+#[derive(Debug, Default)]
+pub(crate) struct Deadlines(HashMap<(OwnedInterfaceName, Option<OwnedMemberName>), Duration>);
+
+impl Deadlines {
+    /// Set the deadline of the calls to `method` of `interface`, or to all its methods if `method`
+    /// is `None`.
+    pub(crate) fn set(
+        &mut self,
+        interface: InterfaceName<'_>,
+        method: Option<MemberName<'_>>,
+        deadline: Option<Duration>,
+    ) {
+        let key = (interface.into(), method.map(Into::into));
+        match deadline {
+            Some(deadline) => self.0.insert(key, deadline),
+            None => self.0.remove(&key),
+        };
+    }
+
+    /// The deadline of a call to `method` of `interface`.
+    ///
+    /// The deadline of the method takes precedence over the one of the interface.
+    pub(crate) fn get(
+        &self,
+        interface: &InterfaceName<'_>,
+        method: &MemberName<'_>,
+    ) -> Option<Duration> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let interface = OwnedInterfaceName::from(interface.to_owned());
+        let method = OwnedMemberName::from(method.to_owned());
+
+        self.0
+            .get(&(interface.clone(), Some(method)))
+            .or_else(|| self.0.get(&(interface, None)))
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fdo, Error};
+    use ntest::timeout;
+    use test_log::test;
+
+    #[test]
+    #[timeout(15000)]
+    fn dispatch_limit_and_slow_handlers() {
+        use crate::{abstractions::timer::Timer, connection, utils::block_on};
+        use std::{
+            sync::{Arc, Mutex},
+            time::Duration,
+        };
+
+        struct Slow;
+        #[crate::interface(name = "org.zbus.Slow")]
+        impl Slow {
+            async fn work(&self) {
+                Timer::after(Duration::from_millis(200)).await;
+            }
+        }
+
+        block_on(async {
+            let service = connection::Builder::session()?
+                .serve_at("/org/zbus/Slow", Slow)?
+                .build()
+                .await?;
+            let server = service.object_server();
+            server.set_dispatch_limit("/org/zbus/Slow", Some(1)).await?;
+            let slow = Arc::new(Mutex::new(vec![]));
+            let reported = slow.clone();
+            server
+                .watch_slow_handlers(Duration::from_millis(50), move |slow| {
+                    let member = slow.message().header().member().unwrap().to_string();
+                    reported.lock().unwrap().push(member);
+                })
+                .await;
+
+            let client = connection::Builder::session()?.build().await?;
+            let work = || {
+                client.call_method(
+                    service.unique_name(),
+                    "/org/zbus/Slow",
+                    Some("org.zbus.Slow"),
+                    "Work",
+                    &(),
+                )
+            };
+            let (first, second) = futures_util::join!(work(), work());
+            let err = match (first, second) {
+                (Ok(_), Err(e)) | (Err(e), Ok(_)) => e,
+                res => panic!("Expected exactly one call to fail: {res:?}"),
+            };
+            assert!(matches!(
+                fdo::Error::from(err),
+                fdo::Error::LimitsExceeded(_)
+            ));
+            assert_eq!(*slow.lock().unwrap(), ["Work"]);
+
+            // The limit can be lifted.
+            server.set_dispatch_limit("/org/zbus/Slow", None).await?;
+            let (first, second) = futures_util::join!(work(), work());
+            first?;
+            second?;
+
+            Ok::<_, Error>(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn method_deadlines() {
+        use crate::blocking;
+        use std::{
+            sync::{
+                atomic::{AtomicBool, Ordering},
+                Arc,
+            },
+            time::Duration,
+        };
+
+        // Sets the flag when dropped, i.e once the handler is cancelled.
+        struct SetOnDrop(Arc<AtomicBool>);
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        struct Backend {
+            cancelled: Arc<AtomicBool>,
+        }
+        #[crate::interface(name = "org.zbus.Backend")]
+        impl Backend {
+            async fn stuck(&self) {
+                let _guard = SetOnDrop(self.cancelled.clone());
+                std::future::pending::<()>().await;
+            }
+
+            async fn slow(&self) -> u32 {
+                crate::abstractions::timer::Timer::after(Duration::from_millis(200)).await;
+
+                42
+            }
+        }
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let path = "/org/zbus/Backend";
+        let service = blocking::connection::Builder::session()
+            .unwrap()
+            .serve_at(
+                path,
+                Backend {
+                    cancelled: cancelled.clone(),
+                },
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let object_server = service.object_server();
+        object_server
+            .set_deadline("org.zbus.Backend", Some(Duration::from_millis(100)))
+            .unwrap();
+        let conn = blocking::Connection::session().unwrap();
+        let call = |method| {
+            conn.call_method(
+                service.unique_name(),
+                path,
+                Some("org.zbus.Backend"),
+                method,
+                &(),
+            )
+        };
+
+        for method in ["Stuck", "Slow"] {
+            let err = call(method).unwrap_err();
+            assert!(
+                matches!(&err, Error::MethodError(name, _, _) if name.as_str() == "org.freedesktop.DBus.Error.TimedOut"),
+                "{err}"
+            );
+        }
+        assert!(cancelled.load(Ordering::SeqCst));
+
+        // The deadline of a method takes precedence over the one of its interface.
+        object_server
+            .set_method_deadline("org.zbus.Backend", "Slow", Some(Duration::from_secs(5)))
+            .unwrap();
+        let reply: u32 = call("Slow").unwrap().body().deserialize().unwrap();
+        assert_eq!(reply, 42);
+    }
+}
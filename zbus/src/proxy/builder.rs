@@ -4,7 +4,7 @@ use static_assertions::assert_impl_all;
 use zbus_names::{BusName, InterfaceName};
 use zvariant::{ObjectPath, Str};
 
-use crate::{proxy::ProxyInner, Connection, Error, Proxy, Result};
+use crate::{connection::SharedMatchRule, proxy::ProxyInner, Connection, Error, Proxy, Result};
 
 /// The properties caching mode.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
@@ -30,6 +30,8 @@ pub struct Builder<'a, T = ()> {
     proxy_type: PhantomData<T>,
     cache: CacheProperties,
     uncached_properties: Option<HashSet<Str<'a>>>,
+    shared_match_rules: Vec<SharedMatchRule>,
+    verify: bool,
 }
 
 impl<'a, T> Clone for Builder<'a, T> {
@@ -41,6 +43,8 @@ impl<'a, T> Clone for Builder<'a, T> {
             interface: self.interface.clone(),
             cache: self.cache,
             uncached_properties: self.uncached_properties.clone(),
+            shared_match_rules: self.shared_match_rules.clone(),
+            verify: self.verify,
             proxy_type: PhantomData,
         }
     }
@@ -95,6 +99,33 @@ impl<'a, T> Builder<'a, T> {
         self
     }
 
+    /// Keep a shared match rule alive for the lifetime of the proxy.
+    ///
+    /// Match rules added by the proxy and the streams it creates, which are covered by `rule`,
+    /// share it on the bus instead of being added individually. See
+    /// [`Connection::add_shared_match`] for details.
+    #[must_use]
+    pub fn shared_match_rule(mut self, rule: &SharedMatchRule) -> Self {
+        self.shared_match_rules.push(rule.clone());
+
+        self
+    }
+
+    /// Verify the remote object when building the proxy.
+    ///
+    /// If enabled, [`Builder::build`] will check that the destination exists (or is activatable)
+    /// and that the object implements the proxy interface, through introspection. This allows
+    /// failing early with a descriptive error ([`crate::fdo::Error::ServiceUnknown`] or
+    /// [`crate::fdo::Error::UnknownInterface`] respectively), rather than on the first call.
+    ///
+    /// Note that this requires the object to implement the `org.freedesktop.DBus.Introspectable`
+    /// interface. By default, the proxy is not verified.
+    #[must_use]
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
     pub(crate) fn build_internal(self) -> Result<Proxy<'a>> {
         let conn = self.conn;
         let destination = self
@@ -113,6 +144,7 @@ impl<'a, T> Builder<'a, T> {
                 interface,
                 cache,
                 uncached_properties,
+                self.shared_match_rules,
             )),
         })
     }
@@ -122,14 +154,20 @@ impl<'a, T> Builder<'a, T> {
     /// # Errors
     ///
     /// If the builder is lacking the necessary parameters to build a proxy,
-    /// [`Error::MissingParameter`] is returned.
+    /// [`Error::MissingParameter`] is returned. If [verification](Builder::verify) is enabled and
+    /// fails, the corresponding error is returned.
     pub async fn build(self) -> Result<T>
     where
         T: From<Proxy<'a>>,
     {
         let cache_upfront = self.cache == CacheProperties::Yes;
+        let verify = self.verify;
         let proxy = self.build_internal()?;
 
+        if verify {
+            proxy.verify().await?;
+        }
+
         if cache_upfront {
             proxy
                 .get_property_cache()
@@ -158,6 +196,8 @@ where
                 .map(|i| InterfaceName::from_static_str(i).expect("invalid interface name")),
             cache: CacheProperties::default(),
             uncached_properties: None,
+            shared_match_rules: vec![],
+            verify: false,
             proxy_type: PhantomData,
         }
     }
@@ -220,4 +260,49 @@ mod tests {
         let proxy = builder.build().await.unwrap();
         assert!(matches!(proxy.inner.destination, BusName::Unique(_)));
     }
+
+    #[test]
+    #[ntest::timeout(15000)]
+    fn verify() {
+        crate::utils::block_on(verify_async());
+    }
+
+    async fn verify_async() {
+        use crate::fdo;
+
+        let conn = Connection::session().await.unwrap();
+        let builder = Builder::<Proxy<'_>>::new(&conn)
+            .destination("org.freedesktop.DBus")
+            .unwrap()
+            .path("/org/freedesktop/DBus")
+            .unwrap()
+            .interface("org.freedesktop.DBus")
+            .unwrap()
+            .cache_properties(CacheProperties::No)
+            .verify(true);
+        builder.clone().build().await.unwrap();
+
+        let err = builder
+            .clone()
+            .interface("org.freedesktop.zbus.NoSuchInterface")
+            .unwrap()
+            .build()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::FDO(e) if matches!(**e, fdo::Error::UnknownInterface(_))),
+            "{err}"
+        );
+
+        let err = builder
+            .destination("org.freedesktop.zbus.NoSuchService")
+            .unwrap()
+            .build()
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::FDO(e) if matches!(**e, fdo::Error::ServiceUnknown(_))),
+            "{err}"
+        );
+    }
 }
@@ -14,13 +14,15 @@ use std::{
     pin::Pin,
     sync::{Arc, OnceLock, RwLock, RwLockReadGuard},
     task::{Context, Poll},
+    time::Duration,
 };
-use tracing::{debug, info_span, instrument, trace, Instrument};
+use tracing::{debug, instrument, trace};
 
 use zbus_names::{BusName, InterfaceName, MemberName, UniqueName};
-use zvariant::{ObjectPath, OwnedValue, Str, Value};
+use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Str, Value};
 
 use crate::{
+    connection::SharedMatchRule,
     fdo::{self, IntrospectableProxy, NameOwnerChanged, PropertiesChangedStream, PropertiesProxy},
     message::{Flags, Message, Sequence, Type},
     AsyncDrop, Connection, Error, Executor, MatchRule, MessageStream, OwnedMatchRule, Result, Task,
@@ -28,6 +30,8 @@ use crate::{
 
 mod builder;
 pub use builder::{Builder, CacheProperties, ProxyDefault};
+mod rate_limit;
+pub use rate_limit::{Debounce, Throttle};
 
 /// A client-side interface proxy.
 ///
@@ -78,6 +82,8 @@ assert_impl_all!(Proxy<'_>: Send, Sync, Unpin);
 pub(crate) struct ProxyInnerStatic {
     pub(crate) conn: Connection,
     dest_owner_change_match_rule: OnceLock<OwnedMatchRule>,
+    // Only kept around so the rules are kept alive as long as the proxy is.
+    shared_match_rules: Vec<SharedMatchRule>,
 }
 
 impl fmt::Debug for ProxyInnerStatic {
@@ -87,6 +93,7 @@ impl fmt::Debug for ProxyInnerStatic {
                 "dest_owner_change_match_rule",
                 &self.dest_owner_change_match_rule,
             )
+            .field("shared_match_rules", &self.shared_match_rules)
             .finish_non_exhaustive()
     }
 }
@@ -220,6 +227,79 @@ pub struct PropertyStream<'a, T> {
     phantom: std::marker::PhantomData<T>,
 }
 
+impl<'a, T> PropertyStream<'a, T> {
+    /// Only yield a change once the property has not changed for `period`.
+    ///
+    /// This is useful for properties that change in bursts, where only the settled value is of
+    /// interest. Since [`PropertyChanged`] fetches the new value on demand, the changes skipped by
+    /// this adapter don't incur any D-Bus traffic.
+    pub fn debounce(self, period: Duration) -> Debounce<Self>
+    where
+        T: Unpin,
+    {
+        let executor = self.proxy.connection().executor().clone();
+
+        Debounce::new(self, period, executor)
+    }
+
+    /// Yield at most one change per `period`.
+    ///
+    /// The first change is yielded immediately, and any changes during the following `period` are
+    /// coalesced into a single one, yielded at the end of the period. This is useful for rapidly
+    /// changing properties (e.g signal strength or progress) where you want to limit how often you
+    /// react to changes, while still eventually seeing the latest value.
+    pub fn throttle(self, period: Duration) -> Throttle<Self>
+    where
+        T: Unpin,
+    {
+        let executor = self.proxy.connection().executor().clone();
+
+        Throttle::new(self, period, executor)
+    }
+
+    /// Yield the property values, skipping values equal to the previous one.
+    ///
+    /// Unlike the other adapters, this needs to know the value of the property for each change and
+    /// hence the resulting stream yields the values (fetching them if needed) instead of
+    /// [`PropertyChanged`] instances. Errors in fetching the value are yielded as is and don't
+    /// affect the comparison.
+    pub fn distinct_until_changed(self) -> impl stream::Stream<Item = Result<T>> + 'a
+    where
+        T: TryFrom<OwnedValue> + PartialEq + Clone + Unpin + Send + 'a,
+        T::Error: Into<Error>,
+    {
+        self.distinct_until_changed_by(|a: &T, b: &T| a == b)
+    }
+
+    /// Same as [`PropertyStream::distinct_until_changed`] but with a custom equality function.
+    pub fn distinct_until_changed_by<F>(self, eq: F) -> impl stream::Stream<Item = Result<T>> + 'a
+    where
+        T: TryFrom<OwnedValue> + Clone + Unpin + Send + 'a,
+        T::Error: Into<Error>,
+        F: FnMut(&T, &T) -> bool + Send + 'a,
+    {
+        use futures_util::StreamExt;
+
+        futures_util::stream::unfold(
+            (self, None::<T>, eq),
+            |(mut stream, mut last, mut eq)| async move {
+                loop {
+                    let changed = stream.next().await?;
+                    let value = match changed.get().await {
+                        Ok(value) => value,
+                        Err(e) => return Some((Err(e), (stream, last, eq))),
+                    };
+                    if last.as_ref().map_or(true, |last| !eq(last, &value)) {
+                        last = Some(value.clone());
+
+                        return Some((Ok(value), (stream, last, eq)));
+                    }
+                }
+            },
+        )
+    }
+}
+
 impl<'a, T> stream::Stream for PropertyStream<'a, T>
 where
     T: Unpin,
@@ -281,7 +361,10 @@ impl PropertiesCache {
         });
 
         let cache_clone = cache.clone();
-        let task_name = format!("{interface} proxy caching");
+        let task_name = proxy
+            .inner()
+            .connection()
+            .task_name(&format!("`{interface}` proxy caching"));
         let proxy_caching = async move {
             let result = cache_clone
                 .init(proxy, interface, uncached_properties)
@@ -316,9 +399,8 @@ impl PropertiesCache {
             {
                 debug!("Error keeping properties cache updated: {e}");
             }
-        }
-        .instrument(info_span!("{}", task_name));
-        let task = executor.spawn(proxy_caching, &task_name);
+        };
+        let task = executor.spawn_long_lived(proxy_caching, &task_name);
 
         (cache, task)
     }
@@ -494,6 +576,7 @@ impl<'a> ProxyInner<'a> {
         interface: InterfaceName<'a>,
         cache: CacheProperties,
         uncached_properties: HashSet<Str<'a>>,
+        shared_match_rules: Vec<SharedMatchRule>,
     ) -> Self {
         let property_cache = match cache {
             CacheProperties::Yes | CacheProperties::Lazily => Some(OnceLock::new()),
@@ -503,6 +586,7 @@ impl<'a> ProxyInner<'a> {
             inner_without_borrows: ProxyInnerStatic {
                 conn,
                 dest_owner_change_match_rule: OnceLock::new(),
+                shared_match_rules,
             },
             destination,
             path,
@@ -570,6 +654,26 @@ impl<'a> ProxyInner<'a> {
 
 const MAX_NAME_OWNER_CHANGED_SIGNALS_QUEUED: usize = 8;
 
+/// Check if the `<interface>` elements of an introspection XML include `interface`.
+///
+/// This is a lightweight scan rather than a full XML parse, sufficient for the output of
+/// `org.freedesktop.DBus.Introspectable.Introspect`.
+fn introspection_has_interface(xml: &str, interface: &str) -> bool {
+    xml.split("<interface")
+        .skip(1)
+        .filter(|element| element.starts_with(|c: char| c.is_ascii_whitespace()))
+        .filter_map(|element| {
+            let tag = &element[..element.find('>')?];
+            let value = tag.split_once("name")?.1.trim_start().strip_prefix('=')?;
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+
+            value.find(quote).map(|end| &value[..end])
+        })
+        .any(|name| name == interface)
+}
+
 impl<'a> Proxy<'a> {
     /// Create a new `Proxy` for the given destination/path/interface.
     pub async fn new<D, P, I>(
@@ -623,6 +727,15 @@ impl<'a> Proxy<'a> {
         &self.inner.inner_without_borrows.conn
     }
 
+    /// Convert into a [blocking proxy](crate::blocking::Proxy).
+    ///
+    /// The returned proxy shares the connection, configuration and property cache of `self`, so
+    /// this is cheap. This is useful for code bases where a blocking layer (e.g a CLI) is built on
+    /// top of an async core.
+    pub fn into_blocking(self) -> crate::blocking::Proxy<'a> {
+        self.into()
+    }
+
     /// Get a reference to the destination service name.
     pub fn destination(&self) -> &BusName<'_> {
         &self.inner.destination
@@ -652,6 +765,51 @@ impl<'a> Proxy<'a> {
         proxy.introspect().await
     }
 
+    /// Check that the destination exists (or is activatable) and that the object implements the
+    /// interface of the proxy.
+    pub(crate) async fn verify(&self) -> Result<()> {
+        let conn = &self.inner.inner_without_borrows.conn;
+        let destination = &self.inner.destination;
+        if conn.is_bus() {
+            let dbus: fdo::DBusProxy<'_> = fdo::DBusProxy::builder(conn)
+                .cache_properties(CacheProperties::No)
+                .build_internal()?
+                .into();
+            let exists = dbus.name_has_owner(destination.as_ref()).await?
+                || (matches!(destination, BusName::WellKnown(_))
+                    && dbus
+                        .list_activatable_names()
+                        .await?
+                        .iter()
+                        .any(|name| name == destination));
+            if !exists {
+                return Err(fdo::Error::ServiceUnknown(format!(
+                    "`{destination}` is neither owned nor activatable"
+                ))
+                .into());
+            }
+        }
+
+        // Not using `Builder::build` as that would be recursive.
+        let introspectable: IntrospectableProxy<'_> = IntrospectableProxy::builder(conn)
+            .destination(destination)?
+            .path(&self.inner.path)?
+            .cache_properties(CacheProperties::No)
+            .build_internal()?
+            .into();
+        let xml = introspectable.introspect().await?;
+        let interface = self.inner.interface.as_str();
+        if !introspection_has_interface(&xml, interface) {
+            return Err(fdo::Error::UnknownInterface(format!(
+                "object `{}` on `{destination}` doesn't implement `{interface}`",
+                self.inner.path,
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
     fn properties_proxy(&self) -> PropertiesProxy<'_> {
         PropertiesProxy::builder(&self.inner.inner_without_borrows.conn)
             // Safe because already checked earlier
@@ -719,9 +877,22 @@ impl<'a> Proxy<'a> {
         T: TryFrom<OwnedValue>,
         T::Error: Into<Error>,
     {
-        self.cached_property_raw(property_name)
-            .as_deref()
-            .map(|v| T::try_from(OwnedValue::try_from(v)?).map_err(Into::into))
+        self.inner
+            .property_cache
+            .as_ref()
+            .and_then(OnceLock::get)
+            .and_then(|c| {
+                let values = c.0.values.read().expect("lock poisoned");
+
+                // Cloning is cheap, the cache and the clone share the value (unless it contains
+                // file descriptors).
+                values
+                    .get(property_name)?
+                    .value
+                    .as_ref()
+                    .map(OwnedValue::try_clone)
+            })
+            .map(|v| T::try_from(v?).map_err(Into::into))
             .transpose()
     }
 
@@ -799,6 +970,64 @@ impl<'a> Proxy<'a> {
         value.try_into().map_err(Into::into)
     }
 
+    /// Get all the properties of the interface, deserialized into `T`.
+    ///
+    /// Effectively, call the `GetAll` method of the `org.freedesktop.DBus.Properties` interface and
+    /// deserialize the returned `a{sv}` dictionary into `T`. This saves you from unpacking the
+    /// properties from a `HashMap<String, OwnedValue>` manually. The easiest way to create a
+    /// suitable type is to derive [`zvariant::DeserializeDict`] and [`zvariant::Type`] for a
+    /// struct, with the `a{sv}` signature. Make fields `Option` for properties that may be
+    /// absent.
+    ///
+    /// This method always calls the peer, regardless of the caching mode of the proxy.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use zbus::{Connection, Proxy};
+    /// use zbus::zvariant::{DeserializeDict, Type};
+    ///
+    /// #[derive(Debug, DeserializeDict, Type)]
+    /// #[zvariant(signature = "a{sv}", rename_all = "PascalCase")]
+    /// struct BusProperties {
+    ///     features: Option<Vec<String>>,
+    ///     interfaces: Option<Vec<String>>,
+    /// }
+    ///
+    /// # zbus::block_on(async {
+    /// let connection = Connection::session().await?;
+    /// let proxy = Proxy::new(
+    ///     &connection,
+    ///     "org.freedesktop.DBus",
+    ///     "/org/freedesktop/DBus",
+    ///     "org.freedesktop.DBus",
+    /// )
+    /// .await?;
+    /// let props: BusProperties = proxy.get_all_as().await?;
+    /// println!("Bus features: {:?}", props.features);
+    /// println!("Bus interfaces: {:?}", props.interfaces);
+    /// # Ok::<_, zbus::Error>(())
+    /// # }).unwrap();
+    /// ```
+    pub async fn get_all_as<T>(&self) -> Result<T>
+    where
+        T: for<'d> zvariant::DynamicDeserialize<'d>,
+    {
+        self.inner
+            .inner_without_borrows
+            .conn
+            .call_method(
+                Some(&self.inner.destination),
+                self.inner.path.as_str(),
+                Some("org.freedesktop.DBus.Properties"),
+                "GetAll",
+                &self.inner.interface,
+            )
+            .await?
+            .body()
+            .deserialize()
+    }
+
     /// Set the property `property_name`.
     ///
     /// Effectively, call the `Set` method of the `org.freedesktop.DBus.Properties` interface.
@@ -909,6 +1138,110 @@ impl<'a> Proxy<'a> {
         Ok(())
     }
 
+    /// Call a method and wait for a signal correlated with its reply.
+    ///
+    /// Many D-Bus APIs (e.g UDisks or PackageKit) reply to a method call with a job or transaction
+    /// token and report its completion later through a signal. Subscribing to the signal only after
+    /// the reply has been received is racy, as the signal could be emitted before the subscription
+    /// is in place. This method subscribes to signals matching `signal_rule` *before* making the
+    /// call, so no signal can be missed.
+    ///
+    /// Each received signal is passed to `is_terminal`, along with the deserialized reply. The
+    /// method returns the reply and the first signal for which `is_terminal` returns `true`. Any
+    /// other signals (e.g progress reports) are only seen by `is_terminal`, so you can use it to
+    /// track progress as well.
+    ///
+    /// See [`Proxy::call_job`] for a convenient wrapper for the common case of a method returning
+    /// the path of a job object.
+    pub async fn call_and_receive_signal<'m, 'r, M, B, R, F>(
+        &self,
+        method_name: M,
+        body: &B,
+        signal_rule: MatchRule<'r>,
+        mut is_terminal: F,
+    ) -> Result<(R, Message)>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        R: for<'d> zvariant::DynamicDeserialize<'d>,
+        F: FnMut(&R, &Message) -> Result<bool>,
+    {
+        use futures_util::StreamExt;
+
+        let mut stream =
+            MessageStream::for_match_rule(signal_rule.into_owned(), self.connection(), None)
+                .await?;
+        let reply = self.call(method_name, body).await;
+        let reply: R = match reply {
+            Ok(reply) => reply,
+            Err(e) => {
+                stream.async_drop().await;
+
+                return Err(e);
+            }
+        };
+
+        let result = loop {
+            match stream.next().await {
+                Some(Ok(msg)) => match is_terminal(&reply, &msg) {
+                    Ok(true) => break Ok(msg),
+                    Ok(false) => (),
+                    Err(e) => break Err(e),
+                },
+                Some(Err(e)) => break Err(e),
+                None => {
+                    break Err(Error::InputOutput(
+                        std::io::Error::new(std::io::ErrorKind::BrokenPipe, "connection closed")
+                            .into(),
+                    ))
+                }
+            }
+        };
+        stream.async_drop().await;
+
+        result.map(|msg| (reply, msg))
+    }
+
+    /// Call a method returning a job object path and wait for the job's terminal signal.
+    ///
+    /// This is a wrapper around [`Proxy::call_and_receive_signal`] for the common "returns a job
+    /// object" pattern: the method named `method_name` is expected to reply with an object path and
+    /// the job is considered complete once the destination emits the `signal_name` signal of
+    /// `signal_interface` from that path.
+    ///
+    /// Returns the job path and the terminal signal message.
+    pub async fn call_job<'m, 'i, 's, M, B, I, S>(
+        &self,
+        method_name: M,
+        body: &B,
+        signal_interface: I,
+        signal_name: S,
+    ) -> Result<(OwnedObjectPath, Message)>
+    where
+        M: TryInto<MemberName<'m>>,
+        M::Error: Into<Error>,
+        B: serde::ser::Serialize + zvariant::DynamicType,
+        I: TryInto<InterfaceName<'i>>,
+        I::Error: Into<Error>,
+        S: TryInto<MemberName<'s>>,
+        S::Error: Into<Error>,
+    {
+        let signal_interface = signal_interface.try_into().map_err(Into::into)?;
+        let signal_name = signal_name.try_into().map_err(Into::into)?;
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .sender(self.destination().to_owned())?
+            .interface(signal_interface.into_owned())?
+            .member(signal_name.into_owned())?
+            .build();
+
+        self.call_and_receive_signal(method_name, body, rule, |job: &OwnedObjectPath, msg| {
+            Ok(msg.header().path().map(|p| p.as_str()) == Some(job.as_str()))
+        })
+        .await
+    }
+
     /// Create a stream for signal named `signal_name`.
     pub async fn receive_signal<'m, M>(&self, signal_name: M) -> Result<SignalStream<'m>>
     where
@@ -1423,6 +1756,148 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[timeout(15000)]
+    fn call_job() {
+        block_on(test_call_job()).unwrap();
+    }
+
+    async fn test_call_job() -> Result<()> {
+        struct Jobs;
+
+        #[interface(name = "org.zbus.Jobs")]
+        impl Jobs {
+            // Emits the completion signal before replying, so the caller can only catch it if it
+            // subscribed before making the call.
+            async fn start(
+                &self,
+                #[zbus(signal_context)] ctxt: SignalContext<'_>,
+            ) -> fdo::Result<OwnedObjectPath> {
+                let path = OwnedObjectPath::try_from("/org/zbus/Jobs/1").unwrap();
+                let job_ctxt = SignalContext::new(ctxt.connection(), path.as_ref())?;
+                Job::progress(&job_ctxt, 50).await?;
+                Job::completed(&job_ctxt, true).await?;
+
+                Ok(path)
+            }
+        }
+
+        struct Job;
+
+        #[interface(name = "org.zbus.Jobs.Job")]
+        impl Job {
+            #[zbus(signal)]
+            async fn progress(ctxt: &SignalContext<'_>, percent: u32) -> Result<()>;
+
+            #[zbus(signal)]
+            async fn completed(ctxt: &SignalContext<'_>, success: bool) -> Result<()>;
+        }
+
+        let service_name = "org.freedesktop.zbus.async.ProxyCallJobTest";
+        let _server_conn = connection::Builder::session()?
+            .name(service_name)?
+            .serve_at("/org/zbus/Jobs", Jobs)?
+            .build()
+            .await?;
+        let client_conn = Connection::session().await?;
+        let proxy = Proxy::new(
+            &client_conn,
+            service_name,
+            "/org/zbus/Jobs",
+            "org.zbus.Jobs",
+        )
+        .await?;
+
+        let (job, signal) = proxy
+            .call_job("Start", &(), "org.zbus.Jobs.Job", "Completed")
+            .await?;
+        assert_eq!(job.as_str(), "/org/zbus/Jobs/1");
+        assert!(signal.body().deserialize::<bool>()?);
+
+        let rule = MatchRule::builder()
+            .msg_type(Type::Signal)
+            .interface("org.zbus.Jobs.Job")?
+            .build();
+        let mut progress = vec![];
+        let (_, signal) = proxy
+            .call_and_receive_signal("Start", &(), rule, |_: &OwnedObjectPath, msg| {
+                let header = msg.header();
+                match header.member().map(|m| m.as_str()) {
+                    Some("Progress") => {
+                        progress.push(msg.body().deserialize::<u32>()?);
+
+                        Ok(false)
+                    }
+                    _ => Ok(true),
+                }
+            })
+            .await?;
+        assert_eq!(progress, [50]);
+        assert_eq!(signal.header().member().unwrap(), "Completed");
+
+        Ok(())
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn get_all_as() {
+        block_on(test_get_all_as()).unwrap();
+    }
+
+    async fn test_get_all_as() -> Result<()> {
+        use zvariant::{DeserializeDict, Type};
+
+        struct Device;
+
+        #[interface(name = "org.zbus.Device")]
+        impl Device {
+            #[zbus(property)]
+            fn model(&self) -> &str {
+                "ZB-1"
+            }
+
+            #[zbus(property)]
+            fn battery_level(&self) -> u8 {
+                42
+            }
+        }
+
+        #[derive(Debug, DeserializeDict, Type, PartialEq)]
+        #[zvariant(signature = "a{sv}", rename_all = "PascalCase")]
+        struct DeviceProperties {
+            model: String,
+            battery_level: Option<u8>,
+            serial_number: Option<String>,
+        }
+
+        let service_name = "org.freedesktop.zbus.async.ProxyGetAllAsTest";
+        let _server_conn = connection::Builder::session()?
+            .name(service_name)?
+            .serve_at("/org/zbus/Device", Device)?
+            .build()
+            .await?;
+        let client_conn = Connection::session().await?;
+        let proxy = Proxy::new(
+            &client_conn,
+            service_name,
+            "/org/zbus/Device",
+            "org.zbus.Device",
+        )
+        .await?;
+
+        let props: DeviceProperties = proxy.get_all_as().await?;
+        assert_eq!(
+            props,
+            DeviceProperties {
+                model: "ZB-1".into(),
+                battery_level: Some(42),
+                serial_number: None,
+            }
+        );
+
+        Ok(())
+    }
+
     #[test]
     #[timeout(15000)]
     fn signal_stream_deadlock() {
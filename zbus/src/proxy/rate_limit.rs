@@ -0,0 +1,233 @@
+use futures_core::{ready, stream};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{abstractions::timer::Timer, Executor};
+
+/// A [`stream::Stream`] adapter that only yields an item once the underlying stream has been quiet
+/// for a given period.
+///
+/// Use [`PropertyStream::debounce`] to create an instance of this type.
+///
+/// When the underlying stream ends, the last pending item (if any) is yielded immediately.
+///
+/// [`PropertyStream::debounce`]: super::PropertyStream::debounce
+#[derive(Debug)]
+pub struct Debounce<S: stream::Stream> {
+    stream: S,
+    period: Duration,
+    pending: Option<S::Item>,
+    timer: Option<Timer>,
+    // Creating the timers.
+    executor: Executor<'static>,
+    terminated: bool,
+}
+
+impl<S: stream::Stream> Debounce<S> {
+    pub(crate) fn new(stream: S, period: Duration, executor: Executor<'static>) -> Self {
+        Self {
+            stream,
+            period,
+            pending: None,
+            timer: None,
+            executor,
+            terminated: false,
+        }
+    }
+
+    /// Consumes `self`, returning the underlying stream.
+    ///
+    /// Any pending item is discarded.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> stream::Stream for Debounce<S>
+where
+    S: stream::Stream + Unpin,
+    S::Item: Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.terminated {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    // Every new item restarts the quiet period.
+                    this.pending = Some(item);
+                    this.timer = Some(this.executor.timer(this.period));
+                }
+                Poll::Ready(None) => this.terminated = true,
+                Poll::Pending => break,
+            }
+        }
+        if this.terminated {
+            this.timer = None;
+
+            return Poll::Ready(this.pending.take());
+        }
+
+        if let Some(timer) = &mut this.timer {
+            ready!(Pin::new(timer).poll(cx));
+            this.timer = None;
+
+            return Poll::Ready(this.pending.take());
+        }
+
+        Poll::Pending
+    }
+}
+
+/// A [`stream::Stream`] adapter that yields at most one item per period.
+///
+/// Use [`PropertyStream::throttle`] to create an instance of this type.
+///
+/// The first item is yielded immediately. Items received during the following period are coalesced
+/// and only the latest one is yielded once the period is over.
+///
+/// [`PropertyStream::throttle`]: super::PropertyStream::throttle
+#[derive(Debug)]
+pub struct Throttle<S: stream::Stream> {
+    stream: S,
+    period: Duration,
+    pending: Option<S::Item>,
+    timer: Option<Timer>,
+    // Creating the timers.
+    executor: Executor<'static>,
+    terminated: bool,
+}
+
+impl<S: stream::Stream> Throttle<S> {
+    pub(crate) fn new(stream: S, period: Duration, executor: Executor<'static>) -> Self {
+        Self {
+            stream,
+            period,
+            pending: None,
+            timer: None,
+            executor,
+            terminated: false,
+        }
+    }
+
+    /// Consumes `self`, returning the underlying stream.
+    ///
+    /// Any pending item is discarded.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> stream::Stream for Throttle<S>
+where
+    S: stream::Stream + Unpin,
+    S::Item: Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.terminated {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    if this.timer.is_none() {
+                        this.timer = Some(this.executor.timer(this.period));
+
+                        return Poll::Ready(Some(item));
+                    }
+                    this.pending = Some(item);
+                }
+                Poll::Ready(None) => this.terminated = true,
+                Poll::Pending => break,
+            }
+        }
+        if this.terminated {
+            this.timer = None;
+
+            return Poll::Ready(this.pending.take());
+        }
+
+        if let Some(timer) = &mut this.timer {
+            ready!(Pin::new(timer).poll(cx));
+            this.timer = None;
+
+            if let Some(item) = this.pending.take() {
+                // Start a new period for the item we're yielding now.
+                this.timer = Some(this.executor.timer(this.period));
+
+                return Poll::Ready(Some(item));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::block_on;
+    use futures_util::{stream::iter, StreamExt};
+    use test_log::test;
+
+    #[test]
+    fn debounce() {
+        let items: Vec<u32> = block_on(
+            Debounce::new(
+                iter([1, 2, 3]),
+                Duration::from_secs(10),
+                Executor::new(None),
+            )
+            .collect(),
+        );
+        assert_eq!(items, [3]);
+
+        let items: Vec<u32> = block_on(
+            Debounce::new(
+                iter([1, 2, 3]).then(delay),
+                Duration::from_millis(1),
+                Executor::new(None),
+            )
+            .collect(),
+        );
+        assert_eq!(items, [1, 2, 3]);
+    }
+
+    #[test]
+    fn throttle() {
+        let items: Vec<u32> = block_on(
+            Throttle::new(
+                iter([1, 2, 3]),
+                Duration::from_secs(10),
+                Executor::new(None),
+            )
+            .collect(),
+        );
+        assert_eq!(items, [1, 3]);
+
+        let items: Vec<u32> = block_on(
+            Throttle::new(
+                iter([1, 2, 3]).then(delay),
+                Duration::from_millis(1),
+                Executor::new(None),
+            )
+            .collect(),
+        );
+        assert_eq!(items, [1, 2, 3]);
+    }
+
+    fn delay(item: u32) -> Pin<Box<dyn Future<Output = u32> + Send>> {
+        Box::pin(async move {
+            Timer::after(Duration::from_millis(20)).await;
+
+            item
+        })
+    }
+}
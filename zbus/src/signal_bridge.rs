@@ -0,0 +1,344 @@
+//! Signal bridging API.
+//!
+//! A [`SignalBridge`] forwards signals received on one connection by re-emitting them from another,
+//! e.g to re-export signals of a system service on the session bus, or of the host inside a
+//! container.
+
+use futures_util::{stream::select_all, StreamExt};
+use static_assertions::assert_impl_all;
+use tracing::{debug, trace, warn};
+use zbus_names::{InterfaceName, OwnedInterfaceName};
+#[cfg(unix)]
+use zvariant::OwnedFd;
+use zvariant::{Endian, ObjectPath, OwnedObjectPath, Signature};
+
+use crate::{
+    message::{Message, Type},
+    Connection, Error, MessageStream, OwnedMatchRule, Result, Task,
+};
+
+/// Forwards signals from one connection to another.
+///
+/// Each signal received on the source connection that matches any of the given rules, is
+/// re-emitted as is from the target connection, apart from the optional rewriting of its path and
+/// interface. Forwarding stops when the bridge is dropped.
+///
+/// Beware of bridging the same signals in both directions, as that results in an endless loop.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// use zbus::{signal_bridge::SignalBridge, Connection, MatchRule};
+///
+/// # zbus::block_on(async {
+/// let system = Connection::system().await?;
+/// let session = Connection::session().await?;
+///
+/// let rule = MatchRule::builder()
+///     .msg_type(zbus::message::Type::Signal)
+///     .interface("org.freedesktop.UPower.Device")?
+///     .build();
+/// let bridge = SignalBridge::builder(&system, &session)
+///     .rule(rule)?
+///     .rewrite_path("/org/freedesktop/UPower", "/org/example/Host/UPower")?
+///     .build()
+///     .await?;
+///
+/// // Signals are forwarded until `bridge` is dropped.
+/// # drop(bridge);
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+#[derive(Debug)]
+pub struct SignalBridge {
+    #[allow(unused)]
+    task: Task<()>,
+}
+
+assert_impl_all!(SignalBridge: Send, Sync, Unpin);
+
+impl SignalBridge {
+    /// Create a builder for a bridge from `source` to `target`.
+    pub fn builder<'c>(source: &'c Connection, target: &'c Connection) -> Builder<'c> {
+        Builder {
+            source,
+            target,
+            rules: vec![],
+            rewrites: Rewrites::default(),
+        }
+    }
+}
+
+/// A builder for [`SignalBridge`].
+#[derive(Debug)]
+#[must_use]
+pub struct Builder<'c> {
+    source: &'c Connection,
+    target: &'c Connection,
+    rules: Vec<OwnedMatchRule>,
+    rewrites: Rewrites,
+}
+
+assert_impl_all!(Builder<'_>: Send, Sync, Unpin);
+
+impl<'c> Builder<'c> {
+    /// Forward the signals matching `rule`.
+    ///
+    /// The rule must be restricted to signals, with [`Type::Signal`]. This method can be called
+    /// multiple times to forward the signals matching any of the rules.
+    pub fn rule<R>(mut self, rule: R) -> Result<Self>
+    where
+        R: Into<OwnedMatchRule>,
+    {
+        let rule = rule.into();
+        if rule.msg_type() != Some(Type::Signal) {
+            return Err(Error::InvalidMatchRule);
+        }
+        self.rules.push(rule);
+
+        Ok(self)
+    }
+
+    /// Rewrite the paths of the forwarded signals, from under `from` to under `to`.
+    ///
+    /// The signals emitted from `from` itself are emitted from `to`. Those emitted from paths
+    /// under `from` are emitted from the same relative path under `to`. When multiple rewrites
+    /// apply, the first one added is used.
+    pub fn rewrite_path<'f, 't, F, T>(mut self, from: F, to: T) -> Result<Self>
+    where
+        F: TryInto<ObjectPath<'f>>,
+        F::Error: Into<Error>,
+        T: TryInto<ObjectPath<'t>>,
+        T::Error: Into<Error>,
+    {
+        let from = from.try_into().map_err(Into::into)?;
+        let to = to.try_into().map_err(Into::into)?;
+        self.rewrites.paths.push((from.into(), to.into()));
+
+        Ok(self)
+    }
+
+    /// Rewrite the interface of the forwarded signals, from `from` to `to`.
+    pub fn rewrite_interface<'f, 't, F, T>(mut self, from: F, to: T) -> Result<Self>
+    where
+        F: TryInto<InterfaceName<'f>>,
+        F::Error: Into<Error>,
+        T: TryInto<InterfaceName<'t>>,
+        T::Error: Into<Error>,
+    {
+        let from = from.try_into().map_err(Into::into)?;
+        let to = to.try_into().map_err(Into::into)?;
+        self.rewrites.interfaces.push((from.into(), to.into()));
+
+        Ok(self)
+    }
+
+    /// Subscribe to the signals on the source connection and start forwarding them.
+    pub async fn build(self) -> Result<SignalBridge> {
+        let mut streams = Vec::with_capacity(self.rules.len());
+        for rule in self.rules {
+            streams.push(MessageStream::for_match_rule(rule, self.source, None).await?);
+        }
+        let mut stream = select_all(streams);
+        let target = self.target.clone();
+        let rewrites = self.rewrites;
+        let task_name = self.target.task_name("signal bridge");
+
+        let task = self.target.executor().spawn_long_lived(
+            async move {
+                while let Some(msg) = stream.next().await {
+                    let msg = match msg {
+                        Ok(msg) => msg,
+                        Err(e) => {
+                            debug!("Error receiving signal to forward: {e}");
+
+                            continue;
+                        }
+                    };
+                    trace!("Forwarding signal: {msg:?}");
+                    if let Err(e) = forward(&target, &msg, &rewrites).await {
+                        warn!("Failed to forward signal {msg:?}: {e}");
+                    }
+                }
+            },
+            &task_name,
+        );
+
+        Ok(SignalBridge { task })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Rewrites {
+    paths: Vec<(OwnedObjectPath, OwnedObjectPath)>,
+    interfaces: Vec<(OwnedInterfaceName, OwnedInterfaceName)>,
+}
+
+impl Rewrites {
+    fn path(&self, path: &ObjectPath<'_>) -> Result<ObjectPath<'static>> {
+        let path = path.as_str();
+        for (from, to) in &self.paths {
+            let rest = if from.as_str() == "/" {
+                path.trim_start_matches('/')
+            } else {
+                match path.strip_prefix(from.as_str()) {
+                    Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+                        rest.trim_start_matches('/')
+                    }
+                    _ => continue,
+                }
+            };
+            let rewritten = match (to.as_str(), rest) {
+                (to, "") => to.to_string(),
+                ("/", rest) => format!("/{rest}"),
+                (to, rest) => format!("{to}/{rest}"),
+            };
+
+            return ObjectPath::try_from(rewritten).map_err(Into::into);
+        }
+
+        Ok(ObjectPath::from_str_unchecked(path).into_owned())
+    }
+
+    fn interface(&self, interface: &InterfaceName<'_>) -> InterfaceName<'static> {
+        self.interfaces
+            .iter()
+            .find(|(from, _)| *from == *interface)
+            .map(|(_, to)| to.inner().clone())
+            .unwrap_or_else(|| interface.to_owned())
+    }
+}
+
+/// Re-emit the signal `msg` from `target`.
+async fn forward(target: &Connection, msg: &Message, rewrites: &Rewrites) -> Result<()> {
+    let hdr = msg.header();
+    let (path, interface, member) = match (hdr.path(), hdr.interface(), hdr.member()) {
+        (Some(path), Some(interface), Some(member)) => (path, interface, member),
+        _ => return Err(Error::MissingField),
+    };
+    let builder = Message::signal(
+        rewrites.path(path)?,
+        rewrites.interface(interface),
+        member.to_owned(),
+    )?
+    .endian(Endian::from(hdr.primary().endian_sig()));
+
+    let body = msg.body();
+    let data = body.data();
+    // Wrap the signature in a structure, as `build_raw_body` unwraps it.
+    let signature = match body.signature() {
+        Some(signature) if !signature.is_empty() => Signature::try_from(format!("({signature})"))?,
+        _ => Signature::from_static_str_unchecked(""),
+    };
+    #[cfg(unix)]
+    let fds = data
+        .fds()
+        .iter()
+        .map(|fd| {
+            fd.try_to_owned()
+                .map_err(Error::from)
+                .and_then(|fd| Ok(OwnedFd::from(std::os::fd::OwnedFd::try_from(fd)?)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // SAFETY: The body, its signature, endianness and FDs all come from a valid message.
+    let forwarded = unsafe {
+        builder.build_raw_body(
+            data.bytes(),
+            signature,
+            #[cfg(unix)]
+            fds,
+        )?
+    };
+
+    target.send(&forwarded).await
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::SignalBridge;
+    use crate::{connection, message::Type, utils::block_on, MatchRule, MessageStream};
+
+    #[test]
+    #[timeout(15000)]
+    fn signal_bridge() {
+        block_on(async {
+            let emitter = connection::Builder::session()?.build().await?;
+            let source = connection::Builder::session()?.build().await?;
+            let target = connection::Builder::session()?.build().await?;
+            let listener = connection::Builder::session()?.build().await?;
+
+            let rule = MatchRule::builder()
+                .msg_type(Type::Signal)
+                .sender(emitter.unique_name().unwrap())?
+                .interface("org.zbus.Sensor")?
+                .build();
+            let err = SignalBridge::builder(&source, &target)
+                .rule(MatchRule::builder().interface("org.zbus.Sensor")?.build())
+                .unwrap_err();
+            assert_eq!(err, crate::Error::InvalidMatchRule);
+            let bridge = SignalBridge::builder(&source, &target)
+                .rule(rule)?
+                .rewrite_path("/org/zbus/Sensors", "/org/zbus/Bridged")?
+                .rewrite_interface("org.zbus.Sensor", "org.zbus.BridgedSensor")?
+                .build()
+                .await?;
+
+            let forwarded = MatchRule::builder()
+                .msg_type(Type::Signal)
+                .sender(target.unique_name().unwrap())?
+                .build();
+            let mut stream = MessageStream::for_match_rule(forwarded, &listener, None).await?;
+
+            emitter
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/Sensors/Kitchen",
+                    "org.zbus.Sensor",
+                    "Reading",
+                    &(("celsius", 21.5), vec!["calibrated"]),
+                )
+                .await?;
+            let msg = stream.next().await.unwrap()?;
+            let hdr = msg.header();
+            assert_eq!(hdr.path().unwrap(), "/org/zbus/Bridged/Kitchen");
+            assert_eq!(hdr.interface().unwrap(), "org.zbus.BridgedSensor");
+            assert_eq!(hdr.member().unwrap(), "Reading");
+            let ((unit, value), tags): ((String, f64), Vec<String>) = msg.body().deserialize()?;
+            assert_eq!((unit.as_str(), value), ("celsius", 21.5));
+            assert_eq!(tags, ["calibrated"]);
+
+            // Nothing is forwarded once the bridge is dropped.
+            drop(bridge);
+            emitter
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/Sensors",
+                    "org.zbus.Sensor",
+                    "Reading",
+                    &(("celsius", 22.0), vec![""]),
+                )
+                .await?;
+            target
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/Target",
+                    "org.zbus.Marker",
+                    "Done",
+                    &(),
+                )
+                .await?;
+            let msg = stream.next().await.unwrap()?;
+            assert_eq!(msg.header().member().unwrap(), "Done");
+
+            Ok::<_, crate::Error>(())
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,906 @@
+use enumflags2::BitFlags;
+use event_listener::Event;
+use static_assertions::assert_impl_all;
+#[cfg(unix)]
+use std::os::fd::BorrowedFd;
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+use tracing::{trace, warn};
+use zbus_names::{BusName, OwnedUniqueName, UniqueName, WellKnownName};
+use zvariant::{
+    serialized::{self, Context},
+    Endian, Value,
+};
+
+use super::build_with_body;
+use crate::{
+    connection::socket::{BoxedSplit, ReadHalf, RecvmsgResult, Split, WriteHalf},
+    fdo::{self, ReleaseNameReply, RequestNameFlags, RequestNameReply},
+    message::{
+        self,
+        header::{MAX_MESSAGE_SIZE, MIN_MESSAGE_SIZE},
+        Flags, Message, PrimaryHeader, Type,
+    },
+    utils::padding_for_8_bytes,
+    DBusError, Error, Guid, OwnedGuid, OwnedMatchRule, Result,
+};
+
+// The name of the bus itself.
+const BUS_NAME: &str = "org.freedesktop.DBus";
+const BUS_PATH: &str = "/org/freedesktop/DBus";
+
+/// A message bus, running in-process.
+///
+/// It implements the name registration, routing and match rules of a bus daemon, i-e the
+/// `org.freedesktop.DBus` interface, apart from service activation, monitoring and the
+/// credentials other than the process ID. All the connections are made by the current process, so
+/// there's no security policy. File descriptors can't be passed.
+///
+/// Clones refer to the same bus, which lives as long as connections are made to it.
+///
+/// See the [module documentation](mod@crate::test) for an example.
+#[derive(Clone, Debug, Default)]
+pub struct MockBus {
+    inner: Arc<Mutex<Bus>>,
+}
+
+assert_impl_all!(MockBus: Send, Sync, Unpin);
+
+impl MockBus {
+    /// Create a bus, without any connection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The unique names of the connections to the bus.
+    pub fn connections(&self) -> Vec<OwnedUniqueName> {
+        let bus = self.lock();
+        let mut names: Vec<_> = bus.peers.keys().cloned().collect();
+        names.sort();
+
+        names
+    }
+
+    /// The unique name of the primary owner of the well-known `name`.
+    pub fn name_owner(&self, name: &WellKnownName<'_>) -> Option<OwnedUniqueName> {
+        self.lock()
+            .names
+            .get(name.as_str())
+            .and_then(|owners| owners.front())
+            .map(|(owner, _)| owner.clone())
+    }
+
+    /// Create the socket of a new connection to the bus.
+    pub(crate) fn connect(&self) -> BoxedSplit {
+        let queue = Arc::new(Queue::default());
+        let name = self.lock().add_peer(queue.clone());
+        trace!("Connection {name} made to mock bus");
+
+        Split {
+            read: Box::new(MockRead {
+                queue,
+                pending: None,
+                pos: 0,
+            }),
+            write: Box::new(MockWrite {
+                bus: self.clone(),
+                name,
+                buffer: vec![],
+                closed: false,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bus> {
+        // The state is kept consistent even if routing panics, so there's no harm in ignoring the
+        // poisoning.
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Debug)]
+struct Bus {
+    guid: OwnedGuid,
+    next_id: u64,
+    peers: HashMap<OwnedUniqueName, Peer>,
+    // The owners of the well-known names, the primary one first, with the flags they requested
+    // the name with.
+    names: HashMap<String, VecDeque<(OwnedUniqueName, BitFlags<RequestNameFlags>)>>,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Self {
+            guid: Guid::generate().into(),
+            next_id: 1,
+            peers: HashMap::new(),
+            names: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Peer {
+    queue: Arc<Queue>,
+    rules: Vec<OwnedMatchRule>,
+    said_hello: bool,
+}
+
+impl Bus {
+    fn add_peer(&mut self, queue: Arc<Queue>) -> OwnedUniqueName {
+        let name: OwnedUniqueName =
+            UniqueName::from_string_unchecked(format!(":1.{}", self.next_id)).into();
+        self.next_id += 1;
+        self.peers.insert(
+            name.clone(),
+            Peer {
+                queue,
+                rules: vec![],
+                said_hello: false,
+            },
+        );
+
+        name
+    }
+
+    fn remove_peer(&mut self, name: &UniqueName<'_>) {
+        let Some(peer) = self.peers.remove(name.as_str()) else {
+            return;
+        };
+        peer.queue.close();
+
+        let mut out = vec![];
+        let owned: Vec<_> = self
+            .names
+            .iter()
+            .filter(|(_, owners)| owners.iter().any(|(owner, _)| owner == name))
+            .map(|(well_known, _)| well_known.clone())
+            .collect();
+        for well_known in owned {
+            self.release_name(&well_known, name, false, &mut out);
+        }
+        if peer.said_hello {
+            out.extend(name_owner_changed(name, Some(name), None));
+        }
+        for msg in out {
+            self.emit(msg);
+        }
+    }
+
+    // Route `msg`, sent by the connection `sender`.
+    fn route(&mut self, sender: &UniqueName<'_>, msg: &Message) {
+        let msg = match with_sender(msg, sender) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!("Failed to route message from {sender}: {e}");
+                return;
+            }
+        };
+        trace!("Routing message: {:?}", msg);
+        let hdr = msg.header();
+        let destination = hdr.destination().map(|d| d.to_owned());
+        let expects_reply = msg.message_type() == Type::MethodCall
+            && !hdr.primary().flags().contains(Flags::NoReplyExpected);
+
+        match destination {
+            Some(destination) if destination == BUS_NAME => {
+                if msg.message_type() != Type::MethodCall {
+                    return;
+                }
+                let mut out = vec![];
+                if let Err(e) = self.handle_bus_call(&msg, &mut out) {
+                    match error_reply(&msg, &e) {
+                        Ok(reply) => out.push(reply),
+                        Err(e) => warn!("Failed to create error reply: {e}"),
+                    }
+                }
+                if !expects_reply {
+                    out.retain(|m| m.message_type() == Type::Signal);
+                }
+                for msg in out {
+                    self.emit(msg);
+                }
+            }
+            Some(destination) => match self.resolve(&destination) {
+                Some(peer) => self.deliver(&peer, msg),
+                None if expects_reply => {
+                    let e = fdo::Error::ServiceUnknown(format!(
+                        "The name {destination} was not provided by any .service files"
+                    ));
+                    match error_reply(&msg, &e) {
+                        Ok(reply) => self.emit(reply),
+                        Err(e) => warn!("Failed to create error reply: {e}"),
+                    }
+                }
+                None => (),
+            },
+            None => self.broadcast(msg),
+        }
+    }
+
+    // Handle a call to the bus itself, pushing the messages to emit to `out`.
+    fn handle_bus_call(&mut self, call: &Message, out: &mut Vec<Message>) -> fdo::Result<()> {
+        let hdr = call.header();
+        // SAFETY: The sender was set by `route`.
+        let sender = hdr.sender().expect("no sender").to_owned();
+        let interface = hdr.interface().map(|i| i.as_str()).unwrap_or(BUS_NAME);
+        let member = hdr.member().map(|m| m.as_str()).unwrap_or_default();
+        let body = call.body();
+        let reply = Message::method_reply(call)?.sender(BUS_NAME)?;
+
+        let reply = match (interface, member) {
+            (BUS_NAME, "Hello") => {
+                let peer = self.peers.get_mut(sender.as_str()).expect("unknown sender");
+                if peer.said_hello {
+                    return Err(fdo::Error::Failed(
+                        "Already handled an Hello message".to_string(),
+                    ));
+                }
+                peer.said_hello = true;
+                // The reply comes before the signals.
+                out.push(reply.build(&sender)?);
+                out.extend(name_owner_changed(&sender, None, Some(&sender)));
+                out.extend(name_signal("NameAcquired", &sender, &sender));
+
+                return Ok(());
+            }
+            (BUS_NAME, "RequestName") => {
+                let (name, flags) = body.deserialize::<(&str, u32)>()?;
+                let name = self.well_known_name(name)?;
+                let flags = BitFlags::<RequestNameFlags>::from_bits_truncate(flags);
+                let result = self.request_name(&name, &sender, flags, out);
+
+                reply.build(&result)?
+            }
+            (BUS_NAME, "ReleaseName") => {
+                let name = self.well_known_name(body.deserialize::<&str>()?)?;
+                let result = self.release_name(&name, &sender, true, out);
+
+                reply.build(&result)?
+            }
+            (BUS_NAME, "GetNameOwner") => {
+                let name = body.deserialize::<&str>()?;
+                let owner = self.owner(name).ok_or_else(|| no_owner(name))?;
+
+                reply.build(&owner)?
+            }
+            (BUS_NAME, "NameHasOwner") => {
+                let name = body.deserialize::<&str>()?;
+
+                reply.build(&self.owner(name).is_some())?
+            }
+            (BUS_NAME, "ListNames") => {
+                let mut names = vec![BUS_NAME.to_string()];
+                names.extend(self.peers.keys().map(|n| n.to_string()));
+                names.extend(self.names.keys().cloned());
+
+                reply.build(&names)?
+            }
+            (BUS_NAME, "ListActivatableNames") => reply.build(&vec![BUS_NAME])?,
+            (BUS_NAME, "ListQueuedOwners") => {
+                let name = body.deserialize::<&str>()?;
+                let owners: Vec<_> = match self.names.get(name) {
+                    Some(owners) => owners.iter().map(|(o, _)| o.to_string()).collect(),
+                    None => match self.owner(name) {
+                        Some(owner) => vec![owner],
+                        None => return Err(no_owner(name)),
+                    },
+                };
+
+                reply.build(&owners)?
+            }
+            (BUS_NAME, "AddMatch") => {
+                let rule = body.deserialize::<&str>()?;
+                let rule = OwnedMatchRule::try_from(rule)
+                    .map_err(|e| fdo::Error::MatchRuleInvalid(e.to_string()))?;
+                let peer = self.peers.get_mut(sender.as_str()).expect("unknown sender");
+                peer.rules.push(rule);
+
+                reply.build(&())?
+            }
+            (BUS_NAME, "RemoveMatch") => {
+                let rule = body.deserialize::<&str>()?;
+                let rule = OwnedMatchRule::try_from(rule)
+                    .map_err(|e| fdo::Error::MatchRuleInvalid(e.to_string()))?;
+                let peer = self.peers.get_mut(sender.as_str()).expect("unknown sender");
+                let pos = peer.rules.iter().position(|r| *r == rule).ok_or_else(|| {
+                    fdo::Error::MatchRuleNotFound("The given match rule wasn't found".to_string())
+                })?;
+                peer.rules.remove(pos);
+
+                reply.build(&())?
+            }
+            (BUS_NAME, "GetId") => reply.build(&self.guid.as_str())?,
+            (BUS_NAME, "GetConnectionUnixProcessID") => {
+                let name = body.deserialize::<&str>()?;
+                self.owner(name).ok_or_else(|| no_owner(name))?;
+
+                reply.build(&std::process::id())?
+            }
+            (BUS_NAME, "GetConnectionCredentials") => {
+                let name = body.deserialize::<&str>()?;
+                self.owner(name).ok_or_else(|| no_owner(name))?;
+                let mut credentials = HashMap::new();
+                credentials.insert("ProcessID", Value::from(std::process::id()));
+
+                reply.build(&credentials)?
+            }
+            ("org.freedesktop.DBus.Peer", "Ping") => reply.build(&())?,
+            ("org.freedesktop.DBus.Properties", "GetAll") => {
+                let mut properties = HashMap::new();
+                if body.deserialize::<&str>()? == BUS_NAME {
+                    properties.insert("Features", Value::from(Vec::<String>::new()));
+                    properties.insert("Interfaces", Value::from(Vec::<String>::new()));
+                }
+
+                reply.build(&properties)?
+            }
+            _ => {
+                return Err(fdo::Error::UnknownMethod(format!(
+                    "Unknown method `{member}` on interface `{interface}`"
+                )))
+            }
+        };
+        out.push(reply);
+
+        Ok(())
+    }
+
+    fn request_name(
+        &mut self,
+        name: &WellKnownName<'_>,
+        sender: &UniqueName<'_>,
+        flags: BitFlags<RequestNameFlags>,
+        out: &mut Vec<Message>,
+    ) -> RequestNameReply {
+        let owners = self.names.entry(name.to_string()).or_default();
+        let pos = owners.iter().position(|(owner, _)| owner == sender);
+        if pos == Some(0) {
+            owners[0].1 = flags;
+
+            return RequestNameReply::AlreadyOwner;
+        }
+
+        let replace = owners.front().is_some_and(|(_, owner_flags)| {
+            owner_flags.contains(RequestNameFlags::AllowReplacement)
+                && flags.contains(RequestNameFlags::ReplaceExisting)
+        });
+        if !owners.is_empty() && !replace {
+            if flags.contains(RequestNameFlags::DoNotQueue) {
+                if let Some(pos) = pos {
+                    owners.remove(pos);
+                }
+
+                return RequestNameReply::Exists;
+            }
+            match pos {
+                Some(pos) => owners[pos].1 = flags,
+                None => owners.push_back((sender.to_owned().into(), flags)),
+            }
+
+            return RequestNameReply::InQueue;
+        }
+
+        if let Some(pos) = pos {
+            owners.remove(pos);
+        }
+        let old_owner = if replace {
+            // SAFETY: We only replace an existing owner.
+            let (old_owner, old_flags) = owners.pop_front().expect("no owner");
+            // Unless it asked not to be, the replaced owner is first in the queue.
+            if !old_flags.contains(RequestNameFlags::DoNotQueue) {
+                owners.push_front((old_owner.clone(), old_flags));
+            }
+            out.extend(name_signal("NameLost", &old_owner, name));
+
+            Some(old_owner)
+        } else {
+            None
+        };
+        owners.push_front((sender.to_owned().into(), flags));
+        out.extend(name_owner_changed(name, old_owner.as_deref(), Some(sender)));
+        out.extend(name_signal("NameAcquired", sender, name));
+
+        RequestNameReply::PrimaryOwner
+    }
+
+    // Release `name` for `owner`, notifying it if `notify` is true.
+    fn release_name(
+        &mut self,
+        name: &str,
+        owner: &UniqueName<'_>,
+        notify: bool,
+        out: &mut Vec<Message>,
+    ) -> ReleaseNameReply {
+        let Some(owners) = self.names.get_mut(name) else {
+            return ReleaseNameReply::NonExistent;
+        };
+        match owners.iter().position(|(o, _)| o == owner) {
+            None => return ReleaseNameReply::NotOwner,
+            Some(0) => {
+                owners.pop_front();
+                let new_owner = owners.front().map(|(o, _)| o.clone());
+                if notify {
+                    out.extend(name_signal("NameLost", owner, name));
+                }
+                out.extend(name_owner_changed(name, Some(owner), new_owner.as_deref()));
+                if let Some(new_owner) = &new_owner {
+                    out.extend(name_signal("NameAcquired", new_owner, name));
+                }
+            }
+            Some(pos) => {
+                owners.remove(pos);
+            }
+        }
+        if owners.is_empty() {
+            self.names.remove(name);
+        }
+
+        ReleaseNameReply::Released
+    }
+
+    fn well_known_name<'n>(&self, name: &'n str) -> fdo::Result<WellKnownName<'n>> {
+        match WellKnownName::try_from(name) {
+            Ok(name) if name != BUS_NAME => Ok(name),
+            Ok(_) => Err(fdo::Error::InvalidArgs(format!(
+                "Connection is not allowed to own the name {BUS_NAME}"
+            ))),
+            Err(e) => Err(fdo::Error::InvalidArgs(e.to_string())),
+        }
+    }
+
+    // The unique name owning `name`, which can be a unique name itself.
+    fn owner(&self, name: &str) -> Option<String> {
+        if name == BUS_NAME {
+            return Some(BUS_NAME.to_string());
+        }
+        match BusName::try_from(name).ok()? {
+            BusName::Unique(name) => self
+                .peers
+                .contains_key(name.as_str())
+                .then(|| name.to_string()),
+            BusName::WellKnown(name) => self
+                .names
+                .get(name.as_str())
+                .and_then(|owners| owners.front())
+                .map(|(owner, _)| owner.to_string()),
+        }
+    }
+
+    fn resolve(&self, destination: &BusName<'_>) -> Option<OwnedUniqueName> {
+        let owner = self.owner(destination)?;
+
+        Some(UniqueName::from_string_unchecked(owner).into())
+    }
+
+    // Emit a message from the bus itself.
+    fn emit(&mut self, msg: Message) {
+        match msg.header().destination() {
+            Some(destination) => {
+                if let Some(peer) = self.resolve(destination) {
+                    self.deliver(&peer, msg.clone());
+                }
+            }
+            None => self.broadcast(msg.clone()),
+        }
+    }
+
+    // Deliver `msg` to all the connections with a matching rule.
+    fn broadcast(&self, msg: Message) {
+        let hdr = msg.header();
+        let sender = hdr.sender().map(|s| s.as_str());
+        let recipients = self.peers.iter().filter(|(_, peer)| {
+            peer.rules.iter().any(|rule| {
+                // Rules only match the well-known names of the senders through their owner.
+                if let Some(BusName::WellKnown(name)) = rule.sender() {
+                    if self.owner(name).as_deref() != sender {
+                        return false;
+                    }
+                }
+
+                rule.matches(&msg).unwrap_or(false)
+            })
+        });
+        for (_, peer) in recipients {
+            peer.queue.push(msg.clone());
+        }
+    }
+
+    fn deliver(&self, destination: &UniqueName<'_>, msg: Message) {
+        if let Some(peer) = self.peers.get(destination.as_str()) {
+            peer.queue.push(msg);
+        }
+    }
+}
+
+// The messages routed to a connection.
+#[derive(Debug, Default)]
+struct Queue {
+    messages: Mutex<VecDeque<Message>>,
+    closed: AtomicBool,
+    event: Event,
+}
+
+impl Queue {
+    fn push(&self, msg: Message) {
+        self.messages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(msg);
+        self.event.notify(usize::MAX);
+    }
+
+    fn pop(&self) -> Option<Message> {
+        self.messages
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.event.notify(usize::MAX);
+    }
+}
+
+#[derive(Debug)]
+struct MockRead {
+    queue: Arc<Queue>,
+    // The message being read, from `pos`.
+    pending: Option<Message>,
+    pos: usize,
+}
+
+#[async_trait::async_trait]
+impl ReadHalf for MockRead {
+    async fn recvmsg(&mut self, buf: &mut [u8]) -> RecvmsgResult {
+        loop {
+            if let Some(msg) = &self.pending {
+                let data = &msg.data()[self.pos..];
+                let len = buf.len().min(data.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                self.pos += len;
+                if self.pos == msg.data().len() {
+                    self.pending = None;
+                    self.pos = 0;
+                }
+
+                #[cfg(unix)]
+                let ret = (len, vec![]);
+                #[cfg(not(unix))]
+                let ret = len;
+                return Ok(ret);
+            }
+
+            let listener = self.queue.event.listen();
+            match self.queue.pop() {
+                Some(msg) => self.pending = Some(msg),
+                // A disconnected connection is the end of the stream.
+                None if self.queue.closed.load(Ordering::SeqCst) => {
+                    #[cfg(unix)]
+                    let ret = (0, vec![]);
+                    #[cfg(not(unix))]
+                    let ret = 0;
+                    return Ok(ret);
+                }
+                None => listener.await,
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MockWrite {
+    bus: MockBus,
+    name: OwnedUniqueName,
+    // The data written but not routed yet, as it's not a full message.
+    buffer: Vec<u8>,
+    closed: bool,
+}
+
+impl MockWrite {
+    // Take the first message out of the buffer, if it's complete.
+    fn take_message(&mut self) -> Result<Option<Message>> {
+        if self.buffer.len() < MIN_MESSAGE_SIZE {
+            return Ok(None);
+        }
+        let (primary_header, fields_len) = PrimaryHeader::read(&self.buffer)?;
+        let header_len = MIN_MESSAGE_SIZE + fields_len as usize;
+        let total_len =
+            header_len + padding_for_8_bytes(header_len) + primary_header.body_len() as usize;
+        if total_len > MAX_MESSAGE_SIZE {
+            return Err(Error::ExcessData);
+        }
+        if self.buffer.len() < total_len {
+            return Ok(None);
+        }
+
+        let rest = self.buffer.split_off(total_len);
+        let bytes = std::mem::replace(&mut self.buffer, rest);
+        let ctxt = Context::new_dbus(Endian::from(primary_header.endian_sig()), 0);
+
+        Message::from_raw_parts(serialized::Data::new(bytes, ctxt), 0).map(Some)
+    }
+
+    fn disconnect(&mut self) {
+        if !self.closed {
+            self.closed = true;
+            self.bus.lock().remove_peer(&self.name);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WriteHalf for MockWrite {
+    async fn sendmsg(
+        &mut self,
+        buffer: &[u8],
+        #[cfg(unix)] fds: &[BorrowedFd<'_>],
+    ) -> io::Result<usize> {
+        #[cfg(unix)]
+        if !fds.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "fds cannot be sent over a mock bus",
+            ));
+        }
+        if self.closed {
+            return Err(io::Error::from(io::ErrorKind::BrokenPipe));
+        }
+
+        self.buffer.extend_from_slice(buffer);
+        while let Some(msg) = self
+            .take_message()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        {
+            self.bus.lock().route(&self.name, &msg);
+        }
+
+        Ok(buffer.len())
+    }
+
+    async fn close(&mut self) -> io::Result<()> {
+        self.disconnect();
+
+        Ok(())
+    }
+}
+
+impl Drop for MockWrite {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+// A copy of `msg`, with its sender set to `sender`.
+fn with_sender(msg: &Message, sender: &UniqueName<'_>) -> Result<Message> {
+    let builder = message::Builder::from(msg.header()).sender(sender)?;
+
+    build_with_body(builder, msg)
+}
+
+fn error_reply(call: &Message, error: &fdo::Error) -> Result<Message> {
+    let builder = Message::method_error(call, error.name())?.sender(BUS_NAME)?;
+    match error.description() {
+        Some(description) => builder.build(&description),
+        None => builder.build(&()),
+    }
+}
+
+fn no_owner(name: &str) -> fdo::Error {
+    fdo::Error::NameHasNoOwner(format!(
+        "Could not get owner of name '{name}': no such name"
+    ))
+}
+
+fn name_owner_changed(
+    name: &str,
+    old_owner: Option<&UniqueName<'_>>,
+    new_owner: Option<&UniqueName<'_>>,
+) -> Option<Message> {
+    let old_owner = old_owner.map(|o| o.as_str()).unwrap_or_default();
+    let new_owner = new_owner.map(|o| o.as_str()).unwrap_or_default();
+
+    Message::signal(BUS_PATH, BUS_NAME, "NameOwnerChanged")
+        .and_then(|b| b.sender(BUS_NAME))
+        .and_then(|b| b.build(&(name, old_owner, new_owner)))
+        .map_err(|e| warn!("Failed to create `NameOwnerChanged` signal: {e}"))
+        .ok()
+}
+
+// A `NameAcquired` or `NameLost` signal, sent to `destination`.
+fn name_signal(member: &str, destination: &UniqueName<'_>, name: &str) -> Option<Message> {
+    Message::signal(BUS_PATH, BUS_NAME, member)
+        .and_then(|b| b.sender(BUS_NAME))
+        .and_then(|b| b.destination(destination.as_str()))
+        .and_then(|b| b.build(&name))
+        .map_err(|e| warn!("Failed to create `{member}` signal: {e}"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+    use ntest::timeout;
+    use test_log::test;
+
+    use super::MockBus;
+    use crate::{
+        block_on, connection,
+        fdo::{self, DBusProxy, ReleaseNameReply, RequestNameFlags, RequestNameReply},
+        names::WellKnownName,
+        Connection, MatchRule, MessageStream,
+    };
+
+    #[test]
+    #[timeout(15000)]
+    fn names() {
+        block_on(async {
+            let bus = MockBus::new();
+            let first = connection::Builder::mock(&bus).build().await.unwrap();
+            let second = connection::Builder::mock(&bus).build().await.unwrap();
+            assert_eq!(first.unique_name().unwrap().as_str(), ":1.1");
+            assert_eq!(second.unique_name().unwrap().as_str(), ":1.2");
+            assert_eq!(bus.connections(), [":1.1", ":1.2"]);
+
+            let name = WellKnownName::from_static_str_unchecked("org.zbus.MockBus");
+            let first_dbus = DBusProxy::new(&first).await.unwrap();
+            let second_dbus = DBusProxy::new(&second).await.unwrap();
+            let mut owner_changed = second_dbus.receive_name_owner_changed().await.unwrap();
+
+            let flags = RequestNameFlags::AllowReplacement.into();
+            let reply = first_dbus.request_name(name.clone(), flags).await.unwrap();
+            assert_eq!(reply, RequestNameReply::PrimaryOwner);
+            let reply = second_dbus
+                .request_name(name.clone(), Default::default())
+                .await
+                .unwrap();
+            assert_eq!(reply, RequestNameReply::InQueue);
+            let owners = first_dbus.list_queued_owners(name.clone()).await.unwrap();
+            assert_eq!(owners, [":1.1", ":1.2"]);
+
+            // Replacing the primary owner queues it.
+            let flags = RequestNameFlags::ReplaceExisting.into();
+            let reply = second_dbus.request_name(name.clone(), flags).await.unwrap();
+            assert_eq!(reply, RequestNameReply::PrimaryOwner);
+            assert_eq!(bus.name_owner(&name).unwrap(), ":1.2");
+            let owner = first_dbus
+                .get_name_owner(name.clone().into())
+                .await
+                .unwrap();
+            assert_eq!(owner, ":1.2");
+
+            let reply = second_dbus.release_name(name.clone()).await.unwrap();
+            assert_eq!(reply, ReleaseNameReply::Released);
+            assert_eq!(bus.name_owner(&name).unwrap(), ":1.1");
+
+            // The names of a connection are released when it's dropped.
+            drop(first_dbus);
+            drop(first);
+            loop {
+                let signal = owner_changed.next().await.unwrap();
+                let args = signal.args().unwrap();
+                if args.name() == &name && args.new_owner().is_none() {
+                    assert_eq!(args.old_owner().as_ref().unwrap(), ":1.1");
+                    break;
+                }
+            }
+            assert_eq!(bus.name_owner(&name), None);
+            assert_eq!(bus.connections(), [":1.2"]);
+            let e = second_dbus.get_name_owner(name.into()).await.unwrap_err();
+            assert!(matches!(e, fdo::Error::NameHasNoOwner(_)));
+        })
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn routing() {
+        struct Counter(u32);
+
+        #[crate::interface(name = "org.zbus.MockBus.Counter")]
+        impl Counter {
+            fn increment(&mut self) -> u32 {
+                self.0 += 1;
+
+                self.0
+            }
+        }
+
+        block_on(async {
+            let bus = MockBus::new();
+            let service = connection::Builder::mock(&bus)
+                .name("org.zbus.MockBus.Counter")
+                .unwrap()
+                .serve_at("/org/zbus/Counter", Counter(0))
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+            let client = connection::Builder::mock(&bus).build().await.unwrap();
+            let other = connection::Builder::mock(&bus).build().await.unwrap();
+
+            // Only the client subscribes to the signal.
+            let rule = MatchRule::builder()
+                .msg_type(crate::message::Type::Signal)
+                .sender("org.zbus.MockBus.Counter")
+                .unwrap()
+                .member("Tick")
+                .unwrap()
+                .build();
+            let mut signals = MessageStream::for_match_rule(rule, &client, None)
+                .await
+                .unwrap();
+
+            for (i, destination) in ["org.zbus.MockBus.Counter", ":1.1"].into_iter().enumerate() {
+                let reply = client
+                    .call_method(
+                        Some(destination),
+                        "/org/zbus/Counter",
+                        Some("org.zbus.MockBus.Counter"),
+                        "Increment",
+                        &(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(reply.body().deserialize::<u32>().unwrap(), i as u32 + 1);
+                assert_eq!(reply.header().sender().unwrap(), ":1.1");
+            }
+
+            let e = client
+                .call_method(
+                    Some("org.zbus.MockBus.Nobody"),
+                    "/",
+                    Some("org.zbus.MockBus.Counter"),
+                    "Increment",
+                    &(),
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(fdo::Error::from(e), fdo::Error::ServiceUnknown(_)));
+
+            other
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/Counter",
+                    "org.zbus.MockBus.Counter",
+                    "Tick",
+                    &1u32,
+                )
+                .await
+                .unwrap();
+            service
+                .emit_signal(
+                    None::<()>,
+                    "/org/zbus/Counter",
+                    "org.zbus.MockBus.Counter",
+                    "Tick",
+                    &2u32,
+                )
+                .await
+                .unwrap();
+            let signal = signals.next().await.unwrap().unwrap();
+            assert_eq!(signal.body().deserialize::<u32>().unwrap(), 2);
+            assert_eq!(signal.header().sender().unwrap(), ":1.1");
+        })
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn blocking() {
+        let bus = MockBus::new();
+        let conn: Connection = crate::blocking::connection::Builder::mock(&bus)
+            .build()
+            .unwrap()
+            .into();
+        let dbus = crate::blocking::fdo::DBusProxy::new(&conn.into()).unwrap();
+        assert!(dbus.name_has_owner(":1.1".try_into().unwrap()).unwrap());
+        assert!(!dbus.name_has_owner(":1.2".try_into().unwrap()).unwrap());
+        assert!(dbus.get_id().is_ok());
+    }
+}
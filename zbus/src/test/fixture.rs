@@ -0,0 +1,38 @@
+use crate::{blocking, connection, Connection, Result};
+
+use super::MockBus;
+
+/// A value created for the test functions taking it as an argument.
+///
+/// The [`test`](macro@crate::test) attribute creates the arguments of the test function it's
+/// applied to through this trait, from the private bus of the test. It can be implemented to have
+/// the attribute take care of more of the setup, such as creating proxies.
+#[async_trait::async_trait]
+pub trait Fixture: Sized {
+    /// Create the fixture for a test running on `bus`.
+    async fn create(bus: &MockBus) -> Result<Self>;
+}
+
+/// A new connection to the bus.
+#[async_trait::async_trait]
+impl Fixture for Connection {
+    async fn create(bus: &MockBus) -> Result<Self> {
+        connection::Builder::mock(bus).build().await
+    }
+}
+
+/// A new connection to the bus.
+#[async_trait::async_trait]
+impl Fixture for blocking::Connection {
+    async fn create(bus: &MockBus) -> Result<Self> {
+        Connection::create(bus).await.map(Into::into)
+    }
+}
+
+/// The bus itself.
+#[async_trait::async_trait]
+impl Fixture for MockBus {
+    async fn create(bus: &MockBus) -> Result<Self> {
+        Ok(bus.clone())
+    }
+}
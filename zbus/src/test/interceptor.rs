@@ -0,0 +1,312 @@
+use static_assertions::assert_impl_all;
+use std::{
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tracing::warn;
+use zbus_names::{InterfaceName, MemberName, OwnedInterfaceName, OwnedMemberName};
+
+use crate::{
+    fdo,
+    message::{Flags, Type},
+    DBusError, Error, Executor, Message, Result,
+};
+
+type Responder = Arc<dyn Fn(&Message) -> Result<Message> + Send + Sync>;
+
+/// Intercepts the method calls of a connection, answering them with canned replies.
+///
+/// The interceptor is set up with [`connection::Builder::intercept`]. The method calls the
+/// connection then sends are matched against the interceptions registered with
+/// [`Interceptor::on`] and, instead of being sent, are answered by the matching one. The calls
+/// not matching any interception are sent as usual.
+///
+/// Clones refer to the same interceptor, so interceptions can be registered and the intercepted
+/// calls inspected after the connection is built.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// use std::time::Duration;
+/// use zbus::{connection, fdo, proxy, test::{Interceptor, MockBus}};
+///
+/// #[proxy(
+///     interface = "org.example.Greeter",
+///     default_service = "org.example.Greeter",
+///     default_path = "/org/example/Greeter"
+/// )]
+/// trait Greeter {
+///     fn greet(&self, name: &str) -> zbus::Result<String>;
+/// }
+///
+/// # zbus::block_on(async {
+/// let interceptor = Interceptor::new();
+/// interceptor
+///     .on("org.example.Greeter", "Greet")?
+///     .delay(Duration::from_millis(10))
+///     .reply("Hello, zbus!");
+///
+/// let conn = connection::Builder::mock(&MockBus::new())
+///     .intercept(interceptor.clone())
+///     .build()
+///     .await?;
+/// let greeter = GreeterProxy::new(&conn).await?;
+/// assert_eq!(greeter.greet("zbus").await?, "Hello, zbus!");
+///
+/// // Later interceptions take precedence.
+/// interceptor
+///     .on("org.example.Greeter", "Greet")?
+///     .error(fdo::Error::AccessDenied("Not today".to_string()));
+/// let e = greeter.greet("zbus").await.unwrap_err();
+/// assert!(matches!(fdo::Error::from(e), fdo::Error::AccessDenied(_)));
+///
+/// let calls = interceptor.calls();
+/// assert_eq!(calls.len(), 2);
+/// assert_eq!(calls[0].body().deserialize::<&str>()?, "zbus");
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+///
+/// [`connection::Builder::intercept`]: crate::connection::Builder::intercept
+#[derive(Clone, Debug, Default)]
+pub struct Interceptor {
+    inner: Arc<Mutex<Inner>>,
+}
+
+assert_impl_all!(Interceptor: Send, Sync, Unpin);
+
+#[derive(Debug, Default)]
+struct Inner {
+    rules: Vec<Rule>,
+    calls: Vec<Message>,
+}
+
+struct Rule {
+    interface: OwnedInterfaceName,
+    member: OwnedMemberName,
+    delay: Option<Duration>,
+    responder: Responder,
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rule")
+            .field("interface", &self.interface)
+            .field("member", &self.member)
+            .field("delay", &self.delay)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Interceptor {
+    /// Create an interceptor, without any interception.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Intercept the calls to the `member` method of `interface`.
+    ///
+    /// The interception is registered once its response is given. It takes precedence over the
+    /// ones registered before for the same method.
+    pub fn on<'i, 'm, I, M>(&self, interface: I, member: M) -> Result<Interception<'_>>
+    where
+        I: TryInto<InterfaceName<'i>>,
+        M: TryInto<MemberName<'m>>,
+        I::Error: Into<Error>,
+        M::Error: Into<Error>,
+    {
+        Ok(Interception {
+            interceptor: self,
+            interface: interface.try_into().map_err(Into::into)?.into(),
+            member: member.try_into().map_err(Into::into)?.into(),
+            delay: None,
+        })
+    }
+
+    /// The calls intercepted so far, in the order they were made.
+    pub fn calls(&self) -> Vec<Message> {
+        self.lock().calls.clone()
+    }
+
+    /// Remove all the interceptions, and forget about the intercepted calls.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.rules.clear();
+        inner.calls.clear();
+    }
+
+    /// Intercept `msg`, if it's a call matching an interception.
+    pub(crate) fn intercept(&self, msg: &Message) -> Option<Intercepted> {
+        if msg.message_type() != Type::MethodCall {
+            return None;
+        }
+        let hdr = msg.header();
+        let (interface, member) = (hdr.interface()?, hdr.member()?);
+        let mut inner = self.lock();
+        let rule = inner
+            .rules
+            .iter()
+            .rev()
+            .find(|r| r.interface == *interface && r.member == *member)?;
+        let intercepted = Intercepted {
+            call: msg.clone(),
+            delay: rule.delay,
+            responder: rule.responder.clone(),
+        };
+        inner.calls.push(msg.clone());
+
+        Some(intercepted)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// An interception being registered.
+///
+/// Created by [`Interceptor::on`], it's registered by giving the response to the intercepted calls
+/// with [`Interception::reply`], [`Interception::error`] or [`Interception::respond`].
+#[derive(Debug)]
+#[must_use = "the interception is only registered once its response is given"]
+pub struct Interception<'i> {
+    interceptor: &'i Interceptor,
+    interface: OwnedInterfaceName,
+    member: OwnedMemberName,
+    delay: Option<Duration>,
+}
+
+impl Interception<'_> {
+    /// Delay the response by `delay`.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+
+        self
+    }
+
+    /// Reply to the calls with `body`.
+    pub fn reply<B>(self, body: B)
+    where
+        B: serde::ser::Serialize + zvariant::DynamicType + Send + Sync + 'static,
+    {
+        self.respond(move |call| Message::method_reply(call)?.build(&body))
+    }
+
+    /// Reply to the calls with `error`.
+    pub fn error<E>(self, error: E)
+    where
+        E: DBusError + Send + Sync + 'static,
+    {
+        self.respond(move |call| error.create_reply(&call.header()))
+    }
+
+    /// Reply to the calls with the message `responder` creates for each of them.
+    ///
+    /// If `responder` fails, the call is replied to with the error.
+    pub fn respond<F>(self, responder: F)
+    where
+        F: Fn(&Message) -> Result<Message> + Send + Sync + 'static,
+    {
+        self.interceptor.lock().rules.push(Rule {
+            interface: self.interface,
+            member: self.member,
+            delay: self.delay,
+            responder: Arc::new(responder),
+        });
+    }
+}
+
+/// A call intercepted by an [`Interceptor`].
+pub(crate) struct Intercepted {
+    call: Message,
+    delay: Option<Duration>,
+    responder: Responder,
+}
+
+impl Intercepted {
+    /// The reply to the call, after the delay, if it expects one.
+    pub(crate) async fn reply(self, executor: &Executor<'_>) -> Option<Message> {
+        if let Some(delay) = self.delay {
+            executor.timer(delay).await;
+        }
+        if self
+            .call
+            .primary_header()
+            .flags()
+            .contains(Flags::NoReplyExpected)
+        {
+            return None;
+        }
+
+        let header = self.call.header();
+        (self.responder)(&self.call)
+            .or_else(|e| fdo::Error::from(e).create_reply(&header))
+            .map_err(|e| warn!("Failed to create reply to intercepted call: {e}"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use std::time::Duration;
+    use test_log::test;
+
+    use super::Interceptor;
+    use crate::{block_on, connection, fdo::DBusProxy, test::MockBus, Message};
+
+    #[test]
+    #[timeout(15000)]
+    fn intercept() {
+        block_on(async {
+            let interceptor = Interceptor::new();
+            let conn = connection::Builder::mock(&MockBus::new())
+                .intercept(interceptor.clone())
+                .build()
+                .await
+                .unwrap();
+            let dbus = DBusProxy::new(&conn).await.unwrap();
+
+            // Calls not matching any interception go through.
+            let id = dbus.get_id().await.unwrap();
+            assert!(interceptor.calls().is_empty());
+
+            interceptor
+                .on("org.freedesktop.DBus", "GetId")
+                .unwrap()
+                .delay(Duration::from_millis(50))
+                .reply("0123456789abcdef0123456789abcdef");
+            interceptor
+                .on("org.freedesktop.DBus", "ListNames")
+                .unwrap()
+                .respond(|call| {
+                    let sender = call.header().sender().unwrap().to_string();
+
+                    Message::method_reply(call)?.build(&vec![sender])
+                });
+            let (slow, fast) = futures_util::join!(dbus.get_id(), dbus.list_names());
+            assert_eq!(slow.unwrap().as_str(), "0123456789abcdef0123456789abcdef");
+            assert_eq!(fast.unwrap(), [conn.unique_name().unwrap().as_str()]);
+            let calls: Vec<_> = interceptor
+                .calls()
+                .iter()
+                .map(|c| c.header().member().unwrap().to_string())
+                .collect();
+            assert_eq!(calls, ["GetId", "ListNames"]);
+
+            // A failing responder replies with its error.
+            interceptor
+                .on("org.freedesktop.DBus", "ListNames")
+                .unwrap()
+                .respond(|_| Err(crate::Error::Unsupported));
+            assert!(dbus.list_names().await.is_err());
+
+            interceptor.clear();
+            assert_eq!(dbus.get_id().await.unwrap(), id);
+            assert!(interceptor.calls().is_empty());
+        })
+    }
+}
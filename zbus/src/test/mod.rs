@@ -0,0 +1,88 @@
+//! Testing facilities.
+//!
+//! Tests involving several connections usually need a running bus daemon, and a separate one per
+//! test to keep them from interfering with each other. A [`MockBus`] instead routes the messages of
+//! the connections made to it in memory: the connections are created with
+//! [`connection::Builder::mock`], and behave as if they were connected to a bus daemon. The
+//! [`test`](macro@crate::test) attribute takes care of this setup for a test function.
+//!
+//! Code calling a service can also be tested without the service, by intercepting its calls with
+//! an [`Interceptor`] and answering them with canned replies.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::error::Error;
+//! use zbus::{connection, interface, proxy, test::MockBus};
+//!
+//! struct Greeter;
+//!
+//! #[interface(name = "org.example.Greeter")]
+//! impl Greeter {
+//!     fn greet(&self, name: &str) -> String {
+//!         format!("Hello, {name}!")
+//!     }
+//! }
+//!
+//! #[proxy(
+//!     interface = "org.example.Greeter",
+//!     default_service = "org.example.Greeter",
+//!     default_path = "/org/example/Greeter"
+//! )]
+//! trait Greeter {
+//!     fn greet(&self, name: &str) -> zbus::Result<String>;
+//! }
+//!
+//! # zbus::block_on(async {
+//! let bus = MockBus::new();
+//! let _service = connection::Builder::mock(&bus)
+//!     .name("org.example.Greeter")?
+//!     .serve_at("/org/example/Greeter", Greeter)?
+//!     .build()
+//!     .await?;
+//!
+//! let client = connection::Builder::mock(&bus).build().await?;
+//! let greeter = GreeterProxy::new(&client).await?;
+//! assert_eq!(greeter.greet("zbus").await?, "Hello, zbus!");
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! # })?;
+//! # Ok::<_, Box<dyn Error + Send + Sync>>(())
+//! ```
+//!
+//! [`connection::Builder::mock`]: crate::connection::Builder::mock
+
+mod bus;
+pub use bus::MockBus;
+
+mod fixture;
+pub use fixture::Fixture;
+
+mod interceptor;
+pub use interceptor::*;
+
+mod replay;
+pub use replay::*;
+
+use zvariant::Signature;
+
+use crate::{message, Message, Result};
+
+// Build a message with the body of `msg`, as is.
+fn build_with_body(builder: message::Builder<'_>, msg: &Message) -> Result<Message> {
+    let body = msg.body();
+    // Wrap the signature in a structure, as `build_raw_body` unwraps it.
+    let signature = match body.signature() {
+        Some(signature) if !signature.is_empty() => Signature::try_from(format!("({signature})"))?,
+        _ => Signature::from_static_str_unchecked(""),
+    };
+
+    // SAFETY: The body, its signature and endianness all come from a valid message, without FDs.
+    unsafe {
+        builder.build_raw_body(
+            body.data().bytes(),
+            signature,
+            #[cfg(unix)]
+            vec![],
+        )
+    }
+}
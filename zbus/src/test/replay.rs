@@ -0,0 +1,582 @@
+use futures_util::{future::join_all, StreamExt};
+use static_assertions::assert_impl_all;
+use std::{
+    collections::HashSet,
+    fmt::{self, Write},
+    io,
+    time::{Duration, SystemTime},
+};
+use zbus_names::{BusName, OwnedBusName};
+use zvariant::{Endian, Structure};
+
+use super::build_with_body;
+use crate::{
+    message::{Flags, Type},
+    Connection, Error, Message, MessageStream, Result,
+};
+
+/// Replays the calls of a capture against a service, comparing its replies to the captured ones.
+///
+/// The method calls made to the service in the capture, such as the one read by a
+/// [`PcapngReader`], are sent again by a connection, at their original pace or a faster one (see
+/// [`Replay::timing`]). The replies of the service are then compared to the captured ones, turning
+/// a capture of a bug into a regression test.
+///
+/// The calls made to the service are the ones sent to its name, and to the unique names the
+/// captured replies came from. They are all sent to its name, so the service and the replaying
+/// connection are usually connected to a [`MockBus`]. The file descriptors of the captured calls
+/// aren't replayed.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// use std::time::SystemTime;
+/// use zbus::{connection, interface, test::{MockBus, Replay}, Message};
+///
+/// struct Counter(u32);
+///
+/// #[interface(name = "org.example.Counter")]
+/// impl Counter {
+///     fn next(&mut self) -> u32 {
+///         self.0 += 1;
+///         self.0
+///     }
+/// }
+///
+/// # zbus::block_on(async {
+/// // Usually read from a capture, e.g with `zbus::capture::PcapngReader`.
+/// let now = SystemTime::now();
+/// let call = Message::method("/org/example/Counter", "Next")?
+///     .interface("org.example.Counter")?
+///     .sender(":1.3")?
+///     .destination("org.example.Counter")?
+///     .build(&())?;
+/// let reply = Message::method_reply(&call)?
+///     .sender(":1.2")?
+///     .destination(":1.3")?
+///     .build(&1u32)?;
+/// let replay = Replay::new("org.example.Counter", [(now, call), (now, reply)])?;
+///
+/// let bus = MockBus::new();
+/// let _service = connection::Builder::mock(&bus)
+///     .name("org.example.Counter")?
+///     .serve_at("/org/example/Counter", Counter(0))?
+///     .build()
+///     .await?;
+/// let client = connection::Builder::mock(&bus).build().await?;
+/// replay.assert(&client).await;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// # })?;
+/// # Ok::<_, Box<dyn Error + Send + Sync>>(())
+/// ```
+///
+/// [`PcapngReader`]: crate::capture::PcapngReader
+/// [`MockBus`]: super::MockBus
+#[derive(Debug, Clone)]
+pub struct Replay {
+    service: OwnedBusName,
+    // The calls to replay, with their captured reply and their offset from the first one.
+    calls: Vec<(Message, Option<Message>, Duration)>,
+    timing: Timing,
+}
+
+assert_impl_all!(Replay: Send, Sync, Unpin);
+
+/// The pace at which a [`Replay`] sends its calls.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Timing {
+    /// The original pace of the capture.
+    #[default]
+    Original,
+    /// The original pace, sped up by the given factor. It must be positive.
+    Accelerated(f64),
+    /// All the calls at once.
+    Immediate,
+}
+
+impl Replay {
+    /// Create a replay of the calls made to `service` in `records`.
+    ///
+    /// The records are the messages of the capture, with their timestamp.
+    pub fn new<'s, S, I>(service: S, records: I) -> Result<Self>
+    where
+        S: TryInto<BusName<'s>>,
+        S::Error: Into<Error>,
+        I: IntoIterator<Item = (SystemTime, Message)>,
+    {
+        let service = OwnedBusName::from(service.try_into().map_err(Into::into)?);
+        let records: Vec<_> = records.into_iter().collect();
+
+        // The unique names of the service in the capture, as found from its replies.
+        let mut names = HashSet::from([service.to_string()]);
+        for (_, call) in &records {
+            let hdr = call.header();
+            if call.message_type() != Type::MethodCall
+                || !hdr
+                    .destination()
+                    .is_some_and(|d| d.as_str() == service.as_str())
+            {
+                continue;
+            }
+            if let Some(sender) =
+                reply_to(&records, call).and_then(|r| r.header().sender().map(|s| s.to_string()))
+            {
+                names.insert(sender);
+            }
+        }
+
+        let mut calls = vec![];
+        let mut start = None;
+        for (time, call) in &records {
+            let hdr = call.header();
+            if call.message_type() != Type::MethodCall
+                || !hdr
+                    .destination()
+                    .is_some_and(|d| names.contains(d.as_str()))
+            {
+                continue;
+            }
+            let start = *start.get_or_insert(*time);
+            let offset = time.duration_since(start).unwrap_or_default();
+            let reply = reply_to(&records, call).cloned();
+            calls.push((call.clone(), reply, offset));
+        }
+
+        Ok(Self {
+            service,
+            calls,
+            timing: Timing::default(),
+        })
+    }
+
+    /// Set the pace at which the calls are sent.
+    ///
+    /// # Panics
+    ///
+    /// The calls panic on being replayed if the [`Timing::Accelerated`] factor isn't positive.
+    pub fn timing(mut self, timing: Timing) -> Self {
+        self.timing = timing;
+
+        self
+    }
+
+    /// Send the calls through `conn`, and wait for all their replies.
+    ///
+    /// The calls are sent concurrently, each one at its offset from the first one. The exchanges
+    /// are returned in the order of the calls.
+    pub async fn run(&self, conn: &Connection) -> Result<Vec<Exchange>> {
+        let exchanges = self
+            .calls
+            .iter()
+            .map(|(call, expected, offset)| async move {
+                let delay = match self.timing {
+                    Timing::Original => *offset,
+                    Timing::Accelerated(factor) => offset.div_f64(factor),
+                    Timing::Immediate => Duration::ZERO,
+                };
+                if !delay.is_zero() {
+                    conn.executor().timer(delay).await;
+                }
+                let actual = self.send(conn, call).await?;
+
+                Ok(Exchange {
+                    call: call.clone(),
+                    expected: expected.clone(),
+                    actual,
+                })
+            });
+
+        join_all(exchanges).await.into_iter().collect()
+    }
+
+    /// Replay the calls through `conn`, and assert the replies match the captured ones.
+    ///
+    /// # Panics
+    ///
+    /// If replaying fails, or any reply doesn't match, listing the mismatching exchanges.
+    pub async fn assert(&self, conn: &Connection) {
+        let exchanges = match self.run(conn).await {
+            Ok(exchanges) => exchanges,
+            Err(e) => panic!("failed to replay the calls to `{}`: {e}", self.service),
+        };
+        let mut mismatches = String::new();
+        for exchange in exchanges.iter().filter(|e| !e.is_match()) {
+            writeln!(mismatches, "{exchange}").unwrap();
+        }
+        if !mismatches.is_empty() {
+            panic!(
+                "the replies of `{}` don't match the captured ones:\n{mismatches}",
+                self.service
+            );
+        }
+    }
+
+    // Send a fresh copy of `call` to the service, and wait for its reply if it expects one.
+    async fn send(&self, conn: &Connection, call: &Message) -> Result<Option<Message>> {
+        let hdr = call.header();
+        let (Some(path), Some(member)) = (hdr.path(), hdr.member()) else {
+            return Err(Error::InvalidField);
+        };
+        let mut builder = Message::method(path.clone(), member.clone())?
+            .endian(Endian::from(hdr.primary().endian_sig()))
+            .destination(&self.service)?;
+        if let Some(interface) = hdr.interface() {
+            builder = builder.interface(interface.clone())?;
+        }
+        for flag in hdr.primary().flags() {
+            builder = builder.with_flags(flag)?;
+        }
+        let msg = build_with_body(builder, call)?;
+
+        if msg
+            .primary_header()
+            .flags()
+            .contains(Flags::NoReplyExpected)
+        {
+            conn.send(&msg).await?;
+
+            return Ok(None);
+        }
+        // Subscribe before sending, not to miss the reply.
+        let serial = msg.primary_header().serial_num();
+        let mut stream = MessageStream::from(conn);
+        conn.send(&msg).await?;
+        while let Some(reply) = stream.next().await {
+            let reply = reply?;
+            if matches!(reply.message_type(), Type::MethodReturn | Type::Error)
+                && reply.header().reply_serial() == Some(serial)
+            {
+                return Ok(Some(reply));
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::BrokenPipe, "connection closed").into())
+    }
+}
+
+/// A replayed call, with its captured and actual replies.
+#[derive(Debug, Clone)]
+pub struct Exchange {
+    call: Message,
+    expected: Option<Message>,
+    actual: Option<Message>,
+}
+
+assert_impl_all!(Exchange: Send, Sync, Unpin);
+
+impl Exchange {
+    /// The call, as captured.
+    pub fn call(&self) -> &Message {
+        &self.call
+    }
+
+    /// The captured reply, if the capture has one.
+    pub fn expected(&self) -> Option<&Message> {
+        self.expected.as_ref()
+    }
+
+    /// The reply of the service, unless the call expects none.
+    pub fn actual(&self) -> Option<&Message> {
+        self.actual.as_ref()
+    }
+
+    /// Whether the reply of the service matches the captured one.
+    ///
+    /// The replies match if they're of the same type, with the same error name and the same
+    /// arguments. The exchanges without a captured reply always match.
+    pub fn is_match(&self) -> bool {
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => same_reply(expected, actual),
+            (Some(_), None) => false,
+            (None, _) => true,
+        }
+    }
+}
+
+impl fmt::Display for Exchange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hdr = self.call.header();
+        write!(f, "call ")?;
+        if let Some(interface) = hdr.interface() {
+            write!(f, "{interface}.")?;
+        }
+        if let Some(member) = hdr.member() {
+            write!(f, "{member}")?;
+        }
+        write!(f, " (serial {})", hdr.primary().serial_num())?;
+        write!(f, ": expected ")?;
+        describe(f, self.expected.as_ref())?;
+        write!(f, ", got ")?;
+        describe(f, self.actual.as_ref())
+    }
+}
+
+// The captured reply to `call`, i-e the reply sent to its sender with its serial.
+fn reply_to<'r>(records: &'r [(SystemTime, Message)], call: &Message) -> Option<&'r Message> {
+    let serial = call.primary_header().serial_num();
+    let sender = call.header().sender().map(|s| s.to_string());
+
+    records.iter().map(|(_, msg)| msg).find(|msg| {
+        let hdr = msg.header();
+        matches!(msg.message_type(), Type::MethodReturn | Type::Error)
+            && hdr.reply_serial() == Some(serial)
+            && hdr.destination().map(|d| d.to_string()) == sender
+    })
+}
+
+fn same_reply(expected: &Message, actual: &Message) -> bool {
+    if expected.message_type() != actual.message_type()
+        || expected.header().error_name() != actual.header().error_name()
+    {
+        return false;
+    }
+    let (expected, actual) = (expected.body(), actual.body());
+    match (args_signature(&expected), args_signature(&actual)) {
+        (None, None) => true,
+        (Some(e), Some(a)) if e == a => {
+            match (
+                expected.deserialize::<Structure<'_>>(),
+                actual.deserialize::<Structure<'_>>(),
+            ) {
+                (Ok(e), Ok(a)) => e == a,
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+// The signature of the body, if it has any arguments.
+fn args_signature(body: &crate::message::Body) -> Option<String> {
+    body.signature()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+fn describe(f: &mut fmt::Formatter<'_>, reply: Option<&Message>) -> fmt::Result {
+    let Some(reply) = reply else {
+        return write!(f, "no reply");
+    };
+    match reply.header().error_name() {
+        Some(name) => write!(f, "error {name}")?,
+        None => write!(f, "reply")?,
+    }
+    let body = reply.body();
+    if args_signature(&body).is_some() {
+        match body.deserialize::<Structure<'_>>() {
+            Ok(args) => write!(f, " {args}")?,
+            Err(e) => write!(f, " <failed to decode the body: {e}>")?,
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ntest::timeout;
+    use std::time::{Duration, Instant, SystemTime};
+    use test_log::test;
+
+    use super::{Replay, Timing};
+    use crate::{
+        block_on,
+        capture::{PcapngReader, PcapngWriter},
+        connection, fdo, interface,
+        message::Flags,
+        test::MockBus,
+        Message,
+    };
+
+    struct Counter(u32);
+
+    #[interface(name = "org.zbus.Counter")]
+    impl Counter {
+        fn next(&mut self) -> u32 {
+            self.0 += 1;
+            self.0
+        }
+
+        fn reset(&mut self) {
+            self.0 = 0;
+        }
+
+        fn fail(&self) -> fdo::Result<()> {
+            Err(fdo::Error::Failed("failed".into()))
+        }
+    }
+
+    fn call(member: &str, destination: &str) -> Message {
+        Message::method("/org/zbus/Counter", member)
+            .unwrap()
+            .interface("org.zbus.Counter")
+            .unwrap()
+            .sender(":1.3")
+            .unwrap()
+            .destination(destination)
+            .unwrap()
+            .build(&())
+            .unwrap()
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn replay() {
+        block_on(async {
+            // A capture where the second `Next` call went to the unique name of the service, and
+            // its reply was wrong.
+            let calls = [
+                call("Next", "org.zbus.Counter"),
+                call("Next", ":1.2"),
+                call("Fail", "org.zbus.Counter"),
+                call("Reset", "org.zbus.Other"),
+            ];
+            let replies = [
+                Message::method_reply(&calls[0])
+                    .unwrap()
+                    .sender(":1.2")
+                    .unwrap()
+                    .destination(":1.3")
+                    .unwrap()
+                    .build(&1u32)
+                    .unwrap(),
+                Message::method_reply(&calls[1])
+                    .unwrap()
+                    .sender(":1.2")
+                    .unwrap()
+                    .destination(":1.3")
+                    .unwrap()
+                    .build(&3u32)
+                    .unwrap(),
+                Message::method_error(&calls[2], "org.freedesktop.DBus.Error.Failed")
+                    .unwrap()
+                    .sender(":1.2")
+                    .unwrap()
+                    .destination(":1.3")
+                    .unwrap()
+                    .build(&"failed")
+                    .unwrap(),
+            ];
+            let start = SystemTime::now();
+            let mut capture = PcapngWriter::new(vec![]).unwrap();
+            for (i, msg) in calls
+                .iter()
+                .zip(&replies)
+                .flat_map(|(c, r)| [c, r])
+                .enumerate()
+            {
+                let time = start + Duration::from_millis(100 * i as u64);
+                capture.write_at(msg, time).unwrap();
+            }
+            capture.write_at(&calls[3], start).unwrap();
+            let records = PcapngReader::new(&capture.get_ref()[..])
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            let bus = MockBus::new();
+            let _service = connection::Builder::mock(&bus)
+                .name("org.zbus.Counter")
+                .unwrap()
+                .serve_at("/org/zbus/Counter", Counter(0))
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+            let client = connection::Builder::mock(&bus).build().await.unwrap();
+
+            let replay = Replay::new("org.zbus.Counter", records).unwrap();
+            let now = Instant::now();
+            let exchanges = replay.run(&client).await.unwrap();
+            // The last call was sent 400ms after the first one.
+            assert!(now.elapsed() >= Duration::from_millis(400));
+            assert_eq!(exchanges.len(), 3);
+            assert!(exchanges[0].is_match());
+            assert_eq!(
+                exchanges[1]
+                    .actual()
+                    .unwrap()
+                    .body()
+                    .deserialize::<u32>()
+                    .unwrap(),
+                2
+            );
+            assert!(!exchanges[1].is_match());
+            assert!(exchanges[2].is_match());
+            assert_eq!(
+                exchanges[1].to_string(),
+                format!(
+                    "call org.zbus.Counter.Next (serial {}): expected reply (uint32 3,), got reply (uint32 2,)",
+                    calls[1].primary_header().serial_num()
+                )
+            );
+
+            // Only the `Reset` call to the other service.
+            let other = Replay::new("org.zbus.Other", [(start, calls[3].clone())]).unwrap();
+            let exchanges = other.run(&client).await.unwrap();
+            assert_eq!(exchanges.len(), 1);
+            assert!(exchanges[0].expected().is_none());
+            assert_eq!(
+                exchanges[0]
+                    .actual()
+                    .unwrap()
+                    .header()
+                    .error_name()
+                    .unwrap(),
+                "org.freedesktop.DBus.Error.ServiceUnknown"
+            );
+            assert!(exchanges[0].is_match());
+        })
+    }
+
+    #[test]
+    #[timeout(15000)]
+    fn timing() {
+        block_on(async {
+            let start = SystemTime::now();
+            let mut records = vec![];
+            for i in 0..3 {
+                let call = Message::method("/org/zbus/Counter", "Next")
+                    .unwrap()
+                    .interface("org.zbus.Counter")
+                    .unwrap()
+                    .destination("org.zbus.Counter")
+                    .unwrap()
+                    .with_flags(Flags::NoReplyExpected)
+                    .unwrap()
+                    .build(&())
+                    .unwrap();
+                records.push((start + Duration::from_secs(10 * i), call));
+            }
+
+            let bus = MockBus::new();
+            let _service = connection::Builder::mock(&bus)
+                .name("org.zbus.Counter")
+                .unwrap()
+                .serve_at("/org/zbus/Counter", Counter(0))
+                .unwrap()
+                .build()
+                .await
+                .unwrap();
+            let client = connection::Builder::mock(&bus).build().await.unwrap();
+
+            let replay = Replay::new("org.zbus.Counter", records).unwrap();
+            let now = Instant::now();
+            let exchanges = replay
+                .clone()
+                .timing(Timing::Accelerated(100.))
+                .run(&client)
+                .await
+                .unwrap();
+            assert!(now.elapsed() >= Duration::from_millis(200));
+            assert!(exchanges
+                .iter()
+                .all(|e| e.actual().is_none() && e.is_match()));
+
+            let now = Instant::now();
+            replay.timing(Timing::Immediate).assert(&client).await;
+            assert!(now.elapsed() < Duration::from_secs(10));
+        })
+    }
+}
@@ -28,12 +28,19 @@ impl<T, E> ResultAdapter for Result<T, E> {
     type Err = E;
 }
 
-#[cfg(not(feature = "tokio"))]
+#[cfg(all(not(feature = "tokio"), not(target_family = "wasm")))]
 #[doc(hidden)]
 pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
     async_io::block_on(future)
 }
 
+#[cfg(target_family = "wasm")]
+#[doc(hidden)]
+pub fn block_on<F: std::future::Future>(_future: F) -> F::Output {
+    // The browser event loop can't be blocked, it's the one driving the I/O.
+    panic!("Blocking on futures is not supported on wasm, use the async API instead")
+}
+
 #[cfg(feature = "tokio")]
 #[doc(hidden)]
 pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
@@ -37,6 +37,7 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     let mut error_names = quote! {};
     let mut error_descriptions = quote! {};
     let mut error_converts = quote! {};
+    let mut all_error_names = vec![];
 
     let mut zbus_error_variant = None;
 
@@ -55,10 +56,17 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
         let error_name = quote! {
             #zbus::names::ErrorName::from_static_str_unchecked(#fqn)
         };
+        if !error {
+            all_error_names.push(error_name.clone());
+        }
         let e = match variant.fields {
             Fields::Unit => quote! {
                 Self::#ident => #error_name,
             },
+            Fields::Unnamed(_) if error => quote! {
+                Self::#ident(#zbus::Error::Registered(e)) => #zbus::DBusError::name(e),
+                Self::#ident(..) => #error_name,
+            },
             Fields::Unnamed(_) => quote! {
                 Self::#ident(..) => #error_name,
             },
@@ -86,6 +94,7 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
                 if error {
                     quote! {
                         Self::#ident(#zbus::Error::MethodError(_, desc, _)) => desc.as_deref(),
+                        Self::#ident(#zbus::Error::Registered(e)) => #zbus::DBusError::description(e),
                         Self::#ident(_) => None,
                     }
                 } else {
@@ -101,7 +110,7 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
                     .ok_or_else(|| Error::new(n.span(), "expected at least one field"))?
                     .ident;
                 quote! {
-                    Self::#ident { #f, } => Some(#f),
+                    Self::#ident { #f, .. } => Some(#f),
                 }
             }
         };
@@ -109,13 +118,42 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
 
         // The conversion for #[zbus(error)] variant is handled separately/explicitly.
         if !error {
-            // FIXME: deserialize msg to error field instead, to support variable args
             let e = match &variant.fields {
                 Fields::Unit => quote! {
                     #fqn => Self::#ident,
                 },
+                Fields::Unnamed(f) if f.unnamed.len() > 1 => {
+                    let vars: Vec<_> = (0..f.unnamed.len())
+                        .map(|i| Ident::new(&format!("arg{i}"), f.span()))
+                        .collect();
+                    let args = deserialize_error_args(&zbus, f.unnamed.iter());
+
+                    quote! {
+                        #fqn => match #args {
+                            ::std::option::Option::Some(::std::result::Result::Ok((#(#vars),*))) => {
+                                Self::#ident(#(#vars),*)
+                            }
+                            _ => fallback(value),
+                        },
+                    }
+                }
+                Fields::Named(n) if n.named.len() > 1 => {
+                    let vars: Vec<_> = n.named.iter().map(|f| f.ident.clone()).collect();
+                    let args = deserialize_error_args(&zbus, n.named.iter());
+
+                    quote! {
+                        #fqn => match #args {
+                            ::std::option::Option::Some(::std::result::Result::Ok((#(#vars),*))) => {
+                                Self::#ident { #(#vars),* }
+                            }
+                            _ => fallback(value),
+                        },
+                    }
+                }
                 Fields::Unnamed(_) => quote! {
-                    #fqn => { Self::#ident(::std::clone::Clone::clone(desc).unwrap_or_default()) },
+                    #fqn if only_description => {
+                        Self::#ident(::std::clone::Clone::clone(desc).unwrap_or_default())
+                    },
                 },
                 Fields::Named(n) => {
                     let f = &n
@@ -124,7 +162,7 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
                         .ok_or_else(|| Error::new(n.span(), "expected at least one field"))?
                         .ident;
                     quote! {
-                        #fqn => {
+                        #fqn if only_description => {
                             let desc = ::std::clone::Clone::clone(desc).unwrap_or_default();
 
                             Self::#ident { #f: desc }
@@ -144,13 +182,30 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
             quote! {
                 impl ::std::convert::From<#zbus::Error> for #name {
                     fn from(value: #zbus::Error) -> #name {
-                        if let #zbus::Error::MethodError(name, desc, _) = &value {
-                            match name.as_str() {
-                                #error_converts
-                                _ => Self::#ident(value),
+                        let fallback = Self::#ident;
+                        let (name, desc) = match &value {
+                            #zbus::Error::MethodError(name, desc, _) => {
+                                (::std::string::ToString::to_string(name.as_str()), ::std::clone::Clone::clone(desc))
                             }
-                        } else {
-                            Self::#ident(value)
+                            #zbus::Error::Registered(e) => (
+                                ::std::string::ToString::to_string(#zbus::DBusError::name(e).as_str()),
+                                #zbus::DBusError::description(e).map(::std::string::ToString::to_string),
+                            ),
+                            _ => return fallback(value),
+                        };
+                        let desc = &desc;
+                        // Replies carrying more than a description are kept whole in the
+                        // fallback variant, rather than dropping the extra arguments.
+                        #[allow(unused_variables)]
+                        let only_description = #zbus::Error::reply(&value).map_or(true, |reply| {
+                            reply
+                                .body()
+                                .signature()
+                                .map_or(true, |s| matches!(s.as_str(), "" | "s"))
+                        });
+                        match name.as_str() {
+                            #error_converts
+                            _ => fallback(value),
                         }
                     }
                 }
@@ -192,6 +247,10 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
                     #replies
                 }
             }
+
+            fn error_names() -> ::std::vec::Vec<#zbus::names::ErrorName<'static>> {
+                ::std::vec![#(#all_error_names),*]
+            }
         }
 
         #display_impl
@@ -202,6 +261,18 @@ pub fn expand_derive(input: DeriveInput) -> Result<TokenStream, Error> {
     })
 }
 
+// Deserialize all the arguments of the error reply to the fields of a variant.
+fn deserialize_error_args<'f>(
+    zbus: &TokenStream,
+    fields: impl Iterator<Item = &'f syn::Field>,
+) -> TokenStream {
+    let types = fields.map(|f| &f.ty);
+
+    quote! {
+        #zbus::Error::reply(&value).map(|reply| reply.body().deserialize::<(#(#types),*)>())
+    }
+}
+
 fn gen_reply_for_variant(
     variant: &Variant,
     zbus_error_variant: bool,
@@ -239,7 +310,16 @@ fn gen_reply_for_variant(
                 in_fields.clone()
             };
 
+            let registered = if zbus_error_variant {
+                quote! {
+                    Self::#ident(#zbus::Error::Registered(e)) => #zbus::DBusError::create_reply(e, call),
+                }
+            } else {
+                quote! {}
+            };
+
             Ok(quote! {
+                #registered
                 Self::#ident(#(#in_fields),*) => #zbus::message::Builder::error(call, name)?.build(&(#(#out_fields),*)),
             })
         }
@@ -39,7 +39,9 @@ def_attrs! {
 
     pub TraitAttributes("trait") {
         interface str,
-        name str
+        name str,
+        polkit_action str,
+        dispatch str
     };
 
     pub MethodAttributes("method") {
@@ -50,14 +52,17 @@ def_attrs! {
                 emits_changed_signal str
             }
         },
-        out_args [str]
+        out_args [str],
+        polkit_action str
     };
 
     pub ArgAttributes("argument") {
         object_server none,
         connection none,
         header none,
-        signal_context none
+        signal_context none,
+        caller_info none,
+        responder none
     };
 }
 
@@ -103,6 +108,8 @@ struct MethodInfo {
     method_type: MethodType,
     /// Whether the method has inputs
     has_inputs: bool,
+    /// Whether the property getter takes the caller info
+    property_caller_info: bool,
     /// Whether the method is async
     is_async: bool,
     /// Doc comments on the methods
@@ -172,7 +179,22 @@ impl MethodInfo {
         };
         assert!(!is_property || !is_signal);
 
-        let has_inputs = inputs.len() > 1;
+        // Property getters may take the caller info, which isn't a value to set.
+        let mut property_caller_info = false;
+        if is_property {
+            for input in inputs.iter().filter_map(typed_arg) {
+                if ArgAttributes::parse(&input.attrs)?.caller_info {
+                    if inputs.len() > 2 {
+                        return Err(Error::new_spanned(
+                            input,
+                            "`caller_info` can only be taken by property getters",
+                        ));
+                    }
+                    property_caller_info = true;
+                }
+            }
+        }
+        let has_inputs = inputs.len() > 1 + usize::from(property_caller_info);
 
         let is_mut = if let FnArg::Receiver(r) = inputs
             .first()
@@ -210,14 +232,42 @@ impl MethodInfo {
             None
         };
 
+        // The reply of methods taking a responder is sent through it.
+        let mut responder_output = None;
+        for input in &typed_inputs {
+            if ArgAttributes::parse(&input.attrs)?.responder {
+                responder_output = Some(get_responder_type(input)?);
+            }
+        }
+
         let mut intro_args = quote!();
         intro_args.extend(introspect_input_args(&typed_inputs, is_signal, cfg_attrs));
-        let is_result_output =
-            introspect_add_output_args(&mut intro_args, output, out_args.as_deref(), cfg_attrs)?;
+        let is_result_output = match &responder_output {
+            Some(ty) => {
+                let output = parse_quote!(-> #ty);
+                introspect_add_output_args(
+                    &mut intro_args,
+                    &output,
+                    out_args.as_deref(),
+                    cfg_attrs,
+                )?;
+
+                false
+            }
+            None => {
+                introspect_add_output_args(&mut intro_args, output, out_args.as_deref(), cfg_attrs)?
+            }
+        };
 
         let (args_from_msg, args_names) = get_args_from_inputs(&typed_inputs, zbus)?;
 
-        let reply = if is_result_output {
+        let reply = if responder_output.is_some() {
+            quote!({
+                let () = reply;
+
+                ::std::result::Result::Ok(())
+            })
+        } else if is_result_output {
             let ret = quote!(r);
 
             quote!(match reply {
@@ -255,6 +305,7 @@ impl MethodInfo {
         Ok(MethodInfo {
             method_type,
             has_inputs,
+            property_caller_info,
             is_async,
             doc_comments,
             is_mut,
@@ -283,8 +334,12 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
     let mut set_mut_dispatch = quote!();
     let mut get_dispatch = quote!();
     let mut get_all = quote!();
+    let mut get_for_caller_dispatch = quote!();
+    let mut get_all_for_caller = quote!();
     let mut call_dispatch = quote!();
     let mut call_mut_dispatch = quote!();
+    let mut polkit_dispatch = quote!();
+    let mut emits_changed_signal_dispatch = quote!();
     let mut introspect = quote!();
     let mut generated_signals = quote!();
 
@@ -300,10 +355,32 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
         _ => return Err(Error::new_spanned(&input.self_ty, "Invalid type")),
     };
 
+    let mut default_polkit_action = None;
+    let mut dispatch_mode = quote!();
     let iface_name =
         {
             let (name, interface) = match T::parse_nested_metas(&args)?.into() {
-                TraitAttrs::New(new) => (new.name, new.interface),
+                TraitAttrs::New(new) => {
+                    default_polkit_action = new.polkit_action;
+                    if let Some(dispatch) = new.dispatch {
+                        let mode = match dispatch.as_str() {
+                            "concurrent" => quote!(Concurrent),
+                            "serial" => quote!(Serial),
+                            _ => {
+                                return Err(syn::Error::new(
+                                    input.span(),
+                                    "`dispatch` must be either `concurrent` or `serial`",
+                                ))
+                            }
+                        };
+                        dispatch_mode = quote! {
+                            fn dispatch_mode(&self) -> #zbus::object_server::DispatchMode {
+                                #zbus::object_server::DispatchMode::#mode
+                            }
+                        };
+                    }
+                    (new.name, new.interface)
+                }
                 TraitAttrs::Old(old) => (old.name, old.interface),
             };
 
@@ -338,6 +415,16 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             .collect();
 
         let method_info = MethodInfo::new(&zbus, method, &attrs, &cfg_attrs)?;
+        let polkit_action = match &attrs {
+            MethodAttrs::Old(_) => None,
+            MethodAttrs::New(n) => n.polkit_action.clone(),
+        };
+        if polkit_action.is_some() && method_info.method_type != MethodType::Other {
+            return Err(syn::Error::new(
+                method.span(),
+                "`polkit_action` can only be specified on methods",
+            ));
+        }
         let attr_property = match attrs {
             MethodAttrs::Old(o) => o.property.map(|op| PropertyAttributes {
                 emits_changed_signal: op.emits_changed_signal,
@@ -348,9 +435,27 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
             if method_info.method_type == MethodType::Property(PropertyType::NoInputs) {
                 let emits_changed_signal = if let Some(s) = &prop_attrs.emits_changed_signal {
                     PropertyEmitsChangedSignal::parse(s, method.span())?
+                } else if method_info.property_caller_info {
+                    PropertyEmitsChangedSignal::Invalidates
                 } else {
                     PropertyEmitsChangedSignal::True
                 };
+                if method_info.property_caller_info
+                    && emits_changed_signal == PropertyEmitsChangedSignal::True
+                {
+                    return Err(syn::Error::new(
+                        method.span(),
+                        "Properties depending on the caller can't send their value in `PropertiesChanged`",
+                    ));
+                }
+                if emits_changed_signal != PropertyEmitsChangedSignal::True {
+                    let member_name = &method_info.member_name;
+                    let variant = format_ident!("{emits_changed_signal:?}");
+                    emits_changed_signal_dispatch.extend(quote! {
+                        #(#cfg_attrs)*
+                        #member_name => #zbus::object_server::EmitsChangedSignal::#variant,
+                    });
+                }
                 let mut property = Property::new();
                 property.emits_changed_signal = emits_changed_signal;
                 properties.insert(method_info.member_name.to_string(), property);
@@ -361,10 +466,10 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 ));
             }
         };
-        methods.push((method, method_info));
+        methods.push((method, method_info, polkit_action));
     }
 
-    for (method, method_info) in methods {
+    for (method, method_info, polkit_action) in methods {
         let cfg_attrs: Vec<_> = method
             .attrs
             .iter()
@@ -374,6 +479,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
         let MethodInfo {
             method_type,
             has_inputs,
+            property_caller_info,
             is_async,
             doc_comments,
             is_mut,
@@ -567,25 +673,61 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                         )
                         .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))
                     );
+                    let caller_arg = if property_caller_info {
+                        quote!(caller)
+                    } else {
+                        quote!()
+                    };
                     let inner = if is_fallible_property {
-                        quote!(self.#ident() #method_await .and_then(|value| #value_convert))
+                        quote!(self.#ident(#caller_arg) #method_await .and_then(|value| #value_convert))
                     } else {
                         quote!({
-                            let value = self.#ident()#method_await;
+                            let value = self.#ident(#caller_arg)#method_await;
                             #value_convert
                         })
                     };
 
-                    let q = quote!(
-                        #(#cfg_attrs)*
-                        #member_name => {
-                            ::std::option::Option::Some(#inner)
-                        },
-                    );
-                    get_dispatch.extend(q);
+                    if property_caller_info {
+                        // The value can only be read on behalf of a caller, through
+                        // `Interface::get_for_caller`.
+                        get_dispatch.extend(quote!(
+                            #(#cfg_attrs)*
+                            #member_name => {
+                                ::std::option::Option::Some(::std::result::Result::Err(
+                                    #zbus::fdo::Error::NotSupported(::std::format!(
+                                        "Property `{}` depends on the caller",
+                                        #member_name,
+                                    )),
+                                ))
+                            },
+                        ));
+                        get_for_caller_dispatch.extend(quote!(
+                            #(#cfg_attrs)*
+                            #member_name => {
+                                ::std::option::Option::Some(
+                                    match server.caller_info(connection, header).await {
+                                        ::std::result::Result::Ok(caller) => #inner,
+                                        ::std::result::Result::Err(e) => ::std::result::Result::Err(e),
+                                    },
+                                )
+                            },
+                        ));
+                    } else {
+                        get_dispatch.extend(quote!(
+                            #(#cfg_attrs)*
+                            #member_name => {
+                                ::std::option::Option::Some(#inner)
+                            },
+                        ));
+                    }
 
+                    let caller_arg = if property_caller_info {
+                        quote!(::std::clone::Clone::clone(&caller))
+                    } else {
+                        quote!()
+                    };
                     let q = if is_fallible_property {
-                        quote!(if let Ok(prop) = self.#ident()#method_await {
+                        quote!(if let Ok(prop) = self.#ident(#caller_arg)#method_await {
                             props.insert(
                                 ::std::string::ToString::to_string(#member_name),
                                 <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
@@ -601,14 +743,18 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                         ::std::string::ToString::to_string(#member_name),
                         <#zbus::zvariant::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
                             <#zbus::zvariant::Value as ::std::convert::From<_>>::from(
-                                self.#ident()#method_await,
+                                self.#ident(#caller_arg)#method_await,
                             ),
                         )
                         .map_err(|e| #zbus::fdo::Error::Failed(e.to_string()))?,
                     );)
                     };
 
-                    get_all.extend(q);
+                    if property_caller_info {
+                        get_all_for_caller.extend(q);
+                    } else {
+                        get_all.extend(q);
+                    }
 
                     let prop_value_handled = if is_fallible_property {
                         quote!(self.#ident()#method_await?)
@@ -616,20 +762,58 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                         quote!(self.#ident()#method_await)
                     };
 
+                    let invalidate = quote!(
+                        #zbus::fdo::Properties::properties_changed(
+                            signal_context,
+                            #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                            &::std::collections::HashMap::new(),
+                            &[#member_name],
+                        ).await
+                    );
+                    let (changed_body, invalidate_body) = match p.emits_changed_signal {
+                        PropertyEmitsChangedSignal::True => (
+                            quote!(
+                                let mut changed = ::std::collections::HashMap::new();
+                                let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(#prop_value_handled);
+                                changed.insert(#member_name, &value);
+                                #zbus::fdo::Properties::properties_changed(
+                                    signal_context,
+                                    #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
+                                    &changed,
+                                    &[#member_name],
+                                ).await
+                            ),
+                            invalidate.clone(),
+                        ),
+                        PropertyEmitsChangedSignal::Invalidates => (invalidate.clone(), invalidate),
+                        PropertyEmitsChangedSignal::False => {
+                            let noop = quote!(
+                                let _ = signal_context;
+
+                                ::std::result::Result::Ok(())
+                            );
+
+                            (noop.clone(), noop)
+                        }
+                        PropertyEmitsChangedSignal::Const => {
+                            let refuse = quote!(
+                                let _ = signal_context;
+
+                                ::std::result::Result::Err(#zbus::Error::Failure(
+                                    ::std::format!("Property `{}` is constant", #member_name),
+                                ))
+                            );
+
+                            (refuse.clone(), refuse)
+                        }
+                    };
+
                     let prop_changed_method = quote!(
                         pub async fn #prop_changed_method_name(
                             &self,
                             signal_context: &#zbus::object_server::SignalContext<'_>,
                         ) -> #zbus::Result<()> {
-                            let mut changed = ::std::collections::HashMap::new();
-                            let value = <#zbus::zvariant::Value as ::std::convert::From<_>>::from(#prop_value_handled);
-                            changed.insert(#member_name, &value);
-                            #zbus::fdo::Properties::properties_changed(
-                                signal_context,
-                                #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
-                                &changed,
-                                &[#member_name],
-                            ).await
+                            #changed_body
                         }
                     );
 
@@ -640,12 +824,7 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                             &self,
                             signal_context: &#zbus::object_server::SignalContext<'_>,
                         ) -> #zbus::Result<()> {
-                            #zbus::fdo::Properties::properties_changed(
-                                signal_context,
-                                #zbus::names::InterfaceName::from_static_str_unchecked(#iface_name),
-                                &::std::collections::HashMap::new(),
-                                &[#member_name],
-                            ).await
+                            #invalidate_body
                         }
                     );
 
@@ -656,6 +835,13 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 introspect.extend(doc_comments);
                 introspect.extend(introspect_method(&member_name, &intro_args));
 
+                if let Some(action_id) = polkit_action.as_ref().or(default_polkit_action.as_ref()) {
+                    polkit_dispatch.extend(quote! {
+                        #(#cfg_attrs)*
+                        #member_name => ::std::option::Option::Some(#action_id),
+                    });
+                }
+
                 let m = quote! {
                     #(#cfg_attrs)*
                     #member_name => {
@@ -688,6 +874,43 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
     let generics = &input.generics;
     let where_clause = &generics.where_clause;
 
+    let get_for_caller_impl = if get_for_caller_dispatch.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            async fn get_for_caller(
+                &self,
+                property_name: &str,
+                server: &#zbus::ObjectServer,
+                connection: &#zbus::Connection,
+                header: &#zbus::message::Header<'_>,
+            ) -> ::std::option::Option<#zbus::fdo::Result<#zbus::zvariant::OwnedValue>> {
+                match property_name {
+                    #get_for_caller_dispatch
+                    _ => #zbus::object_server::Interface::get(self, property_name).await,
+                }
+            }
+
+            async fn get_all_for_caller(
+                &self,
+                server: &#zbus::ObjectServer,
+                connection: &#zbus::Connection,
+                header: &#zbus::message::Header<'_>,
+            ) -> #zbus::fdo::Result<::std::collections::HashMap<
+                ::std::string::String,
+                #zbus::zvariant::OwnedValue,
+            >> {
+                let mut props = #zbus::object_server::Interface::get_all(self).await?;
+                if let ::std::result::Result::Ok(caller) =
+                    server.caller_info(connection, header).await
+                {
+                    #get_all_for_caller
+                }
+                Ok(props)
+            }
+        }
+    };
+
     let generated_signals_impl = if generated_signals.is_empty() {
         quote!()
     } else {
@@ -737,6 +960,8 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 Ok(props)
             }
 
+            #get_for_caller_impl
+
             fn set<'call>(
                 &'call self,
                 property_name: &'call str,
@@ -787,6 +1012,22 @@ pub fn expand<T: AttrParse + Into<TraitAttrs>, M: AttrParse + Into<MethodAttrs>>
                 }
             }
 
+            fn polkit_action(&self, method: &str) -> ::std::option::Option<&str> {
+                match method {
+                    #polkit_dispatch
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            #dispatch_mode
+
+            fn emits_changed_signal(&self, property: &str) -> #zbus::object_server::EmitsChangedSignal {
+                match property {
+                    #emits_changed_signal_dispatch
+                    _ => #zbus::object_server::EmitsChangedSignal::True,
+                }
+            }
+
             fn introspect_to_writer(&self, writer: &mut dyn ::std::fmt::Write, level: usize) {
                 ::std::writeln!(
                     writer,
@@ -818,6 +1059,8 @@ fn get_args_from_inputs(
         let mut conn_arg_decl = None;
         let mut header_arg_decl = None;
         let mut signal_context_arg_decl = None;
+        let mut caller_info_arg_decl = None;
+        let mut responder_arg_decl = None;
         let mut args_names = Vec::new();
         let mut tys = Vec::new();
 
@@ -827,6 +1070,8 @@ fn get_args_from_inputs(
                 connection,
                 header,
                 signal_context,
+                caller_info,
+                responder,
             } = ArgAttributes::parse(&input.attrs)?;
 
             if object_server {
@@ -883,6 +1128,37 @@ fn get_args_from_inputs(
                         }
                     };
                 });
+            } else if caller_info {
+                if caller_info_arg_decl.is_some() {
+                    return Err(Error::new_spanned(
+                        input,
+                        "There can only be one `caller_info` argument",
+                    ));
+                }
+
+                let caller_info_arg = &input.pat;
+
+                caller_info_arg_decl = Some(quote! {
+                    let #caller_info_arg = match s.caller_info(c, &hdr).await {
+                        ::std::result::Result::Ok(info) => info,
+                        ::std::result::Result::Err(err) => {
+                            return c.reply_dbus_error(&hdr, err).await;
+                        }
+                    };
+                });
+            } else if responder {
+                if responder_arg_decl.is_some() {
+                    return Err(Error::new_spanned(
+                        input,
+                        "There can only be one `responder` argument",
+                    ));
+                }
+
+                let responder_arg = &input.pat;
+
+                responder_arg_decl = Some(quote! {
+                    let #responder_arg = #zbus::object_server::Responder::new(c, m);
+                });
             } else {
                 args_names.push(pat_ident(input).unwrap());
                 tys.push(&input.ty);
@@ -901,6 +1177,8 @@ fn get_args_from_inputs(
 
             #signal_context_arg_decl
 
+            #caller_info_arg_decl
+
             let (#(#args_names),*): (#(#tys),*) =
                 match msg_body.deserialize() {
                     ::std::result::Result::Ok(r) => r,
@@ -909,6 +1187,8 @@ fn get_args_from_inputs(
                         return c.reply_dbus_error(&hdr, err).await;
                     }
                 };
+
+            #responder_arg_decl
         };
 
         let all_args_names = inputs.iter().filter_map(pat_ident);
@@ -977,7 +1257,7 @@ fn introspect_input_args<'i>(
                     matches!(
                         nested_meta,
                         NestedMeta::Meta(Meta::Path(path))
-                        if path.is_ident("object_server") || path.is_ident("connection") || path.is_ident("header") || path.is_ident("signal_context")
+                        if path.is_ident("object_server") || path.is_ident("connection") || path.is_ident("header") || path.is_ident("signal_context") || path.is_ident("caller_info") || path.is_ident("responder")
                     )
                 });
 
@@ -1031,6 +1311,26 @@ fn get_result_type(p: &TypePath) -> syn::Result<&Type> {
     Err(Error::new_spanned(p, "unhandled Result return"))
 }
 
+/// The type of the reply sent through a `Responder<T>` argument.
+fn get_responder_type(input: &PatType) -> syn::Result<Type> {
+    if let Type::Path(p) = &*input.ty {
+        if let Some(segment) = p.path.segments.last() {
+            if let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) =
+                &segment.arguments
+            {
+                if let Some(GenericArgument::Type(ty)) = args.first() {
+                    return Ok(ty.clone());
+                }
+            }
+        }
+    }
+
+    Err(Error::new_spanned(
+        &input.ty,
+        "Expected a `zbus::object_server::Responder<T>` argument",
+    ))
+}
+
 fn introspect_add_output_args(
     args: &mut TokenStream,
     output: &ReturnType,
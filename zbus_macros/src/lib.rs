@@ -11,11 +11,12 @@
 )))]
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemImpl, ItemTrait};
+use syn::{parse_macro_input, AttributeArgs, DeriveInput, ItemFn, ItemImpl, ItemTrait};
 
 mod error;
 mod iface;
 mod proxy;
+mod test;
 mod utils;
 
 /// Attribute macro for defining D-Bus proxies (using [`zbus::Proxy`] and
@@ -215,6 +216,16 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// properties or signal depending on the item attributes. It will implement the [`Interface`] trait
 /// `for T` on your behalf, to handle the message dispatching and introspection support.
 ///
+/// The macro accepts the following attributes:
+///
+/// * `name` - the D-Bus name of the interface (`org.freedesktop.<type name>` by default).
+///
+/// * `polkit_action` - the default polkit action of the methods (see below).
+///
+/// * `dispatch` - how method calls are dispatched. Either `"concurrent"` (the default), where calls
+///   are dispatched as soon as they are received, or `"serial"`, where calls are dispatched one at
+///   a time, in the order they were received in.
+///
 /// The methods accepts the `interface` attributes:
 ///
 /// * `name` - override the D-Bus name (pascal case form of the method by default)
@@ -245,6 +256,11 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   In such case, your method must return a tuple containing
 ///   your out arguments, in the same order as passed to `out_args`.
 ///
+/// * `polkit_action` - the ID of the polkit action the caller must be authorized for to call the
+///   method. The authorization is checked with the polkit authority before the method is invoked,
+///   and an `AccessDenied` error is returned to the caller on failure. A default for all methods of
+///   the interface can be given by passing `polkit_action` to the `interface` macro itself.
+///
 /// The `struct_return` attribute (from zbus 1.x) is no longer supported. If you want to return a
 /// single structure from a method, declare it to return a tuple containing either a named structure
 /// or a nested tuple.
@@ -260,6 +276,10 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// using this since it will force all interested peers to fetch the new value and hence result in
 /// excess traffic on the bus.
 ///
+/// Both methods honor the `emits_changed_signal` attribute of the property: for `"invalidates"`,
+/// `_changed` only invalidates the property, for `"false"`, both do nothing and for `"const"`,
+/// both return an error.
+///
 /// The method arguments support the following `zbus` attributes:
 ///
 /// * `object_server` - This marks the method argument to receive a reference to the
@@ -270,6 +290,15 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///   D-Bus method call being handled.
 /// * `signal_context` - This marks the method argument to receive a [`SignalContext`] instance,
 ///   which is needed for emitting signals the easy way.
+/// * `caller_info` - This marks the method argument to receive a [`CallerInfo`] instance, holding
+///   the unique name and credentials (user ID, process ID, security label) of the caller. The
+///   credentials are looked up on the bus, or taken from the peer on peer-to-peer connections.
+///   Property getters can take it as well, to return a value specific to the caller, or an
+///   `AccessDenied` error. Their value is never sent in `PropertiesChanged` signals, so their
+///   `emits_changed_signal` defaults to `invalidates` and can't be `true`.
+/// * `responder` - This marks the method argument to receive a [`Responder<T>`] instance, through
+///   which the reply is sent later, e.g from another task. The method must not return a value
+///   itself, and `T` is the type of the reply in the introspection data.
 ///
 /// # Example
 ///
@@ -339,6 +368,8 @@ pub fn dbus_proxy(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// [`Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
 /// [`Connection::emit_signal()`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html#method.emit_signal
 /// [`SignalContext`]: https://docs.rs/zbus/latest/zbus/object_server/struct.SignalContext.html
+/// [`CallerInfo`]: https://docs.rs/zbus/latest/zbus/object_server/struct.CallerInfo.html
+/// [`Responder<T>`]: https://docs.rs/zbus/latest/zbus/object_server/struct.Responder.html
 /// [`Interface`]: https://docs.rs/zbus/latest/zbus/object_server/trait.Interface.html
 /// [dbus_emits_changed_signal]: https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format
 #[proc_macro_attribute]
@@ -360,6 +391,70 @@ pub fn dbus_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Attribute macro for tests running on a private bus.
+///
+/// The macro turns the function it's applied on into a test, creating a private in-process bus
+/// ([`zbus::test::MockBus`]) for it, and giving it the connections it needs. Each argument of the
+/// function is created from the bus through the [`zbus::test::Fixture`] trait, which is
+/// implemented for:
+///
+/// * [`zbus::Connection`] and [`zbus::blocking::Connection`], giving a new connection to the bus.
+///   Each argument is a separate connection, with its own unique name.
+/// * [`zbus::test::MockBus`], giving the bus itself.
+///
+/// The function can be `async`, and return anything a `#[test]` function can. The bus and the
+/// connections are torn down once it returns. Other attributes of the function, such as
+/// `#[ignore]` or `#[should_panic]`, are kept.
+///
+/// # Example
+///
+/// ```
+/// # // The test function is only compiled in tests.
+/// # #![allow(unused)]
+/// use zbus::{interface, Connection};
+///
+/// struct Greeter;
+///
+/// #[interface(name = "org.zbus.Greeter")]
+/// impl Greeter {
+///     fn greet(&self, name: &str) -> String {
+///         format!("Hello, {name}!")
+///     }
+/// }
+///
+/// #[zbus::test]
+/// async fn greet(service: Connection, client: Connection) -> zbus::Result<()> {
+///     service.object_server().at("/org/zbus/Greeter", Greeter).await?;
+///     service.request_name("org.zbus.Greeter").await?;
+///
+///     let reply = client
+///         .call_method(
+///             Some("org.zbus.Greeter"),
+///             "/org/zbus/Greeter",
+///             Some("org.zbus.Greeter"),
+///             "Greet",
+///             &"zbus",
+///         )
+///         .await?;
+///     assert_eq!(reply.body().deserialize::<&str>()?, "Hello, zbus!");
+///
+///     Ok(())
+/// }
+/// ```
+///
+/// [`zbus::test::MockBus`]: https://docs.rs/zbus/latest/zbus/test/struct.MockBus.html
+/// [`zbus::test::Fixture`]: https://docs.rs/zbus/latest/zbus/test/trait.Fixture.html
+/// [`zbus::Connection`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html
+/// [`zbus::blocking::Connection`]: https://docs.rs/zbus/latest/zbus/blocking/connection/struct.Connection.html
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as AttributeArgs);
+    let input = parse_macro_input!(item as ItemFn);
+    test::expand(args, input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Derive macro for implementing [`zbus::DBusError`] trait.
 ///
 /// This macro makes it easy to implement the [`zbus::DBusError`] trait for your custom error type
@@ -368,10 +463,15 @@ pub fn dbus_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// If a special variant marked with the `zbus` attribute is present, `From<zbus::Error>` is
 /// also implemented for your type. This variant can only have a single unnamed field of type
 /// [`zbus::Error`]. This implementation makes it possible for you to declare proxy methods to
-/// directly return this type, rather than [`zbus::Error`].
+/// directly return this type, rather than [`zbus::Error`]. Such types can also be registered on
+/// a connection with [`zbus::Connection::register_error`], to get error replies converted to them
+/// automatically.
 ///
 /// Each variant (except for the special `zbus` one) can optionally have a (named or unnamed)
-/// `String` field (which is used as the human-readable error description).
+/// `String` field (which is used as the human-readable error description). Further fields hold the
+/// other arguments of the error reply, so their types need to be serializable and deserializable.
+/// If the arguments of an error reply don't match the fields, it's converted to the `zbus`
+/// variant instead.
 ///
 /// # Example
 ///
@@ -390,6 +490,7 @@ pub fn dbus_interface(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///
 /// [`zbus::DBusError`]: https://docs.rs/zbus/latest/zbus/trait.DBusError.html
 /// [`zbus::Error`]: https://docs.rs/zbus/latest/zbus/enum.Error.html
+/// [`zbus::Connection::register_error`]: https://docs.rs/zbus/latest/zbus/connection/struct.Connection.html#method.register_error
 /// [`zvariant::Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 /// [`serde::Serialize`]: https://docs.rs/serde/1.0.132/serde/trait.Serialize.html
 #[proc_macro_derive(DBusError, attributes(zbus))]
@@ -162,22 +162,24 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
         "Can't set asynchronous proxy's name if you disabled it. 😸",
     );
 
+    let blocking_proxy_name = blocking_name.unwrap_or_else(|| {
+        if gen_async {
+            format!("{}ProxyBlocking", input.ident)
+        } else {
+            // When only generating blocking proxy, there is no need for a suffix.
+            format!("{}Proxy", input.ident)
+        }
+    });
+    let async_proxy_name = async_name.unwrap_or_else(|| format!("{}Proxy", input.ident));
+
     let blocking_proxy = if gen_blocking {
-        let proxy_name = blocking_name.unwrap_or_else(|| {
-            if gen_async {
-                format!("{}ProxyBlocking", input.ident)
-            } else {
-                // When only generating blocking proxy, there is no need for a suffix.
-                format!("{}Proxy", input.ident)
-            }
-        });
         create_proxy::<M>(
             &input,
             iface_name.as_deref(),
             assume_defaults,
             default_path.as_deref(),
             default_service.as_deref(),
-            &proxy_name,
+            &blocking_proxy_name,
             true,
             // Signal args structs are shared between the two proxies so always generate it for
             // async proxy only unless async proxy generation is disabled.
@@ -187,28 +189,74 @@ pub fn expand<I: AttrParse + Into<ImplAttrs>, M: AttrParse + Into<MethodAttrs>>(
         quote! {}
     };
     let async_proxy = if gen_async {
-        let proxy_name = async_name.unwrap_or_else(|| format!("{}Proxy", input.ident));
         create_proxy::<M>(
             &input,
             iface_name.as_deref(),
             assume_defaults,
             default_path.as_deref(),
             default_service.as_deref(),
-            &proxy_name,
+            &async_proxy_name,
             false,
             true,
         )?
     } else {
         quote! {}
     };
+    let conversions = if gen_async && gen_blocking {
+        gen_proxy_conversions(&async_proxy_name, &blocking_proxy_name)
+    } else {
+        quote! {}
+    };
 
     Ok(quote! {
         #blocking_proxy
 
         #async_proxy
+
+        #conversions
     })
 }
 
+fn gen_proxy_conversions(async_proxy_name: &str, blocking_proxy_name: &str) -> TokenStream {
+    let zbus = zbus_path();
+    let async_proxy_name = Ident::new(async_proxy_name, Span::call_site());
+    let blocking_proxy_name = Ident::new(blocking_proxy_name, Span::call_site());
+    let into_blocking_doc = format!(
+        "Converts `self` into a [`{blocking_proxy_name}`], reusing the same underlying connection."
+    );
+    let into_async_doc = format!(
+        "Converts `self` into a [`{async_proxy_name}`], reusing the same underlying connection."
+    );
+
+    quote! {
+        impl<'p> #async_proxy_name<'p> {
+            #[doc = #into_blocking_doc]
+            pub fn into_blocking(self) -> #blocking_proxy_name<'p> {
+                ::std::convert::From::from(self)
+            }
+        }
+
+        impl<'p> #blocking_proxy_name<'p> {
+            #[doc = #into_async_doc]
+            pub fn into_async(self) -> #async_proxy_name<'p> {
+                ::std::convert::From::from(self)
+            }
+        }
+
+        impl<'p> ::std::convert::From<#async_proxy_name<'p>> for #blocking_proxy_name<'p> {
+            fn from(proxy: #async_proxy_name<'p>) -> Self {
+                #blocking_proxy_name(#zbus::Proxy::into_blocking(proxy.into_inner()))
+            }
+        }
+
+        impl<'p> ::std::convert::From<#blocking_proxy_name<'p>> for #async_proxy_name<'p> {
+            fn from(proxy: #blocking_proxy_name<'p>) -> Self {
+                #async_proxy_name(#zbus::blocking::Proxy::into_async(proxy.into_inner()))
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn create_proxy<M: AttrParse + Into<MethodAttrs>>(
     input: &ItemTrait,
@@ -0,0 +1,65 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{spanned::Spanned, AttributeArgs, Error, FnArg, ItemFn};
+
+use crate::utils::zbus_path;
+
+pub fn expand(args: AttributeArgs, input: ItemFn) -> Result<TokenStream, Error> {
+    if let Some(arg) = args.first() {
+        return Err(Error::new(arg.span(), "unexpected argument"));
+    }
+    let sig = &input.sig;
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new(
+            sig.generics.span(),
+            "test functions can't be generic",
+        ));
+    }
+
+    let zbus = zbus_path();
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+    let name = &sig.ident;
+    let output = &sig.output;
+
+    let mut arg_names = vec![];
+    let mut fixtures = vec![];
+    for (i, arg) in sig.inputs.iter().enumerate() {
+        let ty = match arg {
+            FnArg::Typed(arg) => &arg.ty,
+            FnArg::Receiver(arg) => {
+                return Err(Error::new(arg.span(), "test functions can't take `self`"));
+            }
+        };
+        arg_names.push(format_ident!("__zbus_fixture{}", i));
+        fixtures.push(quote! {
+            <#ty as #zbus::test::Fixture>::create(&__zbus_bus)
+                .await
+                .expect("failed to create the test fixture")
+        });
+    }
+    let call = if sig.asyncness.is_some() {
+        quote! { #zbus::block_on(#name(#(#arg_names),*)) }
+    } else {
+        quote! { #name(#(#arg_names),*) }
+    };
+
+    Ok(quote! {
+        #[::core::prelude::v1::test]
+        #(#attrs)*
+        #vis fn #name() #output {
+            #sig #block
+
+            let __zbus_bus = #zbus::test::MockBus::new();
+            let (#(#arg_names,)*) = #zbus::block_on(async {
+                (#(#fixtures,)*)
+            });
+
+            #call
+        }
+    })
+}
@@ -76,6 +76,16 @@ fn test_proxy() {
             .build()
             .await
             .unwrap();
+        // Round-trip through the blocking proxy shouldn't lose any configuration.
+        let proxy = proxy.into_blocking().into_async();
+        assert_eq!(
+            proxy.inner().path().as_str(),
+            "/org/freedesktop/zbus_macros/test"
+        );
+        assert_eq!(
+            proxy.inner().destination().as_str(),
+            "org.freedesktop.zbus_macros"
+        );
         fdo::DBusProxy::builder(&connection)
             .build()
             .await
@@ -289,6 +299,27 @@ fn test_interface() {
     }
 }
 
+#[zbus::test]
+async fn test_test(
+    bus: zbus::test::MockBus,
+    first: zbus::Connection,
+    second: zbus::Connection,
+) -> zbus::Result<()> {
+    // Each connection argument is a separate connection to the same private bus.
+    let (first, second) = (first.unique_name().unwrap(), second.unique_name().unwrap());
+    assert_ne!(first, second);
+    assert_eq!(bus.connections(), [first.clone(), second.clone()]);
+
+    Ok(())
+}
+
+#[zbus::test]
+fn test_test_blocking(conn: zbus::blocking::Connection) {
+    let dbus = zbus::blocking::fdo::DBusProxy::new(&conn).unwrap();
+    let name = conn.unique_name().unwrap().clone();
+    assert!(dbus.name_has_owner(name.into()).unwrap());
+}
+
 mod signal_from_message {
     use super::*;
     use zbus::message::Message;
@@ -92,6 +92,38 @@ impl<'name> BusName<'name> {
         }
     }
 
+    /// Check if the name is in the dot-separated `namespace`.
+    ///
+    /// See [`starts_with_namespace`](crate::starts_with_namespace) for the rules.
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        crate::starts_with_namespace(self.as_str(), namespace)
+    }
+
+    /// The name without its last element, if that's still a bus name of the same kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::BusName;
+    ///
+    /// let name = BusName::try_from("org.freedesktop.portal.Desktop").unwrap();
+    /// let parent = name.parent().unwrap();
+    /// assert!(matches!(parent, BusName::WellKnown(_)));
+    /// assert_eq!(parent, "org.freedesktop.portal");
+    /// assert!(name.is_in_namespace("org.freedesktop"));
+    /// assert!(!name.is_in_namespace("org.freedesktop.port"));
+    ///
+    /// let name = BusName::try_from(":1.42").unwrap();
+    /// assert_eq!(name.parent(), None);
+    /// assert!(name.is_in_namespace(":1"));
+    /// ```
+    pub fn parent(&self) -> Option<BusName<'_>> {
+        match self {
+            BusName::Unique(name) => name.parent().map(BusName::Unique),
+            BusName::WellKnown(name) => name.parent().map(BusName::WellKnown),
+        }
+    }
+
     /// Same as `try_from`, except it takes a `&'static str`.
     pub fn from_static_str(name: &'static str) -> Result<Self> {
         match Self::try_from(name)? {
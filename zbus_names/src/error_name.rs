@@ -96,6 +96,43 @@ impl<'name> ErrorName<'name> {
     pub fn into_owned(self) -> ErrorName<'static> {
         ErrorName(self.0.into_owned())
     }
+
+    /// Check if the name is in the dot-separated `namespace`.
+    ///
+    /// See [`starts_with_namespace`](crate::starts_with_namespace) for the rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::ErrorName;
+    ///
+    /// let name = ErrorName::try_from("org.zbus.Error.Failed").unwrap();
+    /// assert!(name.is_in_namespace("org.zbus"));
+    /// assert!(name.is_in_namespace("org.zbus.Error.Failed"));
+    /// assert!(!name.is_in_namespace("org.zbus.Error.Faile"));
+    /// ```
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        crate::starts_with_namespace(self.as_str(), namespace)
+    }
+
+    /// The name without its last element, if that's still an error name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::ErrorName;
+    ///
+    /// let name = ErrorName::try_from("org.zbus.Error.Failed").unwrap();
+    /// assert_eq!(name.parent().unwrap(), "org.zbus.Error");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap(), "org.zbus");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap().parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<ErrorName<'_>> {
+        let parent = crate::namespace::parent_namespace(self.as_str())?;
+        ensure_correct_error_name(parent).ok()?;
+
+        Some(ErrorName::from_str_unchecked(parent))
+    }
 }
 
 impl Deref for ErrorName<'_> {
@@ -0,0 +1,122 @@
+use std::fmt::Write;
+
+use zvariant::ObjectPath;
+
+/// Escape `s` into a valid object path element.
+///
+/// This is the escaping systemd uses for the paths of its units: the characters other than ASCII
+/// letters and digits are replaced by `_` followed by the two lowercase hex digits of each of their
+/// bytes, and the empty string is escaped as a lone `_`. [`unescape_path_element`] reverses it.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_names::{escape_path_element, unescape_path_element};
+///
+/// assert_eq!(escape_path_element("dbus.service"), "dbus_2eservice");
+/// assert_eq!(escape_path_element("AA:BB"), "AA_3aBB");
+/// assert_eq!(escape_path_element("Grüße"), "Gr_c3_bc_c3_9fe");
+/// assert_eq!(escape_path_element(""), "_");
+///
+/// assert_eq!(unescape_path_element("dbus_2eservice").unwrap(), "dbus.service");
+/// assert_eq!(unescape_path_element("_").unwrap(), "");
+/// assert_eq!(unescape_path_element("dbus_2"), None);
+/// ```
+pub fn escape_path_element(s: &str) -> String {
+    escape(s, false)
+}
+
+/// Unescape an object path element escaped by [`escape_path_element`].
+///
+/// Returns `None` if `element` isn't a valid escaped element, or if it doesn't unescape to UTF-8.
+pub fn unescape_path_element(element: &str) -> Option<String> {
+    if element == "_" {
+        return Some(String::new());
+    }
+
+    let mut bytes = Vec::with_capacity(element.len());
+    let mut chars = element.bytes();
+    while let Some(b) = chars.next() {
+        match b {
+            b'_' => {
+                let hex = [chars.next()?, chars.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            b if b.is_ascii_alphanumeric() => bytes.push(b),
+            _ => return None,
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Escape `s` into a valid element of a bus, interface or error name.
+///
+/// This uses the same escaping as [`escape_path_element`], except that a leading digit is escaped
+/// as well, since name elements can't start with one.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_names::{escape_name_element, WellKnownName};
+///
+/// assert_eq!(escape_name_element("my-app"), "my_2dapp");
+/// assert_eq!(escape_name_element("2048"), "_32048");
+///
+/// let name = format!("org.example.{}", escape_name_element("user@host"));
+/// assert_eq!(
+///     WellKnownName::try_from(name).unwrap(),
+///     "org.example.user_40host",
+/// );
+/// ```
+pub fn escape_name_element(s: &str) -> String {
+    escape(s, true)
+}
+
+/// The object path of the `id` element under `prefix`, escaping `id` with
+/// [`escape_path_element`].
+///
+/// This is the equivalent of systemd's `sd_bus_path_encode`, to export an object per instance of
+/// something identified by an arbitrary string, such as a device or a user.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_names::object_path_with_id;
+/// use zvariant::ObjectPath;
+///
+/// let prefix = ObjectPath::try_from("/org/freedesktop/systemd1/unit").unwrap();
+/// assert_eq!(
+///     object_path_with_id(&prefix, "dbus.service"),
+///     "/org/freedesktop/systemd1/unit/dbus_2eservice",
+/// );
+///
+/// let root = ObjectPath::try_from("/").unwrap();
+/// assert_eq!(object_path_with_id(&root, "hci0"), "/hci0");
+/// ```
+pub fn object_path_with_id(prefix: &ObjectPath<'_>, id: &str) -> ObjectPath<'static> {
+    let prefix = prefix.as_str().trim_end_matches('/');
+    let path = format!("{prefix}/{}", escape_path_element(id));
+
+    ObjectPath::from_string_unchecked(path)
+}
+
+fn escape(s: &str, escape_leading_digit: bool) -> String {
+    if s.is_empty() {
+        return String::from("_");
+    }
+
+    let mut escaped = String::with_capacity(s.len());
+    for (i, b) in s.bytes().enumerate() {
+        let leading_digit = escape_leading_digit && i == 0 && b.is_ascii_digit();
+        if b.is_ascii_alphanumeric() && !leading_digit {
+            escaped.push(b as char);
+        } else {
+            // Writing to a `String` can't fail.
+            let _ = write!(escaped, "_{b:02x}");
+        }
+    }
+
+    escaped
+}
@@ -94,6 +94,43 @@ impl<'name> InterfaceName<'name> {
     pub fn into_owned(self) -> InterfaceName<'static> {
         InterfaceName(self.0.into_owned())
     }
+
+    /// Check if the name is in the dot-separated `namespace`.
+    ///
+    /// See [`starts_with_namespace`](crate::starts_with_namespace) for the rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::InterfaceName;
+    ///
+    /// let name = InterfaceName::try_from("org.freedesktop.DBus.Properties").unwrap();
+    /// assert!(name.is_in_namespace("org.freedesktop"));
+    /// assert!(name.is_in_namespace("org.freedesktop.DBus.Properties"));
+    /// assert!(!name.is_in_namespace("org.freedesktop.DBus.Propertie"));
+    /// ```
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        crate::starts_with_namespace(self.as_str(), namespace)
+    }
+
+    /// The name without its last element, if that's still an interface name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::InterfaceName;
+    ///
+    /// let name = InterfaceName::try_from("org.freedesktop.DBus.Properties").unwrap();
+    /// assert_eq!(name.parent().unwrap(), "org.freedesktop.DBus");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap(), "org.freedesktop");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap().parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<InterfaceName<'_>> {
+        let parent = crate::namespace::parent_namespace(self.as_str())?;
+        ensure_correct_interface_name(parent).ok()?;
+
+        Some(InterfaceName::from_str_unchecked(parent))
+    }
 }
 
 impl Deref for InterfaceName<'_> {
@@ -34,4 +34,10 @@ pub use error::*;
 mod error_name;
 pub use error_name::*;
 
+mod escape;
+pub use escape::*;
+
+mod namespace;
+pub use namespace::*;
+
 mod utils;
@@ -0,0 +1,38 @@
+/// Check if `name` is in the dot-separated `namespace`.
+///
+/// Following the rules of the D-Bus specification for `arg0namespace` match rules, a name is in a
+/// namespace if it's the namespace itself, or if it starts with the namespace followed by a `.`.
+/// This is what a plain `starts_with` gets wrong: `org.freedesktop.DBusX` isn't in the
+/// `org.freedesktop.DBus` namespace.
+///
+/// This works with any string, not only valid names, so it can be used on message arguments. Names
+/// of this crate have an `is_in_namespace` method doing the same.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_names::starts_with_namespace;
+///
+/// assert!(starts_with_namespace("org.freedesktop.DBus", "org.freedesktop"));
+/// assert!(starts_with_namespace("org.freedesktop.DBus", "org.freedesktop.DBus"));
+/// assert!(starts_with_namespace(":1.42", ":1"));
+///
+/// assert!(!starts_with_namespace("org.freedesktop.DBusX", "org.freedesktop.DBus"));
+/// assert!(!starts_with_namespace("org.freedesktop", "org.freedesktop.DBus"));
+/// assert!(!starts_with_namespace("org.freedesktop.DBus", "org."));
+/// assert!(!starts_with_namespace("org.freedesktop.DBus", ""));
+/// ```
+pub fn starts_with_namespace(name: &str, namespace: &str) -> bool {
+    if namespace.is_empty() {
+        return false;
+    }
+
+    name.strip_prefix(namespace)
+        .map(|rest| rest.is_empty() || rest.starts_with('.'))
+        .unwrap_or(false)
+}
+
+// The part of `name` before its last element, if it has more than one.
+pub(crate) fn parent_namespace(name: &str) -> Option<&str> {
+    name.rsplit_once('.').map(|(parent, _)| parent)
+}
@@ -91,6 +91,42 @@ impl<'name> UniqueName<'name> {
     pub fn into_owned(self) -> UniqueName<'static> {
         UniqueName(self.0.into_owned())
     }
+
+    /// Check if the name is in the dot-separated `namespace`.
+    ///
+    /// See [`starts_with_namespace`](crate::starts_with_namespace) for the rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::UniqueName;
+    ///
+    /// let name = UniqueName::try_from(":1.42").unwrap();
+    /// assert!(name.is_in_namespace(":1"));
+    /// assert!(name.is_in_namespace(":1.42"));
+    /// assert!(!name.is_in_namespace(":1.4"));
+    /// ```
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        crate::starts_with_namespace(self.as_str(), namespace)
+    }
+
+    /// The name without its last element, if that's still a unique name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::UniqueName;
+    ///
+    /// let name = UniqueName::try_from(":org.zbus.Connection").unwrap();
+    /// assert_eq!(name.parent().unwrap(), ":org.zbus");
+    /// assert_eq!(UniqueName::try_from(":1.42").unwrap().parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<UniqueName<'_>> {
+        let parent = crate::namespace::parent_namespace(self.as_str())?;
+        ensure_correct_unique_name(parent).ok()?;
+
+        Some(UniqueName::from_str_unchecked(parent))
+    }
 }
 
 impl Deref for UniqueName<'_> {
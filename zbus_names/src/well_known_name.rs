@@ -92,6 +92,97 @@ impl<'name> WellKnownName<'name> {
     pub fn into_owned(self) -> WellKnownName<'static> {
         WellKnownName(self.0.into_owned())
     }
+
+    /// Append `element` to the name, escaping it with [`escape_name_element`].
+    ///
+    /// This is handy to derive the name of an instance of an application from an arbitrary
+    /// string identifying it.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidWellKnownName`] if the resulting name is longer than 255 characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::WellKnownName;
+    ///
+    /// let name = WellKnownName::try_from("org.example.Editor").unwrap();
+    /// assert_eq!(
+    ///     name.join("notes.txt").unwrap(),
+    ///     "org.example.Editor.notes_2etxt",
+    /// );
+    /// ```
+    ///
+    /// [`escape_name_element`]: crate::escape_name_element
+    pub fn join(&self, element: &str) -> Result<WellKnownName<'static>> {
+        let name = format!("{self}.{}", crate::escape_name_element(element));
+
+        WellKnownName::try_from(name)
+    }
+
+    /// Append an `instance<PID>` element to the name, with the ID of the current process.
+    ///
+    /// This is the convention of the MPRIS specification, for applications to own a name per
+    /// running instance.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidWellKnownName`] if the resulting name is longer than 255 characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::WellKnownName;
+    ///
+    /// let name = WellKnownName::try_from("org.mpris.MediaPlayer2.vlc").unwrap();
+    /// assert_eq!(
+    ///     name.join_instance().unwrap(),
+    ///     format!("org.mpris.MediaPlayer2.vlc.instance{}", std::process::id()).as_str(),
+    /// );
+    /// ```
+    pub fn join_instance(&self) -> Result<WellKnownName<'static>> {
+        let name = format!("{self}.instance{}", std::process::id());
+
+        WellKnownName::try_from(name)
+    }
+
+    /// Check if the name is in the dot-separated `namespace`.
+    ///
+    /// See [`starts_with_namespace`](crate::starts_with_namespace) for the rules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::WellKnownName;
+    ///
+    /// let name = WellKnownName::try_from("org.freedesktop.portal.Desktop").unwrap();
+    /// assert!(name.is_in_namespace("org.freedesktop"));
+    /// assert!(name.is_in_namespace("org.freedesktop.portal.Desktop"));
+    /// assert!(!name.is_in_namespace("org.freedesktop.portal.Deskto"));
+    /// ```
+    pub fn is_in_namespace(&self, namespace: &str) -> bool {
+        crate::starts_with_namespace(self.as_str(), namespace)
+    }
+
+    /// The name without its last element, if that's still a well-known name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::WellKnownName;
+    ///
+    /// let name = WellKnownName::try_from("org.freedesktop.portal.Desktop").unwrap();
+    /// assert_eq!(name.parent().unwrap(), "org.freedesktop.portal");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap(), "org.freedesktop");
+    /// assert_eq!(name.parent().unwrap().parent().unwrap().parent(), None);
+    /// ```
+    pub fn parent(&self) -> Option<WellKnownName<'_>> {
+        let parent = crate::namespace::parent_namespace(self.as_str())?;
+        ensure_correct_well_known_name(parent).ok()?;
+
+        Some(WellKnownName::from_str_unchecked(parent))
+    }
 }
 
 impl Deref for WellKnownName<'_> {
@@ -0,0 +1,47 @@
+#![deny(rust_2018_idioms)]
+
+//! Compare two introspection documents and report the changes.
+//!
+//! Exits with status 1 if any of the changes can break existing clients, 2 on errors.
+
+use std::{env, error::Error, ffi::OsString, fs::File, process::ExitCode};
+
+use zbus_xml::{Diff, Node};
+
+fn main() -> ExitCode {
+    let args: Vec<_> = env::args_os().skip(1).collect();
+    let [old, new] = args.as_slice() else {
+        eprintln!("Usage: zbus-xml-diff OLD.xml NEW.xml");
+        return ExitCode::from(2);
+    };
+
+    let parse = |path: &OsString| -> Result<Node<'static>, Box<dyn Error>> {
+        let file = File::open(path)
+            .map_err(|e| format!("failed to open `{}`: {e}", path.to_string_lossy()))?;
+
+        Ok(Node::from_reader(file)?)
+    };
+    let (old, new) = match (parse(old), parse(new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let diff = Diff::new(&old, &new);
+    for change in diff.changes() {
+        let severity = if change.is_breaking() {
+            "breaking"
+        } else {
+            "additive"
+        };
+        println!("{severity}: {change}");
+    }
+
+    if diff.is_breaking() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
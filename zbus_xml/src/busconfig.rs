@@ -0,0 +1,516 @@
+//! Message bus configuration files.
+//!
+//! These are the XML files configuring a message bus, such as `/usr/share/dbus-1/system.conf`,
+//! and the policy files they include, such as the ones in `/usr/share/dbus-1/system.d`. See the
+//! [`dbus-daemon` manual] for their format.
+//!
+//! [`dbus-daemon` manual]: https://dbus.freedesktop.org/doc/dbus-daemon.1.html
+
+use quick_xml::de::Deserializer;
+use serde::Deserialize;
+use static_assertions::assert_impl_all;
+use std::io::Read;
+
+use crate::{parse::read_to_string, Result};
+
+/// A message bus configuration file, the `busconfig` element.
+///
+/// Included files and directories aren't followed: the configuration of a bus is the merge of this
+/// file with the ones it includes.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_xml::busconfig::{BusConfig, MessageType, Rule};
+///
+/// let config = BusConfig::try_from(
+///     r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN"
+///          "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+///        <busconfig>
+///          <policy user="root">
+///            <allow own="org.example.Service"/>
+///          </policy>
+///          <policy context="default">
+///            <deny send_type="method_call"/>
+///            <allow send_destination="org.example.Service" send_interface="org.example.Sample"/>
+///          </policy>
+///        </busconfig>"#,
+/// )
+/// .unwrap();
+///
+/// let [root, default] = config.policies() else {
+///     panic!("expected 2 policies");
+/// };
+/// assert_eq!(root.user(), Some("root"));
+/// assert_eq!(root.rules()[0].own(), Some("org.example.Service"));
+/// let [Rule::Deny(deny), Rule::Allow(allow)] = default.rules() else {
+///     panic!("expected a `deny` and an `allow` rule");
+/// };
+/// assert_eq!(deny.send_type(), Some(MessageType::MethodCall));
+/// assert_eq!(allow.send_destination(), Some("org.example.Service"));
+/// ```
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct BusConfig {
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    user: Option<String>,
+    fork: Option<()>,
+    keep_umask: Option<()>,
+    syslog: Option<()>,
+    allow_anonymous: Option<()>,
+    pidfile: Option<String>,
+    servicehelper: Option<String>,
+    #[serde(default)]
+    listen: Vec<String>,
+    #[serde(default)]
+    auth: Vec<String>,
+    #[serde(rename = "servicedir", default)]
+    servicedirs: Vec<String>,
+    standard_session_servicedirs: Option<()>,
+    standard_system_servicedirs: Option<()>,
+    #[serde(rename = "include", default)]
+    includes: Vec<Include>,
+    #[serde(rename = "includedir", default)]
+    includedirs: Vec<String>,
+    #[serde(rename = "policy", default)]
+    policies: Vec<Policy>,
+    #[serde(rename = "limit", default)]
+    limits: Vec<Limit>,
+}
+
+assert_impl_all!(BusConfig: Send, Sync, Unpin);
+
+impl BusConfig {
+    /// Parse the configuration file from reader.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Self::try_from(read_to_string(reader)?.as_str())
+    }
+
+    /// The well-known type of the bus, e.g. `system` or `session`.
+    pub fn ty(&self) -> Option<&str> {
+        self.ty.as_deref()
+    }
+
+    /// The user the bus runs as.
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// Whether the bus forks into the background.
+    pub fn fork(&self) -> bool {
+        self.fork.is_some()
+    }
+
+    /// Whether the bus keeps the umask it was started with when forking.
+    pub fn keep_umask(&self) -> bool {
+        self.keep_umask.is_some()
+    }
+
+    /// Whether the bus logs to syslog.
+    pub fn syslog(&self) -> bool {
+        self.syslog.is_some()
+    }
+
+    /// Whether clients can connect without authenticating.
+    pub fn allow_anonymous(&self) -> bool {
+        self.allow_anonymous.is_some()
+    }
+
+    /// The file the bus writes its process ID to.
+    pub fn pidfile(&self) -> Option<&str> {
+        self.pidfile.as_deref()
+    }
+
+    /// The setuid helper activating services on the system bus.
+    pub fn servicehelper(&self) -> Option<&str> {
+        self.servicehelper.as_deref()
+    }
+
+    /// The addresses the bus listens on.
+    pub fn listen(&self) -> &[String] {
+        &self.listen
+    }
+
+    /// The allowed authentication mechanisms. All of them are allowed if this is empty.
+    pub fn auth(&self) -> &[String] {
+        &self.auth
+    }
+
+    /// The directories to look for service files in.
+    pub fn servicedirs(&self) -> &[String] {
+        &self.servicedirs
+    }
+
+    /// Whether the standard session bus service directories are searched as well.
+    pub fn standard_session_servicedirs(&self) -> bool {
+        self.standard_session_servicedirs.is_some()
+    }
+
+    /// Whether the standard system bus service directories are searched as well.
+    pub fn standard_system_servicedirs(&self) -> bool {
+        self.standard_system_servicedirs.is_some()
+    }
+
+    /// The included configuration files.
+    pub fn includes(&self) -> &[Include] {
+        &self.includes
+    }
+
+    /// The directories whose `.conf` files are included.
+    pub fn includedirs(&self) -> &[String] {
+        &self.includedirs
+    }
+
+    /// The security policies, in document order.
+    pub fn policies(&self) -> &[Policy] {
+        &self.policies
+    }
+
+    /// The resource limits.
+    pub fn limits(&self) -> &[Limit] {
+        &self.limits
+    }
+}
+
+impl TryFrom<&str> for BusConfig {
+    type Error = crate::Error;
+
+    /// Parse the configuration file from `s`.
+    fn try_from(s: &str) -> Result<Self> {
+        let mut deserializer = Deserializer::from_str(s);
+
+        Ok(BusConfig::deserialize(&mut deserializer)?)
+    }
+}
+
+/// An included configuration file, the `include` element.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Include {
+    #[serde(rename = "$text")]
+    path: String,
+    #[serde(rename = "@ignore_missing")]
+    ignore_missing: Option<String>,
+    #[serde(rename = "@if_selinux_enabled")]
+    if_selinux_enabled: Option<String>,
+    #[serde(rename = "@selinux_root_relative")]
+    selinux_root_relative: Option<String>,
+}
+
+assert_impl_all!(Include: Send, Sync, Unpin);
+
+impl Include {
+    /// The path of the file, relative to the directory of the including file if it's relative.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether it's fine for the file not to exist.
+    pub fn ignore_missing(&self) -> bool {
+        self.ignore_missing.as_deref() == Some("yes")
+    }
+
+    /// Whether the file is only included if SELinux is enabled.
+    pub fn if_selinux_enabled(&self) -> bool {
+        self.if_selinux_enabled.as_deref() == Some("yes")
+    }
+
+    /// Whether the path is relative to the SELinux policy root.
+    pub fn selinux_root_relative(&self) -> bool {
+        self.selinux_root_relative.as_deref() == Some("yes")
+    }
+}
+
+/// A resource limit, the `limit` element.
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct Limit {
+    #[serde(rename = "@name")]
+    name: String,
+    #[serde(rename = "$text")]
+    value: u64,
+}
+
+assert_impl_all!(Limit: Send, Sync, Unpin);
+
+impl Limit {
+    /// The name of the limit, e.g. `max_incoming_bytes`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the limit.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+/// The contexts a [`Policy`] applies in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum PolicyContext {
+    /// The policy applies to every connection, before the user and group specific policies.
+    #[serde(rename = "default")]
+    Default,
+    /// The policy applies to every connection, after all the other policies.
+    #[serde(rename = "mandatory")]
+    Mandatory,
+}
+
+/// A security policy, the `policy` element.
+///
+/// A policy applies to the connections in its context, of its user or group, or of the users
+/// logged in at the console, or not. Its rules are applied in order, the last matching one
+/// deciding whether a message or a name ownership is allowed.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Policy {
+    #[serde(rename = "@context")]
+    context: Option<PolicyContext>,
+    #[serde(rename = "@user")]
+    user: Option<String>,
+    #[serde(rename = "@group")]
+    group: Option<String>,
+    #[serde(rename = "@at_console")]
+    at_console: Option<bool>,
+    #[serde(rename = "$value", default)]
+    rules: Vec<Rule>,
+}
+
+assert_impl_all!(Policy: Send, Sync, Unpin);
+
+impl Policy {
+    /// The context the policy applies in, if it's not user, group or console specific.
+    pub fn context(&self) -> Option<PolicyContext> {
+        self.context
+    }
+
+    /// The user the policy applies to. `*` is any user.
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// The group the policy applies to. `*` is any group.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Whether the policy applies to the users logged in at the console, or to the others.
+    pub fn at_console(&self) -> Option<bool> {
+        self.at_console
+    }
+
+    /// The rules, in document order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+}
+
+/// A rule of a [`Policy`], the `allow` and `deny` elements.
+///
+/// The rule derefs to its [`RuleMatch`], to get to its attributes whatever its kind.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub enum Rule {
+    /// Allow what matches.
+    #[serde(rename = "allow")]
+    Allow(RuleMatch),
+    /// Deny what matches.
+    #[serde(rename = "deny")]
+    Deny(RuleMatch),
+}
+
+assert_impl_all!(Rule: Send, Sync, Unpin);
+
+impl Rule {
+    /// Whether the rule allows what it matches.
+    pub fn is_allow(&self) -> bool {
+        matches!(self, Rule::Allow(_))
+    }
+}
+
+impl std::ops::Deref for Rule {
+    type Target = RuleMatch;
+
+    fn deref(&self) -> &RuleMatch {
+        match self {
+            Rule::Allow(m) | Rule::Deny(m) => m,
+        }
+    }
+}
+
+/// The attributes of a [`Rule`], telling what it matches.
+///
+/// Name and path values can be `*`, to match anything.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+pub struct RuleMatch {
+    #[serde(rename = "@send_interface")]
+    send_interface: Option<String>,
+    #[serde(rename = "@send_member")]
+    send_member: Option<String>,
+    #[serde(rename = "@send_error")]
+    send_error: Option<String>,
+    #[serde(rename = "@send_destination")]
+    send_destination: Option<String>,
+    #[serde(rename = "@send_destination_prefix")]
+    send_destination_prefix: Option<String>,
+    #[serde(rename = "@send_path")]
+    send_path: Option<String>,
+    #[serde(rename = "@send_type")]
+    send_type: Option<MessageType>,
+    #[serde(rename = "@send_requested_reply")]
+    send_requested_reply: Option<bool>,
+    #[serde(rename = "@send_broadcast")]
+    send_broadcast: Option<bool>,
+    #[serde(rename = "@receive_interface")]
+    receive_interface: Option<String>,
+    #[serde(rename = "@receive_member")]
+    receive_member: Option<String>,
+    #[serde(rename = "@receive_error")]
+    receive_error: Option<String>,
+    #[serde(rename = "@receive_sender")]
+    receive_sender: Option<String>,
+    #[serde(rename = "@receive_path")]
+    receive_path: Option<String>,
+    #[serde(rename = "@receive_type")]
+    receive_type: Option<MessageType>,
+    #[serde(rename = "@receive_requested_reply")]
+    receive_requested_reply: Option<bool>,
+    #[serde(rename = "@eavesdrop")]
+    eavesdrop: Option<bool>,
+    #[serde(rename = "@own")]
+    own: Option<String>,
+    #[serde(rename = "@own_prefix")]
+    own_prefix: Option<String>,
+    #[serde(rename = "@user")]
+    user: Option<String>,
+    #[serde(rename = "@group")]
+    group: Option<String>,
+    #[serde(rename = "@log")]
+    log: Option<bool>,
+}
+
+assert_impl_all!(RuleMatch: Send, Sync, Unpin);
+
+impl RuleMatch {
+    /// The interface of the sent messages.
+    pub fn send_interface(&self) -> Option<&str> {
+        self.send_interface.as_deref()
+    }
+
+    /// The member of the sent messages.
+    pub fn send_member(&self) -> Option<&str> {
+        self.send_member.as_deref()
+    }
+
+    /// The error name of the sent messages.
+    pub fn send_error(&self) -> Option<&str> {
+        self.send_error.as_deref()
+    }
+
+    /// The destination of the sent messages.
+    pub fn send_destination(&self) -> Option<&str> {
+        self.send_destination.as_deref()
+    }
+
+    /// The prefix of the destination of the sent messages.
+    pub fn send_destination_prefix(&self) -> Option<&str> {
+        self.send_destination_prefix.as_deref()
+    }
+
+    /// The path of the sent messages.
+    pub fn send_path(&self) -> Option<&str> {
+        self.send_path.as_deref()
+    }
+
+    /// The type of the sent messages.
+    pub fn send_type(&self) -> Option<MessageType> {
+        self.send_type
+    }
+
+    /// Whether the sent messages are replies that were requested.
+    pub fn send_requested_reply(&self) -> Option<bool> {
+        self.send_requested_reply
+    }
+
+    /// Whether the sent messages are broadcast signals.
+    pub fn send_broadcast(&self) -> Option<bool> {
+        self.send_broadcast
+    }
+
+    /// The interface of the received messages.
+    pub fn receive_interface(&self) -> Option<&str> {
+        self.receive_interface.as_deref()
+    }
+
+    /// The member of the received messages.
+    pub fn receive_member(&self) -> Option<&str> {
+        self.receive_member.as_deref()
+    }
+
+    /// The error name of the received messages.
+    pub fn receive_error(&self) -> Option<&str> {
+        self.receive_error.as_deref()
+    }
+
+    /// The sender of the received messages.
+    pub fn receive_sender(&self) -> Option<&str> {
+        self.receive_sender.as_deref()
+    }
+
+    /// The path of the received messages.
+    pub fn receive_path(&self) -> Option<&str> {
+        self.receive_path.as_deref()
+    }
+
+    /// The type of the received messages.
+    pub fn receive_type(&self) -> Option<MessageType> {
+        self.receive_type
+    }
+
+    /// Whether the received messages are replies that were requested.
+    pub fn receive_requested_reply(&self) -> Option<bool> {
+        self.receive_requested_reply
+    }
+
+    /// Whether the rule applies to eavesdropping.
+    pub fn eavesdrop(&self) -> Option<bool> {
+        self.eavesdrop
+    }
+
+    /// The name to own.
+    pub fn own(&self) -> Option<&str> {
+        self.own.as_deref()
+    }
+
+    /// The prefix of the names to own.
+    pub fn own_prefix(&self) -> Option<&str> {
+        self.own_prefix.as_deref()
+    }
+
+    /// The user allowed or denied to connect.
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// The group allowed or denied to connect.
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    /// Whether the matched messages are logged.
+    pub fn log(&self) -> Option<bool> {
+        self.log
+    }
+}
+
+/// The type of the messages a [`Rule`] matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum MessageType {
+    #[serde(rename = "method_call")]
+    MethodCall,
+    #[serde(rename = "method_return")]
+    MethodReturn,
+    #[serde(rename = "signal")]
+    Signal,
+    #[serde(rename = "error")]
+    Error,
+    /// Any type of message.
+    #[serde(rename = "*")]
+    Any,
+}
@@ -0,0 +1,321 @@
+use std::fmt;
+
+use crate::{Arg, ArgDirection, Interface, Node};
+
+/// The differences between two versions of an introspection document.
+///
+/// Interfaces are compared by name, and their members by name and kind. The changes that can break
+/// existing clients, such as removed members, changed signatures or narrowed property access, are
+/// told apart from the additive ones, see [`Change::is_breaking`]. Child nodes present in both
+/// documents are compared as well.
+///
+/// Argument names, annotations and documentation are not compared.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_xml::{ChangeKind, Diff, Node};
+///
+/// let old = Node::try_from(
+///     r#"<node>
+///          <interface name="org.example.Sample">
+///            <method name="Frobate"><arg type="i" direction="in"/></method>
+///            <property name="Bar" type="y" access="readwrite"/>
+///          </interface>
+///        </node>"#,
+/// )
+/// .unwrap();
+/// let new = Node::try_from(
+///     r#"<node>
+///          <interface name="org.example.Sample">
+///            <method name="Frobate"><arg type="i" direction="in"/></method>
+///            <method name="Bazify"/>
+///            <property name="Bar" type="y" access="read"/>
+///          </interface>
+///        </node>"#,
+/// )
+/// .unwrap();
+///
+/// let diff = Diff::new(&old, &new);
+/// assert!(diff.is_breaking());
+/// let changes: Vec<_> = diff.changes().iter().map(|c| c.to_string()).collect();
+/// assert_eq!(
+///     changes,
+///     [
+///         "org.example.Sample: method `Bazify` added",
+///         "org.example.Sample: property `Bar` access narrowed",
+///     ],
+/// );
+/// let breaking: Vec<_> = diff.breaking_changes().map(|c| c.kind()).collect();
+/// assert_eq!(breaking, [ChangeKind::AccessNarrowed]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Compare `old` against `new`.
+    pub fn new(old: &Node<'_>, new: &Node<'_>) -> Self {
+        let mut diff = Self {
+            changes: Vec::new(),
+        };
+        diff.compare_nodes("", old, new);
+
+        diff
+    }
+
+    /// All the changes, in document order.
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// The changes that can break existing clients.
+    pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|c| c.is_breaking())
+    }
+
+    /// Whether any of the changes can break existing clients.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking_changes().next().is_some()
+    }
+
+    /// Whether the documents describe the same API.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn compare_nodes(&mut self, path: &str, old: &Node<'_>, new: &Node<'_>) {
+        for old_iface in old.interfaces() {
+            match new
+                .interfaces()
+                .iter()
+                .find(|i| i.name() == old_iface.name())
+            {
+                Some(new_iface) => self.compare_interfaces(path, old_iface, new_iface),
+                None => self.push(path, old_iface, None, ChangeKind::Removed),
+            }
+        }
+        for new_iface in new.interfaces() {
+            if !old
+                .interfaces()
+                .iter()
+                .any(|i| i.name() == new_iface.name())
+            {
+                self.push(path, new_iface, None, ChangeKind::Added);
+            }
+        }
+
+        for old_child in old.nodes() {
+            let (Some(name), Some(new_child)) = (
+                old_child.name(),
+                new.nodes().iter().find(|n| n.name() == old_child.name()),
+            ) else {
+                continue;
+            };
+            let child_path = if path.is_empty() {
+                name.to_string()
+            } else {
+                format!("{path}/{name}")
+            };
+            self.compare_nodes(&child_path, old_child, new_child);
+        }
+    }
+
+    fn compare_interfaces(&mut self, path: &str, old: &Interface<'_>, new: &Interface<'_>) {
+        for old_method in old.methods() {
+            let member = Member::Method(old_method.name().to_string());
+            match new.methods().iter().find(|m| m.name() == old_method.name()) {
+                Some(new_method) => {
+                    if signature(old_method.args(), ArgDirection::In)
+                        != signature(new_method.args(), ArgDirection::In)
+                        || signature(old_method.args(), ArgDirection::Out)
+                            != signature(new_method.args(), ArgDirection::Out)
+                    {
+                        self.push(path, old, Some(member), ChangeKind::SignatureChanged);
+                    }
+                }
+                None => self.push(path, old, Some(member), ChangeKind::Removed),
+            }
+        }
+        for new_method in new.methods() {
+            if !old.methods().iter().any(|m| m.name() == new_method.name()) {
+                let member = Member::Method(new_method.name().to_string());
+                self.push(path, new, Some(member), ChangeKind::Added);
+            }
+        }
+
+        for old_signal in old.signals() {
+            let member = Member::Signal(old_signal.name().to_string());
+            match new.signals().iter().find(|s| s.name() == old_signal.name()) {
+                Some(new_signal) => {
+                    let types = |args: &[Arg<'_>]| {
+                        args.iter()
+                            .map(|a| a.ty().signature().as_str().to_string())
+                            .collect::<Vec<_>>()
+                    };
+                    if types(old_signal.args()) != types(new_signal.args()) {
+                        self.push(path, old, Some(member), ChangeKind::SignatureChanged);
+                    }
+                }
+                None => self.push(path, old, Some(member), ChangeKind::Removed),
+            }
+        }
+        for new_signal in new.signals() {
+            if !old.signals().iter().any(|s| s.name() == new_signal.name()) {
+                let member = Member::Signal(new_signal.name().to_string());
+                self.push(path, new, Some(member), ChangeKind::Added);
+            }
+        }
+
+        for old_prop in old.properties() {
+            let member = Member::Property(old_prop.name().to_string());
+            let Some(new_prop) = new
+                .properties()
+                .iter()
+                .find(|p| p.name() == old_prop.name())
+            else {
+                self.push(path, old, Some(member), ChangeKind::Removed);
+                continue;
+            };
+            if old_prop.ty() != new_prop.ty() {
+                self.push(path, old, Some(member), ChangeKind::SignatureChanged);
+                continue;
+            }
+            let (old_access, new_access) = (old_prop.access(), new_prop.access());
+            if (old_access.read() && !new_access.read())
+                || (old_access.write() && !new_access.write())
+            {
+                self.push(path, old, Some(member), ChangeKind::AccessNarrowed);
+            } else if old_access != new_access {
+                self.push(path, old, Some(member), ChangeKind::AccessWidened);
+            }
+        }
+        for new_prop in new.properties() {
+            if !old.properties().iter().any(|p| p.name() == new_prop.name()) {
+                let member = Member::Property(new_prop.name().to_string());
+                self.push(path, new, Some(member), ChangeKind::Added);
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        path: &str,
+        iface: &Interface<'_>,
+        member: Option<Member>,
+        kind: ChangeKind,
+    ) {
+        self.changes.push(Change {
+            path: path.to_string(),
+            interface: iface.name().to_string(),
+            member,
+            kind,
+        });
+    }
+}
+
+/// A change between two versions of an introspection document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    path: String,
+    interface: String,
+    member: Option<Member>,
+    kind: ChangeKind,
+}
+
+impl Change {
+    /// The path of the node, relative to the compared ones.
+    ///
+    /// This is an empty string for the compared nodes themselves.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The name of the changed interface, or of the interface of the changed member.
+    pub fn interface(&self) -> &str {
+        &self.interface
+    }
+
+    /// The changed member, if the change isn't about the interface as a whole.
+    pub fn member(&self) -> Option<&Member> {
+        self.member.as_ref()
+    }
+
+    /// The kind of change.
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+
+    /// Whether the change can break existing clients.
+    pub fn is_breaking(&self) -> bool {
+        matches!(
+            self.kind,
+            ChangeKind::Removed | ChangeKind::SignatureChanged | ChangeKind::AccessNarrowed
+        )
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.interface)?;
+        match &self.member {
+            Some(member) => write!(f, ": {member} ")?,
+            None => f.write_str(" ")?,
+        }
+        let kind = match self.kind {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::SignatureChanged => "signature changed",
+            ChangeKind::AccessNarrowed => "access narrowed",
+            ChangeKind::AccessWidened => "access widened",
+        };
+
+        f.write_str(kind)
+    }
+}
+
+/// A member of an interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Member {
+    Method(String),
+    Signal(String),
+    Property(String),
+}
+
+impl fmt::Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Member::Method(name) => write!(f, "method `{name}`"),
+            Member::Signal(name) => write!(f, "signal `{name}`"),
+            Member::Property(name) => write!(f, "property `{name}`"),
+        }
+    }
+}
+
+/// The kind of a [`Change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The interface or member was added.
+    Added,
+    /// The interface or member was removed.
+    Removed,
+    /// The arguments of a method or signal, or the type of a property, changed.
+    SignatureChanged,
+    /// A property can't be read or written anymore.
+    AccessNarrowed,
+    /// A property can now be read or written as well.
+    AccessWidened,
+}
+
+// The signatures of the method arguments in `direction`, taking arguments without a direction as
+// `in` ones.
+fn signature<'a>(args: &'a [Arg<'_>], direction: ArgDirection) -> Vec<&'a str> {
+    args.iter()
+        .filter(|a| a.direction().unwrap_or(ArgDirection::In) == direction)
+        .map(|a| a.ty().signature().as_str())
+        .collect()
+}
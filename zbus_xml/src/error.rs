@@ -3,6 +3,8 @@ use static_assertions::assert_impl_all;
 use std::{convert::Infallible, error, fmt};
 use zvariant::Error as VariantError;
 
+use crate::Diagnostic;
+
 /// The error type for `zbus_names`.
 ///
 /// The various errors that can be reported by this crate.
@@ -12,6 +14,8 @@ pub enum Error {
     Variant(VariantError),
     /// An XML error from quick_xml
     QuickXml(DeError),
+    /// An error in the document, with its location.
+    Parse(Diagnostic),
 }
 
 assert_impl_all!(Error: Send, Sync, Unpin);
@@ -21,6 +25,7 @@ impl PartialEq for Error {
         match (self, other) {
             (Self::Variant(s), Self::Variant(o)) => s == o,
             (Self::QuickXml(_), Self::QuickXml(_)) => false,
+            (Self::Parse(s), Self::Parse(o)) => s == o,
             (_, _) => false,
         }
     }
@@ -31,6 +36,7 @@ impl error::Error for Error {
         match self {
             Error::Variant(e) => Some(e),
             Error::QuickXml(e) => Some(e),
+            Error::Parse(_) => None,
         }
     }
 }
@@ -40,6 +46,7 @@ impl fmt::Display for Error {
         match self {
             Error::Variant(e) => write!(f, "{e}"),
             Error::QuickXml(e) => write!(f, "XML error: {e}"),
+            Error::Parse(e) => write!(f, "Invalid document: {e}"),
         }
     }
 }
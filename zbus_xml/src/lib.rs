@@ -13,13 +13,37 @@
 mod error;
 pub use error::{Error, Result};
 
-use quick_xml::{de::Deserializer, se::to_writer};
+mod diff;
+pub use diff::{Change, ChangeKind, Diff, Member};
+
+mod parse;
+pub use parse::{Diagnostic, ParseOptions};
+
+pub mod busconfig;
+pub mod service;
+
+use quick_xml::{
+    de::{DeError, Deserializer},
+    se::Serializer,
+};
 use serde::{Deserialize, Serialize};
 use static_assertions::assert_impl_all;
-use std::io::{BufReader, Read, Write};
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{Read, Write},
+};
 
 use zbus_names::{InterfaceName, MemberName, PropertyName};
-use zvariant::CompleteType;
+use zvariant::{CompleteType, ObjectPath, OwnedObjectPath};
+
+// The namespace of the documentation elements.
+const DOC_NAMESPACE: &str = "http://www.freedesktop.org/dbus/1.0/doc.dtd";
+
+// The DOCTYPE declaration of introspection documents.
+const DOCTYPE: &str = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+"#;
 
 /// Annotations are generic key/value pairs of metadata.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -33,6 +57,14 @@ pub struct Annotation {
 assert_impl_all!(Annotation: Send, Sync, Unpin);
 
 impl Annotation {
+    /// Create a new annotation.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
     /// Return the annotation name/key.
     pub fn name(&self) -> &str {
         &self.name
@@ -44,6 +76,85 @@ impl Annotation {
     }
 }
 
+/// Documentation of an interface or of one of its members.
+///
+/// This is the `doc:doc` element of the documentation format that `gdbus-codegen` understands,
+/// with the `doc` prefix bound to `http://www.freedesktop.org/dbus/1.0/doc.dtd`:
+///
+/// ```xml
+/// <doc:doc>
+///   <doc:summary>A short summary</doc:summary>
+///   <doc:description>
+///     <doc:para>A first paragraph.</doc:para>
+///     <doc:para>A second paragraph.</doc:para>
+///   </doc:description>
+/// </doc:doc>
+/// ```
+///
+/// The elements are also recognized without the prefix. Instead of reading this element directly,
+/// prefer the `summary` and `description` methods of the documented types, since they also fall
+/// back to the `org.gtk.GDBus.DocString` annotations.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Doc {
+    #[serde(
+        rename = "doc:summary",
+        alias = "summary",
+        skip_serializing_if = "Option::is_none"
+    )]
+    summary: Option<String>,
+    #[serde(
+        rename = "doc:description",
+        alias = "description",
+        skip_serializing_if = "Option::is_none"
+    )]
+    description: Option<Description>,
+}
+
+assert_impl_all!(Doc: Send, Sync, Unpin);
+
+impl Doc {
+    /// Create a new, empty documentation element.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the summary.
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+
+        self
+    }
+
+    /// Add a paragraph to the description.
+    pub fn add_paragraph(mut self, paragraph: impl Into<String>) -> Self {
+        self.description
+            .get_or_insert_with(Default::default)
+            .paragraphs
+            .push(paragraph.into());
+
+        self
+    }
+
+    /// Return the summary, if any.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Return the paragraphs of the description.
+    pub fn paragraphs(&self) -> &[String] {
+        self.description
+            .as_ref()
+            .map(|d| d.paragraphs.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+struct Description {
+    #[serde(rename = "doc:para", alias = "para", default)]
+    paragraphs: Vec<String>,
+}
+
 /// A direction of an argument
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ArgDirection {
@@ -56,12 +167,18 @@ pub enum ArgDirection {
 /// An argument
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Arg<'a> {
-    #[serde(rename = "@name")]
+    #[serde(rename = "@name", skip_serializing_if = "Option::is_none")]
     name: Option<String>,
     #[serde(rename = "@type", borrow)]
     ty: CompleteType<'a>,
-    #[serde(rename = "@direction")]
+    #[serde(rename = "@direction", skip_serializing_if = "Option::is_none")]
     direction: Option<ArgDirection>,
+    #[serde(
+        rename = "doc:doc",
+        alias = "doc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -69,6 +186,45 @@ pub struct Arg<'a> {
 assert_impl_all!(Arg<'_>: Send, Sync, Unpin);
 
 impl<'a> Arg<'a> {
+    /// Create a new argument of type `ty`, without a name or a direction.
+    pub fn new(ty: CompleteType<'a>) -> Self {
+        Self {
+            name: None,
+            ty,
+            direction: None,
+            doc: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Set the argument name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+
+        self
+    }
+
+    /// Set the argument direction.
+    pub fn with_direction(mut self, direction: ArgDirection) -> Self {
+        self.direction = Some(direction);
+
+        self
+    }
+
+    /// Set the documentation.
+    pub fn with_doc(mut self, doc: Doc) -> Self {
+        self.doc = Some(doc);
+
+        self
+    }
+
+    /// Add an annotation.
+    pub fn add_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+
+        self
+    }
+
     /// Return the argument name, if any.
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
@@ -88,6 +244,27 @@ impl<'a> Arg<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Return the documentation element, if any.
+    pub fn doc(&self) -> Option<&Doc> {
+        self.doc.as_ref()
+    }
+
+    /// Return the summary of the documentation, if any.
+    ///
+    /// This is the summary of the [`Doc`] element or else, the `org.gtk.GDBus.DocString.Short`
+    /// annotation.
+    pub fn summary(&self) -> Option<&str> {
+        summary(self.doc.as_ref(), &self.annotations)
+    }
+
+    /// Return the description of the documentation, if any.
+    ///
+    /// This is the paragraphs of the [`Doc`] element, separated by empty lines, or else the
+    /// `org.gtk.GDBus.DocString` annotation.
+    pub fn description(&self) -> Option<Cow<'_, str>> {
+        description(self.doc.as_ref(), &self.annotations)
+    }
 }
 
 /// A method
@@ -97,6 +274,12 @@ pub struct Method<'a> {
     name: MemberName<'a>,
     #[serde(rename = "arg", default, borrow)]
     args: Vec<Arg<'a>>,
+    #[serde(
+        rename = "doc:doc",
+        alias = "doc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -104,6 +287,37 @@ pub struct Method<'a> {
 assert_impl_all!(Method<'_>: Send, Sync, Unpin);
 
 impl<'a> Method<'a> {
+    /// Create a new method, without arguments.
+    pub fn new(name: MemberName<'a>) -> Self {
+        Self {
+            name,
+            args: Vec::new(),
+            doc: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Add an argument.
+    pub fn add_arg(mut self, arg: Arg<'a>) -> Self {
+        self.args.push(arg);
+
+        self
+    }
+
+    /// Set the documentation.
+    pub fn with_doc(mut self, doc: Doc) -> Self {
+        self.doc = Some(doc);
+
+        self
+    }
+
+    /// Add an annotation.
+    pub fn add_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+
+        self
+    }
+
     /// Return the method name.
     pub fn name(&self) -> MemberName<'_> {
         self.name.as_ref()
@@ -118,6 +332,41 @@ impl<'a> Method<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Whether the method is deprecated.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Deprecated` annotation.
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.annotations)
+    }
+
+    /// Whether the caller shouldn't expect a reply.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Method.NoReply` annotation.
+    pub fn no_reply(&self) -> bool {
+        annotation_is_true(&self.annotations, "org.freedesktop.DBus.Method.NoReply")
+    }
+
+    /// Return the documentation element, if any.
+    pub fn doc(&self) -> Option<&Doc> {
+        self.doc.as_ref()
+    }
+
+    /// Return the summary of the documentation, if any.
+    ///
+    /// This is the summary of the [`Doc`] element or else, the `org.gtk.GDBus.DocString.Short`
+    /// annotation.
+    pub fn summary(&self) -> Option<&str> {
+        summary(self.doc.as_ref(), &self.annotations)
+    }
+
+    /// Return the description of the documentation, if any.
+    ///
+    /// This is the paragraphs of the [`Doc`] element, separated by empty lines, or else the
+    /// `org.gtk.GDBus.DocString` annotation.
+    pub fn description(&self) -> Option<Cow<'_, str>> {
+        description(self.doc.as_ref(), &self.annotations)
+    }
 }
 
 /// A signal
@@ -128,6 +377,12 @@ pub struct Signal<'a> {
 
     #[serde(rename = "arg", default)]
     args: Vec<Arg<'a>>,
+    #[serde(
+        rename = "doc:doc",
+        alias = "doc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -135,6 +390,37 @@ pub struct Signal<'a> {
 assert_impl_all!(Signal<'_>: Send, Sync, Unpin);
 
 impl<'a> Signal<'a> {
+    /// Create a new signal, without arguments.
+    pub fn new(name: MemberName<'a>) -> Self {
+        Self {
+            name,
+            args: Vec::new(),
+            doc: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Add an argument.
+    pub fn add_arg(mut self, arg: Arg<'a>) -> Self {
+        self.args.push(arg);
+
+        self
+    }
+
+    /// Set the documentation.
+    pub fn with_doc(mut self, doc: Doc) -> Self {
+        self.doc = Some(doc);
+
+        self
+    }
+
+    /// Add an annotation.
+    pub fn add_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+
+        self
+    }
+
     /// Return the signal name.
     pub fn name(&self) -> MemberName<'_> {
         self.name.as_ref()
@@ -149,6 +435,34 @@ impl<'a> Signal<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Whether the signal is deprecated.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Deprecated` annotation.
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.annotations)
+    }
+
+    /// Return the documentation element, if any.
+    pub fn doc(&self) -> Option<&Doc> {
+        self.doc.as_ref()
+    }
+
+    /// Return the summary of the documentation, if any.
+    ///
+    /// This is the summary of the [`Doc`] element or else, the `org.gtk.GDBus.DocString.Short`
+    /// annotation.
+    pub fn summary(&self) -> Option<&str> {
+        summary(self.doc.as_ref(), &self.annotations)
+    }
+
+    /// Return the description of the documentation, if any.
+    ///
+    /// This is the paragraphs of the [`Doc`] element, separated by empty lines, or else the
+    /// `org.gtk.GDBus.DocString` annotation.
+    pub fn description(&self) -> Option<Cow<'_, str>> {
+        description(self.doc.as_ref(), &self.annotations)
+    }
 }
 
 /// The possible property access types
@@ -172,6 +486,22 @@ impl PropertyAccess {
     }
 }
 
+/// Standard annotation `org.freedesktop.DBus.Property.EmitsChangedSignal`.
+///
+/// See <https://dbus.freedesktop.org/doc/dbus-specification.html#introspection-format>.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PropertyEmitsChangedSignal {
+    /// `PropertiesChanged` is emitted with the new value.
+    #[default]
+    True,
+    /// `PropertiesChanged` is emitted, without the new value.
+    Invalidates,
+    /// The property never changes.
+    Const,
+    /// `PropertiesChanged` isn't emitted.
+    False,
+}
+
 /// A property
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Property<'a> {
@@ -183,6 +513,12 @@ pub struct Property<'a> {
     #[serde(rename = "@access")]
     access: PropertyAccess,
 
+    #[serde(
+        rename = "doc:doc",
+        alias = "doc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -190,6 +526,31 @@ pub struct Property<'a> {
 assert_impl_all!(Property<'_>: Send, Sync, Unpin);
 
 impl<'a> Property<'a> {
+    /// Create a new property.
+    pub fn new(name: PropertyName<'a>, ty: CompleteType<'a>, access: PropertyAccess) -> Self {
+        Self {
+            name,
+            ty,
+            access,
+            doc: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Set the documentation.
+    pub fn with_doc(mut self, doc: Doc) -> Self {
+        self.doc = Some(doc);
+
+        self
+    }
+
+    /// Add an annotation.
+    pub fn add_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+
+        self
+    }
+
     /// Returns the property name.
     pub fn name(&self) -> PropertyName<'_> {
         self.name.as_ref()
@@ -209,6 +570,43 @@ impl<'a> Property<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Whether the property is deprecated.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Deprecated` annotation.
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.annotations)
+    }
+
+    /// How the property's changes are signaled, if the property specifies it.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Property.EmitsChangedSignal` annotation. If
+    /// the property isn't annotated, the value for its interface applies, see
+    /// [`Interface::emits_changed_signal`].
+    pub fn emits_changed_signal(&self) -> Option<PropertyEmitsChangedSignal> {
+        emits_changed_signal(&self.annotations)
+    }
+
+    /// Return the documentation element, if any.
+    pub fn doc(&self) -> Option<&Doc> {
+        self.doc.as_ref()
+    }
+
+    /// Return the summary of the documentation, if any.
+    ///
+    /// This is the summary of the [`Doc`] element or else, the `org.gtk.GDBus.DocString.Short`
+    /// annotation.
+    pub fn summary(&self) -> Option<&str> {
+        summary(self.doc.as_ref(), &self.annotations)
+    }
+
+    /// Return the description of the documentation, if any.
+    ///
+    /// This is the paragraphs of the [`Doc`] element, separated by empty lines, or else the
+    /// `org.gtk.GDBus.DocString` annotation.
+    pub fn description(&self) -> Option<Cow<'_, str>> {
+        description(self.doc.as_ref(), &self.annotations)
+    }
 }
 
 /// An interface
@@ -223,6 +621,12 @@ pub struct Interface<'a> {
     properties: Vec<Property<'a>>,
     #[serde(rename = "signal", default)]
     signals: Vec<Signal<'a>>,
+    #[serde(
+        rename = "doc:doc",
+        alias = "doc",
+        skip_serializing_if = "Option::is_none"
+    )]
+    doc: Option<Doc>,
     #[serde(rename = "annotation", default)]
     annotations: Vec<Annotation>,
 }
@@ -230,6 +634,53 @@ pub struct Interface<'a> {
 assert_impl_all!(Interface<'_>: Send, Sync, Unpin);
 
 impl<'a> Interface<'a> {
+    /// Create a new, empty interface.
+    pub fn new(name: InterfaceName<'a>) -> Self {
+        Self {
+            name,
+            methods: Vec::new(),
+            properties: Vec::new(),
+            signals: Vec::new(),
+            doc: None,
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Add a method.
+    pub fn add_method(mut self, method: Method<'a>) -> Self {
+        self.methods.push(method);
+
+        self
+    }
+
+    /// Add a property.
+    pub fn add_property(mut self, property: Property<'a>) -> Self {
+        self.properties.push(property);
+
+        self
+    }
+
+    /// Add a signal.
+    pub fn add_signal(mut self, signal: Signal<'a>) -> Self {
+        self.signals.push(signal);
+
+        self
+    }
+
+    /// Set the documentation.
+    pub fn with_doc(mut self, doc: Doc) -> Self {
+        self.doc = Some(doc);
+
+        self
+    }
+
+    /// Add an annotation.
+    pub fn add_annotation(mut self, annotation: Annotation) -> Self {
+        self.annotations.push(annotation);
+
+        self
+    }
+
     /// Returns the interface name.
     pub fn name(&self) -> InterfaceName<'_> {
         self.name.as_ref()
@@ -254,12 +705,60 @@ impl<'a> Interface<'a> {
     pub fn annotations(&self) -> &[Annotation] {
         &self.annotations
     }
+
+    /// Whether the interface is deprecated.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Deprecated` annotation.
+    pub fn is_deprecated(&self) -> bool {
+        is_deprecated(&self.annotations)
+    }
+
+    /// How the changes of the interface properties are signaled, unless they specify otherwise.
+    ///
+    /// This is the value of the `org.freedesktop.DBus.Property.EmitsChangedSignal` annotation, or
+    /// [`PropertyEmitsChangedSignal::True`] if the interface isn't annotated.
+    pub fn emits_changed_signal(&self) -> PropertyEmitsChangedSignal {
+        emits_changed_signal(&self.annotations).unwrap_or_default()
+    }
+
+    /// How the changes of `property` are signaled.
+    ///
+    /// This is [`Property::emits_changed_signal`], falling back to the value for the interface.
+    pub fn property_emits_changed_signal(
+        &self,
+        property: &Property<'_>,
+    ) -> PropertyEmitsChangedSignal {
+        property
+            .emits_changed_signal()
+            .unwrap_or_else(|| self.emits_changed_signal())
+    }
+
+    /// Return the documentation element, if any.
+    pub fn doc(&self) -> Option<&Doc> {
+        self.doc.as_ref()
+    }
+
+    /// Return the summary of the documentation, if any.
+    ///
+    /// This is the summary of the [`Doc`] element or else, the `org.gtk.GDBus.DocString.Short`
+    /// annotation.
+    pub fn summary(&self) -> Option<&str> {
+        summary(self.doc.as_ref(), &self.annotations)
+    }
+
+    /// Return the description of the documentation, if any.
+    ///
+    /// This is the paragraphs of the [`Doc`] element, separated by empty lines, or else the
+    /// `org.gtk.GDBus.DocString` annotation.
+    pub fn description(&self) -> Option<Cow<'_, str>> {
+        description(self.doc.as_ref(), &self.annotations)
+    }
 }
 
 /// An introspection tree node (typically the root of the XML document).
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Node<'a> {
-    #[serde(rename = "@name")]
+    #[serde(rename = "@name", skip_serializing_if = "Option::is_none")]
     name: Option<String>,
 
     #[serde(rename = "interface", default, borrow)]
@@ -271,32 +770,131 @@ pub struct Node<'a> {
 assert_impl_all!(Node<'_>: Send, Sync, Unpin);
 
 impl<'a> Node<'a> {
+    /// Create a new node, without a name, interfaces or children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_names::{InterfaceName, MemberName};
+    /// use zbus_xml::{Arg, ArgDirection, Interface, Method, Node};
+    /// use zvariant::{CompleteType, Signature};
+    ///
+    /// let ty = CompleteType::try_from(Signature::from_static_str("s").unwrap()).unwrap();
+    /// let method = Method::new(MemberName::try_from("Hello").unwrap()).add_arg(
+    ///     Arg::new(ty)
+    ///         .with_name("greeting")
+    ///         .with_direction(ArgDirection::Out),
+    /// );
+    /// let node = Node::new()
+    ///     .with_name("/org/example/Greeter")
+    ///     .add_interface(
+    ///         Interface::new(InterfaceName::try_from("org.example.Greeter").unwrap())
+    ///             .add_method(method),
+    ///     )
+    ///     .add_node(Node::new().with_name("child"));
+    ///
+    /// let xml = node.to_string();
+    /// assert!(xml.contains(r#"<arg name="greeting" type="s" direction="out"/>"#));
+    /// assert_eq!(Node::try_from(xml.as_str()).unwrap(), node);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            interfaces: Vec::new(),
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Set the node name.
+    ///
+    /// The root node of a document is usually named after the object path, if at all, while child
+    /// nodes are named with a path relative to their parent.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+
+        self
+    }
+
+    /// Add an interface.
+    pub fn add_interface(mut self, interface: Interface<'a>) -> Self {
+        self.interfaces.push(interface);
+
+        self
+    }
+
+    /// Add a child node.
+    pub fn add_node(mut self, node: Node<'a>) -> Self {
+        self.nodes.push(node);
+
+        self
+    }
+
     /// Parse the introspection XML document from reader.
+    ///
+    /// Unknown elements and attributes are ignored. Use [`ParseOptions`] to reject them, or to get
+    /// warnings about them.
     pub fn from_reader<R: Read>(reader: R) -> Result<Node<'a>> {
-        let mut deserializer = Deserializer::from_reader(BufReader::new(reader));
+        Self::from_str_owned(&parse::read_to_string(reader)?)
+    }
+
+    // Parse the document from `s`, without borrowing from it.
+    pub(crate) fn from_str_owned(s: &str) -> Result<Node<'a>> {
+        let mut deserializer = Deserializer::from_reader(s.as_bytes());
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        Node::deserialize(&mut deserializer).map_err(|e| parse::locate_error(s, e))
     }
 
     /// Write the XML document to writer.
+    ///
+    /// The document starts with the introspection format DOCTYPE and its elements are indented.
+    /// Its [`Display`](fmt::Display) implementation writes the same document to a string.
     pub fn to_writer<W: Write>(&self, writer: W) -> Result<()> {
         // Need this wrapper until this is resolved: https://github.com/tafia/quick-xml/issues/499
         struct Writer<T>(T);
 
-        impl<T> std::fmt::Write for Writer<T>
+        impl<T> fmt::Write for Writer<T>
         where
             T: Write,
         {
-            fn write_str(&mut self, s: &str) -> std::fmt::Result {
-                self.0.write_all(s.as_bytes()).map_err(|_| std::fmt::Error)
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.write_all(s.as_bytes()).map_err(|_| fmt::Error)
             }
         }
 
-        to_writer(Writer(writer), &self)?;
+        self.write_xml(Writer(writer))
+    }
+
+    fn write_xml<W: fmt::Write>(&self, mut writer: W) -> Result<()> {
+        writer.write_str(DOCTYPE).map_err(DeError::from)?;
+        let mut serializer = Serializer::with_root(&mut writer, Some("node"))?;
+        serializer.indent(' ', 2);
+        // The root element declares the `doc` namespace prefix, if it's used.
+        Root {
+            name: self.name.as_deref(),
+            doc_namespace: self.has_doc().then_some(DOC_NAMESPACE),
+            interfaces: &self.interfaces,
+            nodes: &self.nodes,
+        }
+        .serialize(serializer)?;
+        writer.write_char('\n').map_err(DeError::from)?;
 
         Ok(())
     }
 
+    fn has_doc(&self) -> bool {
+        self.nodes.iter().any(Node::has_doc)
+            || self.interfaces.iter().any(|i| {
+                i.doc.is_some()
+                    || i.methods
+                        .iter()
+                        .any(|m| m.doc.is_some() || m.args.iter().any(|a| a.doc.is_some()))
+                    || i.signals
+                        .iter()
+                        .any(|s| s.doc.is_some() || s.args.iter().any(|a| a.doc.is_some()))
+                    || i.properties.iter().any(|p| p.doc.is_some())
+            })
+    }
+
     /// Returns the node name, if any.
     pub fn name(&self) -> Option<&str> {
         self.name.as_deref()
@@ -311,15 +909,170 @@ impl<'a> Node<'a> {
     pub fn interfaces(&self) -> &[Interface<'a>] {
         &self.interfaces
     }
+
+    /// Returns the absolute path of this node, as a child of the node at `parent`.
+    ///
+    /// Child nodes are usually named with a path relative to their parent, which is appended to
+    /// `parent`. Absolute names are returned as is, and nodes without a name are at `parent`.
+    pub fn resolve_path(&self, parent: &ObjectPath<'_>) -> Result<OwnedObjectPath> {
+        let path = match self.name.as_deref() {
+            None => return Ok(parent.to_owned().into()),
+            Some(name) if name.starts_with('/') => name.to_string(),
+            Some(name) => match parent.as_str() {
+                "/" => format!("/{name}"),
+                parent => format!("{parent}/{name}"),
+            },
+        };
+
+        Ok(ObjectPath::try_from(path)?.into())
+    }
+
+    /// Iterate over this node and all its descendants, with their absolute paths.
+    ///
+    /// `path` is the object path this node was introspected at, regardless of the node's name. The
+    /// nodes are visited depth-first, parents before their children. If the name of a child node
+    /// doesn't resolve to a valid object path, an error is returned in its place and its children
+    /// are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zbus_xml::Node;
+    /// use zvariant::ObjectPath;
+    ///
+    /// let node = Node::try_from(
+    ///     r#"<node name="/org/example">
+    ///          <node name="first"><node name="nested"/></node>
+    ///          <node name="second"/>
+    ///        </node>"#,
+    /// )
+    /// .unwrap();
+    /// let paths = node
+    ///     .walk(ObjectPath::from_static_str_unchecked("/org/example"))
+    ///     .map(|n| n.map(|(path, _)| path.to_string()))
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     paths,
+    ///     [
+    ///         "/org/example",
+    ///         "/org/example/first",
+    ///         "/org/example/first/nested",
+    ///         "/org/example/second",
+    ///     ],
+    /// );
+    /// ```
+    pub fn walk(&self, path: ObjectPath<'_>) -> Walk<'_, 'a> {
+        Walk {
+            stack: vec![Ok((path.into(), self))],
+        }
+    }
+}
+
+/// An iterator over a [`Node`] tree, with the absolute paths of the nodes.
+///
+/// This is created by [`Node::walk`].
+#[derive(Debug)]
+pub struct Walk<'n, 'a> {
+    stack: Vec<Result<(OwnedObjectPath, &'n Node<'a>)>>,
+}
+
+impl<'n, 'a> Iterator for Walk<'n, 'a> {
+    type Item = Result<(OwnedObjectPath, &'n Node<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, node) = match self.stack.pop()? {
+            Ok(next) => next,
+            Err(e) => return Some(Err(e)),
+        };
+        let children = node.nodes.iter().rev().map(|child| {
+            child
+                .resolve_path(&path)
+                .map(|child_path| (child_path, child))
+        });
+        self.stack.extend(children);
+
+        Some(Ok((path, node)))
+    }
+}
+
+impl Default for Node<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Node<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_xml(f).map_err(|_| fmt::Error)
+    }
 }
 
 impl<'a> TryFrom<&'a str> for Node<'a> {
     type Error = Error;
 
     /// Parse the introspection XML document from `s`.
+    ///
+    /// Unknown elements and attributes are ignored. Use [`ParseOptions`] to reject them, or to get
+    /// warnings about them.
     fn try_from(s: &'a str) -> Result<Node<'a>> {
         let mut deserializer = Deserializer::from_str(s);
         deserializer.event_buffer_size(Some(1024_usize.try_into().unwrap()));
-        Ok(Node::deserialize(&mut deserializer)?)
+        Node::deserialize(&mut deserializer).map_err(|e| parse::locate_error(s, e))
+    }
+}
+
+#[derive(Serialize)]
+struct Root<'n, 'a> {
+    #[serde(rename = "@name", skip_serializing_if = "Option::is_none")]
+    name: Option<&'n str>,
+    #[serde(rename = "@xmlns:doc", skip_serializing_if = "Option::is_none")]
+    doc_namespace: Option<&'static str>,
+
+    #[serde(rename = "interface")]
+    interfaces: &'n [Interface<'a>],
+    #[serde(rename = "node")]
+    nodes: &'n [Node<'a>],
+}
+
+fn summary<'s>(doc: Option<&'s Doc>, annotations: &'s [Annotation]) -> Option<&'s str> {
+    doc.and_then(Doc::summary)
+        .or_else(|| annotation_value(annotations, "org.gtk.GDBus.DocString.Short"))
+}
+
+fn description<'s>(doc: Option<&'s Doc>, annotations: &'s [Annotation]) -> Option<Cow<'s, str>> {
+    match doc.map(Doc::paragraphs) {
+        Some(paragraphs) if !paragraphs.is_empty() => Some(paragraphs.join("\n\n").into()),
+        _ => annotation_value(annotations, "org.gtk.GDBus.DocString").map(Cow::Borrowed),
+    }
+}
+
+fn annotation_value<'s>(annotations: &'s [Annotation], name: &str) -> Option<&'s str> {
+    annotations
+        .iter()
+        .find(|a| a.name == name)
+        .map(|a| a.value.as_str())
+}
+
+fn annotation_is_true(annotations: &[Annotation], name: &str) -> bool {
+    annotation_value(annotations, name) == Some("true")
+}
+
+fn is_deprecated(annotations: &[Annotation]) -> bool {
+    annotation_is_true(annotations, "org.freedesktop.DBus.Deprecated")
+}
+
+fn emits_changed_signal(annotations: &[Annotation]) -> Option<PropertyEmitsChangedSignal> {
+    use PropertyEmitsChangedSignal::*;
+
+    match annotation_value(
+        annotations,
+        "org.freedesktop.DBus.Property.EmitsChangedSignal",
+    )? {
+        "true" => Some(True),
+        "invalidates" => Some(Invalidates),
+        "const" => Some(Const),
+        "false" => Some(False),
+        _ => None,
     }
 }
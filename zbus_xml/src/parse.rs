@@ -0,0 +1,431 @@
+use quick_xml::{
+    de::DeError,
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::{fmt, io::Read};
+
+use zbus_names::{InterfaceName, MemberName, PropertyName};
+use zvariant::{CompleteType, Signature};
+
+use crate::{Error, Node, Result};
+
+/// Options to parse introspection documents.
+///
+/// Unlike [`Node::from_reader`] and `Node::try_from`, which silently ignore the elements and
+/// attributes they don't know about, parsing with these options is strict by default: unknown
+/// elements, attributes and text are errors. In the lenient mode, they're skipped instead, and
+/// reported as warnings.
+///
+/// Either way, errors in the document are reported as [`Error::Parse`], with their location.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_xml::{Error, ParseOptions};
+///
+/// let xml = r#"<node>
+///   <interface name="org.example.Sample">
+///     <method name="Frobate" vendor:flags="fast"/>
+///   </interface>
+/// </node>"#;
+///
+/// let Err(Error::Parse(error)) = ParseOptions::new().parse(xml) else {
+///     panic!("expected a parse error");
+/// };
+/// assert_eq!((error.line(), error.column()), (3, 5));
+/// assert_eq!(error.message(), "unknown attribute `vendor:flags`");
+///
+/// let (node, warnings) = ParseOptions::new().with_lenient(true).parse(xml).unwrap();
+/// assert_eq!(node.interfaces()[0].methods().len(), 1);
+/// assert_eq!(
+///     warnings[0].to_string(),
+///     "line 3, column 5, in `interface org.example.Sample` > `method Frobate`: \
+///      unknown attribute `vendor:flags`",
+/// );
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    lenient: bool,
+}
+
+impl ParseOptions {
+    /// Create options for strict parsing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether unknown elements, attributes and text are skipped with a warning, instead of
+    /// being errors.
+    pub fn with_lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+
+        self
+    }
+
+    /// Whether unknown elements, attributes and text are skipped with a warning.
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Parse the introspection XML document from `s`, returning the warnings along with it.
+    pub fn parse<'a>(&self, s: &'a str) -> Result<(Node<'a>, Vec<Diagnostic>)> {
+        let warnings = self.validate(s)?;
+
+        Ok((Node::try_from(s)?, warnings))
+    }
+
+    /// Parse the introspection XML document from reader, returning the warnings along with it.
+    pub fn parse_reader<R: Read>(&self, reader: R) -> Result<(Node<'static>, Vec<Diagnostic>)> {
+        let s = read_to_string(reader)?;
+        let warnings = self.validate(&s)?;
+
+        Ok((Node::from_str_owned(&s)?, warnings))
+    }
+
+    fn validate(&self, s: &str) -> Result<Vec<Diagnostic>> {
+        let unknown = if self.lenient {
+            Unknown::Warn
+        } else {
+            Unknown::Fail
+        };
+
+        Validator::new(s, unknown).validate()
+    }
+}
+
+/// An error or a warning about a document, with its location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    line: usize,
+    column: usize,
+    context: Vec<String>,
+    message: String,
+}
+
+impl Diagnostic {
+    pub(crate) fn new(line: usize, column: usize, message: String) -> Self {
+        Self {
+            line,
+            column,
+            context: Vec::new(),
+            message,
+        }
+    }
+
+    /// The line of the element the diagnostic is about, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column of the element the diagnostic is about, in characters and starting at 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The ancestors of the element the diagnostic is about, from the outermost one.
+    ///
+    /// Elements are described by their name, followed by their `name` attribute if they have
+    /// one, e.g. `method Frobate`. The root node isn't included.
+    pub fn context(&self) -> &[String] {
+        &self.context
+    }
+
+    /// The description of the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)?;
+        for (i, element) in self.context.iter().enumerate() {
+            let separator = if i == 0 { ", in" } else { " >" };
+            write!(f, "{separator} `{element}`")?;
+        }
+
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Locate the error `e`, returned by the deserializer for `s`.
+///
+/// The deserializer doesn't report where errors are, so this looks for the first error in the
+/// document. If there's none to be found, `e` is returned as is.
+pub(crate) fn locate_error(s: &str, e: DeError) -> Error {
+    match Validator::new(s, Unknown::Ignore).validate() {
+        Err(located) => located,
+        Ok(_) => Error::QuickXml(e),
+    }
+}
+
+pub(crate) fn read_to_string<R: Read>(mut reader: R) -> Result<String> {
+    let mut s = String::new();
+    reader
+        .read_to_string(&mut s)
+        .map_err(|e| DeError::from(quick_xml::Error::from(e)))?;
+
+    Ok(s)
+}
+
+// What to do about unknown elements, attributes and text.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Unknown {
+    Ignore,
+    Warn,
+    Fail,
+}
+
+struct Validator<'s> {
+    input: &'s str,
+    reader: Reader<&'s [u8]>,
+    unknown: Unknown,
+    warnings: Vec<Diagnostic>,
+    // The names of the open elements, and their descriptions for the diagnostics.
+    open: Vec<(&'s str, String)>,
+}
+
+impl<'s> Validator<'s> {
+    fn new(input: &'s str, unknown: Unknown) -> Self {
+        let mut reader = Reader::from_str(input);
+        reader.trim_text(true);
+
+        Self {
+            input,
+            reader,
+            unknown,
+            warnings: Vec::new(),
+            open: Vec::new(),
+        }
+    }
+
+    fn validate(mut self) -> Result<Vec<Diagnostic>> {
+        let mut has_root = false;
+        loop {
+            let event = self
+                .reader
+                .read_event()
+                .map_err(|e| self.error(self.reader.buffer_position(), e.to_string()))?;
+            let (start, is_empty) = match event {
+                Event::Start(start) => (start, false),
+                Event::Empty(start) => (start, true),
+                Event::End(_) => {
+                    self.open.pop();
+                    continue;
+                }
+                Event::Text(text) => {
+                    // The text is trimmed, so skip the trailing whitespace to find its start.
+                    let end = self.input[..self.reader.buffer_position()].trim_end().len();
+                    let offset = end - text.len();
+                    self.unknown(offset, "unexpected text".to_string())?;
+                    continue;
+                }
+                Event::CData(cdata) => {
+                    // `<![CDATA[` and `]]>` aren't part of the contents.
+                    let offset = self.reader.buffer_position() - cdata.len() - 12;
+                    self.unknown(offset, "unexpected text".to_string())?;
+                    continue;
+                }
+                Event::Eof => break,
+                Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => continue,
+            };
+            // The offset of the `<` of the tag, which spans the name and attributes, `<` and `>`
+            // or `/>`.
+            let tag_len = start.len() + if is_empty { 3 } else { 2 };
+            let offset = self.reader.buffer_position() - tag_len;
+            // Borrow the name from the input, so it can outlive `start`.
+            let name = &self.input[offset + 1..offset + 1 + start.name().as_ref().len()];
+
+            let parent = self.open.last().map(|(parent, _)| *parent);
+            let known = match parent {
+                None if has_root => false,
+                None => {
+                    if name != "node" {
+                        let message = format!("expected a root `node`, found `{name}`");
+
+                        return Err(self.error(offset, message));
+                    }
+                    has_root = true;
+
+                    true
+                }
+                Some(parent) => children(parent).contains(&name),
+            };
+            if !known {
+                self.unknown(offset, format!("unknown element `{name}`"))?;
+                self.skip(&start, is_empty, offset)?;
+                continue;
+            }
+            if is_doc(name) {
+                // The contents of documentation elements are free-form.
+                self.skip(&start, is_empty, offset)?;
+                continue;
+            }
+
+            // The element is part of the context of the errors about its attributes.
+            self.open.push((name, describe(&start, name)));
+            self.check_attributes(&start, name, offset)?;
+            if is_empty {
+                self.open.pop();
+            }
+        }
+
+        Ok(self.warnings)
+    }
+
+    // Check the attributes of the `name` element.
+    fn check_attributes(
+        &mut self,
+        start: &BytesStart<'_>,
+        name: &str,
+        offset: usize,
+    ) -> Result<()> {
+        let (required, optional) = attributes(name);
+        let mut found = Vec::new();
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(|e| self.error(offset, e.to_string()))?;
+            let key = decode(attribute.key.into_inner());
+            if key == "xmlns" || key.starts_with("xmlns:") {
+                continue;
+            }
+            if !required.contains(&key) && !optional.contains(&key) {
+                self.unknown(offset, format!("unknown attribute `{key}`"))?;
+                continue;
+            }
+            let value = attribute
+                .unescape_value()
+                .map_err(|e| self.error(offset, e.to_string()))?;
+            check_value(name, key, &value).map_err(|e| {
+                self.error(offset, format!("invalid `{key}` attribute `{value}`: {e}"))
+            })?;
+            found.push(key);
+        }
+
+        match required.iter().find(|key| !found.contains(key)) {
+            Some(key) => Err(self.error(offset, format!("missing `{key}` attribute"))),
+            None => Ok(()),
+        }
+    }
+
+    // Skip the contents of the element that starts with `start`.
+    fn skip(&mut self, start: &BytesStart<'s>, is_empty: bool, offset: usize) -> Result<()> {
+        if is_empty {
+            return Ok(());
+        }
+
+        self.reader
+            .read_to_end(start.name())
+            .map(|_| ())
+            .map_err(|e| self.error(offset, e.to_string()))
+    }
+
+    fn unknown(&mut self, offset: usize, message: String) -> Result<()> {
+        match self.unknown {
+            Unknown::Ignore => Ok(()),
+            Unknown::Warn => {
+                let warning = self.diagnostic(offset, message);
+                self.warnings.push(warning);
+
+                Ok(())
+            }
+            Unknown::Fail => Err(self.error(offset, message)),
+        }
+    }
+
+    fn error(&self, offset: usize, message: String) -> Error {
+        Error::Parse(self.diagnostic(offset, message))
+    }
+
+    fn diagnostic(&self, offset: usize, message: String) -> Diagnostic {
+        let mut offset = offset.min(self.input.len());
+        while !self.input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Diagnostic {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            // The root node isn't part of the context.
+            context: self.open.iter().skip(1).map(|(_, d)| d.clone()).collect(),
+            message,
+        }
+    }
+}
+
+fn decode(bytes: &[u8]) -> &str {
+    // The input is a `str` and names are split on ASCII characters, so this can't fail.
+    std::str::from_utf8(bytes).unwrap_or_default()
+}
+
+// The elements allowed in `parent`.
+fn children(parent: &str) -> &'static [&'static str] {
+    match parent {
+        "node" => &["node", "interface"],
+        "interface" => &[
+            "method",
+            "signal",
+            "property",
+            "annotation",
+            "doc:doc",
+            "doc",
+        ],
+        "method" | "signal" => &["arg", "annotation", "doc:doc", "doc"],
+        "property" | "arg" => &["annotation", "doc:doc", "doc"],
+        _ => &[],
+    }
+}
+
+// Describe the `name` element for the diagnostics: its name, followed by its `name` attribute.
+fn describe(start: &BytesStart<'_>, name: &str) -> String {
+    match start
+        .try_get_attribute("name")
+        .ok()
+        .flatten()
+        .and_then(|a| a.unescape_value().ok())
+    {
+        Some(value) => format!("{name} {value}"),
+        None => name.to_string(),
+    }
+}
+
+fn is_doc(name: &str) -> bool {
+    name == "doc:doc" || name == "doc"
+}
+
+// The required and optional attributes of the `element` element.
+fn attributes(element: &str) -> (&'static [&'static str], &'static [&'static str]) {
+    match element {
+        "node" => (&[], &["name"]),
+        "interface" | "method" | "signal" => (&["name"], &[]),
+        "property" => (&["name", "type", "access"], &[]),
+        "arg" => (&["type"], &["name", "direction"]),
+        "annotation" => (&["name", "value"], &[]),
+        _ => (&[], &[]),
+    }
+}
+
+fn check_value(element: &str, key: &str, value: &str) -> std::result::Result<(), String> {
+    let result = match (element, key) {
+        ("interface", "name") => InterfaceName::try_from(value).map(drop),
+        ("method" | "signal", "name") => MemberName::try_from(value).map(drop),
+        ("property", "name") => PropertyName::try_from(value).map(drop),
+        (_, "type") => {
+            return Signature::try_from(value)
+                .and_then(CompleteType::try_from)
+                .map(drop)
+                .map_err(|e| e.to_string())
+        }
+        (_, "access") if !["read", "write", "readwrite"].contains(&value) => {
+            return Err("expected `read`, `write` or `readwrite`".to_string())
+        }
+        (_, "direction") if !["in", "out"].contains(&value) => {
+            return Err("expected `in` or `out`".to_string())
+        }
+        _ => Ok(()),
+    };
+
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,172 @@
+//! Service description files.
+//!
+//! These are the `.service` files, such as the ones in `/usr/share/dbus-1/services` and
+//! `/usr/share/dbus-1/system-services`, telling the message bus how to activate a service when its
+//! name is requested. See the [D-Bus specification] for their format.
+//!
+//! [D-Bus specification]: https://dbus.freedesktop.org/doc/dbus-specification.html#message-bus-starting-services
+
+use static_assertions::assert_impl_all;
+use std::io::Read;
+
+use zbus_names::WellKnownName;
+
+use crate::{parse::read_to_string, Diagnostic, Error, Result};
+
+const GROUP: &str = "D-BUS Service";
+
+/// A service description file.
+///
+/// Only the `[D-BUS Service]` group is read. Other groups, comments and the keys this doesn't know
+/// about are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use zbus_xml::service::ServiceFile;
+///
+/// let service = ServiceFile::try_from(
+///     "# Activated by systemd\n\
+///      [D-BUS Service]\n\
+///      Name=org.freedesktop.hostname1\n\
+///      Exec=/bin/false\n\
+///      User=root\n\
+///      SystemdService=dbus-org.freedesktop.hostname1.service\n",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(service.name().as_str(), "org.freedesktop.hostname1");
+/// assert_eq!(service.exec(), "/bin/false");
+/// assert_eq!(service.user(), Some("root"));
+/// assert_eq!(
+///     service.systemd_service(),
+///     Some("dbus-org.freedesktop.hostname1.service"),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceFile {
+    name: WellKnownName<'static>,
+    exec: String,
+    user: Option<String>,
+    systemd_service: Option<String>,
+    assumed_apparmor_label: Option<String>,
+}
+
+assert_impl_all!(ServiceFile: Send, Sync, Unpin);
+
+impl ServiceFile {
+    /// Parse the service file from reader.
+    pub fn from_reader<R: Read>(reader: R) -> Result<Self> {
+        Self::try_from(read_to_string(reader)?.as_str())
+    }
+
+    /// The well-known name the service is activated for, the `Name` key.
+    pub fn name(&self) -> &WellKnownName<'static> {
+        &self.name
+    }
+
+    /// The command line launching the service, the `Exec` key.
+    pub fn exec(&self) -> &str {
+        &self.exec
+    }
+
+    /// The user the service runs as, the `User` key.
+    ///
+    /// This is required for the services of the system bus.
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+
+    /// The systemd unit activating the service instead, the `SystemdService` key.
+    pub fn systemd_service(&self) -> Option<&str> {
+        self.systemd_service.as_deref()
+    }
+
+    /// The AppArmor label the service is assumed to run under, the `AssumedAppArmorLabel` key.
+    pub fn assumed_apparmor_label(&self) -> Option<&str> {
+        self.assumed_apparmor_label.as_deref()
+    }
+}
+
+impl TryFrom<&str> for ServiceFile {
+    type Error = Error;
+
+    /// Parse the service file from `s`.
+    fn try_from(s: &str) -> Result<Self> {
+        let error = |line: usize, column: usize, message: String| {
+            Error::Parse(Diagnostic::new(line + 1, column + 1, message))
+        };
+        let mut in_group = false;
+        let mut found_group = false;
+        let mut name = None;
+        let mut exec = None;
+        let mut user = None;
+        let mut systemd_service = None;
+        let mut assumed_apparmor_label = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let indent = line.len() - line.trim_start().len();
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let Some(group) = header.strip_suffix(']') else {
+                    return Err(error(i, indent, "unterminated group header".to_string()));
+                };
+                in_group = group == GROUP;
+                if in_group {
+                    if found_group {
+                        return Err(error(i, indent, format!("duplicate group `{GROUP}`")));
+                    }
+                    found_group = true;
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(error(i, indent, "expected a `Key=Value` pair".to_string()));
+            };
+            if !in_group {
+                continue;
+            }
+            let (key, value) = (key.trim_end(), value.trim_start());
+            let field = match key {
+                "Name" => {
+                    let value = WellKnownName::try_from(value.to_string())
+                        .map_err(|e| error(i, indent, format!("invalid `Name`: {e}")))?;
+                    if name.replace(value).is_some() {
+                        return Err(error(i, indent, "duplicate key `Name`".to_string()));
+                    }
+                    continue;
+                }
+                "Exec" => &mut exec,
+                "User" => &mut user,
+                "SystemdService" => &mut systemd_service,
+                "AssumedAppArmorLabel" => &mut assumed_apparmor_label,
+                _ => continue,
+            };
+            if field.replace(value.to_string()).is_some() {
+                return Err(error(i, indent, format!("duplicate key `{key}`")));
+            }
+        }
+
+        let end = s.lines().count().max(1) - 1;
+        if !found_group {
+            return Err(error(end, 0, format!("missing group `{GROUP}`")));
+        }
+        let Some(name) = name else {
+            return Err(error(end, 0, "missing key `Name`".to_string()));
+        };
+        let Some(exec) = exec else {
+            return Err(error(end, 0, "missing key `Exec`".to_string()));
+        };
+
+        Ok(Self {
+            name,
+            exec,
+            user,
+            systemd_service,
+            assumed_apparmor_label,
+        })
+    }
+}
@@ -1,7 +1,13 @@
-use quick_xml::de::DeError;
 use std::error::Error;
 
-use zbus_xml::{ArgDirection, Node};
+use zbus_names::{InterfaceName, MemberName, PropertyName};
+use zbus_xml::{
+    busconfig::{BusConfig, MessageType, PolicyContext, Rule},
+    service::ServiceFile,
+    Annotation, Arg, ArgDirection, ChangeKind, Diff, Doc, Interface, Member, Method, Node,
+    ParseOptions, Property, PropertyAccess, PropertyEmitsChangedSignal, Signal,
+};
+use zvariant::{CompleteType, ObjectPath, Signature};
 
 #[test]
 fn serde() -> Result<(), Box<dyn Error>> {
@@ -25,14 +31,556 @@ fn serde() -> Result<(), Box<dyn Error>> {
 
     let mut writer = Vec::with_capacity(128);
     node.to_writer(&mut writer).unwrap();
+    let written = String::from_utf8(writer)?;
+    assert!(written.starts_with("<!DOCTYPE node PUBLIC"));
+    assert_eq!(Node::try_from(written.as_str())?, node);
+    assert_eq!(node.to_string(), written);
+    Ok(())
+}
+
+#[test]
+fn build() -> Result<(), Box<dyn Error>> {
+    let interface = Interface::new(InterfaceName::try_from("org.example.Sample")?)
+        .add_method(
+            Method::new(MemberName::try_from("Frobate")?)
+                .add_arg(
+                    Arg::new(ty("i")?)
+                        .with_name("foo")
+                        .with_direction(ArgDirection::In),
+                )
+                .add_arg(Arg::new(ty("a{us}")?).with_direction(ArgDirection::Out))
+                .add_annotation(Annotation::new("org.freedesktop.DBus.Deprecated", "true")),
+        )
+        .add_signal(
+            Signal::new(MemberName::try_from("Changed")?)
+                .add_arg(Arg::new(ty("b")?).with_name("new_value")),
+        )
+        .add_property(Property::new(
+            PropertyName::try_from("Bar")?,
+            ty("y")?,
+            PropertyAccess::ReadWrite,
+        ));
+    let node = Node::new()
+        .add_interface(interface)
+        .add_node(Node::new().with_name("child"));
+
+    let xml = node.to_string();
+    assert_eq!(
+        xml,
+        r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+  <interface name="org.example.Sample">
+    <method name="Frobate">
+      <arg name="foo" type="i" direction="in"/>
+      <arg type="a{us}" direction="out"/>
+      <annotation name="org.freedesktop.DBus.Deprecated" value="true"/>
+    </method>
+    <property name="Bar" type="y" access="readwrite"/>
+    <signal name="Changed">
+      <arg name="new_value" type="b"/>
+    </signal>
+  </interface>
+  <node name="child"/>
+</node>
+"#
+    );
+    assert_eq!(Node::try_from(xml.as_str())?, node);
+
+    Ok(())
+}
+
+#[test]
+fn doc() -> Result<(), Box<dyn Error>> {
+    let example = include_str!("data/sample_doc.xml");
+    let node = Node::try_from(example)?;
+    let interface = &node.interfaces()[0];
+    assert_eq!(interface.summary(), Some("A documented interface"));
+    assert_eq!(
+        interface.description().as_deref(),
+        Some("The first paragraph.\n\nThe second paragraph.")
+    );
+    assert_eq!(
+        interface.doc().unwrap().paragraphs(),
+        ["The first paragraph.", "The second paragraph."]
+    );
+
+    // Falls back to the annotations.
+    let method = &interface.methods()[0];
+    assert!(method.doc().is_none());
+    assert_eq!(method.summary(), Some("Frobate things"));
+    assert_eq!(
+        method.description().as_deref(),
+        Some("Frobate the things, thoroughly.")
+    );
+    assert_eq!(method.args()[0].summary(), Some("The thing to frobate"));
+    assert_eq!(method.args()[0].description(), None);
+
+    // Unprefixed elements.
+    let signal = &interface.signals()[0];
+    assert_eq!(signal.summary(), Some("Emitted on changes"));
+    assert_eq!(signal.args()[0].doc(), None);
+
+    let property = &interface.properties()[0];
+    assert_eq!(property.summary(), None);
+    assert_eq!(property.description().as_deref(), Some("The bar."));
+
+    let xml = node.to_string();
+    assert!(xml.contains(r#"<node name="/com/example/sample_doc" xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">"#));
+    assert!(xml.contains("<doc:summary>The thing to frobate</doc:summary>"));
+    assert_eq!(Node::try_from(xml.as_str())?, node);
+
+    let built = Node::new().add_interface(
+        Interface::new(InterfaceName::try_from("org.example.Sample")?).with_doc(
+            Doc::new()
+                .with_summary("Summary")
+                .add_paragraph("Paragraph"),
+        ),
+    );
+    let xml = built.to_string();
+    assert!(xml.contains(r#"<node xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">"#));
+    assert_eq!(Node::try_from(xml.as_str())?, built);
+    // No namespace declaration without documentation.
+    assert!(!Node::new().to_string().contains("xmlns:doc"));
+
+    Ok(())
+}
+
+#[test]
+fn standard_annotations() -> Result<(), Box<dyn Error>> {
+    let example = include_str!("data/sample_object0.xml");
+    let node = Node::try_from(example)?;
+    let interface = &node.interfaces()[0];
+    assert!(!interface.is_deprecated());
+    assert!(interface.methods()[0].is_deprecated());
+    assert!(!interface.methods()[1].is_deprecated());
+    assert!(!interface.methods()[0].no_reply());
+    assert!(!interface.signals()[0].is_deprecated());
+    let property = &interface.properties()[0];
+    assert_eq!(property.emits_changed_signal(), None);
+    assert_eq!(
+        interface.property_emits_changed_signal(property),
+        PropertyEmitsChangedSignal::True
+    );
+
+    let emits_changed_signal =
+        |value| Annotation::new("org.freedesktop.DBus.Property.EmitsChangedSignal", value);
+    let property = |name, value| -> Result<Property<'static>, Box<dyn Error>> {
+        Ok(Property::new(
+            PropertyName::try_from(name)?,
+            ty("s")?,
+            PropertyAccess::Read,
+        )
+        .add_annotation(emits_changed_signal(value)))
+    };
+    let interface =
+        Interface::new(InterfaceName::try_from("org.example.Sample")?)
+            .add_annotation(emits_changed_signal("invalidates"))
+            .add_annotation(Annotation::new("org.freedesktop.DBus.Deprecated", "true"))
+            .add_method(Method::new(MemberName::try_from("Notify")?).add_annotation(
+                Annotation::new("org.freedesktop.DBus.Method.NoReply", "true"),
+            ))
+            .add_property(property("Const", "const")?)
+            .add_property(property("Invalid", "sometimes")?);
+    assert!(interface.is_deprecated());
+    assert!(interface.methods()[0].no_reply());
+    assert_eq!(
+        interface.emits_changed_signal(),
+        PropertyEmitsChangedSignal::Invalidates
+    );
+    let [constant, invalid] = interface.properties() else {
+        panic!("expected 2 properties");
+    };
+    assert_eq!(
+        constant.emits_changed_signal(),
+        Some(PropertyEmitsChangedSignal::Const)
+    );
+    assert_eq!(
+        interface.property_emits_changed_signal(invalid),
+        PropertyEmitsChangedSignal::Invalidates
+    );
+
+    Ok(())
+}
+
+#[test]
+fn diff() -> Result<(), Box<dyn Error>> {
+    let old = Node::try_from(include_str!("data/sample_object0.xml"))?;
+    assert!(Diff::new(&old, &old).is_empty());
+
+    let new = Node::try_from(
+        r#"<node name="/com/example/sample_object0">
+             <interface name="com.example.SampleInterface0">
+               <method name="Frobate">
+                 <arg name="renamed" type="i"/>
+                 <arg name="bar" type="s" direction="out"/>
+                 <arg name="baz" type="a{us}" direction="out"/>
+               </method>
+               <method name="Bazify">
+                 <arg name="bar" type="(iiu)" direction="in"/>
+                 <arg name="bar" type="v" direction="out"/>
+                 <arg name="extra" type="s" direction="out"/>
+               </method>
+               <method name="Mogrify">
+                 <arg name="bar" type="(iiav)" direction="in"/>
+               </method>
+               <signal name="Changed">
+                 <arg name="new_value" type="b"/>
+                 <arg name="old_value" type="b"/>
+               </signal>
+               <property name="Bar" type="y" access="readwrite"/>
+               <property name="Baz" type="s" access="read"/>
+             </interface>
+             <node name="first">
+               <interface name="com.example.Child"/>
+             </node>
+           </node>"#,
+    )?;
+    let diff = Diff::new(&old, &new);
+    let changes: Vec<_> = diff
+        .changes()
+        .iter()
+        .map(|c| {
+            (
+                c.path(),
+                c.interface(),
+                c.member().cloned(),
+                c.kind(),
+                c.is_breaking(),
+            )
+        })
+        .collect();
+    let iface = "com.example.SampleInterface0";
+    assert_eq!(
+        changes,
+        [
+            (
+                "",
+                iface,
+                Some(Member::Method("Bazify".into())),
+                ChangeKind::SignatureChanged,
+                true,
+            ),
+            (
+                "",
+                iface,
+                Some(Member::Signal("Changed".into())),
+                ChangeKind::SignatureChanged,
+                true,
+            ),
+            (
+                "",
+                iface,
+                Some(Member::Property("Baz".into())),
+                ChangeKind::Added,
+                false,
+            ),
+            ("first", "com.example.Child", None, ChangeKind::Added, false),
+        ]
+    );
+    assert_eq!(
+        diff.changes()[3].to_string(),
+        "first: com.example.Child added"
+    );
+
+    // Widening is fine, narrowing isn't.
+    let diff = Diff::new(&new, &old);
+    assert!(diff.is_breaking());
+    let access = |access| {
+        Node::new().add_interface(
+            Interface::new(InterfaceName::from_static_str_unchecked(
+                "org.example.Sample",
+            ))
+            .add_property(Property::new(
+                PropertyName::from_static_str_unchecked("Bar"),
+                ty("y").unwrap(),
+                access,
+            )),
+        )
+    };
+    let read = access(PropertyAccess::Read);
+    let read_write = access(PropertyAccess::ReadWrite);
+    let write = access(PropertyAccess::Write);
+    let kinds = |old, new| -> Vec<_> {
+        Diff::new(old, new)
+            .changes()
+            .iter()
+            .map(|c| c.kind())
+            .collect()
+    };
+    assert_eq!(kinds(&read, &read_write), [ChangeKind::AccessWidened]);
+    assert_eq!(kinds(&read_write, &write), [ChangeKind::AccessNarrowed]);
+    assert_eq!(kinds(&read, &write), [ChangeKind::AccessNarrowed]);
+
+    Ok(())
+}
+
+#[test]
+fn walk() -> Result<(), Box<dyn Error>> {
+    let node = Node::try_from(include_str!("data/sample_object0.xml"))?;
+    let path = ObjectPath::try_from("/com/example/sample_object0")?;
+    let nodes: Vec<_> = node.walk(path.clone()).collect();
+    assert_eq!(nodes.len(), 5);
+    let (root_path, root) = nodes[0].as_ref().unwrap();
+    assert_eq!(root_path.as_str(), path.as_str());
+    assert_eq!(*root, &node);
+    let (first_path, first) = nodes[1].as_ref().unwrap();
+    assert_eq!(first_path.as_str(), "/com/example/sample_object0/first");
+    assert_eq!(*first, &node.nodes()[0]);
+    // Dashes aren't allowed in object paths.
+    assert!(matches!(nodes[4], Err(zbus_xml::Error::Variant(_))));
+
+    let node = Node::try_from(
+        r#"<node>
+             <node name="relative/deeper"/>
+             <node name="/absolute/path"/>
+             <node/>
+           </node>"#,
+    )?;
+    let paths = node
+        .walk(ObjectPath::try_from("/")?)
+        .map(|n| n.map(|(path, _)| path.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(paths, ["/", "/relative/deeper", "/absolute/path", "/"]);
+
     Ok(())
 }
 
 #[test]
 fn invalid_arg_type() {
     let input = include_str!("data/invalid_arg_type.xml");
-    assert!(matches!(
-        Node::try_from(input),
-        Err(zbus_xml::Error::QuickXml(DeError::Custom(_)))
-    ));
+    let Err(zbus_xml::Error::Parse(error)) = Node::try_from(input) else {
+        panic!("expected a parse error");
+    };
+    assert_eq!((error.line(), error.column()), (7, 8));
+    assert_eq!(
+        error.context(),
+        [
+            "interface com.example.SampleInterface0",
+            "method Frobate",
+            "arg foz"
+        ]
+    );
+    assert!(error.message().starts_with("invalid `type` attribute `is`"));
+    assert_eq!(
+        Node::from_reader(input.as_bytes()),
+        Err(zbus_xml::Error::Parse(error))
+    );
+}
+
+#[test]
+fn parse_options() -> Result<(), Box<dyn Error>> {
+    let input = r#"<node xmlns:vendor="http://example.org/vendor">
+  <interface name="org.example.Sample">
+    <vendor:extension>
+      <method name="Hidden"/>
+    </vendor:extension>
+    <method name="Frobate" vendor:flags="fast">
+      Some text
+    </method>
+  </interface>
+</node>"#;
+    let strict = ParseOptions::new();
+    assert!(!strict.is_lenient());
+    let Err(zbus_xml::Error::Parse(error)) = strict.parse(input) else {
+        panic!("expected a parse error");
+    };
+    assert_eq!(
+        error.to_string(),
+        "line 3, column 5, in `interface org.example.Sample`: unknown element `vendor:extension`"
+    );
+
+    let lenient = ParseOptions::new().with_lenient(true);
+    let (node, warnings) = lenient.parse(input)?;
+    assert_eq!(node, Node::try_from(input)?);
+    assert_eq!(node.interfaces()[0].methods().len(), 1);
+    let warnings: Vec<_> = warnings
+        .iter()
+        .map(|w| (w.line(), w.column(), w.message()))
+        .collect();
+    assert_eq!(
+        warnings,
+        [
+            (3, 5, "unknown element `vendor:extension`"),
+            (6, 5, "unknown attribute `vendor:flags`"),
+            (7, 7, "unexpected text"),
+        ]
+    );
+    let (node_r, warnings_r) = lenient.parse_reader(input.as_bytes())?;
+    assert_eq!((node_r, warnings_r.len()), (node, 3));
+
+    // Errors are errors, even in lenient mode.
+    for (input, line, column, message) in [
+        (
+            "<node>\n  <interface/>\n</node>",
+            2,
+            3,
+            "missing `name` attribute",
+        ),
+        (
+            r#"<node><interface name="a.b"><property name="P" type="s" access="readonly"/></interface></node>"#,
+            1,
+            29,
+            "invalid `access` attribute `readonly`: expected `read`, `write` or `readwrite`",
+        ),
+        (
+            r#"<node><interface name="a.b"></node>"#,
+            1,
+            31,
+            "Expecting </interface> found </node>",
+        ),
+        (
+            r#"<interface name="a.b"/>"#,
+            1,
+            1,
+            "expected a root `node`, found `interface`",
+        ),
+    ] {
+        let Err(zbus_xml::Error::Parse(error)) = lenient.parse(input) else {
+            panic!("expected a parse error for `{input}`");
+        };
+        assert_eq!(
+            (error.line(), error.column(), error.message()),
+            (line, column, message),
+            "{input}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn busconfig() -> Result<(), Box<dyn Error>> {
+    let config = BusConfig::from_reader(&include_bytes!("data/sample_busconfig.conf")[..])?;
+
+    assert_eq!(config.ty(), Some("system"));
+    assert_eq!(config.user(), Some("messagebus"));
+    assert!(config.fork());
+    assert!(config.syslog());
+    assert!(!config.keep_umask());
+    assert!(config.standard_system_servicedirs());
+    assert!(!config.standard_session_servicedirs());
+    assert_eq!(config.pidfile(), Some("/run/dbus/pid"));
+    assert_eq!(
+        config.servicehelper(),
+        Some("/usr/lib/dbus-1.0/dbus-daemon-launch-helper")
+    );
+    assert_eq!(config.auth(), ["EXTERNAL"]);
+    assert_eq!(config.listen(), ["unix:path=/run/dbus/system_bus_socket"]);
+    assert_eq!(config.includedirs(), ["system.d"]);
+
+    let [selinux, local] = config.includes() else {
+        panic!("expected 2 includes");
+    };
+    assert_eq!(selinux.path(), "contexts/dbus_contexts");
+    assert!(selinux.if_selinux_enabled() && selinux.selinux_root_relative());
+    assert!(!selinux.ignore_missing());
+    assert_eq!(local.path(), "system-local.conf");
+    assert!(local.ignore_missing());
+
+    let limits: Vec<_> = config
+        .limits()
+        .iter()
+        .map(|l| (l.name(), l.value()))
+        .collect();
+    assert_eq!(
+        limits,
+        [
+            ("max_incoming_bytes", 133169152),
+            ("max_match_rules_per_connection", 512)
+        ]
+    );
+
+    let [default, root, console] = config.policies() else {
+        panic!("expected 3 policies");
+    };
+    assert_eq!(default.context(), Some(PolicyContext::Default));
+    assert_eq!(default.user(), None);
+    let rules = default.rules();
+    assert_eq!(rules.len(), 8);
+    assert!(rules[0].is_allow());
+    assert_eq!(rules[0].user(), Some("*"));
+    assert!(matches!(&rules[1], Rule::Deny(m) if m.own() == Some("*")));
+    assert_eq!(rules[2].send_type(), Some(MessageType::MethodCall));
+    assert!(!rules[2].is_allow());
+    assert_eq!(rules[4].send_type(), Some(MessageType::MethodReturn));
+    assert_eq!(rules[4].send_requested_reply(), Some(true));
+    assert_eq!(rules[6].receive_type(), Some(MessageType::Any));
+    assert_eq!(rules[7].send_destination(), Some("org.freedesktop.DBus"));
+    assert_eq!(
+        rules[7].send_interface(),
+        Some("org.freedesktop.DBus.Introspectable")
+    );
+    assert_eq!(rules[7].send_member(), None);
+
+    assert_eq!(root.context(), None);
+    assert_eq!(root.user(), Some("root"));
+    assert_eq!(root.rules()[0].own_prefix(), Some("org.example"));
+
+    assert_eq!(console.at_console(), Some(true));
+    assert_eq!(console.rules()[0].eavesdrop(), Some(false));
+
+    assert!(BusConfig::try_from("<busconfig><limit name=\"x\">many</limit></busconfig>").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn service_file() -> Result<(), Box<dyn Error>> {
+    let service = ServiceFile::try_from(
+        "[D-BUS Service]\n\
+         Name=org.example.Sample\n\
+         Name[de]=ignored\n\
+         Exec = /usr/libexec/sample --system\n\
+         AssumedAppArmorLabel=unconfined\n\
+         \n\
+         [Other Group]\n\
+         User=ignored\n",
+    )?;
+    assert_eq!(service.name().as_str(), "org.example.Sample");
+    assert_eq!(service.exec(), "/usr/libexec/sample --system");
+    assert_eq!(service.user(), None);
+    assert_eq!(service.systemd_service(), None);
+    assert_eq!(service.assumed_apparmor_label(), Some("unconfined"));
+
+    for (input, line, column, message) in [
+        ("Name=a.b\nExec=c\n", 2, 1, "missing group `D-BUS Service`"),
+        ("[D-BUS Service]\nExec=c\n", 2, 1, "missing key `Name`"),
+        ("[D-BUS Service]\nName=a.b\n", 2, 1, "missing key `Exec`"),
+        (
+            "[D-BUS Service]\nName=a.b\n  Name=a.c\nExec=c\n",
+            3,
+            3,
+            "duplicate key `Name`",
+        ),
+        (
+            "[D-BUS Service]\nName\n",
+            2,
+            1,
+            "expected a `Key=Value` pair",
+        ),
+        ("[D-BUS Service\n", 1, 1, "unterminated group header"),
+    ] {
+        let Err(zbus_xml::Error::Parse(error)) = ServiceFile::try_from(input) else {
+            panic!("expected a parse error for `{input}`");
+        };
+        assert_eq!(
+            (error.line(), error.column(), error.message()),
+            (line, column, message),
+            "{input}"
+        );
+    }
+    let Err(zbus_xml::Error::Parse(error)) =
+        ServiceFile::try_from("[D-BUS Service]\nName=:1.42\nExec=c\n")
+    else {
+        panic!("expected a parse error for a unique name");
+    };
+    assert_eq!(error.line(), 2);
+    assert!(error.message().starts_with("invalid `Name`"));
+
+    Ok(())
+}
+
+fn ty(signature: &str) -> zbus_xml::Result<CompleteType<'_>> {
+    Ok(CompleteType::try_from(Signature::try_from(signature)?)?)
 }
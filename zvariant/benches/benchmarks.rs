@@ -141,8 +141,36 @@ fn big_array_ser_and_de(c: &mut Criterion) {
     }
 }
 
+// String validation dominates the decoding of string arrays, see the `simd` feature.
+fn string_array(c: &mut Criterion) {
+    let strings: Vec<String> = (0..1024 * 10)
+        .map(|idx| format!("/org/freedesktop/Example/Object{idx}/with/a/rather/long/path"))
+        .collect();
+    let ctxt = Context::new_dbus(LE, 0);
+    let signature = Vec::<String>::signature();
+    let enc = to_bytes_for_signature(ctxt, &signature, &strings).unwrap();
+    c.bench_function("string_array_de", |b| {
+        b.iter(|| {
+            let _: (Vec<&str>, _) = enc
+                .deserialize_for_signature(black_box(&signature))
+                .unwrap();
+        })
+    });
+}
+
 #[cfg(feature = "serde_bytes")]
-criterion_group!(benches, big_array_ser_and_de, byte_array, fixed_size_array);
+criterion_group!(
+    benches,
+    big_array_ser_and_de,
+    byte_array,
+    fixed_size_array,
+    string_array
+);
 #[cfg(not(feature = "serde_bytes"))]
-criterion_group!(benches, big_array_ser_and_de, fixed_size_array);
+criterion_group!(
+    benches,
+    big_array_ser_and_de,
+    fixed_size_array,
+    string_array
+);
 criterion_main!(benches);
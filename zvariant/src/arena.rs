@@ -0,0 +1,347 @@
+use bumpalo::{
+    collections::{String as BumpString, Vec as BumpVec},
+    Bump,
+};
+use core::{fmt, marker::PhantomData};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+#[cfg(all(unix, feature = "std"))]
+use crate::Fd;
+use crate::{
+    DynamicType, FixedArray, FixedArrayElement, ObjectPath, OwnedValue, Signature, Str, Type, Value,
+};
+
+/// Types that can be deserialized with their allocations made in a [`Bump`] arena.
+///
+/// Decoding a message usually allocates for each of its arrays. For consumers decoding many
+/// messages per second, deserializing into an arena instead avoids that heap churn: everything
+/// decoded is freed at once, when the arena is reset.
+///
+/// Arrays are deserialized into [`bumpalo::collections::Vec`] or `&[T]` and strings into
+/// [`bumpalo::collections::String`], or borrowed from the serialized data as `&str`. Other types
+/// implementing this trait don't allocate, or their allocations can't be made in the arena (e.g.
+/// [`Value`] and `HashMap`). For your own types, implement this trait on top of the existing
+/// implementations, using [`InArena`] as the seed for the fields.
+///
+/// This trait is only available with the `bumpalo` feature.
+///
+/// # Examples
+///
+/// ```
+/// use bumpalo::{collections::Vec as BumpVec, Bump};
+/// use zvariant::{serialized::Context, to_bytes, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let mut bump = Bump::new();
+/// for i in 0..10u32 {
+///     let encoded = to_bytes(ctxt, &(i, vec![("a", i), ("b", i * 2)])).unwrap();
+///     let (n, pairs): (u32, BumpVec<'_, (&str, u32)>) = encoded.deserialize_in(&bump).unwrap().0;
+///     assert_eq!(n, i);
+///     assert_eq!(pairs[1], ("b", i * 2));
+///
+///     // Everything decoded from this message is freed here, at once.
+///     drop(pairs);
+///     bump.reset();
+/// }
+/// ```
+pub trait DeserializeIn<'de, 'b>: Sized {
+    /// Deserialize `Self` from `deserializer`, allocating in `bump`.
+    fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// The [`DeserializeSeed`] of a [`DeserializeIn`] type.
+pub struct InArena<'b, T> {
+    bump: &'b Bump,
+    phantom: PhantomData<T>,
+}
+
+impl<'b, T> InArena<'b, T> {
+    /// Create a seed to deserialize `T` into `bump`.
+    pub fn new(bump: &'b Bump) -> Self {
+        Self {
+            bump,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for InArena<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for InArena<'_, T> {}
+
+impl<T> fmt::Debug for InArena<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InArena").finish_non_exhaustive()
+    }
+}
+
+impl<'de, 'b, T> DeserializeSeed<'de> for InArena<'b, T>
+where
+    T: DeserializeIn<'de, 'b>,
+{
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize_in(deserializer, self.bump)
+    }
+}
+
+impl<T> DynamicType for InArena<'_, T>
+where
+    T: Type,
+{
+    fn dynamic_signature(&self) -> Signature<'_> {
+        T::signature()
+    }
+}
+
+macro_rules! deserialize_in_impl {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl<'de, 'b> DeserializeIn<'de, 'b> for $ty {
+                fn deserialize_in<D>(deserializer: D, _bump: &'b Bump) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    <$ty>::deserialize(deserializer)
+                }
+            }
+        )+
+    };
+}
+
+deserialize_in_impl!(
+    (),
+    u8,
+    bool,
+    i16,
+    u16,
+    i32,
+    u32,
+    i64,
+    u64,
+    f64,
+    &'de str,
+    Str<'de>,
+    ObjectPath<'de>,
+    Signature<'de>,
+    Value<'de>,
+    OwnedValue,
+);
+#[cfg(all(unix, feature = "std"))]
+deserialize_in_impl!(Fd<'de>);
+
+impl<'de, 'b, T> DeserializeIn<'de, 'b> for FixedArray<'de, T>
+where
+    T: FixedArrayElement,
+{
+    fn deserialize_in<D>(deserializer: D, _bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        FixedArray::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, 'b, K, V, H> DeserializeIn<'de, 'b> for HashMap<K, V, H>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    H: BuildHasher + Default,
+{
+    fn deserialize_in<D>(deserializer: D, _bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        HashMap::deserialize(deserializer)
+    }
+}
+
+impl<'de, 'b, T> DeserializeIn<'de, 'b> for BumpVec<'b, T>
+where
+    T: DeserializeIn<'de, 'b> + 'b,
+{
+    fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(VecVisitor(InArena::new(bump)))
+    }
+}
+
+impl<'de, 'b, T> DeserializeIn<'de, 'b> for &'b [T]
+where
+    T: DeserializeIn<'de, 'b> + 'b,
+{
+    fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        BumpVec::deserialize_in(deserializer, bump).map(BumpVec::into_bump_slice)
+    }
+}
+
+struct VecVisitor<'b, T>(InArena<'b, T>);
+
+impl<'de, 'b, T> Visitor<'de> for VecVisitor<'b, T>
+where
+    T: DeserializeIn<'de, 'b> + 'b,
+{
+    type Value = BumpVec<'b, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an array")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut vec = BumpVec::new_in(self.0.bump);
+        while let Some(element) = seq.next_element_seed(self.0)? {
+            vec.push(element);
+        }
+
+        Ok(vec)
+    }
+}
+
+impl<'de, 'b> DeserializeIn<'de, 'b> for BumpString<'b> {
+    fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(StringVisitor(bump))
+    }
+}
+
+struct StringVisitor<'b>(&'b Bump);
+
+impl<'de, 'b> Visitor<'de> for StringVisitor<'b> {
+    type Value = BumpString<'b>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(BumpString::from_str_in(value, self.0))
+    }
+}
+
+impl<'de, 'b, T> DeserializeIn<'de, 'b> for Option<T>
+where
+    T: DeserializeIn<'de, 'b>,
+{
+    fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_option(OptionVisitor(InArena::new(bump)))
+    }
+}
+
+struct OptionVisitor<'b, T>(InArena<'b, T>);
+
+impl<'de, 'b, T> Visitor<'de> for OptionVisitor<'b, T>
+where
+    T: DeserializeIn<'de, 'b>,
+{
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an optional value")
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(deserializer).map(Some)
+    }
+}
+
+struct TupleVisitor<'b, T>(&'b Bump, PhantomData<T>);
+
+macro_rules! tuple_impls {
+    ($($len:expr => ($($n:tt $name:ident)+))+) => {
+        $(
+            impl<'de, 'b, $($name),+> DeserializeIn<'de, 'b> for ($($name,)+)
+            where
+                $($name: DeserializeIn<'de, 'b>,)+
+            {
+                fn deserialize_in<D>(deserializer: D, bump: &'b Bump) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    deserializer.deserialize_tuple($len, TupleVisitor::<Self>(bump, PhantomData))
+                }
+            }
+
+            impl<'de, 'b, $($name),+> Visitor<'de> for TupleVisitor<'b, ($($name,)+)>
+            where
+                $($name: DeserializeIn<'de, 'b>,)+
+            {
+                type Value = ($($name,)+);
+
+                fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    formatter.write_str("a structure")
+                }
+
+                fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: SeqAccess<'de>,
+                {
+                    Ok(($(
+                        seq.next_element_seed(InArena::<$name>::new(self.0))?
+                            .ok_or_else(|| serde::de::Error::invalid_length($n, &self))?,
+                    )+))
+                }
+            }
+        )+
+    }
+}
+
+tuple_impls! {
+    1 => (0 T0)
+    2 => (0 T0 1 T1)
+    3 => (0 T0 1 T1 2 T2)
+    4 => (0 T0 1 T1 2 T2 3 T3)
+    5 => (0 T0 1 T1 2 T2 3 T3 4 T4)
+    6 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5)
+    7 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6)
+    8 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7)
+    9 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8)
+    10 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9)
+    11 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10)
+    12 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11)
+    13 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12)
+    14 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13)
+    15 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14)
+    16 => (0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12 13 T13 14 T14 15 T15)
+}
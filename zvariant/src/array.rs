@@ -1,11 +1,14 @@
 #![allow(unknown_lints)]
+use alloc::{borrow::ToOwned, format, string::String, vec, vec::Vec};
+use core::fmt::{Display, Write};
 use serde::{
     de::{DeserializeSeed, Deserializer, SeqAccess, Visitor},
     ser::{Serialize, SerializeSeq, Serializer},
 };
 use static_assertions::assert_impl_all;
-use std::fmt::{Display, Write};
 
+#[cfg(all(unix, feature = "std"))]
+use crate::Fd;
 use crate::{
     value::{value_display_fmt, SignatureSeed},
     DynamicDeserialize, DynamicType, Error, Result, Signature, Type, Value,
@@ -132,19 +135,33 @@ impl<'a> Array<'a> {
             signature: self.signature.clone(),
         })
     }
+
+    #[cfg(all(unix, feature = "std"))]
+    pub(crate) fn map_fds<F>(self, f: &mut F) -> Result<Self>
+    where
+        F: FnMut(Fd<'a>) -> Result<Fd<'a>>,
+    {
+        let elements = self
+            .elements
+            .into_iter()
+            .map(|v| v.map_fds_with(f))
+            .collect::<Result<_>>()?;
+
+        Ok(Self { elements, ..self })
+    }
 }
 
 impl Display for Array<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         array_display_fmt(self, f, true)
     }
 }
 
 pub(crate) fn array_display_fmt(
     array: &Array<'_>,
-    f: &mut std::fmt::Formatter<'_>,
+    f: &mut core::fmt::Formatter<'_>,
     type_annotate: bool,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     // Print as string if it is a bytestring (i.e., first nul character is the last byte)
     if let [leading @ .., Value::U8(b'\0')] = array.as_ref() {
         if !leading.contains(&Value::U8(b'\0')) {
@@ -240,7 +257,7 @@ impl<'a> DynamicDeserialize<'a> for Array<'a> {
     }
 }
 
-impl<'a> std::ops::Deref for Array<'a> {
+impl<'a> core::ops::Deref for Array<'a> {
     type Target = [Value<'a>];
 
     fn deref(&self) -> &Self::Target {
@@ -337,7 +354,7 @@ impl<'a> Serialize for Array<'a> {
 
 impl<'de> DeserializeSeed<'de> for ArraySeed<'de> {
     type Value = Array<'de>;
-    fn deserialize<D>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error>
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
     where
         D: Deserializer<'de>,
     {
@@ -355,11 +372,11 @@ struct ArrayVisitor<'a> {
 impl<'de> Visitor<'de> for ArrayVisitor<'de> {
     type Value = Array<'de>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("an Array value")
     }
 
-    fn visit_seq<V>(self, visitor: V) -> std::result::Result<Array<'de>, V::Error>
+    fn visit_seq<V>(self, visitor: V) -> core::result::Result<Array<'de>, V::Error>
     where
         V: SeqAccess<'de>,
     {
@@ -0,0 +1,345 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt, marker::PhantomData};
+use serde::de::{
+    value::{BoolDeserializer, BorrowedBytesDeserializer, SeqDeserializer, StringDeserializer},
+    Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor,
+};
+use static_assertions::assert_impl_all;
+
+use crate::{
+    serialized::{Context, Format},
+    Endian, Error, Result, Signature, Type,
+};
+
+/// The name of the newtype struct our deserializers recognize, to provide the encoded elements of
+/// an [`ArrayView`].
+pub(crate) const ARRAY_VIEW_NAME: &str = "zvariant::ArrayView";
+
+/// A borrowed array whose elements are only decoded on access.
+///
+/// Deserializing an `ArrayView` borrows the encoded array from the serialized data and only
+/// notes where each element ends. The elements are still checked for validity at that point, but
+/// none of them are built until they're accessed through [`ArrayView::get`] or
+/// [`ArrayView::iter`]. This is useful when only a few elements of a large array are needed.
+///
+/// Dictionaries aren't supported. Neither are elements containing file descriptors, since the
+/// file descriptors aren't available to the view.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, ArrayView, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let points: Vec<_> = (0..1000u32).map(|i| (i, format!("point {i}"))).collect();
+/// let encoded = to_bytes(ctxt, &points).unwrap();
+///
+/// let view: ArrayView<'_, (u32, &str)> = encoded.deserialize().unwrap().0;
+/// assert_eq!(view.len(), 1000);
+/// assert_eq!(view.get(500).unwrap(), Some((500, "point 500")));
+/// assert_eq!(view.get(1000).unwrap(), None);
+/// let sum: u32 = view.iter().map(|point| point.unwrap().0).take(10).sum();
+/// assert_eq!(sum, 45);
+/// ```
+#[derive(Clone)]
+pub struct ArrayView<'a, T> {
+    bytes: &'a [u8],
+    // The context of the first element.
+    ctxt: Context,
+    // The end of each element, relative to `bytes`.
+    ends: Vec<usize>,
+    phantom: PhantomData<T>,
+}
+
+assert_impl_all!(ArrayView<'_, u32>: Send, Sync, Unpin);
+
+impl<'a, T> ArrayView<'a, T>
+where
+    T: Deserialize<'a> + Type,
+{
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Decode the element at index `idx`, if any.
+    pub fn get(&self, idx: usize) -> Result<Option<T>> {
+        let Some(end) = self.ends.get(idx) else {
+            return Ok(None);
+        };
+        let start = match idx {
+            0 => 0,
+            _ => self.ends[idx - 1],
+        };
+
+        self.decode(start, *end).map(Some)
+    }
+
+    /// Iterate over the elements, decoding each of them as it's reached.
+    pub fn iter(&self) -> ArrayViewIter<'_, 'a, T> {
+        ArrayViewIter { view: self, idx: 0 }
+    }
+
+    /// Decode all the elements into a `Vec`.
+    pub fn to_vec(&self) -> Result<Vec<T>> {
+        self.iter().collect()
+    }
+
+    /// The encoded elements, including any padding between them.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    fn decode(&self, start: usize, end: usize) -> Result<T> {
+        let ctxt = self.ctxt.offset_by(start);
+        let bytes = &self.bytes[start..end];
+        let signature = T::signature();
+
+        // The elements were validated with their FDs, but those aren't available here.
+        #[cfg(all(unix, feature = "std"))]
+        let fds = None::<&[std::os::fd::BorrowedFd<'_>]>;
+        let mut de = match ctxt.format() {
+            #[cfg(feature = "gvariant")]
+            Format::GVariant => {
+                #[cfg(all(unix, feature = "std"))]
+                {
+                    crate::gvariant::Deserializer::new(bytes, fds, signature, ctxt)
+                }
+                #[cfg(not(all(unix, feature = "std")))]
+                {
+                    crate::gvariant::Deserializer::<()>::new(bytes, signature, ctxt)
+                }
+            }
+            .map(crate::de::Deserializer::GVariant)?,
+            Format::DBus => {
+                #[cfg(all(unix, feature = "std"))]
+                {
+                    crate::dbus::Deserializer::new(bytes, fds, signature, ctxt)
+                }
+                #[cfg(not(all(unix, feature = "std")))]
+                {
+                    crate::dbus::Deserializer::<()>::new(bytes, signature, ctxt)
+                }
+            }
+            .map(crate::de::Deserializer::DBus)?,
+        };
+
+        T::deserialize(&mut de)
+    }
+}
+
+/// An iterator over the elements of an [`ArrayView`].
+///
+/// Created by [`ArrayView::iter`].
+pub struct ArrayViewIter<'v, 'a, T> {
+    view: &'v ArrayView<'a, T>,
+    idx: usize,
+}
+
+impl<'a, T> Iterator for ArrayViewIter<'_, 'a, T>
+where
+    T: Deserialize<'a> + Type,
+{
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let element = self.view.get(self.idx).transpose()?;
+        self.idx += 1;
+
+        Some(element)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.view.len() - self.idx;
+
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ArrayViewIter<'_, 'a, T> where T: Deserialize<'a> + Type {}
+
+impl<T> fmt::Debug for ArrayViewIter<'_, '_, T>
+where
+    T: Type,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayViewIter")
+            .field("view", self.view)
+            .field("idx", &self.idx)
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for ArrayView<'_, T>
+where
+    T: Type,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayView")
+            .field("signature", &<[T]>::signature())
+            .field("len", &self.ends.len())
+            .finish()
+    }
+}
+
+impl<T> Type for ArrayView<'_, T>
+where
+    T: Type,
+{
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
+impl<'de: 'a, 'a, T> Deserialize<'de> for ArrayView<'a, T>
+where
+    T: Type,
+{
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(ARRAY_VIEW_NAME, ArrayViewVisitor(PhantomData))
+    }
+}
+
+struct ArrayViewVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for ArrayViewVisitor<T>
+where
+    T: Type,
+{
+    type Value = ArrayView<'de, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "an array of `{}`", T::signature())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        macro_rules! next {
+            ($idx:literal) => {
+                seq.next_element()?
+                    .ok_or_else(|| A::Error::invalid_length($idx, &self))?
+            };
+        }
+
+        let element: String = next!(0);
+        if T::signature() != element.as_str() {
+            return Err(A::Error::invalid_value(Unexpected::Str(&element), &self));
+        }
+        let gvariant: bool = next!(1);
+        let big_endian: bool = next!(2);
+        let lenient: bool = next!(3);
+        let position: usize = next!(4);
+        let bytes: &'de [u8] = next!(5);
+        let ends: Vec<usize> = next!(6);
+
+        let format = if gvariant {
+            #[cfg(feature = "gvariant")]
+            {
+                Format::GVariant
+            }
+            #[cfg(not(feature = "gvariant"))]
+            return Err(A::Error::invalid_value(Unexpected::Bool(gvariant), &self));
+        } else {
+            Format::DBus
+        };
+        let endian = if big_endian {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+        if ends.windows(2).any(|w| w[0] > w[1]) || ends.last().is_some_and(|e| *e > bytes.len()) {
+            return Err(A::Error::invalid_value(Unexpected::Bytes(bytes), &self));
+        }
+
+        Ok(ArrayView {
+            bytes,
+            ctxt: Context::new(format, endian, position).with_lenient(lenient),
+            ends,
+            phantom: PhantomData,
+        })
+    }
+}
+
+/// Provides the encoded elements of an [`ArrayView`] to its visitor.
+pub(crate) struct ArrayViewAccess<'de> {
+    element: Option<String>,
+    format: Option<Format>,
+    endian: Option<Endian>,
+    lenient: Option<bool>,
+    position: Option<usize>,
+    bytes: Option<&'de [u8]>,
+    ends: Option<Vec<usize>>,
+}
+
+impl<'de> ArrayViewAccess<'de> {
+    /// `ctxt` is that of the first element, and `ends` are the ends of the elements in `bytes`.
+    pub(crate) fn new(
+        element: &Signature<'_>,
+        ctxt: Context,
+        bytes: &'de [u8],
+        ends: Vec<usize>,
+    ) -> Self {
+        Self {
+            element: Some(element.as_str().into()),
+            format: Some(ctxt.format()),
+            endian: Some(ctxt.endian()),
+            lenient: Some(ctxt.is_lenient()),
+            position: Some(ctxt.position()),
+            bytes: Some(bytes),
+            ends: Some(ends),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArrayViewAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if let Some(element) = self.element.take() {
+            return seed.deserialize(StringDeserializer::new(element)).map(Some);
+        }
+        if let Some(format) = self.format.take() {
+            let gvariant = BoolDeserializer::new(format != Format::DBus);
+
+            return seed.deserialize(gvariant).map(Some);
+        }
+        if let Some(endian) = self.endian.take() {
+            let big_endian = BoolDeserializer::new(endian == Endian::Big);
+
+            return seed.deserialize(big_endian).map(Some);
+        }
+        if let Some(lenient) = self.lenient.take() {
+            return seed.deserialize(BoolDeserializer::new(lenient)).map(Some);
+        }
+        if let Some(position) = self.position.take() {
+            let position = serde::de::IntoDeserializer::into_deserializer(position);
+
+            return seed.deserialize(position).map(Some);
+        }
+        if let Some(bytes) = self.bytes.take() {
+            return seed
+                .deserialize(BorrowedBytesDeserializer::new(bytes))
+                .map(Some);
+        }
+
+        self.ends
+            .take()
+            .map(|ends| seed.deserialize(SeqDeserializer::new(ends.into_iter())))
+            .transpose()
+    }
+}
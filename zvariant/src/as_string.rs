@@ -0,0 +1,65 @@
+//! Serialize and deserialize a field as a string (`s`), using its [`Display`] and [`FromStr`]
+//! implementations.
+//!
+//! This is meant to be used with serde's `with` field attribute, along with the `signature`
+//! field attribute of the [`Type`] derive macro, for types whose serde representation isn't a
+//! string. For example, `uuid::Uuid` is encoded as an array of bytes (`ay`) by default but most
+//! D-Bus interfaces pass UUIDs as strings:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use std::net::Ipv4Addr;
+//! use zvariant::{serialized::Context, to_bytes, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+//! struct Connection {
+//!     id: String,
+//!     #[zvariant(signature = "s")]
+//!     #[serde(with = "zvariant::as_string")]
+//!     address: Ipv4Addr,
+//! }
+//!
+//! assert_eq!(Connection::signature(), "(ss)");
+//! let connection = Connection {
+//!     id: "eth0".to_string(),
+//!     address: Ipv4Addr::new(192, 168, 1, 1),
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &connection).unwrap();
+//! let (id, address): (&str, &str) = encoded.deserialize().unwrap().0;
+//! assert_eq!((id, address), ("eth0", "192.168.1.1"));
+//! let decoded: Connection = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, connection);
+//! ```
+//!
+//! Deserialization fails if the string can't be parsed.
+//!
+//! [`Display`]: std::fmt::Display
+//! [`FromStr`]: std::str::FromStr
+//! [`Type`]: macro@crate::Type
+
+use core::{fmt::Display, str::FromStr};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+use crate::Str;
+
+/// Serialize `value` as a string.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display,
+    S: Serializer,
+{
+    serializer.collect_str(value)
+}
+
+/// Deserialize a value by parsing a string.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s = Str::deserialize(deserializer)?;
+
+    s.as_str().parse().map_err(D::Error::custom)
+}
@@ -0,0 +1,54 @@
+//! Serialize and deserialize a field as a variant (`v`).
+//!
+//! This is meant to be used with serde's `with` field attribute, along with the `signature`
+//! field attribute of the [`Type`] derive macro, when an interface expects a value of a well-known
+//! type to be wrapped in a variant:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use zvariant::{serialized::Context, to_bytes, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+//! struct Property {
+//!     name: String,
+//!     #[zvariant(signature = "v")]
+//!     #[serde(with = "zvariant::as_value")]
+//!     value: u32,
+//! }
+//!
+//! assert_eq!(Property::signature(), "(sv)");
+//! let property = Property {
+//!     name: "Answer".to_string(),
+//!     value: 42,
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &property).unwrap();
+//! let decoded: Property = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, property);
+//! ```
+//!
+//! Deserialization fails if the variant doesn't contain a value of the field's type.
+//!
+//! [`Type`]: macro@crate::Type
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{DeserializeValue, SerializeValue, Type};
+
+/// Serialize `value` as a variant.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Type + Serialize,
+    S: Serializer,
+{
+    SerializeValue(value).serialize(serializer)
+}
+
+/// Deserialize a value from a variant.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Type + Deserialize<'de> + 'de,
+    D: Deserializer<'de>,
+{
+    DeserializeValue::deserialize(deserializer).map(|v| v.0)
+}
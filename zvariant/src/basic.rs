@@ -1,4 +1,5 @@
 use crate::{serialized::Format, Signature, Type};
+use alloc::string::String;
 
 /// Trait for basic types.
 ///
@@ -61,13 +62,13 @@ impl Basic for u8 {
 }
 impl_type!(u8);
 
-impl Basic for std::num::NonZeroU8 {
+impl Basic for core::num::NonZeroU8 {
     const SIGNATURE_CHAR: char = u8::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = u8::SIGNATURE_STR;
 
     alignment_method!(1);
 }
-impl_type!(std::num::NonZeroU8);
+impl_type!(core::num::NonZeroU8);
 
 // No i8 type in D-Bus/GVariant, let's pretend it's i16
 impl Basic for i8 {
@@ -81,7 +82,7 @@ impl Basic for i8 {
 }
 impl_type!(i8);
 
-impl Basic for std::num::NonZeroI8 {
+impl Basic for core::num::NonZeroI8 {
     const SIGNATURE_CHAR: char = i8::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = i8::SIGNATURE_STR;
 
@@ -90,7 +91,7 @@ impl Basic for std::num::NonZeroI8 {
         i16::alignment(Format::GVariant)
     );
 }
-impl_type!(std::num::NonZeroI8);
+impl_type!(core::num::NonZeroI8);
 
 impl Basic for bool {
     const SIGNATURE_CHAR: char = 'b';
@@ -108,13 +109,13 @@ impl Basic for i16 {
 }
 impl_type!(i16);
 
-impl Basic for std::num::NonZeroI16 {
+impl Basic for core::num::NonZeroI16 {
     const SIGNATURE_CHAR: char = i16::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = i16::SIGNATURE_STR;
 
     alignment_method!(2);
 }
-impl_type!(std::num::NonZeroI16);
+impl_type!(core::num::NonZeroI16);
 
 impl Basic for u16 {
     const SIGNATURE_CHAR: char = 'q';
@@ -124,13 +125,13 @@ impl Basic for u16 {
 }
 impl_type!(u16);
 
-impl Basic for std::num::NonZeroU16 {
+impl Basic for core::num::NonZeroU16 {
     const SIGNATURE_CHAR: char = u16::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = u16::SIGNATURE_STR;
 
     alignment_method!(2);
 }
-impl_type!(std::num::NonZeroU16);
+impl_type!(core::num::NonZeroU16);
 
 impl Basic for i32 {
     const SIGNATURE_CHAR: char = 'i';
@@ -140,13 +141,13 @@ impl Basic for i32 {
 }
 impl_type!(i32);
 
-impl Basic for std::num::NonZeroI32 {
+impl Basic for core::num::NonZeroI32 {
     const SIGNATURE_CHAR: char = i32::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = i32::SIGNATURE_STR;
 
     alignment_method!(4);
 }
-impl_type!(std::num::NonZeroI32);
+impl_type!(core::num::NonZeroI32);
 
 impl Basic for u32 {
     const SIGNATURE_CHAR: char = 'u';
@@ -156,13 +157,13 @@ impl Basic for u32 {
 }
 impl_type!(u32);
 
-impl Basic for std::num::NonZeroU32 {
+impl Basic for core::num::NonZeroU32 {
     const SIGNATURE_CHAR: char = u32::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = u32::SIGNATURE_STR;
 
     alignment_method!(4);
 }
-impl_type!(std::num::NonZeroU32);
+impl_type!(core::num::NonZeroU32);
 
 impl Basic for i64 {
     const SIGNATURE_CHAR: char = 'x';
@@ -172,13 +173,13 @@ impl Basic for i64 {
 }
 impl_type!(i64);
 
-impl Basic for std::num::NonZeroI64 {
+impl Basic for core::num::NonZeroI64 {
     const SIGNATURE_CHAR: char = i64::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = i64::SIGNATURE_STR;
 
     alignment_method!(8);
 }
-impl_type!(std::num::NonZeroI64);
+impl_type!(core::num::NonZeroI64);
 
 impl Basic for u64 {
     const SIGNATURE_CHAR: char = 't';
@@ -188,13 +189,13 @@ impl Basic for u64 {
 }
 impl_type!(u64);
 
-impl Basic for std::num::NonZeroU64 {
+impl Basic for core::num::NonZeroU64 {
     const SIGNATURE_CHAR: char = u64::SIGNATURE_CHAR;
     const SIGNATURE_STR: &'static str = u64::SIGNATURE_STR;
 
     alignment_method!(8);
 }
-impl_type!(std::num::NonZeroU64);
+impl_type!(core::num::NonZeroU64);
 
 // No f32 type in D-Bus/GVariant, let's pretend it's f64
 impl Basic for f32 {
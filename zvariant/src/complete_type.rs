@@ -19,7 +19,7 @@ impl<'a> CompleteType<'a> {
 
 impl<'a> Display for CompleteType<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        std::fmt::Display::fmt(&self.0.as_str(), f)
+        core::fmt::Display::fmt(&self.0.as_str(), f)
     }
 }
 
@@ -1,20 +1,25 @@
+use alloc::{format, vec::Vec};
 use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, Visitor};
 use static_assertions::assert_impl_all;
 
-use std::{marker::PhantomData, str};
+use core::{marker::PhantomData, str};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use std::os::fd::AsFd;
 
 use crate::{
+    array_view::{ArrayViewAccess, ARRAY_VIEW_NAME},
     de::{DeserializerCommon, ValueParseStage},
+    dict::{DictKeyAccess, DICT_KEY_NAME},
+    fixed_array::{fixed_array_element, is_fixed_array_signature, FixedArrayAccess},
     serialized::{Context, Format},
     signature_parser::SignatureParser,
     utils::*,
+    validate::Skip,
     Basic, Error, ObjectPath, Result, Signature,
 };
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 /// Our D-Bus deserialization implementation.
@@ -29,7 +34,7 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
     /// On Windows, there is no `fds` argument.
     pub fn new<'r: 'de, S>(
         bytes: &'r [u8],
-        #[cfg(unix)] fds: Option<&'f [F]>,
+        #[cfg(all(unix, feature = "std"))] fds: Option<&'f [F]>,
         signature: S,
         ctxt: Context,
     ) -> Result<Self>
@@ -45,9 +50,9 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
             ctxt,
             sig_parser,
             bytes,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             fds,
-            #[cfg(not(unix))]
+            #[cfg(not(all(unix, feature = "std")))]
             fds: PhantomData,
             pos: 0,
             container_depths: Default::default(),
@@ -87,8 +92,14 @@ macro_rules! deserialize_as {
     }
 }
 
-impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
-    for &'d mut Deserializer<'de, 'sig, 'f, F>
+impl<
+        'de,
+        'd,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > de::Deserializer<'de> for &'d mut Deserializer<'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -168,7 +179,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         V: Visitor<'de>,
     {
         let v = match self.0.sig_parser.next_char()? {
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Fd::SIGNATURE_CHAR => {
                 self.0.sig_parser.skip_char()?;
                 let alignment = u32::alignment(Format::DBus);
@@ -239,14 +250,19 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
             }
         };
         let slice = self.0.next_slice(len)?;
-        if slice.contains(&0) {
+        if contains_nul(slice) {
             return Err(serde::de::Error::invalid_value(
                 serde::de::Unexpected::Char('\0'),
                 &"D-Bus string type must not contain interior null bytes",
             ));
         }
-        self.0.pos += 1; // skip trailing null byte
-        let s = str::from_utf8(slice).map_err(Error::Utf8)?;
+        if self.0.next_slice(1)? != [0] {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Bytes(&self.0.bytes[self.0.pos - 1..self.0.pos]),
+                &"nul byte expected at the end of strings",
+            ));
+        }
+        let s = str_from_utf8(slice)?;
         self.0.sig_parser.skip_char()?;
 
         visitor.visit_borrowed_str(s)
@@ -256,15 +272,17 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     where
         V: Visitor<'de>,
     {
+        // `Option<T>` can only be decoded from an array in D-Bus format. The GVariant maybe type
+        // has no D-Bus counterpart at all.
+        let c = self.0.sig_parser.next_char()?;
+        if cfg!(not(feature = "option-as-array")) || c != ARRAY_SIGNATURE_CHAR {
+            let signature = self.0.sig_parser.next_signature()?.to_owned();
+
+            return Err(Error::IncompatibleFormat(signature, Format::DBus));
+        }
+
         #[cfg(feature = "option-as-array")]
         {
-            let c = self.0.sig_parser.next_char()?;
-            if c != ARRAY_SIGNATURE_CHAR {
-                return Err(de::Error::invalid_type(
-                    de::Unexpected::Char(c),
-                    &ARRAY_SIGNATURE_STR,
-                ));
-            }
             self.0.sig_parser.skip_char()?;
             // This takes care of parsing all the padding and getting the byte length.
             let len = ArrayDeserializer::new(self)?.len;
@@ -278,9 +296,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         }
 
         #[cfg(not(feature = "option-as-array"))]
-        Err(de::Error::custom(
-            "Can only decode Option<T> from D-Bus format if `option-as-array` feature is enabled",
-        ))
+        unreachable!()
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
@@ -297,10 +313,25 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if let Some(element) = fixed_array_element(name) {
+            let endian = self.0.ctxt.endian();
+            let bytes = deserialize_fixed_array(self, element)?;
+
+            return visitor.visit_seq(FixedArrayAccess::new(element, endian, bytes));
+        }
+        if name == ARRAY_VIEW_NAME {
+            return visitor.visit_seq(deserialize_array_view(self)?);
+        }
+        if name == DICT_KEY_NAME {
+            let lenient = self.0.ctxt.is_lenient();
+
+            return visitor.visit_seq(DictKeyAccess::new(lenient, self));
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -416,8 +447,14 @@ struct ArrayDeserializer<'d, 'de, 'sig, 'f, F> {
     element_signature_len: usize,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
-    ArrayDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > ArrayDeserializer<'d, 'de, 'sig, 'f, F>
 {
     fn new(de: &'d mut Deserializer<'de, 'sig, 'f, F>) -> Result<Self> {
         de.0.parse_padding(ARRAY_ALIGNMENT_DBUS)?;
@@ -427,12 +464,37 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
         let element_signature = de.0.sig_parser.next_signature()?;
         let element_alignment = alignment_for_signature(&element_signature, Format::DBus)?;
         let mut element_signature_len = element_signature.len();
+        // The size of fixed-sized basic types is the same as their alignment.
+        let fixed_sized_elements = matches!(
+            element_signature.as_bytes(),
+            [b'y' | b'n' | b'q' | b'i' | b'u' | b'x' | b't' | b'd' | b'b' | b'h']
+        );
 
         // D-Bus requires padding for the first element even when there is no first element
         // (i-e empty array) so we parse padding already.
         de.0.parse_padding(element_alignment)?;
         let start = de.0.pos;
 
+        // Validate the bounds of the array upfront, rather than after going through its elements.
+        if len > MAX_ARRAY_LEN_DBUS {
+            return Err(de::Error::invalid_length(
+                len,
+                &format!("<= {MAX_ARRAY_LEN_DBUS}").as_str(),
+            ));
+        }
+        if start + len > de.0.bytes.len() {
+            return Err(de::Error::invalid_length(
+                de.0.bytes.len(),
+                &format!(">= {}", start + len).as_str(),
+            ));
+        }
+        if fixed_sized_elements && len % element_alignment != 0 {
+            return Err(de::Error::invalid_length(
+                len,
+                &format!("a multiple of {element_alignment}").as_str(),
+            ));
+        }
+
         if de.0.sig_parser.next_char()? == DICT_ENTRY_SIG_START_CHAR {
             de.0.sig_parser.skip_char()?;
             element_signature_len -= 1;
@@ -451,10 +513,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
     where
         T: DeserializeSeed<'de>,
     {
-        let ctxt = Context::new_dbus(
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self.de.0.ctxt.offset_by(self.de.0.pos);
 
         let mut de = Deserializer::<F>(DeserializerCommon {
             ctxt,
@@ -506,13 +565,35 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
     }
 }
 
-fn deserialize_ay<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
+fn deserialize_ay<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
     de: &mut Deserializer<'de, '_, '_, F>,
 ) -> Result<&'de [u8]> {
     if de.0.sig_parser.next_signature()? != "ay" {
         return Err(de::Error::invalid_type(de::Unexpected::Seq, &"ay"));
     }
 
+    deserialize_fixed_array(de, 'y')
+}
+
+fn deserialize_fixed_array<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+    element: char,
+) -> Result<&'de [u8]> {
+    if !is_fixed_array_signature(&de.0.sig_parser.next_signature()?, element) {
+        return Err(de::Error::invalid_type(
+            de::Unexpected::Seq,
+            &"array of fixed-sized numbers",
+        ));
+    }
+
     de.0.sig_parser.skip_char()?;
     let ad = ArrayDeserializer::new(de)?;
     let len = ad.len;
@@ -520,10 +601,51 @@ fn deserialize_ay<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
     de.0.next_slice(len)
 }
 
+fn deserialize_array_view<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+) -> Result<ArrayViewAccess<'de>> {
+    let signature = de.0.sig_parser.next_signature()?;
+    if !signature.starts_with(ARRAY_SIGNATURE_CHAR)
+        || signature[1..].starts_with(DICT_ENTRY_SIG_START_CHAR)
+    {
+        return Err(de::Error::invalid_type(
+            de::Unexpected::Other(signature.as_str()),
+            &"an array that isn't a dictionary",
+        ));
+    }
+    let element_signature = signature.slice(1..).to_owned();
+
+    de.0.sig_parser.skip_char()?;
+    let mut seq = ArraySeqDeserializer(ArrayDeserializer::new(de)?);
+    let start = seq.0.start;
+    let ctxt = seq.0.de.0.ctxt.offset_by(start);
+    // Go through the elements without decoding them, only noting where each of them ends.
+    let mut ends = Vec::new();
+    while let Some(()) = seq.next_element_seed(Skip {
+        signature: element_signature.clone(),
+    })? {
+        ends.push(seq.0.de.0.pos - start);
+    }
+    let end = ends.last().copied().unwrap_or(0);
+    let bytes = subslice(de.0.bytes, start..start + end)?;
+
+    Ok(ArrayViewAccess::new(&element_signature, ctxt, bytes, ends))
+}
+
 struct ArraySeqDeserializer<'d, 'de, 'sig, 'f, F>(ArrayDeserializer<'d, 'de, 'sig, 'f, F>);
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for ArraySeqDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for ArraySeqDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -538,8 +660,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
 
 struct ArrayMapDeserializer<'d, 'de, 'sig, 'f, F>(ArrayDeserializer<'d, 'de, 'sig, 'f, F>);
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de>
-    for ArrayMapDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > MapAccess<'de> for ArrayMapDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -567,8 +695,14 @@ struct StructureDeserializer<'d, 'de, 'sig, 'f, F> {
     de: &'d mut Deserializer<'de, 'sig, 'f, F>,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for StructureDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for StructureDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -594,8 +728,14 @@ struct ValueDeserializer<'d, 'de, 'sig, 'f, F> {
     sig_start: usize,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
-    ValueDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > ValueDeserializer<'d, 'de, 'sig, 'f, F>
 {
     fn new(de: &'d mut Deserializer<'de, 'sig, 'f, F>) -> Self {
         let sig_start = de.0.pos;
@@ -607,8 +747,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
     }
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for ValueDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for ValueDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -636,11 +782,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
                 let signature = Signature::try_from(slice)?;
                 let sig_parser = SignatureParser::new(signature);
 
-                let ctxt = Context::new(
-                    Format::DBus,
-                    self.de.0.ctxt.endian(),
-                    self.de.0.ctxt.position() + value_start,
-                );
+                let ctxt = self.de.0.ctxt.offset_by(value_start);
                 let mut de = Deserializer::<F>(DeserializerCommon {
                     ctxt,
                     sig_parser,
@@ -660,8 +802,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
     }
 }
 
-impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> EnumAccess<'de>
-    for crate::de::Enum<&'d mut Deserializer<'de, 'sig, 'f, F>, F>
+impl<
+        'de,
+        'd,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > EnumAccess<'de> for crate::de::Enum<&'d mut Deserializer<'de, 'sig, 'f, F>, F>
 {
     type Error = Error;
     type Variant = Self;
@@ -1,19 +1,18 @@
+use alloc::{format, string::ToString};
+use core::str;
 use serde::{ser, ser::SerializeSeq, Serialize};
 use static_assertions::assert_impl_all;
-use std::{
-    io::{Seek, Write},
-    str,
-};
 
 use crate::{
     container_depths::ContainerDepths,
+    io::{Seek, SeekFrom, Write, WriteBytes},
     serialized::{Context, Format},
     signature_parser::SignatureParser,
     utils::*,
-    Basic, Error, ObjectPath, Result, Signature, WriteBytes,
+    Basic, Error, ObjectPath, Result, Signature,
 };
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 /// Our D-Bus serialization implementation.
@@ -31,7 +30,7 @@ where
     pub fn new<'w: 'ser, 'f: 'ser, S>(
         signature: S,
         writer: &'w mut W,
-        #[cfg(unix)] fds: &'f mut crate::ser::FdList,
+        #[cfg(all(unix, feature = "std"))] fds: &'f mut crate::ser::FdList,
         ctxt: Context,
     ) -> Result<Self>
     where
@@ -46,7 +45,7 @@ where
             ctxt,
             sig_parser,
             writer,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             fds,
             bytes_written: 0,
             value_sign: None,
@@ -55,6 +54,20 @@ where
     }
 }
 
+impl<W> Serializer<'_, '_, W> {
+    // `Option<T>` can only be encoded as an array in D-Bus format. The GVariant maybe type, e.g
+    // from `Value::Maybe`, has no D-Bus counterpart at all.
+    fn check_option_as_array(&self) -> Result<()> {
+        let c = self.0.sig_parser.next_char()?;
+        if cfg!(feature = "option-as-array") && c == ARRAY_SIGNATURE_CHAR {
+            return Ok(());
+        }
+        let signature = self.0.sig_parser.next_signature()?.to_owned();
+
+        Err(Error::IncompatibleFormat(signature, Format::DBus))
+    }
+}
+
 macro_rules! serialize_basic {
     ($method:ident($type:ty) $write_method:ident) => {
         serialize_basic!($method($type) $write_method($type));
@@ -62,7 +75,7 @@ macro_rules! serialize_basic {
     ($method:ident($type:ty) $write_method:ident($as:ty)) => {
         fn $method(self, v: $type) -> Result<()> {
             self.0.prep_serialize_basic::<$type>()?;
-            self.0.$write_method(self.0.ctxt.endian(), v as $as).map_err(|e| Error::InputOutput(e.into()))
+            self.0.$write_method(self.0.ctxt.endian(), v as $as).map_err(Error::from)
         }
     };
 }
@@ -90,20 +103,20 @@ where
 
     fn serialize_i32(self, v: i32) -> Result<()> {
         match self.0.sig_parser.next_char()? {
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Fd::SIGNATURE_CHAR => {
                 self.0.sig_parser.skip_char()?;
                 self.0.add_padding(u32::alignment(Format::DBus))?;
                 let idx = self.0.add_fd(v)?;
                 self.0
                     .write_u32(self.0.ctxt.endian(), idx)
-                    .map_err(|e| Error::InputOutput(e.into()))
+                    .map_err(Error::from)
             }
             _ => {
                 self.0.prep_serialize_basic::<i32>()?;
                 self.0
                     .write_i32(self.0.ctxt.endian(), v)
-                    .map_err(|e| Error::InputOutput(e.into()))
+                    .map_err(Error::from)
             }
         }
     }
@@ -113,7 +126,7 @@ where
         // Endianness is irrelevant for single bytes.
         self.0
             .write_u8(self.0.ctxt.endian(), v)
-            .map_err(|e| Error::InputOutput(e.into()))
+            .map_err(Error::from)
     }
 
     serialize_basic!(serialize_u16(u16) write_u16);
@@ -145,12 +158,12 @@ where
                 self.0.add_padding(<&str>::alignment(Format::DBus))?;
                 self.0
                     .write_u32(self.0.ctxt.endian(), usize_to_u32(v.len()))
-                    .map_err(|e| Error::InputOutput(e.into()))?;
+                    .map_err(Error::from)?;
             }
             Signature::SIGNATURE_CHAR | VARIANT_SIGNATURE_CHAR => {
                 self.0
                     .write_u8(self.0.ctxt.endian(), usize_to_u8(v.len()))
-                    .map_err(|e| Error::InputOutput(e.into()))?;
+                    .map_err(Error::from)?;
             }
             _ => {
                 let expected = format!(
@@ -168,53 +181,34 @@ where
         }
 
         self.0.sig_parser.skip_char()?;
-        self.0
-            .write_all(v.as_bytes())
-            .map_err(|e| Error::InputOutput(e.into()))?;
-        self.0
-            .write_all(&b"\0"[..])
-            .map_err(|e| Error::InputOutput(e.into()))?;
+        self.0.write_all(v.as_bytes()).map_err(Error::from)?;
+        self.0.write_all(&b"\0"[..]).map_err(Error::from)?;
 
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         let seq = self.serialize_seq(Some(v.len()))?;
-        seq.ser
-            .0
-            .write(v)
-            .map_err(|e| Error::InputOutput(e.into()))?;
+        seq.ser.0.write(v).map_err(Error::from)?;
         seq.end()
     }
 
     fn serialize_none(self) -> Result<()> {
-        #[cfg(feature = "option-as-array")]
-        {
-            let seq = self.serialize_seq(Some(0))?;
-            seq.end()
-        }
+        self.check_option_as_array()?;
 
-        #[cfg(not(feature = "option-as-array"))]
-        unreachable!(
-            "Can only encode Option<T> in D-Bus format if `option-as-array` feature is enabled",
-        );
+        let seq = self.serialize_seq(Some(0))?;
+        seq.end()
     }
 
-    fn serialize_some<T>(self, #[allow(unused)] value: &T) -> Result<()>
+    fn serialize_some<T>(self, value: &T) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        #[cfg(feature = "option-as-array")]
-        {
-            let mut seq = self.serialize_seq(Some(1))?;
-            seq.serialize_element(value)?;
-            seq.end()
-        }
+        self.check_option_as_array()?;
 
-        #[cfg(not(feature = "option-as-array"))]
-        unreachable!(
-            "Can only encode Option<T> in D-Bus format if `option-as-array` feature is enabled",
-        );
+        let mut seq = self.serialize_seq(Some(1))?;
+        seq.serialize_element(value)?;
+        seq.end()
     }
 
     fn serialize_unit(self) -> Result<()> {
@@ -272,7 +266,7 @@ where
         // initially set to 0.
         self.0
             .write_u32(self.0.ctxt.endian(), 0_u32)
-            .map_err(|e| Error::InputOutput(e.into()))?;
+            .map_err(Error::from)?;
 
         let element_signature = self.0.sig_parser.next_signature()?;
         let element_signature_len = element_signature.len();
@@ -381,18 +375,18 @@ where
         self.ser
             .0
             .writer
-            .seek(std::io::SeekFrom::Current(-total_array_len))
-            .map_err(|e| Error::InputOutput(e.into()))?;
+            .seek(SeekFrom::Current(-total_array_len))
+            .map_err(Error::from)?;
         self.ser
             .0
             .writer
             .write_u32(self.ser.0.ctxt.endian(), len)
-            .map_err(|e| Error::InputOutput(e.into()))?;
+            .map_err(Error::from)?;
         self.ser
             .0
             .writer
-            .seek(std::io::SeekFrom::Current(total_array_len - 4))
-            .map_err(|e| Error::InputOutput(e.into()))?;
+            .seek(SeekFrom::Current(total_array_len - 4))
+            .map_err(Error::from)?;
 
         self.ser.0.container_depths = self.ser.0.container_depths.dec_array();
 
@@ -518,7 +512,7 @@ where
                     ctxt: self.ser.0.ctxt,
                     sig_parser,
                     writer: self.ser.0.writer,
-                    #[cfg(unix)]
+                    #[cfg(all(unix, feature = "std"))]
                     fds: self.ser.0.fds,
                     bytes_written,
                     value_sign: None,
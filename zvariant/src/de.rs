@@ -1,9 +1,10 @@
+use alloc::format;
 use serde::de::{self, DeserializeSeed, VariantAccess, Visitor};
 use static_assertions::assert_impl_all;
 
-use std::{marker::PhantomData, str};
+use core::{marker::PhantomData, str};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use std::os::fd::{AsFd, AsRawFd};
 
 #[cfg(feature = "gvariant")]
@@ -13,7 +14,7 @@ use crate::{
     signature_parser::SignatureParser, utils::*, Basic, Error, ObjectPath, Result, Signature,
 };
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 /// Our deserialization implementation.
@@ -22,9 +23,9 @@ pub(crate) struct DeserializerCommon<'de, 'sig, 'f, F> {
     pub(crate) ctxt: Context,
     pub(crate) bytes: &'de [u8],
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub(crate) fds: Option<&'f [F]>,
-    #[cfg(not(unix))]
+    #[cfg(not(all(unix, feature = "std")))]
     pub(crate) fds: PhantomData<&'f F>,
 
     pub(crate) pos: usize,
@@ -47,7 +48,7 @@ pub(crate) enum Deserializer<'ser, 'sig, 'f, F> {
 
 assert_impl_all!(Deserializer<'_, '_, '_, ()>: Send, Sync, Unpin);
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 impl<'de, 'sig, 'f, F> DeserializerCommon<'de, 'sig, 'f, F>
 where
     F: AsFd,
@@ -70,10 +71,11 @@ impl<'de, 'sig, 'f, F> DeserializerCommon<'de, 'sig, 'f, F> {
                 ));
             }
 
-            for i in 0..padding {
-                let byte = self.bytes[self.pos + i];
-                if byte != 0 {
-                    return Err(Error::PaddingNot0(byte));
+            // Lenient contexts ignore the contents of the padding.
+            let padding_bytes = &self.bytes[self.pos..self.pos + padding];
+            if let Some(byte) = padding_bytes.iter().find(|b| **b != 0) {
+                if !self.ctxt.is_lenient() {
+                    return Err(Error::PaddingNot0(*byte));
                 }
             }
             self.pos += padding;
@@ -140,8 +142,14 @@ macro_rules! deserialize_method {
     }
 }
 
-impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
-    for &'d mut Deserializer<'de, 'sig, 'f, F>
+impl<
+        'de,
+        'd,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > de::Deserializer<'de> for &'d mut Deserializer<'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -208,7 +216,7 @@ where
         i16::SIGNATURE_CHAR => de.deserialize_i16(visitor),
         u16::SIGNATURE_CHAR => de.deserialize_u16(visitor),
         i32::SIGNATURE_CHAR => de.deserialize_i32(visitor),
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         Fd::SIGNATURE_CHAR => de.deserialize_i32(visitor),
         u32::SIGNATURE_CHAR => de.deserialize_u32(visitor),
         i64::SIGNATURE_CHAR => de.deserialize_i64(visitor),
@@ -242,7 +250,7 @@ where
 {
     type Error = Error;
 
-    fn unit_variant(self) -> std::result::Result<(), Self::Error> {
+    fn unit_variant(self) -> core::result::Result<(), Self::Error> {
         Ok(())
     }
 
@@ -1,5 +1,4 @@
-use core::str;
-use std::marker::PhantomData;
+use core::{marker::PhantomData, str};
 
 use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
 use static_assertions::assert_impl_all;
@@ -25,7 +24,7 @@ use crate::{Signature, Type, Value};
 /// [`Value`]: enum.Value.html
 pub struct DeserializeValue<'de, T: Type + Deserialize<'de>>(
     pub T,
-    std::marker::PhantomData<&'de T>,
+    core::marker::PhantomData<&'de ()>,
 );
 
 assert_impl_all!(DeserializeValue<'_, i32>: Send, Sync, Unpin);
@@ -52,7 +51,7 @@ struct DeserializeValueVisitor<T>(PhantomData<T>);
 impl<'de, T: Type + Deserialize<'de>> Visitor<'de> for DeserializeValueVisitor<T> {
     type Value = T;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("zvariant::Value")
     }
 
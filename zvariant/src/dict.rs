@@ -1,13 +1,19 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    fmt::{Display, Write},
-    hash::{BuildHasher, Hash},
+use alloc::{collections::BTreeMap, format};
+use core::fmt::{self, Display, Write};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use serde::{
+    de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor},
+    ser::{Serialize, SerializeMap, SerializeSeq, SerializeStruct, Serializer},
 };
-
-use serde::ser::{Serialize, SerializeSeq, SerializeStruct, Serializer};
 use static_assertions::assert_impl_all;
 
-use crate::{value_display_fmt, Basic, DynamicType, Error, Signature, Type, Value};
+#[cfg(all(unix, feature = "std"))]
+use crate::Fd;
+use crate::{value_display_fmt, Basic, DictDiff, DynamicType, Error, Signature, Type, Value};
 
 /// A helper type to wrap dictionaries in a [`Value`].
 ///
@@ -89,6 +95,26 @@ impl<'k, 'v> Dict<'k, 'v> {
         self.map.get(&key).map(|v| v.downcast_ref()).transpose()
     }
 
+    /// Get the value for the given key, if the keys are strings, object paths or signatures.
+    pub(crate) fn get_by_str(&self, key: &str) -> Option<&Value<'v>> {
+        self.map
+            .iter()
+            .find(|(k, _)| match k {
+                Value::Str(k) => k.as_str() == key,
+                Value::ObjectPath(k) => k.as_str() == key,
+                Value::Signature(k) => k.as_str() == key,
+                _ => false,
+            })
+            .map(|(_, v)| v)
+    }
+
+    /// Compare `self` against `new`, entry by entry.
+    ///
+    /// See [`DictDiff`] for details.
+    pub fn diff<'d>(&'d self, new: &'d Dict<'k, 'v>) -> DictDiff<'d, Value<'k>, Value<'v>> {
+        DictDiff::from_entries(&self.map, &new.map, |k| self.map.get(k), |k| new.map.get(k))
+    }
+
     /// Get the signature of this `Dict`.
     ///
     /// NB: This method potentially allocates and copies. Use [`full_signature`] if you'd like to
@@ -138,6 +164,11 @@ impl<'k, 'v> Dict<'k, 'v> {
         })
     }
 
+    #[cfg(any(all(unix, feature = "std"), feature = "serde_json"))]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&Value<'k>, &Value<'v>)> {
+        self.map.iter()
+    }
+
     /// Create a new empty `Dict`, given the complete signature.
     pub(crate) fn new_full_signature<'s: 'k + 'v>(signature: Signature<'s>) -> Self {
         let key_signature = signature.slice(2..3);
@@ -154,17 +185,33 @@ impl<'k, 'v> Dict<'k, 'v> {
     // TODO: Provide more API like https://docs.rs/toml/0.5.5/toml/map/struct.Map.html
 }
 
+#[cfg(all(unix, feature = "std"))]
+impl<'a> Dict<'a, 'a> {
+    pub(crate) fn map_fds<F>(self, f: &mut F) -> Result<Self, Error>
+    where
+        F: FnMut(Fd<'a>) -> Result<Fd<'a>, Error>,
+    {
+        let map = self
+            .map
+            .into_iter()
+            .map(|(k, v)| Ok((k.map_fds_with(f)?, v.map_fds_with(f)?)))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self { map, ..self })
+    }
+}
+
 impl Display for Dict<'_, '_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         dict_display_fmt(self, f, true)
     }
 }
 
 pub(crate) fn dict_display_fmt(
     dict: &Dict<'_, '_>,
-    f: &mut std::fmt::Formatter<'_>,
+    f: &mut core::fmt::Formatter<'_>,
     type_annotate: bool,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     if dict.map.is_empty() {
         if type_annotate {
             write!(f, "@{} ", dict.full_signature())?;
@@ -242,6 +289,7 @@ macro_rules! from_dict {
         }
     };
 }
+#[cfg(feature = "std")]
 from_dict!(HashMap<K: Eq + Hash, V, H>);
 from_dict!(BTreeMap<K: Ord, V>);
 
@@ -276,6 +324,7 @@ macro_rules! to_dict {
         }
     };
 }
+#[cfg(feature = "std")]
 to_dict!(HashMap<K: Eq + Hash, V, H>);
 to_dict!(BTreeMap<K: Ord, V>);
 
@@ -300,6 +349,146 @@ impl Serialize for DictEntry<'_, '_, '_, '_> {
     }
 }
 
+/// Serialization of a struct as entries of a dictionary.
+///
+/// Implemented by the `SerializeDict` macro, so the entries of a struct can be flattened into the
+/// dictionary of another struct. Not part of the public API.
+#[doc(hidden)]
+pub trait SerializeDictEntries {
+    fn serialize_dict_entries<M>(&self, map: &mut M) -> Result<(), M::Error>
+    where
+        M: SerializeMap;
+}
+
+/// Deserialization of a struct from entries of a dictionary.
+///
+/// Implemented by the `DeserializeDict` macro, so the entries of a struct can be flattened into the
+/// dictionary of another struct. Not part of the public API.
+#[doc(hidden)]
+pub trait DeserializeDictEntries<'de>: Sized {
+    /// The fields deserialized so far.
+    type Partial: Default;
+
+    /// Deserialize the value of the entry with the given `key`, if it's one of ours.
+    ///
+    /// Returns `false` if the entry is unknown, in which case its value isn't consumed.
+    fn deserialize_dict_entry<M>(
+        partial: &mut Self::Partial,
+        key: &str,
+        access: &mut M,
+    ) -> Result<bool, M::Error>
+    where
+        M: MapAccess<'de>;
+
+    /// Build the struct from the deserialized fields, once all entries have been consumed.
+    fn from_dict_entries<E>(partial: Self::Partial) -> Result<Self, E>
+    where
+        E: de::Error;
+}
+
+/// The name of the newtype struct our deserializers recognize, to provide a [`DictKey`] along with
+/// the leniency of their context.
+pub(crate) const DICT_KEY_NAME: &str = "zvariant::DictKey";
+
+/// The key of a dictionary entry, deserialized by the `DeserializeDict` macro.
+///
+/// Our deserializers also tell whether their context is lenient, in which case unknown entries are
+/// skipped even with `deny_unknown_fields`. Not part of the public API.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy)]
+pub struct DictKey<'de> {
+    pub key: &'de str,
+    pub lenient: bool,
+}
+
+impl<'de> Deserialize<'de> for DictKey<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(DICT_KEY_NAME, DictKeyVisitor)
+    }
+}
+
+struct DictKeyVisitor;
+
+impl<'de> Visitor<'de> for DictKeyVisitor {
+    type Value = DictKey<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a string key")
+    }
+
+    fn visit_borrowed_str<E>(self, key: &'de str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(DictKey {
+            key,
+            lenient: false,
+        })
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        <&str>::deserialize(deserializer).map(|key| DictKey {
+            key,
+            lenient: false,
+        })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let lenient = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let key = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Ok(DictKey { key, lenient })
+    }
+}
+
+/// Provides the leniency of the context and then the key to the visitor of a [`DictKey`].
+pub(crate) struct DictKeyAccess<D> {
+    lenient: Option<bool>,
+    key: Option<D>,
+}
+
+impl<D> DictKeyAccess<D> {
+    pub(crate) fn new(lenient: bool, key: D) -> Self {
+        Self {
+            lenient: Some(lenient),
+            key: Some(key),
+        }
+    }
+}
+
+impl<'de, D> SeqAccess<'de> for DictKeyAccess<D>
+where
+    D: Deserializer<'de, Error = Error>,
+{
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if let Some(lenient) = self.lenient.take() {
+            return seed
+                .deserialize(de::value::BoolDeserializer::new(lenient))
+                .map(Some);
+        }
+
+        self.key.take().map(|key| seed.deserialize(key)).transpose()
+    }
+}
+
 fn create_signature(
     key_signature: &Signature<'_>,
     value_signature: &Signature<'_>,
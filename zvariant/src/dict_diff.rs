@@ -0,0 +1,152 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// The differences between two dictionaries.
+///
+/// Entries are compared by key: keys only in the new dictionary are added, keys only in the old
+/// one are removed and keys in both, with unequal values, are changed. Everything is borrowed from
+/// the compared dictionaries.
+///
+/// This is the information a `org.freedesktop.DBus.Properties.PropertiesChanged` signal carries,
+/// see [`DictDiff::changed_properties`] and [`DictDiff::invalidated_properties`].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{DictDiff, Value};
+///
+/// let old = HashMap::from([
+///     ("Volume", Value::from(30u8)),
+///     ("Muted", Value::from(false)),
+///     ("Name", Value::from("Speakers")),
+/// ]);
+/// let new = HashMap::from([
+///     ("Volume", Value::from(40u8)),
+///     ("Muted", Value::from(false)),
+///     ("Balance", Value::from(0.5)),
+/// ]);
+///
+/// let diff = DictDiff::new(&old, &new);
+/// assert_eq!(diff.added(), [(&"Balance", &Value::from(0.5))]);
+/// assert_eq!(diff.removed(), [(&"Name", &Value::from("Speakers"))]);
+/// assert_eq!(
+///     diff.changed(),
+///     [(&"Volume", &Value::from(30u8), &Value::from(40u8))]
+/// );
+///
+/// let changed = diff.changed_properties::<str>();
+/// assert_eq!(changed.len(), 2);
+/// assert_eq!(changed["Volume"], &Value::from(40u8));
+/// assert_eq!(diff.invalidated_properties::<str>(), ["Name"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DictDiff<'d, K, V> {
+    added: Vec<(&'d K, &'d V)>,
+    removed: Vec<(&'d K, &'d V)>,
+    changed: Vec<(&'d K, &'d V, &'d V)>,
+}
+
+impl<'d, K, V> DictDiff<'d, K, V> {
+    /// Compare `old` against `new`.
+    #[cfg(feature = "std")]
+    pub fn new<S1, S2>(old: &'d HashMap<K, V, S1>, new: &'d HashMap<K, V, S2>) -> Self
+    where
+        K: Eq + Hash,
+        V: PartialEq,
+        S1: BuildHasher,
+        S2: BuildHasher,
+    {
+        Self::from_entries(old, new, |k| old.get(k), |k| new.get(k))
+    }
+
+    // Compare the entries of `old` against those of `new`, looking up keys in the other
+    // dictionary with `get_old` and `get_new`.
+    pub(crate) fn from_entries<O, N>(
+        old: O,
+        new: N,
+        get_old: impl Fn(&K) -> Option<&'d V>,
+        get_new: impl Fn(&K) -> Option<&'d V>,
+    ) -> Self
+    where
+        O: IntoIterator<Item = (&'d K, &'d V)>,
+        N: IntoIterator<Item = (&'d K, &'d V)>,
+        V: PartialEq,
+    {
+        let mut diff = Self {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (key, value) in new {
+            match get_old(key) {
+                None => diff.added.push((key, value)),
+                Some(old_value) if old_value != value => diff.changed.push((key, old_value, value)),
+                Some(_) => (),
+            }
+        }
+        diff.removed = old
+            .into_iter()
+            .filter(|(key, _)| get_new(key).is_none())
+            .collect();
+
+        diff
+    }
+
+    /// The entries only in the new dictionary.
+    pub fn added(&self) -> &[(&'d K, &'d V)] {
+        &self.added
+    }
+
+    /// The entries only in the old dictionary.
+    pub fn removed(&self) -> &[(&'d K, &'d V)] {
+        &self.removed
+    }
+
+    /// The entries whose value changed, as the key, the old value and the new value.
+    pub fn changed(&self) -> &[(&'d K, &'d V, &'d V)] {
+        &self.changed
+    }
+
+    /// Whether the dictionaries are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The added and changed entries, with their new values.
+    ///
+    /// This is the `changed_properties` argument of a `PropertiesChanged` signal, e.g. with
+    /// `Q = str` for dictionaries keyed by `String` or `&str`.
+    #[cfg(feature = "std")]
+    pub fn changed_properties<Q>(&self) -> HashMap<&'d Q, &'d V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.added
+            .iter()
+            .map(|(key, value)| (*key, *value))
+            .chain(self.changed.iter().map(|(key, _, value)| (*key, *value)))
+            .map(|(key, value)| (key.borrow(), value))
+            .collect()
+    }
+
+    /// The keys of the removed entries.
+    ///
+    /// This is the `invalidated_properties` argument of a `PropertiesChanged` signal, e.g. with
+    /// `Q = str` for dictionaries keyed by `String` or `&str`.
+    pub fn invalidated_properties<Q>(&self) -> Vec<&'d Q>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized,
+    {
+        self.removed
+            .iter()
+            .map(|(key, _)| (*key).borrow())
+            .collect()
+    }
+}
@@ -1,6 +1,9 @@
+use alloc::string::{String, ToString};
+use core::{convert::Infallible, fmt, result};
 use serde::{de, ser};
 use static_assertions::assert_impl_all;
-use std::{convert::Infallible, error, fmt, io, result, sync::Arc};
+#[cfg(feature = "std")]
+use std::{error, io, sync::Arc};
 
 /// Enum representing the max depth exceeded error.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -40,11 +43,12 @@ pub enum Error {
     Message(String),
 
     /// Wrapper for [`std::io::Error`](https://doc.rust-lang.org/std/io/struct.Error.html)
+    #[cfg(feature = "std")]
     InputOutput(Arc<io::Error>),
     /// Type conversions errors.
     IncorrectType,
     /// Wrapper for [`std::str::Utf8Error`](https://doc.rust-lang.org/std/str/struct.Utf8Error.html)
-    Utf8(std::str::Utf8Error),
+    Utf8(core::str::Utf8Error),
     /// Non-0 padding byte(s) encountered.
     PaddingNot0(u8),
     /// The deserialized file descriptor is not in the given FD index.
@@ -79,6 +83,7 @@ impl PartialEq for Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl error::Error for Error {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
@@ -93,6 +98,7 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Message(s) => write!(f, "{s}"),
+            #[cfg(feature = "std")]
             Error::InputOutput(e) => e.fmt(f),
             Error::IncorrectType => write!(f, "incorrect type"),
             Error::Utf8(e) => write!(f, "{e}"),
@@ -123,6 +129,7 @@ impl Clone for Error {
     fn clone(&self) -> Self {
         match self {
             Error::Message(s) => Error::Message(s.clone()),
+            #[cfg(feature = "std")]
             Error::InputOutput(e) => Error::InputOutput(e.clone()),
             Error::IncorrectType => Error::IncorrectType,
             Error::Utf8(e) => Error::Utf8(*e),
@@ -147,6 +154,10 @@ impl From<Infallible> for Error {
     }
 }
 
+// Without `std`, serde requires its own replacement of the `Error` trait instead.
+#[cfg(not(feature = "std"))]
+impl de::StdError for Error {}
+
 impl de::Error for Error {
     // TODO: Add more specific error variants to Error enum above so we can implement other methods
     // here too.
@@ -167,6 +178,7 @@ impl ser::Error for Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
     fn from(val: io::Error) -> Self {
         Error::InputOutput(Arc::new(val))
@@ -89,8 +89,8 @@ where
     }
 }
 
-impl std::fmt::Display for Fd<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Fd<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.as_raw_fd().fmt(f)
     }
 }
@@ -148,19 +148,19 @@ impl PartialEq for Fd<'_> {
 impl Eq for Fd<'_> {}
 
 impl PartialOrd for Fd<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
 impl Ord for Fd<'_> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.as_raw_fd().cmp(&other.as_raw_fd())
     }
 }
 
-impl std::hash::Hash for Fd<'_> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+impl core::hash::Hash for Fd<'_> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.as_raw_fd().hash(state)
     }
 }
@@ -235,8 +235,8 @@ impl From<Fd<'static>> for OwnedFd {
     }
 }
 
-impl std::fmt::Display for OwnedFd {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for OwnedFd {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         self.inner.fmt(f)
     }
 }
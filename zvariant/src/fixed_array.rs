@@ -0,0 +1,324 @@
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData, mem::size_of};
+use serde::{
+    de::{
+        value::{BoolDeserializer, BorrowedBytesDeserializer, CharDeserializer},
+        Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor,
+    },
+    ser::{Serialize, SerializeSeq, Serializer},
+};
+use static_assertions::assert_impl_all;
+
+use crate::{Basic, Endian, Error, Signature, Type, NATIVE_ENDIAN};
+
+/// A borrowed array of fixed-sized numbers, e.g `ay`, `ai` or `ad`.
+///
+/// Deserializing a `FixedArray` borrows the encoded elements from the serialized data, instead of
+/// allocating and decoding each of them, like `Vec<T>` does. The elements are then decoded on
+/// access, taking the byte order of the data into account. When the data is in the native byte
+/// order and suitably aligned, [`FixedArray::as_slice`] gives direct access to the elements.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, FixedArray, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &vec![1u64, 2, 3]).unwrap();
+/// let array: FixedArray<'_, u64> = encoded.deserialize().unwrap().0;
+/// assert_eq!(array.len(), 3);
+/// assert_eq!(array.get(1), Some(2));
+/// assert_eq!(array.iter().sum::<u64>(), 6);
+/// ```
+#[derive(Clone, Copy)]
+pub struct FixedArray<'a, T> {
+    bytes: &'a [u8],
+    endian: Endian,
+    phantom: PhantomData<T>,
+}
+
+assert_impl_all!(FixedArray<'_, u64>: Send, Sync, Unpin);
+
+impl<'a, T> FixedArray<'a, T>
+where
+    T: FixedArrayElement,
+{
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.bytes.len() / size_of::<T>()
+    }
+
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Get the element at index `idx`, if any.
+    pub fn get(&self, idx: usize) -> Option<T> {
+        let size = size_of::<T>();
+        let start = idx.checked_mul(size)?;
+        let end = start.checked_add(size)?;
+
+        self.bytes
+            .get(start..end)
+            .map(|bytes| T::read(self.endian, bytes))
+    }
+
+    /// Iterate over the elements.
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let endian = self.endian;
+
+        self.bytes
+            .chunks_exact(size_of::<T>())
+            .map(move |bytes| T::read(endian, bytes))
+    }
+
+    /// The elements, if they're in the native byte order and suitably aligned for `T`.
+    ///
+    /// This never fails for `FixedArray<'_, u8>`.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        if self.endian != NATIVE_ENDIAN && size_of::<T>() > 1 {
+            return None;
+        }
+
+        // SAFETY: All the element types are plain numbers, valid for any bit pattern.
+        let (prefix, elements, suffix) = unsafe { self.bytes.align_to::<T>() };
+
+        (prefix.is_empty() && suffix.is_empty()).then_some(elements)
+    }
+
+    /// Copy the elements into a `Vec`.
+    pub fn to_vec(&self) -> Vec<T> {
+        match self.as_slice() {
+            Some(elements) => elements.to_vec(),
+            None => self.iter().collect(),
+        }
+    }
+
+    /// The encoded elements.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The byte order of the encoded elements.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+}
+
+impl<'a, T> From<&'a [T]> for FixedArray<'a, T>
+where
+    T: FixedArrayElement,
+{
+    fn from(elements: &'a [T]) -> Self {
+        // SAFETY: All the element types are plain numbers, without any padding.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(elements.as_ptr().cast(), core::mem::size_of_val(elements))
+        };
+
+        Self {
+            bytes,
+            endian: NATIVE_ENDIAN,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> fmt::Debug for FixedArray<'_, T>
+where
+    T: FixedArrayElement + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for FixedArray<'_, T>
+where
+    T: FixedArrayElement + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Type for FixedArray<'_, T>
+where
+    T: FixedArrayElement,
+{
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
+impl<T> Serialize for FixedArray<'_, T>
+where
+    T: FixedArrayElement + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for element in self.iter() {
+            seq.serialize_element(&element)?;
+        }
+
+        seq.end()
+    }
+}
+
+impl<'de: 'a, 'a, T> Deserialize<'de> for FixedArray<'a, T>
+where
+    T: FixedArrayElement,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(T::FIXED_ARRAY_NAME, FixedArrayVisitor(PhantomData))
+    }
+}
+
+struct FixedArrayVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for FixedArrayVisitor<T>
+where
+    T: FixedArrayElement,
+{
+    type Value = FixedArray<'de, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an array of fixed-sized numbers")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        use serde::de::{Error, Unexpected};
+
+        let element: char = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        if element != T::SIGNATURE_CHAR {
+            return Err(A::Error::invalid_value(Unexpected::Char(element), &self));
+        }
+        let big_endian: bool = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let bytes: &'de [u8] = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(2, &self))?;
+        if bytes.len() % size_of::<T>() != 0 {
+            return Err(A::Error::invalid_length(bytes.len(), &self));
+        }
+
+        Ok(FixedArray {
+            bytes,
+            endian: if big_endian {
+                Endian::Big
+            } else {
+                Endian::Little
+            },
+            phantom: PhantomData,
+        })
+    }
+}
+
+/// The types that can be the elements of a [`FixedArray`].
+///
+/// This trait is sealed and can't be implemented outside of this crate.
+pub trait FixedArrayElement: Basic + Copy + sealed::Sealed {
+    #[doc(hidden)]
+    const FIXED_ARRAY_NAME: &'static str;
+
+    #[doc(hidden)]
+    fn read(endian: Endian, bytes: &[u8]) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! fixed_array_element {
+    ($ty:ty, $signature:literal, $read:ident) => {
+        impl sealed::Sealed for $ty {}
+
+        impl FixedArrayElement for $ty {
+            const FIXED_ARRAY_NAME: &'static str =
+                concat!("zvariant::FixedArray<", $signature, ">");
+
+            fn read(endian: Endian, bytes: &[u8]) -> Self {
+                endian.$read(bytes)
+            }
+        }
+    };
+}
+
+fixed_array_element!(u8, "y", read_u8);
+fixed_array_element!(i16, "n", read_i16);
+fixed_array_element!(u16, "q", read_u16);
+fixed_array_element!(i32, "i", read_i32);
+fixed_array_element!(u32, "u", read_u32);
+fixed_array_element!(i64, "x", read_i64);
+fixed_array_element!(u64, "t", read_u64);
+fixed_array_element!(f64, "d", read_f64);
+
+/// The element signature of a [`FixedArray`], if `name` is that of the newtype struct our
+/// deserializers recognize, to borrow the encoded elements of an array along with their byte order.
+pub(crate) fn fixed_array_element(name: &str) -> Option<char> {
+    let element = name
+        .strip_prefix("zvariant::FixedArray<")?
+        .strip_suffix('>')?;
+
+    match element.as_bytes() {
+        [c] => Some(*c as char),
+        _ => None,
+    }
+}
+
+/// Whether the signature is that of an array of fixed-sized numbers of `element` signature.
+pub(crate) fn is_fixed_array_signature(signature: &str, element: char) -> bool {
+    matches!(signature.as_bytes(), [b'a', c] if *c as char == element && b"ynqiuxtd".contains(c))
+}
+
+/// Provides the byte order and the encoded elements of a [`FixedArray`] to its visitor.
+pub(crate) struct FixedArrayAccess<'de> {
+    element: Option<char>,
+    endian: Option<Endian>,
+    bytes: Option<&'de [u8]>,
+}
+
+impl<'de> FixedArrayAccess<'de> {
+    pub(crate) fn new(element: char, endian: Endian, bytes: &'de [u8]) -> Self {
+        Self {
+            element: Some(element),
+            endian: Some(endian),
+            bytes: Some(bytes),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for FixedArrayAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if let Some(element) = self.element.take() {
+            return seed.deserialize(CharDeserializer::new(element)).map(Some);
+        }
+        if let Some(endian) = self.endian.take() {
+            let big_endian = BoolDeserializer::new(endian == Endian::Big);
+
+            return seed.deserialize(big_endian).map(Some);
+        }
+
+        self.bytes
+            .take()
+            .map(|bytes| seed.deserialize(BorrowedBytesDeserializer::new(bytes)))
+            .transpose()
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{Error, Result, WriteBytes, LE};
+use crate::{
+    io::{Write, WriteBytes},
+    Error, Result, LE,
+};
 
 // Used internally for GVariant encoding and decoding.
 //
@@ -35,7 +38,7 @@ impl FramingOffsetSize {
 
     pub(crate) fn write_offset<W>(self, writer: &mut W, offset: usize) -> Result<()>
     where
-        W: std::io::Write,
+        W: Write,
     {
         match self {
             FramingOffsetSize::U8 => writer.write_u8(LE, offset as u8),
@@ -44,7 +47,7 @@ impl FramingOffsetSize {
             #[cfg(not(target_pointer_width = "32"))]
             FramingOffsetSize::U64 => writer.write_u64(LE, offset as u64),
         }
-        .map_err(|e| Error::InputOutput(e.into()))
+        .map_err(Error::from)
     }
 
     pub fn read_last_offset_from_buffer(self, buffer: &[u8]) -> usize {
@@ -64,11 +67,11 @@ impl FramingOffsetSize {
 
     fn max(self) -> usize {
         match self {
-            FramingOffsetSize::U8 => std::u8::MAX as usize,
-            FramingOffsetSize::U16 => std::u16::MAX as usize,
-            FramingOffsetSize::U32 => std::u32::MAX as usize,
+            FramingOffsetSize::U8 => core::u8::MAX as usize,
+            FramingOffsetSize::U16 => core::u16::MAX as usize,
+            FramingOffsetSize::U32 => core::u32::MAX as usize,
             #[cfg(not(target_pointer_width = "32"))]
-            FramingOffsetSize::U64 => std::u64::MAX as usize,
+            FramingOffsetSize::U64 => core::u64::MAX as usize,
         }
     }
 
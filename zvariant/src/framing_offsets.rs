@@ -1,5 +1,5 @@
-use crate::{framing_offset_size::FramingOffsetSize, Result};
-use std::collections::VecDeque;
+use crate::{framing_offset_size::FramingOffsetSize, io::Write, Result};
+use alloc::{collections::VecDeque, format};
 
 // Used internally for GVariant encoding and decoding.
 //
@@ -64,7 +64,7 @@ impl FramingOffsets {
 
     pub fn write_all<W>(self, writer: &mut W, container_len: usize) -> Result<()>
     where
-        W: std::io::Write,
+        W: Write,
     {
         if self.is_empty() {
             return Ok(());
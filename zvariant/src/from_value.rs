@@ -5,10 +5,14 @@ use crate::{
     Signature, Str, Structure, Value,
 };
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
-use std::{collections::HashMap, hash::BuildHasher};
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 macro_rules! value_try_from {
     ($kind:ident, $to:ty) => {
@@ -117,13 +121,66 @@ value_try_from_ref!(Maybe, Maybe<'a>);
 #[cfg(feature = "gvariant")]
 value_try_from_ref_try_clone!(Maybe, Maybe<'a>);
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 value_try_from!(Fd, Fd<'a>);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 value_try_from_ref!(Fd, Fd<'a>);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 value_try_from_ref_try_clone!(Fd, Fd<'a>);
 
+// `TryFrom<Value<'a>> for Option<T>` can't be implemented generically, as it would conflict with
+// `From<T> for Option<T>` from core when `T` is `Value` itself.
+#[cfg(any(feature = "gvariant", feature = "option-as-array"))]
+macro_rules! value_try_from_option {
+    ($($to:ty),*) => {
+        $(
+            impl<'a> TryFrom<Value<'a>> for Option<$to> {
+                type Error = Error;
+
+                fn try_from(value: Value<'a>) -> Result<Self, Self::Error> {
+                    value_into_option(value)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(any(feature = "gvariant", feature = "option-as-array"))]
+value_try_from_option!(
+    u8,
+    bool,
+    i16,
+    u16,
+    i32,
+    u32,
+    i64,
+    u64,
+    f64,
+    Str<'a>,
+    Signature<'a>,
+    ObjectPath<'a>,
+    String,
+    OwnedObjectPath,
+    OwnedSignature
+);
+
+// The inverse of `From<Option<T>> for Value`: a `Maybe` in GVariant and an array of at most one
+// element with the `option-as-array` feature.
+#[cfg(any(feature = "gvariant", feature = "option-as-array"))]
+fn value_into_option<'a, T>(value: Value<'a>) -> Result<Option<T>, Error>
+where
+    T: TryFrom<Value<'a>>,
+    T::Error: Into<Error>,
+{
+    match value {
+        #[cfg(feature = "gvariant")]
+        Value::Maybe(maybe) => maybe.downcast(),
+        #[cfg(feature = "option-as-array")]
+        Value::Array(array) if array.len() <= 1 => Vec::<T>::try_from(array).map(|mut v| v.pop()),
+        _ => Err(Error::IncorrectType),
+    }
+}
+
 impl TryFrom<&Value<'_>> for String {
     type Error = Error;
 
@@ -180,9 +237,10 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, K, V, H> TryFrom<Value<'a>> for HashMap<K, V, H>
 where
-    K: crate::Basic + TryFrom<Value<'a>> + std::hash::Hash + std::cmp::Eq,
+    K: crate::Basic + TryFrom<Value<'a>> + core::hash::Hash + core::cmp::Eq,
     V: TryFrom<Value<'a>>,
     H: BuildHasher + Default,
     K::Error: Into<crate::Error>,
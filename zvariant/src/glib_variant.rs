@@ -0,0 +1,264 @@
+//! Conversion between [`Value`] and [`glib::Variant`].
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use glib::{variant::ToVariant, Variant, VariantClass, VariantTy};
+
+#[cfg(feature = "gvariant")]
+use crate::Maybe;
+use crate::{
+    Array, Dict, Error, ObjectPath, OwnedValue, Result, Signature, StructureBuilder, Value,
+};
+
+/// Convert a [`Value`] to a [`glib::Variant`].
+///
+/// The conversion is done value by value, without going through the serialized form. Variants,
+/// arrays, dictionaries, structures and (with the `gvariant` feature) maybe values keep their
+/// types, so the resulting `Variant` has the same signature as `value`.
+///
+/// # Errors
+///
+/// If `value` contains file descriptors, since they don't mean anything to GLib outside of a
+/// message.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::Value;
+///
+/// let v = Value::from(HashMap::from([("answer", Value::from(42u32))]));
+/// let variant = glib::Variant::try_from(&v).unwrap();
+/// assert_eq!(variant.type_().as_str(), "a{sv}");
+/// assert_eq!(variant.to_string(), "{'answer': <uint32 42>}");
+///
+/// let decoded = Value::try_from(&variant).unwrap();
+/// assert_eq!(decoded, v);
+/// ```
+impl TryFrom<&Value<'_>> for Variant {
+    type Error = Error;
+
+    fn try_from(value: &Value<'_>) -> Result<Self> {
+        let variant = match value {
+            Value::U8(v) => v.to_variant(),
+            Value::Bool(v) => v.to_variant(),
+            Value::I16(v) => v.to_variant(),
+            Value::U16(v) => v.to_variant(),
+            Value::I32(v) => v.to_variant(),
+            Value::U32(v) => v.to_variant(),
+            Value::I64(v) => v.to_variant(),
+            Value::U64(v) => v.to_variant(),
+            Value::F64(v) => v.to_variant(),
+            Value::Str(v) => v.as_str().to_variant(),
+            Value::Signature(v) => glib::variant::Signature::try_from(v.as_str())
+                .map_err(glib_error)?
+                .to_variant(),
+            Value::ObjectPath(v) => glib::variant::ObjectPath::try_from(v.as_str())
+                .map_err(glib_error)?
+                .to_variant(),
+            Value::Value(v) => Variant::from_variant(&Variant::try_from(&**v)?),
+            Value::Array(array) => {
+                let element_type = variant_type(array.element_signature())?;
+                let elements = array
+                    .inner()
+                    .iter()
+                    .map(Variant::try_from)
+                    .collect::<Result<Vec<_>>>()?;
+
+                Variant::array_from_iter_with_type(element_type, elements)
+            }
+            Value::Dict(dict) => {
+                // The entry type, i-e the signature without the leading `a`.
+                let entry_signature = dict.full_signature().slice(1..);
+                let entry_type = variant_type(&entry_signature)?;
+                let entries = dict
+                    .iter()
+                    .map(|(key, value)| {
+                        Ok(Variant::from_dict_entry(
+                            &Variant::try_from(key)?,
+                            &Variant::try_from(value)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Variant::array_from_iter_with_type(entry_type, entries)
+            }
+            Value::Structure(structure) => {
+                let fields = structure
+                    .fields()
+                    .iter()
+                    .map(Variant::try_from)
+                    .collect::<Result<Vec<_>>>()?;
+
+                Variant::tuple_from_iter(fields)
+            }
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(maybe) => match maybe.inner() {
+                Some(value) => Variant::from_some(&Variant::try_from(value)?),
+                None => Variant::from_none(variant_type(maybe.value_signature())?),
+            },
+            #[cfg(all(unix, feature = "std"))]
+            Value::Fd(_) => {
+                return Err(Error::Message(
+                    "file descriptors can't be converted to a `glib::Variant`".to_string(),
+                ))
+            }
+        };
+
+        Ok(variant)
+    }
+}
+
+impl TryFrom<Value<'_>> for Variant {
+    type Error = Error;
+
+    fn try_from(value: Value<'_>) -> Result<Self> {
+        Variant::try_from(&value)
+    }
+}
+
+impl TryFrom<&OwnedValue> for Variant {
+    type Error = Error;
+
+    fn try_from(value: &OwnedValue) -> Result<Self> {
+        Variant::try_from(&**value)
+    }
+}
+
+impl TryFrom<OwnedValue> for Variant {
+    type Error = Error;
+
+    fn try_from(value: OwnedValue) -> Result<Self> {
+        Variant::try_from(&*value)
+    }
+}
+
+/// Convert a [`glib::Variant`] to a [`Value`].
+///
+/// This is the reverse of the conversion from [`Value`] to [`glib::Variant`]. Arrays of dictionary
+/// entries are converted to [`Dict`]s.
+///
+/// # Errors
+///
+/// If `variant` contains types that `Value` can't represent: handles, standalone dictionary
+/// entries, empty tuples and, without the `gvariant` feature, maybe values.
+impl TryFrom<&Variant> for Value<'static> {
+    type Error = Error;
+
+    fn try_from(variant: &Variant) -> Result<Self> {
+        let unsupported = || {
+            Error::Message(format!(
+                "`glib::Variant` of type `{}` can't be converted to a `Value`",
+                variant.type_()
+            ))
+        };
+
+        let value = match variant.classify() {
+            VariantClass::Byte => Value::U8(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Boolean => Value::Bool(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Int16 => Value::I16(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Uint16 => Value::U16(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Int32 => Value::I32(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Uint32 => Value::U32(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Int64 => Value::I64(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Uint64 => Value::U64(variant.get().ok_or_else(unsupported)?),
+            VariantClass::Double => Value::F64(variant.get().ok_or_else(unsupported)?),
+            VariantClass::String => Value::from(variant.str().ok_or_else(unsupported)?.to_string()),
+            VariantClass::Signature => {
+                let s = variant.str().ok_or_else(unsupported)?;
+
+                Value::Signature(Signature::try_from(s.to_string())?)
+            }
+            VariantClass::ObjectPath => {
+                let s = variant.str().ok_or_else(unsupported)?;
+
+                Value::ObjectPath(ObjectPath::try_from(s.to_string())?)
+            }
+            VariantClass::Variant => {
+                let inner = variant.as_variant().ok_or_else(unsupported)?;
+
+                Value::Value(Box::new(Value::try_from(&inner)?))
+            }
+            VariantClass::Array => {
+                let element_type = variant.type_().element();
+                if element_type.is_dict_entry() {
+                    let mut dict = Dict::new(
+                        signature(element_type.key())?,
+                        signature(element_type.value())?,
+                    );
+                    for entry in variant.iter() {
+                        dict.append(
+                            Value::try_from(&entry.child_value(0))?,
+                            Value::try_from(&entry.child_value(1))?,
+                        )?;
+                    }
+
+                    Value::Dict(dict)
+                } else {
+                    let mut array = Array::new(signature(element_type)?);
+                    for element in variant.iter() {
+                        array.append(Value::try_from(&element)?)?;
+                    }
+
+                    Value::Array(array)
+                }
+            }
+            VariantClass::Tuple if variant.n_children() > 0 => {
+                let mut builder = StructureBuilder::new();
+                for field in variant.iter() {
+                    builder = builder.append_field(Value::try_from(&field)?);
+                }
+
+                Value::Structure(builder.build())
+            }
+            #[cfg(feature = "gvariant")]
+            VariantClass::Maybe => match variant.as_maybe() {
+                Some(inner) => Value::Maybe(Maybe::just(Value::try_from(&inner)?)),
+                None => Value::Maybe(Maybe::nothing(signature(variant.type_().element())?)),
+            },
+            _ => return Err(unsupported()),
+        };
+
+        Ok(value)
+    }
+}
+
+impl TryFrom<Variant> for Value<'static> {
+    type Error = Error;
+
+    fn try_from(variant: Variant) -> Result<Self> {
+        Value::try_from(&variant)
+    }
+}
+
+impl TryFrom<&Variant> for OwnedValue {
+    type Error = Error;
+
+    fn try_from(variant: &Variant) -> Result<Self> {
+        Value::try_from(variant).and_then(OwnedValue::try_from)
+    }
+}
+
+impl TryFrom<Variant> for OwnedValue {
+    type Error = Error;
+
+    fn try_from(variant: Variant) -> Result<Self> {
+        OwnedValue::try_from(&variant)
+    }
+}
+
+fn variant_type<'s>(signature: &'s Signature<'_>) -> Result<&'s VariantTy> {
+    VariantTy::new(signature.as_str()).map_err(glib_error)
+}
+
+fn signature(variant_type: &VariantTy) -> Result<Signature<'static>> {
+    Signature::try_from(String::from(variant_type.as_str()))
+}
+
+fn glib_error(e: glib::BoolError) -> Error {
+    Error::Message(e.to_string())
+}
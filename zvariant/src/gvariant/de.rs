@@ -1,18 +1,23 @@
+use alloc::{format, vec::Vec};
 use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, Visitor};
 use static_assertions::assert_impl_all;
 
-use std::{ffi::CStr, marker::PhantomData, str};
+use core::{ffi::CStr, marker::PhantomData, str};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use std::os::fd::AsFd;
 
 use crate::{
+    array_view::{ArrayViewAccess, ARRAY_VIEW_NAME},
     de::{DeserializerCommon, ValueParseStage},
+    dict::{DictKeyAccess, DICT_KEY_NAME},
+    fixed_array::{fixed_array_element, is_fixed_array_signature, FixedArrayAccess},
     framing_offset_size::FramingOffsetSize,
     framing_offsets::FramingOffsets,
     serialized::{Context, Format},
     signature_parser::SignatureParser,
     utils::*,
+    validate::Skip,
     Basic, Error, Result, Signature,
 };
 
@@ -28,7 +33,7 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
     /// On Windows, the function doesn't have `fds` argument.
     pub fn new<'r: 'de, S>(
         bytes: &'r [u8],
-        #[cfg(unix)] fds: Option<&'f [F]>,
+        #[cfg(all(unix, feature = "std"))] fds: Option<&'f [F]>,
         signature: S,
         ctxt: Context,
     ) -> Result<Self>
@@ -44,9 +49,9 @@ impl<'de, 'sig, 'f, F> Deserializer<'de, 'sig, 'f, F> {
             ctxt,
             sig_parser,
             bytes,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             fds,
-            #[cfg(not(unix))]
+            #[cfg(not(all(unix, feature = "std")))]
             fds: PhantomData,
             pos: 0,
             container_depths: Default::default(),
@@ -60,7 +65,8 @@ macro_rules! deserialize_basic {
         where
             V: Visitor<'de>,
         {
-            let ctxt = Context::new_dbus(self.0.ctxt.endian(), self.0.ctxt.position() + self.0.pos);
+            let ctxt = Context::new_dbus(self.0.ctxt.endian(), self.0.ctxt.position() + self.0.pos)
+                .with_lenient(self.0.ctxt.is_lenient());
 
             let mut dbus_de = crate::dbus::Deserializer::<F>(DeserializerCommon::<F> {
                 ctxt,
@@ -96,8 +102,14 @@ macro_rules! deserialize_as {
     }
 }
 
-impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deserializer<'de>
-    for &'d mut Deserializer<'de, 'sig, 'f, F>
+impl<
+        'de,
+        'd,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > de::Deserializer<'de> for &'d mut Deserializer<'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -153,7 +165,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     {
         let slice = subslice(self.0.bytes, self.0.pos..)?;
         let s = if self.0.sig_parser.next_char()? == VARIANT_SIGNATURE_CHAR {
-            if slice.contains(&0) {
+            if contains_nul(slice) {
                 return Err(serde::de::Error::invalid_value(
                     serde::de::Unexpected::Char('\0'),
                     &"GVariant string type must not contain interior null bytes",
@@ -161,7 +173,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
             }
 
             // GVariant decided to skip the trailing nul at the end of signature string
-            str::from_utf8(slice).map_err(Error::Utf8)?
+            str_from_utf8(slice)?
         } else {
             let cstr = CStr::from_bytes_with_nul(slice).map_err(|_| -> Error {
                 let unexpected = if self.0.bytes.is_empty() {
@@ -173,7 +185,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
 
                 de::Error::invalid_value(unexpected, &"nul byte expected at the end of strings")
             })?;
-            let s = cstr.to_str().map_err(Error::Utf8)?;
+            let s = str_from_utf8(cstr.to_bytes())?;
             self.0.pos += s.len() + 1; // string and trailing null byte
 
             s
@@ -212,11 +224,7 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
 
             visitor.visit_none()
         } else {
-            let ctxt = Context::new(
-                self.0.ctxt.format(),
-                self.0.ctxt.endian(),
-                self.0.ctxt.position() + self.0.pos,
-            );
+            let ctxt = self.0.ctxt.offset_by(self.0.pos);
             let end = if fixed_sized_child {
                 self.0.bytes.len()
             } else {
@@ -277,10 +285,25 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
         visitor.visit_unit()
     }
 
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if let Some(element) = fixed_array_element(name) {
+            let endian = self.0.ctxt.endian();
+            let bytes = deserialize_fixed_array(self, element)?;
+
+            return visitor.visit_seq(FixedArrayAccess::new(element, endian, bytes));
+        }
+        if name == ARRAY_VIEW_NAME {
+            return visitor.visit_seq(deserialize_array_view(self)?);
+        }
+        if name == DICT_KEY_NAME {
+            let lenient = self.0.ctxt.is_lenient();
+
+            return visitor.visit_seq(DictKeyAccess::new(lenient, self));
+        }
+
         visitor.visit_newtype_struct(self)
     }
 
@@ -394,19 +417,77 @@ impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> de::Deseriali
     }
 }
 
-fn deserialize_ay<'de, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>(
+fn deserialize_ay<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
     de: &mut Deserializer<'de, '_, '_, F>,
 ) -> Result<&'de [u8]> {
     if de.0.sig_parser.next_signature()? != "ay" {
         return Err(de::Error::invalid_type(de::Unexpected::Seq, &"ay"));
     }
 
+    deserialize_fixed_array(de, 'y')
+}
+
+fn deserialize_fixed_array<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+    element: char,
+) -> Result<&'de [u8]> {
+    if !is_fixed_array_signature(&de.0.sig_parser.next_signature()?, element) {
+        return Err(de::Error::invalid_type(
+            de::Unexpected::Seq,
+            &"array of fixed-sized numbers",
+        ));
+    }
+
     de.0.sig_parser.skip_char()?;
     let ad = ArrayDeserializer::new(de)?;
-    let len = dbg!(ad.len);
+    let len = ad.len;
+    de.0.sig_parser.skip_char()?;
     de.0.next_slice(len)
 }
 
+fn deserialize_array_view<
+    'de,
+    #[cfg(all(unix, feature = "std"))] F: AsFd,
+    #[cfg(not(all(unix, feature = "std")))] F,
+>(
+    de: &mut Deserializer<'de, '_, '_, F>,
+) -> Result<ArrayViewAccess<'de>> {
+    let signature = de.0.sig_parser.next_signature()?;
+    if !signature.starts_with(ARRAY_SIGNATURE_CHAR)
+        || signature[1..].starts_with(DICT_ENTRY_SIG_START_CHAR)
+    {
+        return Err(de::Error::invalid_type(
+            de::Unexpected::Other(signature.as_str()),
+            &"an array that isn't a dictionary",
+        ));
+    }
+    let element_signature = signature.slice(1..).to_owned();
+
+    de.0.sig_parser.skip_char()?;
+    let mut ad = ArrayDeserializer::new(de)?;
+    let start = ad.start;
+    let ctxt = ad.de.0.ctxt.offset_by(start);
+    // Go through the elements without decoding them, only noting where each of them ends.
+    let mut ends = Vec::new();
+    while let Some(()) = ad.next_element_seed(Skip {
+        signature: element_signature.clone(),
+    })? {
+        ends.push(ad.de.0.pos - start);
+    }
+    let end = ends.last().copied().unwrap_or(0);
+    let bytes = subslice(de.0.bytes, start..start + end)?;
+
+    Ok(ArrayViewAccess::new(&element_signature, ctxt, bytes, ends))
+}
+
 struct ArrayDeserializer<'d, 'de, 'sig, 'f, F> {
     de: &'d mut Deserializer<'de, 'sig, 'f, F>,
     len: usize,
@@ -423,8 +504,14 @@ struct ArrayDeserializer<'d, 'de, 'sig, 'f, F> {
     key_offset_size: Option<FramingOffsetSize>,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
-    ArrayDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > ArrayDeserializer<'d, 'de, 'sig, 'f, F>
 {
     fn new(de: &'d mut Deserializer<'de, 'sig, 'f, F>) -> Result<Self> {
         de.0.container_depths = de.0.container_depths.inc_array()?;
@@ -508,8 +595,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
     }
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for ArrayDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for ArrayDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -528,11 +621,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
             return Ok(None);
         }
 
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self.de.0.ctxt.offset_by(self.de.0.pos);
         let end = self.element_end(true)?;
 
         let mut de = Deserializer::<F>(DeserializerCommon {
@@ -559,8 +648,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
     }
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de>
-    for ArrayDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > MapAccess<'de> for ArrayDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -581,11 +676,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
 
         self.de.0.parse_padding(self.element_alignment)?;
 
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self.de.0.ctxt.offset_by(self.de.0.pos);
         let element_end = self.element_end(false)?;
 
         let key_end = match self.key_offset_size {
@@ -627,11 +718,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> MapAccess<'de
     where
         V: DeserializeSeed<'de>,
     {
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self.de.0.ctxt.offset_by(self.de.0.pos);
         let element_end = self.element_end(true)?;
         let value_end = match self.key_offset_size {
             Some(key_offset_size) => element_end - key_offset_size as usize,
@@ -679,8 +766,14 @@ struct StructureDeserializer<'d, 'de, 'sig, 'f, F> {
     offset_size: FramingOffsetSize,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for StructureDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for StructureDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -688,11 +781,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
     where
         T: DeserializeSeed<'de>,
     {
-        let ctxt = Context::new(
-            self.de.0.ctxt.format(),
-            self.de.0.ctxt.endian(),
-            self.de.0.ctxt.position() + self.de.0.pos,
-        );
+        let ctxt = self.de.0.ctxt.offset_by(self.de.0.pos);
         let element_signature = self.de.0.sig_parser.next_signature()?;
         let fixed_sized_element = crate::utils::is_fixed_sized_signature(&element_signature)?;
         let element_end = if !fixed_sized_element {
@@ -761,8 +850,14 @@ struct ValueDeserializer<'d, 'de, 'sig, 'f, F> {
     value_end: usize,
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
-    ValueDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > ValueDeserializer<'d, 'de, 'sig, 'f, F>
 {
     fn new(de: &'d mut Deserializer<'de, 'sig, 'f, F>) -> Result<Self> {
         // GVariant format has signature at the end
@@ -805,8 +900,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F>
     }
 }
 
-impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de>
-    for ValueDeserializer<'d, 'de, 'sig, 'f, F>
+impl<
+        'd,
+        'de,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > SeqAccess<'de> for ValueDeserializer<'d, 'de, 'sig, 'f, F>
 {
     type Error = Error;
 
@@ -841,11 +942,7 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
                 let signature = Signature::try_from(slice)?;
                 let sig_parser = SignatureParser::new(signature);
 
-                let ctxt = Context::new(
-                    self.de.0.ctxt.format(),
-                    self.de.0.ctxt.endian(),
-                    self.de.0.ctxt.position() + self.value_start,
-                );
+                let ctxt = self.de.0.ctxt.offset_by(self.value_start);
                 let mut de = Deserializer::<F>(DeserializerCommon {
                     ctxt,
                     sig_parser,
@@ -866,8 +963,14 @@ impl<'d, 'de, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> SeqAccess<'de
     }
 }
 
-impl<'de, 'd, 'sig, 'f, #[cfg(unix)] F: AsFd, #[cfg(not(unix))] F> EnumAccess<'de>
-    for crate::de::Enum<&'d mut Deserializer<'de, 'sig, 'f, F>, F>
+impl<
+        'de,
+        'd,
+        'sig,
+        'f,
+        #[cfg(all(unix, feature = "std"))] F: AsFd,
+        #[cfg(not(all(unix, feature = "std")))] F,
+    > EnumAccess<'de> for crate::de::Enum<&'d mut Deserializer<'de, 'sig, 'f, F>, F>
 {
     type Error = Error;
     type Variant = Self;
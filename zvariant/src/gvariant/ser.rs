@@ -1,14 +1,13 @@
+use alloc::{format, string::ToString};
+use core::str;
 use serde::{ser, ser::SerializeSeq, Serialize};
 use static_assertions::assert_impl_all;
-use std::{
-    io::{Seek, Write},
-    str,
-};
 
 use crate::{
     container_depths::ContainerDepths,
     framing_offset_size::FramingOffsetSize,
     framing_offsets::FramingOffsets,
+    io::{Seek, Write},
     serialized::{Context, Format},
     signature_parser::SignatureParser,
     utils::*,
@@ -30,7 +29,7 @@ where
     pub fn new<'w: 'ser, 'f: 'ser, S>(
         signature: S,
         writer: &'w mut W,
-        #[cfg(unix)] fds: &'f mut crate::ser::FdList,
+        #[cfg(all(unix, feature = "std"))] fds: &'f mut crate::ser::FdList,
         ctxt: Context,
     ) -> Result<Self>
     where
@@ -45,7 +44,7 @@ where
             ctxt,
             sig_parser,
             writer,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             fds,
             bytes_written: 0,
             value_sign: None,
@@ -76,9 +75,7 @@ where
                 self.0.container_depths = self.0.container_depths.dec_maybe();
 
                 if !fixed_sized_child {
-                    self.0
-                        .write_all(&b"\0"[..])
-                        .map_err(|e| Error::InputOutput(e.into()))?;
+                    self.0.write_all(&b"\0"[..]).map_err(Error::from)?;
                 }
             }
             None => {
@@ -99,7 +96,7 @@ macro_rules! serialize_basic {
                 ctxt,
                 sig_parser: self.0.sig_parser.clone(),
                 writer: &mut self.0.writer,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 fds: self.0.fds,
                 bytes_written,
                 value_sign: None,
@@ -177,22 +174,15 @@ where
         // Strings in GVariant format require no alignment.
 
         self.0.sig_parser.skip_char()?;
-        self.0
-            .write_all(v.as_bytes())
-            .map_err(|e| Error::InputOutput(e.into()))?;
-        self.0
-            .write_all(&b"\0"[..])
-            .map_err(|e| Error::InputOutput(e.into()))?;
+        self.0.write_all(v.as_bytes()).map_err(Error::from)?;
+        self.0.write_all(&b"\0"[..]).map_err(Error::from)?;
 
         Ok(())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
         let seq = self.serialize_seq(Some(v.len()))?;
-        seq.ser
-            .0
-            .write(v)
-            .map_err(|e| Error::InputOutput(e.into()))?;
+        seq.ser.0.write(v).map_err(Error::from)?;
         seq.end()
     }
 
@@ -223,9 +213,7 @@ where
     }
 
     fn serialize_unit(self) -> Result<()> {
-        self.0
-            .write_all(&b"\0"[..])
-            .map_err(|e| Error::InputOutput(e.into()))
+        self.0.write_all(&b"\0"[..]).map_err(Error::from)
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
@@ -485,11 +473,8 @@ where
 
         ser.0.sig_parser.skip_char()?;
 
-        let offsets = if c == STRUCT_SIG_START_CHAR {
-            Some(FramingOffsets::new())
-        } else {
-            None
-        };
+        // Dict entries are encoded just like structures.
+        let offsets = Some(FramingOffsets::new());
         let start = ser.0.bytes_written;
         let container_depths = ser.0.container_depths;
         ser.0.container_depths = ser.0.container_depths.inc_structure()?;
@@ -546,7 +531,7 @@ where
                     ctxt: self.ser.0.ctxt,
                     sig_parser,
                     writer: self.ser.0.writer,
-                    #[cfg(unix)]
+                    #[cfg(all(unix, feature = "std"))]
                     fds: self.ser.0.fds,
                     bytes_written,
                     value_sign: None,
@@ -555,14 +540,11 @@ where
                 value.serialize(&mut ser)?;
                 self.ser.0.bytes_written = ser.0.bytes_written;
 
-                self.ser
-                    .0
-                    .write_all(&b"\0"[..])
-                    .map_err(|e| Error::InputOutput(e.into()))?;
+                self.ser.0.write_all(&b"\0"[..]).map_err(Error::from)?;
                 self.ser
                     .0
                     .write_all(signature.as_bytes())
-                    .map_err(|e| Error::InputOutput(e.into()))?;
+                    .map_err(Error::from)?;
 
                 Ok(())
             }
@@ -1,10 +1,16 @@
-use std::{collections::HashMap, hash::BuildHasher};
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 #[cfg(feature = "gvariant")]
 use crate::Maybe;
-use crate::{Array, Dict, NoneValue, ObjectPath, Optional, Signature, Str, Structure, Type, Value};
+#[cfg(any(feature = "std", feature = "option-as-array"))]
+use crate::Type;
+use crate::{Array, Dict, NoneValue, ObjectPath, Optional, Signature, Str, Structure, Value};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 //
@@ -74,9 +80,9 @@ try_into_value_from_ref!(Dict<'a, 'a>, Dict);
 into_value!(Maybe<'a>, Maybe);
 #[cfg(feature = "gvariant")]
 try_into_value_from_ref!(Maybe<'a>, Maybe);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 into_value!(Fd<'a>, Fd);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 try_into_value_from_ref!(Fd<'a>, Fd);
 
 impl From<String> for Value<'_> {
@@ -121,11 +127,12 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<'a, 'k, 'v, K, V, H> From<HashMap<K, V, H>> for Value<'a>
 where
     'k: 'a,
     'v: 'a,
-    K: Type + Into<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    K: Type + Into<Value<'k>> + core::hash::Hash + core::cmp::Eq,
     V: Type + Into<Value<'v>>,
     H: BuildHasher + Default,
 {
@@ -0,0 +1,162 @@
+//! The I/O traits and types our serializers are built on.
+//!
+//! With the `std` feature, these are simply the ones from `std::io`. Otherwise, minimal
+//! replacements are provided, that only support writing to a `Cursor` over a `Vec<u8>`. These are
+//! `pub` in a private module, so they can be used in the bounds of our public serializer types.
+
+#[cfg(feature = "std")]
+pub(crate) use endi::WriteBytes;
+#[cfg(feature = "std")]
+pub(crate) use std::io::{Cursor, Result, Seek, SeekFrom, Write};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use no_std::*;
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use alloc::vec::Vec;
+    use endi::Endian;
+
+    /// A replacement for `std::io::Error`.
+    ///
+    /// The only possible error is seeking before the start.
+    #[derive(Debug)]
+    pub struct Error;
+
+    impl From<Error> for crate::Error {
+        fn from(_: Error) -> Self {
+            crate::Error::OutOfBounds
+        }
+    }
+
+    /// A replacement for `std::io::Result`.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// A replacement for `std::io::Write`.
+    ///
+    /// Unlike the original, `write` is expected to always write the whole buffer.
+    pub trait Write {
+        fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+        #[allow(dead_code)]
+        fn flush(&mut self) -> Result<()>;
+
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.write(buf).map(|_| ())
+        }
+    }
+
+    impl<W: Write + ?Sized> Write for &mut W {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            (**self).write(buf)
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            (**self).flush()
+        }
+    }
+
+    impl Write for Vec<u8> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.extend_from_slice(buf);
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// A replacement for `std::io::SeekFrom`.
+    #[allow(dead_code)]
+    #[derive(Debug, Clone, Copy)]
+    pub enum SeekFrom {
+        Start(u64),
+        End(i64),
+        Current(i64),
+    }
+
+    /// A replacement for `std::io::Seek`.
+    pub trait Seek {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+    }
+
+    /// A replacement for `std::io::Cursor`.
+    #[derive(Debug, Default)]
+    pub struct Cursor<T> {
+        inner: T,
+        pos: usize,
+    }
+
+    impl<T> Cursor<T> {
+        pub fn new(inner: T) -> Self {
+            Self { inner, pos: 0 }
+        }
+
+        pub fn into_inner(self) -> T {
+            self.inner
+        }
+    }
+
+    impl Write for Cursor<Vec<u8>> {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            let end = self.pos + buf.len();
+            if self.inner.len() < end {
+                self.inner.resize(end, 0);
+            }
+            self.inner[self.pos..end].copy_from_slice(buf);
+            self.pos = end;
+
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+        fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+            let (base, offset) = match pos {
+                SeekFrom::Start(n) => (0, n as i64),
+                SeekFrom::End(n) => (self.inner.as_ref().len(), n),
+                SeekFrom::Current(n) => (self.pos, n),
+            };
+            self.pos = (base as i64)
+                .checked_add(offset)
+                .and_then(|pos| usize::try_from(pos).ok())
+                .ok_or(Error)?;
+
+            Ok(self.pos as u64)
+        }
+    }
+
+    macro_rules! decl_write_method {
+        ($type:ty, $method:ident, $size:literal) => {
+            fn $method(&mut self, endian: Endian, n: $type) -> Result<()> {
+                let mut buf = [0; $size];
+                endian.$method(&mut buf, n);
+
+                self.write_all(&buf)
+            }
+        };
+    }
+
+    /// A replacement for `endi::WriteBytes`, which requires `std`.
+    #[allow(dead_code)]
+    pub trait WriteBytes: Write {
+        decl_write_method!(u8, write_u8, 1);
+        decl_write_method!(u16, write_u16, 2);
+        decl_write_method!(u32, write_u32, 4);
+        decl_write_method!(u64, write_u64, 8);
+        decl_write_method!(i8, write_i8, 1);
+        decl_write_method!(i16, write_i16, 2);
+        decl_write_method!(i32, write_i32, 4);
+        decl_write_method!(i64, write_i64, 8);
+        decl_write_method!(f32, write_f32, 4);
+        decl_write_method!(f64, write_f64, 8);
+    }
+
+    impl<W: Write + ?Sized> WriteBytes for W {}
+}
@@ -0,0 +1,328 @@
+//! Conversion between [`Value`] and [`serde_json::Value`].
+
+use alloc::{boxed::Box, format, string::ToString};
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::{
+    signature_parser::SignatureParser, Array, Dict, Error, ObjectPath, Result, Signature,
+    StructureBuilder, Value,
+};
+#[cfg(feature = "gvariant")]
+use crate::{utils::MAYBE_SIGNATURE_CHAR, Maybe};
+
+const VARIANT_SIGNATURE_KEY: &str = "signature";
+const VARIANT_VALUE_KEY: &str = "value";
+
+impl<'a> Value<'a> {
+    /// Convert `self` to a [`serde_json::Value`].
+    ///
+    /// D-Bus types are mapped to JSON as follows:
+    ///
+    /// * Booleans, integers and doubles are mapped to JSON booleans and numbers. Doubles that are
+    ///   not finite can't be represented in JSON and result in an error.
+    /// * Strings, object paths and signatures are mapped to JSON strings.
+    /// * Arrays (including byte arrays) and structures are mapped to JSON arrays.
+    /// * Dictionaries are mapped to JSON objects. Keys that are not strings are mapped to their
+    ///   JSON representation as a string, e.g `"42"` or `"true"`.
+    /// * Variants are mapped to a JSON object with a `signature` entry holding the signature of the
+    ///   contained value, and a `value` entry holding the value itself.
+    /// * Maybe values are mapped to `null` if they're empty, and to their value otherwise. If the
+    ///   value is itself a maybe value, it's wrapped in a single-element JSON array to tell
+    ///   `Some(None)` from `None` apart.
+    /// * File descriptors are mapped to their number. Since that's meaningless outside of the
+    ///   current process, they can't be converted back.
+    ///
+    /// Since JSON doesn't carry the D-Bus types, converting back with
+    /// [`Value::from_json_with_signature`] requires the signature of the value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let v = Value::from(HashMap::from([("answer", Value::from(42u32))]));
+    /// let json = v.to_json().unwrap();
+    /// assert_eq!(json.to_string(), r#"{"answer":{"signature":"u","value":42}}"#);
+    ///
+    /// let decoded = Value::from_json_with_signature(&json, &v.value_signature()).unwrap();
+    /// assert_eq!(decoded, v);
+    /// ```
+    pub fn to_json(&self) -> Result<JsonValue> {
+        let json = match self {
+            Value::U8(v) => JsonValue::from(*v),
+            Value::Bool(v) => JsonValue::from(*v),
+            Value::I16(v) => JsonValue::from(*v),
+            Value::U16(v) => JsonValue::from(*v),
+            Value::I32(v) => JsonValue::from(*v),
+            Value::U32(v) => JsonValue::from(*v),
+            Value::I64(v) => JsonValue::from(*v),
+            Value::U64(v) => JsonValue::from(*v),
+            Value::F64(v) => Number::from_f64(*v)
+                .map(JsonValue::Number)
+                .ok_or_else(|| Error::Message(format!("`{v}` can't be represented in JSON")))?,
+            Value::Str(v) => JsonValue::from(v.as_str()),
+            Value::Signature(v) => JsonValue::from(v.as_str()),
+            Value::ObjectPath(v) => JsonValue::from(v.as_str()),
+            Value::Value(v) => {
+                let mut map = Map::new();
+                map.insert(
+                    VARIANT_SIGNATURE_KEY.to_string(),
+                    JsonValue::from(v.value_signature().as_str()),
+                );
+                map.insert(VARIANT_VALUE_KEY.to_string(), v.to_json()?);
+
+                JsonValue::Object(map)
+            }
+            Value::Array(array) => array
+                .inner()
+                .iter()
+                .map(Value::to_json)
+                .collect::<Result<_>>()
+                .map(JsonValue::Array)?,
+            Value::Dict(dict) => {
+                let mut map = Map::new();
+                for (key, value) in dict.iter() {
+                    let key = match key.to_json()? {
+                        JsonValue::String(key) => key,
+                        key => key.to_string(),
+                    };
+                    map.insert(key, value.to_json()?);
+                }
+
+                JsonValue::Object(map)
+            }
+            Value::Structure(structure) => structure
+                .fields()
+                .iter()
+                .map(Value::to_json)
+                .collect::<Result<_>>()
+                .map(JsonValue::Array)?,
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(maybe) => match maybe.inner() {
+                Some(value @ Value::Maybe(_)) => JsonValue::Array(alloc::vec![value.to_json()?]),
+                Some(value) => value.to_json()?,
+                None => JsonValue::Null,
+            },
+            #[cfg(all(unix, feature = "std"))]
+            Value::Fd(fd) => {
+                use std::os::fd::AsRawFd;
+
+                JsonValue::from(fd.as_raw_fd())
+            }
+        };
+
+        Ok(json)
+    }
+
+    /// Create a `Value` from a [`serde_json::Value`], given the signature of the value.
+    ///
+    /// This is the reverse of [`Value::to_json`], which documents how values are mapped to JSON.
+    ///
+    /// # Errors
+    ///
+    /// If `json` doesn't match `signature`, or if `signature` contains file descriptors.
+    pub fn from_json_with_signature(
+        json: &JsonValue,
+        signature: &Signature<'_>,
+    ) -> Result<Value<'static>> {
+        let mismatch = || {
+            Error::Message(format!(
+                "JSON value `{json}` doesn't match signature `{signature}`"
+            ))
+        };
+
+        let value = match signature.as_bytes().first().map(|c| *c as char) {
+            Some('y') => Value::U8(int_from_json(json).ok_or_else(mismatch)?),
+            Some('b') => Value::Bool(json.as_bool().ok_or_else(mismatch)?),
+            Some('n') => Value::I16(int_from_json(json).ok_or_else(mismatch)?),
+            Some('q') => Value::U16(int_from_json(json).ok_or_else(mismatch)?),
+            Some('i') => Value::I32(int_from_json(json).ok_or_else(mismatch)?),
+            Some('u') => Value::U32(int_from_json(json).ok_or_else(mismatch)?),
+            Some('x') => Value::I64(int_from_json(json).ok_or_else(mismatch)?),
+            Some('t') => Value::U64(int_from_json(json).ok_or_else(mismatch)?),
+            Some('d') => Value::F64(json.as_f64().ok_or_else(mismatch)?),
+            Some('s') => Value::from(json.as_str().ok_or_else(mismatch)?.to_string()),
+            Some('g') => {
+                let s = json.as_str().ok_or_else(mismatch)?;
+
+                Value::Signature(Signature::try_from(s.to_string())?)
+            }
+            Some('o') => {
+                let s = json.as_str().ok_or_else(mismatch)?;
+
+                Value::ObjectPath(ObjectPath::try_from(s.to_string())?)
+            }
+            Some('v') => {
+                let map = json.as_object().ok_or_else(mismatch)?;
+                let signature = map
+                    .get(VARIANT_SIGNATURE_KEY)
+                    .and_then(JsonValue::as_str)
+                    .ok_or_else(mismatch)?;
+                let signature = Signature::try_from(signature)?;
+                let value = map.get(VARIANT_VALUE_KEY).ok_or_else(mismatch)?;
+
+                Value::Value(Box::new(Value::from_json_with_signature(
+                    value, &signature,
+                )?))
+            }
+            Some('a') if signature.as_bytes().get(1) == Some(&b'{') => {
+                let map = json.as_object().ok_or_else(mismatch)?;
+                let key_signature = signature.slice(2..3).to_owned();
+                let value_signature = signature.slice(3..signature.len() - 1).to_owned();
+                let key_is_string = matches!(key_signature.as_str(), "s" | "o" | "g");
+
+                let mut dict = Dict::new(key_signature.clone(), value_signature.clone());
+                for (key, value) in map {
+                    let key = if key_is_string {
+                        JsonValue::String(key.clone())
+                    } else {
+                        serde_json::from_str(key).map_err(|_| mismatch())?
+                    };
+                    dict.append(
+                        Value::from_json_with_signature(&key, &key_signature)?,
+                        Value::from_json_with_signature(value, &value_signature)?,
+                    )?;
+                }
+
+                Value::Dict(dict)
+            }
+            Some('a') => {
+                let elements = json.as_array().ok_or_else(mismatch)?;
+                let element_signature = signature.slice(1..).to_owned();
+
+                let mut array = Array::new(element_signature.clone());
+                for element in elements {
+                    array.append(Value::from_json_with_signature(
+                        element,
+                        &element_signature,
+                    )?)?;
+                }
+
+                Value::Array(array)
+            }
+            Some('(') => {
+                let mut fields = json.as_array().ok_or_else(mismatch)?.iter();
+                let field_signatures =
+                    SignatureParser::new(signature.slice(1..signature.len() - 1));
+
+                let mut builder = StructureBuilder::new();
+                for field_signature in field_signatures {
+                    let field = fields.next().ok_or_else(mismatch)?;
+                    builder = builder
+                        .append_field(Value::from_json_with_signature(field, &field_signature?)?);
+                }
+                if fields.next().is_some() {
+                    return Err(mismatch());
+                }
+
+                Value::Structure(builder.build())
+            }
+            #[cfg(feature = "gvariant")]
+            Some(MAYBE_SIGNATURE_CHAR) => {
+                let value_signature = signature.slice(1..).to_owned();
+                let json = match json {
+                    JsonValue::Null => None,
+                    JsonValue::Array(array)
+                        if value_signature.starts_with(MAYBE_SIGNATURE_CHAR) =>
+                    {
+                        match array.as_slice() {
+                            [json] => Some(json),
+                            _ => return Err(mismatch()),
+                        }
+                    }
+                    json => Some(json),
+                };
+
+                match json {
+                    Some(json) => Value::Maybe(Maybe::just(Value::from_json_with_signature(
+                        json,
+                        &value_signature,
+                    )?)),
+                    None => Value::Maybe(Maybe::nothing(value_signature)),
+                }
+            }
+            _ => {
+                return Err(Error::Message(format!(
+                    "signature `{signature}` can't be converted from JSON"
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+fn int_from_json<T>(json: &JsonValue) -> Option<T>
+where
+    T: TryFrom<i64> + TryFrom<u64>,
+{
+    match json.as_u64() {
+        Some(n) => T::try_from(n).ok(),
+        None => json.as_i64().and_then(|n| T::try_from(n).ok()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    use crate::{ObjectPath, Signature, StructureBuilder, Value};
+
+    #[test]
+    fn to_and_from_json() {
+        let path = ObjectPath::try_from("/org/freedesktop/DBus").unwrap();
+        let values = [
+            (Value::from(u64::MAX), json!(u64::MAX)),
+            (Value::from(i64::MIN), json!(i64::MIN)),
+            (Value::from(0.5), json!(0.5)),
+            (Value::from(path), json!("/org/freedesktop/DBus")),
+            (Value::from(&[1u8, 2, 3][..]), json!([1, 2, 3])),
+            (
+                StructureBuilder::new()
+                    .add_field("hello")
+                    .add_field(Value::new(true))
+                    .build()
+                    .into(),
+                json!(["hello", { "signature": "b", "value": true }]),
+            ),
+            (
+                Value::from(HashMap::from([(1i32, "one"), (-2, "minus two")])),
+                json!({ "1": "one", "-2": "minus two" }),
+            ),
+        ];
+
+        for (value, expected) in values {
+            let json = value.to_json().unwrap();
+            assert_eq!(json, expected);
+            let decoded = Value::from_json_with_signature(&json, &value.value_signature()).unwrap();
+            assert_eq!(decoded, value);
+        }
+
+        assert!(Value::from(f64::NAN).to_json().is_err());
+        let signature = Signature::try_from("(us)").unwrap();
+        assert!(Value::from_json_with_signature(&json!([1]), &signature).is_err());
+        assert!(Value::from_json_with_signature(&json!([1, "one", 2]), &signature).is_err());
+        let signature = Signature::try_from("y").unwrap();
+        assert!(Value::from_json_with_signature(&json!(256), &signature).is_err());
+    }
+
+    #[cfg(feature = "gvariant")]
+    #[test]
+    fn maybe_to_and_from_json() {
+        let values = [
+            (Value::from(Some(Some(7u8))), json!([7])),
+            (Value::from(Some(None::<u8>)), json!([null])),
+            (Value::from(None::<Option<u8>>), json!(null)),
+            (Value::from(Some("hello")), json!("hello")),
+        ];
+
+        for (value, expected) in values {
+            let json = value.to_json().unwrap();
+            assert_eq!(json, expected);
+            let decoded = Value::from_json_with_signature(&json, &value.value_signature()).unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+}
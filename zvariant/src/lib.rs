@@ -11,6 +11,9 @@
     allow(unused_extern_crates),
 )))]
 #![cfg_attr(test, recursion_limit = "256")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 #[macro_use]
 mod utils;
@@ -25,6 +28,9 @@ pub use basic::*;
 mod dict;
 pub use dict::*;
 
+mod dict_diff;
+pub use dict_diff::*;
+
 #[deprecated(since = "4.0.0", note = "Use `serialized::Context` instead")]
 #[doc(hidden)]
 pub type EncodingContext = serialized::Context;
@@ -34,11 +40,13 @@ pub type EncodingFormat = serialized::Format;
 
 pub mod serialized;
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 mod fd;
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 pub use fd::*;
 
+mod io;
+
 mod object_path;
 pub use crate::object_path::*;
 
@@ -47,6 +55,9 @@ pub use ser::*;
 
 mod de;
 
+mod validate;
+pub use validate::*;
+
 pub mod dbus;
 #[cfg(feature = "gvariant")]
 pub mod gvariant;
@@ -54,6 +65,9 @@ pub mod gvariant;
 mod signature;
 pub use crate::signature::*;
 
+mod signature_builder;
+pub use signature_builder::*;
+
 mod complete_type;
 pub use complete_type::*;
 
@@ -71,9 +85,31 @@ pub use crate::maybe::*;
 mod optional;
 pub use crate::optional::*;
 
+mod fixed_array;
+pub use crate::fixed_array::*;
+
+mod array_view;
+pub use crate::array_view::*;
+
+#[cfg(feature = "bumpalo")]
+mod arena;
+#[cfg(feature = "bumpalo")]
+pub use crate::arena::*;
+
+#[cfg(feature = "std")]
+mod stream_serializer;
+#[cfg(feature = "std")]
+pub use crate::stream_serializer::*;
+
 mod value;
 pub use value::*;
 
+pub mod as_string;
+
+pub mod as_value;
+
+pub mod timestamp;
+
 mod serialize_value;
 pub use serialize_value::*;
 
@@ -105,7 +141,17 @@ mod signature_parser;
 
 mod container_depths;
 
-pub use zvariant_derive::{DeserializeDict, OwnedValue, SerializeDict, Type, Value};
+mod text;
+
+#[cfg(feature = "serde_json")]
+mod json;
+
+#[cfg(feature = "glib")]
+mod glib_variant;
+
+pub use zvariant_derive::{
+    DeserializeDict, DeserializeEnum, OwnedValue, SerializeDict, SerializeEnum, Type, Value,
+};
 
 // Required for the macros to function within this crate.
 extern crate self as zvariant;
@@ -114,6 +160,10 @@ extern crate self as zvariant;
 #[doc(hidden)]
 pub mod export {
     pub use serde;
+
+    // Used by the code generated by our derive macros, so that it doesn't depend on `std`.
+    #[doc(hidden)]
+    pub use alloc::string::String;
 }
 
 // Re-export all of the `endi` API for ease of use.
@@ -130,7 +180,7 @@ mod tests {
     #[cfg(feature = "arrayvec")]
     use arrayvec::{ArrayString, ArrayVec};
     #[cfg(feature = "arrayvec")]
-    use std::str::FromStr;
+    use core::str::FromStr;
 
     #[cfg(feature = "gvariant")]
     use glib::{variant::FromVariant, Bytes, Variant};
@@ -138,13 +188,13 @@ mod tests {
 
     use crate::{serialized::Data, to_bytes, to_bytes_for_signature, MaxDepthExceeded};
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     use crate::Fd;
     use crate::{
         serialized::{Context, Format},
-        Array, Basic, DeserializeDict, DeserializeValue, Dict, Error, ObjectPath, Result,
-        SerializeDict, SerializeValue, Signature, Str, Structure, Type, Value, BE, LE,
-        NATIVE_ENDIAN,
+        Array, ArrayView, Basic, DeserializeDict, DeserializeValue, Dict, DictDiff, Error,
+        FixedArray, ObjectPath, OwnedValue, Result, SerializeDict, SerializeValue, Signature, Str,
+        StreamSerializer, Structure, Type, Value, BE, LE, NATIVE_ENDIAN,
     };
 
     // Test through both generic and specific API (wrt byte order)
@@ -275,7 +325,7 @@ mod tests {
     fn decode_with_gvariant<B, T>(encoded: B, expected_value: Option<T>) -> T
     where
         B: AsRef<[u8]> + Send + 'static,
-        T: glib::variant::FromVariant + std::fmt::Debug + PartialEq,
+        T: glib::variant::FromVariant + core::fmt::Debug + PartialEq,
     {
         let bytes = Bytes::from_owned(encoded);
         let gv = Variant::from_bytes::<T>(&bytes);
@@ -294,7 +344,7 @@ mod tests {
     fn decode_num_with_gvariant<B, T>(encoded: B, expected_value: Option<(T, T)>) -> T
     where
         B: AsRef<[u8]> + Send + 'static,
-        T: glib::variant::FromVariant + std::fmt::Debug + PartialEq,
+        T: glib::variant::FromVariant + core::fmt::Debug + PartialEq,
     {
         #[allow(unused_variables)]
         let expected_value = expected_value.map(|(le, be)| {
@@ -333,7 +383,7 @@ mod tests {
         basic_type_test!(LE, GVariant, 77_i8, 2, i8, 2);
     }
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     macro_rules! fd_value_test {
         ($endian:expr, $format:ident, $test_value:expr, $expected_len:expr, $align:literal, $expected_value_len:expr) => {{
             use std::os::fd::AsFd;
@@ -347,7 +397,7 @@ mod tests {
                 $expected_len + padding,
                 "invalid encoding using `to_bytes`"
             );
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             let (_, parsed): (Fd<'_>, _) = encoded.deserialize().unwrap();
             assert!(
                 parsed == encoded.len(),
@@ -387,7 +437,7 @@ mod tests {
         }};
     }
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     #[test]
     fn fd_value() {
         use std::os::fd::AsFd;
@@ -526,6 +576,33 @@ mod tests {
             assert!(to_bytes(ctxt, &"hello\0world").is_err());
         }
 
+        // Nor is invalid UTF-8, wherever it is in the string.
+        let string = "héllo wörld ".repeat(20);
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &string).unwrap();
+        let decoded: &str = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, string);
+        let mut bytes = encoded.to_vec();
+        let invalid = bytes.len() - 3;
+        bytes[invalid] = 0xff;
+        let utf8_error = std::str::from_utf8(&bytes[4..bytes.len() - 1]).unwrap_err();
+        assert_eq!(
+            Data::new(&bytes, ctxt).deserialize::<&str>().unwrap_err(),
+            Error::Utf8(utf8_error),
+        );
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(LE, 0);
+            let mut bytes = to_bytes(ctxt, &string).unwrap().to_vec();
+            let invalid = bytes.len() - 3;
+            bytes[invalid] = 0xff;
+            let utf8_error = std::str::from_utf8(&bytes[..bytes.len() - 1]).unwrap_err();
+            assert_eq!(
+                Data::new(&bytes, ctxt).deserialize::<&str>().unwrap_err(),
+                Error::Utf8(utf8_error),
+            );
+        }
+
         // Characters are treated as strings
         basic_type_test!(LE, DBus, 'c', 6, char, 4);
         #[cfg(feature = "gvariant")]
@@ -612,7 +689,7 @@ mod tests {
         }
     }
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     #[test]
     fn unit_fds() {
         let ctxt = Context::new_dbus(BE, 0);
@@ -1278,6 +1355,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dict_diff() {
+        let properties = || -> HashMap<String, OwnedValue> {
+            HashMap::from([
+                ("Volume".into(), OwnedValue::from(30u8)),
+                ("Muted".into(), OwnedValue::from(false)),
+                ("Name".into(), Value::from("Speakers").try_into().unwrap()),
+            ])
+        };
+        let old = properties();
+        let mut new = properties();
+        assert!(DictDiff::new(&old, &new).is_empty());
+
+        new.insert("Volume".into(), OwnedValue::from(40u8));
+        new.insert("Balance".into(), OwnedValue::from(0.5));
+        new.remove("Name");
+        let diff = DictDiff::new(&old, &new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added().len(), 1);
+        assert_eq!(diff.added()[0].0, "Balance");
+        assert_eq!(diff.removed().len(), 1);
+        assert_eq!(diff.removed()[0].0, "Name");
+        assert_eq!(diff.changed().len(), 1);
+        let (key, old_value, new_value) = diff.changed()[0];
+        assert_eq!(key, "Volume");
+        assert_eq!(**old_value, Value::U8(30));
+        assert_eq!(**new_value, Value::U8(40));
+
+        // The shape of `PropertiesChanged` arguments.
+        let changed: HashMap<&str, &Value<'_>> = diff
+            .changed_properties::<str>()
+            .into_iter()
+            .map(|(k, v)| (k, &**v))
+            .collect();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed["Balance"], &Value::F64(0.5));
+        assert_eq!(changed["Volume"], &Value::U8(40));
+        let invalidated: Vec<&str> = diff.invalidated_properties();
+        assert_eq!(invalidated, ["Name"]);
+
+        // Through `Value`, including when wrapped in variants.
+        let old = Value::new(HashMap::from([(1u8, "one"), (2, "two")]));
+        let new = Value::new(Value::new(HashMap::from([(1u8, "uno"), (3, "three")])));
+        let diff = old.diff(&new).unwrap();
+        assert_eq!(diff.added(), [(&Value::U8(3), &Value::from("three"))]);
+        assert_eq!(diff.removed(), [(&Value::U8(2), &Value::from("two"))]);
+        assert_eq!(
+            diff.changed(),
+            [(&Value::U8(1), &Value::from("one"), &Value::from("uno"))]
+        );
+        assert!(old.diff(&old).unwrap().is_empty());
+        assert_eq!(old.diff(&Value::U8(1)), Err(Error::IncorrectType));
+    }
+
     #[test]
     fn dict_compare() {
         // the order in which a dict has been constructed must not play a role
@@ -1586,7 +1717,7 @@ mod tests {
         let l = crate::serialized_size(ctxt, &()).unwrap();
         assert_eq!(*l, 0);
 
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         {
             let stdout = std::io::stdout();
             let l = crate::serialized_size(ctxt, &Fd::from(&stdout)).unwrap();
@@ -1600,6 +1731,42 @@ mod tests {
         let v = vec![1, 2];
         let l = crate::serialized_size(ctxt, &('a', "abc", &v)).unwrap();
         assert_eq!(*l, 28);
+
+        // The size always matches the actual encoding, including the padding for the position.
+        let mut map = HashMap::new();
+        map.insert("k", Value::from((1_u8, 2_u64)));
+        map.insert("l", Value::from(vec!["a", "bc"]));
+        let formats = [
+            Format::DBus,
+            #[cfg(feature = "gvariant")]
+            Format::GVariant,
+        ];
+        for format in formats {
+            for position in 0..8 {
+                let ctxt = Context::new(format, LE, position);
+                macro_rules! assert_size {
+                    ($value:expr) => {
+                        let size = crate::serialized_size(ctxt, $value).unwrap();
+                        assert_eq!(*size, to_bytes(ctxt, $value).unwrap().len());
+                    };
+                }
+                assert_size!(&42_u64);
+                assert_size!(&(1_u8, "hello", 3_i16, [0.5_f64, 1.5]));
+                assert_size!(&map);
+                assert_size!(&Value::new(("k", vec!["a", "bc"], 2_u64)));
+            }
+        }
+
+        #[cfg(all(unix, feature = "std"))]
+        {
+            let stdout = std::io::stdout();
+            let stderr = std::io::stderr();
+            let fds = [Fd::from(&stdout), Fd::from(&stderr), Fd::from(&stdout)];
+            let l = crate::serialized_size(ctxt, &fds).unwrap();
+            let encoded = to_bytes(ctxt, &fds).unwrap();
+            assert_eq!(*l, encoded.len());
+            assert_eq!(l.num_fds() as usize, encoded.fds().len());
+        }
     }
 
     #[test]
@@ -1666,6 +1833,359 @@ mod tests {
         assert_eq!(decoded, s);
     }
 
+    #[test]
+    fn fixed_array() {
+        use serde::{Deserialize, Serialize};
+
+        for endian in [LE, BE] {
+            let ctxt = Context::new_dbus(endian, 0);
+            let encoded = to_bytes(ctxt, &vec![1u16, 2, 0xFF_00]).unwrap();
+            let decoded: FixedArray<'_, u16> = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded.len(), 3);
+            assert_eq!(decoded.get(2), Some(0xFF_00));
+            assert_eq!(decoded.get(3), None);
+            assert_eq!(decoded.get(usize::MAX / 2), None);
+            assert_eq!(decoded.to_vec(), [1, 2, 0xFF_00]);
+            assert_eq!(decoded.as_slice().is_some(), endian == NATIVE_ENDIAN);
+            assert_eq!(
+                decoded.as_bytes().as_ptr(),
+                encoded.bytes()[4..].as_ptr(),
+                "elements must be borrowed"
+            );
+
+            let encoded = to_bytes(ctxt, &decoded).unwrap();
+            assert_eq!(
+                encoded.deserialize::<Vec<u16>>().unwrap().0,
+                [1, 2, 0xFF_00]
+            );
+        }
+
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Struct<'s> {
+            field1: u8,
+            #[serde(borrow)]
+            field2: FixedArray<'s, f64>,
+            #[serde(borrow)]
+            field3: FixedArray<'s, u8>,
+            field4: i64,
+        }
+        assert_eq!(Struct::signature(), "(yadayx)");
+        let doubles = [0.5, -1.0, f64::MAX];
+        let s = Struct {
+            field1: 7,
+            field2: FixedArray::from(&doubles[..]),
+            field3: FixedArray::from(&b"hello"[..]),
+            field4: -42,
+        };
+        let ctxt = Context::new_dbus(NATIVE_ENDIAN, 0);
+        let encoded = to_bytes(ctxt, &s).unwrap();
+        let decoded: Struct<'_> = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, s);
+        assert_eq!(decoded.field2.as_slice(), Some(&doubles[..]));
+        assert_eq!(decoded.field3.as_slice(), Some(&b"hello"[..]));
+        #[cfg(feature = "gvariant")]
+        {
+            let ctxt = Context::new_gvariant(NATIVE_ENDIAN, 0);
+            let encoded = to_bytes(ctxt, &s).unwrap();
+            let decoded: Struct<'_> = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded, s);
+            assert_eq!(decoded.field2.as_slice(), Some(&doubles[..]));
+        }
+
+        // Only arrays of fixed-sized numbers can be borrowed.
+        let encoded = to_bytes(ctxt, &vec!["hello"]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, FixedArray<'_, u8>>("as")
+            .is_err());
+        // The elements must all be complete.
+        let encoded = to_bytes(ctxt, &vec![1u8, 2, 3]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, FixedArray<'_, u16>>("ay")
+            .is_err());
+        // The elements must be of the requested type, even if of the same size.
+        let encoded = to_bytes(ctxt, &vec![1i32, -2]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, FixedArray<'_, u32>>("ai")
+            .is_err());
+        let encoded = to_bytes(ctxt, &vec![1i64, -2]).unwrap();
+        assert!(encoded
+            .deserialize_for_signature::<_, FixedArray<'_, f64>>("ax")
+            .is_err());
+    }
+
+    #[test]
+    fn array_view() {
+        use serde::Deserialize;
+
+        let points: Vec<_> = (0..100u32)
+            .map(|i| (i, format!("{i}").repeat(i as usize % 4), i % 2 == 0))
+            .collect();
+        for ctxt in [
+            Context::new_dbus(LE, 0),
+            Context::new_dbus(BE, 3),
+            #[cfg(feature = "gvariant")]
+            Context::new_gvariant(LE, 0),
+            #[cfg(feature = "gvariant")]
+            Context::new_gvariant(BE, 5),
+        ] {
+            let encoded = to_bytes(ctxt, &points).unwrap();
+            let (view, parsed) = encoded
+                .deserialize::<ArrayView<'_, (u32, &str, bool)>>()
+                .unwrap();
+            assert_eq!(parsed, encoded.len());
+            assert_eq!(view.len(), 100);
+            assert_eq!(view.get(7).unwrap(), Some((7, "777", false)));
+            assert_eq!(view.get(100).unwrap(), None);
+            assert_eq!(view.iter().len(), 100);
+            let decoded = view.to_vec().unwrap();
+            assert!(decoded
+                .iter()
+                .zip(&points)
+                .all(|(d, p)| d.0 == p.0 && d.1 == p.1 && d.2 == p.2));
+
+            let encoded = to_bytes(ctxt, &Vec::<(u32, String, bool)>::new()).unwrap();
+            let view: ArrayView<'_, (u32, &str, bool)> = encoded.deserialize().unwrap().0;
+            assert!(view.is_empty());
+            assert_eq!(view.iter().count(), 0);
+
+            #[derive(Deserialize, Type, Debug)]
+            struct Struct<'s> {
+                field1: u8,
+                #[serde(borrow)]
+                field2: ArrayView<'s, &'s str>,
+                field3: i64,
+            }
+            assert_eq!(Struct::signature(), "(yasx)");
+            let encoded = to_bytes(ctxt, &(7u8, vec!["a", "bc", ""], -42i64)).unwrap();
+            let decoded: Struct<'_> = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded.field1, 7);
+            assert_eq!(decoded.field2.to_vec().unwrap(), ["a", "bc", ""]);
+            assert_eq!(decoded.field3, -42);
+
+            // Invalid elements are caught upfront, not on access.
+            let encoded = to_bytes(ctxt, &vec!["/valid", "in valid"]).unwrap();
+            assert!(encoded.deserialize::<ArrayView<'_, &str>>().is_ok());
+            assert!(encoded
+                .deserialize_for_signature::<_, ArrayView<'_, &str>>("ao")
+                .is_err());
+            // The element type must match the signature.
+            assert!(encoded
+                .deserialize_for_signature::<_, ArrayView<'_, u32>>("as")
+                .is_err());
+            // Dictionaries aren't supported.
+            let encoded = to_bytes(ctxt, &HashMap::from([(1u8, "one")])).unwrap();
+            assert!(encoded
+                .deserialize_for_signature::<_, ArrayView<'_, (u8, &str)>>("a{ys}")
+                .is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bumpalo")]
+    fn arena() {
+        use bumpalo::{
+            collections::{String as BumpString, Vec as BumpVec},
+            Bump,
+        };
+
+        use crate::InArena;
+
+        let mut bump = Bump::new();
+        for ctxt in [
+            Context::new_dbus(LE, 0),
+            Context::new_dbus(BE, 3),
+            #[cfg(feature = "gvariant")]
+            Context::new_gvariant(LE, 0),
+        ] {
+            let value = (
+                7u8,
+                vec![("one", vec![1u32]), ("two", vec![2, 2])],
+                "hello",
+                ObjectPath::try_from("/a/b").unwrap(),
+            );
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            type Decoded<'d, 'b> = (
+                u8,
+                BumpVec<'b, (&'d str, &'b [u32])>,
+                BumpString<'b>,
+                ObjectPath<'d>,
+            );
+            let (decoded, parsed): (Decoded<'_, '_>, _) = encoded.deserialize_in(&bump).unwrap();
+            assert_eq!(parsed, encoded.len());
+            assert_eq!(decoded.0, 7);
+            assert_eq!(decoded.1.len(), 2);
+            assert_eq!(decoded.1[0], ("one", &[1][..]));
+            assert_eq!(decoded.1[1], ("two", &[2, 2][..]));
+            assert_eq!(decoded.2, "hello");
+            assert_eq!(decoded.3, "/a/b");
+            assert!(bump.allocated_bytes() > 0);
+
+            // Also through seeds, e.g. for the fields of custom types.
+            let seed = InArena::<(u8, BumpVec<'_, (&str, BumpVec<'_, u32>)>)>::new(&bump);
+            let encoded = to_bytes(ctxt, &(value.0, value.1)).unwrap();
+            let (pairs, _) = encoded.deserialize_with_seed(seed).unwrap();
+            assert_eq!(pairs.1[1].1, [2, 2]);
+
+            // Errors are still reported.
+            let encoded = to_bytes(ctxt, &vec![1u8, 2, 3]).unwrap();
+            assert!(encoded
+                .deserialize_with_seed(InArena::<BumpVec<'_, u32>>::new(&bump))
+                .is_err());
+
+            drop((decoded, pairs));
+            bump.reset();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "glib")]
+    fn glib_variant() {
+        use glib::{variant::ToVariant, Variant};
+
+        use crate::StructureBuilder;
+
+        let mut dict = Dict::new(<u32>::signature(), Value::signature());
+        dict.add(1u32, Value::from("one")).unwrap();
+        dict.add(2u32, Value::from(ObjectPath::try_from("/two").unwrap()))
+            .unwrap();
+        let value = Value::from(
+            StructureBuilder::new()
+                .add_field(u8::MAX)
+                .add_field(true)
+                .add_field(-3i16)
+                .add_field(f64::MIN_POSITIVE)
+                .add_field(Signature::try_from("a(sv)").unwrap())
+                .add_field(vec![vec![1i64, 2], vec![]])
+                .append_field(Value::Dict(dict))
+                .add_field(Value::new(Value::new(u64::MAX)))
+                .build(),
+        );
+
+        let variant = Variant::try_from(&value).unwrap();
+        assert_eq!(variant.type_().as_str(), value.value_signature().as_str());
+        assert_eq!(variant.child_value(0).get::<u8>(), Some(u8::MAX));
+        assert_eq!(variant.child_value(6).n_children(), 2);
+        assert_eq!(Value::try_from(&variant).unwrap(), value);
+        let owned = OwnedValue::try_from(variant).unwrap();
+        assert_eq!(*owned, value);
+        assert_eq!(
+            Variant::try_from(owned).unwrap().type_().as_str(),
+            "(ybndgaaxa{uv}v)"
+        );
+
+        // Values encoded by GLib decode to the same `Value`.
+        let variant = ("hello", HashMap::from([(1u8, "a")])).to_variant();
+        assert_eq!(
+            Value::try_from(variant).unwrap(),
+            Value::from(
+                StructureBuilder::new()
+                    .add_field("hello")
+                    .add_field(HashMap::from([(1u8, "a")]))
+                    .build()
+            )
+        );
+
+        #[cfg(feature = "gvariant")]
+        {
+            let value = Value::from(vec![Some(Some(1u16)), Some(None), None]);
+            let variant = Variant::try_from(&value).unwrap();
+            assert_eq!(variant.to_string(), "[@mmq 1, just nothing, nothing]");
+            assert_eq!(Value::try_from(&variant).unwrap(), value);
+        }
+
+        // Types that `Value` can't represent.
+        assert!(Value::try_from(glib::variant::Handle(0).to_variant()).is_err());
+        assert!(Value::try_from(().to_variant()).is_err());
+        assert!(Value::try_from(Variant::from_dict_entry(&1u8.into(), &2u8.into())).is_err());
+        #[cfg(not(feature = "gvariant"))]
+        assert!(Value::try_from(Some(1u8).to_variant()).is_err());
+        #[cfg(unix)]
+        {
+            let fd = std::io::stdout();
+            assert!(Variant::try_from(Value::from(Fd::from(&fd))).is_err());
+        }
+    }
+
+    #[test]
+    fn stream_serializer() {
+        let ctxt = Context::new_dbus(BE, 0);
+        let strings = vec![
+            vec!["a".to_string()],
+            vec![],
+            vec!["bc".into(), "def".into()],
+        ];
+        let expected = to_bytes(ctxt, &(7u8, &strings, vec![(1u8, 2u64)], "end")).unwrap();
+
+        let mut serializer = StreamSerializer::new(vec![], ctxt).unwrap();
+        serializer.serialize(&7u8).unwrap();
+        // Nested arrays, with the length computed upfront.
+        let len = serializer.array_len(strings.iter()).unwrap();
+        serializer.begin_array::<Vec<String>>(len).unwrap();
+        for s in &strings {
+            serializer.serialize_array(s.iter()).unwrap();
+        }
+        serializer.end_array().unwrap();
+        serializer.serialize_array([(1u8, 2u64)]).unwrap();
+        serializer.serialize("end").unwrap();
+        assert_eq!(serializer.bytes_written(), expected.len());
+        let (bytes, written) = unsafe { serializer.finish() };
+        assert_eq!(*written, expected.len());
+        assert_eq!(bytes, expected.bytes());
+
+        // Values are aligned relative to the start of the data.
+        let ctxt = Context::new_dbus(LE, 4);
+        let mut serializer = StreamSerializer::new(vec![], ctxt).unwrap();
+        serializer.serialize(&u64::MAX).unwrap();
+        let (bytes, _) = unsafe { serializer.finish() };
+        assert_eq!(bytes, [0, 0, 0, 0, 255, 255, 255, 255, 255, 255, 255, 255]);
+
+        // The length of arrays is checked.
+        let ctxt = Context::new_dbus(LE, 0);
+        let mut serializer = StreamSerializer::new(vec![], ctxt).unwrap();
+        serializer.begin_array::<u32>(4).unwrap();
+        assert!(serializer.end_array().is_err());
+        serializer.begin_array::<u32>(4).unwrap();
+        serializer.serialize(&1u32).unwrap();
+        assert!(serializer.serialize(&2u32).is_err());
+        serializer.end_array().unwrap();
+        assert!(serializer.end_array().is_err());
+
+        #[cfg(feature = "gvariant")]
+        assert!(StreamSerializer::new(vec![], Context::new_gvariant(LE, 0)).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "futures-io")]
+    fn async_stream_serializer() {
+        use crate::AsyncStreamSerializer;
+
+        futures_executor::block_on(async {
+            let ctxt = Context::new_dbus(BE, 0);
+            let strings = vec![vec!["a".to_string()], vec![], vec!["bc".into()]];
+            let expected = to_bytes(ctxt, &(7u8, &strings, "end")).unwrap();
+
+            let mut serializer = AsyncStreamSerializer::new(vec![], ctxt).unwrap();
+            serializer.serialize(&7u8).await.unwrap();
+            let len = serializer.array_len(strings.iter()).unwrap();
+            serializer.begin_array::<Vec<String>>(len).await.unwrap();
+            for s in &strings {
+                serializer.serialize_array(s.iter()).await.unwrap();
+            }
+            serializer.end_array().unwrap();
+            serializer.serialize("end").await.unwrap();
+            let (bytes, written) = unsafe { serializer.finish() };
+            assert_eq!(*written, expected.len());
+            assert_eq!(bytes, expected.bytes());
+
+            // The length of arrays is checked.
+            let mut serializer = AsyncStreamSerializer::new(vec![], ctxt).unwrap();
+            serializer.begin_array::<u32>(4).await.unwrap();
+            serializer.serialize(&1u32).await.unwrap();
+            assert!(serializer.serialize(&2u32).await.is_err());
+            serializer.end_array().unwrap();
+        });
+    }
+
     #[test]
     #[cfg(any(feature = "gvariant", feature = "option-as-array"))]
     fn option_value() {
@@ -1903,6 +2423,121 @@ mod tests {
                 .unwrap();
             assert_eq!(decoded, structure);
         }
+
+        #[cfg(all(feature = "gvariant", not(feature = "option-as-array")))]
+        {
+            // In a dict, as Value
+            let map: HashMap<String, Option<u32>> =
+                HashMap::from([("some".into(), Some(42)), ("none".into(), None)]);
+            let v: Value<'_> = Dict::from(map.clone()).into();
+            let encoded = to_bytes(ctxt, &v).unwrap();
+            let decoded: Value<'_> = encoded.deserialize().unwrap().0;
+            assert_eq!(v, decoded);
+            let decoded: HashMap<String, Value<'_>> = decoded.try_into().unwrap();
+            for (key, value) in decoded {
+                let maybe = crate::Maybe::try_from(value).unwrap();
+                assert_eq!(maybe.downcast::<u32>().unwrap(), map[&key]);
+            }
+
+            // Check encoding against GLib
+            let bytes = Bytes::from_owned(encoded);
+            let variant = Variant::from_bytes::<Variant>(&bytes);
+            let decoded = variant
+                .child_value(0)
+                .get::<HashMap<String, Option<u32>>>()
+                .unwrap();
+            assert_eq!(decoded, map);
+        }
+    }
+
+    #[test]
+    #[cfg(any(feature = "gvariant", feature = "option-as-array"))]
+    fn option_value_conversions() {
+        let v = Value::from(Some(42u32));
+        assert_eq!(Option::<u32>::try_from(v).unwrap(), Some(42));
+        let v = Value::from(None::<String>);
+        assert_eq!(Option::<String>::try_from(v).unwrap(), None);
+        let v = Value::from(Some("hello"));
+        assert_eq!(
+            Option::<Str<'_>>::try_from(v).unwrap().unwrap(),
+            Str::from("hello"),
+        );
+
+        // Incorrect inner type
+        let v = Value::from(Some(42u32));
+        assert!(matches!(
+            Option::<String>::try_from(v),
+            Err(Error::IncorrectType)
+        ));
+        // Not an option at all
+        assert!(matches!(
+            Option::<u32>::try_from(Value::from(42u32)),
+            Err(Error::IncorrectType)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "gvariant", not(feature = "option-as-array")))]
+    fn nested_option_value() {
+        use crate::Maybe;
+
+        let ctxt = Context::new_gvariant(NATIVE_ENDIAN, 0);
+        for mmn in [Some(Some(16i16)), Some(None), None] {
+            let encoded = to_bytes(ctxt, &mmn).unwrap();
+            assert_eq!(encoded.deserialize::<Option<Option<i16>>>().unwrap().0, mmn);
+
+            // Check encoding against GLib
+            let bytes = Bytes::from_owned(encoded.to_vec());
+            let variant = Variant::from_bytes::<Option<Option<i16>>>(&bytes);
+            assert_eq!(variant.get::<Option<Option<i16>>>().unwrap(), mmn);
+
+            // As Value
+            let v = Value::Maybe(match mmn.map(Maybe::from) {
+                Some(inner) => Maybe::just(Value::Maybe(inner)),
+                None => Maybe::nothing(Signature::from_static_str_unchecked("mn")),
+            });
+            assert_eq!(v.value_signature(), "mmn");
+            let encoded = to_bytes(ctxt, &v).unwrap();
+            let decoded: Value<'_> = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded, v);
+            let decoded = Maybe::try_from(decoded)
+                .unwrap()
+                .downcast::<Maybe<'_>>()
+                .unwrap()
+                .map(|inner| inner.downcast::<i16>().unwrap());
+            assert_eq!(decoded, mmn);
+
+            let bytes = Bytes::from_owned(encoded.to_vec());
+            let variant = Variant::from_bytes::<Variant>(&bytes);
+            let decoded = variant.child_value(0).get::<Option<Option<i16>>>().unwrap();
+            assert_eq!(decoded, mmn);
+        }
+        let v = Value::Maybe(Maybe::just(Value::Maybe(Maybe::from(None::<i16>))));
+        assert_eq!(v.to_string(), "@mmn just nothing");
+    }
+
+    #[test]
+    #[cfg(all(feature = "gvariant", not(feature = "option-as-array")))]
+    fn maybe_in_dbus_format() {
+        // GVariant's maybe type has no D-Bus counterpart.
+        let ctxt = Context::new_dbus(LE, 0);
+        for v in [Value::from(Some(16i16)), Value::from(None::<i16>)] {
+            assert!(matches!(
+                to_bytes(ctxt, &v),
+                Err(Error::IncompatibleFormat(s, Format::DBus)) if s == "mn"
+            ));
+        }
+        assert!(matches!(
+            to_bytes(ctxt, &Some(16i16)),
+            Err(Error::IncompatibleFormat(s, Format::DBus)) if s == "mn"
+        ));
+
+        let encoded = to_bytes(ctxt, &16i16).unwrap();
+        let data = Data::new(encoded.to_vec(), ctxt);
+        assert!(matches!(
+            data.deserialize_for_signature::<_, Option<i16>>("mn"),
+            Err(Error::IncompatibleFormat(s, Format::DBus)) if s == "mn"
+        ));
     }
 
     #[test]
@@ -1926,6 +2561,281 @@ mod tests {
         assert_eq!(f, foo);
     }
 
+    #[test]
+    fn array_validation() {
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &vec![1u32, 2, 3]).unwrap();
+        let with_len = |len: u32| {
+            let mut bytes = encoded.to_vec();
+            bytes[..4].copy_from_slice(&len.to_le_bytes());
+            bytes
+        };
+
+        // The length of an array must not go past the end of the data.
+        let bytes = with_len(16);
+        assert!(Data::new(&bytes, ctxt).deserialize::<Vec<u32>>().is_err());
+        // Nor exceed the maximum length of arrays.
+        let bytes = with_len(1 << 27);
+        assert!(Data::new(&bytes, ctxt).deserialize::<Vec<u32>>().is_err());
+        // Arrays of fixed-sized elements can only contain whole elements.
+        let bytes = with_len(6);
+        assert!(Data::new(&bytes, ctxt).deserialize::<Vec<u32>>().is_err());
+        let bytes = with_len(8);
+        assert_eq!(
+            Data::new(&bytes, ctxt).deserialize::<Vec<u32>>().unwrap().0,
+            [1, 2]
+        );
+
+        // Padding must be made of nul bytes.
+        let encoded = to_bytes(ctxt, &(1u8, 2u64)).unwrap();
+        let mut bytes = encoded.to_vec();
+        bytes[3] = 7;
+        assert_eq!(
+            Data::new(&bytes, ctxt)
+                .deserialize::<(u8, u64)>()
+                .unwrap_err(),
+            Error::PaddingNot0(7)
+        );
+    }
+
+    #[test]
+    fn validate() {
+        use crate::validate;
+
+        let mut dict = HashMap::new();
+        dict.insert("k", Value::from((1_u8, vec!["a", "bc"])));
+        dict.insert("l", Value::from(Value::from(42_u64)));
+        let value = (7_u16, dict, "end");
+        let signature = "(qa{sv}s)";
+        let formats = [
+            Format::DBus,
+            #[cfg(feature = "gvariant")]
+            Format::GVariant,
+        ];
+        for format in formats {
+            let ctxt = Context::new(format, LE, 0);
+            let encoded = to_bytes(ctxt, &value).unwrap();
+            validate(&encoded, signature, ctxt).unwrap();
+            encoded.validate(signature).unwrap();
+
+            // Neither a shorter encoding, nor any trailing bytes are valid. GVariant reads the
+            // framing offsets from the end, so only D-Bus can tell where the trailing bytes start.
+            validate(&encoded[..encoded.len() - 1], signature, ctxt).unwrap_err();
+            let mut bytes = encoded.to_vec();
+            bytes.push(0);
+            let err = validate(&bytes, signature, ctxt).unwrap_err();
+            if format == Format::DBus {
+                assert_eq!(err.offset(), encoded.len());
+            }
+
+            // Nor is the encoding of another signature.
+            validate(&encoded, "(qa{sv}u)", ctxt).unwrap_err();
+        }
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &(1_u32, "hello")).unwrap();
+        let mut bytes = encoded.to_vec();
+        // Invalid UTF-8 in the string.
+        bytes[9] = 0xff;
+        let err = validate(&bytes, "(us)", ctxt).unwrap_err();
+        assert!(matches!(err.error(), Error::Utf8(_)), "{err}");
+        assert_eq!(err.offset(), encoded.len());
+        // Strings must be nul-terminated.
+        let mut bytes = encoded.to_vec();
+        *bytes.last_mut().unwrap() = b'x';
+        validate(&bytes, "(us)", ctxt).unwrap_err();
+        // An invalid signature.
+        let err = validate(&encoded, "(uz)", ctxt).unwrap_err();
+        assert_eq!(err.offset(), 0);
+
+        // FDs can only be checked with the `Data` API.
+        #[cfg(all(unix, feature = "std"))]
+        {
+            let stdout = std::io::stdout();
+            let encoded = to_bytes(ctxt, &(1_u8, Fd::from(&stdout))).unwrap();
+            encoded.validate("(yh)").unwrap();
+            let err = validate(&encoded, "(yh)", ctxt).unwrap_err();
+            assert_eq!(err.error(), &Error::UnknownFd);
+            assert_eq!(err.offset(), 8);
+        }
+    }
+
+    #[test]
+    fn lenient() {
+        use crate::{serialized::Data, validate};
+
+        #[derive(SerializeDict, DeserializeDict, Type, PartialEq, Debug)]
+        #[zvariant(deny_unknown_fields, signature = "a{sv}")]
+        struct Known {
+            volume: u8,
+        }
+
+        let formats = [
+            Format::DBus,
+            #[cfg(feature = "gvariant")]
+            Format::GVariant,
+        ];
+        for format in formats {
+            let strict = Context::new(format, LE, 0);
+            let lenient = strict.with_lenient(true);
+            assert!(!strict.is_lenient());
+            assert!(lenient.is_lenient());
+
+            // Non-0 padding after the leading byte and in the first array element.
+            let value = (7u8, vec![(1u8, 2u32), (3, 4)]);
+            let signature = "(ya(yu))";
+            let mut bytes = to_bytes(strict, &value).unwrap().to_vec();
+            let element_start = if format == Format::DBus { 8 } else { 4 };
+            bytes[1] = 0xff;
+            bytes[element_start + 1] = 0xfe;
+
+            let data = Data::new(&bytes[..], strict);
+            let err = data.deserialize::<(u8, Vec<(u8, u32)>)>().unwrap_err();
+            assert_eq!(err, Error::PaddingNot0(0xff));
+            validate(&bytes, signature, strict).unwrap_err();
+
+            let data = Data::new(&bytes[..], lenient);
+            let decoded: (u8, Vec<(u8, u32)>) = data.deserialize().unwrap().0;
+            assert_eq!(decoded, value);
+            validate(&bytes, signature, lenient).unwrap();
+            data.validate(signature).unwrap();
+            // Array views decode their elements in the same context.
+            let (_, view): (u8, ArrayView<'_, (u8, u32)>) = data.deserialize().unwrap().0;
+            assert_eq!(view.get(0).unwrap(), Some((1, 2)));
+            // And so do slices.
+            let element = data.slice(element_start..element_start + 8);
+            assert_eq!(element.deserialize::<(u8, u32)>().unwrap().0, (1, 2));
+
+            // Unknown entries, even for structs denying them.
+            let dict = HashMap::from([("volume", Value::from(3u8)), ("muted", Value::from(true))]);
+            let encoded = to_bytes(strict, &dict).unwrap();
+            let err = encoded.deserialize::<Known>().unwrap_err();
+            assert_eq!(
+                err,
+                Error::Message("unknown field `muted`, expected `volume`".to_string())
+            );
+            let data = Data::new(encoded.bytes(), lenient);
+            assert_eq!(data.deserialize::<Known>().unwrap().0, Known { volume: 3 });
+        }
+
+        // Trailing bytes. GVariant reads the framing offsets from the end, so only D-Bus can tell
+        // them apart from the encoding.
+        let ctxt = Context::new_dbus(LE, 0);
+        let mut bytes = to_bytes(ctxt, &(1u8, "hello")).unwrap().to_vec();
+        bytes.extend([1, 2, 3]);
+        validate(&bytes, "(ys)", ctxt).unwrap_err();
+        validate(&bytes, "(ys)", ctxt.with_lenient(true)).unwrap();
+    }
+
+    #[test]
+    fn text_format() {
+        use crate::StructureBuilder;
+
+        let mut dict = Dict::new(<u16>::signature(), Value::signature());
+        dict.add(1u16, Value::from("one")).unwrap();
+        dict.add(2u16, Value::from(vec![-2.5f64, 3.])).unwrap();
+        let values = [
+            Value::from(0x2au8),
+            Value::from(true),
+            Value::from(i16::MIN),
+            Value::from(u16::MAX),
+            Value::from(-7i32),
+            Value::from(u32::MAX),
+            Value::from(i64::MIN),
+            Value::from(u64::MAX),
+            Value::from(1e100),
+            Value::from("quotes ' \" and \\ escapes\n\t\u{1f980}"),
+            Value::from(ObjectPath::try_from("/org/zbus").unwrap()),
+            Value::from(Signature::try_from("a{sv}").unwrap()),
+            Value::new(Value::new(42u64)),
+            Value::from(Vec::<String>::new()),
+            Value::from(vec![vec![1u8, 2], vec![]]),
+            Value::from(b"bytes\0".to_vec()),
+            Value::Dict(dict),
+            Value::from(HashMap::<&str, u8>::new()),
+            Value::from(
+                StructureBuilder::new()
+                    .add_field((1u8,))
+                    .add_field(vec![Value::new("a"), Value::new(1u32)])
+                    .build(),
+            ),
+        ];
+        for value in values {
+            let text = value.to_string();
+            let parsed = Value::parse(&text).unwrap_or_else(|e| panic!("{text}: {e}"));
+            assert_eq!(parsed, value, "{text}");
+            let signature = value.value_signature();
+            assert_eq!(
+                Value::parse_with_signature(&text, &signature).unwrap(),
+                value
+            );
+        }
+
+        // The usual GLib syntax.
+        let v: Value<'_> = "{'k': <@a(ii) [(1, 2)]>, \"l\": <[0x10, -1]>}"
+            .parse()
+            .unwrap();
+        let expected = HashMap::from([
+            ("k", Value::new(vec![(1, 2)])),
+            ("l", Value::new(vec![16, -1])),
+        ]);
+        assert_eq!(v, Value::from(expected));
+        let v = Value::parse(" [ uint32 3 , 4, ] ").unwrap();
+        assert_eq!(v, Value::from(vec![3u32, 4]));
+        let v = Value::parse("[[], [1.5]]").unwrap();
+        assert_eq!(v, Value::from(vec![vec![], vec![1.5]]));
+
+        // With a known signature, annotations aren't needed.
+        let signature = Signature::try_from("(yqaog)").unwrap();
+        let v = Value::parse_with_signature("(1, 2, ['/a'], 's')", &signature).unwrap();
+        assert_eq!(v.value_signature(), signature);
+
+        for text in [
+            "",
+            "[]",
+            "{}",
+            "()",
+            "[1, 'a']",
+            "[1,, 2]",
+            "{1, 2}",
+            "'unterminated",
+            "1 2",
+            "@as [1]",
+            "byte 256",
+            "uint32 -1",
+            "objectpath 'not a path'",
+            "signature 'z'",
+            "handle 1",
+            "<>",
+            "@a{sv} {1: <2>}",
+            "unknown 1",
+        ] {
+            Value::parse(text).unwrap_err();
+        }
+        let signature = Signature::try_from("(ys)").unwrap();
+        Value::parse_with_signature("(1, 2)", &signature).unwrap_err();
+        Value::parse_with_signature("(1, 's', 3)", &signature).unwrap_err();
+
+        #[cfg(feature = "gvariant")]
+        {
+            for value in [
+                Value::from(Some(Some(1i16))),
+                Value::from(Some(None::<i16>)),
+                Value::from(None::<i16>),
+                Value::from(vec![Some("a"), None]),
+            ] {
+                let text = value.to_string();
+                assert_eq!(Value::parse(&text).unwrap(), value, "{text}");
+            }
+            let v = Value::parse("just just nothing").unwrap_err();
+            assert!(v.to_string().contains("infer"), "{v}");
+            let v = Value::parse("[just 1, nothing]").unwrap();
+            assert_eq!(v, Value::from(vec![Some(1), None]));
+        }
+        #[cfg(not(feature = "gvariant"))]
+        Value::parse("just 1").unwrap_err();
+    }
+
     #[test]
     fn issue_59() {
         // Ensure we don't panic on deserializing tuple of smaller than expected length.
@@ -1981,6 +2891,78 @@ mod tests {
         assert_eq!(localhost_v6, decoded);
     }
 
+    #[test]
+    fn as_string() {
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Addresses(
+            #[zvariant(signature = "s")]
+            #[serde(with = "crate::as_string")]
+            IpAddr,
+            #[zvariant(signature = "s")]
+            #[serde(with = "crate::as_string")]
+            u16,
+        );
+        assert_eq!(Addresses::signature(), "(ss)");
+
+        let ctxt = Context::new_dbus(LE, 0);
+        let addresses = Addresses(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080);
+        let encoded = to_bytes(ctxt, &addresses).unwrap();
+        let raw: (&str, &str) = encoded.deserialize().unwrap().0;
+        assert_eq!(raw, ("127.0.0.1", "8080"));
+        let decoded: Addresses = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, addresses);
+
+        let encoded = to_bytes(ctxt, &("::1", "http")).unwrap();
+        encoded.deserialize::<Addresses>().unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "uuid")]
+    fn uuid() {
+        let uuid = uuid::Uuid::from_u128(0x936da01f_9abd_4d9d_80c7_02af85c822a8);
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &uuid).unwrap();
+        let bytes: Vec<u8> = encoded.deserialize().unwrap().0;
+        assert_eq!(bytes, uuid.as_bytes());
+        let decoded: uuid::Uuid = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, uuid);
+
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Connection {
+            #[zvariant(signature = "s")]
+            #[serde(with = "crate::as_string")]
+            uuid: uuid::Uuid,
+        }
+
+        let connection = Connection { uuid };
+        let encoded = to_bytes(ctxt, &connection).unwrap();
+        let s: &str = encoded.deserialize().unwrap().0;
+        assert_eq!(s, "936da01f-9abd-4d9d-80c7-02af85c822a8");
+        let decoded: Connection = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, connection);
+
+        let encoded = to_bytes(ctxt, "not-a-uuid").unwrap();
+        encoded.deserialize::<Connection>().unwrap_err();
+        // Invalid lengths are rejected as well.
+        let encoded = to_bytes(ctxt, &[0u8; 3][..]).unwrap();
+        encoded.deserialize::<uuid::Uuid>().unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "url")]
+    fn url() {
+        let url = url::Url::parse("https://example.org/path?query=1").unwrap();
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &url).unwrap();
+        let s: &str = encoded.deserialize().unwrap().0;
+        assert_eq!(s, "https://example.org/path?query=1");
+        let decoded: url::Url = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, url);
+
+        let encoded = to_bytes(ctxt, "not a url").unwrap();
+        encoded.deserialize::<url::Url>().unwrap_err();
+    }
+
     #[cfg(feature = "ostree-tests")]
     #[test]
     fn ostree_de() {
@@ -2040,6 +3022,145 @@ mod tests {
         assert_eq!(date, decoded);
     }
 
+    #[test]
+    fn to_endian() {
+        let mut dict = HashMap::new();
+        dict.insert("answer", Value::new(42u64));
+        dict.insert(
+            "nested",
+            Value::new(vec![(1u8, -2i64, "three"), (4, -5, "six")]),
+        );
+        let value = (
+            true,
+            (0x1234u16, -0x1234i16),
+            0x12345678u32,
+            -0x12345678i32,
+            vec![1.5f64, -2.5],
+            ObjectPath::try_from("/org/example").unwrap(),
+            Signature::try_from("a{sv}").unwrap(),
+            Value::new(dict),
+            vec![vec![0xabu8, 0xcd], vec![]],
+        );
+        let signature = "(b(qn)uiadogvaay)";
+
+        for position in [0, 3] {
+            let le = to_bytes(Context::new_dbus(LE, position), &value).unwrap();
+            let be = to_bytes(Context::new_dbus(BE, position), &value).unwrap();
+            assert_ne!(le.bytes(), be.bytes());
+
+            let converted = be.to_endian(signature, LE).unwrap();
+            assert_eq!(converted.bytes(), le.bytes());
+            assert_eq!(converted.context(), le.context());
+            let converted = le.to_endian(signature, BE).unwrap();
+            assert_eq!(converted.bytes(), be.bytes());
+            // Nothing to do for the same byte order.
+            let converted = le.to_endian(signature, LE).unwrap();
+            assert_eq!(converted.bytes(), le.bytes());
+        }
+
+        let ctxt = Context::new_dbus(BE, 0);
+        let encoded = to_bytes(ctxt, &vec!["hello", "world"]).unwrap();
+        assert!(matches!(
+            encoded.slice(..encoded.len() - 4).to_endian("as", LE),
+            Err(Error::OutOfBounds),
+        ));
+        encoded.to_endian("ai", LE).unwrap_err();
+    }
+
+    #[test]
+    fn timestamps() {
+        use std::time::{Duration, SystemTime};
+
+        #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+        struct Times {
+            #[zvariant(signature = "t")]
+            #[serde(with = "crate::timestamp::usec")]
+            since: SystemTime,
+            #[zvariant(signature = "x")]
+            #[serde(with = "crate::timestamp::signed::msec")]
+            before: SystemTime,
+            #[zvariant(signature = "t")]
+            #[serde(with = "crate::timestamp::sec")]
+            timeout: Duration,
+        }
+        assert_eq!(Times::signature(), "(txt)");
+
+        let times = Times {
+            since: SystemTime::UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456),
+            before: SystemTime::UNIX_EPOCH - Duration::from_millis(1_500),
+            timeout: Duration::from_secs(90),
+        };
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &times).unwrap();
+        let raw: (u64, i64, u64) = encoded.deserialize().unwrap().0;
+        assert_eq!(raw, (1_700_000_000_123_456, -1_500, 90));
+        let decoded: Times = encoded.deserialize().unwrap().0;
+        assert_eq!(decoded, times);
+
+        // Timestamps before the epoch don't fit in unsigned integers.
+        let times = Times {
+            since: times.before,
+            ..times
+        };
+        to_bytes(ctxt, &times).unwrap_err();
+
+        // Values are rounded down to the unit.
+        let times = Times {
+            since: SystemTime::UNIX_EPOCH + Duration::from_nanos(1_999),
+            before: SystemTime::UNIX_EPOCH - Duration::from_nanos(1),
+            timeout: Duration::from_millis(1_999),
+        };
+        let encoded = to_bytes(ctxt, &times).unwrap();
+        let raw: (u64, i64, u64) = encoded.deserialize().unwrap().0;
+        assert_eq!(raw, (1, -1, 1));
+
+        #[cfg(feature = "chrono")]
+        {
+            #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+            struct ChronoTimes(
+                #[zvariant(signature = "x")]
+                #[serde(with = "crate::timestamp::signed::usec")]
+                chrono::DateTime<chrono::Utc>,
+                #[zvariant(signature = "t")]
+                #[serde(with = "crate::timestamp::nsec")]
+                chrono::Duration,
+            );
+
+            let times = ChronoTimes(
+                chrono::DateTime::from_timestamp(-42, 999_000).unwrap(),
+                chrono::Duration::milliseconds(1_234),
+            );
+            let encoded = to_bytes(ctxt, &times).unwrap();
+            let raw: (i64, u64) = encoded.deserialize().unwrap().0;
+            assert_eq!(raw, (-41_999_001, 1_234_000_000));
+            let decoded: ChronoTimes = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded, times);
+        }
+
+        #[cfg(feature = "time")]
+        {
+            #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+            struct TimeTimes(
+                #[zvariant(signature = "t")]
+                #[serde(with = "crate::timestamp::usec")]
+                time::OffsetDateTime,
+                #[zvariant(signature = "x")]
+                #[serde(with = "crate::timestamp::signed::usec")]
+                time::Duration,
+            );
+
+            let times = TimeTimes(
+                time::OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap(),
+                time::Duration::microseconds(-1_500_001),
+            );
+            let encoded = to_bytes(ctxt, &times).unwrap();
+            let raw: (u64, i64) = encoded.deserialize().unwrap().0;
+            assert_eq!(raw, (1_700_000_000_000_000, -1_500_001));
+            let decoded: TimeTimes = encoded.deserialize().unwrap().0;
+            assert_eq!(decoded, times);
+        }
+    }
+
     #[test]
     fn recursion_limits() {
         let ctxt = Context::new_dbus(LE, 0);
@@ -1,7 +1,10 @@
+use alloc::{boxed::Box, format};
+use core::fmt::Display;
 use serde::ser::{Serialize, Serializer};
 use static_assertions::assert_impl_all;
-use std::fmt::Display;
 
+#[cfg(all(unix, feature = "std"))]
+use crate::Fd;
 use crate::{value_display_fmt, Error, Signature, Type, Value};
 
 /// A helper type to wrap `Option<T>` (GVariant's Maybe type) in [`Value`].
@@ -77,6 +80,23 @@ impl<'a> Maybe<'a> {
             .transpose()
     }
 
+    /// Get the inner value as a concrete type, consuming `self`.
+    ///
+    /// This is the owned counterpart of [`Maybe::get`], allowing to convert a `Maybe` back to the
+    /// `Option<T>` it was created from.
+    pub fn downcast<T>(self) -> core::result::Result<Option<T>, Error>
+    where
+        T: ?Sized + TryFrom<Value<'a>>,
+        <T as TryFrom<Value<'a>>>::Error: Into<crate::Error>,
+    {
+        (*self.value).map(|v| v.downcast()).transpose()
+    }
+
+    /// Get the underlying value, consuming `self`.
+    pub fn into_inner(self) -> Option<Value<'a>> {
+        *self.value
+    }
+
     /// Get the signature of `Maybe`.
     ///
     /// NB: This method potentially allocates and copies. Use [`full_signature`] if you'd like to
@@ -125,19 +145,29 @@ impl<'a> Maybe<'a> {
             signature: self.signature.clone(),
         })
     }
+
+    #[cfg(all(unix, feature = "std"))]
+    pub(crate) fn map_fds<F>(self, f: &mut F) -> Result<Self, Error>
+    where
+        F: FnMut(Fd<'a>) -> Result<Fd<'a>, Error>,
+    {
+        let value = Box::new((*self.value).map(|v| v.map_fds_with(f)).transpose()?);
+
+        Ok(Self { value, ..self })
+    }
 }
 
 impl Display for Maybe<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         maybe_display_fmt(self, f, true)
     }
 }
 
 pub(crate) fn maybe_display_fmt(
     maybe: &Maybe<'_>,
-    f: &mut std::fmt::Formatter<'_>,
+    f: &mut core::fmt::Formatter<'_>,
     type_annotate: bool,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     if type_annotate {
         write!(f, "@{} ", maybe.full_signature())?;
     }
@@ -1,10 +1,13 @@
+use alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+};
 use core::{fmt::Debug, str};
 use serde::{
     de::{self, Deserialize, Deserializer, Visitor},
     ser::{Serialize, Serializer},
 };
 use static_assertions::assert_impl_all;
-use std::borrow::Cow;
 
 use crate::{serialized::Format, Basic, Error, Result, Signature, Str, Type};
 
@@ -62,7 +65,7 @@ impl<'a> ObjectPath<'a> {
     ///
     /// See [`std::str::from_utf8_unchecked`].
     pub unsafe fn from_bytes_unchecked<'s: 'a>(bytes: &'s [u8]) -> Self {
-        Self(std::str::from_utf8_unchecked(bytes).into())
+        Self(core::str::from_utf8_unchecked(bytes).into())
     }
 
     /// Create a new `ObjectPath` from the given string.
@@ -114,7 +117,7 @@ impl<'a> ObjectPath<'a> {
     }
 }
 
-impl std::default::Default for ObjectPath<'_> {
+impl core::default::Default for ObjectPath<'_> {
     fn default() -> Self {
         ObjectPath::from_str_unchecked("/")
     }
@@ -186,7 +189,7 @@ impl<'o> From<&ObjectPath<'o>> for ObjectPath<'o> {
     }
 }
 
-impl<'a> std::ops::Deref for ObjectPath<'a> {
+impl<'a> core::ops::Deref for ObjectPath<'a> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -207,14 +210,14 @@ impl<'a> PartialEq<&str> for ObjectPath<'a> {
 }
 
 impl<'a> Debug for ObjectPath<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("ObjectPath").field(&self.as_str()).finish()
     }
 }
 
-impl<'a> std::fmt::Display for ObjectPath<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.as_str(), f)
+impl<'a> core::fmt::Display for ObjectPath<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.as_str(), f)
     }
 }
 
@@ -243,7 +246,7 @@ struct ObjectPathVisitor;
 impl<'de> Visitor<'de> for ObjectPathVisitor {
     type Value = ObjectPath<'de>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("an ObjectPath")
     }
 
@@ -321,7 +324,7 @@ impl Basic for OwnedObjectPath {
     }
 }
 
-impl std::ops::Deref for OwnedObjectPath {
+impl core::ops::Deref for OwnedObjectPath {
     type Target = ObjectPath<'static>;
 
     fn deref(&self) -> &Self::Target {
@@ -329,13 +332,13 @@ impl std::ops::Deref for OwnedObjectPath {
     }
 }
 
-impl std::convert::From<OwnedObjectPath> for ObjectPath<'static> {
+impl core::convert::From<OwnedObjectPath> for ObjectPath<'static> {
     fn from(o: OwnedObjectPath) -> Self {
         o.into_inner()
     }
 }
 
-impl std::convert::From<OwnedObjectPath> for crate::Value<'_> {
+impl core::convert::From<OwnedObjectPath> for crate::Value<'_> {
     fn from(o: OwnedObjectPath) -> Self {
         o.into_inner().into()
     }
@@ -347,7 +350,7 @@ impl<'unowned, 'owned: 'unowned> From<&'owned OwnedObjectPath> for ObjectPath<'u
     }
 }
 
-impl<'a> std::convert::From<ObjectPath<'a>> for OwnedObjectPath {
+impl<'a> core::convert::From<ObjectPath<'a>> for OwnedObjectPath {
     fn from(o: ObjectPath<'a>) -> Self {
         OwnedObjectPath(o.into_owned())
     }
@@ -380,9 +383,9 @@ impl<'de> Deserialize<'de> for OwnedObjectPath {
     }
 }
 
-impl std::fmt::Display for OwnedObjectPath {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.as_str(), f)
+impl core::fmt::Display for OwnedObjectPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.as_str(), f)
     }
 }
 
@@ -1,13 +1,17 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use alloc::{borrow::ToOwned, string::String, sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use core::hash::BuildHasher;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use static_assertions::assert_impl_all;
-use std::{collections::HashMap, hash::BuildHasher};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
 
 use crate::{
     Array, Dict, NoneValue, ObjectPath, Optional, OwnedObjectPath, OwnedSignature, Signature, Str,
     Structure, Type, Value,
 };
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 #[cfg(feature = "gvariant")]
@@ -17,23 +21,65 @@ use crate::Maybe;
 // https://github.com/dbus2/zbus/issues/138
 
 /// Owned [`Value`](enum.Value.html)
-#[derive(Debug, PartialEq, Serialize, Type)]
-pub struct OwnedValue(pub(crate) Value<'static>);
+///
+/// The value is reference-counted, so [`OwnedValue::try_clone`] is cheap and can't fail,
+/// regardless of the size of the value, as long as it doesn't contain any file descriptors. The
+/// clones then share the same value. Values containing file descriptors are never shared: they're
+/// copied, which duplicates the file descriptors and hence can fail.
+#[derive(Debug, PartialEq)]
+pub struct OwnedValue {
+    value: Arc<Value<'static>>,
+    has_fds: bool,
+}
 
 assert_impl_all!(OwnedValue: Send, Sync, Unpin);
 
 impl OwnedValue {
+    pub(crate) fn new(value: Value<'static>) -> Self {
+        Self {
+            has_fds: value.has_fds(),
+            value: Arc::new(value),
+        }
+    }
+
     /// Attempt to clone the value.
+    ///
+    /// Unless the value contains file descriptors, this never fails and the clone shares the
+    /// value with `self`. Otherwise, the value is copied, duplicating the file descriptors.
     pub fn try_clone(&self) -> Result<Self, crate::Error> {
-        self.0.try_clone().map(Self)
+        if self.has_fds {
+            return self.value.try_clone().map(Self::new);
+        }
+
+        Ok(Self {
+            value: self.value.clone(),
+            has_fds: false,
+        })
+    }
+
+    /// Whether `self` and `other` share the same value, i-e one is a clone of the other.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
     }
 
     pub(crate) fn into_inner(self) -> Value<'static> {
-        self.0
+        match Arc::try_unwrap(self.value) {
+            Ok(value) => value,
+            // Only values without file descriptors are shared and copying those can't fail.
+            Err(value) => value
+                .try_clone()
+                .unwrap_or_else(|_| unreachable!("shared value with file descriptors")),
+        }
     }
 
     pub(crate) fn inner(&self) -> &Value<'_> {
-        &self.0
+        &self.value
+    }
+}
+
+impl Type for OwnedValue {
+    fn signature() -> Signature<'static> {
+        Value::signature()
     }
 }
 
@@ -43,7 +89,7 @@ macro_rules! ov_try_from {
             type Error = crate::Error;
 
             fn try_from(v: OwnedValue) -> Result<Self, Self::Error> {
-                <$to>::try_from(v.0)
+                <$to>::try_from(v.into_inner())
             }
         }
     };
@@ -55,7 +101,7 @@ macro_rules! ov_try_from_ref {
             type Error = crate::Error;
 
             fn try_from(v: &'a OwnedValue) -> Result<Self, Self::Error> {
-                <$to>::try_from(&v.0)
+                <$to>::try_from(v.inner())
             }
         }
     };
@@ -81,7 +127,7 @@ ov_try_from!(Dict<'static, 'static>);
 ov_try_from!(Maybe<'static>);
 ov_try_from!(Str<'static>);
 ov_try_from!(Structure<'static>);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 ov_try_from!(Fd<'static>);
 
 ov_try_from_ref!(u8);
@@ -102,7 +148,7 @@ ov_try_from_ref!(&'a Str<'a>);
 ov_try_from_ref!(&'a Structure<'a>);
 #[cfg(feature = "gvariant")]
 ov_try_from_ref!(&'a Maybe<'a>);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 ov_try_from_ref!(&'a Fd<'a>);
 
 impl<'a, T> TryFrom<OwnedValue> for Vec<T>
@@ -113,7 +159,7 @@ where
     type Error = crate::Error;
 
     fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
-        if let Value::Array(v) = value.0 {
+        if let Value::Array(v) = value.into_inner() {
             Self::try_from(v)
         } else {
             Err(crate::Error::IncorrectType)
@@ -130,13 +176,14 @@ where
     type Error = crate::Error;
 
     fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
-        Self::try_from(value.0)
+        Self::try_from(value.into_inner())
     }
 }
 
+#[cfg(feature = "std")]
 impl<'k, 'v, K, V, H> TryFrom<OwnedValue> for HashMap<K, V, H>
 where
-    K: crate::Basic + TryFrom<Value<'k>> + std::hash::Hash + std::cmp::Eq,
+    K: crate::Basic + TryFrom<Value<'k>> + core::hash::Hash + core::cmp::Eq,
     V: TryFrom<Value<'v>>,
     H: BuildHasher + Default,
     K::Error: Into<crate::Error>,
@@ -145,7 +192,7 @@ where
     type Error = crate::Error;
 
     fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
-        if let Value::Dict(v) = value.0 {
+        if let Value::Dict(v) = value.into_inner() {
             Self::try_from(v)
         } else {
             Err(crate::Error::IncorrectType)
@@ -153,14 +200,15 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 impl<K, V, H> From<HashMap<K, V, H>> for OwnedValue
 where
-    K: Type + Into<Value<'static>> + std::hash::Hash + std::cmp::Eq,
+    K: Type + Into<Value<'static>> + core::hash::Hash + core::cmp::Eq,
     V: Type + Into<Value<'static>>,
     H: BuildHasher + Default,
 {
     fn from(value: HashMap<K, V, H>) -> Self {
-        Self(value.into())
+        Self::new(value.into())
     }
 }
 
@@ -172,7 +220,7 @@ where
     type Error = crate::Error;
 
     fn try_from(value: OwnedValue) -> Result<Self, Self::Error> {
-        Self::try_from(value.0)
+        Self::try_from(value.into_inner())
     }
 }
 
@@ -181,7 +229,7 @@ where
     V: Into<Value<'static>> + NoneValue<NoneType = V>,
 {
     fn from(v: Optional<V>) -> OwnedValue {
-        Self(Value::from(v))
+        Self::new(Value::from(v))
     }
 }
 
@@ -208,7 +256,7 @@ macro_rules! to_value {
     ($from:ty, $variant:ident) => {
         impl<'a> From<$from> for OwnedValue {
             fn from(v: $from) -> Self {
-                OwnedValue(<Value<'static>>::$variant(v.to_owned()))
+                OwnedValue::new(<Value<'static>>::$variant(v.to_owned()))
             }
         }
     };
@@ -244,7 +292,7 @@ try_to_value!(Dict<'a, 'a>);
 #[cfg(feature = "gvariant")]
 try_to_value!(Maybe<'a>);
 try_to_value!(Structure<'a>);
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 try_to_value!(Fd<'a>);
 
 impl From<OwnedValue> for Value<'_> {
@@ -261,11 +309,20 @@ impl<'o> TryFrom<&'o OwnedValue> for Value<'o> {
     }
 }
 
-impl std::ops::Deref for OwnedValue {
+impl core::ops::Deref for OwnedValue {
     type Target = Value<'static>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
+    }
+}
+
+impl Serialize for OwnedValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.value.serialize(serializer)
     }
 }
 
@@ -321,6 +378,44 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn clone() -> Result<(), Box<dyn Error>> {
+        let map = HashMap::from([("one", vec![1u32]), ("two", vec![2, 2])]);
+        let value = OwnedValue::from(map.clone());
+        let clone = value.try_clone()?;
+        assert!(clone.ptr_eq(&value));
+        assert_eq!(clone, value);
+
+        // Converting a shared value leaves the other clones intact.
+        let map2 = <HashMap<String, Vec<u32>>>::try_from(clone)?;
+        assert_eq!(map2.len(), 2);
+        assert_eq!(map2["two"], [2, 2]);
+        let clone = value.try_clone()?;
+        assert_eq!(Value::from(value), Value::from(clone));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clone_fds() -> Result<(), Box<dyn Error>> {
+        use crate::{Fd, Structure};
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::File::open("/dev/null")?;
+        let s = Structure::from((1u32, Fd::from(&file)));
+        let value = Value::from(s).try_to_owned()?;
+        let clone = value.try_clone()?;
+        // Values with file descriptors are never shared, so converting them can't fail.
+        assert!(!clone.ptr_eq(&value));
+        let fds = |v: Value<'_>| v.fds().iter().map(|fd| fd.as_raw_fd()).collect::<Vec<_>>();
+        let (fds1, fds2) = (fds(Value::from(value)), fds(Value::from(clone)));
+        assert_eq!(fds1.len(), 1);
+        assert_ne!(fds1, fds2);
+
+        Ok(())
+    }
+
     #[test]
     fn map_conversion() -> Result<(), Box<dyn Error>> {
         let mut map = HashMap::<String, String>::new();
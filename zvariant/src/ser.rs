@@ -1,7 +1,7 @@
+use alloc::{format, vec};
 use serde::Serialize;
-use std::io::{Seek, Write};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use std::os::fd::OwnedFd;
 
 #[cfg(feature = "gvariant")]
@@ -9,32 +9,37 @@ use crate::gvariant::Serializer as GVSerializer;
 use crate::{
     container_depths::ContainerDepths,
     dbus::Serializer as DBusSerializer,
+    io::{self, Cursor, Seek, SeekFrom, Write, WriteBytes},
     serialized::{Context, Data, Format, Size, Written},
     signature_parser::SignatureParser,
     utils::*,
-    Basic, DynamicType, Error, Result, Signature, WriteBytes,
+    Basic, DynamicType, Error, Result, Signature,
 };
 
 struct NullWriteSeek;
 
 impl Write for NullWriteSeek {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         Ok(buf.len())
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
+    fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
 }
 
 impl Seek for NullWriteSeek {
-    fn seek(&mut self, _pos: std::io::SeekFrom) -> std::io::Result<u64> {
-        Ok(std::u64::MAX) // should never read the return value!
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Ok(core::u64::MAX) // should never read the return value!
     }
 }
 
 /// Calculate the serialized size of `T`.
 ///
+/// The size is exact, including any padding needed for the position in `ctxt`, but no bytes are
+/// produced. This allows preallocating buffers or enforcing size limits before serializing. On
+/// Unix, the returned [`Size`] also holds the number of file descriptors the encoding refers to.
+///
 /// # Examples
 ///
 /// ```
@@ -46,14 +51,45 @@ impl Seek for NullWriteSeek {
 ///
 /// let len = serialized_size(ctxt, &("hello world!", 42_u64)).unwrap();
 /// assert_eq!(*len, 32);
+///
+/// // 4 bytes of padding are needed to align the `u64` at position 4.
+/// let ctxt = Context::new_dbus(LE, 4);
+/// let len = serialized_size(ctxt, &42_u64).unwrap();
+/// assert_eq!(*len, 12);
 /// ```
 pub fn serialized_size<T>(ctxt: Context, value: &T) -> Result<Size>
 where
     T: ?Sized + Serialize + DynamicType,
+{
+    serialized_size_for_signature(ctxt, value.dynamic_signature(), value)
+}
+
+/// Calculate the serialized size of `T` that has the given signature.
+///
+/// Use this function instead of [`serialized_size`] if the value does not implement
+/// [`DynamicType`].
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, serialized_size_for_signature, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let len = serialized_size_for_signature(ctxt, "(ss)", &["hello", "world"]).unwrap();
+/// assert_eq!(*len, 22);
+/// ```
+pub fn serialized_size_for_signature<'s, S, T>(
+    ctxt: Context,
+    signature: S,
+    value: &T,
+) -> Result<Size>
+where
+    S: TryInto<Signature<'s>>,
+    S::Error: Into<Error>,
+    T: ?Sized + Serialize,
 {
     let mut null = NullWriteSeek;
-    let signature = value.dynamic_signature();
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     let mut fds = FdList::Number(0);
 
     let len = match ctxt.format() {
@@ -61,7 +97,7 @@ where
             let mut ser = DBusSerializer::<NullWriteSeek>::new(
                 signature,
                 &mut null,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 &mut fds,
                 ctxt,
             )?;
@@ -73,7 +109,7 @@ where
             let mut ser = GVSerializer::<NullWriteSeek>::new(
                 signature,
                 &mut null,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 &mut fds,
                 ctxt,
             )?;
@@ -83,7 +119,7 @@ where
     };
 
     let size = Size::new(len, ctxt);
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     let size = match fds {
         FdList::Number(n) => size.set_num_fds(n),
         FdList::Fds(_) => unreachable!("`Fds::Fds` is not possible here"),
@@ -119,6 +155,7 @@ where
 /// hence is safe to drop.
 ///
 /// [`to_writer_fds`]: fn.to_writer_fds.html
+#[cfg(feature = "std")]
 pub unsafe fn to_writer<W, T>(writer: &mut W, ctxt: Context, value: &T) -> Result<Written>
 where
     W: Write + Seek,
@@ -155,6 +192,7 @@ where
 /// hence is safe to drop.
 ///
 /// [`to_writer`]: fn.to_writer.html
+#[cfg(feature = "std")]
 pub unsafe fn to_writer_for_signature<'s, W, S, T>(
     writer: &mut W,
     ctxt: Context,
@@ -167,7 +205,25 @@ where
     S::Error: Into<Error>,
     T: ?Sized + Serialize,
 {
-    #[cfg(unix)]
+    write_for_signature(writer, ctxt, signature, value)
+}
+
+// The implementation of `to_writer_for_signature`, which is also available without `std`.
+//
+// SAFETY: Same as `to_writer_for_signature`.
+unsafe fn write_for_signature<'s, W, S, T>(
+    writer: &mut W,
+    ctxt: Context,
+    signature: S,
+    value: &T,
+) -> Result<Written>
+where
+    W: Write + Seek,
+    S: TryInto<Signature<'s>>,
+    S::Error: Into<Error>,
+    T: ?Sized + Serialize,
+{
+    #[cfg(all(unix, feature = "std"))]
     let mut fds = FdList::Fds(vec![]);
 
     let len = match ctxt.format() {
@@ -175,7 +231,7 @@ where
             let mut ser = DBusSerializer::<W>::new(
                 signature,
                 writer,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 &mut fds,
                 ctxt,
             )?;
@@ -187,7 +243,7 @@ where
             let mut ser = GVSerializer::<W>::new(
                 signature,
                 writer,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 &mut fds,
                 ctxt,
             )?;
@@ -197,7 +253,7 @@ where
     };
 
     let written = Written::new(len, ctxt);
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     let written = match fds {
         FdList::Fds(fds) => written.set_fds(fds),
         FdList::Number(_) => unreachable!("`Fds::Number` is not possible here"),
@@ -224,13 +280,13 @@ where
     S::Error: Into<Error>,
     T: ?Sized + Serialize,
 {
-    let mut cursor = std::io::Cursor::new(vec![]);
+    let mut cursor = Cursor::new(vec![]);
     // SAFETY: We put the bytes and FDs in the `Data` to ensure that the data and FDs are only
     // dropped together.
-    let ret = unsafe { to_writer_for_signature(&mut cursor, ctxt, signature, value) }?;
-    #[cfg(unix)]
+    let ret = unsafe { write_for_signature(&mut cursor, ctxt, signature, value) }?;
+    #[cfg(all(unix, feature = "std"))]
     let encoded = Data::new_fds(cursor.into_inner(), ctxt, ret.into_fds());
-    #[cfg(not(unix))]
+    #[cfg(not(all(unix, feature = "std")))]
     let encoded = {
         let _ = ret;
         Data::new(cursor.into_inner(), ctxt)
@@ -244,7 +300,7 @@ pub(crate) struct SerializerCommon<'ser, 'sig, W> {
     pub(crate) ctxt: Context,
     pub(crate) writer: &'ser mut W,
     pub(crate) bytes_written: usize,
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub(crate) fds: &'ser mut FdList,
 
     pub(crate) sig_parser: SignatureParser<'sig>,
@@ -254,7 +310,8 @@ pub(crate) struct SerializerCommon<'ser, 'sig, W> {
     pub(crate) container_depths: ContainerDepths,
 }
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
+#[derive(Debug)]
 pub(crate) enum FdList {
     Fds(Vec<OwnedFd>),
     Number(u32),
@@ -264,7 +321,7 @@ impl<'ser, 'sig, W> SerializerCommon<'ser, 'sig, W>
 where
     W: Write + Seek,
 {
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub(crate) fn add_fd(&mut self, fd: std::os::fd::RawFd) -> Result<u32> {
         use std::os::fd::{AsRawFd, BorrowedFd};
 
@@ -295,8 +352,7 @@ where
         if padding > 0 {
             let byte = [0_u8; 1];
             for _ in 0..padding {
-                self.write_all(&byte)
-                    .map_err(|e| Error::InputOutput(e.into()))?;
+                self.write_all(&byte).map_err(Error::from)?;
             }
         }
 
@@ -331,7 +387,7 @@ where
 
         // Now serialize the veriant index.
         self.write_u32(self.ctxt.endian(), variant_index)
-            .map_err(|e| Error::InputOutput(e.into()))?;
+            .map_err(Error::from)?;
 
         // Skip the `(`, `u`.
         self.sig_parser.skip_chars(2)?;
@@ -349,7 +405,7 @@ where
     W: Write + Seek,
 {
     /// Write `buf` and increment internal bytes written counter.
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.writer.write(buf).map(|n| {
             self.bytes_written += n;
 
@@ -357,7 +413,7 @@ where
         })
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
+    fn flush(&mut self) -> io::Result<()> {
         self.writer.flush()
     }
 }
@@ -33,6 +33,7 @@ pub struct Context {
     format: Format,
     position: usize,
     endian: Endian,
+    lenient: bool,
 }
 
 assert_impl_all!(Context: Send, Sync, Unpin);
@@ -44,6 +45,7 @@ impl Context {
             format,
             position,
             endian,
+            lenient: false,
         }
     }
 
@@ -76,4 +78,57 @@ impl Context {
     pub fn position(self) -> usize {
         self.position
     }
+
+    /// Set whether deserialization tolerates slightly out-of-spec data.
+    ///
+    /// Contexts are strict by default. Real-world services sometimes emit data that strict
+    /// deserialization rejects though, and debugging tools need to accept it anyway. In a lenient
+    /// context:
+    ///
+    /// * Non-0 padding bytes are ignored, instead of resulting in [`Error::PaddingNot0`].
+    /// * [`validate`] and [`Data::validate`] accept trailing bytes after the encoded values.
+    /// * Types deriving [`DeserializeDict`] with `deny_unknown_fields` skip unknown entries.
+    ///
+    /// This has no effect on serialization, which always produces canonical data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{serialized::{Context, Data}, Error, LE};
+    ///
+    /// // A byte, followed by non-0 padding before a `u32`.
+    /// let bytes = [7, 0xff, 0xff, 0xff, 42, 0, 0, 0];
+    /// let ctxt = Context::new_dbus(LE, 0);
+    /// let data = Data::new(&bytes[..], ctxt);
+    /// assert_eq!(
+    ///     data.deserialize::<(u8, u32)>().unwrap_err(),
+    ///     Error::PaddingNot0(0xff),
+    /// );
+    ///
+    /// let data = Data::new(&bytes[..], ctxt.with_lenient(true));
+    /// assert_eq!(data.deserialize::<(u8, u32)>().unwrap().0, (7, 42));
+    /// ```
+    ///
+    /// [`Error::PaddingNot0`]: crate::Error::PaddingNot0
+    /// [`validate`]: crate::validate
+    /// [`Data::validate`]: crate::serialized::Data::validate
+    /// [`DeserializeDict`]: crate::DeserializeDict
+    pub fn with_lenient(self, lenient: bool) -> Self {
+        Self { lenient, ..self }
+    }
+
+    /// Whether deserialization tolerates slightly out-of-spec data.
+    ///
+    /// See [`Context::with_lenient`] for details.
+    pub fn is_lenient(self) -> bool {
+        self.lenient
+    }
+
+    /// The same context, `offset` bytes further in the message.
+    pub(crate) fn offset_by(self, offset: usize) -> Self {
+        Self {
+            position: self.position + offset,
+            ..self
+        }
+    }
 }
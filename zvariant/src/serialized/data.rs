@@ -1,17 +1,14 @@
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::{Fd, OwnedFd};
-use std::{
-    borrow::Cow,
-    ops::{Bound, Deref, Range, RangeBounds},
-    sync::Arc,
-};
+use alloc::{borrow::Cow, sync::Arc};
+use core::ops::{Bound, Deref, Range, RangeBounds};
 
 use serde::{de::DeserializeSeed, Deserialize};
 
 use crate::{
     de::Deserializer,
-    serialized::{Context, Format},
-    DynamicDeserialize, DynamicType, Error, Result, Signature, Type,
+    serialized::{swap::swap_dbus_endian, Context, Format},
+    DynamicDeserialize, DynamicType, Endian, Error, Result, Signature, Type, ValidationError,
 };
 
 /// Represents serialized bytes in a specific format.
@@ -29,17 +26,17 @@ pub struct Data<'bytes, 'fds> {
 #[derive(Debug)]
 pub struct Inner<'bytes, 'fds> {
     bytes: Cow<'bytes, [u8]>,
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     fds: Vec<Fd<'fds>>,
-    #[cfg(not(unix))]
-    _fds: std::marker::PhantomData<&'fds ()>,
+    #[cfg(not(all(unix, feature = "std")))]
+    _fds: core::marker::PhantomData<&'fds ()>,
 }
 
 impl<'bytes, 'fds> Data<'bytes, 'fds> {
     /// Create a new `Data` instance containing borrowed file descriptors.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn new_borrowed_fds<T>(
         bytes: T,
         context: Context,
@@ -76,7 +73,7 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
     /// The file descriptors that are references by the serialized bytes.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn fds(&self) -> &[Fd<'fds>] {
         &self.inner.fds
     }
@@ -104,11 +101,7 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
         );
         assert!(end <= len, "range end out of bounds: {end:?} > {len:?}");
 
-        let context = Context::new(
-            self.context.format(),
-            self.context.endian(),
-            self.context.position() + start,
-        );
+        let context = self.context.offset_by(start);
         let range = Range {
             start: self.range.start + start,
             end: self.range.start + end,
@@ -221,12 +214,12 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
     {
         let signature = signature.try_into().map_err(Into::into)?;
 
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         let fds = &self.inner.fds;
         let mut de = match self.context.format() {
             #[cfg(feature = "gvariant")]
             Format::GVariant => {
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 {
                     crate::gvariant::Deserializer::new(
                         self.bytes(),
@@ -235,18 +228,18 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
                         self.context,
                     )
                 }
-                #[cfg(not(unix))]
+                #[cfg(not(all(unix, feature = "std")))]
                 {
                     crate::gvariant::Deserializer::<()>::new(self.bytes(), signature, self.context)
                 }
             }
             .map(Deserializer::GVariant)?,
             Format::DBus => {
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 {
                     crate::dbus::Deserializer::new(self.bytes(), Some(fds), signature, self.context)
                 }
-                #[cfg(not(unix))]
+                #[cfg(not(all(unix, feature = "std")))]
                 {
                     crate::dbus::Deserializer::<()>::new(self.bytes(), signature, self.context)
                 }
@@ -288,12 +281,12 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
     {
         let signature = S::dynamic_signature(&seed).to_owned();
 
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         let fds = &self.inner.fds;
         let mut de = match self.context.format() {
             #[cfg(feature = "gvariant")]
             Format::GVariant => {
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 {
                     crate::gvariant::Deserializer::new(
                         self.bytes(),
@@ -302,18 +295,18 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
                         self.context,
                     )
                 }
-                #[cfg(not(unix))]
+                #[cfg(not(all(unix, feature = "std")))]
                 {
                     crate::gvariant::Deserializer::new(self.bytes(), signature, self.context)
                 }
             }
             .map(Deserializer::GVariant)?,
             Format::DBus => {
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 {
                     crate::dbus::Deserializer::new(self.bytes(), Some(fds), signature, self.context)
                 }
-                #[cfg(not(unix))]
+                #[cfg(not(all(unix, feature = "std")))]
                 {
                     crate::dbus::Deserializer::<()>::new(self.bytes(), signature, self.context)
                 }
@@ -327,6 +320,113 @@ impl<'bytes, 'fds> Data<'bytes, 'fds> {
             Deserializer::DBus(de) => (t, de.0.pos),
         })
     }
+
+    /// Deserialize `T` from `self`, with its allocations made in `bump`.
+    ///
+    /// See [`DeserializeIn`] for details. This method is only available with the `bumpalo`
+    /// feature.
+    ///
+    /// # Return value
+    ///
+    /// A tuple containing the deserialized value and the number of bytes parsed from `bytes`.
+    ///
+    /// [`DeserializeIn`]: crate::DeserializeIn
+    #[cfg(feature = "bumpalo")]
+    pub fn deserialize_in<'d, 'b, T>(&'d self, bump: &'b bumpalo::Bump) -> Result<(T, usize)>
+    where
+        T: crate::DeserializeIn<'d, 'b> + Type,
+    {
+        self.deserialize_with_seed(crate::InArena::new(bump))
+    }
+
+    /// Check that `self` is a valid encoding of values of `signature`, without decoding them.
+    ///
+    /// Same as [`crate::validate`], except that file descriptors in the encoding are checked
+    /// against the ones in `self`.
+    pub fn validate<'s, S>(&self, signature: S) -> core::result::Result<(), ValidationError>
+    where
+        S: TryInto<Signature<'s>>,
+        S::Error: Into<Error>,
+    {
+        crate::validate::validate_with_fds(
+            self.bytes(),
+            #[cfg(all(unix, feature = "std"))]
+            Some(&self.inner.fds),
+            signature,
+            self.context,
+        )
+    }
+
+    /// Convert `self` to the `endian` byte order, given the signature of the encoded value(s).
+    ///
+    /// Since the layout of the D-Bus format doesn't depend on the byte order, this only swaps the
+    /// bytes of the encoded numbers. Any bytes after the encoded value(s) are copied as is. The
+    /// file descriptors are cloned into the returned `Data`, since they're referenced by index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{serialized::Context, to_bytes, BE, LE};
+    ///
+    /// let ctxt = Context::new_dbus(BE, 0);
+    /// let encoded = to_bytes(ctxt, &(0x1234u16, vec!["hello"])).unwrap();
+    /// let converted = encoded.to_endian("(qas)", LE).unwrap();
+    /// assert_eq!(converted.context().endian(), LE);
+    /// assert_eq!(&converted[..2], &[0x34, 0x12]);
+    /// let decoded: (u16, Vec<&str>) = converted.deserialize().unwrap().0;
+    /// assert_eq!(decoded, (0x1234, vec!["hello"]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `self` isn't in the D-Bus format, or doesn't contain a valid encoding of `signature`.
+    pub fn to_endian<'s, S>(&self, signature: S, endian: Endian) -> Result<Data<'static, 'fds>>
+    where
+        S: TryInto<Signature<'s>>,
+        S::Error: Into<Error>,
+    {
+        let signature = signature.try_into().map_err(Into::into)?;
+        match self.context.format() {
+            Format::DBus => (),
+            #[cfg(feature = "gvariant")]
+            Format::GVariant => {
+                return Err(Error::Message(
+                    "byte order conversion is only supported for the D-Bus format".into(),
+                ))
+            }
+        }
+
+        let mut bytes = self.bytes().to_vec();
+        if endian != self.context.endian() {
+            swap_dbus_endian(
+                &mut bytes,
+                self.context.position(),
+                &signature,
+                self.context.endian(),
+            )?;
+        }
+        let range = Range {
+            start: 0,
+            end: bytes.len(),
+        };
+
+        Ok(Data {
+            inner: Arc::new(Inner {
+                bytes: Cow::Owned(bytes),
+                #[cfg(all(unix, feature = "std"))]
+                fds: self
+                    .fds()
+                    .iter()
+                    .map(|fd| fd.try_clone())
+                    .collect::<Result<_>>()?,
+                #[cfg(not(all(unix, feature = "std")))]
+                _fds: core::marker::PhantomData,
+            }),
+            context: Context::new_dbus(endian, self.context.position())
+                .with_lenient(self.context.is_lenient()),
+            range,
+        })
+    }
 }
 
 impl<'bytes> Data<'bytes, 'static> {
@@ -343,10 +443,10 @@ impl<'bytes> Data<'bytes, 'static> {
         Data {
             inner: Arc::new(Inner {
                 bytes,
-                #[cfg(unix)]
+                #[cfg(all(unix, feature = "std"))]
                 fds: vec![],
-                #[cfg(not(unix))]
-                _fds: std::marker::PhantomData,
+                #[cfg(not(all(unix, feature = "std")))]
+                _fds: core::marker::PhantomData,
             }),
             context,
             range,
@@ -356,7 +456,7 @@ impl<'bytes> Data<'bytes, 'static> {
     /// Create a new `Data` instance containing owned file descriptors.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn new_fds<T>(
         bytes: T,
         context: Context,
@@ -14,8 +14,8 @@ pub enum Format {
 
 assert_impl_all!(Format: Send, Sync, Unpin);
 
-impl std::fmt::Display for Format {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Format {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Format::DBus => write!(f, "D-Bus"),
             #[cfg(feature = "gvariant")]
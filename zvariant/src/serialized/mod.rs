@@ -8,3 +8,4 @@ mod format;
 pub use format::Format;
 mod context;
 pub use context::Context;
+mod swap;
@@ -1,4 +1,4 @@
-use std::ops::Deref;
+use core::ops::Deref;
 
 use crate::serialized::Context;
 
@@ -12,7 +12,7 @@ use crate::serialized::Context;
 pub struct Size {
     size: usize,
     context: Context,
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     num_fds: u32,
 }
 
@@ -22,13 +22,13 @@ impl Size {
         Self {
             size,
             context,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             num_fds: 0,
         }
     }
 
     /// Set the number of file descriptors.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn set_num_fds(mut self, num_fds: u32) -> Self {
         self.num_fds = num_fds;
         self
@@ -47,7 +47,7 @@ impl Size {
     /// The number file descriptors that are references by the serialized bytes.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn num_fds(&self) -> u32 {
         self.num_fds
     }
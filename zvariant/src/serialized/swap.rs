@@ -0,0 +1,209 @@
+use alloc::{format, string::String};
+use endi::Endian;
+
+use crate::{utils::*, Error, MaxDepthExceeded, Result, Signature};
+
+// The maximum total depth of containers allowed by the D-Bus specification.
+const MAX_DEPTH: usize = 64;
+
+/// Swap the byte order of the D-Bus encoded `bytes` of values of `signature`, in place.
+///
+/// `endian` is the current byte order of `bytes` and `position` is their position in the whole
+/// encoded data, which the alignment is relative to. Returns the number of bytes swapped.
+pub(crate) fn swap_dbus_endian(
+    bytes: &mut [u8],
+    position: usize,
+    signature: &Signature<'_>,
+    endian: Endian,
+) -> Result<usize> {
+    let mut swapper = Swapper {
+        bytes,
+        position,
+        pos: 0,
+        endian,
+        depth: 0,
+    };
+    let signature = signature.as_bytes();
+    let mut i = 0;
+    while i < signature.len() {
+        let len = complete_type_len(&signature[i..]);
+        swapper.swap(&signature[i..i + len])?;
+        i += len;
+    }
+
+    Ok(swapper.pos)
+}
+
+struct Swapper<'b> {
+    bytes: &'b mut [u8],
+    position: usize,
+    pos: usize,
+    endian: Endian,
+    depth: usize,
+}
+
+impl Swapper<'_> {
+    /// Swap a value of the single complete type `signature`.
+    fn swap(&mut self, signature: &[u8]) -> Result<()> {
+        match signature[0] {
+            b'y' => {
+                self.take(1)?;
+            }
+            b'n' | b'q' => self.swap_n(2)?,
+            b'b' | b'i' | b'u' | b'h' => self.swap_n(4)?,
+            b'x' | b't' | b'd' => self.swap_n(8)?,
+            b's' | b'o' => {
+                let len = self.swap_u32()?;
+                // The string and its trailing nul byte.
+                self.take(len + 1)?;
+            }
+            b'g' => {
+                let len = self.take(1)?[0] as usize;
+                self.take(len + 1)?;
+            }
+            b'v' => {
+                let len = self.take(1)?[0] as usize;
+                let value_signature = self.take(len + 1)?[..len].to_vec();
+                let value_signature =
+                    core::str::from_utf8(&value_signature).map_err(Error::Utf8)?;
+                let value_signature = Signature::try_from(value_signature)?;
+                let value_signature = value_signature.as_bytes();
+                if value_signature.is_empty()
+                    || complete_type_len(value_signature) != value_signature.len()
+                {
+                    return Err(Error::Message(format!(
+                        "variant signature `{}` is not a single complete type",
+                        String::from_utf8_lossy(value_signature),
+                    )));
+                }
+
+                self.enter()?;
+                self.swap(value_signature)?;
+                self.depth -= 1;
+            }
+            b'a' => {
+                let len = self.swap_u32()?;
+                let element_signature = &signature[1..];
+                // The padding of the first element isn't included in the length of the array.
+                self.align(alignment(element_signature[0]))?;
+                let end = self.pos + len;
+                if end > self.bytes.len() {
+                    return Err(Error::OutOfBounds);
+                }
+
+                self.enter()?;
+                while self.pos < end {
+                    self.swap(element_signature)?;
+                }
+                if self.pos != end {
+                    return Err(Error::OutOfBounds);
+                }
+                self.depth -= 1;
+            }
+            b'(' | b'{' => {
+                self.align(8)?;
+
+                self.enter()?;
+                // Skip the opening and closing characters.
+                let fields = &signature[1..signature.len() - 1];
+                let mut i = 0;
+                while i < fields.len() {
+                    let len = complete_type_len(&fields[i..]);
+                    self.swap(&fields[i..i + len])?;
+                    i += len;
+                }
+                self.depth -= 1;
+            }
+            c => {
+                return Err(Error::Message(format!(
+                    "type `{}` is not supported in the D-Bus format",
+                    c as char,
+                )))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn take(&mut self, n: usize) -> Result<&mut [u8]> {
+        let end = self.pos.checked_add(n).ok_or(Error::OutOfBounds)?;
+        let bytes = self
+            .bytes
+            .get_mut(self.pos..end)
+            .ok_or(Error::OutOfBounds)?;
+        self.pos = end;
+
+        Ok(bytes)
+    }
+
+    fn align(&mut self, alignment: usize) -> Result<()> {
+        let padding = padding_for_n_bytes(self.position + self.pos, alignment);
+        self.take(padding)?;
+
+        Ok(())
+    }
+
+    fn swap_n(&mut self, n: usize) -> Result<()> {
+        self.align(n)?;
+        self.take(n)?.reverse();
+
+        Ok(())
+    }
+
+    /// Swap a `u32` and return its value.
+    fn swap_u32(&mut self) -> Result<usize> {
+        self.align(4)?;
+        let endian = self.endian;
+        let bytes = self.take(4)?;
+        let n = endian.read_u32(bytes);
+        bytes.reverse();
+
+        Ok(n as usize)
+    }
+
+    fn enter(&mut self) -> Result<()> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::MaxDepthExceeded(MaxDepthExceeded::Container));
+        }
+
+        Ok(())
+    }
+}
+
+/// The length of the first complete type in the valid `signature`.
+fn complete_type_len(signature: &[u8]) -> usize {
+    match signature[0] {
+        b'a' => 1 + complete_type_len(&signature[1..]),
+        b'(' | b'{' => {
+            let mut depth = 0;
+            for (i, c) in signature.iter().enumerate() {
+                match c {
+                    b'(' | b'{' => depth += 1,
+                    b')' | b'}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return i + 1;
+                        }
+                    }
+                    _ => (),
+                }
+            }
+
+            signature.len()
+        }
+        _ => 1,
+    }
+}
+
+/// The D-Bus alignment of the type starting with `c`.
+fn alignment(c: u8) -> usize {
+    match c {
+        b'n' | b'q' => 2,
+        b'b' | b'i' | b'u' | b'h' | b's' | b'o' | b'a' => ARRAY_ALIGNMENT_DBUS,
+        b'x' | b't' | b'd' => 8,
+        b'(' => STRUCT_ALIGNMENT_DBUS,
+        b'{' => DICT_ENTRY_ALIGNMENT_DBUS,
+        _ => 1,
+    }
+}
@@ -1,6 +1,6 @@
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::OwnedFd;
-use std::ops::Deref;
+use core::ops::Deref;
 
 use crate::serialized::Context;
 
@@ -14,7 +14,7 @@ use crate::serialized::Context;
 pub struct Written {
     size: usize,
     context: Context,
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     fds: Vec<OwnedFd>,
 }
 
@@ -24,13 +24,13 @@ impl Written {
         Self {
             size,
             context,
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             fds: vec![],
         }
     }
 
     /// Set the file descriptors.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn set_fds(mut self, fds: impl IntoIterator<Item = impl Into<OwnedFd>>) -> Self {
         self.fds = fds.into_iter().map(Into::into).collect();
         self
@@ -49,7 +49,7 @@ impl Written {
     /// Consume `self` and return the file descriptors.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn into_fds(self) -> Vec<OwnedFd> {
         self.fds
     }
@@ -57,7 +57,7 @@ impl Written {
     /// The file descriptors that are references by the serialized bytes.
     ///
     /// This method is only available on Unix platforms.
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     pub fn fds(&self) -> &[OwnedFd] {
         &self.fds
     }
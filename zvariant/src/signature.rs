@@ -1,7 +1,9 @@
+use alloc::{borrow::Cow, string::String, sync::Arc, vec::Vec};
 use core::{
     cmp::Ordering,
     fmt::{self, Debug, Display, Formatter},
     hash::{Hash, Hasher},
+    ops::{Bound, RangeBounds},
     str,
 };
 use serde::{
@@ -9,13 +11,13 @@ use serde::{
     ser::{Serialize, Serializer},
 };
 use static_assertions::assert_impl_all;
-use std::{
-    borrow::Cow,
-    ops::{Bound, RangeBounds},
-    sync::Arc,
-};
 
-use crate::{serialized::Format, signature_parser::SignatureParser, Basic, Error, Result, Type};
+#[cfg(feature = "gvariant")]
+use crate::MAYBE_SIGNATURE_CHAR;
+use crate::{
+    serialized::Format, signature_parser::SignatureParser, Basic, Error, Result, Type,
+    ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_START_CHAR, STRUCT_SIG_START_CHAR,
+};
 
 // A data type similar to Cow and [`bytes::Bytes`] but unlike the former won't allow us to only keep
 // the owned bytes in Arc and latter doesn't have a notion of borrowed data and would require API
@@ -48,7 +50,7 @@ impl<'b> Bytes<'b> {
     }
 }
 
-impl std::ops::Deref for Bytes<'_> {
+impl core::ops::Deref for Bytes<'_> {
     type Target = [u8];
 
     fn deref(&self) -> &[u8] {
@@ -336,10 +338,152 @@ impl<'a> Signature<'a> {
         }
         Ok(count)
     }
+
+    /// Iterator over the complete types of the signature.
+    ///
+    /// The signature is validated as it's iterated over, so the iterator yields an error for the
+    /// first invalid complete type.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let sig = Signature::try_from("ua{sv}(bo)").unwrap();
+    /// let types = sig.complete_types().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(types, ["u", "a{sv}", "(bo)"]);
+    /// ```
+    pub fn complete_types(&self) -> CompleteTypes<'a> {
+        CompleteTypes(Some(SignatureParser::new(self.clone())))
+    }
+
+    /// The signature of the elements, if `self` is an array signature.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let sig = Signature::try_from("a(sv)").unwrap();
+    /// assert_eq!(sig.array_element().unwrap(), "(sv)");
+    /// Signature::try_from("s").unwrap().array_element().unwrap_err();
+    /// ```
+    pub fn array_element(&self) -> Result<Signature<'a>> {
+        self.expect_container(ARRAY_SIGNATURE_CHAR, "an array signature")?;
+
+        Ok(self.slice(1..))
+    }
+
+    /// The signature of the child, if `self` is a maybe signature.
+    #[cfg(feature = "gvariant")]
+    pub fn maybe_child(&self) -> Result<Signature<'a>> {
+        self.expect_container(MAYBE_SIGNATURE_CHAR, "a maybe signature")?;
+
+        Ok(self.slice(1..))
+    }
+
+    /// The signatures of the key and the value, if `self` is a dictionary signature.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let sig = Signature::try_from("a{oa{sv}}").unwrap();
+    /// let (key, value) = sig.dict_key_value().unwrap();
+    /// assert_eq!(key, "o");
+    /// assert_eq!(value, "a{sv}");
+    /// ```
+    pub fn dict_key_value(&self) -> Result<(Signature<'a>, Signature<'a>)> {
+        self.expect_container(ARRAY_SIGNATURE_CHAR, "a dictionary signature")?;
+        if self.as_bytes()[1] != DICT_ENTRY_SIG_START_CHAR as u8 {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(self.as_str()),
+                &"a dictionary signature",
+            ));
+        }
+
+        // `a{`, the key, the value and `}`. The key is always a single basic type.
+        Ok((self.slice(2..3), self.slice(3..self.len() - 1)))
+    }
+
+    /// Iterator over the signatures of the fields, if `self` is a structure signature.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// let sig = Signature::try_from("(so(ii))").unwrap();
+    /// let fields = sig.structure_fields().unwrap();
+    /// let fields = fields.collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(fields.len(), 3);
+    /// assert_eq!(fields[2].as_str(), "(ii)");
+    /// ```
+    pub fn structure_fields(&self) -> Result<CompleteTypes<'a>> {
+        self.expect_container(STRUCT_SIG_START_CHAR, "a structure signature")?;
+
+        Ok(self.slice(1..self.len() - 1).complete_types())
+    }
+
+    /// The maximum nesting depth of containers in the signature.
+    ///
+    /// Arrays, structures, dictionary entries and maybes each add a level. Variants don't, since
+    /// the signature doesn't say anything about their contents.
+    ///
+    /// ```
+    /// use zvariant::Signature;
+    ///
+    /// assert_eq!(Signature::try_from("us").unwrap().nesting_depth().unwrap(), 0);
+    /// assert_eq!(Signature::try_from("a{sv}").unwrap().nesting_depth().unwrap(), 2);
+    /// assert_eq!(Signature::try_from("(aay)u").unwrap().nesting_depth().unwrap(), 3);
+    /// ```
+    pub fn nesting_depth(&self) -> Result<usize> {
+        let mut depth = 0;
+        for sig in self.complete_types() {
+            let sig = sig?;
+            let child_depth = match sig.as_bytes()[0] as char {
+                STRUCT_SIG_START_CHAR | DICT_ENTRY_SIG_START_CHAR => {
+                    1 + sig.slice(1..sig.len() - 1).nesting_depth()?
+                }
+                ARRAY_SIGNATURE_CHAR => 1 + sig.slice(1..).nesting_depth()?,
+                #[cfg(feature = "gvariant")]
+                MAYBE_SIGNATURE_CHAR => 1 + sig.slice(1..).nesting_depth()?,
+                _ => 0,
+            };
+            depth = depth.max(child_depth);
+        }
+
+        Ok(depth)
+    }
+
+    // Checks that `self` is a single complete type, of the container type starting with `c`.
+    fn expect_container(&self, c: char, expected: &str) -> Result<()> {
+        if self.n_complete_types()? != 1 || self.as_bytes()[0] != c as u8 {
+            return Err(serde::de::Error::invalid_value(
+                serde::de::Unexpected::Str(self.as_str()),
+                &expected,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Iterator over the complete types of a [`Signature`].
+///
+/// Use [`Signature::complete_types`] or [`Signature::structure_fields`] to create it.
+#[derive(Debug, Clone)]
+pub struct CompleteTypes<'a>(Option<SignatureParser<'a>>);
+
+impl<'a> Iterator for CompleteTypes<'a> {
+    type Item = Result<Signature<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.0.as_mut()?.next();
+        if let Some(Err(_)) = next {
+            // The parser doesn't advance on errors.
+            self.0 = None;
+        }
+
+        next
+    }
 }
 
 impl<'a> Debug for Signature<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Signature").field(&self.as_str()).finish()
     }
 }
@@ -423,7 +567,7 @@ impl<'a> From<&Signature<'a>> for String {
     }
 }
 
-impl<'a> std::ops::Deref for Signature<'a> {
+impl<'a> core::ops::Deref for Signature<'a> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -481,7 +625,7 @@ impl Eq for Signature<'_> {}
 
 impl<'a> Display for Signature<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        std::fmt::Display::fmt(&self.as_str(), f)
+        core::fmt::Display::fmt(&self.as_str(), f)
     }
 }
 
@@ -499,7 +643,7 @@ impl<'de: 'a, 'a> Deserialize<'de> for Signature<'a> {
     where
         D: Deserializer<'de>,
     {
-        let val = <std::borrow::Cow<'a, str>>::deserialize(deserializer)?;
+        let val = <alloc::borrow::Cow<'a, str>>::deserialize(deserializer)?;
 
         Self::try_from(val).map_err(serde::de::Error::custom)
     }
@@ -526,7 +670,7 @@ impl Basic for OwnedSignature {
     }
 }
 
-impl std::ops::Deref for OwnedSignature {
+impl core::ops::Deref for OwnedSignature {
     type Target = Signature<'static>;
 
     fn deref(&self) -> &Self::Target {
@@ -534,19 +678,19 @@ impl std::ops::Deref for OwnedSignature {
     }
 }
 
-impl std::convert::From<OwnedSignature> for Signature<'static> {
+impl core::convert::From<OwnedSignature> for Signature<'static> {
     fn from(o: OwnedSignature) -> Self {
         o.into_inner()
     }
 }
 
-impl<'a> std::convert::From<Signature<'a>> for OwnedSignature {
+impl<'a> core::convert::From<Signature<'a>> for OwnedSignature {
     fn from(o: Signature<'a>) -> Self {
         OwnedSignature(o.into_owned())
     }
 }
 
-impl std::convert::From<OwnedSignature> for crate::Value<'static> {
+impl core::convert::From<OwnedSignature> for crate::Value<'static> {
     fn from(o: OwnedSignature) -> Self {
         o.into_inner().into()
     }
@@ -571,9 +715,9 @@ impl<'de> Deserialize<'de> for OwnedSignature {
     }
 }
 
-impl std::fmt::Display for OwnedSignature {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.as_str(), f)
+impl core::fmt::Display for OwnedSignature {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(&self.as_str(), f)
     }
 }
 
@@ -636,4 +780,43 @@ mod tests {
         let sig_b = Signature::from_str_unchecked("(so)u");
         assert_ne!(sig_a, sig_b);
     }
+
+    #[test]
+    fn signature_decomposition() {
+        let sig = Signature::from_str_unchecked("a{s(ia(bo))}x");
+        assert_eq!(sig.nesting_depth().unwrap(), 5);
+        assert_eq!(sig.complete_types().count(), 2);
+        sig.dict_key_value().unwrap_err();
+
+        let dict = sig.slice(..sig.len() - 1);
+        let (key, value) = dict.dict_key_value().unwrap();
+        assert_eq!(key, "s");
+        assert_eq!(value.as_str(), "(ia(bo))");
+        let fields: Vec<_> = value
+            .structure_fields()
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(fields, ["i", "a(bo)"]);
+        assert_eq!(fields[1].array_element().unwrap().as_str(), "(bo)");
+        dict.array_element().unwrap();
+        dict.structure_fields().unwrap_err();
+        fields[0].array_element().unwrap_err();
+
+        // Iteration stops after the first invalid complete type.
+        let invalid = Signature::from_str_unchecked("u(z)s");
+        let mut types = invalid.complete_types();
+        assert_eq!(types.next().unwrap().unwrap(), "u");
+        types.next().unwrap().unwrap_err();
+        assert!(types.next().is_none());
+        invalid.nesting_depth().unwrap_err();
+
+        #[cfg(feature = "gvariant")]
+        {
+            let maybe = Signature::from_str_unchecked("mas");
+            assert_eq!(maybe.nesting_depth().unwrap(), 2);
+            assert_eq!(maybe.maybe_child().unwrap(), "as");
+            maybe.array_element().unwrap_err();
+        }
+    }
 }
@@ -0,0 +1,100 @@
+use alloc::string::String;
+
+#[cfg(feature = "gvariant")]
+use crate::MAYBE_SIGNATURE_CHAR;
+use crate::{
+    Result, Signature, Type, ARRAY_SIGNATURE_CHAR, DICT_ENTRY_SIG_END_CHAR,
+    DICT_ENTRY_SIG_START_CHAR, STRUCT_SIG_END_CHAR, STRUCT_SIG_START_CHAR,
+};
+
+/// Builds a [`Signature`] from its parts.
+///
+/// This saves code generators and other users creating signatures at runtime from formatting
+/// signature strings themselves. The signature is only validated once [`build`] is called.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{SignatureBuilder, Type};
+///
+/// let props = SignatureBuilder::new()
+///     .add_dict(&String::signature(), &"v".try_into().unwrap());
+/// let sig = SignatureBuilder::new()
+///     .add::<u32>()
+///     .add_structure(SignatureBuilder::new().add::<&str>().add_array(&u8::signature()))
+///     .add_signature(&props.build().unwrap())
+///     .build()
+///     .unwrap();
+/// assert_eq!(sig, "u(say)a{sv}");
+///
+/// // Structures need at least one field.
+/// SignatureBuilder::new().add_structure(SignatureBuilder::new()).build().unwrap_err();
+/// ```
+///
+/// [`build`]: SignatureBuilder::build
+#[derive(Debug, Default, Clone)]
+pub struct SignatureBuilder(String);
+
+impl SignatureBuilder {
+    /// Create a builder for an empty signature.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append the signature of `T`.
+    pub fn add<T: Type + ?Sized>(self) -> Self {
+        self.add_signature(&T::signature())
+    }
+
+    /// Append the complete types of `signature`.
+    pub fn add_signature(mut self, signature: &Signature<'_>) -> Self {
+        self.0.push_str(signature.as_str());
+
+        self
+    }
+
+    /// Append an array of `element`.
+    pub fn add_array(mut self, element: &Signature<'_>) -> Self {
+        self.0.push(ARRAY_SIGNATURE_CHAR);
+
+        self.add_signature(element)
+    }
+
+    /// Append a dictionary, mapping `key` to `value`.
+    pub fn add_dict(mut self, key: &Signature<'_>, value: &Signature<'_>) -> Self {
+        self.0.push(ARRAY_SIGNATURE_CHAR);
+        self.0.push(DICT_ENTRY_SIG_START_CHAR);
+        self.0.push_str(key.as_str());
+        self.0.push_str(value.as_str());
+        self.0.push(DICT_ENTRY_SIG_END_CHAR);
+
+        self
+    }
+
+    /// Append a structure whose fields are the complete types built by `fields`.
+    pub fn add_structure(mut self, fields: SignatureBuilder) -> Self {
+        self.0.push(STRUCT_SIG_START_CHAR);
+        self.0.push_str(&fields.0);
+        self.0.push(STRUCT_SIG_END_CHAR);
+
+        self
+    }
+
+    /// Append a maybe of `child`.
+    #[cfg(feature = "gvariant")]
+    pub fn add_maybe(mut self, child: &Signature<'_>) -> Self {
+        self.0.push(MAYBE_SIGNATURE_CHAR);
+
+        self.add_signature(child)
+    }
+
+    /// Validate and return the signature.
+    ///
+    /// # Errors
+    ///
+    /// If the resulting signature is invalid, e.g. because a structure has no fields or a
+    /// dictionary key isn't a basic type.
+    pub fn build(self) -> Result<Signature<'static>> {
+        Signature::try_from(self.0)
+    }
+}
@@ -1,8 +1,9 @@
-use std::ops::{Bound, RangeBounds};
+use alloc::{format, string::ToString};
+use core::ops::{Bound, RangeBounds};
 
 use crate::{subslice, Basic, ObjectPath, Result, Signature, STRUCT_SIG_END_CHAR};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 #[cfg(feature = "gvariant")]
@@ -162,7 +163,7 @@ impl<'s> SignatureParser<'s> {
             | ObjectPath::SIGNATURE_CHAR
             | Signature::SIGNATURE_CHAR
             | VARIANT_SIGNATURE_CHAR => Ok(self.signature_slice(0, 1)),
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Fd::SIGNATURE_CHAR => Ok(self.signature_slice(0, 1)),
             ARRAY_SIGNATURE_CHAR => self.next_array_signature(),
             STRUCT_SIG_START_CHAR => self.next_structure_signature(),
@@ -1,11 +1,14 @@
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use static_assertions::assert_impl_all;
-use std::{
-    borrow::Cow,
+use alloc::{
+    borrow::{Cow, ToOwned},
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::{
     cmp::Ordering,
     hash::{Hash, Hasher},
-    sync::Arc,
 };
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use static_assertions::assert_impl_all;
 
 use crate::{serialized::Format, Basic, Signature, Type};
 
@@ -185,7 +188,7 @@ impl<'a> From<&'a Str<'_>> for &'a str {
     }
 }
 
-impl<'a> std::ops::Deref for Str<'a> {
+impl<'a> core::ops::Deref for Str<'a> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
@@ -205,15 +208,15 @@ impl<'a> PartialEq<&str> for Str<'a> {
     }
 }
 
-impl<'a> std::fmt::Debug for Str<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Debug::fmt(self.as_str(), f)
+impl<'a> core::fmt::Debug for Str<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
     }
 }
 
-impl<'a> std::fmt::Display for Str<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(self.as_str(), f)
+impl<'a> core::fmt::Display for Str<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self.as_str(), f)
     }
 }
 
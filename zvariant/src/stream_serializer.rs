@@ -0,0 +1,488 @@
+use serde::Serialize;
+use static_assertions::assert_impl_all;
+use std::io::{Cursor, Write};
+#[cfg(feature = "futures-io")]
+use std::{future::poll_fn, io, pin::Pin};
+
+#[cfg(all(unix, feature = "std"))]
+use crate::ser::FdList;
+use crate::{
+    dbus::Serializer as DBusSerializer,
+    serialized::{Context, Format, Written},
+    serialized_size,
+    utils::*,
+    DynamicType, Error, Result, Type,
+};
+
+/// Serializes values one after the other into a writer, in the D-Bus format.
+///
+/// Unlike [`to_writer`], which needs to seek back to write the length of arrays, this only needs
+/// [`Write`]. Each value is encoded separately, so huge arrays can be written element by element,
+/// without ever holding the encoding of the whole array in memory. The position of each value is
+/// tracked, so it's correctly aligned relative to the start of the data.
+///
+/// It's the caller's responsibility to write values matching the expected signature, e.g the
+/// signature of a message body.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::{Context, Data}, StreamSerializer, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// // Equivalent to serializing `("hello", vec![0u64, 1, 2, ..., 999])`.
+/// let mut serializer = StreamSerializer::new(vec![], ctxt).unwrap();
+/// serializer.serialize("hello").unwrap();
+/// serializer.serialize_array(0..1000u64).unwrap();
+/// // SAFETY: No FDs are being serialized here so its completely safe.
+/// let (bytes, _) = unsafe { serializer.finish() };
+///
+/// let encoded = Data::new(bytes, ctxt);
+/// let (s, array): (String, Vec<u64>) = encoded.deserialize().unwrap().0;
+/// assert_eq!(s, "hello");
+/// assert_eq!(array.len(), 1000);
+/// assert_eq!(array[999], 999);
+/// ```
+///
+/// See [`AsyncStreamSerializer`] for the asynchronous counterpart of this type, writing into an
+/// `AsyncWrite`.
+///
+/// [`to_writer`]: fn.to_writer.html
+/// [`AsyncStreamSerializer`]: struct.AsyncStreamSerializer.html
+#[derive(Debug)]
+pub struct StreamSerializer<W> {
+    writer: W,
+    encoder: Encoder,
+}
+
+assert_impl_all!(StreamSerializer<Vec<u8>>: Send, Sync, Unpin);
+
+impl<W> StreamSerializer<W>
+where
+    W: Write,
+{
+    /// Create a new `StreamSerializer`, writing into `writer`.
+    ///
+    /// # Errors
+    ///
+    /// If `ctxt` isn't for the D-Bus format, since GVariant containers can only be encoded once
+    /// all their elements are known.
+    pub fn new(writer: W, ctxt: Context) -> Result<Self> {
+        Ok(Self {
+            writer,
+            encoder: Encoder::new(ctxt)?,
+        })
+    }
+
+    /// Serialize `value`, after the values serialized so far.
+    pub fn serialize<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize + DynamicType,
+    {
+        self.encoder.encode(value)?;
+
+        self.write_buffer()
+    }
+
+    /// Begin an array of `T`, whose elements take `len` bytes in total.
+    ///
+    /// The elements must then be serialized, using [`StreamSerializer::serialize`] or nested
+    /// arrays, and the array ended with [`StreamSerializer::end_array`]. The length of the
+    /// elements can be computed with [`StreamSerializer::array_len`] if it's not known upfront.
+    pub fn begin_array<T>(&mut self, len: usize) -> Result<()>
+    where
+        T: Type,
+    {
+        self.encoder.encode_array_start::<T>(len)?;
+
+        self.write_buffer()
+    }
+
+    /// End the innermost array begun with [`StreamSerializer::begin_array`].
+    ///
+    /// # Errors
+    ///
+    /// If there is no such array, or its elements don't take the length given to
+    /// [`StreamSerializer::begin_array`].
+    pub fn end_array(&mut self) -> Result<()> {
+        self.encoder.end_array()
+    }
+
+    /// Serialize an array of `elements`.
+    ///
+    /// `elements` is iterated twice: once to compute the length of the array, and once to
+    /// serialize each element. Hence, the elements can be produced lazily.
+    pub fn serialize_array<T, I>(&mut self, elements: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T> + Clone,
+        T: Serialize + Type,
+    {
+        let len = self.array_len(elements.clone())?;
+        self.begin_array::<T>(len)?;
+        for element in elements {
+            self.serialize(&element)?;
+        }
+
+        self.end_array()
+    }
+
+    /// The length of an array of `elements` begun at the current position, as expected by
+    /// [`StreamSerializer::begin_array`].
+    pub fn array_len<T, I>(&self, elements: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize + Type,
+    {
+        self.encoder.array_len(elements)
+    }
+
+    /// The number of bytes written so far.
+    pub fn bytes_written(&self) -> usize {
+        self.encoder.bytes_written
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Writing to the writer directly would break the alignment of the values serialized
+    /// afterwards.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume `self` and return the writer, along with the size of the serialized data and the
+    /// file descriptors it references.
+    ///
+    /// Arrays that have not been ended are left incomplete.
+    ///
+    /// # Safety
+    ///
+    /// On Unix systems, the returned [`Written`] instance can contain file descriptors and
+    /// therefore the caller is responsible for not dropping the returned [`Written`] instance
+    /// before the serialized data. Otherwise, the file descriptors in the `Written` instance will
+    /// be closed while serialized data will still refer to them. Hence why this method is marked
+    /// unsafe.
+    pub unsafe fn finish(self) -> (W, Written) {
+        (self.writer, self.encoder.finish())
+    }
+
+    fn write_buffer(&mut self) -> Result<()> {
+        self.writer
+            .write_all(&self.encoder.buffer)
+            .map_err(Error::from)?;
+        self.encoder.advance();
+
+        Ok(())
+    }
+}
+
+/// Serializes values one after the other into an `AsyncWrite`, in the D-Bus format.
+///
+/// This is the asynchronous counterpart of [`StreamSerializer`], writing into a
+/// [`futures_io::AsyncWrite`]. Writers implementing tokio's `AsyncWrite` can be used through the
+/// `compat` module of the `tokio-util` crate.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::{Context, Data}, AsyncStreamSerializer, LE};
+///
+/// # futures_executor::block_on(async {
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let mut serializer = AsyncStreamSerializer::new(vec![], ctxt).unwrap();
+/// serializer.serialize("hello").await.unwrap();
+/// serializer.serialize_array(0..1000u64).await.unwrap();
+/// // SAFETY: No FDs are being serialized here so its completely safe.
+/// let (bytes, _) = unsafe { serializer.finish() };
+///
+/// let encoded = Data::new(bytes, ctxt);
+/// let (s, array): (String, Vec<u64>) = encoded.deserialize().unwrap().0;
+/// assert_eq!(s, "hello");
+/// assert_eq!(array[999], 999);
+/// # });
+/// ```
+///
+/// [`futures_io::AsyncWrite`]: https://docs.rs/futures-io/latest/futures_io/trait.AsyncWrite.html
+#[cfg(feature = "futures-io")]
+#[derive(Debug)]
+pub struct AsyncStreamSerializer<W> {
+    writer: W,
+    encoder: Encoder,
+}
+
+#[cfg(feature = "futures-io")]
+assert_impl_all!(AsyncStreamSerializer<Vec<u8>>: Send, Sync, Unpin);
+
+#[cfg(feature = "futures-io")]
+impl<W> AsyncStreamSerializer<W>
+where
+    W: futures_io::AsyncWrite + Unpin,
+{
+    /// Create a new `AsyncStreamSerializer`, writing into `writer`.
+    ///
+    /// # Errors
+    ///
+    /// If `ctxt` isn't for the D-Bus format, since GVariant containers can only be encoded once
+    /// all their elements are known.
+    pub fn new(writer: W, ctxt: Context) -> Result<Self> {
+        Ok(Self {
+            writer,
+            encoder: Encoder::new(ctxt)?,
+        })
+    }
+
+    /// Serialize `value`, after the values serialized so far.
+    pub async fn serialize<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize + DynamicType,
+    {
+        self.encoder.encode(value)?;
+
+        self.write_buffer().await
+    }
+
+    /// Begin an array of `T`, whose elements take `len` bytes in total.
+    ///
+    /// See [`StreamSerializer::begin_array`].
+    pub async fn begin_array<T>(&mut self, len: usize) -> Result<()>
+    where
+        T: Type,
+    {
+        self.encoder.encode_array_start::<T>(len)?;
+
+        self.write_buffer().await
+    }
+
+    /// End the innermost array begun with [`AsyncStreamSerializer::begin_array`].
+    ///
+    /// See [`StreamSerializer::end_array`].
+    pub fn end_array(&mut self) -> Result<()> {
+        self.encoder.end_array()
+    }
+
+    /// Serialize an array of `elements`.
+    ///
+    /// See [`StreamSerializer::serialize_array`].
+    pub async fn serialize_array<T, I>(&mut self, elements: I) -> Result<()>
+    where
+        I: IntoIterator<Item = T> + Clone,
+        T: Serialize + Type,
+    {
+        let len = self.array_len(elements.clone())?;
+        self.begin_array::<T>(len).await?;
+        for element in elements {
+            self.serialize(&element).await?;
+        }
+
+        self.end_array()
+    }
+
+    /// The length of an array of `elements` begun at the current position, as expected by
+    /// [`AsyncStreamSerializer::begin_array`].
+    pub fn array_len<T, I>(&self, elements: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize + Type,
+    {
+        self.encoder.array_len(elements)
+    }
+
+    /// The number of bytes written so far.
+    pub fn bytes_written(&self) -> usize {
+        self.encoder.bytes_written
+    }
+
+    /// Get a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    ///
+    /// Writing to the writer directly would break the alignment of the values serialized
+    /// afterwards.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume `self` and return the writer, along with the size of the serialized data and the
+    /// file descriptors it references.
+    ///
+    /// # Safety
+    ///
+    /// See [`StreamSerializer::finish`].
+    pub unsafe fn finish(self) -> (W, Written) {
+        (self.writer, self.encoder.finish())
+    }
+
+    async fn write_buffer(&mut self) -> Result<()> {
+        let mut buffer = &self.encoder.buffer[..];
+        while !buffer.is_empty() {
+            let n = poll_fn(|cx| Pin::new(&mut self.writer).poll_write(cx, buffer)).await?;
+            if n == 0 {
+                return Err(io::Error::from(io::ErrorKind::WriteZero).into());
+            }
+            buffer = &buffer[n..];
+        }
+        self.encoder.advance();
+
+        Ok(())
+    }
+}
+
+// Encodes each value separately, keeping track of the position and of the arrays being written.
+// The writing itself is left to the (a)synchronous serializers.
+#[derive(Debug)]
+struct Encoder {
+    ctxt: Context,
+    bytes_written: usize,
+    // The encoding of the last value, to be written. Reused for each value.
+    buffer: Vec<u8>,
+    #[cfg(all(unix, feature = "std"))]
+    fds: FdList,
+    // The position at which each of the arrays begun but not yet ended, must end.
+    array_ends: Vec<usize>,
+}
+
+impl Encoder {
+    fn new(ctxt: Context) -> Result<Self> {
+        match ctxt.format() {
+            Format::DBus => (),
+            #[cfg(feature = "gvariant")]
+            Format::GVariant => {
+                return Err(Error::Message(
+                    "streaming serialization is only supported for the D-Bus format".to_string(),
+                ))
+            }
+        }
+
+        Ok(Self {
+            ctxt,
+            bytes_written: 0,
+            buffer: vec![],
+            #[cfg(all(unix, feature = "std"))]
+            fds: FdList::Fds(vec![]),
+            array_ends: vec![],
+        })
+    }
+
+    fn encode<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize + DynamicType,
+    {
+        let ctxt = self.current_context();
+        self.buffer.clear();
+        let mut cursor = Cursor::new(&mut self.buffer);
+        let mut ser = DBusSerializer::new(
+            value.dynamic_signature(),
+            &mut cursor,
+            #[cfg(all(unix, feature = "std"))]
+            &mut self.fds,
+            ctxt,
+        )?;
+        value.serialize(&mut ser)?;
+
+        self.check_array_end()
+    }
+
+    fn encode_array_start<T>(&mut self, len: usize) -> Result<()>
+    where
+        T: Type,
+    {
+        if len > u32::MAX as usize {
+            return Err(Error::Message(format!(
+                "array length {len} doesn't fit in 32 bits"
+            )));
+        }
+        self.encode(&usize_to_u32(len))?;
+
+        // The padding of the first element isn't included in the length of the array.
+        let alignment = alignment_for_signature(&T::signature(), Format::DBus)?;
+        let position = self.current_context().position() + self.buffer.len();
+        let padding = padding_for_n_bytes(position, alignment);
+        self.buffer.resize(self.buffer.len() + padding, 0);
+        self.check_array_end()?;
+
+        self.array_ends
+            .push(self.bytes_written + self.buffer.len() + len);
+
+        Ok(())
+    }
+
+    fn end_array(&mut self) -> Result<()> {
+        let end = self
+            .array_ends
+            .pop()
+            .ok_or_else(|| Error::Message("no array to end".to_string()))?;
+        if self.bytes_written < end {
+            return Err(Error::Message(format!(
+                "array is {} bytes short of its length",
+                end - self.bytes_written,
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn array_len<T, I>(&self, elements: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = T>,
+        T: Serialize + Type,
+    {
+        let alignment = alignment_for_signature(&T::signature(), Format::DBus)?;
+        let position = self.current_context().position();
+        // The length itself is a `u32`.
+        let position = position + padding_for_n_bytes(position, 4) + 4;
+        let start = position + padding_for_n_bytes(position, alignment);
+
+        let mut end = start;
+        for element in elements {
+            let ctxt = Context::new_dbus(self.ctxt.endian(), end);
+            end += *serialized_size(ctxt, &element)?;
+        }
+
+        Ok(end - start)
+    }
+
+    // To be called once the buffer has been written.
+    fn advance(&mut self) {
+        self.bytes_written += self.buffer.len();
+    }
+
+    fn finish(self) -> Written {
+        let written = Written::new(self.bytes_written, self.ctxt);
+        #[cfg(all(unix, feature = "std"))]
+        let written = match self.fds {
+            FdList::Fds(fds) => written.set_fds(fds),
+            FdList::Number(_) => unreachable!("`Fds::Number` is not possible here"),
+        };
+
+        written
+    }
+
+    fn current_context(&self) -> Context {
+        Context::new_dbus(
+            self.ctxt.endian(),
+            self.ctxt.position() + self.bytes_written,
+        )
+    }
+
+    // Check the value in the buffer doesn't go past the end of the innermost array.
+    fn check_array_end(&self) -> Result<()> {
+        if let Some(end) = self.array_ends.last() {
+            let value_end = self.bytes_written + self.buffer.len();
+            if value_end > *end {
+                return Err(Error::Message(format!(
+                    "value exceeds the length of the array by {} bytes",
+                    value_end - end,
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
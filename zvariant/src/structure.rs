@@ -1,11 +1,14 @@
 #![allow(unknown_lints)]
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{Display, Write};
 use serde::{
     de::{DeserializeSeed, Deserializer, Error, SeqAccess, Visitor},
     ser::{Serialize, SerializeTupleStruct, Serializer},
 };
 use static_assertions::assert_impl_all;
-use std::fmt::{Display, Write};
 
+#[cfg(all(unix, feature = "std"))]
+use crate::Fd;
 use crate::{
     signature_parser::SignatureParser, value::SignatureSeed, value_display_fmt, DynamicDeserialize,
     DynamicType, OwnedValue, Signature, Value,
@@ -135,7 +138,7 @@ struct StructureVisitor<'a> {
 impl<'de> Visitor<'de> for StructureVisitor<'de> {
     type Value = Structure<'de>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("a Structure value")
     }
 
@@ -213,19 +216,33 @@ impl<'a> Structure<'a> {
             signature: self.signature.clone(),
         })
     }
+
+    #[cfg(all(unix, feature = "std"))]
+    pub(crate) fn map_fds<F>(self, f: &mut F) -> crate::Result<Self>
+    where
+        F: FnMut(Fd<'a>) -> crate::Result<Fd<'a>>,
+    {
+        let fields = self
+            .fields
+            .into_iter()
+            .map(|v| v.map_fds_with(f))
+            .collect::<crate::Result<_>>()?;
+
+        Ok(Self { fields, ..self })
+    }
 }
 
 impl Display for Structure<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         structure_display_fmt(self, f, true)
     }
 }
 
 pub(crate) fn structure_display_fmt(
     structure: &Structure<'_>,
-    f: &mut std::fmt::Formatter<'_>,
+    f: &mut core::fmt::Formatter<'_>,
     type_annotate: bool,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     f.write_char('(')?;
 
     let fields = structure.fields();
@@ -369,7 +386,7 @@ macro_rules! tuple_impls {
                 type Error = crate::Error;
 
                 fn try_from(v: OwnedValue) -> core::result::Result<Self, Self::Error> {
-                    Self::try_from(Value::from(v))
+                    Self::try_from(v.into_inner())
                 }
             }
         )+
@@ -0,0 +1,611 @@
+//! Parsing of [`Value`] from the GVariant text format.
+//!
+//! The format is the one [`Value`]'s `Display` implementation prints, and that `gdbus` and GLib
+//! use, e.g `{'key': <'value'>}`. See the [GLib documentation] for details.
+//!
+//! [GLib documentation]: https://docs.gtk.org/glib/gvariant-text-format.html
+
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::str::FromStr;
+
+#[cfg(feature = "gvariant")]
+use crate::{utils::MAYBE_SIGNATURE_CHAR, Maybe};
+use crate::{Array, Dict, Error, ObjectPath, Result, Signature, StructureBuilder, Value};
+
+impl Value<'static> {
+    /// Parse a `Value` from its text representation.
+    ///
+    /// This is the reverse of [`Value`]'s `Display` implementation, which prints values in the
+    /// GVariant text format that `gdbus` and GLib use:
+    ///
+    /// * Booleans are `true` and `false`, and strings are quoted with `'` or `"`.
+    /// * Numbers without a fractional part are `int32`s and others are `double`s, unless they're
+    ///   annotated with a type keyword (`byte`, `int16`, `uint16`, `int32`, `uint32`, `int64`,
+    ///   `uint64` or `double`). Strings are strings, unless they're annotated with `objectpath` or
+    ///   `signature`.
+    /// * Arrays are `[...]`, dictionaries `{key: value, ...}`, structures `(...)` and variants
+    ///   `<...>`. Byte strings, i-e nul-terminated byte arrays, are `b'...'`.
+    /// * Maybe values are `nothing` and `just value` (with the `gvariant` feature).
+    /// * Any value can be prefixed with `@` and its signature, e.g. `@as []`.
+    ///
+    /// The types of the elements of arrays and dictionaries are those of the first element that
+    /// has one, so only the first element needs a type annotation. Values whose type can't be
+    /// inferred, e.g. empty arrays, need to be annotated. If the expected type is known, use
+    /// [`Value::parse_with_signature`] instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::Value;
+    ///
+    /// let v = Value::parse("{'volume': <byte 0x20>, 'muted': <false>}").unwrap();
+    /// assert_eq!(v.value_signature(), "a{sv}");
+    ///
+    /// let v = Value::parse("(objectpath '/org/freedesktop/DBus', @as [])").unwrap();
+    /// assert_eq!(v.value_signature(), "(oas)");
+    /// assert_eq!(Value::parse(&v.to_string()).unwrap(), v);
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let node = Parser::new(text).parse()?;
+        let signature = node.infer_signature().ok_or_else(|| {
+            Error::Message(format!(
+                "can't infer the type of the value at offset {}, annotate it with its \
+                 signature, e.g `@as []`",
+                node.offset,
+            ))
+        })?;
+
+        node.to_value(&signature)
+    }
+
+    /// Parse a `Value` of the given signature from its text representation.
+    ///
+    /// This is the same as [`Value::parse`], except that values are parsed as the given type
+    /// rather than their inferred one, so they need no annotations, e.g `[1, 2]` can be parsed as
+    /// an array of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use zvariant::{Signature, Value};
+    ///
+    /// let signature = Signature::try_from("(yoa{sv})").unwrap();
+    /// let v = Value::parse_with_signature("(3, '/a/b', {})", &signature).unwrap();
+    /// assert_eq!(v.value_signature(), signature);
+    ///
+    /// // Values still need to match the signature.
+    /// assert!(Value::parse_with_signature("(300, '/a/b', {})", &signature).is_err());
+    /// ```
+    pub fn parse_with_signature(text: &str, signature: &Signature<'_>) -> Result<Self> {
+        Parser::new(text).parse()?.to_value(signature)
+    }
+}
+
+impl FromStr for Value<'static> {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Value::parse(text)
+    }
+}
+
+// A parsed value, before its type is known.
+#[derive(Debug)]
+struct Node {
+    // The offset of the value in the text, for errors.
+    offset: usize,
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Variant(Box<Node>),
+    Array(Vec<Node>),
+    Dict(Vec<(Node, Node)>),
+    Tuple(Vec<Node>),
+    Nothing,
+    Just(Box<Node>),
+    Typed(Signature<'static>, Box<Node>),
+}
+
+impl Node {
+    fn infer_signature(&self) -> Option<Signature<'static>> {
+        let signature = match &self.kind {
+            Kind::Bool(_) => "b".to_string(),
+            Kind::Int(_) => "i".to_string(),
+            Kind::Float(_) => "d".to_string(),
+            Kind::Str(_) => "s".to_string(),
+            Kind::Bytes(_) => "ay".to_string(),
+            Kind::Variant(_) => "v".to_string(),
+            Kind::Array(elements) => {
+                let element = elements.iter().find_map(Node::infer_signature)?;
+
+                format!("a{element}")
+            }
+            Kind::Dict(entries) => {
+                let key = entries.iter().find_map(|(k, _)| k.infer_signature())?;
+                let value = entries.iter().find_map(|(_, v)| v.infer_signature())?;
+
+                format!("a{{{key}{value}}}")
+            }
+            Kind::Tuple(fields) => {
+                let mut signature = String::from("(");
+                for field in fields {
+                    signature.push_str(field.infer_signature()?.as_str());
+                }
+                signature.push(')');
+
+                signature
+            }
+            Kind::Nothing => return None,
+            Kind::Just(value) => format!("m{}", value.infer_signature()?),
+            Kind::Typed(signature, _) => return Some(signature.clone()),
+        };
+
+        Signature::try_from(signature).ok()
+    }
+
+    fn to_value(&self, signature: &Signature<'_>) -> Result<Value<'static>> {
+        let mismatch = || {
+            Error::Message(format!(
+                "value at offset {} doesn't match signature `{signature}`",
+                self.offset,
+            ))
+        };
+        let int = |v: i128| -> Result<Value<'static>> {
+            let value = match signature.as_str() {
+                "y" => Value::U8(v.try_into().map_err(|_| mismatch())?),
+                "n" => Value::I16(v.try_into().map_err(|_| mismatch())?),
+                "q" => Value::U16(v.try_into().map_err(|_| mismatch())?),
+                "i" => Value::I32(v.try_into().map_err(|_| mismatch())?),
+                "u" => Value::U32(v.try_into().map_err(|_| mismatch())?),
+                "x" => Value::I64(v.try_into().map_err(|_| mismatch())?),
+                "t" => Value::U64(v.try_into().map_err(|_| mismatch())?),
+                "d" => Value::F64(v as f64),
+                _ => return Err(mismatch()),
+            };
+
+            Ok(value)
+        };
+
+        let value = match (&self.kind, signature.as_bytes()[0]) {
+            (Kind::Typed(typed, value), _) => {
+                if typed != signature {
+                    return Err(mismatch());
+                }
+
+                return value.to_value(signature);
+            }
+            (Kind::Bool(v), b'b') => Value::Bool(*v),
+            (Kind::Int(v), c) if b"ynqiuxtd".contains(&c) => return int(*v),
+            (Kind::Float(v), b'd') => Value::F64(*v),
+            (Kind::Str(s), b's') => Value::from(s.clone()),
+            (Kind::Str(s), b'o') => Value::ObjectPath(ObjectPath::try_from(s.clone())?),
+            (Kind::Str(s), b'g') => Value::Signature(Signature::try_from(s.clone())?),
+            (Kind::Variant(value), b'v') => {
+                let signature = value.infer_signature().ok_or_else(|| {
+                    Error::Message(format!(
+                        "can't infer the type of the variant value at offset {}",
+                        value.offset,
+                    ))
+                })?;
+
+                Value::Value(Box::new(value.to_value(&signature)?))
+            }
+            (Kind::Bytes(bytes), _) if signature == "ay" => Value::from(bytes.clone()),
+            (Kind::Dict(entries), b'a') if signature.as_bytes()[1] == b'{' => {
+                let (key_signature, value_signature) = signature.dict_key_value()?;
+                let key_signature = key_signature.to_owned();
+                let value_signature = value_signature.to_owned();
+
+                let mut dict = Dict::new(key_signature.clone(), value_signature.clone());
+                for (key, value) in entries {
+                    dict.append(
+                        key.to_value(&key_signature)?,
+                        value.to_value(&value_signature)?,
+                    )?;
+                }
+
+                Value::Dict(dict)
+            }
+            // Dictionaries are arrays of entries, so `[]` is an empty dictionary too.
+            (Kind::Array(elements), b'a') if signature.as_bytes()[1] == b'{' => {
+                if !elements.is_empty() {
+                    return Err(mismatch());
+                }
+                let (key_signature, value_signature) = signature.dict_key_value()?;
+
+                Value::Dict(Dict::new(
+                    key_signature.to_owned(),
+                    value_signature.to_owned(),
+                ))
+            }
+            (Kind::Array(elements), b'a') => {
+                let element_signature = signature.array_element()?.to_owned();
+
+                let mut array = Array::new(element_signature.clone());
+                for element in elements {
+                    array.append(element.to_value(&element_signature)?)?;
+                }
+
+                Value::Array(array)
+            }
+            (Kind::Tuple(fields), b'(') => {
+                let mut fields = fields.iter();
+
+                let mut builder = StructureBuilder::new();
+                for field_signature in signature.structure_fields()? {
+                    let field = fields.next().ok_or_else(mismatch)?;
+                    builder = builder.append_field(field.to_value(&field_signature?)?);
+                }
+                if fields.next().is_some() {
+                    return Err(mismatch());
+                }
+
+                Value::Structure(builder.build())
+            }
+            #[cfg(feature = "gvariant")]
+            (Kind::Nothing, MAYBE_SIGNATURE_CHAR_BYTE) => {
+                Value::Maybe(Maybe::nothing(signature.maybe_child()?.to_owned()))
+            }
+            #[cfg(feature = "gvariant")]
+            (Kind::Just(value), MAYBE_SIGNATURE_CHAR_BYTE) => {
+                let child_signature = signature.maybe_child()?;
+
+                Value::Maybe(Maybe::just(value.to_value(&child_signature)?))
+            }
+            // Like GLib, allow leaving out `just` when it's not ambiguous.
+            #[cfg(feature = "gvariant")]
+            (_, MAYBE_SIGNATURE_CHAR_BYTE) => {
+                let child_signature = signature.maybe_child()?;
+
+                Value::Maybe(Maybe::just(self.to_value(&child_signature)?))
+            }
+            _ => return Err(mismatch()),
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(feature = "gvariant")]
+const MAYBE_SIGNATURE_CHAR_BYTE: u8 = MAYBE_SIGNATURE_CHAR as u8;
+
+// The keywords annotating basic types, and their signatures.
+const TYPE_KEYWORDS: &[(&str, &str)] = &[
+    ("boolean", "b"),
+    ("byte", "y"),
+    ("int16", "n"),
+    ("uint16", "q"),
+    ("int32", "i"),
+    ("uint32", "u"),
+    ("int64", "x"),
+    ("uint64", "t"),
+    ("handle", "h"),
+    ("double", "d"),
+    ("string", "s"),
+    ("objectpath", "o"),
+    ("signature", "g"),
+];
+
+struct Parser<'t> {
+    text: &'t str,
+    pos: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn new(text: &'t str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    // Parse the whole text as a single value.
+    fn parse(mut self) -> Result<Node> {
+        let node = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos != self.text.len() {
+            return Err(self.error("unexpected trailing characters"));
+        }
+
+        Ok(node)
+    }
+
+    fn parse_value(&mut self) -> Result<Node> {
+        self.skip_whitespace();
+        let offset = self.pos;
+        let c = self.peek().ok_or_else(|| self.error("expected a value"))?;
+
+        let kind = match c {
+            '@' => {
+                self.pos += 1;
+                let signature = self.parse_signature()?;
+
+                Kind::Typed(signature, Box::new(self.parse_value()?))
+            }
+            '<' => {
+                self.pos += 1;
+                let value = self.parse_value()?;
+                self.expect('>')?;
+
+                Kind::Variant(Box::new(value))
+            }
+            '[' => Kind::Array(self.parse_list('[', ']')?),
+            '(' => {
+                let fields = self.parse_list('(', ')')?;
+                if fields.is_empty() {
+                    return Err(Error::Message(format!(
+                        "empty structures aren't supported, at offset {offset}"
+                    )));
+                }
+
+                Kind::Tuple(fields)
+            }
+            '{' => self.parse_dict()?,
+            '\'' | '"' => Kind::Str(self.parse_string()?),
+            'b' if matches!(self.peek_nth(1), Some('\'' | '"')) => {
+                self.pos += 1;
+                let mut bytes = self.parse_string()?.into_bytes();
+                bytes.push(0);
+
+                Kind::Bytes(bytes)
+            }
+            c if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.parse_number()?,
+            c if c.is_ascii_alphabetic() => {
+                let word = self.parse_word();
+                match word {
+                    "true" => Kind::Bool(true),
+                    "false" => Kind::Bool(false),
+                    "nothing" => Kind::Nothing,
+                    "just" => Kind::Just(Box::new(self.parse_value()?)),
+                    "inf" => Kind::Float(f64::INFINITY),
+                    "nan" | "NaN" => Kind::Float(f64::NAN),
+                    _ => {
+                        let (_, signature) = TYPE_KEYWORDS
+                            .iter()
+                            .find(|(keyword, _)| *keyword == word)
+                            .ok_or_else(|| {
+                                Error::Message(format!(
+                                    "unknown keyword `{word}` at offset {offset}"
+                                ))
+                            })?;
+                        let signature = Signature::from_static_str_unchecked(signature);
+
+                        Kind::Typed(signature, Box::new(self.parse_value()?))
+                    }
+                }
+            }
+            _ => return Err(self.error("expected a value")),
+        };
+
+        Ok(Node { offset, kind })
+    }
+
+    // Parse comma-separated values between `open` and `close`. A trailing comma is allowed.
+    fn parse_list(&mut self, open: char, close: char) -> Result<Vec<Node>> {
+        self.expect(open)?;
+
+        let mut nodes = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(nodes);
+            }
+            nodes.push(self.parse_value()?);
+            self.skip_whitespace();
+            if !self.eat(',') {
+                self.expect(close)?;
+
+                return Ok(nodes);
+            }
+        }
+    }
+
+    fn parse_dict(&mut self) -> Result<Kind> {
+        self.expect('{')?;
+
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(Kind::Dict(entries));
+            }
+            let key = self.parse_value()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            entries.push((key, value));
+            self.skip_whitespace();
+            if !self.eat(',') {
+                self.expect('}')?;
+
+                return Ok(Kind::Dict(entries));
+            }
+        }
+    }
+
+    // Parse a single complete type, after a `@`.
+    fn parse_signature(&mut self) -> Result<Signature<'static>> {
+        fn complete_type_len(bytes: &[u8]) -> Option<usize> {
+            match bytes.first()? {
+                b'a' | b'm' => complete_type_len(&bytes[1..]).map(|len| len + 1),
+                open @ (b'(' | b'{') => {
+                    let close = if *open == b'(' { b')' } else { b'}' };
+                    let mut len = 1;
+                    while *bytes.get(len)? != close {
+                        len += complete_type_len(&bytes[len..])?;
+                    }
+
+                    Some(len + 1)
+                }
+                c if b"ybnqiuxtdsogvh".contains(c) => Some(1),
+                _ => None,
+            }
+        }
+
+        let rest = &self.text[self.pos..];
+        let len =
+            complete_type_len(rest.as_bytes()).ok_or_else(|| self.error("expected a signature"))?;
+        let signature = Signature::try_from(rest[..len].to_string())?;
+        self.pos += len;
+
+        Ok(signature)
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        let quote = self.next().ok_or_else(|| self.error("expected a string"))?;
+
+        let mut s = String::new();
+        loop {
+            let c = self
+                .next()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            if c == quote {
+                return Ok(s);
+            }
+            if c != '\\' {
+                s.push(c);
+
+                continue;
+            }
+
+            let escape = self
+                .next()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            let c = match escape {
+                'a' => '\u{07}',
+                'b' => '\u{08}',
+                'f' => '\u{0c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'v' => '\u{0b}',
+                '0' => '\0',
+                // `\uXXXX` and `\UXXXXXXXX` as in GLib, or `\u{...}` as printed by `Display`.
+                'u' | 'U' => {
+                    let digits = if escape == 'u' && self.eat('{') {
+                        let len = self.text[self.pos..]
+                            .find('}')
+                            .ok_or_else(|| self.error("unterminated unicode escape"))?;
+                        let digits = &self.text[self.pos..self.pos + len];
+                        self.pos += len + 1;
+
+                        digits
+                    } else {
+                        let len = if escape == 'u' { 4 } else { 8 };
+                        let digits = self
+                            .text
+                            .get(self.pos..self.pos + len)
+                            .ok_or_else(|| self.error("invalid unicode escape"))?;
+                        self.pos += len;
+
+                        digits
+                    };
+
+                    u32::from_str_radix(digits, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error("invalid unicode escape"))?
+                }
+                c => c,
+            };
+            s.push(c);
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Kind> {
+        let offset = self.pos;
+        let len = self.text[self.pos..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+            .unwrap_or(self.text.len() - self.pos);
+        let number = &self.text[self.pos..self.pos + len];
+        self.pos += len;
+        let invalid = || Error::Message(format!("invalid number `{number}` at offset {offset}"));
+
+        let (negative, digits) = match number.as_bytes()[0] {
+            b'-' => (true, &number[1..]),
+            b'+' => (false, &number[1..]),
+            _ => (false, number),
+        };
+        let int = match digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            Some(hex) => i128::from_str_radix(hex, 16).ok(),
+            None if digits.bytes().all(|c| c.is_ascii_digit()) => digits.parse().ok(),
+            None => {
+                let float = match digits {
+                    "inf" => f64::INFINITY,
+                    "nan" | "NaN" => f64::NAN,
+                    _ => digits.parse::<f64>().map_err(|_| invalid())?,
+                };
+
+                return Ok(Kind::Float(if negative { -float } else { float }));
+            }
+        };
+        let int = int.ok_or_else(invalid)?;
+
+        Ok(Kind::Int(if negative { -int } else { int }))
+    }
+
+    fn parse_word(&mut self) -> &'t str {
+        let text = self.text;
+        let len = text[self.pos..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(text.len() - self.pos);
+        let word = &text[self.pos..self.pos + len];
+        self.pos += len;
+
+        word
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.text[self.pos..].chars().nth(n)
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+
+            return true;
+        }
+
+        false
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if self.eat(c) {
+            return Ok(());
+        }
+
+        Err(self.error(&format!("expected `{c}`")))
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::Message(format!("{msg} at offset {}", self.pos))
+    }
+}
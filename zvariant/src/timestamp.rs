@@ -0,0 +1,252 @@
+//! Serialize and deserialize time types as integers.
+//!
+//! The [`Type`] implementations of [`SystemTime`] and [`Duration`] follow their serde
+//! representation, a structure of seconds and nanoseconds. Most D-Bus interfaces (e.g logind and
+//! UPower) represent timestamps and durations as a number of microseconds instead, the timestamps
+//! relative to the Unix epoch.
+//!
+//! The modules here are meant to be used with serde's `with` field attribute, along with the
+//! `signature` field attribute of the [`Type`] derive macro, to use such a representation. Each
+//! module is named after the unit it uses. Those directly in this module use unsigned integers
+//! (`t`) while those in [`signed`] use signed integers (`x`), for timestamps before the epoch or
+//! negative durations:
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use std::time::{Duration, SystemTime};
+//! use zvariant::{serialized::Context, to_bytes, Type, LE};
+//!
+//! #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+//! struct Session {
+//!     #[zvariant(signature = "t")]
+//!     #[serde(with = "zvariant::timestamp::usec")]
+//!     since: SystemTime,
+//!     #[zvariant(signature = "x")]
+//!     #[serde(with = "zvariant::timestamp::signed::sec")]
+//!     idle: Duration,
+//! }
+//!
+//! assert_eq!(Session::signature(), "(tx)");
+//! let session = Session {
+//!     since: SystemTime::UNIX_EPOCH + Duration::from_micros(1_700_000_000_000_042),
+//!     idle: Duration::from_secs(300),
+//! };
+//! let ctxt = Context::new_dbus(LE, 0);
+//! let encoded = to_bytes(ctxt, &session).unwrap();
+//! let decoded: Session = encoded.deserialize().unwrap().0;
+//! assert_eq!(decoded, session);
+//! ```
+//!
+//! Values are rounded down to the unit of the module on serialization. Values that don't fit in the
+//! integer type (e.g a timestamp before the epoch in the unsigned modules) fail to serialize, and
+//! integers that don't fit in the time type fail to deserialize.
+//!
+//! Besides the types from the standard library, [`Timestamp`] is also implemented for the
+//! `DateTime<Utc>` and `Duration` types of `chrono`, and the `OffsetDateTime` and `Duration` types
+//! of `time`, when the respective features are enabled.
+//!
+//! [`Type`]: macro@crate::Type
+
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::SystemTime;
+
+/// A time type that can be represented as a number of nanoseconds.
+///
+/// For timestamps, the number of nanoseconds is relative to the Unix epoch.
+pub trait Timestamp: Sized + sealed::Sealed {
+    /// The number of nanoseconds `self` represents, or `None` if it doesn't fit.
+    fn to_nanos(&self) -> Option<i128>;
+
+    /// The value representing `nanos` nanoseconds, or `None` if it doesn't fit.
+    fn from_nanos(nanos: i128) -> Option<Self>;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+fn duration_from_nanos(nanos: i128) -> Option<Duration> {
+    let secs = u64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+    let subsec_nanos = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+
+    Some(Duration::new(secs, subsec_nanos))
+}
+
+impl sealed::Sealed for Duration {}
+
+impl Timestamp for Duration {
+    fn to_nanos(&self) -> Option<i128> {
+        Some(self.as_nanos() as i128)
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        duration_from_nanos(nanos)
+    }
+}
+
+#[cfg(feature = "std")]
+impl sealed::Sealed for SystemTime {}
+
+#[cfg(feature = "std")]
+impl Timestamp for SystemTime {
+    fn to_nanos(&self) -> Option<i128> {
+        match self.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(since) => since.to_nanos(),
+            Err(e) => e.duration().to_nanos().map(|before| -before),
+        }
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        if nanos < 0 {
+            SystemTime::UNIX_EPOCH.checked_sub(duration_from_nanos(-nanos)?)
+        } else {
+            SystemTime::UNIX_EPOCH.checked_add(duration_from_nanos(nanos)?)
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl sealed::Sealed for chrono::DateTime<chrono::Utc> {}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn to_nanos(&self) -> Option<i128> {
+        Some(self.timestamp() as i128 * NANOS_PER_SEC + self.timestamp_subsec_nanos() as i128)
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        let subsec_nanos = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+
+        chrono::DateTime::from_timestamp(secs, subsec_nanos)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl sealed::Sealed for chrono::Duration {}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::Duration {
+    fn to_nanos(&self) -> Option<i128> {
+        Some(self.num_seconds() as i128 * NANOS_PER_SEC + self.subsec_nanos() as i128)
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+        let subsec_nanos = nanos.rem_euclid(NANOS_PER_SEC) as u32;
+
+        chrono::Duration::new(secs, subsec_nanos)
+    }
+}
+
+#[cfg(feature = "time")]
+impl sealed::Sealed for time::OffsetDateTime {}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn to_nanos(&self) -> Option<i128> {
+        Some(self.unix_timestamp_nanos())
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+}
+
+#[cfg(feature = "time")]
+impl sealed::Sealed for time::Duration {}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::Duration {
+    fn to_nanos(&self) -> Option<i128> {
+        Some(self.whole_nanoseconds())
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let secs = i64::try_from(nanos / NANOS_PER_SEC).ok()?;
+        // Same sign as `secs`, so this can't overflow.
+        let subsec_nanos = (nanos % NANOS_PER_SEC) as i32;
+
+        Some(time::Duration::new(secs, subsec_nanos))
+    }
+}
+
+macro_rules! unit_modules {
+    ($int:ty, $($(#[$attr:meta])* $unit:ident = $nanos:expr),* $(,)?) => {
+        $(
+            $(#[$attr])*
+            pub mod $unit {
+                use serde::{
+                    de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer,
+                };
+
+                use crate::timestamp::Timestamp;
+
+                /// Serialize `value` as an integer.
+                pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    T: Timestamp,
+                    S: Serializer,
+                {
+                    let n = value
+                        .to_nanos()
+                        .map(|nanos| nanos.div_euclid($nanos))
+                        .and_then(|n| <$int>::try_from(n).ok())
+                        .ok_or_else(|| {
+                            S::Error::custom(::core::concat!(
+                                "time value out of range for `",
+                                ::core::stringify!($int),
+                                "`",
+                            ))
+                        })?;
+
+                    n.serialize(serializer)
+                }
+
+                /// Deserialize a value from an integer.
+                pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+                where
+                    T: Timestamp,
+                    D: Deserializer<'de>,
+                {
+                    let n = <$int>::deserialize(deserializer)?;
+
+                    (n as i128)
+                        .checked_mul($nanos)
+                        .and_then(T::from_nanos)
+                        .ok_or_else(|| D::Error::custom("time value out of range"))
+                }
+            }
+        )*
+    };
+}
+
+unit_modules! {
+    u64,
+    /// Serialize and deserialize as a number of seconds, in a `u64`.
+    sec = 1_000_000_000,
+    /// Serialize and deserialize as a number of milliseconds, in a `u64`.
+    msec = 1_000_000,
+    /// Serialize and deserialize as a number of microseconds, in a `u64`.
+    usec = 1_000,
+    /// Serialize and deserialize as a number of nanoseconds, in a `u64`.
+    nsec = 1,
+}
+
+/// The equivalent of the modules of [`timestamp`](crate::timestamp), using `i64` integers.
+pub mod signed {
+    unit_modules! {
+        i64,
+        /// Serialize and deserialize as a number of seconds, in an `i64`.
+        sec = 1_000_000_000,
+        /// Serialize and deserialize as a number of milliseconds, in an `i64`.
+        msec = 1_000_000,
+        /// Serialize and deserialize as a number of microseconds, in an `i64`.
+        usec = 1_000,
+        /// Serialize and deserialize as a number of nanoseconds, in an `i64`.
+        nsec = 1,
+    }
+}
@@ -1,11 +1,12 @@
 use crate::{
     signature_parser::SignatureParser, utils::*, DynamicDeserialize, DynamicType, Signature,
 };
+use alloc::string::String;
+use core::marker::PhantomData;
 use serde::{
     de::{Deserialize, DeserializeSeed, Deserializer, Error, Visitor},
     Serialize, Serializer,
 };
-use std::marker::PhantomData;
 
 /// A helper type to serialize or deserialize a tuple whose elements implement [DynamicType] but
 /// not [Type].
@@ -91,7 +92,7 @@ macro_rules! tuple_impls {
             {
                 type Value = DynamicTuple<($($name,)+)>;
 
-                fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
                     formatter.write_str("a tuple")
                 }
 
@@ -1,12 +1,12 @@
 use crate::{utils::*, Signature};
+use alloc::{borrow::ToOwned, boxed::Box, format, rc::Rc, string::String, sync::Arc, vec::Vec};
+use core::{marker::PhantomData, time::Duration};
 use serde::de::{Deserialize, DeserializeSeed};
+#[cfg(feature = "std")]
 use std::{
-    marker::PhantomData,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::{Path, PathBuf},
-    rc::Rc,
-    sync::{Arc, Mutex, RwLock},
-    time::Duration,
+    sync::{Mutex, RwLock},
 };
 
 /// Trait implemented by all serializable types.
@@ -157,6 +157,7 @@ macro_rules! array_type {
 array_type!([T]);
 array_type!(Vec<T>);
 
+#[cfg(feature = "std")]
 impl<T, S> Type for std::collections::HashSet<T, S>
 where
     T: Type + Eq + Hash,
@@ -187,6 +188,17 @@ impl<const CAP: usize> Type for arrayvec::ArrayString<CAP> {
     }
 }
 
+#[cfg(feature = "bumpalo")]
+impl<T> Type for bumpalo::collections::Vec<'_, T>
+where
+    T: Type,
+{
+    #[inline]
+    fn signature() -> Signature<'static> {
+        <[T]>::signature()
+    }
+}
+
 // Empty type deserves empty signature
 impl Type for () {
     #[inline]
@@ -213,7 +225,9 @@ deref_impl!(T, <T: ?Sized + Type> Type for &T);
 deref_impl!(T, <T: ?Sized + Type> Type for &mut T);
 deref_impl!(T, <T: ?Sized + Type + ToOwned> Type for Cow<'_, T>);
 deref_impl!(T, <T: ?Sized + Type> Type for Arc<T>);
+#[cfg(feature = "std")]
 deref_impl!(T, <T: ?Sized + Type> Type for Mutex<T>);
+#[cfg(feature = "std")]
 deref_impl!(T, <T: ?Sized + Type> Type for RwLock<T>);
 deref_impl!(T, <T: ?Sized + Type> Type for Box<T>);
 deref_impl!(T, <T: ?Sized + Type> Type for Rc<T>);
@@ -307,12 +321,11 @@ where
 
 ////////////////////////////////////////////////////////////////////////////////
 
-use std::{
-    borrow::Cow,
-    collections::{BTreeMap, HashMap},
-    hash::{BuildHasher, Hash},
-    time::SystemTime,
-};
+use alloc::{borrow::Cow, collections::BTreeMap};
+#[cfg(feature = "std")]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::{collections::HashMap, time::SystemTime};
 
 macro_rules! map_impl {
     ($ty:ident < K $(: $kbound1:ident $(+ $kbound2:ident)*)*, V $(, $typaram:ident : $bound:ident)* >) => {
@@ -331,6 +344,7 @@ macro_rules! map_impl {
 }
 
 map_impl!(BTreeMap<K: Ord, V>);
+#[cfg(feature = "std")]
 map_impl!(HashMap<K: Eq + Hash, V, H: BuildHasher>);
 
 impl Type for Duration {
@@ -339,6 +353,7 @@ impl Type for Duration {
     }
 }
 
+#[cfg(feature = "std")]
 impl Type for SystemTime {
     #[inline]
     fn signature() -> Signature<'static> {
@@ -351,6 +366,7 @@ impl Type for SystemTime {
     }
 }
 
+#[cfg(feature = "std")]
 impl Type for Ipv4Addr {
     #[inline]
     fn signature() -> Signature<'static> {
@@ -358,6 +374,7 @@ impl Type for Ipv4Addr {
     }
 }
 
+#[cfg(feature = "std")]
 impl Type for Ipv6Addr {
     #[inline]
     fn signature() -> Signature<'static> {
@@ -365,6 +382,7 @@ impl Type for Ipv6Addr {
     }
 }
 
+#[cfg(feature = "std")]
 impl Type for IpAddr {
     #[inline]
     fn signature() -> Signature<'static> {
@@ -409,7 +427,9 @@ macro_rules! static_str_type {
     };
 }
 
+#[cfg(feature = "std")]
 static_str_type!(Path);
+#[cfg(feature = "std")]
 static_str_type!(PathBuf);
 
 #[cfg(feature = "uuid")]
@@ -422,6 +442,9 @@ impl Type for uuid::Uuid {
 #[cfg(feature = "url")]
 static_str_type!(url::Url);
 
+#[cfg(feature = "bumpalo")]
+static_str_type!(bumpalo::collections::String<'_>);
+
 // FIXME: Ignoring the `serde-human-readable` feature of `time` crate in these impls:
 // https://github.com/time-rs/time/blob/f9398b9598757508ca3815694f23203843e0011b/src/serde/mod.rs#L110
 #[cfg(feature = "time")]
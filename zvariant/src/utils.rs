@@ -1,10 +1,10 @@
-use std::slice::SliceIndex;
+use core::slice::SliceIndex;
 
 #[cfg(feature = "gvariant")]
 use crate::signature_parser::SignatureParser;
 use crate::{serialized::Format, Basic, Error, ObjectPath, Result, Signature};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
 
 /// The prefix of ARRAY type signature, as a character. Provided for manual signature creation.
@@ -12,6 +12,8 @@ pub const ARRAY_SIGNATURE_CHAR: char = 'a';
 /// The prefix of ARRAY type signature, as a string. Provided for manual signature creation.
 pub const ARRAY_SIGNATURE_STR: &str = "a";
 pub(crate) const ARRAY_ALIGNMENT_DBUS: usize = 4;
+// The maximum length of an array in bytes, as per the D-Bus specification.
+pub(crate) const MAX_ARRAY_LEN_DBUS: usize = 1 << 26;
 /// The opening character of STRUCT type signature. Provided for manual signature creation.
 pub const STRUCT_SIG_START_CHAR: char = '(';
 /// The closing character of STRUCT type signature. Provided for manual signature creation.
@@ -58,7 +60,7 @@ pub(crate) fn padding_for_n_bytes(value: usize, align: usize) -> usize {
 
 pub(crate) fn usize_to_u32(value: usize) -> u32 {
     assert!(
-        value <= (std::u32::MAX as usize),
+        value <= (core::u32::MAX as usize),
         "{} too large for `u32`",
         value,
     );
@@ -68,7 +70,7 @@ pub(crate) fn usize_to_u32(value: usize) -> u32 {
 
 pub(crate) fn usize_to_u8(value: usize) -> u8 {
     assert!(
-        value <= (std::u8::MAX as usize),
+        value <= (core::u8::MAX as usize),
         "{} too large for `u8`",
         value,
     );
@@ -78,7 +80,7 @@ pub(crate) fn usize_to_u8(value: usize) -> u8 {
 
 pub(crate) fn f64_to_f32(value: f64) -> f32 {
     assert!(
-        value <= (std::f32::MAX as f64),
+        value <= (core::f32::MAX as f64),
         "{} too large for `f32`",
         value,
     );
@@ -100,7 +102,7 @@ pub(crate) fn alignment_for_signature(signature: &Signature<'_>, format: Format)
         u16::SIGNATURE_CHAR => u16::alignment(format),
         i32::SIGNATURE_CHAR => i32::alignment(format),
         u32::SIGNATURE_CHAR => u32::alignment(format),
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         Fd::SIGNATURE_CHAR => u32::alignment(format),
         i64::SIGNATURE_CHAR => i64::alignment(format),
         u64::SIGNATURE_CHAR => u64::alignment(format),
@@ -146,7 +148,7 @@ pub(crate) fn is_fixed_sized_signature<'a>(signature: &'a Signature<'a>) -> Resu
         | i64::SIGNATURE_CHAR
         | u64::SIGNATURE_CHAR
         | f64::SIGNATURE_CHAR => Ok(true),
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         Fd::SIGNATURE_CHAR => Ok(true),
         STRUCT_SIG_START_CHAR => is_fixed_sized_struct_signature(signature),
         DICT_ENTRY_SIG_START_CHAR => is_fixed_sized_dict_entry_signature(signature),
@@ -157,7 +159,7 @@ pub(crate) fn is_fixed_sized_signature<'a>(signature: &'a Signature<'a>) -> Resu
 // Given an &str, create an owned (String-based) Signature w/ appropriate capacity
 macro_rules! signature_string {
     ($signature:expr) => {{
-        let mut s = String::with_capacity(255);
+        let mut s = alloc::string::String::with_capacity(255);
         s.push_str($signature);
 
         Signature::from_string_unchecked(s)
@@ -311,3 +313,26 @@ where
 {
     input.get(index).ok_or(Error::OutOfBounds)
 }
+
+/// Whether the given bytes contain a nul byte.
+///
+/// This is already vectorized by `core` (through `memchr`), so there is no need for a SIMD
+/// specific implementation.
+pub(crate) fn contains_nul(bytes: &[u8]) -> bool {
+    bytes.contains(&0)
+}
+
+/// Convert the given bytes to a string, if they're valid UTF-8.
+///
+/// With the `simd` feature, the bytes are validated with `simdutf8`, which uses SIMD instructions
+/// where available.
+pub(crate) fn str_from_utf8(bytes: &[u8]) -> Result<&str> {
+    // `simdutf8` is only fast at telling if the bytes are valid so we still fall back to `core` to
+    // get the details of the error.
+    #[cfg(feature = "simd")]
+    if let Ok(s) = simdutf8::basic::from_utf8(bytes) {
+        return Ok(s);
+    }
+
+    core::str::from_utf8(bytes).map_err(Error::Utf8)
+}
@@ -0,0 +1,315 @@
+use alloc::format;
+use core::{cmp::Ordering, fmt};
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+
+#[cfg(all(unix, feature = "std"))]
+use std::os::fd::AsFd;
+
+use crate::{
+    de::Deserializer,
+    serialized::{Context, Format},
+    Basic, Error, ObjectPath, Signature, ARRAY_SIGNATURE_CHAR, STRUCT_SIG_START_CHAR,
+    VARIANT_SIGNATURE_CHAR,
+};
+
+/// The first violation found by [`validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    offset: usize,
+    error: Error,
+}
+
+impl ValidationError {
+    /// The offset in the bytes at which the violation was detected.
+    ///
+    /// For values that are decoded as a whole (e.g. strings), this can be the offset right after
+    /// the offending value.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The violation.
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// Convert into the violation, dropping the offset.
+    pub fn into_error(self) -> Error {
+        self.error
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at offset {})", self.error, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        e.error
+    }
+}
+
+/// Check that `bytes` is a valid encoding of values of `signature`, without decoding them.
+///
+/// The whole encoding is walked, checking everything that deserialization would check: padding,
+/// array lengths, string contents, variant signatures, container depths, framing offsets etc. No
+/// values are built, which makes this suitable for pre-checking data before zero-copy access, or
+/// for monitoring and fuzzing. All of `bytes` must be used by the encoding, unless `ctxt` is
+/// [lenient].
+///
+/// There are no file descriptors to check against here, so any file descriptor in the encoding is
+/// reported as [`Error::UnknownFd`]. Use [`Data::validate`] to validate data with file descriptors.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, validate, Error, LE};
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &(42u8, "hello")).unwrap();
+/// validate(&encoded, "(ys)", ctxt).unwrap();
+///
+/// // Make the padding after the byte non-zero.
+/// let mut bytes = encoded.to_vec();
+/// bytes[1] = 1;
+/// let err = validate(&bytes, "(ys)", ctxt).unwrap_err();
+/// assert_eq!(err.error(), &Error::PaddingNot0(1));
+/// assert_eq!(err.offset(), 1);
+/// ```
+///
+/// [`Data::validate`]: crate::serialized::Data::validate
+/// [lenient]: Context::with_lenient
+pub fn validate<'s, S>(
+    bytes: &[u8],
+    signature: S,
+    ctxt: Context,
+) -> core::result::Result<(), ValidationError>
+where
+    S: TryInto<Signature<'s>>,
+    S::Error: Into<Error>,
+{
+    #[cfg(all(unix, feature = "std"))]
+    {
+        validate_with_fds::<_, std::os::fd::BorrowedFd<'_>>(bytes, None, signature, ctxt)
+    }
+    #[cfg(not(all(unix, feature = "std")))]
+    {
+        validate_with_fds(bytes, signature, ctxt)
+    }
+}
+
+// The implementation of `validate` and `Data::validate`.
+pub(crate) fn validate_with_fds<'s, S, #[cfg(all(unix, feature = "std"))] F: AsFd>(
+    bytes: &[u8],
+    #[cfg(all(unix, feature = "std"))] fds: Option<&[F]>,
+    signature: S,
+    ctxt: Context,
+) -> core::result::Result<(), ValidationError>
+where
+    S: TryInto<Signature<'s>>,
+    S::Error: Into<Error>,
+{
+    let error = |offset, error| ValidationError { offset, error };
+    let signature = signature.try_into().map_err(|e| error(0, e.into()))?;
+
+    let mut de = match ctxt.format() {
+        #[cfg(feature = "gvariant")]
+        Format::GVariant => {
+            #[cfg(all(unix, feature = "std"))]
+            {
+                crate::gvariant::Deserializer::new(bytes, fds, signature.clone(), ctxt)
+            }
+            #[cfg(not(all(unix, feature = "std")))]
+            {
+                crate::gvariant::Deserializer::<()>::new(bytes, signature.clone(), ctxt)
+            }
+        }
+        .map(Deserializer::GVariant),
+        Format::DBus => {
+            #[cfg(all(unix, feature = "std"))]
+            {
+                crate::dbus::Deserializer::new(bytes, fds, signature.clone(), ctxt)
+            }
+            #[cfg(not(all(unix, feature = "std")))]
+            {
+                crate::dbus::Deserializer::<()>::new(bytes, signature.clone(), ctxt)
+            }
+        }
+        .map(Deserializer::DBus),
+    }
+    .map_err(|e| error(0, e))?;
+
+    let mut res = Ok(());
+    for sig in signature.complete_types() {
+        res = sig.and_then(|signature| Skip { signature }.deserialize(&mut de));
+        if res.is_err() {
+            break;
+        }
+    }
+    // The nested deserializers always update the position of their parent, even on errors, so
+    // this is where the deserialization stopped.
+    let pos = match de {
+        #[cfg(feature = "gvariant")]
+        Deserializer::GVariant(de) => de.0.pos,
+        Deserializer::DBus(de) => de.0.pos,
+    };
+    res.map_err(|e| error(pos, e))?;
+
+    match bytes.len().cmp(&pos) {
+        Ordering::Equal => Ok(()),
+        Ordering::Greater if ctxt.is_lenient() => Ok(()),
+        Ordering::Greater => Err(error(
+            pos,
+            Error::Message(format!(
+                "{} trailing byte(s) after the encoded value(s)",
+                bytes.len() - pos
+            )),
+        )),
+        Ordering::Less => Err(error(bytes.len(), Error::OutOfBounds)),
+    }
+}
+
+// Walks over a value of the single complete type `signature`, without building it.
+//
+// Deserializers need to be told what to expect, so this mirrors how `Value` is deserialized.
+pub(crate) struct Skip<'s> {
+    pub(crate) signature: Signature<'s>,
+}
+
+impl<'de> DeserializeSeed<'de> for Skip<'_> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+macro_rules! skip_basic_method {
+    ($name:ident, $type:ty) => {
+        fn $name<E>(self, _value: $type) -> core::result::Result<(), E>
+        where
+            E: de::Error,
+        {
+            Ok(())
+        }
+    };
+}
+
+impl<'de> Visitor<'de> for Skip<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a value of signature `{}`", self.signature)
+    }
+
+    skip_basic_method!(visit_bool, bool);
+    skip_basic_method!(visit_i16, i16);
+    skip_basic_method!(visit_i32, i32);
+    skip_basic_method!(visit_i64, i64);
+    skip_basic_method!(visit_u8, u8);
+    skip_basic_method!(visit_u16, u16);
+    skip_basic_method!(visit_u32, u32);
+    skip_basic_method!(visit_u64, u64);
+    skip_basic_method!(visit_f64, f64);
+
+    fn visit_str<E>(self, value: &str) -> core::result::Result<(), E>
+    where
+        E: de::Error,
+    {
+        // The deserializers only check that these are valid UTF-8 strings.
+        match self.signature.as_bytes()[0] as char {
+            ObjectPath::SIGNATURE_CHAR => ObjectPath::try_from(value).map(drop).map_err(E::custom),
+            Signature::SIGNATURE_CHAR => Signature::try_from(value).map(drop).map_err(E::custom),
+            _ => Ok(()),
+        }
+    }
+
+    fn visit_seq<V>(self, mut visitor: V) -> core::result::Result<(), V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let missing = || de::Error::invalid_length(0, &"a value");
+        match self.signature.as_bytes()[0] as char {
+            ARRAY_SIGNATURE_CHAR => {
+                let signature = self.signature.slice(1..);
+                while let Some(()) = visitor.next_element_seed(Skip {
+                    signature: signature.clone(),
+                })? {}
+            }
+            STRUCT_SIG_START_CHAR => {
+                for signature in self
+                    .signature
+                    .structure_fields()
+                    .map_err(de::Error::custom)?
+                {
+                    let signature = signature.map_err(de::Error::custom)?;
+                    visitor
+                        .next_element_seed(Skip { signature })?
+                        .ok_or_else(missing)?;
+                }
+            }
+            VARIANT_SIGNATURE_CHAR => {
+                let signature = visitor
+                    .next_element::<Signature<'_>>()?
+                    .ok_or_else(missing)?;
+                if signature.n_complete_types() != Ok(1) {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Str(signature.as_str()),
+                        &"a single complete type",
+                    ));
+                }
+                visitor
+                    .next_element_seed(Skip { signature })?
+                    .ok_or_else(missing)?;
+            }
+            _ => return Err(de::Error::invalid_type(de::Unexpected::Seq, &self)),
+        }
+
+        Ok(())
+    }
+
+    fn visit_map<V>(self, mut visitor: V) -> core::result::Result<(), V::Error>
+    where
+        V: MapAccess<'de>,
+    {
+        let (key, value) = self.signature.dict_key_value().map_err(de::Error::custom)?;
+        while let Some(((), ())) = visitor.next_entry_seed(
+            Skip {
+                signature: key.clone(),
+            },
+            Skip {
+                signature: value.clone(),
+            },
+        )? {}
+
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> core::result::Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(Skip {
+            signature: self.signature.slice(1..),
+        })
+    }
+
+    fn visit_none<E>(self) -> core::result::Result<(), E>
+    where
+        E: de::Error,
+    {
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 use core::{
     cmp::Ordering,
     fmt::{Display, Write},
@@ -18,14 +19,16 @@ use static_assertions::assert_impl_all;
 
 use crate::{
     array_display_fmt, dict_display_fmt, signature_parser::SignatureParser, structure_display_fmt,
-    utils::*, Array, Basic, Dict, DynamicType, ObjectPath, OwnedValue, Signature, Str, Structure,
-    StructureBuilder, Type,
+    utils::*, Array, Basic, Dict, DictDiff, DynamicType, ObjectPath, OwnedValue, Signature, Str,
+    Structure, StructureBuilder, Type,
 };
 #[cfg(feature = "gvariant")]
 use crate::{maybe_display_fmt, Maybe};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "std"))]
 use crate::Fd;
+#[cfg(all(unix, feature = "std"))]
+use std::os::fd::{AsFd, BorrowedFd};
 
 /// A generic container, in the form of an enum that holds exactly one value of any of the other
 /// types.
@@ -99,7 +102,7 @@ pub enum Value<'a> {
     #[cfg(feature = "gvariant")]
     Maybe(Maybe<'a>),
 
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "std"))]
     Fd(Fd<'a>),
 }
 
@@ -125,7 +128,7 @@ impl Hash for Value<'_> {
             Self::Structure(inner) => inner.hash(state),
             #[cfg(feature = "gvariant")]
             Self::Maybe(inner) => inner.hash(state),
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Self::Fd(inner) => inner.hash(state),
         }
     }
@@ -173,7 +176,7 @@ macro_rules! serialize_value {
             #[cfg(feature = "gvariant")]
             Value::Maybe(value) => $serializer.$method($($first_arg,)* value),
 
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Value::Fd(value) => $serializer.$method($($first_arg,)* value),
         }
     }
@@ -217,7 +220,7 @@ impl<'a> Value<'a> {
     /// This method can currently only fail on Unix platforms for [`Value::Fd`] variant. This
     /// happens when the current process exceeds the maximum number of open file descriptors.
     pub fn try_to_owned(&self) -> crate::Result<OwnedValue> {
-        Ok(OwnedValue(match self {
+        Ok(OwnedValue::new(match self {
             Value::U8(v) => Value::U8(*v),
             Value::Bool(v) => Value::Bool(*v),
             Value::I16(v) => Value::I16(*v),
@@ -240,7 +243,7 @@ impl<'a> Value<'a> {
             Value::Structure(v) => Value::Structure(v.try_to_owned()?),
             #[cfg(feature = "gvariant")]
             Value::Maybe(v) => Value::Maybe(v.try_to_owned()?),
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Value::Fd(v) => Value::Fd(v.try_to_owned()?),
         }))
     }
@@ -269,7 +272,7 @@ impl<'a> Value<'a> {
             #[cfg(feature = "gvariant")]
             Value::Maybe(value) => value.full_signature().as_ref(),
 
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Value::Fd(_) => Fd::signature(),
         }
     }
@@ -301,11 +304,112 @@ impl<'a> Value<'a> {
             Value::Structure(v) => Value::Structure(v.try_clone()?),
             #[cfg(feature = "gvariant")]
             Value::Maybe(v) => Value::Maybe(v.try_clone()?),
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             Value::Fd(v) => Value::Fd(v.try_clone()?),
         })
     }
 
+    /// The file descriptors contained in `self`, at any depth.
+    ///
+    /// The file descriptors are in the order they're serialized in, i.e the order of their indices
+    /// in the encoded form of `self`. This is useful to pass them along with the encoded value,
+    /// e.g when forwarding it to another connection.
+    #[cfg(all(unix, feature = "std"))]
+    pub fn fds(&self) -> Vec<BorrowedFd<'_>> {
+        let mut fds = vec![];
+        self.collect_fds(&mut fds);
+
+        fds
+    }
+
+    /// Replace each of the file descriptors contained in `self`, at any depth, with the result of
+    /// `f`.
+    ///
+    /// `f` is called in the same order as the file descriptors are returned by [`Value::fds`], so
+    /// it can be used to remap them, e.g to file descriptors duplicated for another connection:
+    ///
+    /// ```
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::os::fd::{AsFd, AsRawFd};
+    /// use zvariant::{Fd, Value};
+    ///
+    /// let stdin = std::io::stdin();
+    /// let value = Value::from((Fd::from(stdin.as_fd()), "stdin"));
+    /// let value = value.map_fds(|fd| fd.try_to_owned()).unwrap();
+    /// let fds = value.fds();
+    /// assert_eq!(fds.len(), 1);
+    /// assert_ne!(fds[0].as_raw_fd(), stdin.as_raw_fd());
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The first error returned by `f`, if any.
+    #[cfg(all(unix, feature = "std"))]
+    pub fn map_fds<F>(self, mut f: F) -> crate::Result<Self>
+    where
+        F: FnMut(Fd<'a>) -> crate::Result<Fd<'a>>,
+    {
+        self.map_fds_with(&mut f)
+    }
+
+    /// Whether the value contains any file descriptors.
+    pub(crate) fn has_fds(&self) -> bool {
+        #[cfg(all(unix, feature = "std"))]
+        match self {
+            Value::Value(v) => v.has_fds(),
+            Value::Array(v) => v.inner().iter().any(Value::has_fds),
+            Value::Dict(v) => v.iter().any(|(k, v)| k.has_fds() || v.has_fds()),
+            Value::Structure(v) => v.fields().iter().any(Value::has_fds),
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(v) => v.inner().as_ref().is_some_and(Value::has_fds),
+            Value::Fd(_) => true,
+            _ => false,
+        }
+
+        #[cfg(not(all(unix, feature = "std")))]
+        false
+    }
+
+    #[cfg(all(unix, feature = "std"))]
+    fn collect_fds<'s>(&'s self, fds: &mut Vec<BorrowedFd<'s>>) {
+        match self {
+            Value::Value(v) => v.collect_fds(fds),
+            Value::Array(v) => v.inner().iter().for_each(|v| v.collect_fds(fds)),
+            Value::Dict(v) => v.iter().for_each(|(k, v)| {
+                k.collect_fds(fds);
+                v.collect_fds(fds);
+            }),
+            Value::Structure(v) => v.fields().iter().for_each(|v| v.collect_fds(fds)),
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(v) => {
+                if let Some(v) = v.inner() {
+                    v.collect_fds(fds);
+                }
+            }
+            Value::Fd(v) => fds.push(v.as_fd()),
+            _ => (),
+        }
+    }
+
+    #[cfg(all(unix, feature = "std"))]
+    pub(crate) fn map_fds_with<F>(self, f: &mut F) -> crate::Result<Self>
+    where
+        F: FnMut(Fd<'a>) -> crate::Result<Fd<'a>>,
+    {
+        Ok(match self {
+            Value::Value(v) => Value::Value(Box::new(v.map_fds_with(f)?)),
+            Value::Array(v) => Value::Array(v.map_fds(f)?),
+            Value::Dict(v) => Value::Dict(v.map_fds(f)?),
+            Value::Structure(v) => Value::Structure(v.map_fds(f)?),
+            #[cfg(feature = "gvariant")]
+            Value::Maybe(v) => Value::Maybe(v.map_fds(f)?),
+            Value::Fd(v) => Value::Fd(f(v)?),
+            v => v,
+        })
+    }
+
     pub(crate) fn serialize_value_as_struct_field<S>(
         &self,
         name: &'static str,
@@ -449,10 +553,166 @@ impl<'a> Value<'a> {
         }
         .map_err(Into::into)
     }
+
+    /// Get the value at `path` inside `self`.
+    ///
+    /// `path` is a sequence of dictionary keys, separated by `.`, and indices of array elements
+    /// or structure fields, enclosed in `[]`, e.g `Config.Servers[2].Host`. Keys can only be
+    /// looked up in dictionaries whose keys are strings, object paths or signatures. Keys can't
+    /// contain `.` or `[`. An empty path refers to `self`.
+    ///
+    /// Variants ([`Value::Value`]) are looked through, including the one holding the returned
+    /// value, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let servers = vec![("a.example.org", 80u16), ("b.example.org", 8080)];
+    /// let config = HashMap::from([("Servers", Value::new(servers))]);
+    /// let value = Value::new(HashMap::from([("Config", Value::new(config))]));
+    ///
+    /// let host = value.get_path("Config.Servers[1][0]").unwrap().unwrap();
+    /// assert_eq!(host, &Value::from("b.example.org"));
+    /// assert!(value.get_path("Config.Servers[2]").unwrap().is_none());
+    /// assert!(value.get_path("Config..Servers").is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// If `path` isn't valid. If there is no value at `path`, `Ok(None)` is returned.
+    pub fn get_path(&self, path: &str) -> Result<Option<&Value<'a>>, crate::Error> {
+        let segments = parse_value_path(path)?;
+
+        let mut value = self.strip_variants();
+        for segment in segments {
+            let child = match (segment, value) {
+                (PathSegment::Key(key), Value::Dict(dict)) => dict.get_by_str(key),
+                (PathSegment::Index(i), Value::Array(array)) => array.inner().get(i),
+                (PathSegment::Index(i), Value::Structure(structure)) => structure.fields().get(i),
+                _ => None,
+            };
+            match child {
+                Some(child) => value = child.strip_variants(),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Try to get the value at `path` inside `self`, as the underlying type `T`.
+    ///
+    /// Same as [`Value::get_path`], followed by [`Value::downcast_ref`] on the value, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let value = Value::new(HashMap::from([("Size", Value::new((800u32, 600u32)))]));
+    ///
+    /// assert_eq!(value.downcast_at::<u32>("Size[1]").unwrap(), Some(600));
+    /// assert_eq!(value.downcast_at::<u32>("Depth").unwrap(), None);
+    /// assert!(value.downcast_at::<&str>("Size[1]").is_err());
+    /// ```
+    pub fn downcast_at<T>(&'a self, path: &str) -> Result<Option<T>, crate::Error>
+    where
+        T: TryFrom<&'a Value<'a>>,
+        <T as TryFrom<&'a Value<'a>>>::Error: Into<crate::Error>,
+    {
+        self.get_path(path)?
+            .map(|v| T::try_from(v).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Compare the dictionary in `self` against the one in `new`, entry by entry.
+    ///
+    /// See [`DictDiff`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use zvariant::Value;
+    ///
+    /// let old = Value::new(HashMap::from([("Count", 1u32), ("Max", 10)]));
+    /// let new = Value::new(HashMap::from([("Count", 2u32), ("Max", 10)]));
+    ///
+    /// let diff = old.diff(&new).unwrap();
+    /// assert_eq!(
+    ///     diff.changed(),
+    ///     [(&Value::from("Count"), &Value::from(1u32), &Value::from(2u32))]
+    /// );
+    /// assert!(diff.added().is_empty() && diff.removed().is_empty());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// [`Error::IncorrectType`] if either value isn't a dictionary.
+    ///
+    /// [`Error::IncorrectType`]: crate::Error::IncorrectType
+    pub fn diff<'d>(
+        &'d self,
+        new: &'d Value<'a>,
+    ) -> Result<DictDiff<'d, Value<'a>, Value<'a>>, crate::Error> {
+        match (self.strip_variants(), new.strip_variants()) {
+            (Value::Dict(old), Value::Dict(new)) => Ok(old.diff(new)),
+            _ => Err(crate::Error::IncorrectType),
+        }
+    }
+
+    fn strip_variants(&self) -> &Value<'a> {
+        let mut value = self;
+        while let Value::Value(inner) = value {
+            value = inner;
+        }
+
+        value
+    }
+}
+
+enum PathSegment<'p> {
+    Key(&'p str),
+    Index(usize),
+}
+
+fn parse_value_path(path: &str) -> Result<Vec<PathSegment<'_>>, crate::Error> {
+    let invalid = || crate::Error::Message(format!("invalid value path `{path}`"));
+
+    let mut segments = vec![];
+    let mut rest = path;
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let (index, after) = index.split_once(']').ok_or_else(invalid)?;
+            segments.push(PathSegment::Index(index.parse().map_err(|_| invalid())?));
+            rest = after;
+
+            continue;
+        }
+
+        // Only the first key isn't preceded by a `.`.
+        let key = if segments.is_empty() {
+            rest
+        } else {
+            rest.strip_prefix('.').ok_or_else(invalid)?
+        };
+        let end = key.find(['.', '[']).unwrap_or(key.len());
+        if end == 0 {
+            return Err(invalid());
+        }
+        segments.push(PathSegment::Key(&key[..end]));
+        rest = &key[end..];
+    }
+
+    Ok(segments)
 }
 
 impl Display for Value<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         value_display_fmt(self, f, true)
     }
 }
@@ -460,9 +720,9 @@ impl Display for Value<'_> {
 /// Implemented based on https://gitlab.gnome.org/GNOME/glib/-/blob/e1d47f0b0d0893ac9171e24cc7bf635495376546/glib/gvariant.c#L2213
 pub(crate) fn value_display_fmt(
     value: &Value<'_>,
-    f: &mut std::fmt::Formatter<'_>,
+    f: &mut core::fmt::Formatter<'_>,
     type_annotate: bool,
-) -> std::fmt::Result {
+) -> core::fmt::Result {
     match value {
         Value::U8(num) => {
             if type_annotate {
@@ -508,7 +768,8 @@ pub(crate) fn value_display_fmt(
             write!(f, "{}", num)
         }
         Value::F64(num) => {
-            if num.fract() == 0. {
+            // Same as `num.fract() == 0.`, which isn't available without `std`.
+            if num % 1. == 0. {
                 // Add a dot to make it clear that this is a float
                 write!(f, "{}.", num)
             } else {
@@ -545,7 +806,7 @@ pub(crate) fn value_display_fmt(
         Value::Structure(structure) => structure_display_fmt(structure, f, type_annotate),
         #[cfg(feature = "gvariant")]
         Value::Maybe(maybe) => maybe_display_fmt(maybe, f, type_annotate),
-        #[cfg(unix)]
+        #[cfg(all(unix, feature = "std"))]
         Value::Fd(handle) => {
             if type_annotate {
                 f.write_str("handle ")?;
@@ -592,7 +853,7 @@ struct ValueVisitor;
 impl<'de> Visitor<'de> for ValueVisitor {
     type Value = Value<'de>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("a Value")
     }
 
@@ -773,7 +1034,7 @@ where
 {
     type Value = Value<'de>;
 
-    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         formatter.write_str("a Value value")
     }
 
@@ -796,7 +1057,7 @@ where
                 &"i32 or fd signature character",
             )
         })? {
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             b'h' => {
                 // SAFETY: The `'de` lifetimes will ensure the borrow won't outlive the raw FD.
                 let fd = unsafe { std::os::fd::BorrowedFd::borrow_raw(value) };
@@ -890,7 +1151,9 @@ where
     where
         D: Deserializer<'de>,
     {
-        panic!("`Maybe` type is only supported for GVariant format but it's disabled");
+        Err(D::Error::custom(
+            "`Maybe` type is only supported for GVariant format but it's disabled",
+        ))
     }
 
     #[cfg(feature = "gvariant")]
@@ -908,7 +1171,9 @@ where
     where
         E: Error,
     {
-        panic!("`Maybe` type is only supported for GVariant format but it's disabled");
+        Err(E::custom(
+            "`Maybe` type is only supported for GVariant format but it's disabled",
+        ))
     }
 }
 
@@ -1071,7 +1336,7 @@ mod tests {
 
         #[cfg(any(feature = "gvariant", feature = "option-as-array"))]
         {
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             use std::os::fd::BorrowedFd;
 
             #[cfg(all(feature = "gvariant", not(feature = "option-as-array")))]
@@ -1092,7 +1357,7 @@ mod tests {
                 s,
             );
 
-            #[cfg(unix)]
+            #[cfg(all(unix, feature = "std"))]
             assert_eq!(
                 Value::new(vec![
                     Fd::from(unsafe { BorrowedFd::borrow_raw(0) }),
@@ -1145,4 +1410,120 @@ mod tests {
             );
         }
     }
+
+    #[cfg(all(unix, feature = "std"))]
+    #[test]
+    fn fds() {
+        use crate::{serialized::Context, to_bytes, Error, LE};
+        use std::{fs::File, os::fd::AsRawFd};
+
+        let files = [(); 3].map(|_| File::open("/dev/null").unwrap());
+        let raw_fds = files.iter().map(|f| f.as_raw_fd()).collect::<Vec<_>>();
+        let value = Value::new((
+            Fd::from(files[0].as_fd()),
+            vec![Value::new(Fd::from(files[1].as_fd())), Value::new("no fd")],
+            Value::new(Value::new(Fd::from(files[2].as_fd()))),
+        ));
+        let fds = value
+            .fds()
+            .iter()
+            .map(|fd| fd.as_raw_fd())
+            .collect::<Vec<_>>();
+        assert_eq!(fds, raw_fds);
+
+        // The order matches the one of the serialized file descriptors.
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &value).unwrap();
+        let decoded: Value<'_> = encoded.deserialize().unwrap().0;
+        let encoded_fds = encoded
+            .fds()
+            .iter()
+            .map(|fd| fd.as_raw_fd())
+            .collect::<Vec<_>>();
+        let fds = decoded
+            .fds()
+            .iter()
+            .map(|fd| fd.as_raw_fd())
+            .collect::<Vec<_>>();
+        assert_eq!(fds, encoded_fds);
+
+        let mut mapped = vec![];
+        let owned = value
+            .map_fds(|fd| {
+                mapped.push(fd.as_raw_fd());
+                fd.try_to_owned()
+            })
+            .unwrap();
+        assert_eq!(mapped, raw_fds);
+        let fds = owned.fds();
+        assert_eq!(fds.len(), 3);
+        assert!(fds.iter().all(|fd| !raw_fds.contains(&fd.as_raw_fd())));
+
+        let err = owned
+            .map_fds(|_| Err(Error::Message("nope".to_string())))
+            .unwrap_err();
+        assert_eq!(err, Error::Message("nope".to_string()));
+    }
+
+    #[test]
+    fn get_path() {
+        use crate::{serialized::Context, to_bytes, LE};
+
+        let servers = vec![
+            HashMap::from([("Host", Value::new("a.example.org"))]),
+            HashMap::from([("Host", Value::new("b.example.org"))]),
+        ];
+        let objects = HashMap::from([(
+            ObjectPath::from_static_str("/org/example/Foo").unwrap(),
+            (42u32, vec![1u8, 2, 3]),
+        )]);
+        let config = HashMap::from([
+            ("Servers", Value::new(servers)),
+            ("Objects", Value::new(objects)),
+        ]);
+        let root = HashMap::from([("Config", Value::new(config))]);
+
+        // Go through the encoding, to get the variants of `a{sv}` values.
+        let ctxt = Context::new_dbus(LE, 0);
+        let encoded = to_bytes(ctxt, &Value::new(root)).unwrap();
+        let value: Value<'_> = encoded.deserialize().unwrap().0;
+
+        assert_eq!(
+            value.downcast_at::<&str>("Config.Servers[1].Host").unwrap(),
+            Some("b.example.org"),
+        );
+        assert_eq!(
+            value
+                .downcast_at::<u8>("Config.Objects./org/example/Foo[1][2]")
+                .unwrap(),
+            Some(3),
+        );
+        assert_eq!(value.get_path("").unwrap(), Some(&value));
+        assert!(matches!(
+            value.get_path("Config.Servers").unwrap(),
+            Some(Value::Array(a)) if a.len() == 2,
+        ));
+        for missing in [
+            "Config.Servers[2].Host",
+            "Config.Servers[0].Port",
+            "Config.Servers.Host",
+            "Config[0]",
+            "Config.Objects./org/example/Bar",
+        ] {
+            assert_eq!(value.get_path(missing).unwrap(), None, "{missing}");
+        }
+        for invalid in [
+            ".Config",
+            "Config.",
+            "Config..Servers",
+            "Config.Servers[",
+            "Config.Servers[-1]",
+            "Config.Servers[0]Host",
+        ] {
+            assert_eq!(
+                value.get_path(invalid).unwrap_err(),
+                crate::Error::Message(format!("invalid value path `{invalid}`")),
+            );
+        }
+    }
 }
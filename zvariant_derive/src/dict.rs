@@ -1,7 +1,7 @@
 use proc_macro2::{Span, TokenStream};
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote};
 use syn::{punctuated::Punctuated, spanned::Spanned, Data, DeriveInput, Error, Field};
-use zvariant_utils::{case, macros};
+use zvariant_utils::macros;
 
 use crate::utils::*;
 
@@ -10,24 +10,9 @@ fn dict_name_for_field(
     rename_attr: Option<String>,
     rename_all_attr: Option<&str>,
 ) -> Result<String, Error> {
-    if let Some(name) = rename_attr {
-        Ok(name)
-    } else {
-        let ident = f.ident.as_ref().unwrap().to_string();
-
-        match rename_all_attr {
-            Some("lowercase") => Ok(ident.to_ascii_lowercase()),
-            Some("UPPERCASE") => Ok(ident.to_ascii_uppercase()),
-            Some("PascalCase") => Ok(case::pascal_or_camel_case(&ident, true)),
-            Some("camelCase") => Ok(case::pascal_or_camel_case(&ident, false)),
-            Some("snake_case") => Ok(case::snake_case(&ident)),
-            None => Ok(ident),
-            Some(other) => Err(Error::new(
-                f.span(),
-                format!("invalid `rename_all` attribute value {other}"),
-            )),
-        }
-    }
+    let ident = f.ident.as_ref().unwrap().to_string();
+
+    rename_identifier(ident, f.span(), rename_attr, rename_all_attr)
 }
 
 pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -40,12 +25,23 @@ pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error>
 
     let zv = zvariant_path();
     let mut entries = quote! {};
-    let mut num_entries: usize = 0;
+    let mut num_entries = Some(0usize);
 
     for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+        let FieldAttributes {
+            rename, flatten, ..
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
+        if flatten {
+            entries.extend(quote! {
+                #zv::SerializeDictEntries::serialize_dict_entries(&self.#name, map)?;
+            });
+            // The number of entries of the flattened struct isn't known.
+            num_entries = None;
+
+            continue;
+        }
         let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
 
         let is_option = macros::ty_is_option(&f.ty);
@@ -63,27 +59,48 @@ pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error>
         };
 
         entries.extend(e);
-        num_entries += 1;
+        num_entries = num_entries.map(|n| n + 1);
     }
 
-    let generics = input.generics;
+    let mut generics = input.generics;
+    add_type_param_bounds(
+        &mut generics,
+        quote! { #zv::Type + #zv::export::serde::ser::Serialize },
+    );
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
-    let num_entries = num_entries.to_token_stream();
+    let num_entries = match num_entries {
+        Some(n) => quote! { ::core::option::Option::Some(#n) },
+        None => quote! { ::core::option::Option::None },
+    };
     Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::SerializeDictEntries for #name #ty_generics
+        #where_clause
+        {
+            fn serialize_dict_entries<M>(&self, map: &mut M) -> ::core::result::Result<(), M::Error>
+            where
+                M: #zv::export::serde::ser::SerializeMap,
+            {
+                #entries
+
+                ::core::result::Result::Ok(())
+            }
+        }
+
         #[allow(deprecated)]
         impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
         #where_clause
         {
-            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
             where
                 S: #zv::export::serde::ser::Serializer,
             {
                 use #zv::export::serde::ser::SerializeMap;
 
                 // zbus doesn't care about number of entries (it would need bytes instead)
-                let mut map = serializer.serialize_map(::std::option::Option::Some(#num_entries))?;
-                #entries
+                let mut map = serializer.serialize_map(#num_entries)?;
+                #zv::SerializeDictEntries::serialize_dict_entries(self, &mut map)?;
                 map.end()
             }
         }
@@ -99,58 +116,153 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
     let StructAttributes {
         rename_all,
         deny_unknown_fields,
+        default: struct_default,
         ..
     } = StructAttributes::parse(&input.attrs)?;
 
     let visitor = format_ident!("{}Visitor", name);
     let zv = zvariant_path();
     let mut fields = Vec::new();
-    let mut req_fields = Vec::new();
+    let mut partial_types = Vec::new();
+    let mut field_values = Vec::new();
     let mut dict_names = Vec::new();
     let mut entries = Vec::new();
-
-    for f in &data.fields {
-        let FieldAttributes { rename } = FieldAttributes::parse(&f.attrs)?;
+    let mut flattened_entries = Vec::new();
+
+    // The fields deserialized so far are kept in nested pairs, `(field0, (field1, (..., ())))`,
+    // so the `Partial` type can be spelled out without defining a new type.
+    for (i, f) in data.fields.iter().enumerate() {
+        let FieldAttributes {
+            rename,
+            flatten,
+            default,
+            ..
+        } = FieldAttributes::parse(&f.attrs)?;
 
         let name = &f.ident;
-        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
+        let ty = &f.ty;
+        let partial_field = std::iter::repeat(quote! { .1 })
+            .take(i)
+            .chain(Some(quote! { .0 }))
+            .collect::<TokenStream>();
+
+        if flatten {
+            partial_types.push(quote! { <#ty as #zv::DeserializeDictEntries<'de>>::Partial });
+            flattened_entries.push(quote! {
+                if <#ty as #zv::DeserializeDictEntries<'de>>::deserialize_dict_entry(
+                    &mut partial #partial_field,
+                    key,
+                    access,
+                )? {
+                    return ::core::result::Result::Ok(true);
+                }
+            });
+            field_values.push(quote! {
+                <#ty as #zv::DeserializeDictEntries<'de>>::from_dict_entries(
+                    partial #partial_field,
+                )?
+            });
+            fields.push(name);
+
+            continue;
+        }
 
-        let is_option = macros::ty_is_option(&f.ty);
+        let dict_name = dict_name_for_field(f, rename, rename_all.as_deref())?;
+        let is_option = macros::ty_is_option(ty);
 
+        // Values of an unexpected type are treated as unknown entries: an error in strict mode and
+        // skipped otherwise.
+        let parse_value = if deny_unknown_fields {
+            quote! {
+                partial #partial_field =
+                    ::core::option::Option::Some(access.next_value::<#zv::DeserializeValue<_>>()?.0);
+            }
+        } else {
+            quote! {
+                partial #partial_field =
+                    access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+            }
+        };
         entries.push(quote! {
             #dict_name => {
-                // FIXME: add an option about strict parsing (instead of silently skipping the field)
-                #name = access.next_value::<#zv::DeserializeValue<_>>().map(|v| v.0).ok();
+                #parse_value
+
+                ::core::result::Result::Ok(true)
             }
         });
 
+        let missing = if default {
+            quote! { ::core::default::Default::default() }
+        } else if struct_default {
+            quote! { default.#name }
+        } else if is_option {
+            quote! { ::core::option::Option::None }
+        } else {
+            quote! {
+                return ::core::result::Result::Err(E::missing_field(::core::stringify!(#name)))
+            }
+        };
+        if is_option {
+            partial_types.push(quote! { #ty });
+            field_values.push(quote! {
+                match partial #partial_field {
+                    ::core::option::Option::Some(val) => ::core::option::Option::Some(val),
+                    ::core::option::Option::None => #missing,
+                }
+            });
+        } else {
+            partial_types.push(quote! { ::core::option::Option<#ty> });
+            field_values.push(quote! {
+                match partial #partial_field {
+                    ::core::option::Option::Some(val) => val,
+                    ::core::option::Option::None => #missing,
+                }
+            });
+        }
+
         dict_names.push(dict_name);
         fields.push(name);
-
-        if !is_option {
-            req_fields.push(name);
-        }
     }
 
-    let fallback = if deny_unknown_fields {
-        quote! {
-            field => {
-                return ::std::result::Result::Err(
-                    <M::Error as #zv::export::serde::de::Error>::unknown_field(
-                        field,
-                        &[#(#dict_names),*],
-                    ),
-                );
-            }
-        }
+    let partial_type = partial_types
+        .iter()
+        .rev()
+        .fold(quote! { () }, |rest, ty| quote! { (#ty, #rest) });
+
+    // Unknown entries are skipped, unless they're denied and the deserializer isn't lenient.
+    let (key_pattern, fallback) = if deny_unknown_fields {
+        (
+            quote! { #zv::DictKey { key, lenient } },
+            quote! {
+                if !lenient {
+                    return ::core::result::Result::Err(
+                        <M::Error as #zv::export::serde::de::Error>::unknown_field(
+                            key,
+                            &[#(#dict_names),*],
+                        ),
+                    );
+                }
+                let _ = access.next_value::<#zv::Value>();
+            },
+        )
     } else {
-        quote! {
-            unknown => {
+        (
+            quote! { #zv::DictKey { key, .. } },
+            quote! {
                 let _ = access.next_value::<#zv::Value>();
-            }
+            },
+        )
+    };
+
+    // Missing entries take the value of the corresponding field of `Self::default()`.
+    let struct_default = if struct_default {
+        quote! {
+            #[allow(unused_variables)]
+            let default = <Self as ::core::default::Default>::default();
         }
+    } else {
+        quote! {}
     };
-    entries.push(fallback);
 
     let (_, ty_generics, _) = input.generics.split_for_impl();
     let mut generics = input.generics.clone();
@@ -164,59 +276,98 @@ pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Erro
         .into_iter()
         .chain(generics.params)
         .collect();
+    add_type_param_bounds(
+        &mut generics,
+        quote! { #zv::Type + #zv::export::serde::de::Deserialize<'de> },
+    );
 
     let (impl_generics, _, where_clause) = generics.split_for_impl();
 
     Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::DeserializeDictEntries<'de> for #name #ty_generics
+        #where_clause
+        {
+            type Partial = #partial_type;
+
+            fn deserialize_dict_entry<M>(
+                partial: &mut Self::Partial,
+                key: &str,
+                access: &mut M,
+            ) -> ::core::result::Result<bool, M::Error>
+            where
+                M: #zv::export::serde::de::MapAccess<'de>,
+            {
+                match key {
+                    #(#entries)*
+                    _ => {
+                        #(#flattened_entries)*
+
+                        ::core::result::Result::Ok(false)
+                    }
+                }
+            }
+
+            fn from_dict_entries<E>(partial: Self::Partial) -> ::core::result::Result<Self, E>
+            where
+                E: #zv::export::serde::de::Error,
+            {
+                #struct_default
+
+                ::core::result::Result::Ok(#name { #(#fields: #field_values),* })
+            }
+        }
+
         #[allow(deprecated)]
         impl #impl_generics #zv::export::serde::de::Deserialize<'de> for #name #ty_generics
         #where_clause
         {
-            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
             where
                 D: #zv::export::serde::de::Deserializer<'de>,
             {
-                struct #visitor #ty_generics(::std::marker::PhantomData<#name #ty_generics>);
+                struct #visitor #ty_generics(::core::marker::PhantomData<#name #ty_generics>);
 
-                impl #impl_generics #zv::export::serde::de::Visitor<'de> for #visitor #ty_generics {
+                impl #impl_generics #zv::export::serde::de::Visitor<'de> for #visitor #ty_generics
+                #where_clause
+                {
                     type Value = #name #ty_generics;
 
-                    fn expecting(&self, formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
                         formatter.write_str("a dictionary")
                     }
 
                     fn visit_map<M>(
                         self,
                         mut access: M,
-                    ) -> ::std::result::Result<Self::Value, M::Error>
+                    ) -> ::core::result::Result<Self::Value, M::Error>
                     where
                         M: #zv::export::serde::de::MapAccess<'de>,
                     {
-                        #( let mut #fields = ::std::default::Default::default(); )*
+                        let mut partial = <
+                            <#name #ty_generics as #zv::DeserializeDictEntries<'de>>::Partial
+                            as ::core::default::Default
+                        >::default();
 
                         // does not check duplicated fields, since those shouldn't exist in stream
-                        while let ::std::option::Option::Some(key) = access.next_key::<&str>()? {
-                            match key {
-                                #(#entries)*
+                        while let ::core::option::Option::Some(#key_pattern) =
+                            access.next_key::<#zv::DictKey<'de>>()?
+                        {
+                            let known = <#name #ty_generics as #zv::DeserializeDictEntries<'de>>
+                                ::deserialize_dict_entry(&mut partial, key, &mut access)?;
+                            if !known {
+                                #fallback
                             }
                         }
 
-                        #(let #req_fields = if let ::std::option::Option::Some(val) = #req_fields {
-                            val
-                        } else {
-                            return ::std::result::Result::Err(
-                                <M::Error as #zv::export::serde::de::Error>::missing_field(
-                                    ::std::stringify!(#req_fields),
-                                ),
-                            );
-                        };)*
-
-                        ::std::result::Result::Ok(#name { #(#fields),* })
+                        <#name #ty_generics as #zv::DeserializeDictEntries<'de>>::from_dict_entries(
+                            partial,
+                        )
                     }
                 }
 
 
-                deserializer.deserialize_map(#visitor(::std::marker::PhantomData))
+                deserializer.deserialize_map(#visitor(::core::marker::PhantomData))
             }
         }
     })
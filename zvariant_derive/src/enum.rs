@@ -0,0 +1,408 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    punctuated::Punctuated, spanned::Spanned, Data, DataEnum, DeriveInput, Error, Fields, Ident,
+    Variant,
+};
+
+use crate::utils::*;
+
+/// The encodings supported for enums.
+enum Encoding {
+    /// `(uv)`: the index of the variant, followed by its fields in a variant.
+    Tagged,
+    /// `a{sv}`: a single entry, from the name of the variant to its fields in a variant.
+    Dict,
+    /// `s`: the name of the variant, for enums without fields. Unknown names are deserialized as
+    /// the `other` variant, if any.
+    Str { other: Option<Ident> },
+}
+
+fn parse_encoding(input: &DeriveInput) -> Result<(Ident, DataEnum, Encoding), Error> {
+    let data = match &input.data {
+        Data::Enum(data) => data.clone(),
+        _ => return Err(Error::new(input.span(), "only enums supported")),
+    };
+    let StructAttributes { signature, .. } = StructAttributes::parse(&input.attrs)?;
+    let mut encoding =
+        match signature.as_deref() {
+            Some("(uv)") => Encoding::Tagged,
+            Some("a{sv}") | Some("dict") => Encoding::Dict,
+            Some("s") => Encoding::Str { other: None },
+            _ => return Err(Error::new(
+                input.span(),
+                "the encoding must be specified with either `#[zvariant(signature = \"(uv)\")]`, \
+                 `#[zvariant(signature = \"dict\")]` or `#[zvariant(signature = \"s\")]`",
+            )),
+        };
+
+    for variant in &data.variants {
+        let VariantAttributes { other, .. } = VariantAttributes::parse(&variant.attrs)?;
+        match &mut encoding {
+            Encoding::Str { .. } if !matches!(variant.fields, Fields::Unit) => {
+                return Err(Error::new(
+                    variant.span(),
+                    "only unit variants can be encoded as strings",
+                ));
+            }
+            Encoding::Str { other: Some(_) } if other => {
+                return Err(Error::new(
+                    variant.span(),
+                    "only one variant can be marked with `#[zvariant(other)]`",
+                ));
+            }
+            Encoding::Str { other: fallback } if other => {
+                *fallback = Some(variant.ident.clone());
+            }
+            _ if other => {
+                return Err(Error::new(
+                    variant.span(),
+                    "`#[zvariant(other)]` requires `#[zvariant(signature = \"s\")]`",
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    Ok((input.ident.clone(), data, encoding))
+}
+
+fn name_for_variant(variant: &Variant, rename_all_attr: Option<&str>) -> Result<String, Error> {
+    let VariantAttributes { rename, .. } = VariantAttributes::parse(&variant.attrs)?;
+
+    rename_identifier(
+        variant.ident.to_string(),
+        variant.span(),
+        rename,
+        rename_all_attr,
+    )
+}
+
+/// The pattern matching all the fields of `variant`, and the expression for its value.
+///
+/// Newtype variants are encoded as their only field, unit variants as a `y` (like empty
+/// structures) and all other variants as a structure of their fields.
+fn variant_value(variant: &Variant) -> (TokenStream, TokenStream) {
+    let bindings = (0..variant.fields.len())
+        .map(|i| format_ident!("f{}", i))
+        .collect::<Vec<_>>();
+    match &variant.fields {
+        Fields::Unit => (quote! {}, quote! { &0u8 }),
+        Fields::Unnamed(_) if bindings.len() == 1 => (quote! { (f0) }, quote! { f0 }),
+        Fields::Unnamed(_) => (quote! { (#(#bindings),*) }, quote! { &(#(#bindings,)*) }),
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| &f.ident);
+
+            (
+                quote! { { #(#names: #bindings),* } },
+                quote! { &(#(#bindings,)*) },
+            )
+        }
+    }
+}
+
+/// The expression constructing `variant` from `value`, deserialized as the type given by
+/// `variant_value_type`.
+fn variant_from_value(name: &Ident, variant: &Variant) -> TokenStream {
+    let ident = &variant.ident;
+    let bindings = (0..variant.fields.len())
+        .map(|i| format_ident!("f{}", i))
+        .collect::<Vec<_>>();
+    match &variant.fields {
+        // The value of unit variants is ignored.
+        Fields::Unit => quote! {
+            {
+                let _ = value;
+
+                #name::#ident
+            }
+        },
+        Fields::Unnamed(_) if bindings.len() == 1 => quote! { #name::#ident(value.0) },
+        Fields::Unnamed(_) => quote! {
+            {
+                let (#(#bindings,)*) = value.0;
+
+                #name::#ident(#(#bindings),*)
+            }
+        },
+        Fields::Named(fields) => {
+            let names = fields.named.iter().map(|f| &f.ident);
+
+            quote! {
+                {
+                    let (#(#bindings,)*) = value.0;
+
+                    #name::#ident { #(#names: #bindings),* }
+                }
+            }
+        }
+    }
+}
+
+/// The type the value of `variant` is deserialized as.
+fn variant_value_type(variant: &Variant, zv: &TokenStream) -> TokenStream {
+    let types = variant.fields.iter().map(|f| &f.ty);
+    match &variant.fields {
+        Fields::Unit => quote! { #zv::Value<'de> },
+        Fields::Unnamed(_) if variant.fields.len() == 1 => {
+            quote! { #zv::DeserializeValue<'de, #(#types)*> }
+        }
+        _ => quote! { #zv::DeserializeValue<'de, (#(#types,)*)> },
+    }
+}
+
+pub fn expand_serialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let (name, data, encoding) = parse_encoding(&input)?;
+    let StructAttributes { rename_all, .. } = StructAttributes::parse(&input.attrs)?;
+
+    let zv = zvariant_path();
+    let mut arms = Vec::new();
+    for (i, variant) in data.variants.iter().enumerate() {
+        let ident = &variant.ident;
+        let (pattern, value) = variant_value(variant);
+        let serialize = match encoding {
+            Encoding::Tagged => {
+                let tag = i as u32;
+
+                quote! {
+                    let mut structure = serializer.serialize_struct(::core::stringify!(#name), 2)?;
+                    structure.serialize_field("tag", &#tag)?;
+                    structure.serialize_field("value", &#zv::SerializeValue(#value))?;
+                    structure.end()
+                }
+            }
+            Encoding::Dict => {
+                let dict_name = name_for_variant(variant, rename_all.as_deref())?;
+
+                quote! {
+                    let mut map = serializer.serialize_map(::core::option::Option::Some(1))?;
+                    map.serialize_entry(#dict_name, &#zv::SerializeValue(#value))?;
+                    map.end()
+                }
+            }
+            Encoding::Str { .. } => {
+                let str_name = name_for_variant(variant, rename_all.as_deref())?;
+
+                quote! { serializer.serialize_str(#str_name) }
+            }
+        };
+
+        arms.push(quote! {
+            #name::#ident #pattern => {
+                #serialize
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::ser::Serialize for #name #ty_generics
+        #where_clause
+        {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: #zv::export::serde::ser::Serializer,
+            {
+                #[allow(unused_imports)]
+                use #zv::export::serde::ser::{SerializeMap, SerializeStruct};
+
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
+
+pub fn expand_deserialize_derive(input: DeriveInput) -> Result<TokenStream, Error> {
+    let (name, data, encoding) = parse_encoding(&input)?;
+    let StructAttributes { rename_all, .. } = StructAttributes::parse(&input.attrs)?;
+
+    let visitor = format_ident!("{}Visitor", name);
+    let zv = zvariant_path();
+    let mut arms = Vec::new();
+    let mut variant_names = Vec::new();
+    for (i, variant) in data.variants.iter().enumerate() {
+        let ty = variant_value_type(variant, &zv);
+        let from_value = variant_from_value(&name, variant);
+        let arm = match encoding {
+            Encoding::Tagged => {
+                let tag = i as u32;
+
+                quote! {
+                    #tag => {
+                        let value = seq
+                            .next_element::<#ty>()?
+                            .ok_or_else(|| M::Error::invalid_length(1, &self))?;
+
+                        #from_value
+                    }
+                }
+            }
+            Encoding::Dict => {
+                let dict_name = name_for_variant(variant, rename_all.as_deref())?;
+                let arm = quote! {
+                    #dict_name => {
+                        let value = access.next_value::<#ty>()?;
+
+                        #from_value
+                    }
+                };
+                variant_names.push(dict_name);
+
+                arm
+            }
+            Encoding::Str { .. } => {
+                let ident = &variant.ident;
+                let str_name = name_for_variant(variant, rename_all.as_deref())?;
+                let arm = quote! { #str_name => #name::#ident, };
+                variant_names.push(str_name);
+
+                arm
+            }
+        };
+
+        arms.push(arm);
+    }
+
+    let (visit, deserialize) = match encoding {
+        Encoding::Tagged => (
+            quote! {
+                fn visit_seq<M>(self, mut seq: M) -> ::core::result::Result<Self::Value, M::Error>
+                where
+                    M: #zv::export::serde::de::SeqAccess<'de>,
+                {
+                    use #zv::export::serde::de::Error;
+
+                    let tag = seq
+                        .next_element::<u32>()?
+                        .ok_or_else(|| M::Error::invalid_length(0, &self))?;
+                    let value = match tag {
+                        #(#arms)*
+                        tag => {
+                            return ::core::result::Result::Err(M::Error::invalid_value(
+                                #zv::export::serde::de::Unexpected::Unsigned(tag.into()),
+                                &self,
+                            ));
+                        }
+                    };
+
+                    ::core::result::Result::Ok(value)
+                }
+            },
+            quote! {
+                deserializer.deserialize_struct(
+                    ::core::stringify!(#name),
+                    &["tag", "value"],
+                    #visitor(::core::marker::PhantomData),
+                )
+            },
+        ),
+        Encoding::Dict => (
+            quote! {
+                fn visit_map<M>(self, mut access: M) -> ::core::result::Result<Self::Value, M::Error>
+                where
+                    M: #zv::export::serde::de::MapAccess<'de>,
+                {
+                    use #zv::export::serde::de::Error;
+
+                    let key = access
+                        .next_key::<&str>()?
+                        .ok_or_else(|| M::Error::invalid_length(0, &self))?;
+                    let value = match key {
+                        #(#arms)*
+                        key => {
+                            return ::core::result::Result::Err(M::Error::unknown_variant(
+                                key,
+                                &[#(#variant_names),*],
+                            ));
+                        }
+                    };
+                    if access.next_key::<&str>()?.is_some() {
+                        return ::core::result::Result::Err(M::Error::invalid_length(2, &self));
+                    }
+
+                    ::core::result::Result::Ok(value)
+                }
+            },
+            quote! {
+                deserializer.deserialize_map(#visitor(::core::marker::PhantomData))
+            },
+        ),
+        Encoding::Str { other } => {
+            let fallback = match other {
+                Some(other) => quote! { _ => #name::#other, },
+                None => quote! {
+                    value => {
+                        return ::core::result::Result::Err(E::unknown_variant(
+                            value,
+                            &[#(#variant_names),*],
+                        ));
+                    }
+                },
+            };
+
+            (
+                quote! {
+                    fn visit_str<E>(self, value: &str) -> ::core::result::Result<Self::Value, E>
+                    where
+                        E: #zv::export::serde::de::Error,
+                    {
+                        let value = match value {
+                            #(#arms)*
+                            #fallback
+                        };
+
+                        ::core::result::Result::Ok(value)
+                    }
+                },
+                quote! {
+                    deserializer.deserialize_str(#visitor(::core::marker::PhantomData))
+                },
+            )
+        }
+    };
+
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut generics = input.generics.clone();
+    let def = syn::LifetimeDef {
+        attrs: Vec::new(),
+        lifetime: syn::Lifetime::new("'de", Span::call_site()),
+        colon_token: None,
+        bounds: Punctuated::new(),
+    };
+    generics.params = Some(syn::GenericParam::Lifetime(def))
+        .into_iter()
+        .chain(generics.params)
+        .collect();
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[allow(deprecated)]
+        impl #impl_generics #zv::export::serde::de::Deserialize<'de> for #name #ty_generics
+        #where_clause
+        {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: #zv::export::serde::de::Deserializer<'de>,
+            {
+                struct #visitor #ty_generics(::core::marker::PhantomData<#name #ty_generics>);
+
+                impl #impl_generics #zv::export::serde::de::Visitor<'de> for #visitor #ty_generics {
+                    type Value = #name #ty_generics;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(::core::concat!("enum ", ::core::stringify!(#name)))
+                    }
+
+                    #visit
+                }
+
+                #deserialize
+            }
+        }
+    })
+}
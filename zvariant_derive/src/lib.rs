@@ -14,6 +14,7 @@ use proc_macro::TokenStream;
 use syn::DeriveInput;
 
 mod dict;
+mod r#enum;
 mod r#type;
 mod utils;
 mod value;
@@ -101,6 +102,23 @@ mod value;
 /// assert_eq!(StructFields::signature(), "(u(qxs))");
 /// ```
 ///
+/// # Generic types
+///
+/// Type parameters are required to implement [`Type`], so container-like types can be defined once
+/// for any payload:
+///
+/// ```
+/// use zvariant::Type;
+///
+/// #[derive(Type)]
+/// ##[allow(unused)]
+/// struct Page<T> {
+///     items: Vec<T>,
+///     total: u32,
+/// }
+/// assert_eq!(Page::<(String, u8)>::signature(), "(a(sy)u)");
+/// ```
+///
 /// # Custom signatures
 ///
 /// There are times when you'd find yourself wanting to specify a hardcoded signature yourself for
@@ -154,10 +172,46 @@ mod value;
 /// assert_eq!(decoded, StrEnum::Variant2);
 /// ```
 ///
+/// Serde's `rename` and `rename_all` attributes apply as usual. Deserialization then fails on any
+/// unknown string though. If the enum needs to accept states it doesn't know about, derive
+/// [`SerializeEnum`] and [`DeserializeEnum`] with the same `signature` instead, and mark a variant
+/// with `#[zvariant(other)]`. Both approaches produce the same encoding.
+///
+/// # Custom field signatures
+///
+/// The `signature` attribute can also be given to individual fields, for when the signature of
+/// the field's type isn't the one an existing interface expects. It's then up to the
+/// serialization of the field to match that signature. Most notably, a field can be wrapped in a
+/// variant with `#[zvariant(signature = "v")]` and [`zvariant::as_value`]:
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, Type, LE};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+/// struct Struct {
+///     field1: u16,
+///     #[zvariant(signature = "v")]
+///     #[serde(with = "zvariant::as_value")]
+///     field2: i64,
+/// }
+///
+/// assert_eq!(Struct::signature(), "(qv)");
+/// let s = Struct {
+///     field1: 42,
+///     field2: i64::max_value(),
+/// };
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &s).unwrap();
+/// let decoded: Struct = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, s);
+/// ```
+///
 /// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
 /// [serde_repr]: https://crates.io/crates/serde_repr
+/// [`zvariant::as_value`]: https://docs.rs/zvariant/latest/zvariant/as_value/index.html
 #[proc_macro_derive(Type, attributes(zvariant))]
 pub fn type_macro_derive(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = syn::parse(input).unwrap();
@@ -193,6 +247,8 @@ pub fn type_macro_derive(input: TokenStream) -> TokenStream {
 /// The serialized D-Bus version of `Struct {42, 77, None}`
 /// will be `{"field1": Value::U16(42), "another-name": Value::I64(77)}`.
 ///
+/// Type parameters of the struct are required to implement [`Type`] and [`Serialize`].
+///
 /// # Auto renaming fields
 ///
 /// The macro supports specifying a Serde-like `#[zvariant(rename_all = "case")]` attribute on
@@ -224,7 +280,46 @@ pub fn type_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Flattening fields
+///
+/// Entries common to several dictionaries can be factored into their own struct, and then
+/// flattened into the dictionary of another struct using the `#[zvariant(flatten)]` attribute.
+/// The type of a flattened field must also derive `SerializeDict`:
+///
+/// ```
+/// use std::collections::HashMap;
+/// use zvariant::{serialized::Context, to_bytes, OwnedValue, SerializeDict, Type, LE};
+///
+/// #[derive(SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// struct Common {
+///     id: u32,
+/// }
+///
+/// #[derive(SerializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// struct Struct {
+///     #[zvariant(flatten)]
+///     common: Common,
+///     field1: u16,
+/// }
+///
+/// let s = Struct {
+///     common: Common { id: 7 },
+///     field1: 42,
+/// };
+/// let encoded = to_bytes(Context::new_dbus(LE, 0), &s)?;
+/// let dict: HashMap<String, OwnedValue> = encoded.deserialize()?.0;
+/// assert_eq!(u32::try_from(&dict["id"])?, 7);
+/// assert_eq!(u16::try_from(&dict["field1"])?, 42);
+/// # Ok::<(), zvariant::Error>(())
+/// ```
+///
+/// As shown above, the serialized D-Bus version of `Struct { Common { 7 }, 42 }` is
+/// `{"id": Value::U32(7), "field1": Value::U16(42)}`.
+///
 /// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+/// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 #[proc_macro_derive(SerializeDict, attributes(zvariant))]
 pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -261,6 +356,8 @@ pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// The deserialized D-Bus dictionary `{"field1": Value::U16(42), "another-name": Value::I64(77)}`
 /// will be `Struct {42, 77, None}`.
 ///
+/// Type parameters of the struct are required to implement [`Type`] and [`Deserialize`].
+///
 /// # Auto renaming fields
 ///
 /// The macro supports specifying a Serde-like `#[zvariant(rename_all = "case")]` attribute on
@@ -292,7 +389,65 @@ pub fn serialize_dict_macro_derive(input: TokenStream) -> TokenStream {
 /// * `"camelCase"`
 /// * `"snake_case"`
 ///
+/// # Flattening fields
+///
+/// Just like with [`SerializeDict`], the `#[zvariant(flatten)]` attribute allows deserializing
+/// some of the entries into a field, whose type must also derive `DeserializeDict`:
+///
+/// ```
+/// use zvariant::{DeserializeDict, Type};
+///
+/// #[derive(DeserializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// ##[allow(unused)]
+/// struct Common {
+///     id: u32,
+/// }
+///
+/// #[derive(DeserializeDict, Type)]
+/// #[zvariant(signature = "a{sv}")]
+/// ##[allow(unused)]
+/// struct Struct {
+///     #[zvariant(flatten)]
+///     common: Common,
+///     field1: u16,
+/// }
+/// ```
+///
+/// Entries not known to the struct itself are offered to its flattened fields, in order.
+///
+/// # Missing entries
+///
+/// Fields of `Option` type are optional and set to `None` if their entry is missing, while an
+/// error is returned if the entry of any other field is missing. The `#[zvariant(default)]`
+/// attribute makes a field optional, taking its `Default` value if its entry is missing. When
+/// specified on the struct, missing entries take the value of the corresponding field of the
+/// struct's `Default` value instead:
+///
+/// ```
+/// use zvariant::{DeserializeDict, Type};
+///
+/// #[derive(DeserializeDict, Type, Default)]
+/// #[zvariant(signature = "a{sv}", default)]
+/// ##[allow(unused)]
+/// struct Options {
+///     timeout: u32,
+///     #[zvariant(default)]
+///     retries: u8,
+///     interactive: Option<bool>,
+/// }
+/// ```
+///
+/// # Unknown entries
+///
+/// By default, entries with an unknown key are ignored, as are entries whose value is not of the
+/// type of the corresponding field. The `#[zvariant(deny_unknown_fields)]` attribute on the struct
+/// makes both an error instead. Unknown keys are still ignored when deserializing in a [lenient]
+/// context though.
+///
+/// [lenient]: https://docs.rs/zvariant/latest/zvariant/serialized/struct.Context.html#method.with_lenient
 /// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+/// [`Type`]: https://docs.rs/zvariant/latest/zvariant/trait.Type.html
 #[proc_macro_derive(DeserializeDict, attributes(zvariant))]
 pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
@@ -301,6 +456,133 @@ pub fn deserialize_dict_macro_derive(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Adds [`Serialize`] implementation to enums, using one of the encodings below.
+///
+/// Serde's own derive can be used with enums whose variants all have the same number and types
+/// of fields. For other enums, this macro encodes the data of each variant in a variant (`v`), so
+/// the signature of the enum doesn't depend on its variants. The encoding is chosen through the
+/// `signature` attribute, which the [`Type`] derive uses as well:
+///
+/// * `#[zvariant(signature = "(uv)")]`: a structure of the index of the variant (starting from 0),
+///   followed by its data.
+/// * `#[zvariant(signature = "dict")]` (or `"a{sv}"`): a dictionary with a single entry, whose key
+///   is the name of the variant and whose value is its data. Just like with [`SerializeDict`],
+///   variants can be renamed with the `rename` and `rename_all` attributes.
+/// * `#[zvariant(signature = "s")]`: the name of the variant as a string, for enums without any
+///   fields. Many D-Bus APIs pass states this way (e.g. `"active"` or `"suspended"`). Variants can
+///   be renamed just like with the dictionary encoding. The encoding is the same as that of serde's
+///   own derive combined with the [`Type`] derive's `signature = "s"`, and `rename`/`rename_all`
+///   behave like serde's attributes of the same name (though `rename_all` only supports
+///   `lowercase`, `UPPERCASE`, `PascalCase`, `camelCase` and `snake_case`). Prefer serde's derive,
+///   unless you need the lenient deserialization described for [`DeserializeEnum`].
+///
+/// The data of a variant with a single unnamed field is that field. Unit variants are encoded as
+/// a `0` byte, just like empty structures. All other variants are encoded as a structure of their
+/// fields.
+///
+/// # Examples
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, DeserializeEnum, SerializeEnum, Type, LE};
+///
+/// #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+/// #[zvariant(signature = "(uv)")]
+/// enum Shape {
+///     Point,
+///     Circle(f64),
+///     Rectangle { width: u32, height: u32 },
+/// }
+///
+/// assert_eq!(Shape::signature(), "(uv)");
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let shape = Shape::Rectangle { width: 4, height: 2 };
+/// let encoded = to_bytes(ctxt, &shape).unwrap();
+/// let decoded: Shape = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, shape);
+///
+/// #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+/// #[zvariant(signature = "dict", rename_all = "lowercase")]
+/// enum Address {
+///     Ipv4([u8; 4]),
+///     #[zvariant(rename = "unix-path")]
+///     UnixPath(String),
+/// }
+///
+/// assert_eq!(Address::signature(), "a{sv}");
+/// let address = Address::UnixPath("/run/bus".to_string());
+/// let encoded = to_bytes(ctxt, &address).unwrap();
+/// let decoded: Address = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, address);
+/// ```
+///
+/// The serialized D-Bus version of `Address::Ipv4([127, 0, 0, 1])` will be
+/// `{"ipv4": Value::Array([127, 0, 0, 1])}`.
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, DeserializeEnum, SerializeEnum, Type, LE};
+///
+/// #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+/// #[zvariant(signature = "s", rename_all = "lowercase")]
+/// enum State {
+///     Active,
+///     Suspended,
+///     #[zvariant(rename = "shutting-down")]
+///     ShuttingDown,
+/// }
+///
+/// assert_eq!(State::signature(), "s");
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, &State::ShuttingDown).unwrap();
+/// assert_eq!(encoded.deserialize::<&str>().unwrap().0, "shutting-down");
+/// let decoded: State = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, State::ShuttingDown);
+/// ```
+///
+/// [`Serialize`]: https://docs.serde.rs/serde/trait.Serialize.html
+#[proc_macro_derive(SerializeEnum, attributes(zvariant))]
+pub fn serialize_enum_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    r#enum::expand_serialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// Adds [`Deserialize`] implementation to enums, encoded as described for [`SerializeEnum`].
+///
+/// Deserializing fails if the tag, the key of the dictionary entry or the string doesn't
+/// correspond to any variant, or if the dictionary doesn't have exactly one entry.
+///
+/// Services often add new states to their string-encoded enums, so string deserialization can
+/// be made lenient by marking one variant with `#[zvariant(other)]`: any unknown string is then
+/// deserialized as that variant, instead of failing.
+///
+/// ```
+/// use zvariant::{serialized::Context, to_bytes, DeserializeEnum, SerializeEnum, Type, LE};
+///
+/// #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+/// #[zvariant(signature = "s", rename_all = "lowercase")]
+/// enum State {
+///     Active,
+///     Suspended,
+///     #[zvariant(other)]
+///     Unknown,
+/// }
+///
+/// let ctxt = Context::new_dbus(LE, 0);
+/// let encoded = to_bytes(ctxt, "hibernating").unwrap();
+/// let decoded: State = encoded.deserialize().unwrap().0;
+/// assert_eq!(decoded, State::Unknown);
+/// ```
+///
+/// [`Deserialize`]: https://docs.serde.rs/serde/de/trait.Deserialize.html
+#[proc_macro_derive(DeserializeEnum, attributes(zvariant))]
+pub fn deserialize_enum_macro_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    r#enum::expand_deserialize_derive(input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
 /// Implements conversions for your type to/from [`Value`].
 ///
 /// Implements `TryFrom<Value>` and `Into<Value>` for your type.
@@ -1,12 +1,12 @@
 use proc_macro2::TokenStream;
-use quote::{quote, ToTokens};
+use quote::quote;
 use syn::{
-    spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Fields, Generics, Ident,
+    spanned::Spanned, Attribute, Data, DataEnum, DeriveInput, Error, Field, Fields, Generics, Ident,
 };
 
 use crate::utils::*;
 
-pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
+pub fn expand_derive(mut ast: DeriveInput) -> Result<TokenStream, Error> {
     let StructAttributes { signature, .. } = StructAttributes::parse(&ast.attrs)?;
 
     let zv = zvariant_path();
@@ -34,6 +34,8 @@ pub fn expand_derive(ast: DeriveInput) -> Result<TokenStream, Error> {
         });
     }
 
+    add_type_param_bounds(&mut ast.generics, quote! { #zv::Type });
+
     match ast.data {
         Data::Struct(ds) => match ds.fields {
             Fields::Named(_) if ds.fields.is_empty() => {
@@ -65,7 +67,7 @@ fn impl_struct(
     zv: &TokenStream,
 ) -> Result<TokenStream, Error> {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
-    let signature = signature_for_struct(&fields, zv, false);
+    let signature = signature_for_struct(&fields, zv, false)?;
 
     Ok(quote! {
         impl #impl_generics #zv::Type for #name #ty_generics #where_clause {
@@ -81,25 +83,28 @@ fn signature_for_struct(
     fields: &Fields,
     zv: &TokenStream,
     insert_enum_variant: bool,
-) -> TokenStream {
-    let field_types = fields.iter().map(|field| field.ty.to_token_stream());
+) -> Result<TokenStream, Error> {
+    let field_signatures = fields
+        .iter()
+        .map(|field| signature_for_field(field, zv))
+        .collect::<Result<Vec<_>, Error>>()?;
     let new_type = match fields {
         Fields::Named(_) => false,
-        Fields::Unnamed(_) if field_types.len() == 1 => true,
+        Fields::Unnamed(_) if field_signatures.len() == 1 => true,
         Fields::Unnamed(_) => false,
         Fields::Unit => panic!("signature_for_struct must not be called for unit fields"),
     };
     let inner_impl = if new_type {
         quote! {
             #(
-                <#field_types as #zv::Type>::signature()
+                #field_signatures
              )*
         }
     } else {
         quote! {
-            let mut s = <::std::string::String as ::std::convert::From<_>>::from("(");
+            let mut s = <#zv::export::String as ::core::convert::From<_>>::from("(");
             #(
-                s.push_str(<#field_types as #zv::Type>::signature().as_str());
+                s.push_str(#field_signatures.as_str());
             )*
             s.push_str(")");
 
@@ -108,22 +113,36 @@ fn signature_for_struct(
     };
 
     if insert_enum_variant {
-        quote! {
+        Ok(quote! {
             let inner_signature = {
                 #inner_impl
             };
-            let mut s = <::std::string::String as ::std::convert::From<_>>::from("(");
+            let mut s = <#zv::export::String as ::core::convert::From<_>>::from("(");
             s.push_str(<u32 as #zv::Type>::signature().as_str());
             s.push_str(inner_signature.as_str());
             s.push_str(")");
 
             #zv::Signature::from_string_unchecked(s)
-        }
+        })
     } else {
-        inner_impl
+        Ok(inner_impl)
     }
 }
 
+fn signature_for_field(field: &Field, zv: &TokenStream) -> Result<TokenStream, Error> {
+    let FieldAttributes { signature, .. } = FieldAttributes::parse(&field.attrs)?;
+
+    Ok(match signature.as_deref() {
+        Some("dict") => quote! { #zv::Signature::from_static_str_unchecked("a{sv}") },
+        Some(signature) => quote! { #zv::Signature::from_static_str(#signature).unwrap() },
+        None => {
+            let ty = &field.ty;
+
+            quote! { <#ty as #zv::Type>::signature() }
+        }
+    })
+}
+
 fn impl_unit_struct(
     name: Ident,
     generics: Generics,
@@ -176,7 +195,8 @@ fn impl_enum(
         if sig?.to_string() != signature.to_string() {
             return Err(Error::new(
                 name.span(),
-                "all variants must have the same number and type of fields",
+                "all variants must have the same number and type of fields, unless the \
+                 encoding is specified through the `signature` attribute (see `SerializeEnum`)",
             ));
         }
     }
@@ -208,7 +228,6 @@ fn signature_for_variant(
 
             Ok(quote! { <#repr as #zv::Type>::signature() })
         }
-        Fields::Named(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
-        Fields::Unnamed(_) => Ok(signature_for_struct(&variant.fields, zv, true)),
+        Fields::Named(_) | Fields::Unnamed(_) => signature_for_struct(&variant.fields, zv, true),
     }
 }
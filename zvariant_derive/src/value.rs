@@ -91,7 +91,7 @@ fn impl_struct(
             quote! { try_from },
             quote! { type Error = #zv::Error; },
             quote! { #zv::Result<Self> },
-            quote! { .map_err(::std::convert::Into::into) },
+            quote! { .map_err(::core::convert::Into::into) },
         ),
     };
 
@@ -101,14 +101,14 @@ fn impl_struct(
             Some(quote! {
                 where
                 #(
-                    #type_params: ::std::convert::TryFrom<#zv::Value<#value_lifetime>> + #zv::Type,
-                    <#type_params as ::std::convert::TryFrom<#zv::Value<#value_lifetime>>>::Error: ::std::convert::Into<#zv::Error>
+                    #type_params: ::core::convert::TryFrom<#zv::Value<#value_lifetime>> + #zv::Type,
+                    <#type_params as ::core::convert::TryFrom<#zv::Value<#value_lifetime>>>::Error: ::core::convert::Into<#zv::Error>
                 ),*
             }),
             Some(quote! {
                 where
                 #(
-                    #type_params: ::std::convert::Into<#zv::Value<#value_lifetime>> + #zv::Type
+                    #type_params: ::core::convert::Into<#zv::Value<#value_lifetime>> + #zv::Type
                 ),*
             }),
         )
@@ -129,7 +129,7 @@ fn impl_struct(
                     quote! {
                         let mut fields = <::std::collections::HashMap::<::std::string::String, #zv::Value>>::try_from(value)?;
 
-                        ::std::result::Result::Ok(Self {
+                        ::core::result::Result::Ok(Self {
                             #(
                                 #field_names:
                                     fields
@@ -153,7 +153,7 @@ fn impl_struct(
                     quote! {
                         let mut fields = #zv::Structure::try_from(value)?.into_fields();
 
-                        ::std::result::Result::Ok(Self {
+                        ::core::result::Result::Ok(Self {
                             #(
                                 #field_names: fields.remove(0).downcast()?
                             ),*
@@ -170,7 +170,7 @@ fn impl_struct(
                 ),
             };
             Ok(quote! {
-                impl #impl_generics ::std::convert::TryFrom<#value_type> for #name #ty_generics
+                impl #impl_generics ::core::convert::TryFrom<#value_type> for #name #ty_generics
                     #from_value_where_clause
                 {
                     type Error = #zv::Error;
@@ -196,14 +196,14 @@ fn impl_struct(
         Fields::Unnamed(_) if fields.iter().next().is_some() => {
             // Newtype struct.
             Ok(quote! {
-                impl #impl_generics ::std::convert::TryFrom<#value_type> for #name #ty_generics
+                impl #impl_generics ::core::convert::TryFrom<#value_type> for #name #ty_generics
                     #from_value_where_clause
                 {
                     type Error = #zv::Error;
 
                     #[inline]
                     fn try_from(value: #value_type) -> #zv::Result<Self> {
-                        ::std::convert::TryInto::try_into(value).map(Self)
+                        ::core::convert::TryInto::try_into(value).map(Self)
                     }
                 }
 
@@ -268,7 +268,7 @@ fn impl_enum(
         ValueType::Value => (
             quote! { #zv::Value<'_> },
             quote! {
-                impl ::std::convert::From<#name> for #zv::Value<'_> {
+                impl ::core::convert::From<#name> for #zv::Value<'_> {
                     #[inline]
                     fn from(e: #name) -> Self {
                         let u: #repr = match e {
@@ -277,7 +277,7 @@ fn impl_enum(
                             ),*
                         };
 
-                        <#zv::Value as ::std::convert::From<_>>::from(u).into()
+                        <#zv::Value as ::core::convert::From<_>>::from(u).into()
                     }
                 }
             },
@@ -285,7 +285,7 @@ fn impl_enum(
         ValueType::OwnedValue => (
             quote! { #zv::OwnedValue },
             quote! {
-                impl ::std::convert::TryFrom<#name> for #zv::OwnedValue {
+                impl ::core::convert::TryFrom<#name> for #zv::OwnedValue {
                     type Error = #zv::Error;
 
                     #[inline]
@@ -296,8 +296,8 @@ fn impl_enum(
                             ),*
                         };
 
-                        <#zv::OwnedValue as ::std::convert::TryFrom<_>>::try_from(
-                            <#zv::Value as ::std::convert::From<_>>::from(u)
+                        <#zv::OwnedValue as ::core::convert::TryFrom<_>>::try_from(
+                            <#zv::Value as ::core::convert::From<_>>::from(u)
                         )
                     }
                 }
@@ -306,18 +306,18 @@ fn impl_enum(
     };
 
     Ok(quote! {
-        impl ::std::convert::TryFrom<#value_type> for #name {
+        impl ::core::convert::TryFrom<#value_type> for #name {
             type Error = #zv::Error;
 
             #[inline]
             fn try_from(value: #value_type) -> #zv::Result<Self> {
-                let v: #repr = ::std::convert::TryInto::try_into(value)?;
+                let v: #repr = ::core::convert::TryInto::try_into(value)?;
 
-                ::std::result::Result::Ok(match v {
+                ::core::result::Result::Ok(match v {
                     #(
                         #variant_values => #name::#variant_names
                      ),*,
-                    _ => return ::std::result::Result::Err(#zv::Error::IncorrectType),
+                    _ => return ::core::result::Result::Err(#zv::Error::IncorrectType),
                 })
             }
         }
@@ -3,7 +3,7 @@
 use std::collections::HashMap;
 use zvariant::{
     serialized::{Context, Format},
-    DeserializeDict, OwnedValue, SerializeDict, Type, Value, LE,
+    DeserializeDict, DeserializeEnum, OwnedValue, SerializeDict, SerializeEnum, Type, Value, LE,
 };
 
 #[test]
@@ -39,6 +39,82 @@ fn derive_enum() {
     assert_eq!(RequestNameFlags::signature(), "u")
 }
 
+#[test]
+fn derive_field_signature() {
+    #[derive(Type)]
+    struct TestStruct {
+        name: String,
+        #[zvariant(signature = "v")]
+        value: u32,
+        #[zvariant(signature = "dict")]
+        properties: Vec<(String, OwnedValue)>,
+    }
+
+    assert_eq!(TestStruct::signature(), "(sva{sv})");
+
+    #[derive(Type)]
+    struct NewType(#[zvariant(signature = "v")] u8);
+
+    assert_eq!(NewType::signature(), "v");
+}
+
+#[test]
+fn derive_generic() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+    struct Pair<K, V> {
+        key: K,
+        value: V,
+    }
+    assert_eq!(Pair::<String, u32>::signature(), "(su)");
+    assert_eq!(Pair::<u8, Vec<(i32, bool)>>::signature(), "(ya(ib))");
+
+    #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+    struct Wrapper<T>(T)
+    where
+        T: Clone;
+    assert_eq!(Wrapper::<(String, u32)>::signature(), "(su)");
+
+    #[derive(Type)]
+    enum Event<T> {
+        Added(T),
+        Removed(T),
+    }
+    assert_eq!(Event::<String>::signature(), "(us)");
+
+    #[derive(DeserializeDict, SerializeDict, Type, PartialEq, Debug)]
+    #[zvariant(signature = "dict")]
+    struct Page<T> {
+        items: Vec<T>,
+        next: Option<T>,
+        total: u32,
+    }
+    assert_eq!(Page::<u64>::signature(), "a{sv}");
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let page = Page {
+        items: vec![Pair {
+            key: "a".to_string(),
+            value: 1u32,
+        }],
+        next: None,
+        total: 1,
+    };
+    let encoded = zvariant::to_bytes(ctxt, &page).unwrap();
+    let decoded: Page<Pair<String, u32>> = encoded.deserialize().unwrap().0;
+    assert_eq!(decoded, page);
+
+    let page = Page {
+        items: vec![1.5, 2.5],
+        next: Some(3.5),
+        total: 3,
+    };
+    let encoded = zvariant::to_bytes(ctxt, &page).unwrap();
+    let decoded: Page<f64> = encoded.deserialize().unwrap().0;
+    assert_eq!(decoded, page);
+}
+
 #[test]
 fn derive_dict() {
     #[derive(SerializeDict, DeserializeDict, Type)]
@@ -82,3 +158,343 @@ fn derive_dict() {
 
     assert_eq!(Test::signature(), "a{sv}")
 }
+
+#[test]
+fn derive_dict_flatten() {
+    #[derive(SerializeDict, DeserializeDict, Type, PartialEq, Debug)]
+    #[zvariant(signature = "a{sv}", rename_all = "PascalCase")]
+    struct Common {
+        id: u32,
+        #[zvariant(rename = "label-text")]
+        label: Option<String>,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, PartialEq, Debug)]
+    #[zvariant(deny_unknown_fields, signature = "a{sv}")]
+    struct Test {
+        #[zvariant(flatten)]
+        common: Common,
+        size: u64,
+    }
+
+    #[derive(SerializeDict, DeserializeDict, Type, PartialEq, Debug)]
+    #[zvariant(signature = "a{sv}")]
+    struct Nested {
+        #[zvariant(flatten)]
+        test: Test,
+    }
+
+    let test = Nested {
+        test: Test {
+            common: Common {
+                id: 42,
+                label: Some("foo".to_string()),
+            },
+            size: 1024,
+        },
+    };
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let serialized = zvariant::to_bytes(ctxt, &test).unwrap();
+    let deserialized: HashMap<String, OwnedValue> = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized.len(), 3);
+    assert_eq!(deserialized["Id"], Value::from(42u32).try_into().unwrap());
+    assert_eq!(
+        deserialized["label-text"],
+        Value::from("foo").try_into().unwrap()
+    );
+    assert_eq!(
+        deserialized["size"],
+        Value::from(1024u64).try_into().unwrap()
+    );
+
+    let deserialized: Nested = serialized.deserialize().unwrap().0;
+    assert_eq!(deserialized, test);
+
+    // Unknown and missing fields are still reported through the flattened struct.
+    let mut dict = HashMap::from([("size", Value::from(1024u64))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let err = serialized.deserialize::<Test>().unwrap_err();
+    assert_eq!(err.to_string(), "missing field `id`");
+    dict.insert("Id", Value::from(42u32));
+    dict.insert("unknown", Value::from(true));
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let err = serialized.deserialize::<Test>().unwrap_err();
+    assert_eq!(err.to_string(), "unknown field `unknown`, expected `size`");
+
+    assert_eq!(Nested::signature(), "a{sv}")
+}
+
+#[test]
+fn derive_dict_defaults() {
+    #[derive(DeserializeDict, Type, PartialEq, Debug)]
+    #[zvariant(signature = "a{sv}")]
+    struct Fields {
+        required: u32,
+        #[zvariant(default)]
+        retries: u8,
+        #[zvariant(default)]
+        tags: Vec<String>,
+        optional: Option<bool>,
+    }
+
+    #[derive(DeserializeDict, Type, PartialEq, Debug)]
+    #[zvariant(signature = "a{sv}", default, deny_unknown_fields)]
+    struct Container {
+        timeout: u32,
+        #[zvariant(default)]
+        retries: u8,
+        interactive: Option<bool>,
+    }
+
+    impl Default for Container {
+        fn default() -> Self {
+            Self {
+                timeout: 25,
+                retries: 3,
+                interactive: Some(true),
+            }
+        }
+    }
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    let dict = HashMap::from([
+        ("required", Value::from(7u32)),
+        // Ignored since the type doesn't match.
+        ("retries", Value::from("many")),
+        ("unknown", Value::from(true)),
+    ]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let fields: Fields = serialized.deserialize().unwrap().0;
+    assert_eq!(
+        fields,
+        Fields {
+            required: 7,
+            retries: 0,
+            tags: vec![],
+            optional: None,
+        }
+    );
+    let serialized = zvariant::to_bytes(ctxt, &HashMap::<&str, Value<'_>>::new()).unwrap();
+    let err = serialized.deserialize::<Fields>().unwrap_err();
+    assert_eq!(err.to_string(), "missing field `required`");
+
+    // Field defaults take precedence over the struct's default.
+    let serialized = zvariant::to_bytes(ctxt, &HashMap::<&str, Value<'_>>::new()).unwrap();
+    let container: Container = serialized.deserialize().unwrap().0;
+    assert_eq!(
+        container,
+        Container {
+            timeout: 25,
+            retries: 0,
+            interactive: Some(true),
+        }
+    );
+    let dict = HashMap::from([
+        ("timeout", Value::from(5u32)),
+        ("interactive", Value::from(false)),
+    ]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let container: Container = serialized.deserialize().unwrap().0;
+    assert_eq!(
+        container,
+        Container {
+            timeout: 5,
+            retries: 0,
+            interactive: Some(false),
+        }
+    );
+
+    // In strict mode, values of the wrong type are an error.
+    let dict = HashMap::from([("timeout", Value::from("soon"))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    serialized.deserialize::<Container>().unwrap_err();
+}
+
+#[test]
+fn derive_enum_encodings() {
+    #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+    #[zvariant(signature = "(uv)")]
+    enum Tagged {
+        Unit,
+        NewType(String),
+        Tuple(u8, i64),
+        Struct { id: u32, tags: Vec<String> },
+    }
+    assert_eq!(Tagged::signature(), "(uv)");
+
+    #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+    #[zvariant(signature = "dict", rename_all = "snake_case")]
+    enum Dict {
+        Unit,
+        NewType(String),
+        #[zvariant(rename = "a-tuple")]
+        Tuple(u8, i64),
+        Struct {
+            id: u32,
+            tags: Vec<String>,
+        },
+    }
+    assert_eq!(Dict::signature(), "a{sv}");
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+
+    let values = [
+        Tagged::Unit,
+        Tagged::NewType("hello".to_string()),
+        Tagged::Tuple(7, -1),
+        Tagged::Struct {
+            id: 42,
+            tags: vec!["a".to_string(), "b".to_string()],
+        },
+    ];
+    for (tag, value) in values.into_iter().enumerate() {
+        let serialized = zvariant::to_bytes(ctxt, &value).unwrap();
+        let (decoded_tag, _): (u32, OwnedValue) = serialized.deserialize().unwrap().0;
+        assert_eq!(decoded_tag, tag as u32);
+        let deserialized: Tagged = serialized.deserialize().unwrap().0;
+        assert_eq!(deserialized, value);
+    }
+
+    let values = [
+        (Dict::Unit, "unit"),
+        (Dict::NewType("hello".to_string()), "new_type"),
+        (Dict::Tuple(7, -1), "a-tuple"),
+        (
+            Dict::Struct {
+                id: 42,
+                tags: vec!["a".to_string(), "b".to_string()],
+            },
+            "struct",
+        ),
+    ];
+    for (value, key) in values {
+        let serialized = zvariant::to_bytes(ctxt, &value).unwrap();
+        let dict: HashMap<String, OwnedValue> = serialized.deserialize().unwrap().0;
+        assert_eq!(dict.keys().collect::<Vec<_>>(), [key]);
+        let deserialized: Dict = serialized.deserialize().unwrap().0;
+        assert_eq!(deserialized, value);
+    }
+    let dict = HashMap::from([("new_type", Value::from("hello"))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    assert_eq!(
+        serialized.deserialize::<Dict>().unwrap().0,
+        Dict::NewType("hello".to_string())
+    );
+
+    // Unknown variants and payloads of the wrong type are reported.
+    let serialized = zvariant::to_bytes(ctxt, &(4u32, Value::from(1u8))).unwrap();
+    let err = serialized.deserialize::<Tagged>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "invalid value: integer `4`, expected enum Tagged"
+    );
+    let serialized = zvariant::to_bytes(ctxt, &(1u32, Value::from(1u8))).unwrap();
+    assert!(serialized.deserialize::<Tagged>().is_err());
+    let dict = HashMap::from([("unknown", Value::from(1u8))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    let err = serialized.deserialize::<Dict>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `unknown`, expected one of `unit`, `new_type`, `a-tuple`, `struct`"
+    );
+    let dict = HashMap::from([("unit", Value::from(0u8)), ("new_type", Value::from("a"))]);
+    let serialized = zvariant::to_bytes(ctxt, &dict).unwrap();
+    assert!(serialized.deserialize::<Dict>().is_err());
+}
+
+#[test]
+fn derive_str_enum() {
+    #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+    #[zvariant(signature = "s", rename_all = "snake_case")]
+    enum Strict {
+        Active,
+        #[zvariant(rename = "on-hold")]
+        OnHold,
+        ShuttingDown,
+    }
+    assert_eq!(Strict::signature(), "s");
+
+    #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+    #[zvariant(signature = "s")]
+    enum Lenient {
+        Active,
+        #[zvariant(other)]
+        Unknown,
+    }
+    assert_eq!(Lenient::signature(), "s");
+
+    let ctxt = Context::new(Format::DBus, LE, 0);
+
+    let values = [
+        (Strict::Active, "active"),
+        (Strict::OnHold, "on-hold"),
+        (Strict::ShuttingDown, "shutting_down"),
+    ];
+    for (value, name) in values {
+        let serialized = zvariant::to_bytes(ctxt, &value).unwrap();
+        assert_eq!(serialized.deserialize::<&str>().unwrap().0, name);
+        let deserialized: Strict = serialized.deserialize().unwrap().0;
+        assert_eq!(deserialized, value);
+    }
+
+    // Strict enums reject unknown names, while lenient ones fall back to the `other` variant.
+    let serialized = zvariant::to_bytes(ctxt, "Suspended").unwrap();
+    let err = serialized.deserialize::<Strict>().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown variant `Suspended`, expected one of `active`, `on-hold`, `shutting_down`"
+    );
+    assert_eq!(
+        serialized.deserialize::<Lenient>().unwrap().0,
+        Lenient::Unknown
+    );
+    let serialized = zvariant::to_bytes(ctxt, "Active").unwrap();
+    assert_eq!(
+        serialized.deserialize::<Lenient>().unwrap().0,
+        Lenient::Active
+    );
+    let serialized = zvariant::to_bytes(ctxt, &Lenient::Unknown).unwrap();
+    assert_eq!(serialized.deserialize::<&str>().unwrap().0, "Unknown");
+}
+
+#[test]
+fn str_enum_matches_serde() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(DeserializeEnum, SerializeEnum, Type, PartialEq, Debug)]
+    #[zvariant(signature = "s", rename_all = "snake_case")]
+    enum ZvariantState {
+        Active,
+        #[zvariant(rename = "on-hold")]
+        OnHold,
+        ShuttingDown,
+    }
+
+    #[derive(Deserialize, Serialize, Type, PartialEq, Debug)]
+    #[zvariant(signature = "s")]
+    #[serde(rename_all = "snake_case")]
+    enum SerdeState {
+        Active,
+        #[serde(rename = "on-hold")]
+        OnHold,
+        ShuttingDown,
+    }
+
+    let values = [
+        (ZvariantState::Active, SerdeState::Active),
+        (ZvariantState::OnHold, SerdeState::OnHold),
+        (ZvariantState::ShuttingDown, SerdeState::ShuttingDown),
+    ];
+    let ctxt = Context::new(Format::DBus, LE, 0);
+    for (zvariant, serde) in values {
+        let zvariant_bytes = zvariant::to_bytes(ctxt, &zvariant).unwrap();
+        let serde_bytes = zvariant::to_bytes(ctxt, &serde).unwrap();
+        assert_eq!(zvariant_bytes.bytes(), serde_bytes.bytes());
+        assert_eq!(
+            serde_bytes.deserialize::<ZvariantState>().unwrap().0,
+            zvariant
+        );
+        assert_eq!(zvariant_bytes.deserialize::<SerdeState>().unwrap().0, serde);
+    }
+}